@@ -10,6 +10,11 @@
 // Noise is position-keyed (same pixel → same delta) so visual output is
 // stable across reads but differs per seed.  Intensity is kept ≤ ±3 LSB so
 // CAPTCHAs and image-recognition pipelines are not visibly broken.
+//
+// When the profile carries text-rendering parameters, measureText() widths
+// are scaled and fillText()/strokeText() glyphs shifted by the seeded
+// amounts, so text metrics differ per profile the way hinting differs per
+// install.
 
 import type { EvasionConfig } from "./types.js";
 
@@ -48,10 +53,11 @@ export function canvasEvasion(cfg: EvasionConfig): string {
   // Scale noise level (0–1) to a max per-channel delta (0–4 LSB).
   // We clamp hard at 4 to avoid visible artefacts.
   const maxDelta = Math.max(0, Math.min(4, Math.round(canvas.noiseLevel * 4)));
+  const text = canvas.text ? canvasTextEvasion(canvas.text) : "";
 
-  if (maxDelta === 0) return "/* canvas evasion: noise disabled */";
+  if (maxDelta === 0) return text || "/* canvas evasion: noise disabled */";
 
-  return /* js */ `(function () {
+  return text + /* js */ `(function () {
   'use strict';
 
   // ── Shared constants ──────────────────────────────────────────────────────
@@ -199,5 +205,66 @@ export function canvasEvasion(cfg: EvasionConfig): string {
 })();`;
 }
 
+// ── Text metrics ──────────────────────────────────────────────────────────────
+
+function canvasTextEvasion(text: NonNullable<EvasionConfig["canvas"]["text"]>): string {
+  return /* js */ `(function () {
+  'use strict';
+
+  const _SCALE = ${text.measure_scale};
+  const _SHIFT = ${text.baseline_shift};
+  const _SCALED = new Set([
+    'width',
+    'actualBoundingBoxLeft',
+    'actualBoundingBoxRight',
+  ]);
+
+  for (const proto of [
+    CanvasRenderingContext2D.prototype,
+    typeof OffscreenCanvasRenderingContext2D !== 'undefined'
+      ? OffscreenCanvasRenderingContext2D.prototype
+      : null,
+  ]) {
+    if (!proto) continue;
+
+    // ── measureText: scaled horizontal metrics ────────────────────────────
+    const _origMeasure = proto.measureText;
+    Object.defineProperty(proto, 'measureText', {
+      configurable: true,
+      enumerable:   false,
+      writable:     true,
+      value: function measureText(s) {
+        const m = _origMeasure.call(this, s);
+        return new Proxy(m, {
+          get(target, key) {
+            const v = Reflect.get(target, key);
+            return _SCALED.has(key) && typeof v === 'number' ? v * _SCALE : v;
+          },
+        });
+      },
+    });
+
+    // ── fillText / strokeText: seeded baseline offset ─────────────────────
+    for (const name of ['fillText', 'strokeText']) {
+      const _orig = proto[name];
+      // Computed key keeps the native function name
+      const patched = {
+        [name](s, x, y, maxWidth) {
+          return maxWidth !== undefined
+            ? _orig.call(this, s, x, y + _SHIFT, maxWidth)
+            : _orig.call(this, s, x, y + _SHIFT);
+        },
+      }[name];
+      Object.defineProperty(proto, name, {
+        configurable: true,
+        enumerable:   false,
+        writable:     true,
+        value: patched,
+      });
+    }
+  }
+})();`;
+}
+
 // ── Type re-export so index.ts can import EvasionConfig once ─────────────────
 export type { EvasionConfig };
@@ -12,7 +12,8 @@
 //        route-interceptor exported as `installClientHintsRoute`.
 //
 //   2. Screen / Viewport / Letterboxing
-//        screen.{width,height,availWidth,availHeight,colorDepth,pixelDepth}
+//        screen.{width,height,availWidth,availHeight,availLeft,availTop,
+//                colorDepth,pixelDepth,isExtended}
 //        window.{devicePixelRatio,outerWidth,outerHeight,screenX,screenY,
 //                screenLeft,screenTop}
 //        window.{innerWidth,innerHeight} are already set correctly by
@@ -25,24 +26,36 @@
 //   innerWidth  = viewportWidth   (Playwright-managed)
 //   innerHeight = viewportHeight  (Playwright-managed)
 //   chrome_h    = outerHeight - innerHeight  (toolbars; typically 74 px)
-//   screenX/Y   = 0
+//   screenX/Y   = availLeft/availTop
+//
+// A session placed by the profile's screen layout (fingerprint.rs) may open
+// restored instead: screenX/Y come from the placement and the outer size is
+// the viewport plus the window frame (WINDOW_FRAME in fingerprint.rs).
 
-import type { EvasionConfig } from "./types.js";
+import type { EvasionConfig, RequestHeaderProfile } from "./types.js";
 
 // ── Init-script factory ───────────────────────────────────────────────────────
 
 export function clientHintsEvasion(cfg: EvasionConfig): string {
   const { uaCh, screen: s } = cfg;
 
-  // Outer window = full available desktop area (maximised window)
-  const outerW = s.availWidth;
-  const outerH = s.availHeight;
+  // Outer window = full available desktop area (maximised window), or the
+  // viewport plus the frame of a restored one
+  const win = s.window ?? { x: s.availLeft, y: s.availTop, maximized: true };
+  const outerW = win.maximized ? s.availWidth : s.viewportWidth + 16;
+  const outerH = win.maximized ? s.availHeight : s.viewportHeight + 87;
 
   // Serialise brand list once
   const brandsJson       = JSON.stringify(uaCh.brands);
-  // Full-version list is the same set; real Chrome only exposes the same
-  // three brands in getHighEntropyValues({ hints: ['fullVersionList'] }).
-  const fullVersionJson  = JSON.stringify(uaCh.brands);
+  // Full-version list is the same set of brands with four-part versions:
+  // the full Chrome version (kept out of a reduced UA string) for the real
+  // brands, `<v>.0.0.0` for GREASE.
+  const fullVersionJson  = JSON.stringify(uaCh.brands.map((b) => ({
+    brand: b.brand,
+    version: b.brand.startsWith("Not") || !uaCh.fullVersion
+      ? `${b.version}.0.0.0`
+      : uaCh.fullVersion,
+  })));
 
   return /* js */`(function () {
   'use strict';
@@ -55,14 +68,21 @@ export function clientHintsEvasion(cfg: EvasionConfig): string {
   const _ARCHITECTURE      = ${JSON.stringify(uaCh.architecture)};
   const _BITNESS           = ${JSON.stringify(uaCh.bitness)};
   const _FULL_VERSION_LIST = ${fullVersionJson};
-  const _UA_FULL_VERSION   = ${JSON.stringify(uaCh.brands[uaCh.brands.length - 1]?.version ?? "")};
+  const _UA_FULL_VERSION   = ${JSON.stringify(uaCh.fullVersion)};
 
   const _SCR_W    = ${s.width};
   const _SCR_H    = ${s.height};
   const _AVAIL_W  = ${s.availWidth};
   const _AVAIL_H  = ${s.availHeight};
+  const _AVAIL_L  = ${s.availLeft};
+  const _AVAIL_T  = ${s.availTop};
+  const _EXTENDED = ${s.isExtended};
+  const _WIN_X    = ${win.x};
+  const _WIN_Y    = ${win.y};
   const _COLOR_D  = ${s.colorDepth};
   const _DPR      = ${s.pixelRatio};
+  const _GAMUT    = ${JSON.stringify(s.colorGamut)};
+  const _HDR      = ${s.hdr};
   const _OUTER_W  = ${outerW};
   const _OUTER_H  = ${outerH};
 
@@ -152,8 +172,11 @@ export function clientHintsEvasion(cfg: EvasionConfig): string {
     height:      _SCR_H,
     availWidth:  _AVAIL_W,
     availHeight: _AVAIL_H,
+    availLeft:   _AVAIL_L,
+    availTop:    _AVAIL_T,
     colorDepth:  _COLOR_D,
     pixelDepth:  _COLOR_D,
+    isExtended:  _EXTENDED,
   };
 
   const _ScreenProto = Object.getPrototypeOf(screen);
@@ -209,12 +232,15 @@ export function clientHintsEvasion(cfg: EvasionConfig): string {
 
   // ── 5. screenX / screenY / screenLeft / screenTop ─────────────────────────
   //
-  // A maximised window sits at (0, 0) on the primary monitor.
+  // A maximised window sits at its monitor's origin; a restored one where
+  // the session placed it.
 
-  for (const prop of ['screenX', 'screenY', 'screenLeft', 'screenTop']) {
+  for (const [prop, val] of [
+    ['screenX', _WIN_X], ['screenY', _WIN_Y], ['screenLeft', _WIN_X], ['screenTop', _WIN_Y],
+  ]) {
     try {
       Object.defineProperty(window, prop, {
-        get: () => 0,
+        get: (function (v) { return () => v; })(val),
         set: () => {},
         enumerable:   true,
         configurable: true,
@@ -222,21 +248,33 @@ export function clientHintsEvasion(cfg: EvasionConfig): string {
     } catch (_) { /* best-effort */ }
   }
 
-  // ── 6. matchMedia DPR consistency ────────────────────────────────────────
+  // ── 6. matchMedia DPR / gamut / HDR consistency ──────────────────────────
   //
-  // Some scripts probe devicePixelRatio via
+  // Some scripts probe devicePixelRatio and display capabilities via
   //   window.matchMedia('(device-pixel-ratio: 2)').matches
-  // We intercept matchMedia and patch the result for DPR queries.
+  //   window.matchMedia('(color-gamut: p3)').matches
+  //   window.matchMedia('(dynamic-range: high)').matches
+  // We intercept matchMedia and patch the result for those queries.
+
+  // Gamuts in increasing size; a display matches every gamut it covers
+  const _GAMUTS = ['srgb', 'p3', 'rec2020'];
+
+  function _mediaOverride(query) {
+    const dprMatch = query.match(/\\(\\s*(?:-webkit-)?device-pixel-ratio\\s*:\\s*([\\d.]+)\\s*\\)/);
+    if (dprMatch) return Math.abs(parseFloat(dprMatch[1]) - _DPR) < 0.001;
+    const gamutMatch = query.match(/^\\s*\\(\\s*color-gamut\\s*:\\s*(srgb|p3|rec2020)\\s*\\)\\s*$/);
+    if (gamutMatch) return _GAMUTS.indexOf(gamutMatch[1]) <= _GAMUTS.indexOf(_GAMUT);
+    const rangeMatch = query.match(/^\\s*\\(\\s*(?:video-)?dynamic-range\\s*:\\s*(standard|high)\\s*\\)\\s*$/);
+    if (rangeMatch) return rangeMatch[1] === 'standard' || _HDR;
+    return null;
+  }
 
   const _origMatchMedia = window.matchMedia.bind(window);
   Object.defineProperty(window, 'matchMedia', {
     value: function matchMedia(query) {
       const mql = _origMatchMedia(query);
-      // Normalise DPR queries
-      const dprMatch = query.match(/\\(\\s*(?:-webkit-)?device-pixel-ratio\\s*:\\s*([\\d.]+)\\s*\\)/);
-      if (dprMatch) {
-        const queried = parseFloat(dprMatch[1]);
-        const matches = Math.abs(queried - _DPR) < 0.001;
+      const matches = _mediaOverride(String(query));
+      if (matches !== null) {
         return Object.defineProperties(Object.create(Object.getPrototypeOf(mql)), {
           matches:    { value: matches, writable: false, enumerable: true, configurable: true },
           media:      { value: mql.media, writable: false, enumerable: true, configurable: true },
@@ -348,6 +386,27 @@ function buildSecChUa(brands: Array<{ brand: string; version: string }>): string
     .join(", ");
 }
 
+/** Apply the Chrome major's Accept-Encoding, the Accept of the request's
+ *  destination, and — on navigations missing them — fetch metadata.
+ *  Fetch/XHR (`sec-fetch-dest: empty`) keeps the Accept the page set. */
+function applyRequestHeaderProfile(
+  headers: Record<string, string>,
+  profile: RequestHeaderProfile,
+  isNav: boolean,
+): void {
+  headers["accept-encoding"] = profile.accept_encoding;
+  if (isNav && !("sec-fetch-mode" in headers)) {
+    const nav = profile.navigation;
+    headers["sec-fetch-site"] = nav.site;
+    headers["sec-fetch-mode"] = nav.mode;
+    if (nav.user) headers["sec-fetch-user"] = nav.user;
+    headers["sec-fetch-dest"] = nav.dest;
+  }
+  const dest = headers["sec-fetch-dest"] ?? (isNav ? "document" : undefined);
+  const accept = dest ? profile.accept[dest] : undefined;
+  if (accept) headers["accept"] = accept;
+}
+
 export async function installClientHintsRoute(
   page: Page,
   cfg: EvasionConfig
@@ -359,6 +418,7 @@ export async function installClientHintsRoute(
   const secChUaPlatform = `"${uaCh.platform}"`;
   const acceptLanguage  = hdrCfg.acceptLanguage;
   const userAgent       = nav.userAgent;
+  const reqHeaders      = hdrCfg.request ?? null;
 
   await page.route("**/*", async (route: Route, request: Request) => {
     const rtype = request.resourceType();
@@ -376,6 +436,7 @@ export async function installClientHintsRoute(
       "accept-language":    acceptLanguage,
       "user-agent":         userAgent,
     };
+    if (reqHeaders) applyRequestHeaderProfile(patched, reqHeaders, isNav);
 
     // Remove headers that headless Playwright sometimes adds spuriously
     delete patched["sec-ch-ua-full-version-list"];  // only sent after opt-in
@@ -17,6 +17,8 @@
 //   5. audio       — AudioBuffer.getChannelData, AnalyserNode, startRendering
 //   6. fonts       — FontFaceSet filtering, measureText noise, fontFamily hooks
 //   7. webrtc      — RTCPeerConnection masking / mDNS candidate rewriting
+//   8. intl        — resolvedOptions defaults and week info of the locale
+//   9. timing      — performance.now() bucketing, input-event latency
 //
 // All scripts are idempotent: a `__m_*_patched__` guard prevents double-
 // application if addInitScript is somehow called twice.
@@ -37,6 +39,8 @@ export { fontsEvasion } from "./fonts.js";
 export { webrtcEvasion } from "./webrtc.js";
 export { clientHintsEvasion, installClientHintsRoute } from "./client-hints.js";
 export { navigatorEvasion } from "./navigator.js";
+export { intlEvasion } from "./intl.js";
+export { timingEvasion } from "./timing.js";
 export {
   installTlsGreaseRoute,
   buildJa4hPartial,
@@ -52,6 +56,8 @@ import { fontsEvasion } from "./fonts.js";
 import { webrtcEvasion } from "./webrtc.js";
 import { clientHintsEvasion, installClientHintsRoute } from "./client-hints.js";
 import { navigatorEvasion } from "./navigator.js";
+import { intlEvasion } from "./intl.js";
+import { timingEvasion } from "./timing.js";
 import { installTlsGreaseRoute } from "./tls-grease.js";
 import { applyTlsBridge, generateJa4Hash } from "./tls-bridge.js";
 
@@ -74,6 +80,8 @@ const INIT_SCRIPTS: InitScriptEntry[] = [
   { name: "audio", build: audioEvasion },
   { name: "fonts", build: fontsEvasion },
   { name: "webrtc", build: webrtcEvasion },
+  { name: "intl", build: intlEvasion },
+  { name: "timing", build: timingEvasion },
 ];
 
 // ── Disabled-script sentinel ──────────────────────────────────────────────────
@@ -222,6 +230,7 @@ export function buildEvasionConfig(profile: Profile): EvasionConfig {
 
     canvas: {
       noiseLevel: fp.canvas_noise,
+      text: fp.text_rendering ?? null,
     },
 
     webgl: {
@@ -253,8 +262,14 @@ export function buildEvasionConfig(profile: Profile): EvasionConfig {
       height: fp.screen_height,
       availWidth: fp.screen_width,
       availHeight: fp.screen_height - 40, // subtract typical taskbar height
+      availLeft: fp.screen_layout?.avail_left ?? 0,
+      availTop: fp.screen_layout?.avail_top ?? 0,
+      isExtended: (fp.screen_layout?.monitors ?? 1) > 1,
+      window: null,
       colorDepth: fp.color_depth,
       pixelRatio: fp.pixel_ratio,
+      colorGamut: fp.color_gamut ?? "srgb",
+      hdr: fp.hdr ?? false,
       viewportWidth: fp.viewport_width,
       viewportHeight: fp.viewport_height,
     },
@@ -272,10 +287,33 @@ export function buildEvasionConfig(profile: Profile): EvasionConfig {
       platformVersion: fp.ua_platform_version,
       architecture: fp.ua_architecture,
       bitness: fp.ua_bitness,
+      fullVersion:
+        (fp.ua_reduction ? fp.ua_full_version : null) ??
+        fp.user_agent.split("Chrome/")[1]?.split(" ")[0] ??
+        "",
     },
 
     permissions: fp.permissions,
 
+    intl: fp.intl
+      ? {
+          locale: fp.locale.replace("_", "-"),
+          calendar: fp.intl.calendar,
+          numberingSystem: fp.intl.numbering_system,
+          hourCycle: fp.intl.hour_cycle,
+          firstDayOfWeek: fp.intl.first_day_of_week,
+          weekend: fp.intl.weekend,
+          minimalDays: fp.intl.minimal_days,
+        }
+      : null,
+
+    timing: fp.timing_noise
+      ? {
+          precisionUs: fp.timing_noise.precision_us,
+          eventJitterMs: fp.timing_noise.event_jitter_ms,
+        }
+      : null,
+
     headers: {
       acceptLanguage: fp.accept_language,
     },
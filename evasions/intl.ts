@@ -0,0 +1,132 @@
+// @ts-nocheck
+// ── Manifold evasion: Intl locale data ────────────────────────────────────────
+//
+// The context's `locale` option makes Chromium resolve Intl against the
+// profile's locale, but a few values still come from the bundled ICU build
+// and can drift from what a stock Chrome of the claimed major reports.  This
+// script pins them to the profile's `intl` data (intl.rs):
+//
+//   1. Intl.DateTimeFormat / Intl.NumberFormat resolvedOptions()
+//        calendar, numberingSystem and hourCycle of formatters created for
+//        the default locale without explicit calendar / numbering / clock
+//        options.  Anything the page asked for explicitly is left alone.
+//
+//   2. Intl.Locale.prototype.getWeekInfo() / weekInfo
+//        firstDay, weekend and minimalDays for the profile's locale.
+//
+// Constructors are wrapped in Proxies so `toString()` and `name` stay native.
+
+import type { EvasionConfig } from "./types.js";
+
+// ── Init-script factory ───────────────────────────────────────────────────────
+
+export function intlEvasion(cfg: EvasionConfig): string {
+  const intl = cfg.intl;
+  if (!intl) {
+    return "/* intl evasion: no intl data, disabled */";
+  }
+
+  return /* js */`(function () {
+  'use strict';
+  if (window.__m_intl_patched__) return;
+
+  const _LOCALE     = ${JSON.stringify(intl.locale)};
+  const _CALENDAR   = ${JSON.stringify(intl.calendar)};
+  const _NUMBERING  = ${JSON.stringify(intl.numberingSystem)};
+  const _HOUR_CYCLE = ${JSON.stringify(intl.hourCycle)};
+  const _WEEK = Object.freeze({
+    firstDay: ${intl.firstDayOfWeek},
+    weekend: ${JSON.stringify(intl.weekend)},
+    minimalDays: ${intl.minimalDays},
+  });
+
+  // Formatters created for the default locale, with the options they
+  // left to the locale
+  const _defaults = new WeakMap();
+
+  function _wrapFormat(name, implicit) {
+    const Orig = Intl[name];
+    const wrapped = new Proxy(Orig, {
+      construct(target, args, newTarget) {
+        const inst = Reflect.construct(target, args, newTarget);
+        if (args[0] === undefined) _defaults.set(inst, implicit(args[1] || {}));
+        return inst;
+      },
+      apply(target, thisArg, args) {
+        const inst = Reflect.apply(target, thisArg, args);
+        if (args[0] === undefined) _defaults.set(inst, implicit(args[1] || {}));
+        return inst;
+      },
+    });
+    Object.defineProperty(Intl, name, {
+      value: wrapped, writable: true, enumerable: false, configurable: true,
+    });
+
+    const proto = Orig.prototype;
+    // Keep fmt.constructor pointing at the (wrapped) global
+    Object.defineProperty(proto, 'constructor', {
+      value: wrapped, writable: true, enumerable: false, configurable: true,
+    });
+
+    const origResolved = proto.resolvedOptions;
+    Object.defineProperty(proto, 'resolvedOptions', {
+      value: new Proxy(origResolved, {
+        apply(target, thisArg, args) {
+          const opts = Reflect.apply(target, thisArg, args);
+          const fill = _defaults.get(thisArg);
+          if (!fill || opts.locale !== _LOCALE) return opts;
+          if (fill.calendar && 'calendar' in opts) opts.calendar = _CALENDAR;
+          if (fill.numberingSystem) opts.numberingSystem = _NUMBERING;
+          if (fill.hourCycle && 'hourCycle' in opts) {
+            opts.hourCycle = _HOUR_CYCLE;
+            opts.hour12 = _HOUR_CYCLE === 'h12' || _HOUR_CYCLE === 'h11';
+          }
+          return opts;
+        },
+      }),
+      writable: true, enumerable: false, configurable: true,
+    });
+  }
+
+  _wrapFormat('DateTimeFormat', (o) => ({
+    calendar: o.calendar === undefined,
+    numberingSystem: o.numberingSystem === undefined,
+    hourCycle: o.hourCycle === undefined && o.hour12 === undefined,
+  }));
+  _wrapFormat('NumberFormat', (o) => ({
+    numberingSystem: o.numberingSystem === undefined,
+  }));
+
+  // ── Week info ─────────────────────────────────────────────────────────────
+  const _LocaleProto = Intl.Locale && Intl.Locale.prototype;
+  if (_LocaleProto) {
+    const _isProfileLocale = (loc) => loc.baseName === _LOCALE;
+    const _weekInfo = () => ({ ..._WEEK, weekend: _WEEK.weekend.slice() });
+
+    const origGet = _LocaleProto.getWeekInfo;
+    if (typeof origGet === 'function') {
+      Object.defineProperty(_LocaleProto, 'getWeekInfo', {
+        value: new Proxy(origGet, {
+          apply: (t, thisArg, args) =>
+            _isProfileLocale(thisArg) ? _weekInfo() : Reflect.apply(t, thisArg, args),
+        }),
+        writable: true, enumerable: false, configurable: true,
+      });
+    }
+    const desc = Object.getOwnPropertyDescriptor(_LocaleProto, 'weekInfo');
+    if (desc && desc.get) {
+      Object.defineProperty(_LocaleProto, 'weekInfo', {
+        get: new Proxy(desc.get, {
+          apply: (t, thisArg, args) =>
+            _isProfileLocale(thisArg) ? _weekInfo() : Reflect.apply(t, thisArg, args),
+        }),
+        enumerable: desc.enumerable, configurable: true,
+      });
+    }
+  }
+
+  Object.defineProperty(window, '__m_intl_patched__', {
+    value: true, writable: false, configurable: false,
+  });
+})();`;
+}
@@ -0,0 +1,95 @@
+// @ts-nocheck
+// ── Manifold evasion: timing ──────────────────────────────────────────────────
+//
+// Bezier mouse paths don't help if the clock gives automation away.  Events
+// dispatched over CDP are stamped the moment they are dispatched, so a page
+// comparing `event.timeStamp` with `performance.now()` in the handler sees a
+// near-zero latency that never varies; real input sits in OS and compositor
+// queues for a millisecond or more first.  This script applies the profile's
+// `timing_noise` (fingerprint.rs):
+//
+//   1. performance.now()
+//        floored to `precisionUs`, Chrome's own resolution for pages that
+//        aren't cross-origin isolated.
+//
+//   2. Event.prototype.timeStamp
+//        bucketed the same way; trusted input events (mouse, pointer, wheel,
+//        keyboard, touch) additionally get a seeded latency of up to
+//        `eventJitterMs` taken off.  The latency is a hash of the original
+//        stamp, so the mousemove and pointermove of one input still share a
+//        timestamp, and stamps never run backwards.
+//
+// Both getters are wrapped in Proxies so `toString()` and `name` stay native.
+
+import type { EvasionConfig } from "./types.js";
+
+// ── Init-script factory ───────────────────────────────────────────────────────
+
+export function timingEvasion(cfg: EvasionConfig): string {
+  const timing = cfg.timing;
+  if (!timing) {
+    return "/* timing evasion: no timing noise, disabled */";
+  }
+
+  return /* js */`(function () {
+  'use strict';
+  if (window.__m_timing_patched__) return;
+
+  const _SEED      = ${cfg.seed} >>> 0;
+  const _BUCKET_MS = ${timing.precisionUs} / 1000;
+  const _JITTER_MS = ${timing.eventJitterMs};
+
+  const _bucket = (ms) => Math.floor(ms / _BUCKET_MS) * _BUCKET_MS;
+
+  // Deterministic [0, 1) for an original timestamp
+  function _unit(ms) {
+    let h = (Math.round(ms * 1000) ^ _SEED) >>> 0;
+    h = Math.imul(h ^ (h >>> 16), 0x45d9f3b) >>> 0;
+    h = Math.imul(h ^ (h >>> 16), 0x45d9f3b) >>> 0;
+    return ((h ^ (h >>> 16)) >>> 0) / 4294967296;
+  }
+
+  // ── performance.now ───────────────────────────────────────────────────────
+  const _perfProto = Object.getPrototypeOf(performance);
+  const _origNow = _perfProto.now;
+  Object.defineProperty(_perfProto, 'now', {
+    value: new Proxy(_origNow, {
+      apply: (t, thisArg, args) => _bucket(Reflect.apply(t, thisArg, args)),
+    }),
+    writable: true, enumerable: true, configurable: true,
+  });
+
+  // ── Event.timeStamp ───────────────────────────────────────────────────────
+  const _INPUT = [
+    window.MouseEvent, window.KeyboardEvent, window.TouchEvent,
+  ].filter(Boolean); // PointerEvent and WheelEvent extend MouseEvent
+  const _isInput = (ev) => ev.isTrusted && _INPUT.some((C) => ev instanceof C);
+
+  const _stamped = new WeakMap();
+  let _lastInput = 0;
+
+  const desc = Object.getOwnPropertyDescriptor(Event.prototype, 'timeStamp');
+  if (desc && desc.get) {
+    Object.defineProperty(Event.prototype, 'timeStamp', {
+      get: new Proxy(desc.get, {
+        apply(t, thisArg, args) {
+          const raw = Reflect.apply(t, thisArg, args);
+          if (!_isInput(thisArg)) return _bucket(raw);
+          let ts = _stamped.get(thisArg);
+          if (ts === undefined) {
+            ts = Math.max(_lastInput, _bucket(raw - _unit(raw) * _JITTER_MS));
+            _lastInput = ts;
+            _stamped.set(thisArg, ts);
+          }
+          return ts;
+        },
+      }),
+      enumerable: desc.enumerable, configurable: true,
+    });
+  }
+
+  Object.defineProperty(window, '__m_timing_patched__', {
+    value: true, writable: false, configurable: false,
+  });
+})();`;
+}
@@ -41,7 +41,7 @@
 //   await installTlsGreaseRoute(page, cfg, seed);
 
 import type { Page, Route, Request } from "playwright";
-import type { EvasionConfig } from "./types.js";
+import type { EvasionConfig, HeaderOrderProfile } from "./types.js";
 
 // ── Seeded micro-RNG (xorshift32, same family as other evasions) ──────────────
 
@@ -267,6 +267,20 @@ function reorderToJa4h(
   return result;
 }
 
+/** The order to send `headers` in: the launch config's profile for the
+ *  request's kind (classified like `request_kind` in header_order.rs), or
+ *  the built-in JA4H order without one. */
+function headerOrderFor(
+  headers: Record<string, string>,
+  profile: HeaderOrderProfile | null,
+  isNav: boolean,
+): ReadonlyArray<string> {
+  if (!profile) return isNav ? JA4H_NAV_ORDER : JA4H_SUB_ORDER;
+  if (headers["sec-fetch-mode"] === "navigate") return profile.navigation.http2;
+  if (headers["sec-fetch-dest"] === "empty") return profile.fetch.http2;
+  return profile.subresource.http2;
+}
+
 // ── Extract Chrome major version from UA string ───────────────────────────────
 
 function chromeMajorFromUa(ua: string): number {
@@ -307,7 +321,8 @@ export interface TlsGreaseOptions {
  *  2. Adds the HTTP `Priority` header matching Chrome 124+ behavior
  *  3. Suppresses DNT (Chrome 121+ dropped this header)
  *  4. Optionally suppresses Alt-Svc (prevents QUIC fingerprinting)
- *  5. Reorders all headers to match JA4H canonical Chrome order
+ *  5. Reorders all headers to the launch config's Chrome header order
+ *     (JA4H canonical order without one)
  *
  * Call once per page after `applyAllEvasions()` and `installClientHintsRoute()`.
  */
@@ -421,7 +436,7 @@ export async function installTlsGreaseRoute(
     }
 
     // ── 8. JA4H canonical header reordering ──────────────────────────────
-    const order = isNav ? JA4H_NAV_ORDER : JA4H_SUB_ORDER;
+    const order = headerOrderFor(patched, cfg.headers.order ?? null, isNav);
     const ordered = reorderToJa4h(patched, order);
 
     if (debug && isNav) {
@@ -6,6 +6,8 @@
 
 export type {
   EvasionConfig,
+  HeaderOrderProfile,
+  RequestHeaderProfile,
   UaBrand,
   WebRtcMode,
 } from "../playwright-bridge/types.js";
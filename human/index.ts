@@ -31,6 +31,8 @@ import type { Page, ElementHandle } from "playwright";
 import type {
   HumanBehavior,
   MouseConfig,
+  PathPoint,
+  ScrollTick,
   TypingConfig,
   ScrollConfig,
   MacroBehavior,
@@ -505,6 +507,29 @@ export class HumanMouse {
     this.current = { ...target };
   }
 
+  /** Walk a path generated by the backend (`generate_mouse_path`), keeping
+   *  its timestamps.  The first point is where the move starts; the cursor
+   *  jumps there if it isn't already. */
+  async followPath(points: PathPoint[]): Promise<void> {
+    const { page, entropy } = this;
+    if (points.length === 0) return;
+
+    const start = points[0];
+    if (dist(this.current, start) >= 1) {
+      await page.mouse.move(start.x, start.y);
+    }
+    const t0 = Date.now();
+    let prev = start;
+    for (const p of points.slice(1)) {
+      await sleep(p.t - (Date.now() - t0));
+      await page.mouse.move(p.x, p.y);
+      const dt = p.t - prev.t;
+      if (dt > 0) entropy.recordVelocity((dist(prev, p) / dt) * 1000);
+      prev = p;
+    }
+    this.current = { x: prev.x, y: prev.y };
+  }
+
   /** Move to the center of an element's bounding box. */
   async moveToElement(
     handle: ElementHandle,
@@ -823,6 +848,15 @@ export class HumanScroll {
       }
     }
   }
+
+  /** Replay wheel ticks generated by the backend (`generate_scroll_pattern`)
+   *  at the current cursor position. */
+  async followTicks(ticks: ScrollTick[]): Promise<void> {
+    for (const tick of ticks) {
+      await this.page.mouse.wheel(0, tick.delta_y);
+      await sleep(tick.delay_ms);
+    }
+  }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
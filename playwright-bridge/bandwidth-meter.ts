@@ -0,0 +1,42 @@
+// ── Manifold bridge — bandwidth meter ────────────────────────────────────────
+//
+// Sums the request and response sizes of every finished request in the
+// context, so the backend can charge the traffic to the profile's proxy
+// (bandwidth.rs).  Totals since the previous flush are printed as
+// `BANDWIDTH_USAGE {"up":…,"down":…}` stdout lines.  Profiles behind the
+// TLS bridge are counted there instead and these lines are ignored.
+
+import type { BrowserContext, Request } from "playwright";
+
+/** Mirrors `UsageSample` in src-tauri/src/bandwidth.rs */
+export interface UsageSample {
+  up: number;
+  down: number;
+}
+
+export class BandwidthMeter {
+  private pending: UsageSample = { up: 0, down: 0 };
+
+  constructor(context: BrowserContext) {
+    const count = (req: Request) => {
+      req
+        .sizes()
+        .then((s) => {
+          this.pending.up += s.requestHeadersSize + s.requestBodySize;
+          this.pending.down += s.responseHeadersSize + s.responseBodySize;
+        })
+        .catch(() => {
+          // the page or context closed before the sizes were known
+        });
+    };
+    context.on("requestfinished", count);
+  }
+
+  /** Print and clear the bytes counted since the last flush. */
+  flush(): void {
+    const { up, down } = this.pending;
+    if (up === 0 && down === 0) return;
+    this.pending = { up: 0, down: 0 };
+    process.stdout.write(`BANDWIDTH_USAGE ${JSON.stringify({ up, down })}\n`);
+  }
+}
@@ -0,0 +1,83 @@
+// ── Manifold bridge — dispatched input trace ─────────────────────────────────
+//
+// Records every mouse and keyboard event the bridge sends to the page, by
+// wrapping `page.mouse` / `page.keyboard`, so the backend can audit what
+// actually reached the page rather than what the human layer meant to send
+// (behavior_audit.rs).  Samples are printed in batches as
+// `BEHAVIOR_TRACE [...]` stdout lines.
+
+import type { Page } from "playwright";
+
+/** Mirrors `ActionSample` in src-tauri/src/behavior_audit.rs */
+export interface ActionSample {
+  kind: "move" | "down" | "up" | "wheel" | "key";
+  /** Bridge monotonic time, ms */
+  t: number;
+  x?: number;
+  y?: number;
+}
+
+export class ActionRecorder {
+  private pending: ActionSample[] = [];
+  private x = 0;
+  private y = 0;
+
+  constructor(page: Page) {
+    const { mouse, keyboard } = page;
+
+    const move = mouse.move.bind(mouse);
+    mouse.move = async (x, y, opts) => {
+      this.x = x;
+      this.y = y;
+      this.push("move", true);
+      return move(x, y, opts);
+    };
+    const down = mouse.down.bind(mouse);
+    mouse.down = async (opts) => {
+      this.push("down", true);
+      return down(opts);
+    };
+    const up = mouse.up.bind(mouse);
+    mouse.up = async (opts) => {
+      this.push("up", true);
+      return up(opts);
+    };
+    const wheel = mouse.wheel.bind(mouse);
+    mouse.wheel = async (dx, dy) => {
+      this.push("wheel", true);
+      return wheel(dx, dy);
+    };
+    const press = keyboard.press.bind(keyboard);
+    keyboard.press = async (key, opts) => {
+      this.push("key", false);
+      return press(key, opts);
+    };
+    // type() sends its characters on its own schedule; the gaps are only
+    // known when a delay is given
+    const type = keyboard.type.bind(keyboard);
+    keyboard.type = async (text, opts) => {
+      const start = performance.now();
+      const delay = opts?.delay ?? 0;
+      for (let i = 0; i < text.length; i++) {
+        this.pending.push({ kind: "key", t: start + i * delay });
+      }
+      return type(text, opts);
+    };
+  }
+
+  private push(kind: ActionSample["kind"], at: boolean): void {
+    this.pending.push(
+      at
+        ? { kind, t: performance.now(), x: this.x, y: this.y }
+        : { kind, t: performance.now() },
+    );
+  }
+
+  /** Print and clear the samples recorded since the last flush. */
+  flush(): void {
+    if (this.pending.length === 0) return;
+    const batch = this.pending;
+    this.pending = [];
+    process.stdout.write(`BEHAVIOR_TRACE ${JSON.stringify(batch)}\n`);
+  }
+}
@@ -0,0 +1,131 @@
+// ── Manifold virtual clipboard ────────────────────────────────────────────────
+//
+// Every launched context gets a clipboard of its own.  Copy, cut, paste and
+// `navigator.clipboard` read and write an in-bridge store instead of the
+// host's, so a page never sees what the operator copied elsewhere and
+// nothing copied in the profile leaks to the host or another profile.
+//
+// With `control` set the backend (clipboard.rs) drives the store over stdin,
+// one JSON command per line, each answered by a `CLIPBOARD_RESULT` line:
+// `clipboard_write` replaces the value (and with `paste` types it into the
+// focused field), `clipboard_read` returns it.
+
+import { createInterface } from "node:readline";
+import type { BrowserContext, Page } from "playwright";
+
+/** Mirrors `ClipboardCommand` in src-tauri/src/clipboard.rs */
+type ClipboardCommand =
+  | { type: "clipboard_write"; text: string; paste: boolean }
+  | { type: "clipboard_read" };
+
+interface ClipboardResult {
+  ok: boolean;
+  text?: string;
+  error?: string;
+}
+
+/** The profile's clipboard contents, shared by all of its pages */
+export interface VirtualClipboard {
+  text: string;
+}
+
+/** Runs in every frame; talks to the store through the exposed bindings */
+function clipboardInitScript(): void {
+  const w = window as any;
+  const read = (): Promise<string> => w.__manifold_clipboard_read();
+  const write = (text: string): Promise<void> =>
+    w.__manifold_clipboard_write(String(text));
+
+  const clipboard = navigator.clipboard;
+  if (clipboard) {
+    const readText = () => read();
+    const writeText = (text: string) => write(text);
+    Object.defineProperty(readText, "name", { value: "readText" });
+    Object.defineProperty(writeText, "name", { value: "writeText" });
+    Object.defineProperty(clipboard, "readText", { value: readText });
+    Object.defineProperty(clipboard, "writeText", { value: writeText });
+  }
+
+  const selected = (): string => {
+    const el = document.activeElement as HTMLInputElement | null;
+    if (
+      el &&
+      typeof el.value === "string" &&
+      typeof el.selectionStart === "number" &&
+      typeof el.selectionEnd === "number"
+    ) {
+      return el.value.slice(el.selectionStart, el.selectionEnd);
+    }
+    return String(window.getSelection() ?? "");
+  };
+
+  // The page's own handlers run first; only the default action is replaced
+  for (const kind of ["copy", "cut"] as const) {
+    window.addEventListener(kind, (e: ClipboardEvent) => {
+      if (e.defaultPrevented) return;
+      e.preventDefault();
+      void write(selected());
+      if (kind === "cut") document.execCommand("delete");
+    });
+  }
+  window.addEventListener("paste", (e: ClipboardEvent) => {
+    if (e.defaultPrevented) return;
+    e.preventDefault();
+    void read().then((text) => {
+      if (text) document.execCommand("insertText", false, text);
+    });
+  });
+}
+
+export async function installClipboard(
+  context: BrowserContext,
+): Promise<VirtualClipboard> {
+  const store: VirtualClipboard = { text: "" };
+  await context.exposeBinding("__manifold_clipboard_read", () => store.text);
+  await context.exposeBinding(
+    "__manifold_clipboard_write",
+    (_source, text: string) => {
+      store.text = text;
+    },
+  );
+  await context.addInitScript(clipboardInitScript);
+  return store;
+}
+
+async function perform(
+  page: Page,
+  store: VirtualClipboard,
+  command: ClipboardCommand,
+): Promise<string | undefined> {
+  switch (command.type) {
+    case "clipboard_write":
+      store.text = command.text;
+      if (command.paste) await page.keyboard.insertText(command.text);
+      return undefined;
+    case "clipboard_read":
+      return store.text;
+    default:
+      throw new Error(`unknown clipboard command ${JSON.stringify(command)}`);
+  }
+}
+
+export async function runClipboardCommands(
+  page: Page,
+  store: VirtualClipboard,
+): Promise<void> {
+  const lines = createInterface({ input: process.stdin });
+  for await (const line of lines) {
+    if (!line.trim()) continue;
+    let result: ClipboardResult;
+    try {
+      const text = await perform(page, store, JSON.parse(line));
+      result = text === undefined ? { ok: true } : { ok: true, text };
+    } catch (e) {
+      result = {
+        ok: false,
+        error: e instanceof Error ? e.message : String(e),
+      };
+    }
+    process.stdout.write(`CLIPBOARD_RESULT ${JSON.stringify(result)}\n`);
+  }
+}
@@ -11,7 +11,7 @@
 //   4. Apply all evasions (init scripts + header route interceptor)
 //   5. Install HAR capture route
 //   6. Navigate to initial URL
-//   7. Start WebSocket server
+//   7. Start WebSocket server (handshakes need a ticket, see ws-auth.ts)
 //   8. Broadcast "ready" to the first client that connects
 //   9. Dispatch incoming ClientMessages → page actions
 //  10. Push ServerMessages back to all connected clients
@@ -49,11 +49,23 @@ import { applyAllEvasions, buildEvasionConfig } from "../evasions/index.js";
 import { HumanBehaviorMiddleware } from "../human/index.js";
 
 import { LoginRunner } from "./login-runner.js";
+import { runLeakProbe } from "./leak-probe.js";
+import { runPreflight } from "./preflight.js";
+import { runWorkflowSteps } from "./workflow.js";
+import { installClipboard, runClipboardCommands } from "./clipboard.js";
+import type { VirtualClipboard } from "./clipboard.js";
+import { ActionRecorder } from "./behavior-trace.js";
+import { BandwidthMeter } from "./bandwidth-meter.js";
+import { HandshakeAuth, wsTlsServer } from "./ws-auth.js";
+import { BRIDGE_PROTOCOL, LAUNCH_CONFIG_KEYS } from "./types.js";
 
 // ── Constants ─────────────────────────────────────────────────────────────────
 
 const DEFAULT_WS_PORT = 8766;
 const ENTROPY_SCRIPT_INTERVAL_MS = 30_000; // capture entropy every 30 s
+const CLOCK_SAMPLE_INTERVAL_MS = 15_000; // clock drift samples for the backend
+const BEHAVIOR_TRACE_INTERVAL_MS = 10_000; // dispatched input for the audit
+const BANDWIDTH_FLUSH_INTERVAL_MS = 30_000; // traffic for bandwidth accounting
 
 // ── Parse launch config ───────────────────────────────────────────────────────
 
@@ -62,6 +74,7 @@ function parseLaunchConfig(): LaunchConfig {
   if (!raw) {
     // Default config for dev mode
     return {
+      protocol: BRIDGE_PROTOCOL,
       profile: {
         id: "dev",
         name: "Dev Profile",
@@ -155,13 +168,35 @@ function parseLaunchConfig(): LaunchConfig {
       proxy: null,
       url: "https://www.google.com",
       wsPort: 8766,
+      // start_bridge passes the secret in the environment instead
+      wsSecret: process.env["MANIFOLD_BRIDGE_SECRET"],
     };
   }
+  let cfg: LaunchConfig;
   try {
-    return JSON.parse(raw) as LaunchConfig;
+    cfg = JSON.parse(raw) as LaunchConfig;
   } catch (e) {
     throw new Error(`Failed to parse MANIFOLD_LAUNCH_CONFIG: ${String(e)}`);
   }
+  checkLaunchConfig(cfg);
+  return cfg;
+}
+
+/**
+ * Fail loudly on a config from a different backend build or with keys this
+ * bridge doesn't know, instead of silently ignoring them.
+ */
+function checkLaunchConfig(cfg: LaunchConfig): void {
+  if (cfg.protocol !== BRIDGE_PROTOCOL) {
+    throw new Error(
+      `MANIFOLD_LAUNCH_CONFIG is protocol ${String(cfg.protocol)}, bridge speaks ${BRIDGE_PROTOCOL}`,
+    );
+  }
+  const known = new Set<string>(LAUNCH_CONFIG_KEYS);
+  const unknown = Object.keys(cfg).filter((k) => !known.has(k));
+  if (unknown.length > 0) {
+    throw new Error(`MANIFOLD_LAUNCH_CONFIG has unknown keys: ${unknown.join(", ")}`);
+  }
 }
 
 // ── Entropy capture script ────────────────────────────────────────────────────
@@ -288,7 +323,10 @@ interface BridgeSession {
   context: BrowserContext;
   page: Page;
   human: HumanBehaviorMiddleware;
+  actions: ActionRecorder;
+  bandwidth: BandwidthMeter;
   harEntries: HarEntry[];
+  clipboard: VirtualClipboard;
   entropyLogs: EntropyLog[];
   entropyTimer: ReturnType<typeof setInterval> | null;
   alive: boolean;
@@ -391,6 +429,9 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
   const { profile, proxy, url } = cfg;
   const fp = profile.fingerprint;
   const evasionCfg: EvasionConfig = buildEvasionConfig(profile);
+  evasionCfg.headers.request = cfg.requestHeaders ?? null;
+  evasionCfg.headers.order = cfg.headerOrder ?? null;
+  evasionCfg.screen.window = cfg.window ?? null;
   const sessionId = crypto.randomUUID();
 
   // ── Chromium launch args (enhanced anti-detection) ──────────────────────
@@ -416,12 +457,19 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
     "--disable-notifications",
     `--lang=${fp.locale.replace("_", "-")}`,
     `--window-size=${fp.screen_width},${fp.screen_height}`,
+    ...(cfg.window ? [`--window-position=${cfg.window.x},${cfg.window.y}`] : []),
+    ...(cfg.extraArgs ?? []),
   ];
 
+  // Headless runs the full browser's new headless mode rather than the
+  // headless shell; a virtual display is a headed browser on Xvfb
+  const launchMode = cfg.launchMode ?? "headless_new";
   const browser = await chromium.launch({
-    headless: true,
+    headless: launchMode === "headless_new",
+    ...(launchMode === "headless_new" ? { channel: "chromium" } : {}),
     args: launchArgs,
     ignoreDefaultArgs: ["--enable-automation"],
+    ...(cfg.display ? { env: { ...process.env, DISPLAY: cfg.display } } : {}),
   });
 
   // ── Browser context ─────────────────────────────────────────────────────
@@ -434,7 +482,9 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
     userAgent: fp.user_agent,
     locale: fp.locale.replace("_", "-"),
     timezoneId: fp.timezone,
-    colorScheme: "dark",
+    colorScheme: fp.prefers_color_scheme ?? "dark",
+    reducedMotion: fp.prefers_reduced_motion ? "reduce" : "no-preference",
+    forcedColors: fp.forced_colors ? "active" : "none",
     acceptDownloads: true,
     ignoreHTTPSErrors: false,
     extraHTTPHeaders: {
@@ -468,6 +518,9 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
     (route) => route.abort(),
   );
 
+  // ── Virtual clipboard: keeps the host clipboard out of the profile ──────
+  const clipboard = await installClipboard(context);
+
   const page = await context.newPage();
 
   // ── Apply evasions ──────────────────────────────────────────────────────
@@ -492,6 +545,10 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
   installHarCapture(page, harEntries);
 
   // ── Human behavior middleware ────────────────────────────────────────────
+  // The recorder wraps page.mouse / page.keyboard first so it sees every
+  // event, including those sent by `execute` scripts
+  const actions = new ActionRecorder(page);
+  const bandwidth = new BandwidthMeter(context);
   const human = new HumanBehaviorMiddleware(page, profile.human, fp.seed);
 
   // ── Initial navigation ──────────────────────────────────────────────────
@@ -516,7 +573,10 @@ async function launchSession(cfg: LaunchConfig): Promise<BridgeSession> {
     context,
     page,
     human,
+    actions,
+    bandwidth,
     harEntries,
+    clipboard,
     entropyLogs: [],
     entropyTimer: null,
     alive: true,
@@ -667,6 +727,34 @@ async function dispatch(
       return;
     }
 
+    // ── Generated mouse path ──────────────────────────────────────────────
+    case "move_path": {
+      try {
+        await human.mouse.followPath(msg.points);
+      } catch (e) {
+        send(ws, {
+          type: "error",
+          sessionId,
+          error: `move_path failed: ${String(e).slice(0, 200)}`,
+        });
+      }
+      return;
+    }
+
+    // ── Generated scroll ──────────────────────────────────────────────────
+    case "scroll_ticks": {
+      try {
+        await human.scroll.followTicks(msg.ticks);
+      } catch (e) {
+        send(ws, {
+          type: "error",
+          sessionId,
+          error: `scroll_ticks failed: ${String(e).slice(0, 200)}`,
+        });
+      }
+      return;
+    }
+
     // ── Extract text content ──────────────────────────────────────────────
     case "extract": {
       try {
@@ -1022,6 +1110,8 @@ async function teardown(
   }
 
   broadcast(clients, { type: "stopped", sessionId: session.sessionId });
+  session.actions.flush();
+  session.bandwidth.flush();
 
   try {
     await session.context.close();
@@ -1041,6 +1131,16 @@ async function main(): Promise<void> {
   // 1. Parse config
   const cfg = parseLaunchConfig();
   const wsPort = cfg.wsPort ?? DEFAULT_WS_PORT;
+  // Loopback only unless the backend exposes this bridge (agent nodes)
+  const wsHost = cfg.wsHost ?? "127.0.0.1";
+  // Before the browser starts: a missing certificate is a launch failure
+  let tls: ReturnType<typeof wsTlsServer> | null = null;
+  try {
+    tls = cfg.wsTls ? wsTlsServer() : null;
+  } catch (e) {
+    console.error("[bridge] no TLS for the WebSocket:", e);
+    process.exit(1);
+  }
 
   console.log(`[bridge] starting — profile=${cfg.profile.id} port=${wsPort}`);
 
@@ -1055,13 +1155,50 @@ async function main(): Promise<void> {
     process.exit(1);
   }
 
-  // 3. Start WebSocket server
-  const wss = new WebSocketServer({ port: wsPort });
+  // Leak-test mode: report what the page sees on stdout and exit
+  if (cfg.leakProbe) {
+    const probe = await runLeakProbe(
+      session.browser,
+      session.page,
+      cfg.engineQuirks ?? null,
+    );
+    process.stdout.write(`LEAK_PROBE ${JSON.stringify(probe)}\n`);
+    await teardown(session, new Set());
+    process.exit(0);
+  }
+
+  // Preflight mode: leak probe plus the workflow's page, then exit
+  if (cfg.preflight) {
+    const report = await runPreflight(
+      session.browser,
+      session.page,
+      cfg.engineQuirks ?? null,
+      cfg.preflight,
+    );
+    process.stdout.write(`PREFLIGHT ${JSON.stringify(report)}\n`);
+    await teardown(session, new Set());
+    process.exit(0);
+  }
+
+  // 3. Start WebSocket server; handshakes need a ticket for this launch
+  const auth = new HandshakeAuth(cfg.wsSecret);
+  const wss = new WebSocketServer({
+    ...(tls ? { server: tls } : { host: wsHost, port: wsPort }),
+    verifyClient: (info, done) => {
+      const ok = auth.verify(info.req);
+      if (!ok) console.warn("[bridge] refused handshake without a valid ticket");
+      done(ok, 401, "Unauthorized");
+    },
+  });
+  tls?.listen(wsPort, wsHost);
   const clients = new Set<WsSocket>();
 
   console.log(
-    `[bridge] WebSocket server listening on ws://localhost:${wsPort}`,
+    `[bridge] WebSocket server listening on ${tls ? "wss" : "ws"}://${wsHost}:${wsPort}`,
   );
+  if (!auth.enabled) {
+    console.warn("[bridge] no wsSecret — any local process can connect");
+  }
 
   // 4. Start periodic entropy capture
   session.entropyTimer = setInterval(async () => {
@@ -1072,6 +1209,28 @@ async function main(): Promise<void> {
   // Also capture entropy once on first page load (with a slight delay)
   setTimeout(() => captureEntropy(session, clients), 3_000);
 
+  // Clock samples: the backend compares wall vs monotonic deltas between
+  // them to flag host clock jumps (suspend/resume, VM snapshot restore)
+  setInterval(async () => {
+    if (!session.alive) return;
+    const page = await session.page
+      .evaluate(() => ({ wall: Date.now(), perf: performance.now() }))
+      .catch(() => null);
+    const sample = {
+      wallMs: Date.now(),
+      monoMs: performance.now(),
+      pageWallMs: page?.wall ?? null,
+      pagePerfMs: page?.perf ?? null,
+    };
+    process.stdout.write(`CLOCK_SAMPLE ${JSON.stringify(sample)}\n`);
+  }, CLOCK_SAMPLE_INTERVAL_MS);
+
+  // Dispatched input, audited by the backend when the session ends
+  setInterval(() => session.actions.flush(), BEHAVIOR_TRACE_INTERVAL_MS);
+
+  // Traffic, charged to the profile's proxy by the backend
+  setInterval(() => session.bandwidth.flush(), BANDWIDTH_FLUSH_INTERVAL_MS);
+
   // 5. Handle connections
   wss.on("connection", (ws: WsSocket) => {
     clients.add(ws);
@@ -1177,6 +1336,22 @@ async function main(): Promise<void> {
 
   // 9. Emit process-level ready signal (read by Tauri sidecar manager)
   process.stdout.write("BRIDGE_READY\n");
+
+  // Workflow mode: perform the backend's steps until it closes stdin.  The
+  // live view stays up so the operator can act during manual interventions
+  if (cfg.workflow) {
+    void runWorkflowSteps(session.page, session.harEntries).then(() =>
+      shutdown("end of workflow"),
+    );
+  }
+
+  // Launched profiles take clipboard commands from the backend on stdin.
+  // Its end means the backend let go (or the SSH session to a node dropped)
+  if (cfg.control) {
+    void runClipboardCommands(session.page, session.clipboard).then(() =>
+      shutdown("end of control input"),
+    );
+  }
 }
 
 main().catch((e) => {
@@ -0,0 +1,149 @@
+// ── Manifold leak probe ───────────────────────────────────────────────────────
+//
+// Run by the bridge when the launch config has `leakProbe: true`.  Collects
+// what a site could see from inside the page — ICE candidate addresses, the
+// exit IP and its geo, the DNS resolver's geo, the effective timezone, UA,
+// UA-CH and the JS engine values in the launch config's `engineQuirks` —
+// and hands it back to the backend, which judges it (leak_test.rs).
+//
+// Everything goes through the page's own network stack so the proxy, the
+// TLS bridge and the evasions all apply exactly as they would for a site.
+
+import type { Browser, Page } from "playwright";
+
+import type { EngineQuirks, MathProbe } from "./types.js";
+
+export interface EngineObservation {
+  math: MathProbe[];
+  stack: string;
+  stackTraceLimit: number;
+  builtins: string[];
+  calendar: string;
+  numberingSystem: string;
+  hourCycle: string;
+}
+
+export interface LeakProbe {
+  webrtcIps: string[];
+  exitIp: string | null;
+  exitCountry: string | null;
+  exitCountryCode: string | null;
+  exitUtcOffsetSecs: number | null;
+  exitRegion: string | null;
+  exitRegionName: string | null;
+  exitCity: string | null;
+  resolverIp: string | null;
+  resolverCountry: string | null;
+  timezone: string;
+  utcOffsetSecs: number;
+  userAgent: string;
+  uaBrands: { brand: string; version: string }[];
+  uaPlatform: string;
+  browserVersion: string;
+  engine: EngineObservation | null;
+}
+
+/** Exit IP geo; ip-api's free endpoint is plain HTTP. */
+const EXIT_GEO_URL =
+  "http://ip-api.com/json/?fields=status,query,country,countryCode,region,regionName,city,offset";
+/** Resolves a fresh subdomain and reports which resolver asked for it. */
+const resolverGeoUrl = (): string =>
+  `http://${Math.random().toString(36).slice(2, 14)}.edns.ip-api.com/json`;
+const ICE_GATHER_MS = 3_000;
+
+async function fetchJson(page: Page, url: string): Promise<any | null> {
+  try {
+    const res = await page.goto(url, { waitUntil: "load", timeout: 20_000 });
+    return res ? await res.json() : null;
+  } catch {
+    return null;
+  }
+}
+
+/** Read the values `quirks` has expectations for from inside the page. */
+async function observeEngine(
+  page: Page,
+  quirks: EngineQuirks,
+): Promise<EngineObservation> {
+  return page.evaluate((q: EngineQuirks) => {
+    const has = (path: string): boolean => {
+      let obj: any = globalThis;
+      for (const key of path.split(".")) {
+        if (obj == null || !(key in Object(obj))) return false;
+        obj = obj[key];
+      }
+      return obj !== undefined;
+    };
+    const hourCycle = new Intl.DateTimeFormat(undefined, { hour: "numeric" })
+      .resolvedOptions().hourCycle;
+    return {
+      math: q.math.map(({ func, arg }) => ({
+        func,
+        arg,
+        value: (Math as any)[func](arg),
+      })),
+      stack: new Error("probe").stack ?? "",
+      stackTraceLimit: (Error as any).stackTraceLimit ?? 0,
+      builtins: [...q.builtins_present, ...q.builtins_absent].filter(has),
+      calendar: Intl.DateTimeFormat().resolvedOptions().calendar,
+      numberingSystem: new Intl.NumberFormat().resolvedOptions().numberingSystem,
+      hourCycle: hourCycle ?? "",
+    };
+  }, quirks);
+}
+
+export async function runLeakProbe(
+  browser: Browser,
+  page: Page,
+  quirks: EngineQuirks | null,
+): Promise<LeakProbe> {
+  const exit = await fetchJson(page, EXIT_GEO_URL);
+  const edns = await fetchJson(page, resolverGeoUrl());
+  // "United States - Google LLC"
+  const resolverGeo: string | undefined = edns?.dns?.geo;
+
+  const inPage = await page.evaluate(async (gatherMs: number) => {
+    const ips = new Set<string>();
+    try {
+      const pc = new RTCPeerConnection({
+        iceServers: [{ urls: "stun:stun.l.google.com:19302" }],
+      });
+      pc.createDataChannel("probe");
+      pc.onicecandidate = (e) => {
+        const addr = e.candidate?.candidate.split(" ")[4];
+        if (addr && !addr.endsWith(".local")) ips.add(addr);
+      };
+      await pc.setLocalDescription(await pc.createOffer());
+      await new Promise((r) => setTimeout(r, gatherMs));
+      pc.close();
+    } catch {
+      // WebRTC blocked outright — nothing to leak
+    }
+    const uaData = (navigator as any).userAgentData;
+    return {
+      webrtcIps: Array.from(ips),
+      timezone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+      utcOffsetSecs: -new Date().getTimezoneOffset() * 60,
+      userAgent: navigator.userAgent,
+      uaBrands: uaData?.brands ?? [],
+      uaPlatform: uaData?.platform ?? "",
+    };
+  }, ICE_GATHER_MS);
+  const engine = quirks ? await observeEngine(page, quirks) : null;
+
+  const ok = exit?.status === "success";
+  return {
+    ...inPage,
+    exitIp: ok ? exit.query : null,
+    exitCountry: ok ? exit.country : null,
+    exitCountryCode: ok ? exit.countryCode : null,
+    exitUtcOffsetSecs: ok ? exit.offset : null,
+    exitRegion: ok ? exit.region || null : null,
+    exitRegionName: ok ? exit.regionName || null : null,
+    exitCity: ok ? exit.city || null : null,
+    resolverIp: edns?.dns?.ip ?? null,
+    resolverCountry: resolverGeo ? resolverGeo.split(" - ")[0] : null,
+    browserVersion: browser.version(),
+    engine,
+  };
+}
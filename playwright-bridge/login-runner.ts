@@ -580,7 +580,9 @@ async function launchBrowser(
     userAgent: fp.user_agent,
     locale: fp.locale.replace("_", "-"),
     timezoneId: fp.timezone,
-    colorScheme: "dark",
+    colorScheme: fp.prefers_color_scheme ?? "dark",
+    reducedMotion: fp.prefers_reduced_motion ? "reduce" : "no-preference",
+    forcedColors: fp.forced_colors ? "active" : "none",
     acceptDownloads: false,
     ignoreHTTPSErrors: false,
     extraHTTPHeaders: { "Accept-Language": fp.accept_language },
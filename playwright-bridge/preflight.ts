@@ -0,0 +1,75 @@
+// ── Manifold preflight ────────────────────────────────────────────────────────
+//
+// Run by the bridge when the launch config has a `preflight` plan.  Runs the
+// leak probe, then opens the plan's start URL and counts what each of the
+// workflow's selectors matches there, and hands all of it back to the
+// backend (preflight.rs), which decides whether the profile is ready.  The
+// browser is headless and the live view is never started.
+
+import type { Browser, Page } from "playwright";
+
+import { runLeakProbe } from "./leak-probe.js";
+import type { LeakProbe } from "./leak-probe.js";
+import type { EngineQuirks, PreflightPlan } from "./types.js";
+
+export interface SelectorCheck {
+  selector: string;
+  count: number;
+  error?: string;
+}
+
+export interface PreflightResult {
+  leak: LeakProbe;
+  status: number | null;
+  finalUrl: string | null;
+  error: string | null;
+  selectors: SelectorCheck[];
+}
+
+const NAVIGATION_TIMEOUT_MS = 30_000;
+
+export async function runPreflight(
+  browser: Browser,
+  page: Page,
+  quirks: EngineQuirks | null,
+  plan: PreflightPlan,
+): Promise<PreflightResult> {
+  const leak = await runLeakProbe(browser, page, quirks);
+
+  let status: number | null = null;
+  let error: string | null = null;
+  try {
+    const res = await page.goto(plan.url, {
+      waitUntil: "load",
+      timeout: NAVIGATION_TIMEOUT_MS,
+    });
+    status = res?.status() ?? null;
+  } catch (e) {
+    error = e instanceof Error ? e.message : String(e);
+  }
+
+  const selectors: SelectorCheck[] = [];
+  for (const selector of plan.selectors) {
+    if (error) {
+      selectors.push({ selector, count: 0, error: "page did not load" });
+      continue;
+    }
+    try {
+      selectors.push({ selector, count: await page.locator(selector).count() });
+    } catch (e) {
+      selectors.push({
+        selector,
+        count: 0,
+        error: e instanceof Error ? e.message : String(e),
+      });
+    }
+  }
+
+  return {
+    leak,
+    status,
+    finalUrl: error ? null : page.url(),
+    error,
+    selectors,
+  };
+}
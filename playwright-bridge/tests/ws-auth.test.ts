@@ -0,0 +1,43 @@
+// ── Tests: playwright-bridge/ws-auth ─────────────────────────────────────────
+//
+// Covers:
+//   • HandshakeAuth — signed tickets, replayed nonces, wrong secrets, dev mode
+
+import { describe, it, expect } from "vitest";
+import { createHmac } from "node:crypto";
+import type { IncomingMessage } from "node:http";
+import { HandshakeAuth } from "../ws-auth.js";
+
+const SECRET = "ab".repeat(32);
+
+function handshake(path: string): IncomingMessage {
+  return { url: path } as IncomingMessage;
+}
+
+function ticket(secret: string, nonce: string): string {
+  const sig = createHmac("sha256", secret).update(nonce).digest("hex");
+  return `/?nonce=${nonce}&sig=${sig}`;
+}
+
+describe("HandshakeAuth", () => {
+  it("accepts a signed ticket once", () => {
+    const auth = new HandshakeAuth(SECRET);
+    const path = ticket(SECRET, "0f".repeat(16));
+    expect(auth.verify(handshake(path))).toBe(true);
+    expect(auth.verify(handshake(path))).toBe(false);
+    expect(auth.verify(handshake(ticket(SECRET, "1e".repeat(16))))).toBe(true);
+  });
+
+  it("refuses missing, malformed and foreign tickets", () => {
+    const auth = new HandshakeAuth(SECRET);
+    expect(auth.verify(handshake("/"))).toBe(false);
+    expect(auth.verify(handshake("/?nonce=aa&sig=zz"))).toBe(false);
+    expect(auth.verify(handshake(ticket("cd".repeat(32), "aa")))).toBe(false);
+  });
+
+  it("lets everything through without a secret", () => {
+    const auth = new HandshakeAuth(undefined);
+    expect(auth.enabled).toBe(false);
+    expect(auth.verify(handshake("/"))).toBe(true);
+  });
+});
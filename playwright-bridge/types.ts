@@ -17,6 +17,64 @@ export interface UaBrand {
 
 export type WebRtcMode = "block" | "fake_mdns" | "passthrough";
 
+export type ColorGamut = "srgb" | "p3";
+
+export type ColorScheme = "light" | "dark";
+
+/** Mirrors `IntlProfile` in src-tauri/src/intl.rs */
+export interface IntlProfile {
+  calendar: string;
+  numbering_system: string;
+  hour_cycle: string;
+  /** 1 = Monday … 7 = Sunday */
+  first_day_of_week: number;
+  weekend: number[];
+  minimal_days: number;
+  currency: string;
+  /** The locale's format of 1234.56 in `currency` */
+  currency_sample: string;
+}
+
+/** Mirrors `TimingNoise` in src-tauri/src/fingerprint.rs */
+export interface TimingNoise {
+  precision_us: number;
+  /** Upper bound of the latency taken off input events' timeStamp */
+  event_jitter_ms: number;
+}
+
+/** Mirrors `ScreenLayout` in src-tauri/src/fingerprint.rs */
+export interface ScreenLayout {
+  monitors: number;
+  /** Top-left of the browser's monitor on the desktop */
+  avail_left: number;
+  avail_top: number;
+  /** Inclusive ranges each session's screenX / screenY are drawn from */
+  window_x: [number, number];
+  window_y: [number, number];
+}
+
+/** Mirrors `WindowPlacement` in src-tauri/src/fingerprint.rs */
+export interface WindowPlacement {
+  x: number;
+  y: number;
+  /** At the monitor's origin, sized to its available area */
+  maximized: boolean;
+}
+
+export type TextAntialiasing = "subpixel" | "grayscale";
+
+/** Emoji / glyph rendering of the claimed OS build */
+export interface TextRendering {
+  emoji_font: string;
+  /** Newest Unicode Emoji version the emoji font covers, e.g. "15.0" */
+  emoji_version: string;
+  antialiasing: TextAntialiasing;
+  /** Multiplier applied to measureText widths */
+  measure_scale: number;
+  /** Vertical glyph offset in px */
+  baseline_shift: number;
+}
+
 export interface Fingerprint {
   seed: number;
   // Canvas
@@ -29,6 +87,8 @@ export interface Fingerprint {
   audio_noise: number;
   // Fonts
   font_subset: string[];
+  // Null for profiles created before text rendering was modelled
+  text_rendering?: TextRendering | null;
   // Navigator
   user_agent: string;
   platform: string;
@@ -40,8 +100,20 @@ export interface Fingerprint {
   screen_height: number;
   viewport_width: number;
   viewport_height: number;
+  /** Viewport varies slightly from session to session */
+  window_variation?: boolean;
+  /** Multi-monitor desktop; absent keeps a maximised window on the primary */
+  screen_layout?: ScreenLayout | null;
   color_depth: number;
   pixel_ratio: number;
+  color_gamut?: ColorGamut;
+  hdr?: boolean;
+  // Media preferences (absent on profiles created before they were seeded)
+  prefers_color_scheme?: ColorScheme;
+  prefers_reduced_motion?: boolean;
+  forced_colors?: boolean;
+  // Timing
+  timing_noise?: TimingNoise | null;
   // WebRTC
   webrtc_mode: WebRtcMode;
   webrtc_fake_mdns: string | null;
@@ -49,6 +121,7 @@ export interface Fingerprint {
   // Locale
   timezone: string;
   locale: string;
+  intl?: IntlProfile | null;
   // UA-CH
   ua_brands: UaBrand[];
   ua_mobile: boolean;
@@ -56,6 +129,10 @@ export interface Fingerprint {
   ua_platform_version: string;
   ua_architecture: string;
   ua_bitness: string;
+  /** The UA string has the frozen `<major>.0.0.0` version */
+  ua_reduction?: boolean;
+  /** Full Chrome version while `ua_reduction` is on */
+  ua_full_version?: string | null;
   // Permissions
   permissions: Record<string, string>;
 }
@@ -74,6 +151,20 @@ export interface MouseConfig {
   micro_jitter_prob: number;
 }
 
+/** One step of a generated move (motion.rs); `t` is ms since its start */
+export interface PathPoint {
+  x: number;
+  y: number;
+  t: number;
+}
+
+/** One wheel event of a generated scroll (motion.rs) */
+export interface ScrollTick {
+  delta_y: number;
+  /** Wait after the tick */
+  delay_ms: number;
+}
+
 export interface TypingConfig {
   base_wpm: number;
   wpm_jitter: number;
@@ -123,6 +214,13 @@ export interface HumanBehavior {
 
 export type ProfileStatus = "idle" | "running" | "error";
 
+export interface Persona {
+  name: string;
+  age_bracket?: "18-24" | "25-34" | "35-44" | "45-54" | "55+";
+  interests?: string[];
+  home_city?: string | null;
+}
+
 export interface Profile {
   id: string;
   name: string;
@@ -137,6 +235,10 @@ export interface Profile {
   data_dir: string;
   /** Enable TLS bridge for JA4 fingerprinting control */
   tls_bridge?: boolean;
+  /** Let scheduled aging bump the Chrome version */
+  auto_age?: boolean;
+  /** Who the profile is; drives warm-up plans and locale */
+  persona?: Persona | null;
 }
 
 // ── Proxy ─────────────────────────────────────────────────────────────────────
@@ -145,19 +247,136 @@ export type ProxyType = "http" | "https" | "socks5";
 
 export interface ProxyConfig {
   server: string; // e.g. "http://host:port" or "socks5://host:port"
-  username?: string;
-  password?: string;
+  username?: string | null;
+  password?: string | null;
 }
 
 // ── Launch config (env var MANIFOLD_LAUNCH_CONFIG) ────────────────────────────
 
+/**
+ * Launch-config / WebSocket protocol revision.  Must match BRIDGE_PROTOCOL in
+ * src-tauri/src/bridge_locator.rs; shipped bridges record it in their
+ * bridge-manifest.json (scripts/bridge-manifest.ts).
+ */
+export const BRIDGE_PROTOCOL = 1;
+
+/**
+ * Mirrors `LaunchConfig` in src-tauri/src/launch_config.rs; keep the two and
+ * LAUNCH_CONFIG_KEYS in step.
+ */
 export interface LaunchConfig {
+  /** BRIDGE_PROTOCOL of the backend that wrote the config */
+  protocol: number;
   profile: Profile;
   proxy: ProxyConfig | null;
   url: string;
   wsPort: number;
+  /** Address the WebSocket listens on; 127.0.0.1 when absent (set for exposed agent nodes) */
+  wsHost?: string;
+  /** This launch's WebSocket secret; handshakes must carry a ticket signed by it (ws-auth.ts) */
+  wsSecret?: string;
+  /** Serve wss:// with the node's certificate (set whenever wsHost is off loopback) */
+  wsTls?: boolean;
   /** TLS bridge port when profile.tls_bridge is enabled */
   tlsBridgePort?: number;
+  /** Additional Chromium flags (e.g. --disable-quic behind the TLS bridge) */
+  extraArgs?: string[];
+  /** Chrome's request header order/casing for the profile's major */
+  headerOrder?: HeaderOrderProfile;
+  /** Chrome's Accept / Accept-Encoding / fetch metadata values for the profile's major */
+  requestHeaders?: RequestHeaderProfile;
+  /** Engine values the page should show for the profile's major and locale */
+  engineQuirks?: EngineQuirks;
+  /** Probe for leaks, print a LEAK_PROBE line and exit (run_leak_test) */
+  leakProbe?: boolean;
+  /** Probe for leaks, check the plan's page, print a PREFLIGHT line and exit (preflight_profile) */
+  preflight?: PreflightPlan;
+  /** Perform workflow steps from stdin, answering each with a STEP_RESULT line (run_workflow) */
+  workflow?: boolean;
+  /** Take clipboard commands from stdin, answering each with a CLIPBOARD_RESULT line (launch_profile) */
+  control?: boolean;
+  /** How the browser is shown (launch_mode.rs); headless_new when absent */
+  launchMode?: "headed" | "headless_new" | "virtual_display";
+  /** X display of a virtual display launch, e.g. ":99" */
+  display?: string;
+  /** Where the window opens this session (profiles with a screen layout) */
+  window?: WindowPlacement;
+}
+
+/** Mirrors `PreflightPlan` in src-tauri/src/preflight.rs */
+export interface PreflightPlan {
+  url: string;
+  selectors: string[];
+}
+
+/** Every key a LaunchConfig may have; anything else is rejected at startup */
+export const LAUNCH_CONFIG_KEYS: ReadonlyArray<keyof LaunchConfig> = [
+  "protocol",
+  "profile",
+  "proxy",
+  "url",
+  "wsPort",
+  "wsHost",
+  "wsSecret",
+  "wsTls",
+  "tlsBridgePort",
+  "extraArgs",
+  "headerOrder",
+  "requestHeaders",
+  "engineQuirks",
+  "leakProbe",
+  "preflight",
+  "workflow",
+  "control",
+  "launchMode",
+  "display",
+  "window",
+];
+
+export interface HeaderOrder {
+  http2: string[];
+  http1: string[];
+}
+
+export interface HeaderOrderProfile {
+  chrome_major: number | null;
+  pseudo_headers: string[];
+  navigation: HeaderOrder;
+  fetch: HeaderOrder;
+  subresource: HeaderOrder;
+}
+
+export interface FetchMetadata {
+  site: string;
+  mode: string;
+  user: string | null;
+  dest: string;
+}
+
+/** Mirrors `RequestHeaderProfile` in src-tauri/src/request_headers.rs */
+export interface RequestHeaderProfile {
+  chrome_major: number | null;
+  accept_encoding: string;
+  /** sec-fetch-dest → Accept */
+  accept: Record<string, string>;
+  navigation: FetchMetadata;
+}
+
+export interface MathProbe {
+  func: string;
+  arg: number;
+  value: number;
+}
+
+/** Mirrors `EngineQuirks` in src-tauri/src/engine_quirks.rs */
+export interface EngineQuirks {
+  chrome_major: number | null;
+  math: MathProbe[];
+  stack_prefix: string;
+  stack_trace_limit: number;
+  builtins_present: string[];
+  builtins_absent: string[];
+  intl: IntlProfile;
 }
 
 // ── WebSocket protocol ────────────────────────────────────────────────────────
@@ -172,6 +391,8 @@ export type ClientMessage =
   | { type: "click"; sessionId: string; selector: string }
   | { type: "type"; sessionId: string; selector: string; text: string }
   | { type: "scroll"; sessionId: string; selector: string; deltaY: number }
+  | { type: "move_path"; sessionId: string; points: PathPoint[] }
+  | { type: "scroll_ticks"; sessionId: string; ticks: ScrollTick[] }
   | { type: "extract"; sessionId: string; selector: string }
   | { type: "har_export"; sessionId: string }
   | { type: "stop"; sessionId: string }
@@ -333,7 +554,7 @@ export interface SessionRecording {
 
 export interface EvasionConfig {
   seed: number;
-  canvas: { noiseLevel: number };
+  canvas: { noiseLevel: number; text: TextRendering | null };
   webgl: { vendor: string; renderer: string; noiseLevel: number };
   audio: { noiseLevel: number };
   fonts: { subset: string[] };
@@ -349,8 +570,16 @@ export interface EvasionConfig {
     height: number;
     availWidth: number;
     availHeight: number;
+    availLeft: number;
+    availTop: number;
+    /** screen.isExtended: more than one monitor */
+    isExtended: boolean;
+    /** The session's window; null is maximised on the primary monitor */
+    window: WindowPlacement | null;
     colorDepth: number;
     pixelRatio: number;
+    colorGamut: ColorGamut;
+    hdr: boolean;
     viewportWidth: number;
     viewportHeight: number;
   };
@@ -366,9 +595,27 @@ export interface EvasionConfig {
     platformVersion: string;
     architecture: string;
     bitness: string;
+    /** Full Chrome version for fullVersionList / uaFullVersion */
+    fullVersion: string;
   };
   permissions: Record<string, string>;
+  /** Null for profiles without Intl data */
+  intl: {
+    locale: string;
+    calendar: string;
+    numberingSystem: string;
+    hourCycle: string;
+    firstDayOfWeek: number;
+    weekend: number[];
+    minimalDays: number;
+  } | null;
+  /** Null leaves performance.now() and event timestamps native */
+  timing: { precisionUs: number; eventJitterMs: number } | null;
   headers: {
     acceptLanguage: string;
+    /** Version-specific values from the launch config; null keeps Playwright's */
+    request?: RequestHeaderProfile | null;
+    /** Header order from the launch config; null keeps the built-in JA4H order */
+    order?: HeaderOrderProfile | null;
   };
 }
@@ -0,0 +1,180 @@
+// ── Manifold workflow steps ───────────────────────────────────────────────────
+//
+// Run by the bridge when the launch config has `workflow` set.  The backend
+// (workflow.rs) sends one action per line on stdin and owns retries,
+// alternative selectors and on-error branches; this side only performs each
+// action once and answers with a `STEP_RESULT` line, carrying what the
+// extraction steps read in `value`.  The loop ends when
+// stdin closes.  Manual interventions are handled by the backend alone; the
+// operator works the page through the live view meanwhile.
+//
+// When a step gives up the backend sends `capture_failure`, answered with
+// the page's URL, a screenshot and the last requests for the run's
+// artifacts (run_artifacts.rs).  Cookie and authorization header values
+// are left out of the excerpt.
+
+import { createInterface } from "node:readline";
+import type { Page } from "playwright";
+import type { HarEntry } from "./types.js";
+
+/** Mirrors `StepAction` in src-tauri/src/workflow.rs */
+export type StepAction =
+  | { type: "navigate"; url: string }
+  | { type: "click"; selector: string }
+  | { type: "fill"; selector: string; value: string }
+  | { type: "wait_for"; selector: string; timeout_ms: number }
+  | { type: "sleep"; ms: number }
+  | { type: "extract_text"; selector: string; key: string; all: boolean }
+  | {
+      type: "extract_attribute";
+      selector: string;
+      attribute: string;
+      key: string;
+      all: boolean;
+    }
+  | { type: "extract_table"; selector: string; key: string };
+
+/** Bridge-side commands that aren't workflow steps */
+type Command =
+  | StepAction
+  | { type: "capture_failure"; har_entries: number };
+
+export interface StepResult {
+  ok: boolean;
+  value?: unknown;
+  error?: string;
+}
+
+const NAVIGATION_TIMEOUT_MS = 30_000;
+const ACTION_TIMEOUT_MS = 15_000;
+const SECRET_HEADERS = new Set([
+  "authorization",
+  "proxy-authorization",
+  "cookie",
+  "set-cookie",
+]);
+
+function redactHeaders(
+  headers: Array<{ name: string; value: string }>,
+): Array<{ name: string; value: string }> {
+  return headers.map(({ name, value }) => ({
+    name,
+    value: SECRET_HEADERS.has(name.toLowerCase()) ? "[redacted]" : value,
+  }));
+}
+
+async function captureFailure(
+  page: Page,
+  harEntries: HarEntry[],
+  count: number,
+): Promise<unknown> {
+  const screenshot = await page
+    .screenshot({ type: "png", timeout: ACTION_TIMEOUT_MS })
+    .then((png) => png.toString("base64"))
+    .catch(() => null);
+  const har = harEntries.slice(-count).map((e) => ({
+    ...e,
+    requestHeaders: redactHeaders(e.requestHeaders),
+    responseHeaders: redactHeaders(e.responseHeaders),
+  }));
+  return { url: page.url(), screenshot, har };
+}
+
+/** Header-keyed objects, or arrays of cell texts for a table without one */
+function readTable(table: Element): unknown[] {
+  const rows = Array.from((table as HTMLTableElement).rows);
+  const cells = (row: HTMLTableRowElement) =>
+    Array.from(row.cells).map((c) => c.innerText.trim());
+  const first = rows[0];
+  const hasHeader =
+    !!first &&
+    (first.parentElement?.tagName === "THEAD" ||
+      Array.from(first.cells).every((c) => c.tagName === "TH"));
+  if (!hasHeader) return rows.map(cells);
+  const header = cells(first);
+  return rows.slice(1).map((row) => {
+    const values = cells(row);
+    return Object.fromEntries(header.map((h, i) => [h, values[i] ?? ""]));
+  });
+}
+
+async function perform(page: Page, action: StepAction): Promise<unknown> {
+  switch (action.type) {
+    case "navigate":
+      await page.goto(action.url, {
+        waitUntil: "load",
+        timeout: NAVIGATION_TIMEOUT_MS,
+      });
+      return;
+    case "click":
+      await page.click(action.selector, { timeout: ACTION_TIMEOUT_MS });
+      return;
+    case "fill":
+      await page.fill(action.selector, action.value, {
+        timeout: ACTION_TIMEOUT_MS,
+      });
+      return;
+    case "wait_for":
+      await page.waitForSelector(action.selector, {
+        state: "visible",
+        timeout: action.timeout_ms,
+      });
+      return;
+    case "sleep":
+      await page.waitForTimeout(action.ms);
+      return;
+    case "extract_text": {
+      const matches = page.locator(action.selector);
+      if (action.all) {
+        return (await matches.allInnerTexts()).map((t) => t.trim());
+      }
+      return (
+        await matches.first().innerText({ timeout: ACTION_TIMEOUT_MS })
+      ).trim();
+    }
+    case "extract_attribute": {
+      const matches = page.locator(action.selector);
+      if (action.all) {
+        return matches.evaluateAll(
+          (els, attr) => els.map((el) => el.getAttribute(attr)),
+          action.attribute,
+        );
+      }
+      return matches
+        .first()
+        .getAttribute(action.attribute, { timeout: ACTION_TIMEOUT_MS });
+    }
+    case "extract_table": {
+      const table = page.locator(action.selector).first();
+      await table.waitFor({ timeout: ACTION_TIMEOUT_MS });
+      return table.evaluate(readTable);
+    }
+    default:
+      throw new Error(`unknown step ${JSON.stringify(action)}`);
+  }
+}
+
+export async function runWorkflowSteps(
+  page: Page,
+  harEntries: HarEntry[],
+): Promise<void> {
+  const lines = createInterface({ input: process.stdin });
+  for await (const line of lines) {
+    if (!line.trim()) continue;
+    let result: StepResult;
+    try {
+      const command = JSON.parse(line) as Command;
+      const value =
+        command.type === "capture_failure"
+          ? await captureFailure(page, harEntries, command.har_entries)
+          : await perform(page, command);
+      result = value === undefined ? { ok: true } : { ok: true, value };
+    } catch (e) {
+      result = {
+        ok: false,
+        error: e instanceof Error ? e.message : String(e),
+      };
+    }
+    process.stdout.write(`STEP_RESULT ${JSON.stringify(result)}\n`);
+  }
+}
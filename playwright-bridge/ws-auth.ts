@@ -0,0 +1,59 @@
+// ── Manifold bridge — WebSocket handshake auth ───────────────────────────────
+//
+// Every launch carries a fresh secret in `wsSecret` (bridge_auth.rs).  A
+// client connects with a one-time ticket minted by the backend's
+// `get_bridge_url`:
+//
+//   ws://localhost:<port>/?nonce=<hex>&sig=<hex HMAC-SHA256(secret, nonce)>
+//
+// Handshakes with a bad signature, or a nonce already accepted once, are
+// refused with 401 before the socket opens.  Without a secret (dev runs
+// started by hand) every handshake is let through.
+//
+// A bridge that listens off loopback (`wsTls`, exposed agent nodes) serves
+// wss:// with the certificate its node provides, so neither tickets nor the
+// session cross the network in the clear.
+
+import { createHmac, timingSafeEqual } from "node:crypto";
+import { readFileSync } from "node:fs";
+import type { IncomingMessage } from "node:http";
+import { createServer } from "node:https";
+import type { Server as HttpsServer } from "node:https";
+
+export class HandshakeAuth {
+  private readonly used = new Set<string>();
+
+  constructor(private readonly secret: string | undefined) {}
+
+  get enabled(): boolean {
+    return this.secret !== undefined;
+  }
+
+  /** Accept the handshake's ticket, consuming its nonce. */
+  verify(req: IncomingMessage): boolean {
+    if (this.secret === undefined) return true;
+    const params = new URL(req.url ?? "/", "ws://localhost").searchParams;
+    const nonce = params.get("nonce");
+    const sig = params.get("sig");
+    if (!nonce || !sig || !/^[0-9a-f]+$/.test(sig) || this.used.has(nonce)) {
+      return false;
+    }
+    const expected = createHmac("sha256", this.secret).update(nonce).digest();
+    const given = Buffer.from(sig, "hex");
+    if (given.length !== expected.length || !timingSafeEqual(given, expected)) {
+      return false;
+    }
+    this.used.add(nonce);
+    return true;
+  }
+}
+
+/** HTTPS server for a `wsTls` launch, from the node's MANIFOLD_WS_TLS_CERT / _KEY files. */
+export function wsTlsServer(): HttpsServer {
+  const cert = process.env["MANIFOLD_WS_TLS_CERT"];
+  const key = process.env["MANIFOLD_WS_TLS_KEY"];
+  if (!cert || !key) {
+    throw new Error("wsTls needs MANIFOLD_WS_TLS_CERT and MANIFOLD_WS_TLS_KEY");
+  }
+  return createServer({ cert: readFileSync(cert), key: readFileSync(key) });
+}
@@ -0,0 +1,35 @@
+/**
+ * Bridge Manifest
+ *
+ * Writes bridge-manifest.json next to a built bridge entry file (the
+ * compiled sidecar binary or playwright-bridge/dist/index.js).  The app
+ * refuses to launch a shipped bridge whose manifest protocol or checksum
+ * doesn't match (src-tauri/src/bridge_locator.rs).
+ *
+ * Usage:
+ *   npx tsx scripts/bridge-manifest.ts playwright-bridge/dist/index.js
+ */
+
+import { createHash } from "node:crypto";
+import { readFileSync, writeFileSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { BRIDGE_PROTOCOL } from "../playwright-bridge/types.js";
+
+const entry = process.argv[2];
+if (!entry) {
+  console.error("usage: bridge-manifest.ts <bridge entry file>");
+  process.exit(1);
+}
+
+const pkg = JSON.parse(readFileSync("package.json", "utf8")) as {
+  version: string;
+};
+const manifest = {
+  version: pkg.version,
+  protocol: BRIDGE_PROTOCOL,
+  sha3_256: createHash("sha3-256").update(readFileSync(entry)).digest("hex"),
+};
+
+const out = join(dirname(entry), "bridge-manifest.json");
+writeFileSync(out, JSON.stringify(manifest, null, 2) + "\n");
+console.log(`wrote ${out} (protocol ${manifest.protocol})`);
@@ -1,5 +1,7 @@
 // Live monitor for Manifold automation runs.
 // Connects to the Playwright bridge WS (default :8766) and prints login_* events.
+// Launched bridges need a ticketed URL: pass one from get_bridge_url in
+// MANIFOLD_BRIDGE_URL (each ticket works once).
 
 import WebSocket from "ws";
 
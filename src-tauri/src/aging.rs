@@ -0,0 +1,231 @@
+// ── Manifold fingerprint aging ────────────────────────────────────────────────
+//
+// Real Chrome installs auto-update every four weeks; a profile that stays on
+// the major it was generated with drifts into the long tail and gets flagged.
+// The aging task periodically moves opted-in profiles to a major drawn from a
+// realistic adoption curve (most users on current stable, some one or two
+// releases behind, in the shares of the population priors) and rewrites the
+// UA string and UA-CH brands to match.  Everything else in the fingerprint
+// is left untouched.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{NaiveDate, Utc};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::db::Db;
+use crate::error::Result;
+use crate::fingerprint::{chrome_version, Fingerprint, FingerprintOrchestrator};
+
+/// Chrome 124 reached stable on 2024-04-16; releases follow every 28 days.
+const ANCHOR_MAJOR: u32 = 124;
+const ANCHOR_DATE: (i32, u32, u32) = (2024, 4, 16);
+const RELEASE_CADENCE_DAYS: i64 = 28;
+
+/// How often the background task re-checks all profiles.
+const AGING_INTERVAL: StdDuration = StdDuration::from_secs(6 * 60 * 60);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgedProfile {
+    pub id: String,
+    pub from_major: u32,
+    pub to_major: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgingReport {
+    /// Estimated current stable Chrome major.
+    pub stable_major: u32,
+    /// Opted-in, non-running profiles that were examined.
+    pub checked: usize,
+    pub aged: Vec<AgedProfile>,
+}
+
+// ── Version model ─────────────────────────────────────────────────────────────
+
+/// Estimated Chrome stable major on `date`.
+pub fn stable_chrome_major(date: NaiveDate) -> u32 {
+    let (y, m, d) = ANCHOR_DATE;
+    let anchor = NaiveDate::from_ymd_opt(y, m, d).expect("valid anchor date");
+    let days = (date - anchor).num_days().max(0);
+    ANCHOR_MAJOR + (days / RELEASE_CADENCE_DAYS) as u32
+}
+
+/// Major a profile should be on for the given stable release: stable, one
+/// or two behind, in the proportions the priors give those lags (about
+/// 65 / 25 / 10 %).  The pick is deterministic per (seed, stable) so a
+/// profile doesn't flap between runs.
+pub fn target_major(seed: u64, stable: u32) -> u32 {
+    let h = blake3::hash(&[seed.to_le_bytes(), (stable as u64).to_le_bytes()].concat());
+    let roll = h.as_bytes()[0] as f64 / 256.0;
+    let priors = crate::priors::current();
+    let shares = [0, 1, 2].map(|lag: u32| priors.chrome_lag.share(&lag.to_string()));
+    let total: f64 = shares.iter().sum();
+    if total <= 0.0 {
+        return stable;
+    }
+    let mut cumulative = 0.0;
+    for (lag, share) in (0u32..).zip(shares) {
+        cumulative += share / total;
+        if roll < cumulative {
+            return stable.saturating_sub(lag);
+        }
+    }
+    stable.saturating_sub(2)
+}
+
+/// Bump `fp` towards the target major for `today`.  Never downgrades.
+/// Returns `(from, to)` when the fingerprint changed.
+pub fn age_fingerprint(fp: &mut Fingerprint, today: NaiveDate) -> Option<(u32, u32)> {
+    let current: u32 = chrome_version(&fp.user_agent)?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()?;
+    let target = target_major(fp.seed, stable_chrome_major(today));
+    if target <= current {
+        return None;
+    }
+    FingerprintOrchestrator::set_chrome_major(fp, target);
+    Some((current, target))
+}
+
+// ── Task ──────────────────────────────────────────────────────────────────────
+
+/// Age every opted-in profile that is not currently running.  Profiles
+/// pinned to a browser version stay on it.
+pub fn run_aging(db: &Db, today: NaiveDate) -> Result<AgingReport> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, fingerprint_json FROM profiles
+             WHERE auto_age = 1 AND pinned_browser_version IS NULL AND status != 'running'",
+        )?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut aged = Vec::new();
+        for (id, fp_json) in &rows {
+            let Ok(mut fp) = serde_json::from_str::<Fingerprint>(fp_json) else {
+                continue;
+            };
+            if let Some((from_major, to_major)) = age_fingerprint(&mut fp, today) {
+                conn.execute(
+                    "UPDATE profiles SET fingerprint_json = ?1 WHERE id = ?2",
+                    params![serde_json::to_string(&fp)?, id],
+                )?;
+                aged.push(AgedProfile {
+                    id: id.clone(),
+                    from_major,
+                    to_major,
+                });
+            }
+        }
+
+        Ok(AgingReport {
+            stable_major: stable_chrome_major(today),
+            checked: rows.len(),
+            aged,
+        })
+    })
+}
+
+/// Run the aging task now and then every `AGING_INTERVAL` on a background
+/// thread for the lifetime of the app.
+pub fn spawn_scheduler(db: Db) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = run_aging(&db, Utc::now().date_naive()) {
+            eprintln!("[aging] run failed: {e}");
+        }
+        std::thread::sleep(AGING_INTERVAL);
+    });
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn major_of(fp: &Fingerprint) -> u32 {
+        chrome_version(&fp.user_agent)
+            .unwrap()
+            .split('.')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn stable_major_follows_release_cadence() {
+        assert_eq!(stable_chrome_major(date(2024, 4, 16)), 124);
+        assert_eq!(stable_chrome_major(date(2024, 5, 13)), 124);
+        assert_eq!(stable_chrome_major(date(2024, 5, 14)), 125);
+        assert_eq!(stable_chrome_major(date(2020, 1, 1)), 124);
+    }
+
+    #[test]
+    fn target_major_stays_within_two_of_stable() {
+        let mut on_stable = 0;
+        for seed in 0..1000u64 {
+            let t = target_major(seed, 150);
+            assert!((148..=150).contains(&t));
+            if t == 150 {
+                on_stable += 1;
+            }
+        }
+        assert!(
+            on_stable > 500,
+            "most profiles should be on stable, got {on_stable}"
+        );
+    }
+
+    #[test]
+    fn aging_bumps_stale_profile_and_never_downgrades() {
+        let mut fp = FingerprintOrchestrator::generate(42);
+        let before = major_of(&fp);
+        let platform = fp.platform.clone();
+
+        let changed = age_fingerprint(&mut fp, date(2027, 1, 1)).unwrap();
+        assert_eq!(changed.0, before);
+        assert!(major_of(&fp) > before);
+        assert_eq!(fp.platform, platform, "aging must not touch the device");
+
+        // Going back in time never moves the version backwards
+        assert!(age_fingerprint(&mut fp, date(2024, 4, 16)).is_none());
+    }
+
+    #[test]
+    fn run_aging_respects_opt_out_pins_and_running_profiles() {
+        let db = Db::open_in_memory().unwrap();
+        let fp_json = serde_json::to_string(&FingerprintOrchestrator::generate(7)).unwrap();
+        db.with_conn(|conn| {
+            for (id, status, auto_age) in [("a", "idle", 1), ("b", "idle", 0), ("c", "running", 1)] {
+                conn.execute(
+                    "INSERT INTO profiles (id, name, fingerprint_json, status, created_at, auto_age) VALUES (?1, ?1, ?2, ?3, '2025-01-01T00:00:00+00:00', ?4)",
+                    params![id, fp_json, status, auto_age],
+                )?;
+            }
+            // Held on an old Chrome for a legacy site
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, created_at, pinned_browser_version) VALUES ('d', 'd', ?1, '2025-01-01T00:00:00+00:00', '109')",
+                params![fp_json],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let report = run_aging(&db, date(2027, 1, 1)).unwrap();
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.aged.len(), 1);
+        assert_eq!(report.aged[0].id, "a");
+    }
+}
@@ -0,0 +1,398 @@
+// ── Manifold bandwidth accounting ─────────────────────────────────────────────
+//
+// Residential proxies are billed per GB, and a single busy profile can eat
+// a month's allowance.  Bytes up and down are recorded per profile session
+// and aggregated per proxy and per UTC day, so a bill can be attributed to
+// the profiles that ran it up.
+//
+// Two sources feed the counters, never both for the same launch:
+//   * the TLS bridge counts the bytes it exchanges with the upstream proxy,
+//     TLS records included — the closest to what the proxy bills;
+//   * without it, the playwright-bridge sums request and response sizes and
+//     prints them as `BANDWIDTH_USAGE {"up":…,"down":…}` stdout lines.
+//
+// Usage is charged to the proxy assigned to the profile when it is recorded.
+// Rows are kept when a profile or proxy is deleted: the bill still counts.
+
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use chrono::{Duration, NaiveDate, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+/// Prefix of the bridge's usage lines on stdout.
+pub const USAGE_LINE_PREFIX: &str = "BANDWIDTH_USAGE ";
+
+/// Days covered by a report when none are given.
+pub const DEFAULT_REPORT_DAYS: u32 = 30;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// Bytes transferred since the previous sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageSample {
+    pub up: u64,
+    pub down: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyBandwidth {
+    /// `None` for traffic that went out directly.
+    pub proxy_id: Option<String>,
+    /// `None` when the proxy was deleted since.
+    pub name: Option<String>,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileBandwidth {
+    pub profile_id: String,
+    /// `None` when the profile was deleted since.
+    pub name: Option<String>,
+    pub sessions: u32,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+}
+
+/// One proxy's traffic on one UTC day.
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyBandwidth {
+    pub day: NaiveDate,
+    pub proxy_id: Option<String>,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BandwidthReport {
+    /// First UTC day covered; the report runs to today.
+    pub since: NaiveDate,
+    pub bytes_up: u64,
+    pub bytes_down: u64,
+    /// Heaviest first.
+    pub by_proxy: Vec<ProxyBandwidth>,
+    /// Heaviest first.
+    pub by_profile: Vec<ProfileBandwidth>,
+    /// Oldest first.
+    pub by_day: Vec<DailyBandwidth>,
+}
+
+/// Pull a usage sample out of a bridge stdout line, if this is one.
+pub fn parse_usage_line(line: &str) -> Option<Result<UsageSample>> {
+    let json = line.trim().strip_prefix(USAGE_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).map_err(ManifoldError::from))
+}
+
+/// Empty string ↔ `None`: the key columns can't hold NULL and still upsert.
+fn key(id: Option<String>) -> Option<String> {
+    id.filter(|s| !s.is_empty())
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+pub struct BandwidthRepo {
+    db: Db,
+}
+
+impl BandwidthRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Add `sample` to the profile's open session, its current proxy and
+    /// today's totals.
+    pub fn record(&self, profile_id: &str, sample: UsageSample) -> Result<()> {
+        if sample == UsageSample::default() {
+            return Ok(());
+        }
+        let day = Utc::now().date_naive().to_string();
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO bandwidth_usage
+                       (profile_id, session_id, proxy_id, day, bytes_up, bytes_down)
+                   SELECT ?1,
+                          COALESCE((SELECT id FROM sessions
+                                    WHERE profile_id = ?1 AND ended_at IS NULL
+                                    ORDER BY started_at DESC LIMIT 1), ''),
+                          COALESCE((SELECT proxy_id FROM profiles WHERE id = ?1), ''),
+                          ?2, ?3, ?4
+                   ON CONFLICT(profile_id, session_id, proxy_id, day) DO UPDATE SET
+                       bytes_up   = bytes_up + excluded.bytes_up,
+                       bytes_down = bytes_down + excluded.bytes_down"#,
+                params![profile_id, day, sample.up as i64, sample.down as i64],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Usage over the last `days` UTC days (today included), optionally of
+    /// one profile only.
+    pub fn report(&self, days: u32, profile_id: Option<&str>) -> Result<BandwidthReport> {
+        if days == 0 {
+            return Err(ManifoldError::InvalidArg(
+                "a report covers at least one day".into(),
+            ));
+        }
+        let since = Utc::now().date_naive() - Duration::days(i64::from(days) - 1);
+        let since_text = since.to_string();
+        self.db.with_conn(|conn| {
+            let filter = "u.day >= ?1 AND (?2 IS NULL OR u.profile_id = ?2)";
+
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT u.proxy_id, x.name, SUM(u.bytes_up), SUM(u.bytes_down)
+                   FROM bandwidth_usage u LEFT JOIN proxies x ON x.id = u.proxy_id
+                   WHERE {filter}
+                   GROUP BY u.proxy_id
+                   ORDER BY SUM(u.bytes_up) + SUM(u.bytes_down) DESC, u.proxy_id"#
+            ))?;
+            let by_proxy = stmt
+                .query_map(params![since_text, profile_id], |r| {
+                    Ok(ProxyBandwidth {
+                        proxy_id: key(r.get(0)?),
+                        name: r.get(1)?,
+                        bytes_up: r.get::<_, i64>(2)? as u64,
+                        bytes_down: r.get::<_, i64>(3)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT u.profile_id, p.name, COUNT(DISTINCT NULLIF(u.session_id, '')),
+                          SUM(u.bytes_up), SUM(u.bytes_down)
+                   FROM bandwidth_usage u LEFT JOIN profiles p ON p.id = u.profile_id
+                   WHERE {filter}
+                   GROUP BY u.profile_id
+                   ORDER BY SUM(u.bytes_up) + SUM(u.bytes_down) DESC, u.profile_id"#
+            ))?;
+            let by_profile = stmt
+                .query_map(params![since_text, profile_id], |r| {
+                    Ok(ProfileBandwidth {
+                        profile_id: r.get(0)?,
+                        name: r.get(1)?,
+                        sessions: r.get(2)?,
+                        bytes_up: r.get::<_, i64>(3)? as u64,
+                        bytes_down: r.get::<_, i64>(4)? as u64,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(&format!(
+                r#"SELECT u.day, u.proxy_id, SUM(u.bytes_up), SUM(u.bytes_down)
+                   FROM bandwidth_usage u
+                   WHERE {filter}
+                   GROUP BY u.day, u.proxy_id
+                   ORDER BY u.day, u.proxy_id"#
+            ))?;
+            let by_day = stmt
+                .query_map(params![since_text, profile_id], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get(1)?,
+                        r.get::<_, i64>(2)?,
+                        r.get::<_, i64>(3)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .filter_map(|(day, proxy_id, up, down)| {
+                    Some(DailyBandwidth {
+                        day: day.parse().ok()?,
+                        proxy_id: key(proxy_id),
+                        bytes_up: up as u64,
+                        bytes_down: down as u64,
+                    })
+                })
+                .collect();
+
+            Ok(BandwidthReport {
+                since,
+                bytes_up: by_proxy.iter().map(|p| p.bytes_up).sum(),
+                bytes_down: by_proxy.iter().map(|p| p.bytes_down).sum(),
+                by_proxy,
+                by_profile,
+                by_day,
+            })
+        })
+    }
+}
+
+// ── Metering ──────────────────────────────────────────────────────────────────
+
+/// Charges the TLS bridge's upstream traffic to one profile.
+#[derive(Clone)]
+pub struct UsageMeter {
+    repo: BandwidthRepo,
+    profile_id: String,
+}
+
+impl fmt::Debug for UsageMeter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageMeter")
+            .field("profile_id", &self.profile_id)
+            .finish()
+    }
+}
+
+impl UsageMeter {
+    pub fn new(db: Db, profile_id: impl Into<String>) -> Self {
+        Self {
+            repo: BandwidthRepo::new(db),
+            profile_id: profile_id.into(),
+        }
+    }
+
+    pub fn record(&self, sample: UsageSample) {
+        if let Err(e) = self.repo.record(&self.profile_id, sample) {
+            eprintln!("[bandwidth] failed to record usage: {e}");
+        }
+    }
+}
+
+/// A stream that counts the bytes read from and written to it.
+pub struct CountingStream<S> {
+    inner: S,
+    usage: UsageSample,
+}
+
+impl<S> CountingStream<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            usage: UsageSample::default(),
+        }
+    }
+
+    /// Bytes written (`up`) and read (`down`) so far.
+    pub fn usage(&self) -> UsageSample {
+        self.usage
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        self.usage.down += (buf.filled().len() - before) as u64;
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            self.usage.up += n as u64;
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn make_repo() -> BandwidthRepo {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO proxies (id, name, proxy_type, host, port) VALUES ('x1', 'resi-de', 'http', 'h', 1)",
+                [],
+            )?;
+            for (id, name, proxy) in [("p1", "shop-a", Some("x1")), ("p2", "shop-b", None)] {
+                conn.execute(
+                    "INSERT INTO profiles (id, name, fingerprint_json, proxy_id, created_at) VALUES (?1, ?2, '{}', ?3, '2025-01-01T00:00:00+00:00')",
+                    params![id, name, proxy],
+                )?;
+            }
+            conn.execute(
+                "INSERT INTO sessions (id, profile_id, started_at) VALUES ('s1', 'p1', '2025-01-01T00:00:00+00:00')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        BandwidthRepo::new(db)
+    }
+
+    #[test]
+    fn usage_is_summed_per_proxy_profile_and_day() {
+        let repo = make_repo();
+        repo.record(
+            "p1",
+            UsageSample {
+                up: 100,
+                down: 1000,
+            },
+        )
+        .unwrap();
+        repo.record("p1", UsageSample { up: 50, down: 500 })
+            .unwrap();
+        repo.record("p2", UsageSample { up: 1, down: 10 }).unwrap();
+        repo.record("p2", UsageSample::default()).unwrap();
+
+        let report = repo.report(DEFAULT_REPORT_DAYS, None).unwrap();
+        assert_eq!((report.bytes_up, report.bytes_down), (151, 1510));
+        assert_eq!(report.by_proxy[0].name.as_deref(), Some("resi-de"));
+        assert_eq!(report.by_proxy[0].bytes_down, 1500);
+        assert_eq!(report.by_proxy[1].proxy_id, None);
+        assert_eq!(report.by_profile[0].profile_id, "p1");
+        assert_eq!(report.by_profile[0].sessions, 1);
+        assert_eq!(report.by_profile[1].sessions, 0);
+        assert_eq!(report.by_day.len(), 2);
+        assert_eq!(report.by_day[0].day, Utc::now().date_naive());
+
+        let one = repo.report(1, Some("p2")).unwrap();
+        assert_eq!(one.by_profile.len(), 1);
+        assert_eq!(one.bytes_down, 10);
+        assert!(repo.report(0, None).is_err());
+    }
+
+    #[test]
+    fn usage_lines_parse() {
+        let sample = parse_usage_line(r#"BANDWIDTH_USAGE {"up":3,"down":4}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sample, UsageSample { up: 3, down: 4 });
+        assert!(parse_usage_line("CLOCK_SAMPLE {}").is_none());
+        assert!(parse_usage_line("BANDWIDTH_USAGE nope").unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn counting_stream_counts_both_directions() {
+        let (a, mut b) = tokio::io::duplex(64);
+        let mut counted = CountingStream::new(a);
+        counted.write_all(b"hello").await.unwrap();
+        b.write_all(b"abc").await.unwrap();
+        let mut buf = [0u8; 3];
+        counted.read_exact(&mut buf).await.unwrap();
+        assert_eq!(counted.usage(), UsageSample { up: 5, down: 3 });
+    }
+}
@@ -0,0 +1,440 @@
+// ── Manifold behavior audit ───────────────────────────────────────────────────
+//
+// The human layer shapes every cursor move, key press and wheel tick, but
+// what reaches the page is what matters: a CDP call that bypasses it, an
+// entropy tracker stuck at its floor or a replay at a fixed rate all show
+// up in the timings sites measure.  The bridge records the input it actually
+// dispatches and prints it in batches (`BEHAVIOR_TRACE [...]`); when the
+// session ends the collected samples are checked here against human
+// baselines and the result is stored with the session.
+//
+// Checks (each skipped when the session has too few samples for it):
+//   move_timing        spread of the gaps between mousemove events
+//   velocity_entropy   Shannon entropy of the cursor speed histogram
+//   velocity_kurtosis  heavy tails of the speed distribution
+//   keystroke_timing   mean and spread of inter-key intervals
+//   click_dwell        mean and spread of mouse button hold times
+// The humanness score is the share of evaluated checks that passed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::leak_test::CheckStatus;
+
+/// Prefix of the bridge's action trace lines.
+pub const TRACE_LINE_PREFIX: &str = "BEHAVIOR_TRACE ";
+
+/// Samples kept per session; later ones are dropped.
+pub const MAX_SAMPLES: usize = 50_000;
+
+/// Gap (ms) that ends a movement: events further apart belong to separate
+/// moves, and their gap says nothing about the path's own timing.
+const MOVE_GAP_MS: f64 = 250.0;
+/// Gap (ms) that ends a typing burst.
+const TYPING_GAP_MS: f64 = 2_000.0;
+
+/// Speed histogram of entropy.ts: 64 bins over 0–4000 px/s.
+const VELOCITY_BINS: usize = 64;
+const VELOCITY_MAX: f64 = 4_000.0;
+
+// Human baselines
+const MOVE_TIMING_MIN_CV: f64 = 0.25;
+const VELOCITY_ENTROPY_MIN_BITS: f64 = 3.5;
+const VELOCITY_MIN_KURTOSIS: f64 = 1.0;
+const IKI_MEAN_MS: (f64, f64) = (60.0, 600.0);
+const IKI_MIN_CV: f64 = 0.3;
+const DWELL_MEAN_MS: (f64, f64) = (40.0, 300.0);
+const DWELL_MIN_CV: f64 = 0.1;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    Move,
+    Down,
+    Up,
+    Wheel,
+    Key,
+}
+
+/// One dispatched input event.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActionSample {
+    pub kind: ActionKind,
+    /// Bridge monotonic time, ms.
+    pub t: f64,
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    /// The measured statistic; `None` when skipped.
+    pub value: Option<f64>,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorAudit {
+    pub samples: usize,
+    /// 0–100: share of evaluated checks that passed.  `None` when every
+    /// check was skipped.
+    pub score: Option<u8>,
+    pub checks: Vec<AuditCheck>,
+    pub audited_at: DateTime<Utc>,
+}
+
+/// Pull a batch of samples out of a bridge stdout line, if this is one.
+pub fn parse_trace_line(line: &str) -> Option<Result<Vec<ActionSample>>> {
+    let json = line.trim().strip_prefix(TRACE_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).map_err(ManifoldError::from))
+}
+
+/// Samples of one session, as the batches arrive.
+#[derive(Debug, Default)]
+pub struct BehaviorRecorder {
+    samples: Vec<ActionSample>,
+}
+
+impl BehaviorRecorder {
+    pub fn extend(&mut self, batch: Vec<ActionSample>) {
+        let room = MAX_SAMPLES.saturating_sub(self.samples.len());
+        self.samples.extend(batch.into_iter().take(room));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn audit(&self) -> BehaviorAudit {
+        audit(&self.samples)
+    }
+}
+
+// ── Audit ─────────────────────────────────────────────────────────────────────
+
+pub fn audit(samples: &[ActionSample]) -> BehaviorAudit {
+    let mut samples = samples.to_vec();
+    samples.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+    let moves: Vec<&ActionSample> = samples
+        .iter()
+        .filter(|s| s.kind == ActionKind::Move)
+        .collect();
+    let move_gaps: Vec<f64> = moves
+        .windows(2)
+        .map(|w| w[1].t - w[0].t)
+        .filter(|&dt| dt > 0.0 && dt < MOVE_GAP_MS)
+        .collect();
+    let velocities: Vec<f64> = moves
+        .windows(2)
+        .filter_map(|w| {
+            let dt = w[1].t - w[0].t;
+            let (x0, y0, x1, y1) = (w[0].x?, w[0].y?, w[1].x?, w[1].y?);
+            (dt > 0.0 && dt < MOVE_GAP_MS).then(|| (x1 - x0).hypot(y1 - y0) / dt * 1000.0)
+        })
+        .collect();
+    let key_gaps: Vec<f64> = samples
+        .iter()
+        .filter(|s| s.kind == ActionKind::Key)
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|w| w[1].t - w[0].t)
+        .filter(|&dt| dt < TYPING_GAP_MS)
+        .collect();
+    let dwells: Vec<f64> = samples
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.kind == ActionKind::Down)
+        .filter_map(|(i, down)| {
+            let up = samples[i + 1..].iter().find(|s| s.kind == ActionKind::Up)?;
+            Some(up.t - down.t)
+        })
+        .collect();
+
+    let checks = vec![
+        check_min(
+            "move_timing",
+            &move_gaps,
+            30,
+            cv,
+            MOVE_TIMING_MIN_CV,
+            "mousemove gap CV",
+        ),
+        check_min(
+            "velocity_entropy",
+            &velocities,
+            50,
+            velocity_entropy,
+            VELOCITY_ENTROPY_MIN_BITS,
+            "cursor speed entropy (bits)",
+        ),
+        check_min(
+            "velocity_kurtosis",
+            &velocities,
+            50,
+            excess_kurtosis,
+            VELOCITY_MIN_KURTOSIS,
+            "cursor speed excess kurtosis",
+        ),
+        check_timing(
+            "keystroke_timing",
+            &key_gaps,
+            20,
+            IKI_MEAN_MS,
+            IKI_MIN_CV,
+            "inter-key interval",
+        ),
+        check_timing(
+            "click_dwell",
+            &dwells,
+            5,
+            DWELL_MEAN_MS,
+            DWELL_MIN_CV,
+            "button hold",
+        ),
+    ];
+
+    let evaluated: Vec<&AuditCheck> = checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Skipped)
+        .collect();
+    let passed = evaluated
+        .iter()
+        .filter(|c| c.status == CheckStatus::Pass)
+        .count();
+    let score = (!evaluated.is_empty())
+        .then(|| (passed as f64 * 100.0 / evaluated.len() as f64).round() as u8);
+
+    BehaviorAudit {
+        samples: samples.len(),
+        score,
+        checks,
+        audited_at: Utc::now(),
+    }
+}
+
+fn skipped(name: &str, n: usize, needed: usize) -> AuditCheck {
+    AuditCheck {
+        name: name.into(),
+        status: CheckStatus::Skipped,
+        value: None,
+        detail: format!("{n} samples, {needed} needed"),
+    }
+}
+
+/// Pass when `stat(values)` is at least `min`.
+fn check_min(
+    name: &str,
+    values: &[f64],
+    needed: usize,
+    stat: fn(&[f64]) -> f64,
+    min: f64,
+    what: &str,
+) -> AuditCheck {
+    if values.len() < needed {
+        return skipped(name, values.len(), needed);
+    }
+    let value = stat(values);
+    let status = if value >= min {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail
+    };
+    AuditCheck {
+        name: name.into(),
+        status,
+        value: Some(value),
+        detail: format!("{what} {value:.2} (human ≥ {min})"),
+    }
+}
+
+/// Pass when the mean is in the human range and the spread isn't machine-
+/// regular.  The reported value is the mean.
+fn check_timing(
+    name: &str,
+    values: &[f64],
+    needed: usize,
+    (lo, hi): (f64, f64),
+    min_cv: f64,
+    what: &str,
+) -> AuditCheck {
+    if values.len() < needed {
+        return skipped(name, values.len(), needed);
+    }
+    let (m, spread) = (mean(values), cv(values));
+    let mut problems = Vec::new();
+    if !(lo..=hi).contains(&m) {
+        problems.push(format!("mean {m:.0} ms outside {lo:.0}–{hi:.0} ms"));
+    }
+    if spread < min_cv {
+        problems.push(format!("CV {spread:.2} below {min_cv}"));
+    }
+    AuditCheck {
+        name: name.into(),
+        status: if problems.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        },
+        value: Some(m),
+        detail: if problems.is_empty() {
+            format!("{what} mean {m:.0} ms, CV {spread:.2}")
+        } else {
+            format!("{what}: {}", problems.join("; "))
+        },
+    }
+}
+
+// ── Statistics ────────────────────────────────────────────────────────────────
+
+fn mean(v: &[f64]) -> f64 {
+    v.iter().sum::<f64>() / v.len() as f64
+}
+
+/// Coefficient of variation (sample standard deviation over mean).
+fn cv(v: &[f64]) -> f64 {
+    let m = mean(v);
+    if v.len() < 2 || m == 0.0 {
+        return 0.0;
+    }
+    let var = v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (v.len() - 1) as f64;
+    var.sqrt() / m
+}
+
+fn excess_kurtosis(v: &[f64]) -> f64 {
+    let m = mean(v);
+    let m2 = v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / v.len() as f64;
+    if m2 < 1e-10 {
+        return -3.0;
+    }
+    let m4 = v.iter().map(|x| (x - m).powi(4)).sum::<f64>() / v.len() as f64;
+    m4 / (m2 * m2) - 3.0
+}
+
+fn velocity_entropy(v: &[f64]) -> f64 {
+    let mut bins = [0usize; VELOCITY_BINS];
+    for &x in v {
+        let i = (x.clamp(0.0, VELOCITY_MAX - 1e-9) / VELOCITY_MAX * VELOCITY_BINS as f64) as usize;
+        bins[i.min(VELOCITY_BINS - 1)] += 1;
+    }
+    let n = v.len() as f64;
+    bins.iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::human::{BehaviorProfile, MouseConfig};
+    use crate::motion::{generate_mouse_path, Point};
+
+    fn sample(kind: ActionKind, t: f64, x: f64, y: f64) -> ActionSample {
+        ActionSample {
+            kind,
+            t,
+            x: Some(x),
+            y: Some(y),
+        }
+    }
+
+    /// Moves between random targets as the human layer draws them, each
+    /// followed by a click, and some typing with log-normal-ish gaps.
+    fn humanlike() -> Vec<ActionSample> {
+        let cfg = MouseConfig::for_profile(BehaviorProfile::Normal);
+        let mut out = Vec::new();
+        let (mut t, mut at) = (0.0, Point { x: 50.0, y: 50.0 });
+        for seed in 0..20u64 {
+            let to = Point {
+                x: 100.0 + (seed * 397 % 1100) as f64,
+                y: 80.0 + (seed * 211 % 600) as f64,
+            };
+            let path = generate_mouse_path(at, to, &cfg, seed);
+            for p in path.points.iter().skip(1) {
+                out.push(sample(ActionKind::Move, t + p.t, p.x, p.y));
+            }
+            t = out.last().unwrap().t + 400.0;
+            out.push(sample(ActionKind::Down, t, to.x, to.y));
+            t += 80.0 + (seed % 7) as f64 * 15.0;
+            out.push(sample(ActionKind::Up, t, to.x, to.y));
+            t += 900.0;
+            at = to;
+        }
+        for i in 0..40u64 {
+            t += 90.0 + (i * 7919 % 23) as f64 * 12.0;
+            out.push(sample(ActionKind::Key, t, 0.0, 0.0));
+        }
+        out
+    }
+
+    /// Straight lines at a fixed rate and speed, instant clicks, metronome
+    /// typing.
+    fn robotic() -> Vec<ActionSample> {
+        let mut out = Vec::new();
+        let mut t = 0.0;
+        for m in 0..10 {
+            for i in 0..30 {
+                out.push(sample(ActionKind::Move, t, (i * 10) as f64, m as f64));
+                t += 16.0;
+            }
+            out.push(sample(ActionKind::Down, t, 0.0, 0.0));
+            out.push(sample(ActionKind::Up, t + 1.0, 0.0, 0.0));
+            t += 1_000.0;
+        }
+        for _ in 0..40 {
+            t += 50.0;
+            out.push(sample(ActionKind::Key, t, 0.0, 0.0));
+        }
+        out
+    }
+
+    #[test]
+    fn human_layer_output_scores_high_and_fixed_rate_input_low() {
+        let human = audit(&humanlike());
+        assert!(
+            human.checks.iter().all(|c| c.status == CheckStatus::Pass),
+            "{:#?}",
+            human.checks
+        );
+        assert_eq!(human.score, Some(100));
+
+        let bot = audit(&robotic());
+        assert_eq!(bot.score, Some(0), "{:#?}", bot.checks);
+    }
+
+    #[test]
+    fn sparse_sessions_skip_checks() {
+        let a = audit(&[
+            sample(ActionKind::Move, 0.0, 0.0, 0.0),
+            sample(ActionKind::Move, 16.0, 5.0, 5.0),
+        ]);
+        assert!(a.checks.iter().all(|c| c.status == CheckStatus::Skipped));
+        assert_eq!(a.score, None);
+    }
+
+    #[test]
+    fn trace_lines_parse_and_recorder_caps_samples() {
+        let line = r#"BEHAVIOR_TRACE [{"kind":"move","t":1.5,"x":3,"y":4},{"kind":"key","t":9}]"#;
+        let batch = parse_trace_line(line).unwrap().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1].kind, ActionKind::Key);
+        assert!(parse_trace_line("CLOCK_SAMPLE {}").is_none());
+
+        let mut rec = BehaviorRecorder::default();
+        rec.extend(vec![batch[1]; MAX_SAMPLES + 10]);
+        assert_eq!(rec.audit().samples, MAX_SAMPLES);
+    }
+}
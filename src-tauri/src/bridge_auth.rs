@@ -0,0 +1,106 @@
+// ── Manifold bridge WebSocket auth ────────────────────────────────────────────
+//
+// The bridge's WebSocket drives the browser, and anything on the host can
+// reach a loopback port.  Each launch therefore gets a fresh secret, handed
+// to the bridge in its launch config (`wsSecret`) and kept by the backend;
+// it never reaches the frontend.  A client connects with a one-time ticket
+// instead:
+//
+//   ws://localhost:<port>/?nonce=<32 hex>&sig=<hex HMAC-SHA256(secret, nonce)>
+//
+// (wss:// for a bridge listening off loopback, see nodes.rs.)
+//
+// The bridge refuses the handshake unless the signature checks out and the
+// nonce is one it hasn't accepted before, so a ticket seen on the wire can't
+// be replayed, and a ticket from a previous launch fails against the next
+// launch's secret.
+
+use rand::RngCore;
+use ring::hmac;
+
+/// Random bytes in a launch secret.
+const SECRET_BYTES: usize = 32;
+/// Random bytes in a ticket nonce.
+const NONCE_BYTES: usize = 16;
+
+/// A fresh launch secret, hex encoded.
+pub fn new_secret() -> String {
+    hex::encode(random_bytes::<SECRET_BYTES>())
+}
+
+/// Whether `secret` looks like one `new_secret` made.
+pub fn is_valid_secret(secret: &str) -> bool {
+    secret.len() == SECRET_BYTES * 2 && secret.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The bridge's WebSocket URL, carrying a one-time ticket when the running
+/// bridge was launched with `secret`; wss:// when it serves `tls`.
+pub fn bridge_url(host: &str, port: u16, tls: bool, secret: Option<&str>) -> String {
+    let scheme = if tls { "wss" } else { "ws" };
+    let base = format!("{scheme}://{host}:{port}");
+    match secret {
+        Some(secret) => {
+            let nonce = hex::encode(random_bytes::<NONCE_BYTES>());
+            let sig = sign(secret, &nonce);
+            format!("{base}/?nonce={nonce}&sig={sig}")
+        }
+        None => base,
+    }
+}
+
+/// HMAC-SHA256 of `nonce` under `secret`, hex encoded.
+fn sign(secret: &str, nonce: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hex::encode(hmac::sign(&key, nonce.as_bytes()).as_ref())
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(url: &str) -> (String, String) {
+        let url = url::Url::parse(url).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned())
+                .unwrap()
+        };
+        (param("nonce"), param("sig"))
+    }
+
+    #[test]
+    fn tickets_are_one_time_and_bound_to_the_secret() {
+        let secret = new_secret();
+        assert!(is_valid_secret(&secret));
+        assert_ne!(secret, new_secret());
+
+        let (nonce, sig) = ticket(&bridge_url("localhost", 8766, false, Some(&secret)));
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+        assert!(hmac::verify(&key, nonce.as_bytes(), &hex::decode(&sig).unwrap()).is_ok());
+
+        let (other_nonce, _) = ticket(&bridge_url("localhost", 8766, false, Some(&secret)));
+        assert_ne!(nonce, other_nonce);
+        assert_ne!(sign(&new_secret(), &nonce), sig);
+    }
+
+    #[test]
+    fn url_without_a_secret_is_bare() {
+        assert_eq!(
+            bridge_url("localhost", 9000, false, None),
+            "ws://localhost:9000"
+        );
+        assert!(bridge_url("node.test", 9000, true, Some(&new_secret()))
+            .starts_with("wss://node.test:9000/?nonce="));
+        assert!(!is_valid_secret("not-hex"));
+        assert!(!is_valid_secret(&"ab".repeat(8)));
+    }
+}
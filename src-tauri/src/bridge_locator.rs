@@ -0,0 +1,258 @@
+// ── Manifold bridge locator ───────────────────────────────────────────────────
+//
+// Finds the playwright bridge to run.  A packaged build ships it either as a
+// compiled sidecar binary next to the executable or as bundled JS in the
+// resources directory; a dev checkout runs the TypeScript source through
+// `npx tsx`.  Shipped bridges carry a `bridge-manifest.json` with the wire
+// protocol they speak and the SHA3-256 of the entry file — both are checked
+// before launch, so an app/bridge pair from different builds fails with a
+// `BridgeVersionMismatch` instead of misbehaving mid-session.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+use crate::error::{ManifoldError, Result};
+
+/// Launch-config / WebSocket protocol revision.  Bump together with
+/// `BRIDGE_PROTOCOL` in playwright-bridge/types.ts on incompatible changes.
+pub const BRIDGE_PROTOCOL: u32 = 1;
+
+/// Written next to a shipped bridge's entry file by scripts/bridge-manifest.ts.
+pub const MANIFEST_NAME: &str = "bridge-manifest.json";
+
+#[cfg(windows)]
+const SIDECAR_NAME: &str = "manifold-bridge.exe";
+#[cfg(not(windows))]
+const SIDECAR_NAME: &str = "manifold-bridge";
+
+#[cfg(windows)]
+const NODE: &str = "node.exe";
+#[cfg(not(windows))]
+const NODE: &str = "node";
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "path", rename_all = "snake_case")]
+pub enum BridgeRuntime {
+    /// Self-contained compiled bridge.
+    Sidecar(PathBuf),
+    /// Bundled JS run with `node`.
+    PackagedJs(PathBuf),
+    /// TypeScript source in a dev checkout, run with `npx tsx`.
+    Source(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeManifest {
+    /// Bridge package version, for display.
+    pub version: String,
+    pub protocol: u32,
+    /// Hex SHA3-256 of the entry file.
+    pub sha3_256: String,
+}
+
+/// The bridge a launch will use.
+#[derive(Debug, Clone, Serialize)]
+pub struct LocatedBridge {
+    pub runtime: BridgeRuntime,
+    /// `None` for a source checkout, which has no manifest.
+    pub manifest: Option<BridgeManifest>,
+}
+
+/// Where to look, most specific first.
+#[derive(Debug, Clone)]
+pub struct BridgeSearch {
+    /// Directories that may hold the sidecar and the `playwright-bridge/`
+    /// resource folder.
+    pub install_dirs: Vec<PathBuf>,
+    /// Dev checkout root holding `playwright-bridge/index.ts`.
+    pub workspace: PathBuf,
+}
+
+impl BridgeSearch {
+    /// The executable's directory plus where Tauri bundles put resources
+    /// (macOS `Contents/Resources`, Linux `/usr/lib/<app>`), and the CWD as
+    /// the dev workspace.
+    pub fn from_env() -> Self {
+        let install_dirs = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+            .map(|dir| vec![dir.join("../Resources"), dir.join("../lib/manifold"), dir])
+            .unwrap_or_default();
+        Self {
+            install_dirs,
+            workspace: std::env::current_dir().unwrap_or_else(|_| ".".into()),
+        }
+    }
+}
+
+// ── Location ──────────────────────────────────────────────────────────────────
+
+/// Resolve and verify the bridge.  Shipped bridges win over a source
+/// checkout; a shipped bridge that fails verification is an error rather
+/// than a reason to fall back.
+pub fn locate(search: &BridgeSearch) -> Result<LocatedBridge> {
+    for dir in &search.install_dirs {
+        let sidecar = dir.join(SIDECAR_NAME);
+        if sidecar.is_file() {
+            let manifest = verify(&sidecar)?;
+            return Ok(LocatedBridge {
+                runtime: BridgeRuntime::Sidecar(sidecar),
+                manifest: Some(manifest),
+            });
+        }
+        let js = dir.join("playwright-bridge").join("dist").join("index.js");
+        if js.is_file() {
+            let manifest = verify(&js)?;
+            return Ok(LocatedBridge {
+                runtime: BridgeRuntime::PackagedJs(js),
+                manifest: Some(manifest),
+            });
+        }
+    }
+    let source = search.workspace.join("playwright-bridge").join("index.ts");
+    if source.is_file() {
+        return Ok(LocatedBridge {
+            runtime: BridgeRuntime::Source(source),
+            manifest: None,
+        });
+    }
+    Err(ManifoldError::BridgeOffline)
+}
+
+/// Check the manifest next to `entry` against this build and the file.
+pub fn verify(entry: &Path) -> Result<BridgeManifest> {
+    let manifest_path = entry.with_file_name(MANIFEST_NAME);
+    let manifest: BridgeManifest = match std::fs::read(&manifest_path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)?,
+        Err(_) => {
+            return Err(ManifoldError::BridgeVersionMismatch {
+                expected: format!("protocol {BRIDGE_PROTOCOL}"),
+                found: format!("no {MANIFEST_NAME} beside {}", entry.display()),
+            })
+        }
+    };
+    if manifest.protocol != BRIDGE_PROTOCOL {
+        return Err(ManifoldError::BridgeVersionMismatch {
+            expected: format!("protocol {BRIDGE_PROTOCOL}"),
+            found: format!(
+                "protocol {} (bridge {})",
+                manifest.protocol, manifest.version
+            ),
+        });
+    }
+    let actual = hex::encode(Sha3_256::digest(std::fs::read(entry)?));
+    if !actual.eq_ignore_ascii_case(&manifest.sha3_256) {
+        return Err(ManifoldError::BridgeVersionMismatch {
+            expected: format!("sha3-256 {}", manifest.sha3_256),
+            found: format!("sha3-256 {actual} (modified bridge {})", manifest.version),
+        });
+    }
+    Ok(manifest)
+}
+
+impl LocatedBridge {
+    /// The command that starts this bridge.  `node_dir` holds the Node
+    /// install for JS and source bridges; `workspace` is where `tsx` is
+    /// installed for a source bridge.
+    pub fn command(&self, node_dir: Option<&Path>, workspace: &Path) -> Result<Command> {
+        let node_dir = || {
+            node_dir.ok_or_else(|| {
+                ManifoldError::Other(
+                    "node not found — set the Node directory in launch settings".into(),
+                )
+            })
+        };
+        Ok(match &self.runtime {
+            BridgeRuntime::Sidecar(path) => Command::new(path),
+            BridgeRuntime::PackagedJs(entry) => {
+                let mut cmd = Command::new(node_dir()?.join(NODE));
+                cmd.arg(entry);
+                cmd
+            }
+            BridgeRuntime::Source(entry) => {
+                let mut cmd = Command::new(crate::launch_env::npx_path(node_dir()?));
+                // --prefix: tsx is resolved from the workspace, not the working dir
+                cmd.arg("--prefix").arg(workspace).arg("tsx").arg(entry);
+                cmd
+            }
+        })
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_bridge(dir: &Path, protocol: u32, tamper: bool) -> PathBuf {
+        let js_dir = dir.join("playwright-bridge").join("dist");
+        std::fs::create_dir_all(&js_dir).unwrap();
+        let entry = js_dir.join("index.js");
+        std::fs::write(&entry, b"console.log('bridge')").unwrap();
+        let manifest = BridgeManifest {
+            version: "0.1.0".into(),
+            protocol,
+            sha3_256: hex::encode(Sha3_256::digest(b"console.log('bridge')")),
+        };
+        std::fs::write(
+            js_dir.join(MANIFEST_NAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        if tamper {
+            std::fs::write(&entry, b"console.log('patched')").unwrap();
+        }
+        entry
+    }
+
+    fn search(dir: &Path) -> BridgeSearch {
+        BridgeSearch {
+            install_dirs: vec![dir.to_path_buf()],
+            workspace: dir.join("no-checkout"),
+        }
+    }
+
+    #[test]
+    fn packaged_js_with_matching_manifest_is_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = write_bridge(dir.path(), BRIDGE_PROTOCOL, false);
+        let found = locate(&search(dir.path())).unwrap();
+        assert_eq!(found.runtime, BridgeRuntime::PackagedJs(entry));
+        assert_eq!(found.manifest.unwrap().version, "0.1.0");
+    }
+
+    #[test]
+    fn protocol_and_checksum_mismatches_are_typed() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bridge(dir.path(), BRIDGE_PROTOCOL + 1, false);
+        let err = locate(&search(dir.path())).unwrap_err();
+        assert!(matches!(err, ManifoldError::BridgeVersionMismatch { .. }));
+
+        let dir = tempfile::tempdir().unwrap();
+        write_bridge(dir.path(), BRIDGE_PROTOCOL, true);
+        let err = locate(&search(dir.path())).unwrap_err();
+        assert!(err.to_string().contains("modified bridge"), "{err}");
+    }
+
+    #[test]
+    fn falls_back_to_source_checkout_then_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            locate(&search(dir.path())),
+            Err(ManifoldError::BridgeOffline)
+        ));
+
+        let src = dir.path().join("no-checkout").join("playwright-bridge");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("index.ts"), "").unwrap();
+        let found = locate(&search(dir.path())).unwrap();
+        assert!(matches!(found.runtime, BridgeRuntime::Source(_)));
+        assert!(found.manifest.is_none());
+    }
+}
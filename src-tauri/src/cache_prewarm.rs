@@ -0,0 +1,333 @@
+// ── Manifold cache prewarming ─────────────────────────────────────────────────
+//
+// A new profile starts with an empty HTTP cache, so its first pages fetch
+// every web font and CDN library through the profile's (metered) proxy.  A
+// shared layer of immutable CDN responses — harvested from a profile's cache,
+// keeping only entries of the hosts in `CachePrewarmSettings::hosts` — can be
+// copied into each new data dir at creation.
+//
+// Entries are copied, never symlinked: Chromium rewrites and evicts cache
+// files in place, which would change the shared layer under every profile.
+// The cache index is left out; Chromium rebuilds it from the entry files.
+// Entries already in the shared layer are never replaced.
+//
+// Off by default: cache state is itself a fingerprint.  Every prewarmed
+// profile starts with the same entries, which cache-timing probes can link,
+// and Chrome's partitioned cache only reuses an entry under the top-level
+// site it was stored for.  A partitioned key names that site, i.e. where
+// the donor browsed, so a harvest only keeps partitioned entries of the
+// sites in `CachePrewarmSettings::top_frame_sites`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+
+/// `settings` key holding `CachePrewarmSettings` (JSON).
+pub const CACHE_PREWARM_KEY: &str = "cache_prewarm";
+
+/// The shared layer, under the profiles root (skipped by orphan GC).
+pub const SHARED_CACHE_DIR: &str = ".shared_cache";
+
+/// Chromium's HTTP cache inside a user-data dir.
+const CACHE_DATA: &str = "Default/Cache/Cache_Data";
+
+/// `initial_magic_number` of a simple-cache entry file.
+const ENTRY_MAGIC: u64 = 0xfcfb_6d1b_a772_5c30;
+/// `SimpleFileHeader` precedes the key: magic, version, key length, key
+/// hash and 4 bytes of padding.
+const ENTRY_HEADER_LEN: usize = 24;
+
+/// Web font and library CDNs whose responses are immutable by URL.
+pub const DEFAULT_HOSTS: &[&str] = &[
+    "fonts.googleapis.com",
+    "fonts.gstatic.com",
+    "ajax.googleapis.com",
+    "cdnjs.cloudflare.com",
+    "cdn.jsdelivr.net",
+    "unpkg.com",
+    "code.jquery.com",
+    "use.fontawesome.com",
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CachePrewarmSettings {
+    /// Copy the shared layer into new profiles' data dirs.
+    pub enabled: bool,
+    /// Hosts (and their subdomains) whose entries a harvest keeps.
+    pub hosts: Vec<String>,
+    /// Top-level sites (and their subdomains) whose partitioned entries a
+    /// harvest keeps; entries cached under any other site are left out.
+    pub top_frame_sites: Vec<String>,
+}
+
+impl Default for CachePrewarmSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hosts: DEFAULT_HOSTS.iter().map(|h| h.to_string()).collect(),
+            top_frame_sites: Vec::new(),
+        }
+    }
+}
+
+impl CachePrewarmSettings {
+    pub fn validate(&self) -> Result<()> {
+        for host in self.hosts.iter().chain(&self.top_frame_sites) {
+            let valid = !host.is_empty()
+                && host
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"-.".contains(&b));
+            if !valid {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "cache prewarm host {host:?}: expected a lowercase hostname"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The shared layer's contents.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SharedCache {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// Outcome of a harvest.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HarvestReport {
+    /// Entries in the profile's cache.
+    pub scanned: usize,
+    /// Entries new to the shared layer.
+    pub added: usize,
+    /// The shared layer afterwards.
+    pub shared: SharedCache,
+}
+
+// ── Entries ───────────────────────────────────────────────────────────────────
+
+/// The cache key stored in a simple-cache entry file (`<hash>_0`).
+pub fn entry_key(bytes: &[u8]) -> Option<&str> {
+    let header = bytes.get(..ENTRY_HEADER_LEN)?;
+    let magic = u64::from_le_bytes(header[..8].try_into().ok()?);
+    if magic != ENTRY_MAGIC {
+        return None;
+    }
+    let key_len = u32::from_le_bytes(header[12..16].try_into().ok()?) as usize;
+    let key = bytes.get(ENTRY_HEADER_LEN..ENTRY_HEADER_LEN + key_len)?;
+    std::str::from_utf8(key).ok()
+}
+
+/// The URL of a cache key.  Partitioned keys (`1/0/_dk_<site> <site> <url>`)
+/// end with it.
+pub fn key_url(key: &str) -> &str {
+    key.rsplit(' ').next().unwrap_or(key)
+}
+
+/// The top-level site a partitioned key was stored under; `None` for keys
+/// without a partition.
+pub fn key_top_frame_site(key: &str) -> Option<&str> {
+    let (prefix, _) = key.split_once(' ')?;
+    prefix.split_once("_dk_").map(|(_, site)| site)
+}
+
+/// Whether a harvest keeps the entry stored under `key`: its URL is on
+/// `hosts`, and a partitioned key's site is on `sites`.
+fn harvestable(key: &str, hosts: &[String], sites: &[String]) -> bool {
+    shareable(key_url(key), hosts) && key_top_frame_site(key).is_none_or(|s| shareable(s, sites))
+}
+
+/// Whether `url` is an https URL on one of `hosts` or a subdomain of one.
+pub fn shareable(url: &str, hosts: &[String]) -> bool {
+    let Some(rest) = url.strip_prefix("https://") else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#', ':']).next().unwrap_or("");
+    hosts.iter().any(|h| {
+        host == h
+            || host
+                .strip_suffix(h.as_str())
+                .is_some_and(|p| p.ends_with('.'))
+    })
+}
+
+/// The files of the entry whose key file is `key_file` (`<hash>_0`).
+fn entry_files(key_file: &Path) -> Vec<PathBuf> {
+    let name = key_file.file_name().unwrap_or_default().to_string_lossy();
+    let stem = name.trim_end_matches("_0");
+    ["_0", "_1", "_s"]
+        .iter()
+        .map(|suffix| key_file.with_file_name(format!("{stem}{suffix}")))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Key files (`<hash>_0`) under `dir`.
+fn key_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.to_string_lossy().ends_with("_0"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// ── Shared layer ──────────────────────────────────────────────────────────────
+
+pub fn shared_cache_dir(profiles_root: &Path) -> PathBuf {
+    profiles_root.join(SHARED_CACHE_DIR)
+}
+
+pub fn summary(shared: &Path) -> Result<SharedCache> {
+    let mut cache = SharedCache::default();
+    for key_file in key_files(shared)? {
+        cache.entries += 1;
+        cache.bytes += entry_files(&key_file)
+            .iter()
+            .filter_map(|f| f.metadata().ok())
+            .map(|m| m.len())
+            .sum::<u64>();
+    }
+    Ok(cache)
+}
+
+/// Add the entries of `data_dir`'s cache on `hosts` to the shared layer.
+/// Partitioned entries are only added when stored under one of `sites`.
+pub fn harvest(
+    data_dir: &Path,
+    shared: &Path,
+    hosts: &[String],
+    sites: &[String],
+) -> Result<HarvestReport> {
+    let mut report = HarvestReport::default();
+    std::fs::create_dir_all(shared)?;
+    for key_file in key_files(&data_dir.join(CACHE_DATA))? {
+        report.scanned += 1;
+        let bytes = std::fs::read(&key_file)?;
+        if !entry_key(&bytes).is_some_and(|key| harvestable(key, hosts, sites)) {
+            continue;
+        }
+        let name = key_file.file_name().unwrap_or_default();
+        if shared.join(name).exists() {
+            continue;
+        }
+        for file in entry_files(&key_file) {
+            std::fs::copy(&file, shared.join(file.file_name().unwrap_or_default()))?;
+        }
+        report.added += 1;
+    }
+    report.shared = summary(shared)?;
+    Ok(report)
+}
+
+/// Copy the shared layer into a data dir whose cache is still empty.
+/// Returns the entries copied.
+pub fn prewarm(shared: &Path, data_dir: &Path) -> Result<usize> {
+    let target = data_dir.join(CACHE_DATA);
+    if !key_files(&target)?.is_empty() {
+        return Ok(0);
+    }
+    let key_files = key_files(shared)?;
+    if key_files.is_empty() {
+        return Ok(0);
+    }
+    std::fs::create_dir_all(&target)?;
+    for key_file in &key_files {
+        for file in entry_files(key_file) {
+            std::fs::copy(&file, target.join(file.file_name().unwrap_or_default()))?;
+        }
+    }
+    Ok(key_files.len())
+}
+
+/// Delete the shared layer.  Returns the bytes freed.
+pub fn clear(shared: &Path) -> Result<u64> {
+    let bytes = crate::profile::dir_size(shared);
+    match std::fs::remove_dir_all(shared) {
+        Ok(()) => Ok(bytes),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str) -> Vec<u8> {
+        let mut bytes = ENTRY_MAGIC.to_le_bytes().to_vec();
+        bytes.extend(5u32.to_le_bytes());
+        bytes.extend((key.len() as u32).to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(key.as_bytes());
+        bytes.extend(b"response body");
+        bytes
+    }
+
+    #[test]
+    fn harvests_cdn_entries_and_prewarms_empty_caches() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let donor = dir.path().join("donor");
+        let cache = donor.join(CACHE_DATA);
+        std::fs::create_dir_all(&cache).unwrap();
+        let font = "1/0/_dk_https://news.example https://news.example \
+                    https://fonts.gstatic.com/s/roboto/v30/a.woff2";
+        std::fs::write(cache.join("aaaa_0"), entry(font)).unwrap();
+        std::fs::write(cache.join("aaaa_s"), b"sparse").unwrap();
+        let page = "1/0/_dk_https://news.example https://news.example https://news.example/";
+        std::fs::write(cache.join("bbbb_0"), entry(page)).unwrap();
+        let shop_font = "1/0/_dk_https://shop.example https://shop.example \
+                         https://fonts.gstatic.com/s/roboto/v30/b.woff2";
+        std::fs::write(cache.join("cccc_0"), entry(shop_font)).unwrap();
+        std::fs::write(cache.join("index"), b"index").unwrap();
+
+        assert_eq!(
+            key_url(entry_key(&entry(font)).unwrap()),
+            "https://fonts.gstatic.com/s/roboto/v30/a.woff2"
+        );
+        assert_eq!(entry_key(b"not a cache entry at all"), None);
+        assert_eq!(key_top_frame_site(font), Some("https://news.example"));
+        assert_eq!(key_top_frame_site("https://unpkg.com/x"), None);
+        let hosts = CachePrewarmSettings::default().hosts;
+        assert!(shareable("https://cdn.jsdelivr.net/npm/x", &hosts));
+        assert!(!shareable("https://evilunpkg.com/x", &hosts));
+        assert!(!shareable("http://unpkg.com/x", &hosts));
+
+        // Partitioned entries reveal where the donor browsed
+        let shared = shared_cache_dir(dir.path());
+        let report = harvest(&donor, &shared, &hosts, &[]).unwrap();
+        assert_eq!((report.scanned, report.added), (3, 0));
+
+        let sites = vec!["news.example".to_string()];
+        let report = harvest(&donor, &shared, &hosts, &sites).unwrap();
+        assert_eq!((report.scanned, report.added), (3, 1));
+        assert_eq!(report.shared.entries, 1);
+        // Immutable: a second harvest adds nothing
+        assert_eq!(harvest(&donor, &shared, &hosts, &sites).unwrap().added, 0);
+
+        let fresh = dir.path().join("fresh");
+        assert_eq!(prewarm(&shared, &fresh).unwrap(), 1);
+        assert!(fresh.join(CACHE_DATA).join("aaaa_s").is_file());
+        assert!(!fresh.join(CACHE_DATA).join("index").exists());
+        // A cache that has entries already is left alone
+        assert_eq!(prewarm(&shared, &donor).unwrap(), 0);
+
+        assert!(clear(&shared).unwrap() > 0);
+        assert_eq!(summary(&shared).unwrap(), SharedCache::default());
+    }
+}
@@ -0,0 +1,621 @@
+// ── Manifold proxy chains ─────────────────────────────────────────────────────
+//
+// A chain routes a profile through several proxies in order, e.g. a local
+// SOCKS5 hop followed by a residential HTTP exit.  Chromium only speaks to a
+// single proxy, so `ChainForwarder` binds a SOCKS5 listener on 127.0.0.1 that
+// the bridge is pointed at, and builds every browser connection hop by hop:
+// TCP to the first proxy, a tunnel through each proxy to the next, and
+// finally a tunnel from the exit proxy to the target.
+//
+// HTTP hops must support CONNECT; the plain-GET trick the single-proxy health
+// check uses for residential pools cannot carry a further hop.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::{ManifoldError, Result};
+use crate::proxy::{check_tls_config, CheckTarget, Proxy, ProxyType};
+
+/// Longest chain accepted; every hop adds a round trip to each connection.
+pub const MAX_CHAIN_LEN: usize = 5;
+
+const HOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainHealth {
+    /// Proxy ids, first hop first.
+    pub hops: Vec<String>,
+    pub healthy: bool,
+    pub latency_ms: Option<u32>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+// ── Validation ────────────────────────────────────────────────────────────────
+
+/// Check that `hops` can form a chain.
+pub fn validate_chain(hops: &[Proxy]) -> Result<()> {
+    if hops.is_empty() || hops.len() > MAX_CHAIN_LEN {
+        return Err(ManifoldError::InvalidArg(format!(
+            "a proxy chain needs 1–{MAX_CHAIN_LEN} hops, got {}",
+            hops.len()
+        )));
+    }
+    for (i, hop) in hops.iter().enumerate() {
+        // An SSH tunnel is a local SOCKS5 endpoint, so it can only start a chain
+        if i > 0 && hop.proxy_type == ProxyType::Ssh {
+            return Err(ManifoldError::InvalidArg(format!(
+                "SSH proxy {} can only be the first hop",
+                hop.name
+            )));
+        }
+        if hops[..i].iter().any(|h| h.id == hop.id) {
+            return Err(ManifoldError::InvalidArg(format!(
+                "proxy {} appears twice in the chain",
+                hop.name
+            )));
+        }
+        // RFC 1929 carries each credential behind a one-byte length
+        let too_long = |v: &Option<String>| v.as_ref().is_some_and(|v| v.len() > 255);
+        if hop.proxy_type == ProxyType::Socks5
+            && (too_long(&hop.username) || too_long(&hop.password))
+        {
+            return Err(ManifoldError::InvalidArg(format!(
+                "SOCKS5 proxy {} has a username or password longer than 255 bytes",
+                hop.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+// ── Tunnel building ───────────────────────────────────────────────────────────
+
+/// Open a TCP stream to `host:port` through every hop of the chain.
+pub fn open_chain(hops: &[Proxy], host: &str, port: u16) -> io::Result<TcpStream> {
+    let first = hops
+        .first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty proxy chain"))?;
+    let mut stream = connect_direct(first)?;
+
+    for (i, hop) in hops.iter().enumerate() {
+        let (next_host, next_port) = match hops.get(i + 1) {
+            Some(next) => (next.host.as_str(), next.port),
+            None => (host, port),
+        };
+        tunnel_through(&mut stream, hop, next_host, next_port)
+            .map_err(|e| io::Error::new(e.kind(), format!("hop {} ({}): {e}", i + 1, hop.name)))?;
+    }
+
+    stream.set_read_timeout(None)?;
+    stream.set_write_timeout(None)?;
+    Ok(stream)
+}
+
+fn connect_direct(proxy: &Proxy) -> io::Result<TcpStream> {
+    let (stream, _) = crate::dns::connect_proxy(proxy, HOP_TIMEOUT)
+        .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, e))?;
+    stream.set_read_timeout(Some(HOP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HOP_TIMEOUT))?;
+    Ok(stream)
+}
+
+/// Ask `hop` (already connected on `stream`) to open a tunnel to `host:port`.
+fn tunnel_through(stream: &mut TcpStream, hop: &Proxy, host: &str, port: u16) -> io::Result<()> {
+    match hop.proxy_type {
+        ProxyType::Http | ProxyType::Https => http_connect(stream, hop, host, port),
+        ProxyType::Socks5 => socks5_connect(stream, hop, host, port),
+        ProxyType::Ssh => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "SSH hops must be resolved to their local tunnel first",
+        )),
+    }
+}
+
+fn http_connect(stream: &mut TcpStream, hop: &Proxy, host: &str, port: u16) -> io::Result<()> {
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{host}]:{port}"),
+        _ => format!("{host}:{port}"),
+    };
+    let mut req = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let (Some(user), Some(pass)) = (&hop.username, &hop.password) {
+        use base64::Engine;
+        let creds = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+        req.push_str(&format!("Proxy-Authorization: Basic {creds}\r\n"));
+    }
+    req.push_str("\r\n");
+    stream.write_all(req.as_bytes())?;
+
+    // Read the response head byte-wise so no tunnel payload is swallowed
+    // by a buffered reader.
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte)? == 0 || head.len() > 8192 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "CONNECT response truncated",
+            ));
+        }
+        head.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let status = head.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        Some("407") => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "proxy authentication failed (407)",
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("CONNECT refused: {}", status.trim()),
+        )),
+    }
+}
+
+/// Length prefix of a SOCKS5 string field, which cannot exceed 255 bytes.
+fn field_len(value: &str) -> io::Result<u8> {
+    u8::try_from(value.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SOCKS5 field longer than 255 bytes",
+        )
+    })
+}
+
+fn socks5_connect(stream: &mut TcpStream, hop: &Proxy, host: &str, port: u16) -> io::Result<()> {
+    let has_auth = hop.username.is_some();
+    let greeting: &[u8] = if has_auth {
+        &[0x05, 0x02, 0x00, 0x02]
+    } else {
+        &[0x05, 0x01, 0x00]
+    };
+    stream.write_all(greeting)?;
+
+    let mut resp = [0u8; 2];
+    stream.read_exact(&mut resp)?;
+    if resp[0] != 0x05 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a SOCKS5 server",
+        ));
+    }
+    match resp[1] {
+        0x00 => {}
+        0x02 => {
+            let user = hop.username.as_deref().unwrap_or("");
+            let pass = hop.password.as_deref().unwrap_or("");
+            let mut auth = vec![0x01u8, field_len(user)?];
+            auth.extend_from_slice(user.as_bytes());
+            auth.push(field_len(pass)?);
+            auth.extend_from_slice(pass.as_bytes());
+            stream.write_all(&auth)?;
+            let mut ar = [0u8; 2];
+            stream.read_exact(&mut ar)?;
+            if ar[1] != 0x00 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "SOCKS5 authentication failed",
+                ));
+            }
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "SOCKS5 server accepted no offered auth method",
+            ))
+        }
+    }
+
+    let mut req = vec![0x05, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            req.push(0x01);
+            req.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            req.push(0x04);
+            req.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            req.push(0x03);
+            req.push(field_len(host)?);
+            req.extend_from_slice(host.as_bytes());
+        }
+    }
+    req.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&req)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply)?;
+    if reply[1] != 0x00 {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("SOCKS5 connect error 0x{:02x}", reply[1]),
+        ));
+    }
+    // Discard the bound address
+    let skip = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => 0,
+    };
+    let mut bound = vec![0u8; skip + 2];
+    stream.read_exact(&mut bound)?;
+    Ok(())
+}
+
+// ── Health check ──────────────────────────────────────────────────────────────
+
+/// Fetch the first reachable of the health-check `targets` through the full
+/// chain.
+pub fn check_chain(hops: &[Proxy], targets: &[String]) -> ChainHealth {
+    let started = Instant::now();
+    let mut errors = Vec::new();
+    let mut latency_ms = None;
+
+    for target in targets {
+        match fetch_head(hops, target) {
+            Ok(()) => {
+                latency_ms = Some(started.elapsed().as_millis() as u32);
+                break;
+            }
+            Err(e) => {
+                let auth = e.kind() == io::ErrorKind::PermissionDenied;
+                errors.push(format!("{target}: {e}"));
+                if auth {
+                    break;
+                }
+            }
+        }
+    }
+
+    ChainHealth {
+        hops: hops.iter().map(|h| h.id.clone()).collect(),
+        healthy: latency_ms.is_some(),
+        latency_ms,
+        error: latency_ms
+            .is_none()
+            .then(|| format!("chain check failed ({})", errors.join(" | "))),
+        checked_at: Utc::now(),
+    }
+}
+
+fn fetch_head(hops: &[Proxy], target: &str) -> io::Result<()> {
+    let target =
+        CheckTarget::parse(target).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let stream = open_chain(hops, &target.host, target.port)?;
+    stream.set_read_timeout(Some(HOP_TIMEOUT))?;
+    let head = format!(
+        "HEAD {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        target.path,
+        target.host_header()
+    );
+    let mut line = String::new();
+    if target.tls {
+        let name = rustls::pki_types::ServerName::try_from(target.host.clone())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let conn =
+            rustls::ClientConnection::new(check_tls_config(), name).map_err(io::Error::other)?;
+        let mut tls = rustls::StreamOwned::new(conn, stream);
+        tls.write_all(head.as_bytes())?;
+        BufReader::new(tls).read_line(&mut line)?;
+    } else {
+        (&stream).write_all(head.as_bytes())?;
+        BufReader::new(&stream).read_line(&mut line)?;
+    }
+    if line.starts_with("HTTP/") {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected response: {}", line.trim()),
+        ))
+    }
+}
+
+// ── Local forwarder ───────────────────────────────────────────────────────────
+
+/// Local SOCKS5 endpoint that tunnels every connection through a chain.
+/// Stops when dropped.
+pub struct ChainForwarder {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ChainForwarder {
+    /// Bind an ephemeral port on 127.0.0.1 and start accepting.
+    pub fn start(hops: Vec<Proxy>) -> Result<Self> {
+        validate_chain(&hops)?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let hops = Arc::new(hops);
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            for client in listener.incoming() {
+                if stop_flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(client) = client else { continue };
+                let hops = hops.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = serve_client(client, &hops) {
+                        eprintln!("[chain] connection failed: {e}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            port,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// `server` value for the Playwright proxy config.
+    pub fn server_url(&self) -> String {
+        format!("socks5://127.0.0.1:{}", self.port)
+    }
+}
+
+impl Drop for ChainForwarder {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so the loop sees the flag.
+        TcpStream::connect(("127.0.0.1", self.port)).ok();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Minimal SOCKS5 server side (no auth, CONNECT only).
+fn serve_client(mut client: TcpStream, hops: &[Proxy]) -> io::Result<()> {
+    let mut head = [0u8; 2];
+    client.read_exact(&mut head)?;
+    let mut methods = vec![0u8; head[1] as usize];
+    client.read_exact(&mut methods)?;
+    if head[0] != 0x05 || !methods.contains(&0x00) {
+        client.write_all(&[0x05, 0xff])?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "client did not offer SOCKS5 no-auth",
+        ));
+    }
+    client.write_all(&[0x05, 0x00])?;
+
+    let mut req = [0u8; 4];
+    client.read_exact(&mut req)?;
+    let host = match req[3] {
+        0x01 => {
+            let mut ip = [0u8; 4];
+            client.read_exact(&mut ip)?;
+            IpAddr::from(ip).to_string()
+        }
+        0x04 => {
+            let mut ip = [0u8; 16];
+            client.read_exact(&mut ip)?;
+            IpAddr::from(ip).to_string()
+        }
+        0x03 => {
+            let mut len = [0u8; 1];
+            client.read_exact(&mut len)?;
+            let mut name = vec![0u8; len[0] as usize];
+            client.read_exact(&mut name)?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        _ => {
+            client.write_all(&[0x05, 0x08, 0x00, 0x01, 0, 0, 0, 0, 0, 0])?;
+            return Ok(());
+        }
+    };
+    let mut port = [0u8; 2];
+    client.read_exact(&mut port)?;
+    let port = u16::from_be_bytes(port);
+
+    if req[1] != 0x01 {
+        client.write_all(&[0x05, 0x07, 0x00, 0x01, 0, 0, 0, 0, 0, 0])?;
+        return Ok(());
+    }
+
+    let upstream = match open_chain(hops, &host, port) {
+        Ok(s) => s,
+        Err(e) => {
+            client.write_all(&[0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0])?;
+            return Err(e);
+        }
+    };
+    client.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])?;
+    pipe(client, upstream)
+}
+
+/// Copy bytes both ways until either side closes.
+fn pipe(a: TcpStream, b: TcpStream) -> io::Result<()> {
+    let (mut a_read, mut b_write) = (a.try_clone()?, b.try_clone()?);
+    let upload = std::thread::spawn(move || {
+        io::copy(&mut a_read, &mut b_write).ok();
+        b_write.shutdown(Shutdown::Write).ok();
+    });
+    let (mut b_read, mut a_write) = (b, a);
+    io::copy(&mut b_read, &mut a_write).ok();
+    a_write.shutdown(Shutdown::Write).ok();
+    upload.join().ok();
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_proxy(id: &str, proxy_type: ProxyType, port: u16) -> Proxy {
+        Proxy {
+            id: id.into(),
+            name: id.into(),
+            proxy_type,
+            host: "127.0.0.1".into(),
+            port,
+            username: None,
+            password: None,
+            country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: Default::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
+            healthy: true,
+            latency_ms: None,
+            last_checked: None,
+        }
+    }
+
+    /// Echo server; returns its port.
+    fn spawn_echo() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut s in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut r = s.try_clone().unwrap();
+                    io::copy(&mut r, &mut s).ok();
+                });
+            }
+        });
+        port
+    }
+
+    /// Minimal HTTP CONNECT proxy; returns its port.
+    fn spawn_http_proxy() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut client in listener.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let mut head = Vec::new();
+                    let mut byte = [0u8; 1];
+                    while !head.ends_with(b"\r\n\r\n") {
+                        client.read_exact(&mut byte).unwrap();
+                        head.push(byte[0]);
+                    }
+                    let head = String::from_utf8(head).unwrap();
+                    let target = head.split_whitespace().nth(1).unwrap().to_string();
+                    let upstream = TcpStream::connect(target).unwrap();
+                    client
+                        .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                        .unwrap();
+                    pipe(client, upstream).ok();
+                });
+            }
+        });
+        port
+    }
+
+    fn assert_echoes(mut stream: TcpStream) {
+        stream.write_all(b"ping").unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[test]
+    fn validate_rejects_empty_long_and_repeated_chains() {
+        let a = local_proxy("a", ProxyType::Http, 1);
+        assert!(validate_chain(&[]).is_err());
+        assert!(validate_chain(&vec![a.clone(); 2]).is_err());
+        let many: Vec<_> = (0..=MAX_CHAIN_LEN)
+            .map(|i| local_proxy(&i.to_string(), ProxyType::Http, 1))
+            .collect();
+        assert!(validate_chain(&many).is_err());
+        let ssh = local_proxy("ssh", ProxyType::Ssh, 22);
+        assert!(validate_chain(&[a.clone(), ssh.clone()]).is_err());
+        assert!(validate_chain(&[ssh, a.clone()]).is_ok());
+        assert!(validate_chain(&[a]).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_socks5_credentials_over_255_bytes() {
+        let mut hop = local_proxy("s", ProxyType::Socks5, 1080);
+        hop.username = Some("u".repeat(255));
+        hop.password = Some("p".into());
+        assert!(validate_chain(std::slice::from_ref(&hop)).is_ok());
+        hop.password = Some("p".repeat(256));
+        assert!(matches!(
+            validate_chain(&[hop]),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+        assert!(field_len(&"h".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn open_chain_tunnels_through_two_http_hops() {
+        let echo = spawn_echo();
+        let hops = [
+            local_proxy("first", ProxyType::Http, spawn_http_proxy()),
+            local_proxy("exit", ProxyType::Http, spawn_http_proxy()),
+        ];
+        assert_echoes(open_chain(&hops, "127.0.0.1", echo).unwrap());
+    }
+
+    #[test]
+    fn forwarder_serves_socks5_and_can_itself_be_a_hop() {
+        let echo = spawn_echo();
+        let forwarder = ChainForwarder::start(vec![local_proxy(
+            "exit",
+            ProxyType::Http,
+            spawn_http_proxy(),
+        )])
+        .unwrap();
+        assert!(forwarder.server_url().starts_with("socks5://127.0.0.1:"));
+
+        // SOCKS5 hop (the forwarder) → HTTP hop → echo
+        let hops = [
+            local_proxy("local", ProxyType::Socks5, forwarder.port()),
+            local_proxy("residential", ProxyType::Http, spawn_http_proxy()),
+        ];
+        assert_echoes(open_chain(&hops, "127.0.0.1", echo).unwrap());
+    }
+
+    #[test]
+    fn dropping_forwarder_stops_listening() {
+        let forwarder = ChainForwarder::start(vec![local_proxy(
+            "exit",
+            ProxyType::Http,
+            spawn_http_proxy(),
+        )])
+        .unwrap();
+        let port = forwarder.port();
+        drop(forwarder);
+        assert!(TcpStream::connect(("127.0.0.1", port)).is_err());
+    }
+}
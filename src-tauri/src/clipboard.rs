@@ -0,0 +1,270 @@
+// ── Manifold profile clipboards ───────────────────────────────────────────────
+//
+// Each launched browser gets a virtual clipboard of its own: the bridge
+// keeps copy, paste and `navigator.clipboard` inside the profile, so pages
+// never see the host clipboard and nothing copied in a profile lands on it.
+// Operators hand the profile a value (a 2FA code, an order number) through
+// `clipboard_write`, optionally pasting it straight into the focused field,
+// and read the profile's clipboard back with `clipboard_read`.
+//
+// Commands travel on the launched bridge's stdin, one JSON per line, and are
+// answered by a `CLIPBOARD_RESULT` line on its stdout.  Only one bridge runs
+// at a time, so there is at most one channel, tied to the running profile.
+//
+// Every write and read is logged as a `clipboard` event with the value
+// redacted: its length and shape, never its content.
+
+use std::io::Write;
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+
+/// Prefix of the stdout line answering one clipboard command.
+pub const CLIPBOARD_RESULT_LINE_PREFIX: &str = "CLIPBOARD_RESULT ";
+
+/// Longest value a profile's clipboard takes, in characters.
+pub const MAX_CLIPBOARD_CHARS: usize = 10_000;
+
+/// How long the bridge gets to answer.
+const CLIPBOARD_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// A command for the bridge's virtual clipboard.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClipboardCommand {
+    /// Replace the clipboard; `paste` also types it into the focused field.
+    ClipboardWrite {
+        text: String,
+        paste: bool,
+    },
+    ClipboardRead,
+}
+
+/// The bridge's answer to one command.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ClipboardResult {
+    pub ok: bool,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// What the audit log keeps of a value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactedValue {
+    pub chars: usize,
+    /// `digits`, `alphanumeric`, `text` or `multiline`.
+    pub shape: String,
+    /// One `•` per character, up to 12.
+    pub masked: String,
+}
+
+impl RedactedValue {
+    pub fn of(text: &str) -> Self {
+        let chars = text.chars().count();
+        let shape = if text.contains('\n') {
+            "multiline"
+        } else if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) {
+            "digits"
+        } else if !text.is_empty() && text.chars().all(char::is_alphanumeric) {
+            "alphanumeric"
+        } else {
+            "text"
+        };
+        Self {
+            chars,
+            shape: shape.into(),
+            masked: "•".repeat(chars.min(12)),
+        }
+    }
+}
+
+/// Pull a clipboard result out of the bridge's stdout line, if this is one.
+pub fn parse_result_line(line: &str) -> Option<ClipboardResult> {
+    let json = line.trim().strip_prefix(CLIPBOARD_RESULT_LINE_PREFIX)?;
+    Some(
+        serde_json::from_str(json).unwrap_or_else(|e| ClipboardResult {
+            ok: false,
+            text: None,
+            error: Some(format!("unreadable clipboard result: {e}")),
+        }),
+    )
+}
+
+// ── Bridge channel ────────────────────────────────────────────────────────────
+
+struct Channel {
+    profile_id: String,
+    pid: u32,
+    input: Box<dyn Write + Send>,
+    results: Receiver<ClipboardResult>,
+}
+
+/// The running bridge's clipboard channel, if it has one.
+#[derive(Default)]
+pub struct BridgeClipboard {
+    channel: Mutex<Option<Channel>>,
+}
+
+impl BridgeClipboard {
+    /// Route commands for `profile_id` to the bridge with `pid`.
+    pub fn attach(
+        &self,
+        profile_id: &str,
+        pid: u32,
+        input: Box<dyn Write + Send>,
+        results: Receiver<ClipboardResult>,
+    ) {
+        *self.channel.lock().unwrap() = Some(Channel {
+            profile_id: profile_id.into(),
+            pid,
+            input,
+            results,
+        });
+    }
+
+    /// Drop the channel of the bridge with `pid`, once it has exited.
+    pub fn detach(&self, pid: u32) {
+        let mut channel = self.channel.lock().unwrap();
+        if channel.as_ref().is_some_and(|c| c.pid == pid) {
+            *channel = None;
+        }
+    }
+
+    /// Send `command` to `profile_id`'s browser and wait for its answer.
+    pub fn request(&self, profile_id: &str, command: &ClipboardCommand) -> Result<ClipboardResult> {
+        if let ClipboardCommand::ClipboardWrite { text, .. } = command {
+            if text.chars().count() > MAX_CLIPBOARD_CHARS {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "clipboard values are limited to {MAX_CLIPBOARD_CHARS} characters"
+                )));
+            }
+        }
+        let mut guard = self.channel.lock().unwrap();
+        let channel = guard
+            .as_mut()
+            .filter(|c| c.profile_id == profile_id)
+            .ok_or_else(|| {
+                ManifoldError::InvalidArg(format!("profile {profile_id} is not running"))
+            })?;
+        // Answers to earlier commands that timed out
+        while channel.results.try_recv().is_ok() {}
+        let json = serde_json::to_string(command)?;
+        writeln!(channel.input, "{json}")
+            .and_then(|_| channel.input.flush())
+            .map_err(|e| ManifoldError::Bridge(format!("bridge is gone: {e}")))?;
+        let result = channel
+            .results
+            .recv_timeout(CLIPBOARD_TIMEOUT)
+            .map_err(|_| ManifoldError::Bridge("bridge did not answer".into()))?;
+        match result {
+            ClipboardResult {
+                ok: false, error, ..
+            } => Err(ManifoldError::Bridge(
+                error.unwrap_or_else(|| "clipboard command failed".into()),
+            )),
+            ok => Ok(ok),
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    /// Collects what the channel writes so the test can inspect it.
+    #[derive(Clone, Default)]
+    struct Sink(Arc<StdMutex<Vec<u8>>>);
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn commands_reach_only_the_running_profile_and_values_are_redacted() {
+        let clipboard = BridgeClipboard::default();
+        let write = ClipboardCommand::ClipboardWrite {
+            text: "482913".into(),
+            paste: true,
+        };
+        assert!(clipboard.request("p1", &write).is_err());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let sink = Sink::default();
+        clipboard.attach("p1", 7, Box::new(sink.clone()), rx);
+        assert!(clipboard.request("p2", &write).is_err());
+
+        // A stale answer from an earlier timed-out command is skipped
+        tx.send(parse_result_line(r#"CLIPBOARD_RESULT {"ok":true,"text":"old"}"#).unwrap())
+            .unwrap();
+        let answer = std::thread::spawn({
+            let tx = tx.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(50));
+                tx.send(parse_result_line(r#"CLIPBOARD_RESULT {"ok":true}"#).unwrap())
+                    .unwrap();
+            }
+        });
+        assert_eq!(clipboard.request("p1", &write).unwrap().text, None);
+        answer.join().unwrap();
+        let sent = String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            sent,
+            "{\"type\":\"clipboard_write\",\"text\":\"482913\",\"paste\":true}\n"
+        );
+
+        let answer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            tx.send(
+                parse_result_line(r#"CLIPBOARD_RESULT {"ok":false,"error":"no page"}"#).unwrap(),
+            )
+            .unwrap();
+        });
+        let failed = clipboard.request("p1", &ClipboardCommand::ClipboardRead);
+        answer.join().unwrap();
+        assert!(failed.unwrap_err().to_string().contains("no page"));
+
+        let too_long = ClipboardCommand::ClipboardWrite {
+            text: "x".repeat(MAX_CLIPBOARD_CHARS + 1),
+            paste: false,
+        };
+        assert!(matches!(
+            clipboard.request("p1", &too_long),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+
+        clipboard.detach(8);
+        assert!(clipboard.channel.lock().unwrap().is_some());
+        clipboard.detach(7);
+        assert!(clipboard
+            .request("p1", &ClipboardCommand::ClipboardRead)
+            .is_err());
+
+        let code = RedactedValue::of("482913");
+        assert_eq!((code.chars, code.shape.as_str()), (6, "digits"));
+        assert_eq!(code.masked, "••••••");
+        assert_eq!(RedactedValue::of("Ab3x9").shape, "alphanumeric");
+        assert_eq!(RedactedValue::of("a\nb").shape, "multiline");
+        assert_eq!(
+            RedactedValue::of(&"y".repeat(40)).masked.chars().count(),
+            12
+        );
+        assert!(parse_result_line("STEP_RESULT {}").is_none());
+    }
+}
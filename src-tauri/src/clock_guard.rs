@@ -0,0 +1,386 @@
+// ── Manifold clock drift guard ────────────────────────────────────────────────
+//
+// A host clock that jumps mid-session — a VM snapshot restored, a laptop
+// resumed from suspend, an NTP step — shows up to sites as `Date.now()`
+// moving by a different amount than `performance.now()`.  That is a known
+// correlation vector (the same jump appears on every profile of the host),
+// so sessions where it happens are flagged.
+//
+// The bridge prints a `CLOCK_SAMPLE {json}` line periodically with its own
+// wall/monotonic readings and the page's `Date.now()` / `performance.now()`.
+// Between two samples each wall delta must match its monotonic delta; the
+// backend's own monotonic clock is a third reference that a VM pause can't
+// rewind together with the guest.
+//
+// A clock that is steadily off is the other failure: sites compare
+// `Date.now()` with their own time, TLS-adjacent heuristics see timestamps
+// from the wrong minute and one-time codes fall outside their window.  The
+// host's offset from an NTP server (`measure_skew`) is kept in `settings`
+// (`host_clock`) and judged by the geo validator against the tolerance
+// windows in `clock_tolerance`.  The emulated timezone doesn't change the
+// offset: the proxy region's local time is off by exactly the host's skew.
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+
+/// Prefix of the bridge's clock sample lines.
+pub const SAMPLE_LINE_PREFIX: &str = "CLOCK_SAMPLE ";
+
+/// Wall/monotonic disagreement (ms) treated as a jump.  Samples are taken on
+/// a timer, so scheduling delay alone never gets near this.
+pub const JUMP_THRESHOLD_MS: f64 = 2_000.0;
+
+/// `settings` key holding the last `HostClock` (JSON).
+pub const HOST_CLOCK_KEY: &str = "host_clock";
+
+/// `settings` key holding the `ClockTolerance` (JSON).
+pub const CLOCK_TOLERANCE_KEY: &str = "clock_tolerance";
+
+/// Asked in order until one answers.
+const NTP_SERVERS: &[&str] = &[
+    "time.cloudflare.com:123",
+    "time.google.com:123",
+    "pool.ntp.org:123",
+];
+
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Seconds from the NTP epoch (1900) to the Unix epoch.
+const NTP_UNIX_OFFSET_SECS: i64 = 2_208_988_800;
+
+/// A reading this old no longer says what the clock is doing.
+pub const HOST_CLOCK_MAX_AGE_HOURS: i64 = 24;
+
+/// One bridge reading.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockSample {
+    /// Bridge `Date.now()`
+    pub wall_ms: f64,
+    /// Bridge `performance.now()` (monotonic since bridge start)
+    pub mono_ms: f64,
+    /// Page `Date.now()` / `performance.now()`; `None` while navigating.
+    pub page_wall_ms: Option<f64>,
+    pub page_perf_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JumpSource {
+    /// Wall clock moved differently from the backend's monotonic clock.
+    Host,
+    /// Bridge `Date.now()` vs `performance.now()`.
+    Bridge,
+    /// Page `Date.now()` vs `performance.now()`.
+    Page,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockJump {
+    pub source: JumpSource,
+    /// Wall delta minus monotonic delta: positive when the wall clock ran
+    /// ahead (resume, snapshot restore), negative when it was set back.
+    pub skew_ms: i64,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// The host clock against an NTP server, as last measured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostClock {
+    pub server: String,
+    /// Host time minus the server's: positive when the host runs ahead.
+    pub skew_ms: i64,
+    pub round_trip_ms: u64,
+    pub measured_at: DateTime<Utc>,
+}
+
+impl HostClock {
+    pub fn is_stale(&self) -> bool {
+        Utc::now() - self.measured_at > chrono::Duration::hours(HOST_CLOCK_MAX_AGE_HOURS)
+    }
+}
+
+/// How far the host clock may be off before validation flags it: past
+/// `soft_ms` it is a warning, past `hard_ms` an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockTolerance {
+    pub soft_ms: u64,
+    pub hard_ms: u64,
+}
+
+impl Default for ClockTolerance {
+    /// One TOTP step before a warning; five minutes, where OTP servers'
+    /// grace windows and signed-request checks end, before an error.
+    fn default() -> Self {
+        Self {
+            soft_ms: 30_000,
+            hard_ms: 300_000,
+        }
+    }
+}
+
+impl ClockTolerance {
+    pub fn validate(&self) -> Result<()> {
+        if self.soft_ms == 0 || self.soft_ms > self.hard_ms {
+            return Err(ManifoldError::InvalidArg(format!(
+                "clock tolerance needs 0 < soft ({} ms) <= hard ({} ms)",
+                self.soft_ms, self.hard_ms
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Pull a sample out of a bridge stdout line, if this is one.
+pub fn parse_sample_line(line: &str) -> Option<Result<ClockSample>> {
+    let json = line.trim().strip_prefix(SAMPLE_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).map_err(ManifoldError::from))
+}
+
+// ── Tracking ──────────────────────────────────────────────────────────────────
+
+/// Per-session drift state, fed with samples as they arrive.
+#[derive(Debug)]
+pub struct DriftTracker {
+    /// Wall clock (ms) and backend monotonic time of the previous host
+    /// reading — the launch itself until the first sample arrives.
+    host: (f64, Instant),
+    last: Option<ClockSample>,
+}
+
+impl DriftTracker {
+    /// Start tracking from the launch's wall clock and monotonic time.
+    pub fn new(launched_wall: DateTime<Utc>, launched_at: Instant) -> Self {
+        Self {
+            host: (launched_wall.timestamp_millis() as f64, launched_at),
+            last: None,
+        }
+    }
+
+    /// Compare `sample`, received at backend time `at`, with the previous
+    /// reading.  Returns every clock that jumped in between.
+    pub fn observe(&mut self, sample: ClockSample, at: Instant) -> Vec<ClockJump> {
+        let (host_wall, host_at) = std::mem::replace(&mut self.host, (sample.wall_ms, at));
+        let host_mono = at.saturating_duration_since(host_at).as_secs_f64() * 1000.0;
+        let mut pairs = vec![(JumpSource::Host, (sample.wall_ms - host_wall) - host_mono)];
+
+        let Some(prev) = self.last.replace(sample) else {
+            return to_jumps(pairs);
+        };
+        let wall = sample.wall_ms - prev.wall_ms;
+        pairs.push((JumpSource::Bridge, wall - (sample.mono_ms - prev.mono_ms)));
+        // A navigation resets the page's performance.now() origin; only
+        // compare page readings that share one (perf only moves forward)
+        if let (Some(pw), Some(pp), Some(qw), Some(qp)) = (
+            prev.page_wall_ms,
+            prev.page_perf_ms,
+            sample.page_wall_ms,
+            sample.page_perf_ms,
+        ) {
+            if qp >= pp {
+                pairs.push((JumpSource::Page, (qw - pw) - (qp - pp)));
+            }
+        }
+        to_jumps(pairs)
+    }
+}
+
+fn to_jumps(pairs: Vec<(JumpSource, f64)>) -> Vec<ClockJump> {
+    pairs
+        .into_iter()
+        .filter(|(_, skew)| skew.abs() >= JUMP_THRESHOLD_MS)
+        .map(|(source, skew)| ClockJump {
+            source,
+            skew_ms: skew.round() as i64,
+            detected_at: Utc::now(),
+        })
+        .collect()
+}
+
+// ── Host clock skew ───────────────────────────────────────────────────────────
+
+/// Measure the host clock against the first NTP server that answers.
+pub fn measure_skew() -> Result<HostClock> {
+    let mut errors = Vec::new();
+    for server in NTP_SERVERS {
+        match query_ntp(server) {
+            Ok(clock) => return Ok(clock),
+            Err(e) => errors.push(format!("{server}: {e}")),
+        }
+    }
+    Err(ManifoldError::Other(format!(
+        "no NTP server answered ({})",
+        errors.join("; ")
+    )))
+}
+
+/// One SNTP exchange (RFC 4330) with `server`.
+fn query_ntp(server: &str) -> io::Result<HostClock> {
+    let addr = server
+        .to_socket_addrs()?
+        .find(SocketAddr::is_ipv4)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no IPv4 address"))?;
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(NTP_TIMEOUT))?;
+
+    let mut request = [0u8; 48];
+    request[0] = 0x23; // leap 0, version 4, mode 3 (client)
+    let sent_wall = Utc::now().timestamp_millis();
+    let sent = Instant::now();
+    socket.send_to(&request, addr)?;
+    let mut response = [0u8; 48];
+    let (len, _) = socket.recv_from(&mut response)?;
+    let round_trip = sent.elapsed();
+
+    let (received, transmitted) = parse_ntp_response(&response[..len])
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not an NTP server reply"))?;
+    // The return leg is timed on the monotonic clock so a jump mid-query
+    // can't skew the result
+    let returned_wall = sent_wall + round_trip.as_millis() as i64;
+    Ok(HostClock {
+        server: server.trim_end_matches(":123").to_string(),
+        skew_ms: skew_ms(sent_wall, received, transmitted, returned_wall),
+        round_trip_ms: round_trip.as_millis() as u64,
+        measured_at: Utc::now(),
+    })
+}
+
+/// The server's receive and transmit timestamps (Unix ms) from a reply.
+/// Kiss-of-death replies (stratum 0) and non-server modes are rejected.
+fn parse_ntp_response(reply: &[u8]) -> Option<(i64, i64)> {
+    if reply.len() < 48 || reply[0] & 0x07 != 4 || reply[1] == 0 {
+        return None;
+    }
+    let timestamp = |at: usize| {
+        let secs = u32::from_be_bytes(reply[at..at + 4].try_into().unwrap());
+        let frac = u32::from_be_bytes(reply[at + 4..at + 8].try_into().unwrap());
+        (secs as i64 - NTP_UNIX_OFFSET_SECS) * 1000 + ((frac as u64 * 1000) >> 32) as i64
+    };
+    Some((timestamp(32), timestamp(40)))
+}
+
+/// Host minus server time from the four SNTP timestamps: request sent (host
+/// clock), received and answered (server clock), answer back (host clock).
+fn skew_ms(sent: i64, received: i64, transmitted: i64, returned: i64) -> i64 {
+    ((sent - received) + (returned - transmitted)) / 2
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const LAUNCH_MS: i64 = 999_000;
+
+    fn tracker(start: Instant) -> DriftTracker {
+        let launched = DateTime::from_timestamp_millis(LAUNCH_MS).unwrap();
+        DriftTracker::new(launched, start - Duration::from_secs(1))
+    }
+
+    fn sample(wall: f64, mono: f64) -> ClockSample {
+        ClockSample {
+            wall_ms: wall,
+            mono_ms: mono,
+            page_wall_ms: Some(wall),
+            page_perf_ms: Some(mono),
+        }
+    }
+
+    #[test]
+    fn steady_clocks_report_nothing() {
+        let start = Instant::now();
+        let mut t = tracker(start);
+        assert!(t.observe(sample(1_000_000.0, 0.0), start).is_empty());
+        let at = start + Duration::from_secs(30);
+        assert!(t.observe(sample(1_030_050.0, 30_000.0), at).is_empty());
+    }
+
+    #[test]
+    fn suspend_shows_wall_ahead_of_every_monotonic_clock() {
+        let start = Instant::now();
+        let mut t = tracker(start);
+        t.observe(sample(1_000_000.0, 0.0), start);
+        // Ten minutes of wall time pass while monotonic clocks see 30 s
+        let at = start + Duration::from_secs(30);
+        let jumps = t.observe(sample(1_630_000.0, 30_000.0), at);
+        let sources: Vec<_> = jumps.iter().map(|j| j.source).collect();
+        assert_eq!(
+            sources,
+            [JumpSource::Host, JumpSource::Bridge, JumpSource::Page]
+        );
+        assert_eq!(jumps[1].skew_ms, 600_000);
+    }
+
+    #[test]
+    fn clock_set_back_is_negative_skew_and_navigation_is_ignored() {
+        let start = Instant::now();
+        let mut t = tracker(start);
+        t.observe(sample(1_000_000.0, 50_000.0), start);
+        let mut next = sample(1_025_000.0, 80_000.0);
+        // New document: performance.now() restarted near zero
+        next.page_perf_ms = Some(1_200.0);
+        let jumps = t.observe(next, start + Duration::from_secs(30));
+        assert_eq!(jumps.len(), 2);
+        assert!(jumps.iter().all(|j| j.skew_ms == -5_000));
+        assert!(jumps.iter().all(|j| j.source != JumpSource::Page));
+    }
+
+    #[test]
+    fn first_sample_is_checked_against_the_launch_anchor() {
+        let start = Instant::now();
+        let mut t = tracker(start);
+        // One second after launch by the backend's clock, an hour by the wall
+        let jumps = t.observe(sample((LAUNCH_MS + 3_601_000) as f64, 0.0), start);
+        assert_eq!(jumps.len(), 1);
+        assert_eq!(jumps[0].source, JumpSource::Host);
+        assert_eq!(jumps[0].skew_ms, 3_600_000);
+    }
+
+    #[test]
+    fn ntp_reply_gives_the_host_skew() {
+        let mut reply = [0u8; 48];
+        reply[0] = 0x24; // version 4, mode 4 (server)
+        reply[1] = 2;
+        // 2026-01-01T00:00:00Z and half a second later
+        let secs = (1_767_225_600 + NTP_UNIX_OFFSET_SECS) as u32;
+        reply[32..36].copy_from_slice(&secs.to_be_bytes());
+        reply[40..44].copy_from_slice(&secs.to_be_bytes());
+        reply[44..48].copy_from_slice(&(1u32 << 31).to_be_bytes());
+        let (received, transmitted) = parse_ntp_response(&reply).unwrap();
+        assert_eq!(received, 1_767_225_600_000);
+        assert_eq!(transmitted, 1_767_225_600_500);
+
+        // Host 90 s ahead, 100 ms each way
+        let sent = received + 90_000 - 100;
+        let returned = transmitted + 90_000 + 100;
+        assert_eq!(skew_ms(sent, received, transmitted, returned), 90_000);
+
+        reply[1] = 0;
+        assert_eq!(parse_ntp_response(&reply), None);
+        assert_eq!(parse_ntp_response(&reply[..40]), None);
+
+        assert!(ClockTolerance::default().validate().is_ok());
+        let inverted = ClockTolerance {
+            soft_ms: 60_000,
+            hard_ms: 1_000,
+        };
+        assert!(inverted.validate().is_err());
+    }
+
+    #[test]
+    fn parses_sample_line() {
+        let line =
+            r#"CLOCK_SAMPLE {"wallMs":1.0,"monoMs":2.0,"pageWallMs":null,"pagePerfMs":null}"#;
+        let s = parse_sample_line(line).unwrap().unwrap();
+        assert_eq!(s.mono_ms, 2.0);
+        assert!(parse_sample_line("[bridge] starting").is_none());
+    }
+}
@@ -7,14 +7,80 @@
 use std::sync::Mutex;
 use tauri::State;
 
-use crate::db::Db;
-use crate::error::{ManifoldError, Result};
-use crate::fingerprint::{Fingerprint, FingerprintOrchestrator};
-use crate::human::{BehaviorProfile, HumanBehavior};
+use crate::aging::AgingReport;
+use crate::bandwidth::{BandwidthRepo, BandwidthReport, UsageMeter, DEFAULT_REPORT_DAYS};
+use crate::bridge_locator::{BridgeSearch, LocatedBridge};
+use crate::cache_prewarm::{CachePrewarmSettings, HarvestReport, SharedCache};
+use crate::chain::{ChainForwarder, ChainHealth};
+use crate::clipboard::{BridgeClipboard, ClipboardCommand, ClipboardResult};
+use crate::clock_guard::DriftTracker;
+use crate::cost::{CostPeriod, CostRepo, CostReport, ProxyCost};
+use crate::db::{Db, Page};
+use crate::entropy_report::EntropyReport;
+use crate::error::{Context, ManifoldError, Result};
+use crate::events::{ChainLink, Event, EventKind, EventLogVerification, EventRepo, NewEvent};
+use crate::fingerprint::{
+    Fingerprint, FingerprintOrchestrator, ReseedOptions, WindowPlacement, GENERATOR_VERSION,
+};
+use crate::fingerprint_diff::FingerprintDiff;
+use crate::fingerprint_vectors::VectorReport;
+use crate::generation_policy::BulkCreateRequest;
+use crate::har_import::ImportedSession;
+use crate::hash_preview::FingerprintHashes;
+use crate::header_order::HeaderOrderReport;
+use crate::host_audit::HostAudit;
+use crate::human::{BehaviorProfile, HumanBehavior, MouseConfig, ScrollConfig};
+use crate::integrity::{IntegrityReport, OnDelete};
+use crate::launch_config::{LaunchConfig, ProxyConfig};
+use crate::launch_conflicts::{ConflictRepo, ConflictRule, LaunchConflict};
+use crate::launch_env::LaunchEnvSettings;
+use crate::launch_mode::{HostDisplay, LaunchMode, LaunchPlan, LaunchPolicy, VirtualDisplay};
+use crate::leak_test::{LeakTestRepo, LeakTestReport};
+use crate::master_key::RecoveryStatus;
+use crate::metrics::MetricsReport;
+use crate::motion::{MousePath, MouseTrace, Point, ScrollPattern};
+use crate::node_pool::{LaunchRequest, NodePool, Placement, PoolStatus, QueuedLaunch};
+use crate::nodes::{ExecutionNode, NodeHealth, NodeKind, NodeRepo, NodeSpec};
+use crate::notifications::{Alert, SinkResult};
+use crate::permissions::{PermissionPreset, PresetStates};
+use crate::persona::{Persona, WarmupPlan};
+use crate::power::{PowerEvent, PowerSettings};
+use crate::preflight::{PreflightPlan, PreflightReport};
+use crate::priors::{Priors, PriorsSource, PriorsStatus};
 use crate::profile::{
-    CreateProfileRequest, Profile, ProfileRepo, ProfileStatus, UpdateProfileRequest,
+    BatchProfilePatch, BatchUpdateResult, CreateProfileRequest, GcReport, Profile, ProfileFilter,
+    ProfileRepo, ProfileSelection, ProfileStatus, StatusTransition, TransitionReason,
+    UpdateProfileRequest,
+};
+use crate::proxy::{
+    AddProxyRequest, AnonymityLevel, CheckMode, DomainHit, Ipv6LeakReport, Proxy,
+    ProxyDomainStatus, ProxyFilter, ProxyHealth, ProxyRepo, ProxyType, TargetLatency,
+    UpdateProxyRequest,
+};
+use crate::proxy_matching::{AutoAssignResult, ProxyCandidate};
+use crate::proxy_transfer::ProxyImport;
+use crate::quota::{QuotaSettings, QuotaUsage};
+use crate::reference_corpus::{
+    CaptureProfileRequest, ReferenceComparison, ReferenceFingerprint, ReferenceRepo,
+};
+use crate::report::{ReportFormat, WorkspaceReport};
+use crate::rest::{RestRecommendation, RestRepo};
+use crate::run_artifacts::{RunArchive, RunLog, WorkflowRun, WorkflowRunRepo};
+use crate::run_outputs::{RunOutputRepo, RunOutputs};
+use crate::session::{AuditedSession, FlaggedSession, SessionRepo, SessionSummary};
+use crate::settings::{Settings, SettingsRepo};
+use crate::share::{ImportedShare, ShareOptions, ShareSummary};
+use crate::ssh_tunnel::SshTunnelManager;
+use crate::stats::DashboardStats;
+use crate::synthetic_identity::{IdentityValidation, SyntheticIdentity, SyntheticIdentityRepo};
+use crate::timeline::SessionTimeline;
+use crate::tls_bridge::ExpectedJa4;
+use crate::vault::VaultRepo;
+use crate::vpn::{VpnRepo, VpnSummary, VpnTunnel};
+use crate::workflow::{
+    Intervention, Interventions, Workflow, WorkflowRepo, WorkflowRunReport, WorkflowStep,
 };
-use crate::proxy::{AddProxyRequest, Proxy, ProxyHealth, ProxyRepo, UpdateProxyRequest};
+use crate::workspace::{Workspace, WorkspaceRegistry};
 
 // For URL parsing in domain extraction
 use url;
@@ -31,31 +97,84 @@ pub struct AppState {
     pub db: Db,
     pub profiles: Mutex<ProfileRepo>,
     pub proxies: Mutex<ProxyRepo>,
+    pub events: Mutex<EventRepo>,
+    pub sessions: Mutex<SessionRepo>,
     /// PID of the running playwright-bridge process (if any).
     pub bridge_pid: Mutex<Option<u32>>,
     /// Port the bridge WebSocket server is listening on.
     pub bridge_port: Mutex<u16>,
+    /// WebSocket secret of the latest bridge launch; connection tickets are
+    /// signed with it (`bridge_auth`).
+    pub bridge_secret: Mutex<Option<String>>,
+    /// Execution node the bridge runs on; `None` for this machine.
+    pub bridge_node: Mutex<Option<Placement>>,
+    /// Launches spread over the execution nodes besides the bridge.
+    pub node_pool: NodePool,
     /// Port for TLS bridge server (JA4 control).
     pub tls_bridge_port: Mutex<u16>,
     /// PID of the running scraper process (if any).
     pub scraper_pid: Mutex<Option<u32>>,
-    /// Master key for AES-GCM field encryption (set once at startup).
-    pub master_key: Option<String>,
+    /// Local forwarder for the launched profile's proxy chain (if any).
+    pub chain_forwarder: Mutex<Option<ChainForwarder>>,
+    /// `ssh -D` tunnels backing SSH proxies.
+    pub ssh_tunnels: SshTunnelManager,
+    pub vpns: Mutex<VpnRepo>,
+    pub leak_tests: Mutex<LeakTestRepo>,
+    pub conflicts: Mutex<ConflictRepo>,
+    pub settings: Mutex<SettingsRepo>,
+    /// Workflow runs paused for the operator.
+    pub interventions: Interventions,
+    /// Clipboard channel to the launched bridge.
+    pub clipboard: BridgeClipboard,
+    /// The latest host contamination audit.
+    pub host_audit: Mutex<Option<HostAudit>>,
+    /// WireGuard tunnel of the launched profile (if it has one).
+    pub vpn_tunnel: Mutex<Option<VpnTunnel>>,
+    /// Xvfb display of the launched bridge (virtual display launches).
+    pub virtual_display: Mutex<Option<VirtualDisplay>>,
+    /// The open workspace.
+    pub workspace: Mutex<Workspace>,
+    /// Master key for AES-GCM field encryption of the open workspace.
+    pub master_key: Mutex<Option<String>>,
 }
 
 impl AppState {
-    pub fn new(db: Db, master_key: Option<String>) -> Self {
+    pub fn new(db: Db, workspace: Workspace, master_key: Option<String>) -> Self {
         let profiles = ProfileRepo::new(db.clone());
         let proxies = ProxyRepo::new(db.clone());
+        let events = EventRepo::new(db.clone());
+        let sessions = SessionRepo::new(db.clone());
+        let vpns = VpnRepo::new(db.clone());
+        let leak_tests = LeakTestRepo::new(db.clone());
+        let conflicts = ConflictRepo::new(db.clone());
+        let settings = SettingsRepo::new(db.clone());
+        let app = settings.app().unwrap_or_default();
         Self {
             db,
             profiles: Mutex::new(profiles),
             proxies: Mutex::new(proxies),
+            events: Mutex::new(events),
+            sessions: Mutex::new(sessions),
             bridge_pid: Mutex::new(None),
-            bridge_port: Mutex::new(8766),
-            tls_bridge_port: Mutex::new(8767),
+            bridge_port: Mutex::new(app.bridge_port),
+            bridge_secret: Mutex::new(None),
+            bridge_node: Mutex::new(None),
+            node_pool: NodePool::default(),
+            tls_bridge_port: Mutex::new(app.tls_bridge_port),
             scraper_pid: Mutex::new(None),
-            master_key,
+            chain_forwarder: Mutex::new(None),
+            ssh_tunnels: SshTunnelManager::new(),
+            vpns: Mutex::new(vpns),
+            leak_tests: Mutex::new(leak_tests),
+            conflicts: Mutex::new(conflicts),
+            settings: Mutex::new(settings),
+            interventions: Interventions::default(),
+            clipboard: BridgeClipboard::default(),
+            host_audit: Mutex::new(None),
+            vpn_tunnel: Mutex::new(None),
+            virtual_display: Mutex::new(None),
+            workspace: Mutex::new(workspace),
+            master_key: Mutex::new(master_key),
         }
     }
 }
@@ -82,10 +201,20 @@ fn kill_process_pid(pid: u32, include_children: bool) {
 
 // ── Profile commands ──────────────────────────────────────────────────────────
 
-/// List all profiles (summary fields only — fingerprint blob included).
+/// List profiles newest first (summary fields only — fingerprint blob
+/// included).  `limit` and `after` (the last id of the previous page) page
+/// through them; without them every profile is returned.
 #[tauri::command]
-pub fn list_profiles(state: State<'_, AppState>) -> Result<Vec<Profile>> {
-    state.profiles.lock().unwrap().list()
+pub fn list_profiles(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    after: Option<String>,
+) -> Result<Vec<Profile>> {
+    state
+        .profiles
+        .lock()
+        .unwrap()
+        .list_page(&Page::new(limit, after))
 }
 
 /// Fetch a single profile by UUID.
@@ -96,6 +225,7 @@ pub fn get_profile(state: State<'_, AppState>, id: String) -> Result<Profile> {
 
 /// Create a new profile.  `seed` is optional — auto-generated if omitted.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn create_profile(
     state: State<'_, AppState>,
     name: String,
@@ -104,6 +234,7 @@ pub fn create_profile(
     notes: Option<String>,
     tags: Option<Vec<String>>,
     behavior_profile: Option<String>,
+    persona: Option<Persona>,
 ) -> Result<Profile> {
     state.profiles.lock().unwrap().create(CreateProfileRequest {
         name,
@@ -112,11 +243,30 @@ pub fn create_profile(
         notes,
         tags,
         behavior_profile,
+        persona,
     })
 }
 
+/// Create a batch of profiles whose OS and country shares follow
+/// `request.policy` (see generation_policy.rs).
+#[tauri::command]
+pub fn bulk_create_profiles(
+    state: State<'_, AppState>,
+    request: BulkCreateRequest,
+) -> Result<Vec<Profile>> {
+    let pool = state.proxies.lock().unwrap().list()?;
+    let profiles = state.profiles.lock().unwrap();
+    let mut assigned = std::collections::BTreeMap::new();
+    for pid in profiles.list()?.into_iter().filter_map(|p| p.proxy_id) {
+        *assigned.entry(pid).or_insert(0) += 1;
+    }
+    crate::generation_policy::bulk_create(&profiles, &pool, assigned, request)
+}
+
 /// Update an existing profile's metadata / fingerprint / human config.
+/// A changed persona re-derives locale and timezone; `clear_persona` drops it.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn update_profile(
     state: State<'_, AppState>,
     id: String,
@@ -127,8 +277,21 @@ pub fn update_profile(
     notes: Option<String>,
     tags: Option<Vec<String>>,
     behavior_profile: Option<String>,
+    persona: Option<Persona>,
+    clear_persona: Option<bool>,
 ) -> Result<Profile> {
-    state.profiles.lock().unwrap().update(
+    let repo = state.profiles.lock().unwrap();
+    // A hand-edited screen drags its viewport and pixel ratio along
+    let fingerprint = match fingerprint {
+        Some(mut fp) => {
+            if FingerprintOrchestrator::screen_edited(&repo.get(&id)?.fingerprint, &fp) {
+                FingerprintOrchestrator::recompute_derived_fields(&mut fp);
+            }
+            Some(fp)
+        }
+        None => None,
+    };
+    repo.update(
         &id,
         UpdateProfileRequest {
             name,
@@ -139,6 +302,10 @@ pub fn update_profile(
             tags,
             behavior_profile,
             tls_bridge: None,
+            persona: match clear_persona {
+                Some(true) => Some(None),
+                _ => persona.map(Some),
+            },
         },
     )
 }
@@ -149,37 +316,227 @@ pub fn delete_profile(state: State<'_, AppState>, id: String) -> Result<()> {
     state.profiles.lock().unwrap().delete(&id)
 }
 
+/// Broken references between profiles, proxies and per-profile tables.
+#[tauri::command]
+pub fn check_integrity(state: State<'_, AppState>) -> Result<IntegrityReport> {
+    crate::integrity::check(&state.db)
+}
+
+/// Clear or delete every broken reference `check_integrity` reports.
+#[tauri::command]
+pub fn repair_integrity(state: State<'_, AppState>) -> Result<IntegrityReport> {
+    crate::integrity::repair(&state.db)
+}
+
+/// Report (and unless `dry_run`, delete) data directories that no profile
+/// owns.  Running profiles are never touched: their ids are in the DB.
+#[tauri::command]
+pub fn gc_orphaned_data(state: State<'_, AppState>, dry_run: bool) -> Result<GcReport> {
+    state.profiles.lock().unwrap().gc_orphaned_data(dry_run)
+}
+
+/// Set a profile's data dir size limit in MiB (`None` = app-wide default).
+#[tauri::command]
+pub fn set_profile_disk_quota(
+    state: State<'_, AppState>,
+    id: String,
+    quota_mb: Option<u64>,
+) -> Result<Profile> {
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_disk_quota(&id, quota_mb)?;
+    profiles.get(&id)
+}
+
+/// Current data dir usage of every profile against its limit.
+#[tauri::command]
+pub fn get_disk_usage(state: State<'_, AppState>) -> Result<Vec<QuotaUsage>> {
+    let settings = state.profiles.lock().unwrap().quota_settings()?;
+    crate::quota::measure(&state.db, &crate::db::profiles_dir(), &settings, false)
+}
+
+/// Empty a stopped profile's Chromium cache folders.  Returns bytes freed.
+#[tauri::command]
+pub fn clear_profile_cache(state: State<'_, AppState>, id: String) -> Result<u64> {
+    let profile = state.profiles.lock().unwrap().get(&id)?;
+    if profile.status == ProfileStatus::Running {
+        return Err(ManifoldError::InvalidArg(
+            "stop the profile before clearing its cache".into(),
+        ));
+    }
+    Ok(crate::quota::clear_cache_dirs(std::path::Path::new(
+        &profile.data_dir,
+    )))
+}
+
+#[tauri::command]
+pub fn get_quota_settings(state: State<'_, AppState>) -> Result<QuotaSettings> {
+    state.profiles.lock().unwrap().quota_settings()
+}
+
+#[tauri::command]
+pub fn set_quota_settings(
+    state: State<'_, AppState>,
+    settings: QuotaSettings,
+) -> Result<QuotaSettings> {
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_quota_settings(&settings)?;
+    profiles.quota_settings()
+}
+
+#[tauri::command]
+pub fn get_cache_prewarm_settings(state: State<'_, AppState>) -> Result<CachePrewarmSettings> {
+    state.profiles.lock().unwrap().cache_prewarm_settings()
+}
+
+#[tauri::command]
+pub fn set_cache_prewarm_settings(
+    state: State<'_, AppState>,
+    settings: CachePrewarmSettings,
+) -> Result<CachePrewarmSettings> {
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_cache_prewarm_settings(&settings)?;
+    profiles.cache_prewarm_settings()
+}
+
+/// Add the web font and CDN entries of a stopped profile's cache to the
+/// shared layer new profiles are prewarmed from.
+#[tauri::command]
+pub fn harvest_shared_cache(state: State<'_, AppState>, id: String) -> Result<HarvestReport> {
+    state.profiles.lock().unwrap().harvest_shared_cache(&id)
+}
+
+#[tauri::command]
+pub fn get_shared_cache(state: State<'_, AppState>) -> Result<SharedCache> {
+    state.profiles.lock().unwrap().shared_cache()
+}
+
+/// Delete the shared cache layer.  Returns bytes freed.
+#[tauri::command]
+pub fn clear_shared_cache(state: State<'_, AppState>) -> Result<u64> {
+    state.profiles.lock().unwrap().clear_shared_cache()
+}
+
 /// Re-generate the fingerprint for an existing profile from a new seed.
-/// If `seed` is None, a fresh random seed is used.
+/// If `seed` is None, a fresh random seed is used.  `options` selects which
+/// parts of the device identity (OS, GPU, locale/timezone, screen) to keep.
 #[tauri::command]
 pub fn reseed_profile(
     state: State<'_, AppState>,
     id: String,
     seed: Option<u64>,
+    options: Option<ReseedOptions>,
 ) -> Result<Profile> {
-    state.profiles.lock().unwrap().reseed_fingerprint(&id, seed)
+    state
+        .profiles
+        .lock()
+        .unwrap()
+        .reseed_fingerprint(&id, seed, &options.unwrap_or_default())
 }
 
 /// Duplicate a profile (new UUID, same fingerprint + settings).
+/// With `deep`, the browser user-data directory (cookies, storage) is copied
+/// too, minus device-bound files, so a logged-in state can be forked.
 #[tauri::command]
 pub fn duplicate_profile(
     state: State<'_, AppState>,
     id: String,
     new_name: Option<String>,
+    deep: Option<bool>,
 ) -> Result<Profile> {
     let repo = state.profiles.lock().unwrap();
     let src = repo.get(&id)?;
 
     let name = new_name.unwrap_or_else(|| format!("{} (copy)", src.name));
 
-    repo.create(CreateProfileRequest {
+    let mut copy = repo.create(CreateProfileRequest {
         name,
         seed: Some(src.fingerprint.seed),
         proxy_id: src.proxy_id.clone(),
         notes: Some(src.notes.clone()),
         tags: Some(src.tags.clone()),
         behavior_profile: Some(src.human.profile.to_string()),
-    })
+        persona: src.persona.clone(),
+    })?;
+    if !src.proxy_chain.is_empty() {
+        repo.set_proxy_chain(&copy.id, &src.proxy_chain)?;
+        copy.proxy_chain = src.proxy_chain.clone();
+    }
+    if !src.launch_env.is_empty() {
+        repo.set_launch_env(&copy.id, &src.launch_env)?;
+        copy.launch_env = src.launch_env.clone();
+    }
+
+    if deep.unwrap_or(false) {
+        if let Err(e) = repo.copy_browser_data(&src.id, &copy.id) {
+            repo.delete(&copy.id).ok();
+            return Err(e);
+        }
+    }
+
+    Ok(copy)
+}
+
+/// Write a bundle handing the profile to another Manifold user.  Proxy
+/// credentials and cookies are stripped unless `options` keeps them.
+#[tauri::command]
+pub fn share_profile(
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+    options: Option<ShareOptions>,
+) -> Result<ShareSummary> {
+    let profiles = state.profiles.lock().unwrap();
+    let proxies = state.proxies.lock().unwrap();
+    crate::share::export(
+        &profiles,
+        &proxies,
+        &id,
+        &options.unwrap_or_default(),
+        Path::new(&path),
+    )
+}
+
+/// Validate a share bundle and create its profile, reusing local proxies
+/// that match the shared ones.
+#[tauri::command]
+pub fn import_shared_profile(state: State<'_, AppState>, path: String) -> Result<ImportedShare> {
+    let profiles = state.profiles.lock().unwrap();
+    let proxies = state.proxies.lock().unwrap();
+    crate::share::import(&profiles, &proxies, Path::new(&path))
+}
+
+/// Apply one patch (proxy pool, tags, behavior tier, TLS bridge) to many
+/// profiles at once.  All-or-nothing; see `BatchUpdateResult`.
+#[tauri::command]
+pub fn batch_update_profiles(
+    state: State<'_, AppState>,
+    selection: ProfileSelection,
+    patch: BatchProfilePatch,
+) -> Result<BatchUpdateResult> {
+    state
+        .profiles
+        .lock()
+        .unwrap()
+        .batch_update(selection, patch)
+}
+
+/// Warm-up plan (sites, locale, active hours) derived from the profile's
+/// persona.  Profiles without a persona get a generic plan.
+#[tauri::command]
+pub fn get_warmup_plan(
+    state: State<'_, AppState>,
+    id: String,
+    site_count: Option<usize>,
+) -> Result<WarmupPlan> {
+    let profile = state.profiles.lock().unwrap().get(&id)?;
+    let persona = profile.persona.unwrap_or_default();
+    Ok(persona.warmup_plan(&profile.fingerprint, site_count.unwrap_or(8)))
+}
+
+/// Opt a profile in or out of scheduled Chrome version aging.
+#[tauri::command]
+pub fn set_profile_auto_age(state: State<'_, AppState>, id: String, enabled: bool) -> Result<()> {
+    state.profiles.lock().unwrap().set_auto_age(&id, enabled)
 }
 
 // ── Fingerprint commands ──────────────────────────────────────────────────────
@@ -195,10 +552,108 @@ pub fn generate_fingerprint(seed: Option<u64>) -> Fingerprint {
     FingerprintOrchestrator::generate(s)
 }
 
-/// Re-derive a fingerprint from the same seed (deterministic).
+/// Re-derive a fingerprint from the same seed (deterministic).  Pass the
+/// profile fingerprint's `generator` to get what that generator produced,
+/// stable across app upgrades; the current one is used otherwise.
+#[tauri::command]
+pub fn reseed_fingerprint(seed: u64, generator: Option<u32>) -> Result<Fingerprint> {
+    FingerprintOrchestrator::generate_with(seed, generator.unwrap_or(GENERATOR_VERSION))
+}
+
+/// Expected canvas / WebGL / audio hashes for the fingerprint generated from
+/// `seed`, computed with the same noise algorithm as the injection scripts.
+#[tauri::command]
+pub fn preview_fingerprint_hashes(seed: u64) -> FingerprintHashes {
+    crate::hash_preview::preview_hashes(&FingerprintOrchestrator::generate(seed))
+}
+
+/// Bits of identifying information each field of the fingerprint `seed`
+/// generates carries against population priors, most unusual first.
+#[tauri::command]
+pub fn get_entropy_report(seed: u64) -> EntropyReport {
+    crate::entropy_report::seed_report(seed, Utc::now().date_naive())
+}
+
+/// Which population priors are in use: the built-in dataset or the last
+/// one fetched.
+#[tauri::command]
+pub fn get_priors_status(state: State<'_, AppState>) -> Result<PriorsStatus> {
+    crate::priors::status(&state.db)
+}
+
+/// The population priors in use.
+#[tauri::command]
+pub fn get_priors() -> Priors {
+    (*crate::priors::current()).clone()
+}
+
+#[tauri::command]
+pub fn get_priors_source(state: State<'_, AppState>) -> Result<PriorsSource> {
+    crate::priors::source(&state.db)
+}
+
+#[tauri::command]
+pub fn set_priors_source(state: State<'_, AppState>, source: PriorsSource) -> Result<PriorsStatus> {
+    crate::priors::set_source(&state.db, &source)
+}
+
+/// Fetch the dataset from the configured source and put it in use if its
+/// signature checks out and it is newer than the one in use.
+#[tauri::command]
+pub async fn update_priors(state: State<'_, AppState>) -> Result<PriorsStatus> {
+    let db = state.db.clone();
+    let Some(url) = crate::priors::source(&db)?.url else {
+        return Err(ManifoldError::InvalidArg(
+            "no priors source configured".into(),
+        ));
+    };
+    let document = crate::priors::fetch(&url).await?;
+    crate::priors::accept(&db, document, Utc::now())
+}
+
+/// Field-by-field comparison of a current fingerprint `a` and a proposed one
+/// `b` (a reseed or auto-correct candidate), with the canvas / WebGL / audio
+/// probe Hamming distances between their noise.
+#[tauri::command]
+pub fn diff_fingerprints(a: Fingerprint, b: Fingerprint) -> Result<FingerprintDiff> {
+    Ok(crate::fingerprint_diff::diff(&a, &b)?)
+}
+
+/// Write the fingerprints `seeds` generate with this build to a golden file
+/// at `path`.  Without `seeds`, the built-in seed list plus every profile's
+/// seed is used.  Returns the seeds written.
+#[tauri::command]
+pub fn export_fingerprint_vectors(
+    state: State<'_, AppState>,
+    path: String,
+    seeds: Option<Vec<u64>>,
+) -> Result<Vec<u64>> {
+    let seeds = match seeds {
+        Some(seeds) => seeds,
+        None => {
+            let profiles = state.profiles.lock().unwrap().list()?;
+            crate::fingerprint_vectors::DEFAULT_SEEDS
+                .iter()
+                .copied()
+                .chain(profiles.iter().map(|p| p.fingerprint.seed))
+                .collect()
+        }
+    };
+    let file = crate::fingerprint_vectors::export(Path::new(&path), &seeds)?;
+    Ok(file.vectors.iter().map(|v| v.seed).collect())
+}
+
+/// Regenerate the fingerprints in a golden file and list the seeds whose
+/// identity this build would change.
+#[tauri::command]
+pub fn verify_fingerprint_vectors(path: String) -> Result<VectorReport> {
+    crate::fingerprint_vectors::verify(Path::new(&path))
+}
+
+/// Run the fingerprint aging task immediately (it also runs on a schedule).
 #[tauri::command]
-pub fn reseed_fingerprint(seed: u64) -> Fingerprint {
-    FingerprintOrchestrator::generate(seed)
+pub fn run_fingerprint_aging(state: State<'_, AppState>) -> Result<AgingReport> {
+    crate::aging::run_aging(&state.db, Utc::now().date_naive())
 }
 
 // ── Human behavior commands ───────────────────────────────────────────────────
@@ -211,11 +666,48 @@ pub fn get_human_defaults(profile: String) -> Result<HumanBehavior> {
     Ok(HumanBehavior::from_profile(bp))
 }
 
+/// Timestamped cursor path for one move, deterministic in `seed`.  The
+/// frontend sends the points to the bridge as a `move_path` message.
+#[tauri::command]
+pub fn generate_mouse_path(from: Point, to: Point, mouse: MouseConfig, seed: u64) -> MousePath {
+    crate::motion::generate_mouse_path(from, to, &mouse, seed)
+}
+
+/// The same move as a `MouseTraceSegment` for the trace calibrator.
+#[tauri::command]
+pub fn export_mouse_trace(from: Point, to: Point, mouse: MouseConfig, seed: u64) -> MouseTrace {
+    crate::motion::generate_mouse_path(from, to, &mouse, seed).trace()
+}
+
+/// Wheel ticks for the `nth` scroll of a profile seeded with `seed`, sent to
+/// the bridge as a `scroll_ticks` message.
+#[tauri::command]
+pub fn generate_scroll_pattern(
+    delta_y: f64,
+    viewport_height: f64,
+    scroll: ScrollConfig,
+    seed: u64,
+    nth: u64,
+) -> ScrollPattern {
+    crate::motion::scroll_pattern(delta_y, viewport_height, &scroll, seed, nth)
+}
+
 // ── Proxy commands ────────────────────────────────────────────────────────────
 
+/// List proxies by name; paged like `list_profiles`.  `filter` keeps those
+/// with a tag and/or whose name or notes contain a search string.
 #[tauri::command]
-pub fn list_proxies(state: State<'_, AppState>) -> Result<Vec<Proxy>> {
-    state.proxies.lock().unwrap().list()
+pub fn list_proxies(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    after: Option<String>,
+    filter: Option<ProxyFilter>,
+) -> Result<Vec<Proxy>> {
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .list_page_filtered(&Page::new(limit, after), &filter.unwrap_or_default())
 }
 
 #[tauri::command]
@@ -223,57 +715,40 @@ pub fn get_proxy(state: State<'_, AppState>, id: String) -> Result<Proxy> {
     state.proxies.lock().unwrap().get(&id)
 }
 
+/// Add a proxy from the fields the proxy form collects.
 #[tauri::command]
-pub fn add_proxy(
-    state: State<'_, AppState>,
-    name: String,
-    proxy_type: String,
-    host: String,
-    port: u16,
-    username: Option<String>,
-    password: Option<String>,
-    country: Option<String>,
-) -> Result<Proxy> {
-    state.proxies.lock().unwrap().add(AddProxyRequest {
-        name,
-        proxy_type,
-        host,
-        port,
-        username,
-        password,
-        country,
-    })
+pub fn add_proxy(state: State<'_, AppState>, proxy: AddProxyRequest) -> Result<Proxy> {
+    state.proxies.lock().unwrap().add(proxy)
 }
 
+/// Update a proxy; fields left out of `patch` are kept.
 #[tauri::command]
 pub fn update_proxy(
     state: State<'_, AppState>,
     id: String,
-    name: Option<String>,
-    proxy_type: Option<String>,
-    host: Option<String>,
-    port: Option<u16>,
-    username: Option<String>,
-    password: Option<String>,
-    country: Option<String>,
+    patch: UpdateProxyRequest,
 ) -> Result<Proxy> {
-    state.proxies.lock().unwrap().update(
-        &id,
-        UpdateProxyRequest {
-            name,
-            proxy_type,
-            host,
-            port,
-            username,
-            password,
-            country,
-        },
-    )
+    let proxy = state.proxies.lock().unwrap().update(&id, patch)?;
+    // A running tunnel still dials the old server; the next launch restarts it
+    state.ssh_tunnels.stop(&id);
+    Ok(proxy)
 }
 
+/// Delete a proxy.  Profiles still using it are detached from it unless
+/// `on_delete` is `block`, which refuses instead.
 #[tauri::command]
-pub fn delete_proxy(state: State<'_, AppState>, id: String) -> Result<()> {
-    state.proxies.lock().unwrap().delete(&id)
+pub fn delete_proxy(
+    state: State<'_, AppState>,
+    id: String,
+    on_delete: Option<OnDelete>,
+) -> Result<()> {
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .delete_with(&id, on_delete.unwrap_or_default())?;
+    state.ssh_tunnels.stop(&id);
+    Ok(())
 }
 
 /// Run a health check on a single proxy (blocking — runs in ~10 s worst case).
@@ -282,108 +757,1413 @@ pub fn check_proxy(state: State<'_, AppState>, id: String) -> Result<ProxyHealth
     state.proxies.lock().unwrap().check_health(&id)
 }
 
-/// Health-check all proxies sequentially.
+/// Health-check all proxies, `proxy_check_concurrency` at a time.
 #[tauri::command]
 pub fn check_all_proxies(state: State<'_, AppState>) -> Result<Vec<ProxyHealth>> {
-    state.proxies.lock().unwrap().check_all()
+    let concurrency = state
+        .settings
+        .lock()
+        .unwrap()
+        .app()?
+        .proxy_check_concurrency;
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .check_all(concurrency as usize)
 }
 
-// ── Bridge / launcher commands ────────────────────────────────────────────────
-
-/// Launch the playwright-bridge Node.js process for a given profile.
-///
-/// The bridge process:
-///   1. Reads the profile + fingerprint config from the payload
-///   2. Launches a Chromium instance with all evasion scripts injected
-///   3. Exposes a local WebSocket server for the frontend live-trace view
-///
-/// Returns the WebSocket port the bridge is listening on.
+/// Check whether this machine can reach IPv6 targets directly while the
+/// proxy exit cannot — the condition under which an IPv6 address leaks.
 #[tauri::command]
-pub fn launch_profile(
-    _app: tauri::AppHandle,
-    state: State<'_, AppState>,
-    id: String,
-    url: Option<String>,
-) -> Result<u16> {
-    use crate::error::ManifoldError;
-    use std::process::{Command, Stdio};
-
-    // Stop any existing bridge before launching a new one for this profile.
-    if let Some(old_pid) = state.bridge_pid.lock().unwrap().take() {
-        kill_process_pid(old_pid, true);
-    }
-
-    // Retrieve the profile and (optionally) its proxy
-    let profile = state.profiles.lock().unwrap().get(&id)?;
-    let proxy = match &profile.proxy_id {
-        Some(pid) => state.proxies.lock().unwrap().get(pid).ok(),
-        None => None,
-    };
-
-    // Serialise launch config to JSON for the bridge
-    let mut launch_config = serde_json::json!({
-        "profile": profile,
-        "proxy":   proxy.as_ref().map(|p| serde_json::json!({
-            "server":   p.to_playwright_server(),
-            "username": p.username,
-            "password": p.password,
-        })),
-        "url": url.unwrap_or_else(|| "about:blank".into()),
-        "wsPort": *state.bridge_port.lock().unwrap(),
-    });
-
-    // If TLS bridge is enabled, include the bridge port
-    if profile.tls_bridge.unwrap_or(false) {
-        let tls_bridge_port = *state.tls_bridge_port.lock().unwrap();
-        launch_config["tlsBridgePort"] = serde_json::json!(tls_bridge_port);
-    }
-
-    let config_json = serde_json::to_string(&launch_config).map_err(|e| ManifoldError::Json(e))?;
+pub fn check_ipv6_leak(state: State<'_, AppState>, id: String) -> Result<Ipv6LeakReport> {
+    state.proxies.lock().unwrap().ipv6_leak_check(&id)
+}
 
-    // Locate the bridge entry-point relative to the workspace
-    let workspace = std::env::current_dir().unwrap_or_else(|_| ".".into());
-    let bridge_path = workspace.join("playwright-bridge").join("index.ts");
+/// Pin a proxy to one IP so rotating DNS can't move it.  Without `ip`, pins
+/// the address that currently answers first.  Returns the pinned IP.
+#[tauri::command]
+pub fn pin_proxy_ip(state: State<'_, AppState>, id: String, ip: Option<String>) -> Result<String> {
+    state.proxies.lock().unwrap().pin_ip(&id, ip.as_deref())
+}
 
-    if !bridge_path.exists() {
-        return Err(ManifoldError::BridgeOffline);
-    }
+/// Set how health checks talk to an HTTP(S) proxy: "get", "connect" (with a
+/// TLS handshake through the tunnel) or "both".
+#[tauri::command]
+pub fn set_proxy_check_mode(state: State<'_, AppState>, id: String, mode: String) -> Result<Proxy> {
+    let mode: CheckMode = mode.parse()?;
+    state.proxies.lock().unwrap().set_check_mode(&id, mode)
+}
 
-    let mut cmd = Command::new("npx");
-    cmd.args([
-        "tsx",
-        bridge_path.to_str().unwrap_or("playwright-bridge/index.ts"),
-    ])
-    .env("MANIFOLD_LAUNCH_CONFIG", &config_json)
-    .current_dir(&workspace)
-    .stdout(Stdio::inherit())
-    .stderr(Stdio::inherit());
+/// Health-check a proxy and measure its download speed (`size_kb`, default
+/// 1 MB).  SSH proxies are measured through their local tunnel.
+#[tauri::command]
+pub fn measure_proxy_speed(
+    state: State<'_, AppState>,
+    id: String,
+    size_kb: Option<u32>,
+) -> Result<ProxyHealth> {
+    let proxy = state.proxies.lock().unwrap().get(&id)?;
+    let dial = state.ssh_tunnels.resolve(&proxy)?;
+    // The download can take a while; run it outside the proxies lock
+    ProxyRepo::new(state.db.clone()).measure_speed(&id, &dial, size_kb)
+}
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| ManifoldError::Other(format!("failed to spawn bridge: {e}")))?;
+/// App-wide health-check targets (URLs).
+#[tauri::command]
+pub fn get_proxy_check_targets(state: State<'_, AppState>) -> Result<Vec<String>> {
+    state.proxies.lock().unwrap().default_check_targets()
+}
 
-    let pid = child.id();
-    *state.bridge_pid.lock().unwrap() = Some(pid);
+/// Replace the app-wide health-check targets (URLs or hosts); an empty list
+/// restores the built-in endpoints.
+#[tauri::command]
+pub fn set_proxy_check_targets(
+    state: State<'_, AppState>,
+    targets: Vec<String>,
+) -> Result<Vec<String>> {
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .set_default_check_targets(&targets)
+}
 
-    // Mark profile as running
+/// Override the health-check targets for one proxy; an empty list falls
+/// back to the app-wide targets.
+#[tauri::command]
+pub fn set_proxy_own_check_targets(
+    state: State<'_, AppState>,
+    id: String,
+    targets: Vec<String>,
+) -> Result<Proxy> {
     state
-        .profiles
+        .proxies
         .lock()
         .unwrap()
-        .set_status(&id, ProfileStatus::Running)
-        .ok();
-    state.profiles.lock().unwrap().touch_last_used(&id).ok();
+        .set_check_targets(&id, &targets)
+}
 
-    let port = *state.bridge_port.lock().unwrap();
-    Ok(port)
+/// Latency through the proxy to each of its check targets, fastest first.
+#[tauri::command]
+pub fn compare_proxy_check_targets(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<TargetLatency>> {
+    state.proxies.lock().unwrap().compare_check_targets(&id)
 }
 
-/// Start the playwright bridge in background using default config (dev profile).
-/// Useful for packaged app startup so bridge WS is available immediately.
 #[tauri::command]
-pub fn start_bridge(state: State<'_, AppState>) -> Result<u16> {
-    use crate::error::ManifoldError;
-    use std::process::{Command, Stdio};
+pub fn unpin_proxy_ip(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.proxies.lock().unwrap().unpin_ip(&id)
+}
+
+/// Set (or with an empty list, remove) a profile's proxy chain.  Hops are
+/// proxy ids, first hop first.
+#[tauri::command]
+pub fn set_profile_proxy_chain(
+    state: State<'_, AppState>,
+    id: String,
+    proxy_ids: Vec<String>,
+) -> Result<Profile> {
+    if !proxy_ids.is_empty() {
+        let hops = resolve_chain(&state, &proxy_ids)?;
+        crate::chain::validate_chain(&hops)?;
+    }
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_proxy_chain(&id, &proxy_ids)?;
+    profiles.get(&id)
+}
+
+/// Replace the environment variables a profile's bridge gets on top of the
+/// scrubbed host environment.
+#[tauri::command]
+pub fn set_profile_launch_env(
+    state: State<'_, AppState>,
+    id: String,
+    env: std::collections::BTreeMap<String, String>,
+) -> Result<Profile> {
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_launch_env(&id, &env)?;
+    profiles.get(&id)
+}
+
+/// Hold a profile on a Chrome version for a legacy site (`"109"` or
+/// `"109.0.5414.120"`), or release it with `None`.  UA, UA-CH and the TLS
+/// profile move to the version together; aging skips the profile.
+#[tauri::command]
+pub fn set_profile_pinned_version(
+    state: State<'_, AppState>,
+    id: String,
+    version: Option<String>,
+) -> Result<Profile> {
+    state
+        .profiles
+        .lock()
+        .unwrap()
+        .set_pinned_browser_version(&id, version.as_deref())
+}
+
+/// Replace a profile's launch mode and its fallbacks.
+#[tauri::command]
+pub fn set_profile_launch_policy(
+    state: State<'_, AppState>,
+    id: String,
+    policy: LaunchPolicy,
+) -> Result<Profile> {
+    policy.validate()?;
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_launch_policy(&id, &policy)?;
+    profiles.get(&id)
+}
+
+/// The mode a launch of the profile would settle on on this host, without
+/// launching.  A virtual display counts as available when Xvfb is installed.
+#[tauri::command]
+pub fn check_launch_mode(state: State<'_, AppState>, profile_id: String) -> Result<LaunchPlan> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let (plan, _) = crate::launch_mode::plan(
+        &profile.launch_policy,
+        &HostDisplay::detect(),
+        &profile.fingerprint,
+        || Ok(()),
+    )?;
+    Ok(plan)
+}
+
+/// A profile's sessions, newest first, with the mode each was launched in.
+#[tauri::command]
+pub fn list_profile_sessions(
+    state: State<'_, AppState>,
+    profile_id: String,
+    limit: Option<u32>,
+    after: Option<String>,
+) -> Result<Vec<SessionSummary>> {
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .list(&profile_id, &Page::new(limit, after))
+}
+
+/// Health-check a chain end to end (blocking — one hop timeout per hop worst
+/// case).
+#[tauri::command]
+pub fn check_proxy_chain(
+    state: State<'_, AppState>,
+    proxy_ids: Vec<String>,
+) -> Result<ChainHealth> {
+    let hops = resolve_chain(&state, &proxy_ids)?;
+    crate::chain::validate_chain(&hops)?;
+    // The exit hop's targets, since that is where the check leaves the chain
+    let exit = hops.last().expect("validated chains have hops");
+    let targets = state
+        .proxies
+        .lock()
+        .unwrap()
+        .effective_check_targets(exit)?;
+    Ok(crate::chain::check_chain(&hops, &targets))
+}
+
+fn resolve_chain(state: &AppState, proxy_ids: &[String]) -> Result<Vec<Proxy>> {
+    let proxies = state.proxies.lock().unwrap();
+    proxy_ids.iter().map(|pid| proxies.get(pid)).collect()
+}
+
+/// Attach a WireGuard config to a profile (stored encrypted).  At launch the
+/// browser is routed through it instead of the profile's proxy.
+#[tauri::command]
+pub fn set_profile_vpn(
+    state: State<'_, AppState>,
+    profile_id: String,
+    config: String,
+    kind: Option<String>,
+) -> Result<VpnSummary> {
+    let kind = match kind {
+        Some(k) => k.parse()?,
+        None => Default::default(),
+    };
+    state.vpns.lock().unwrap().set(&profile_id, kind, &config)
+}
+
+#[tauri::command]
+pub fn get_profile_vpn(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<Option<VpnSummary>> {
+    state.vpns.lock().unwrap().summary(&profile_id)
+}
+
+#[tauri::command]
+pub fn remove_profile_vpn(state: State<'_, AppState>, profile_id: String) -> Result<()> {
+    state.vpns.lock().unwrap().remove(&profile_id)
+}
+
+/// Record a block or captcha seen through a proxy on a target domain; the
+/// proxy is skipped for that domain until its cooldown expires.
+#[tauri::command]
+pub fn record_proxy_domain_hit(
+    state: State<'_, AppState>,
+    proxy_id: String,
+    domain: String,
+    hit: DomainHit,
+) -> Result<ProxyDomainStatus> {
+    let proxies = state.proxies.lock().unwrap();
+    let status = proxies.record_domain_hit(&proxy_id, &domain, hit)?;
+    let name = proxies.get(&proxy_id)?.name;
+    if let Some(alert) = Alert::for_domain_hit(&name, &status, hit) {
+        crate::notifications::notify(&state.db, alert);
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn list_proxy_domain_status(
+    state: State<'_, AppState>,
+    proxy_id: Option<String>,
+) -> Result<Vec<ProxyDomainStatus>> {
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .list_domain_status(proxy_id.as_deref())
+}
+
+#[tauri::command]
+pub fn clear_proxy_domain_status(
+    state: State<'_, AppState>,
+    proxy_id: String,
+    domain: String,
+) -> Result<()> {
+    state
+        .proxies
+        .lock()
+        .unwrap()
+        .clear_domain_status(&proxy_id, &domain)
+}
+
+/// Every proxy with its last health check and per-domain block history, as
+/// CSV or JSON text.  Credentials are left out unless asked for.
+#[tauri::command]
+pub fn export_proxies(
+    state: State<'_, AppState>,
+    format: ReportFormat,
+    include_credentials: Option<bool>,
+) -> Result<String> {
+    let proxies = state.proxies.lock().unwrap();
+    crate::proxy_transfer::export(&proxies, format, include_credentials.unwrap_or(false))
+}
+
+/// Usable proxies scored for a profile (country fit, latency, load and
+/// cooldowns), best first.
+#[tauri::command]
+pub fn suggest_proxy(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<Vec<ProxyCandidate>> {
+    let profiles = state.profiles.lock().unwrap();
+    let proxies = state.proxies.lock().unwrap();
+    crate::proxy_matching::suggest(&profiles, &proxies, &profile_id)
+}
+
+/// Give each profile matching `filter` (all when omitted) that has no proxy
+/// or chain its best-scoring proxy, spreading the batch over the pool.
+#[tauri::command]
+pub fn auto_assign_proxies(
+    state: State<'_, AppState>,
+    filter: Option<ProfileFilter>,
+) -> Result<AutoAssignResult> {
+    let profiles = state.profiles.lock().unwrap();
+    let proxies = state.proxies.lock().unwrap();
+    crate::proxy_matching::auto_assign(&profiles, &proxies, &filter.unwrap_or_default())
+}
+
+/// Add the proxies of an `export_proxies` list that aren't here yet, with
+/// their health and domain history.
+#[tauri::command]
+pub fn import_proxies(
+    state: State<'_, AppState>,
+    format: ReportFormat,
+    data: String,
+) -> Result<ProxyImport> {
+    let proxies = state.proxies.lock().unwrap();
+    crate::proxy_transfer::import(&proxies, format, &data)
+}
+
+// ── Bridge / launcher commands ────────────────────────────────────────────────
+
+/// Launch the playwright-bridge Node.js process for a given profile.
+///
+/// The bridge process:
+///   1. Reads the profile + fingerprint config from the payload
+///   2. Launches a Chromium instance with all evasion scripts injected
+///   3. Exposes a local WebSocket server for the frontend live-trace view
+///
+/// With `node_id` the bridge runs on that execution node instead (`nodes`).
+/// Returns the WebSocket port the bridge is listening on.
+#[tauri::command]
+pub async fn launch_profile(
+    app: tauri::AppHandle,
+    id: String,
+    url: Option<String>,
+    target_domain: Option<String>,
+    node_id: Option<String>,
+) -> Result<u16> {
+    // Off the async runtime: an agent node may take a while to bring the
+    // bridge up
+    tauri::async_runtime::spawn_blocking(move || {
+        launch_profile_blocking(&app, id, url, target_domain, node_id)
+    })
+    .await
+    .map_err(|e| ManifoldError::Other(format!("launch failed: {e}")))?
+}
+
+fn launch_profile_blocking(
+    app: &tauri::AppHandle,
+    id: String,
+    url: Option<String>,
+    target_domain: Option<String>,
+    node_id: Option<String>,
+) -> Result<u16> {
+    use std::io::Write;
+    use std::process::Stdio;
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+
+    // Looked up first: an unknown node mustn't stop the running bridge
+    let node = node_id
+        .map(|nid| NodeRepo::new(state.db.clone()).get_with_token(&nid))
+        .transpose()?;
+    let request = LaunchRequest {
+        profile_id: id.clone(),
+        url: url.clone(),
+        target_domain: target_domain.clone(),
+    };
+    let mut launch = prepare_launch(&state, &id, url, target_domain)?;
+    launch.config.control = true;
+    if let Some(node) = &node {
+        // Remote browsers run headless; tunnels started for a local route
+        // are of no use to them
+        state.virtual_display.lock().unwrap().take();
+        if let Err(e) = crate::nodes::place(node, 0, &mut launch.config) {
+            state.chain_forwarder.lock().unwrap().take();
+            state.vpn_tunnel.lock().unwrap().take();
+            return Err(e);
+        }
+    }
+    let config_json = launch.config.to_env_json()?;
+    if let Some(node) = node.clone().filter(|n| n.spec.kind == NodeKind::Agent) {
+        return launch_on_agent(app, &state, request, &launch, &config_json, node);
+    }
+
+    let mut cmd = match &node {
+        Some(node) => {
+            let tls_bridge = launch
+                .config
+                .tls_bridge_port
+                .map(|_| *state.tls_bridge_port.lock().unwrap());
+            crate::nodes::ssh_bridge_command(
+                node,
+                0,
+                *state.bridge_port.lock().unwrap(),
+                tls_bridge,
+            )
+        }
+        None => {
+            let mut cmd = bridge_command(&state, &launch.profile)?;
+            cmd.env("MANIFOLD_LAUNCH_CONFIG", &config_json);
+            cmd
+        }
+    };
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit());
+
+    // Claim the profile before spawning.  `prepare_launch` has already
+    // replaced a running bridge, so this only fails when a racing launch of
+    // the same profile claimed it first
+    state.profiles.lock().unwrap().transition(
+        &id,
+        ProfileStatus::Running,
+        TransitionReason::Launch,
+    )?;
+
+    // Clock anchor for the drift guard
+    let launched = (Utc::now(), std::time::Instant::now());
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(&id, ProfileStatus::Error, TransitionReason::LaunchFailed)
+                .ok();
+            return Err(ManifoldError::spawn("bridge", e));
+        }
+    };
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    if node.is_some() {
+        // The node's shell reads the config as its first line of input
+        if let Err(e) = writeln!(stdin, "{config_json}") {
+            kill_process_pid(child.id(), true);
+            child.wait().ok();
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(&id, ProfileStatus::Error, TransitionReason::LaunchFailed)
+                .ok();
+            return Err(ManifoldError::Io(e))
+                .context("failed to send the launch config to the node");
+        }
+    }
+
+    let pid = child.id();
+    *state.bridge_pid.lock().unwrap() = Some(pid);
+    *state.bridge_secret.lock().unwrap() = launch.config.ws_secret.clone();
+    *state.bridge_node.lock().unwrap() = node.clone().map(|node| {
+        // The node's WebSocket is forwarded to the local bridge port
+        let mut placement = Placement::new(request, node, 0);
+        placement.ws_port = Some(*state.bridge_port.lock().unwrap());
+        placement
+    });
+    state.profiles.lock().unwrap().touch_last_used(&id).ok();
+    let (clipboard_tx, clipboard_rx) = std::sync::mpsc::channel();
+    state
+        .clipboard
+        .attach(&id, pid, Box::new(stdin), clipboard_rx);
+    let session_id = record_launch(&state, &id, &launch, node.as_ref());
+
+    let tracker = DriftTracker::new(launched.0, launched.1);
+    let tls_bridge = launch.profile.tls_bridge.unwrap_or(false);
+    watch_bridge(
+        app.clone(),
+        child,
+        tracker,
+        id.clone(),
+        session_id,
+        tls_bridge,
+        clipboard_tx,
+    );
+
+    let port = *state.bridge_port.lock().unwrap();
+    Ok(port)
+}
+
+/// `launch_profile` on an agent node.  The agent runs the bridge, so there
+/// is no process here to watch: the profile stays Running until it is
+/// stopped or replaced.  A bridge on the node's loopback is reached through
+/// an SSH forward to the local bridge port.
+fn launch_on_agent(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    request: LaunchRequest,
+    launch: &PreparedLaunch,
+    config_json: &str,
+    node: ExecutionNode,
+) -> Result<u16> {
+    let id = request.profile_id.clone();
+    state.profiles.lock().unwrap().transition(
+        &id,
+        ProfileStatus::Running,
+        TransitionReason::Launch,
+    )?;
+    let local_port = *state.bridge_port.lock().unwrap();
+    let mut placement = Placement::new(request, node.clone(), 0);
+    let forwarded = placement.route_ws(local_port);
+    let launched = tauri::async_runtime::block_on(crate::nodes::agent_launch(&node, config_json))
+        .and_then(|()| {
+            if !forwarded {
+                return Ok(None);
+            }
+            crate::nodes::start_ws_forward(&node, 0, local_port)
+                .map(Some)
+                .inspect_err(|_| stop_placement(&placement))
+        });
+    let forward = match launched {
+        Ok(forward) => forward,
+        Err(e) => {
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(&id, ProfileStatus::Error, TransitionReason::LaunchFailed)
+                .ok();
+            return Err(e).with_context(|| format!("node {} failed to launch", node.spec.name));
+        }
+    };
+    if let Some(forward) = forward {
+        placement.pid = Some(forward.id());
+        watch_ws_forward(app, forward);
+    }
+
+    *state.bridge_secret.lock().unwrap() = launch.config.ws_secret.clone();
+    let port = placement.ws_port.unwrap_or(launch.config.ws_port);
+    *state.bridge_node.lock().unwrap() = Some(placement);
+    state.profiles.lock().unwrap().touch_last_used(&id).ok();
+    record_launch(state, &id, launch, Some(&node));
+    Ok(port)
+}
+
+/// Reap the launch_profile bridge's WebSocket forward to its agent node once
+/// it ends, so a later stop can't kill whatever reuses its pid.
+fn watch_ws_forward(app: &tauri::AppHandle, mut forward: std::process::Child) {
+    use tauri::Manager;
+
+    let app = app.clone();
+    let pid = forward.id();
+    std::thread::spawn(move || {
+        forward.wait().ok();
+        let state = app.state::<AppState>();
+        let mut bridge_node = state.bridge_node.lock().unwrap();
+        if let Some(placement) = bridge_node.as_mut().filter(|p| p.pid == Some(pid)) {
+            eprintln!(
+                "[manifold] WebSocket forward to node {} ended",
+                placement.node_name
+            );
+            placement.pid = None;
+        }
+    });
+}
+
+/// Open the launch's session (closing any left open by a crashed bridge)
+/// and log the launch event.  Returns the new session's id.
+fn record_launch(
+    state: &AppState,
+    id: &str,
+    launch: &PreparedLaunch,
+    node: Option<&ExecutionNode>,
+) -> Option<String> {
+    let sessions = state.sessions.lock().unwrap();
+    sessions.end_open(id).ok();
+    let session_id = sessions.start(id).ok();
+    if let Some(sid) = &session_id {
+        sessions
+            .set_launch_mode(sid, launch.config.launch_mode)
+            .ok();
+    }
+    drop(sessions);
+    for warning in &launch.plan.warnings {
+        eprintln!("[manifold] launch {id}: {warning}");
+    }
+    state
+        .events
+        .lock()
+        .unwrap()
+        .record(NewEvent {
+            detail: Some(serde_json::json!({
+                "launch_mode": launch.config.launch_mode,
+                "skipped": launch.plan.skipped,
+                "warnings": launch.plan.warnings,
+                "node": node.map(|n| &n.id),
+            })),
+            ..NewEvent::simple(Some(id), EventKind::Launch)
+        })
+        .ok();
+    session_id
+}
+
+/// Stop the chain forwarder, VPN tunnel and virtual display a launch
+/// started for its route.
+fn release_route(state: &AppState) {
+    state.chain_forwarder.lock().unwrap().take();
+    state.vpn_tunnel.lock().unwrap().take();
+    state.virtual_display.lock().unwrap().take();
+}
+
+/// Let go of the execution node the bridge runs on.
+fn release_bridge_node(state: &AppState) {
+    if let Some(placement) = state.bridge_node.lock().unwrap().take() {
+        stop_placement(&placement);
+    }
+}
+
+/// Stop a bridge running on a node.  An SSH node's bridge ends with its
+/// `ssh` process; an agent is asked to stop it, in the background so a slow
+/// node can't hold up a stop or panic.
+fn stop_placement(placement: &Placement) {
+    if let Some(pid) = placement.pid {
+        kill_process_pid(pid, true);
+    }
+    if placement.node.spec.kind == NodeKind::Agent {
+        let node = placement.node.clone();
+        let profile_id = placement.request.profile_id.clone();
+        std::thread::spawn(move || {
+            let stopped = crate::nodes::agent_stop(&node, &profile_id);
+            if let Err(e) = tauri::async_runtime::block_on(stopped) {
+                eprintln!("[manifold] node {}: stop failed: {e}", node.spec.name);
+            }
+        });
+    }
+}
+
+/// Pass the bridge's stdout through and feed its clock samples to a drift
+/// tracker; each jump flags the session and is logged as an event.  Its
+/// action traces are collected and audited once the bridge's output ends.
+/// Its bandwidth samples are recorded unless the profile goes through the
+/// TLS bridge, which counts the same traffic on the wire.  Clipboard results
+/// go to `clipboard`.
+/// When the bridge exits on its own (it is still the registered bridge), the profile
+/// is stopped — Idle on a clean exit, Error otherwise — instead of being
+/// left Running.
+fn watch_bridge(
+    app: tauri::AppHandle,
+    mut child: std::process::Child,
+    mut tracker: DriftTracker,
+    profile_id: String,
+    session_id: Option<String>,
+    tls_bridge: bool,
+    clipboard: std::sync::mpsc::Sender<ClipboardResult>,
+) {
+    use crate::bandwidth::parse_usage_line;
+    use crate::behavior_audit::{parse_trace_line, BehaviorRecorder};
+    use crate::clock_guard::parse_sample_line;
+    use std::io::{BufRead, BufReader};
+    use tauri::Manager;
+
+    let pid = child.id();
+    let stdout = child.stdout.take();
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let events = EventRepo::new(state.db.clone());
+        let sessions = SessionRepo::new(state.db.clone());
+        let bandwidth = BandwidthRepo::new(state.db.clone());
+        let mut recorder = BehaviorRecorder::default();
+        let lines = stdout
+            .into_iter()
+            .flat_map(|out| BufReader::new(out).lines().map_while(|l| l.ok()));
+        for line in lines {
+            if let Some(result) = crate::clipboard::parse_result_line(&line) {
+                clipboard.send(result).ok();
+                continue;
+            }
+            if let Some(batch) = parse_trace_line(&line) {
+                if let Ok(batch) = batch {
+                    recorder.extend(batch);
+                }
+                continue;
+            }
+            if let Some(usage) = parse_usage_line(&line) {
+                if let (Ok(usage), false) = (usage, tls_bridge) {
+                    bandwidth.record(&profile_id, usage).ok();
+                }
+                continue;
+            }
+            let Some(sample) = parse_sample_line(&line) else {
+                println!("{line}");
+                continue;
+            };
+            let Ok(sample) = sample else { continue };
+            for jump in tracker.observe(sample, std::time::Instant::now()) {
+                if let Some(sid) = &session_id {
+                    sessions.flag_clock_jump(sid).ok();
+                }
+                events
+                    .record(NewEvent {
+                        profile_id: Some(profile_id.clone()),
+                        kind: EventKind::ClockJump,
+                        severity: Some("warning".into()),
+                        domain: None,
+                        detail: serde_json::to_value(&jump).ok(),
+                    })
+                    .ok();
+            }
+        }
+
+        if let (Some(sid), false) = (&session_id, recorder.is_empty()) {
+            sessions.save_behavior_audit(sid, &recorder.audit()).ok();
+        }
+
+        let exit = child.wait();
+        state.clipboard.detach(pid);
+        let mut registered = state.bridge_pid.lock().unwrap();
+        if *registered != Some(pid) {
+            // Stopped, replaced or paused by someone who handles the status
+            return;
+        }
+        *registered = None;
+        drop(registered);
+        state.bridge_node.lock().unwrap().take();
+        let (to, reason) = match exit {
+            Ok(status) if status.success() => (ProfileStatus::Idle, TransitionReason::Exited),
+            _ => (ProfileStatus::Error, TransitionReason::Crashed),
+        };
+        if state
+            .profiles
+            .lock()
+            .unwrap()
+            .transition(&profile_id, to, reason)
+            .is_ok()
+        {
+            sessions.end_open(&profile_id).ok();
+            events
+                .record(NewEvent {
+                    profile_id: Some(profile_id.clone()),
+                    kind: EventKind::Stop,
+                    severity: (to == ProfileStatus::Error).then(|| "warning".into()),
+                    domain: None,
+                    detail: Some(serde_json::json!({ "reason": reason })),
+                })
+                .ok();
+        }
+    });
+}
+
+/// Bytes up and down over the last `days` UTC days (30 by default), per
+/// proxy, per profile and per day.  With `profile_id`, that profile only.
+#[tauri::command]
+pub fn get_bandwidth_report(
+    state: State<'_, AppState>,
+    days: Option<u32>,
+    profile_id: Option<String>,
+) -> Result<BandwidthReport> {
+    BandwidthRepo::new(state.db.clone())
+        .report(days.unwrap_or(DEFAULT_REPORT_DAYS), profile_id.as_deref())
+}
+
+#[tauri::command]
+pub fn get_proxy_cost(state: State<'_, AppState>, proxy_id: String) -> Result<ProxyCost> {
+    CostRepo::new(state.db.clone()).proxy_cost(&proxy_id)
+}
+
+/// Set the proxy's price per GB and per IP (30 days); `None` clears one.
+#[tauri::command]
+pub fn set_proxy_cost(
+    state: State<'_, AppState>,
+    proxy_id: String,
+    cost: ProxyCost,
+) -> Result<ProxyCost> {
+    let costs = CostRepo::new(state.db.clone());
+    costs.set_proxy_cost(&proxy_id, &cost)?;
+    costs.proxy_cost(&proxy_id)
+}
+
+/// Count a captcha solved for the profile by `provider`, one of the
+/// providers in the cost settings.
+#[tauri::command]
+pub fn record_captcha_solve(
+    state: State<'_, AppState>,
+    profile_id: String,
+    provider: String,
+) -> Result<Event> {
+    if CostRepo::new(state.db.clone())
+        .settings()?
+        .captcha_price(&provider)
+        .is_none()
+    {
+        return Err(ManifoldError::InvalidArg(format!(
+            "unknown captcha provider: {provider:?}"
+        )));
+    }
+    state.profiles.lock().unwrap().get(&profile_id)?;
+    state.events.lock().unwrap().record(NewEvent {
+        profile_id: Some(profile_id),
+        kind: EventKind::CaptchaSolve,
+        severity: None,
+        domain: None,
+        detail: Some(serde_json::json!({ "provider": provider })),
+    })
+}
+
+/// Proxy and captcha costs over `period`, per profile and per tag.
+#[tauri::command]
+pub fn get_cost_report(state: State<'_, AppState>, period: CostPeriod) -> Result<CostReport> {
+    CostRepo::new(state.db.clone()).report(period)
+}
+
+/// Sessions of the profile during which the host clock jumped.
+#[tauri::command]
+pub fn list_clock_flagged_sessions(
+    state: State<'_, AppState>,
+    profile_id: String,
+    limit: Option<u32>,
+    after: Option<String>,
+) -> Result<Vec<FlaggedSession>> {
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .clock_flagged(&profile_id, &Page::new(limit, after))
+}
+
+/// The profile's sessions with a behavior audit, newest first.
+#[tauri::command]
+pub fn list_behavior_audits(
+    state: State<'_, AppState>,
+    profile_id: String,
+    limit: Option<u32>,
+    after: Option<String>,
+) -> Result<Vec<AuditedSession>> {
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .behavior_audits(&profile_id, &Page::new(limit, after))
+}
+
+// ── Launch conflicts ──────────────────────────────────────────────────────────
+
+/// Forbid two profiles from being online at the same time.
+#[tauri::command]
+pub fn add_launch_conflict(
+    state: State<'_, AppState>,
+    profile_id: String,
+    other_id: String,
+    reason: Option<String>,
+) -> Result<()> {
+    state.conflicts.lock().unwrap().add(
+        &profile_id,
+        &other_id,
+        reason.as_deref().unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+pub fn remove_launch_conflict(
+    state: State<'_, AppState>,
+    profile_id: String,
+    other_id: String,
+) -> Result<bool> {
+    state
+        .conflicts
+        .lock()
+        .unwrap()
+        .remove(&profile_id, &other_id)
+}
+
+/// Every conflict rule involving the profile.
+#[tauri::command]
+pub fn list_launch_conflicts(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<Vec<ConflictRule>> {
+    state.conflicts.lock().unwrap().rules_for(&profile_id)
+}
+
+/// The online profiles that currently keep `id` from launching; empty when
+/// it may launch.
+#[tauri::command]
+pub fn check_launch_conflicts(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<LaunchConflict>> {
+    state.conflicts.lock().unwrap().check(&id)
+}
+
+/// A profile's resolved launch: the bridge config plus what went into it.
+struct PreparedLaunch {
+    profile: Profile,
+    /// The single proxy the browser uses, before SSH resolution (`None` for
+    /// VPN, chain and direct launches).
+    proxy: Option<Proxy>,
+    config: LaunchConfig,
+    /// How the browser is shown.
+    plan: LaunchPlan,
+}
+
+/// Resolve the profile's proxy / chain / VPN and build the bridge config.
+/// Stops any running bridge, since the forwarders it used are replaced.
+fn prepare_launch(
+    state: &AppState,
+    id: &str,
+    url: Option<String>,
+    target_domain: Option<String>,
+) -> Result<PreparedLaunch> {
+    let mut profile = state.profiles.lock().unwrap().get(id)?;
+    if state.node_pool.contains(id) {
+        return Err(ManifoldError::InvalidArg(format!(
+            "profile {id} is in the node pool; stop it there first"
+        )));
+    }
+    // Checked before the running bridge is replaced: a conflicting profile
+    // has to be stopped on purpose, not switched away from
+    state.conflicts.lock().unwrap().ensure_launchable(id)?;
+    let (proxy, mut chain_hops) = resolve_route(state, &profile, &url, target_domain)?;
+
+    // Stop any existing bridge (and its chain forwarder) before launching a
+    // new one for this profile.
+    if let Some(old_pid) = state.bridge_pid.lock().unwrap().take() {
+        kill_process_pid(old_pid, true);
+    }
+    release_bridge_node(state);
+    state.chain_forwarder.lock().unwrap().take();
+    state.vpn_tunnel.lock().unwrap().take();
+    state.virtual_display.lock().unwrap().take();
+    // Pool launches run alongside the bridge and aren't replaced
+    let replaced: Vec<String> = {
+        let profiles = state.profiles.lock().unwrap();
+        profiles
+            .list()?
+            .into_iter()
+            .filter(|p| p.status == ProfileStatus::Running && !state.node_pool.contains(&p.id))
+            .filter(|p| {
+                profiles
+                    .transition(&p.id, ProfileStatus::Idle, TransitionReason::Replaced)
+                    .is_ok()
+            })
+            .map(|p| p.id)
+            .collect()
+    };
+    for old in &replaced {
+        state.sessions.lock().unwrap().end_open(old).ok();
+    }
+
+    // SSH proxies are reached through their local `ssh -D` tunnel
+    let resolved = proxy
+        .as_ref()
+        .map(|p| state.ssh_tunnels.resolve(p))
+        .transpose()?;
+    if let Some(first) = chain_hops.first_mut() {
+        *first = state.ssh_tunnels.resolve(first)?;
+    }
+
+    // A profile VPN takes precedence over its chain and proxy
+    let vpn = state.vpns.lock().unwrap().config(id)?;
+    let single_proxy = if vpn.is_none() && chain_hops.is_empty() {
+        proxy
+    } else {
+        None
+    };
+
+    let proxy_config = if let Some((kind, config)) = vpn {
+        let tunnel = VpnTunnel::start(kind, &config)?;
+        let config = ProxyConfig::local(tunnel.server_url());
+        *state.vpn_tunnel.lock().unwrap() = Some(tunnel);
+        Some(config)
+    } else if chain_hops.is_empty() {
+        resolved.as_ref().map(|p| ProxyConfig {
+            server: p.to_playwright_server(),
+            username: p.username.clone(),
+            password: p.password.clone(),
+        })
+    } else {
+        let forwarder = ChainForwarder::start(chain_hops)?;
+        let config = ProxyConfig::local(forwarder.server_url());
+        *state.chain_forwarder.lock().unwrap() = Some(forwarder);
+        Some(config)
+    };
+
+    let window = session_window(state, &mut profile)?;
+
+    // A virtual display is started here and lives until the bridge is
+    // replaced or stopped
+    let host = HostDisplay::detect();
+    let (plan, display) = crate::launch_mode::plan(
+        &profile.launch_policy,
+        &host,
+        &profile.fingerprint,
+        || match &host.xvfb {
+            Some(xvfb) => VirtualDisplay::start(
+                xvfb,
+                profile.fingerprint.screen_width,
+                profile.fingerprint.screen_height,
+            ),
+            None => Err(ManifoldError::Other("Xvfb is not installed".into())),
+        },
+    )?;
+
+    let mut config = build_launch_config(state, &profile, proxy_config, url);
+    config.launch_mode = plan.mode;
+    config.display = display.as_ref().map(|d| d.display().to_string());
+    config.window = window;
+    *state.virtual_display.lock().unwrap() = display;
+    Ok(PreparedLaunch {
+        profile,
+        proxy: single_proxy,
+        config,
+        plan,
+    })
+}
+
+/// Size the profile's viewport for the session about to start, and place
+/// its window when it has a screen layout.  The session's ordinal is the
+/// sub-seed of both.
+fn session_window(state: &AppState, profile: &mut Profile) -> Result<Option<WindowPlacement>> {
+    if !profile.fingerprint.window_variation && profile.fingerprint.screen_layout.is_none() {
+        return Ok(None);
+    }
+    let session = state.sessions.lock().unwrap().count(&profile.id)?;
+    let (w, h) = FingerprintOrchestrator::session_viewport(&profile.fingerprint, session);
+    profile.fingerprint.viewport_width = w;
+    profile.fingerprint.viewport_height = h;
+    Ok(profile
+        .fingerprint
+        .screen_layout
+        .is_some()
+        .then(|| FingerprintOrchestrator::session_window(&profile.fingerprint, session)))
+}
+
+/// The profile's single proxy (or chain hops) for this launch.  With a
+/// target domain (explicit or taken from the start URL), a proxy cooling
+/// down for that domain is swapped for another one from its pool.
+/// Transparent proxies are refused.  No side effects.
+fn resolve_route(
+    state: &AppState,
+    profile: &Profile,
+    url: &Option<String>,
+    target_domain: Option<String>,
+) -> Result<(Option<Proxy>, Vec<Proxy>)> {
+    let target_domain = target_domain.or_else(|| url.clone());
+    let proxy = match (&profile.proxy_id, &target_domain) {
+        _ if !profile.proxy_chain.is_empty() => None,
+        (Some(pid), Some(domain)) => {
+            let selected = state
+                .proxies
+                .lock()
+                .unwrap()
+                .select_for_domain(pid, domain)
+                .ok();
+            match selected {
+                Some(None) => {
+                    return Err(ManifoldError::Other(format!(
+                        "every proxy in the pool is cooling down for {domain}"
+                    )))
+                }
+                other => other.flatten(),
+            }
+        }
+        (Some(pid), None) => state.proxies.lock().unwrap().get(pid).ok(),
+        (None, _) => None,
+    };
+
+    // A chain is served to the browser as a local SOCKS5 endpoint
+    let chain_hops = profile
+        .proxy_chain
+        .iter()
+        .map(|pid| state.proxies.lock().unwrap().get(pid))
+        .collect::<Result<Vec<Proxy>>>()?;
+
+    // A transparent proxy hands every site the real IP
+    if let Some(p) = proxy
+        .iter()
+        .chain(&chain_hops)
+        .find(|p| p.anonymity == Some(AnonymityLevel::Transparent))
+    {
+        return Err(ManifoldError::InvalidArg(format!(
+            "proxy {} is transparent (forwards the real IP) and can't be used",
+            p.name
+        )));
+    }
+    Ok((proxy, chain_hops))
+}
+
+/// The JSON handed to the bridge in `MANIFOLD_LAUNCH_CONFIG`.
+fn build_launch_config(
+    state: &AppState,
+    profile: &Profile,
+    proxy: Option<ProxyConfig>,
+    url: Option<String>,
+) -> LaunchConfig {
+    // If TLS bridge is enabled, include the bridge port and the flags that
+    // keep the browser from upgrading past it to HTTP/3
+    let tls_bridge = profile.tls_bridge.unwrap_or(false);
+    LaunchConfig {
+        protocol: crate::bridge_locator::BRIDGE_PROTOCOL,
+        profile: profile.clone(),
+        proxy,
+        url: url.unwrap_or_else(|| "about:blank".into()),
+        ws_port: *state.bridge_port.lock().unwrap(),
+        ws_host: None,
+        ws_tls: false,
+        // Rotated with every launch; see `bridge_auth`
+        ws_secret: Some(crate::bridge_auth::new_secret()),
+        tls_bridge_port: tls_bridge.then(|| *state.tls_bridge_port.lock().unwrap()),
+        extra_args: if tls_bridge {
+            crate::tls_bridge::BRIDGE_CHROMIUM_ARGS
+                .iter()
+                .map(|a| a.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        },
+        header_order: Some(crate::header_order::chrome_header_order(
+            &profile.fingerprint,
+        )),
+        request_headers: Some(crate::request_headers::request_header_profile(
+            &profile.fingerprint,
+        )),
+        engine_quirks: Some(crate::engine_quirks::engine_quirks(&profile.fingerprint)),
+        leak_probe: false,
+        preflight: None,
+        workflow: false,
+        control: false,
+        launch_mode: Default::default(),
+        display: None,
+        window: None,
+    }
+}
+
+/// Stands in for values a preview can't or mustn't show.
+const REDACTED: &str = "[redacted]";
+
+/// The config `launch_profile` would hand the bridge, built without
+/// stopping the running bridge or starting tunnels: endpoints that only
+/// exist once a launch starts them are shown as placeholders, and the proxy
+/// password and profile environment values are redacted.
+#[tauri::command]
+pub fn preview_launch_config(
+    state: State<'_, AppState>,
+    profile_id: String,
+    url: Option<String>,
+    target_domain: Option<String>,
+) -> Result<serde_json::Value> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let (proxy, chain_hops) = resolve_route(&state, &profile, &url, target_domain)?;
+    let vpn = state.vpns.lock().unwrap().config(&profile_id)?;
+
+    let proxy_config = if let Some((kind, _)) = vpn {
+        Some(ProxyConfig::local(format!("[local {kind} tunnel]")))
+    } else if !chain_hops.is_empty() {
+        Some(ProxyConfig::local(format!(
+            "[local forwarder, {}-hop chain]",
+            chain_hops.len()
+        )))
+    } else {
+        proxy.map(|p| {
+            if p.proxy_type == ProxyType::Ssh {
+                ProxyConfig::local(format!("[local ssh tunnel to {}:{}]", p.host, p.port))
+            } else {
+                ProxyConfig {
+                    server: p.to_playwright_server(),
+                    username: p.username,
+                    password: p.password,
+                }
+            }
+        })
+    };
+
+    let mut config =
+        serde_json::to_value(build_launch_config(&state, &profile, proxy_config, url))?;
+    redact_launch_config(&mut config);
+    Ok(config)
+}
+
+/// Blank out secrets in a launch config: the WebSocket secret, the proxy
+/// password and the values of the profile's environment variables (names
+/// are kept).
+fn redact_launch_config(config: &mut serde_json::Value) {
+    if let Some(secret) = config.get_mut("wsSecret") {
+        *secret = REDACTED.into();
+    }
+    if let Some(password) = config.pointer_mut("/proxy/password") {
+        if !password.is_null() {
+            *password = REDACTED.into();
+        }
+    }
+    if let Some(env) = config
+        .pointer_mut("/profile/launch_env")
+        .and_then(|v| v.as_object_mut())
+    {
+        for value in env.values_mut() {
+            *value = REDACTED.into();
+        }
+    }
+}
+
+/// The located bridge for `profile`: scrubbed environment plus the
+/// profile's own variables, the configured Node install, and a working
+/// directory inside the profile directory.
+fn bridge_command(state: &AppState, profile: &Profile) -> Result<std::process::Command> {
+    use crate::launch_env;
+
+    let search = BridgeSearch::from_env();
+    let bridge = crate::bridge_locator::locate(&search)?;
+
+    let settings = state.profiles.lock().unwrap().launch_env_settings()?;
+    let node_dir = settings.resolve_node_dir();
+    let work_dir = Path::new(&profile.data_dir).join(launch_env::WORK_DIR_NAME);
+    std::fs::create_dir_all(&work_dir)?;
+
+    let mut cmd = bridge.command(node_dir.as_deref(), &search.workspace)?;
+    cmd.env_clear()
+        .envs(launch_env::build_env(
+            std::env::vars(),
+            &settings,
+            node_dir.as_deref(),
+            &profile.launch_env,
+        ))
+        .current_dir(&work_dir);
+    Ok(cmd)
+}
+
+/// Which bridge launches will use (sidecar, packaged JS or source checkout)
+/// and its manifest.  Fails with the same version/checksum error a launch
+/// would.
+#[tauri::command]
+pub fn get_bridge_info() -> Result<LocatedBridge> {
+    crate::bridge_locator::locate(&BridgeSearch::from_env())
+}
+
+/// App-wide launch environment: extra allowlisted host variables and the
+/// Node install bridges are started with.
+#[tauri::command]
+pub fn get_launch_env_settings(state: State<'_, AppState>) -> Result<LaunchEnvSettings> {
+    state.profiles.lock().unwrap().launch_env_settings()
+}
+
+#[tauri::command]
+pub fn set_launch_env_settings(
+    state: State<'_, AppState>,
+    settings: LaunchEnvSettings,
+) -> Result<LaunchEnvSettings> {
+    settings.validate()?;
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_launch_env_settings(&settings)?;
+    profiles.launch_env_settings()
+}
+
+/// Launch the profile with the bridge in probe mode and check what the page
+/// sees for WebRTC, DNS, IPv6, timezone and UA/UA-CH/TLS leaks.  The report
+/// is stored as the profile's latest leak test.
+///
+/// Stops the running bridge, like a launch does.
+#[tauri::command]
+pub async fn run_leak_test(app: tauri::AppHandle, profile_id: String) -> Result<LeakTestReport> {
+    // Off the async runtime: the probe blocks until the bridge reports or
+    // times out
+    tauri::async_runtime::spawn_blocking(move || run_leak_test_blocking(&app, &profile_id))
+        .await
+        .map_err(|e| ManifoldError::Other(format!("leak test failed: {e}")))?
+}
+
+fn run_leak_test_blocking(app: &tauri::AppHandle, profile_id: &str) -> Result<LeakTestReport> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let mut launch = prepare_launch(&state, profile_id, None, None)?;
+    launch.config.leak_probe = true;
+    let probe = run_bridge_probe(
+        &state,
+        &launch,
+        crate::leak_test::PROBE_TIMEOUT_SECS,
+        crate::leak_test::parse_probe_line,
+    )?
+    .ok_or_else(|| ManifoldError::Bridge("exited or timed out without a leak probe".into()))??;
+
+    let report = judge_leak_probe(&state, &launch, &probe)?;
+    state.leak_tests.lock().unwrap().save(&report)?;
+    Ok(report)
+}
+
+/// Validate a profile without showing a browser: the bridge starts headless
+/// on the profile's route, runs the leak probe, opens `plan.url` and checks
+/// that each of `plan.selectors` matches there.  The leak test part is
+/// stored as the profile's latest leak test.  No session is recorded.
+///
+/// Stops the running bridge, like a launch does.
+#[tauri::command]
+pub fn preflight_profile(
+    state: State<'_, AppState>,
+    profile_id: String,
+    plan: PreflightPlan,
+) -> Result<PreflightReport> {
+    plan.validate()?;
+    let mut launch = prepare_launch(&state, &profile_id, None, None)?;
+    launch.config.preflight = Some(plan.clone());
+    let probe = run_bridge_probe(
+        &state,
+        &launch,
+        crate::preflight::PREFLIGHT_TIMEOUT_SECS,
+        crate::preflight::parse_preflight_line,
+    )?
+    .ok_or_else(|| ManifoldError::Bridge("exited or timed out without a preflight".into()))??;
+
+    let leak_test = judge_leak_probe(&state, &launch, &probe.leak)?;
+    state.leak_tests.lock().unwrap().save(&leak_test)?;
+    Ok(crate::preflight::evaluate(&plan, probe, leak_test))
+}
+
+/// Spawn the bridge for a prepared probe-mode launch and return the first
+/// stdout line `parse` recognises.  The bridge and any tunnel it used are
+/// torn down either way; `None` means it exited or ran past `timeout_secs`
+/// without printing one.
+fn run_bridge_probe<T: Send + 'static>(
+    state: &AppState,
+    launch: &PreparedLaunch,
+    timeout_secs: u64,
+    parse: fn(&str) -> Option<T>,
+) -> Result<Option<T>> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let config_json = launch.config.to_env_json()?;
+    let mut child = bridge_command(state, &launch.profile)?
+        .env("MANIFOLD_LAUNCH_CONFIG", &config_json)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| ManifoldError::spawn("bridge", e))?;
+
+    // Read stdout on a thread so a hung browser can't outlast the timeout
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+            if let Some(parsed) = parse(&line) {
+                tx.send(parsed).ok();
+                return;
+            }
+        }
+    });
+    let parsed = rx.recv_timeout(Duration::from_secs(timeout_secs));
+    kill_process_pid(child.id(), true);
+    child.wait().ok();
+    state.chain_forwarder.lock().unwrap().take();
+    state.vpn_tunnel.lock().unwrap().take();
+    state.virtual_display.lock().unwrap().take();
+    Ok(parsed.ok())
+}
+
+/// Judge a leak probe against the launched profile and its proxy, and give
+/// the proxy the measured region unless the user set one.
+fn judge_leak_probe(
+    state: &AppState,
+    launch: &PreparedLaunch,
+    probe: &crate::leak_test::LeakProbe,
+) -> Result<LeakTestReport> {
+    let ipv6 = match &launch.proxy {
+        Some(p) => Some(state.proxies.lock().unwrap().ipv6_leak_check(&p.id)?),
+        None => None,
+    };
+    // The measured exit names the proxy's region when the user didn't
+    if let Some(p) = &launch.proxy {
+        let measured = p
+            .country
+            .as_deref()
+            .and_then(|cc| crate::leak_test::measured_region(probe, cc));
+        if let Some(region) = measured {
+            state.proxies.lock().unwrap().fill_region(&p.id, &region)?;
+        }
+    }
+    Ok(crate::leak_test::evaluate(
+        &launch.profile.id,
+        &launch.profile.fingerprint,
+        probe,
+        ipv6.as_ref(),
+        launch.profile.tls_bridge.unwrap_or(false),
+    ))
+}
+
+/// The profile's latest leak test report, if it has been tested.
+#[tauri::command]
+pub fn get_leak_test(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<Option<LeakTestReport>> {
+    state.leak_tests.lock().unwrap().latest(&profile_id)
+}
+
+/// Start the playwright bridge in background using default config (dev profile).
+/// Useful for packaged app startup so bridge WS is available immediately.
+#[tauri::command]
+pub fn start_bridge(state: State<'_, AppState>) -> Result<u16> {
+    use crate::error::ManifoldError;
+    use std::process::Stdio;
 
     // Already running
     if state.bridge_pid.lock().unwrap().is_some() {
@@ -391,339 +2171,1975 @@ pub fn start_bridge(state: State<'_, AppState>) -> Result<u16> {
         return Ok(port);
     }
 
-    let workspace = std::env::current_dir().unwrap_or_else(|_| ".".into());
-    let bridge_path = workspace.join("playwright-bridge").join("index.ts");
-    if !bridge_path.exists() {
-        return Err(ManifoldError::BridgeOffline);
+    let search = BridgeSearch::from_env();
+    let bridge = crate::bridge_locator::locate(&search)?;
+    let node_dir = state
+        .profiles
+        .lock()
+        .unwrap()
+        .launch_env_settings()?
+        .resolve_node_dir();
+
+    // The dev profile has no launch config to carry the secret
+    let secret = crate::bridge_auth::new_secret();
+    let child = bridge
+        .command(node_dir.as_deref(), &search.workspace)?
+        .current_dir(&search.workspace)
+        .env("MANIFOLD_BRIDGE_SECRET", &secret)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| ManifoldError::spawn("bridge", e))?;
+
+    *state.bridge_pid.lock().unwrap() = Some(child.id());
+    *state.bridge_secret.lock().unwrap() = Some(secret);
+    let port = *state.bridge_port.lock().unwrap();
+    Ok(port)
+}
+
+/// Directly set a profile's status (used by panic shutdown).  Goes through
+/// the status state machine like every other change.
+#[tauri::command]
+pub fn set_profile_status(state: State<'_, AppState>, id: String, status: String) -> Result<()> {
+    use crate::error::ManifoldError;
+    let ps = status
+        .parse::<ProfileStatus>()
+        .map_err(|e| ManifoldError::InvalidArg(e.to_string()))?;
+    state.profiles.lock().unwrap().set_status(&id, ps)?;
+    Ok(())
+}
+
+/// Emergency panic shutdown:
+///   1. Kill the bridge process
+///   2. Set ALL running profiles to Idle
+///   3. Close their sessions and log a panic event
+///   4. Returns a summary of affected profiles
+#[tauri::command]
+pub fn panic_shutdown(state: State<'_, AppState>) -> Result<PanicShutdownResult> {
+    // ── 1. Kill bridge ────────────────────────────────────────────────────
+    let mut pid_guard = state.bridge_pid.lock().unwrap();
+    if let Some(pid) = pid_guard.take() {
+        #[cfg(target_os = "windows")]
+        {
+            std::process::Command::new("taskkill")
+                .args(["/PID", &pid.to_string(), "/F", "/T"])
+                .output()
+                .ok();
+        }
+        #[cfg(not(target_os = "windows"))]
+        {
+            // SIGKILL for guaranteed termination
+            unsafe {
+                libc::kill(pid as i32, libc::SIGKILL);
+            }
+        }
+    }
+    drop(pid_guard);
+    release_bridge_node(&state);
+    state.chain_forwarder.lock().unwrap().take();
+    state.vpn_tunnel.lock().unwrap().take();
+    state.virtual_display.lock().unwrap().take();
+    state.ssh_tunnels.stop_all();
+
+    // Pool launches go too, queued ones included
+    state.node_pool.clear_queue();
+    for placement in state.node_pool.take_all() {
+        stop_placement(&placement);
+    }
+
+    // Also stop scraper sidecar if running.
+    if let Some(scraper_pid) = state.scraper_pid.lock().unwrap().take() {
+        kill_process_pid(scraper_pid, true);
+    }
+
+    // ── 2. Mark all running profiles as idle ──────────────────────────────
+    let stopped_profiles = state
+        .profiles
+        .lock()
+        .unwrap()
+        .transition_all(
+            &[ProfileStatus::Running, ProfileStatus::Suspended],
+            ProfileStatus::Idle,
+            TransitionReason::Panic,
+        )
+        .unwrap_or_default();
+
+    // ── 3. Close sessions and log the shutdown ────────────────────────────
+    let sessions = state.sessions.lock().unwrap();
+    let events = state.events.lock().unwrap();
+    for id in &stopped_profiles {
+        sessions.end_open(id).ok();
+        events
+            .record(NewEvent::simple(Some(id), EventKind::Panic))
+            .ok();
+    }
+    crate::notifications::notify(&state.db, Alert::panic_shutdown(stopped_profiles.len()));
+
+    Ok(PanicShutdownResult {
+        stopped_profiles,
+        timestamp: Utc::now().to_rfc3339(),
+    })
+}
+
+#[derive(Serialize)]
+pub struct PanicShutdownResult {
+    pub stopped_profiles: Vec<String>,
+    pub timestamp: String,
+}
+
+/// Stop the running playwright-bridge process.  A profile running in the
+/// node pool is stopped on its own and a queued one taken off the queue;
+/// the bridge is left alone.
+#[tauri::command]
+pub fn stop_bridge(state: State<'_, AppState>, profile_id: Option<String>) -> Result<()> {
+    let pooled = profile_id
+        .as_deref()
+        .and_then(|id| state.node_pool.remove(id));
+    if let Some(placement) = &pooled {
+        stop_placement(placement);
+    } else if profile_id
+        .as_deref()
+        .is_some_and(|id| state.node_pool.cancel(id))
+    {
+        return Ok(());
+    } else {
+        let mut pid_guard = state.bridge_pid.lock().unwrap();
+
+        if let Some(pid) = pid_guard.take() {
+            kill_process_pid(pid, true);
+        }
+        drop(pid_guard);
+        release_bridge_node(&state);
+        state.chain_forwarder.lock().unwrap().take();
+        state.vpn_tunnel.lock().unwrap().take();
+        state.virtual_display.lock().unwrap().take();
+        state.ssh_tunnels.stop_all();
+    }
+
+    // Mark profile idle
+    if let Some(id) = profile_id {
+        state.profiles.lock().unwrap().transition(
+            &id,
+            ProfileStatus::Idle,
+            TransitionReason::Stop,
+        )?;
+        state.sessions.lock().unwrap().end_open(&id).ok();
+        state
+            .events
+            .lock()
+            .unwrap()
+            .record(NewEvent::simple(Some(&id), EventKind::Stop))
+            .ok();
+    }
+
+    Ok(())
+}
+
+// ── Workflow commands ─────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub fn list_workflows(state: State<'_, AppState>) -> Result<Vec<Workflow>> {
+    WorkflowRepo::new(state.db.clone()).list()
+}
+
+#[tauri::command]
+pub fn get_workflow(state: State<'_, AppState>, id: String) -> Result<Workflow> {
+    WorkflowRepo::new(state.db.clone()).get(&id)
+}
+
+#[tauri::command]
+pub fn create_workflow(
+    state: State<'_, AppState>,
+    name: String,
+    steps: Vec<WorkflowStep>,
+    cleanup: Option<Vec<WorkflowStep>>,
+) -> Result<Workflow> {
+    WorkflowRepo::new(state.db.clone()).create(name, steps, cleanup.unwrap_or_default())
+}
+
+/// Replace a workflow's name, steps and cleanup steps.
+#[tauri::command]
+pub fn update_workflow(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+    steps: Vec<WorkflowStep>,
+    cleanup: Option<Vec<WorkflowStep>>,
+) -> Result<Workflow> {
+    WorkflowRepo::new(state.db.clone()).update(&id, name, steps, cleanup.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn delete_workflow(state: State<'_, AppState>, id: String) -> Result<()> {
+    WorkflowRepo::new(state.db.clone()).delete(&id)
+}
+
+/// Tauri event announcing a run paused on a `manual_intervention` step;
+/// the payload is the `Intervention`.
+pub const WORKFLOW_INTERVENTION_EVENT: &str = "workflow-intervention";
+
+/// Run a workflow on a profile.  The bridge starts in workflow mode on the
+/// profile's route (routed for the workflow's start page) and performs the
+/// steps one at a time; retries, alternative selectors and on-error
+/// branches are decided here.  No session is recorded.
+///
+/// `{{variables}}` in the steps are filled in from `params`, then from the
+/// profile's vault, then from its synthetic identity (`identity.*`); the run
+/// doesn't start while any is missing.
+///
+/// A `manual_intervention` step emits `WORKFLOW_INTERVENTION_EVENT`, raises
+/// an alert and waits for `resume_workflow`; the bridge's live view stays
+/// up meanwhile so the operator can act on the page.
+///
+/// Every run leaves an archive under the profile's sessions dir — report,
+/// outputs, the bridge's log and a screenshot and request excerpt per
+/// failed step — listed by `list_workflow_runs`.
+///
+/// Stops the running bridge, like a launch does.
+#[tauri::command]
+pub async fn run_workflow(
+    app: tauri::AppHandle,
+    profile_id: String,
+    workflow_id: String,
+    params: Option<std::collections::BTreeMap<String, String>>,
+) -> Result<WorkflowRunReport> {
+    // Off the async runtime: the run blocks for as long as its steps take
+    tauri::async_runtime::spawn_blocking(move || {
+        run_workflow_blocking(&app, &profile_id, &workflow_id, params.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| ManifoldError::Other(format!("workflow run failed: {e}")))?
+}
+
+fn run_workflow_blocking(
+    app: &tauri::AppHandle,
+    profile_id: &str,
+    workflow_id: &str,
+    params: std::collections::BTreeMap<String, String>,
+) -> Result<WorkflowRunReport> {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use tauri::{Emitter, Manager};
+
+    let state = app.state::<AppState>();
+    let workflow = WorkflowRepo::new(state.db.clone()).get(workflow_id)?;
+    // Variables are filled in before anything is launched
+    let mut vars = std::collections::BTreeMap::new();
+    let wants_identity = crate::workflow::referenced_variables(&workflow)?
+        .iter()
+        .any(|v| v.starts_with(crate::synthetic_identity::VARIABLE_PREFIX));
+    if wants_identity {
+        let profile = state.profiles.lock().unwrap().get(profile_id)?;
+        vars.extend(profile_identity(&state, &profile, false)?.variables());
+    }
+    vars.extend(VaultRepo::new(state.db.clone()).values(profile_id)?);
+    vars.extend(params);
+    let workflow = crate::workflow::bind(&workflow, &vars)?;
+    let target_domain = workflow.start_url().map(str::to_string);
+    let mut launch = prepare_launch(&state, profile_id, None, target_domain)?;
+    launch.config.workflow = true;
+
+    // Claimed like a launch, so nothing else starts the profile mid-run
+    let claimed = state.profiles.lock().unwrap().transition(
+        profile_id,
+        ProfileStatus::Running,
+        TransitionReason::Launch,
+    );
+    if let Err(e) = claimed {
+        release_route(&state);
+        return Err(e);
+    }
+    let spawned = launch.config.to_env_json().and_then(|config_json| {
+        bridge_command(&state, &launch.profile)?
+            .env("MANIFOLD_LAUNCH_CONFIG", &config_json)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ManifoldError::spawn("bridge", e))
+    });
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            release_route(&state);
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(
+                    profile_id,
+                    ProfileStatus::Error,
+                    TransitionReason::LaunchFailed,
+                )
+                .ok();
+            return Err(e);
+        }
+    };
+    let pid = child.id();
+    // Registered as the bridge: a stop or another launch ends the run
+    *state.bridge_pid.lock().unwrap() = Some(pid);
+    // The live view stays reachable for manual interventions
+    *state.bridge_secret.lock().unwrap() = launch.config.ws_secret.clone();
+
+    let log = RunLog::default();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn({
+        let log = log.clone();
+        move || {
+            for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+                match crate::workflow::parse_step_result_line(&line) {
+                    Some(result) => {
+                        if tx.send(result).is_err() {
+                            return;
+                        }
+                    }
+                    None => log.push(line),
+                }
+            }
+        }
+    });
+    let stderr = child.stderr.take().expect("stderr is piped");
+    std::thread::spawn({
+        let log = log.clone();
+        move || {
+            for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                eprintln!("{line}");
+                log.push(line);
+            }
+        }
+    });
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let operator = {
+        let app = app.clone();
+        let (run_id, workflow_id, profile_id) =
+            (run_id.clone(), workflow.id.clone(), profile_id.to_string());
+        Box::new(move |prompt: &str, timeout_secs: u64| {
+            let state = app.state::<AppState>();
+            let intervention =
+                Intervention::new(&run_id, &workflow_id, &profile_id, prompt, timeout_secs);
+            state.interventions.wait(intervention, |i| {
+                app.emit(WORKFLOW_INTERVENTION_EVENT, i.clone()).ok();
+                crate::notifications::notify(&state.db, Alert::workflow_intervention(i));
+            })
+        })
+    };
+    let stdin = child.stdin.take().expect("stdin is piped");
+    let mut runner = crate::workflow::BridgeStepRunner::new(
+        stdin,
+        rx,
+        Duration::from_secs(crate::workflow::STEP_TIMEOUT_SECS),
+    )
+    .with_operator(operator);
+    let report = crate::workflow::execute(&workflow, &run_id, profile_id, &mut runner);
+    let failures = runner.failures().to_vec();
+
+    // Closing stdin ends the bridge's step loop; the kill covers a hung browser
+    drop(runner);
+    kill_process_pid(pid, true);
+    child.wait().ok();
+    let mut registered = state.bridge_pid.lock().unwrap();
+    // Otherwise stopped or replaced mid-run by someone who handled the rest
+    if *registered == Some(pid) {
+        *registered = None;
+        release_route(&state);
+        drop(registered);
+        state
+            .profiles
+            .lock()
+            .unwrap()
+            .transition(profile_id, ProfileStatus::Idle, TransitionReason::Exited)
+            .ok();
+    }
+
+    RunOutputRepo::new(state.db.clone()).save(
+        &run_id,
+        &workflow.id,
+        profile_id,
+        &report.outputs,
+    )?;
+    let archive = RunArchive {
+        format: crate::run_artifacts::ARCHIVE_FORMAT,
+        workflow_name: workflow.name.clone(),
+        report: report.clone(),
+        failures,
+        log: log.lines(),
+    };
+    let run = crate::run_artifacts::write_archive(&crate::db::profiles_dir(), &archive)?;
+    WorkflowRunRepo::new(state.db.clone()).record(&run)?;
+    Ok(report)
+}
+
+/// Tauri event sent as each profile of a group run finishes; the payload is
+/// its `ProfileRunResult`.
+pub const WORKFLOW_GROUP_PROGRESS_EVENT: &str = "workflow-group-progress";
+
+/// Run a workflow on every profile tagged `group_id`, one profile at a time,
+/// and store the aggregated report.  Profiles that fail or can't start are
+/// reported and don't stop the others unless `options.stop_on_failure`;
+/// `options.resume_from` re-runs only the failures of an earlier group run.
+#[tauri::command]
+pub async fn run_workflow_on_group(
+    app: tauri::AppHandle,
+    workflow_id: String,
+    group_id: String,
+    options: Option<GroupRunOptions>,
+) -> Result<GroupRunReport> {
+    tauri::async_runtime::spawn_blocking(move || {
+        run_workflow_on_group_blocking(&app, &workflow_id, &group_id, options.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| ManifoldError::Other(format!("group run failed: {e}")))?
+}
+
+fn run_workflow_on_group_blocking(
+    app: &tauri::AppHandle,
+    workflow_id: &str,
+    group_id: &str,
+    options: GroupRunOptions,
+) -> Result<GroupRunReport> {
+    use crate::group_run::{check_resumable, plan, GroupRunRepo, ProfileRunResult};
+    use crate::workflow::StepStatus;
+    use tauri::{Emitter, Manager};
+
+    let state = app.state::<AppState>();
+    let workflow = WorkflowRepo::new(state.db.clone()).get(workflow_id)?;
+    let filter = ProfileFilter {
+        tag: Some(group_id.to_string()),
+        ..ProfileFilter::default()
+    };
+    let group: Vec<Profile> = state
+        .profiles
+        .lock()
+        .unwrap()
+        .list()?
+        .into_iter()
+        .filter(|p| filter.matches(p))
+        .collect();
+    if group.is_empty() {
+        return Err(ManifoldError::InvalidArg(format!(
+            "no profiles in group {group_id:?}"
+        )));
+    }
+    let runs = GroupRunRepo::new(state.db.clone());
+    let previous = options
+        .resume_from
+        .as_deref()
+        .map(|id| runs.get(id))
+        .transpose()?;
+    if let Some(previous) = &previous {
+        check_resumable(previous, &workflow.id, group_id)?;
+    }
+
+    let started_at = Utc::now();
+    let (queue, mut results) = plan(group, previous.as_ref());
+    let mut stopped = false;
+    for profile in &queue {
+        let result = if stopped {
+            ProfileRunResult::not_run(profile)
+        } else {
+            let run = run_workflow_blocking(app, &profile.id, &workflow.id, options.params.clone());
+            let (run_id, status, error) = match run {
+                Ok(report) => {
+                    let error = report
+                        .steps
+                        .iter()
+                        .rev()
+                        .find(|s| s.status == StepStatus::Failed)
+                        .and_then(|s| s.error.clone());
+                    (Some(report.run_id), Some(report.status), error)
+                }
+                Err(e) => (None, None, Some(e.to_string())),
+            };
+            ProfileRunResult {
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                run_id,
+                status,
+                error,
+            }
+        };
+        stopped |= options.stop_on_failure && !result.succeeded();
+        app.emit(WORKFLOW_GROUP_PROGRESS_EVENT, result.clone()).ok();
+        results.push(result);
+    }
+
+    let report = GroupRunReport::new(
+        uuid::Uuid::new_v4().to_string(),
+        &workflow.id,
+        group_id,
+        options.resume_from,
+        results,
+        started_at,
+    );
+    runs.save(&report)?;
+    Ok(report)
+}
+
+/// Workflow runs newest first, optionally of one profile and/or workflow.
+#[tauri::command]
+pub fn list_workflow_runs(
+    state: State<'_, AppState>,
+    profile_id: Option<String>,
+    workflow_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<WorkflowRun>> {
+    WorkflowRunRepo::new(state.db.clone()).list(
+        profile_id.as_deref(),
+        workflow_id.as_deref(),
+        limit.unwrap_or(100),
+    )
+}
+
+/// A run's artifact archive.
+#[tauri::command]
+pub fn get_workflow_run_archive(state: State<'_, AppState>, run_id: String) -> Result<RunArchive> {
+    let run = WorkflowRunRepo::new(state.db.clone()).get(&run_id)?;
+    crate::run_artifacts::read_archive(&run)
+}
+
+#[tauri::command]
+pub fn get_group_run(state: State<'_, AppState>, id: String) -> Result<GroupRunReport> {
+    crate::group_run::GroupRunRepo::new(state.db.clone()).get(&id)
+}
+
+/// Let a run paused on a `manual_intervention` step carry on.
+#[tauri::command]
+pub fn resume_workflow(state: State<'_, AppState>, run_id: String) -> Result<()> {
+    state.interventions.resume(&run_id)
+}
+
+/// Runs currently waiting for the operator, oldest first.
+#[tauri::command]
+pub fn list_interventions(state: State<'_, AppState>) -> Vec<Intervention> {
+    state.interventions.pending()
+}
+
+/// What a workflow run's extraction steps captured.
+#[tauri::command]
+pub fn list_run_outputs(state: State<'_, AppState>, run_id: String) -> Result<RunOutputs> {
+    RunOutputRepo::new(state.db.clone()).get(&run_id)
+}
+
+/// A workflow run's captured values as CSV (one row per value) or JSON.
+#[tauri::command]
+pub fn export_run_outputs(
+    state: State<'_, AppState>,
+    run_id: String,
+    format: ReportFormat,
+) -> Result<String> {
+    let outputs = RunOutputRepo::new(state.db.clone()).get(&run_id)?;
+    crate::run_outputs::export(&outputs, format)
+}
+
+/// The `{{variables}}` a workflow needs, sorted.
+#[tauri::command]
+pub fn workflow_variables(state: State<'_, AppState>, workflow_id: String) -> Result<Vec<String>> {
+    let workflow = WorkflowRepo::new(state.db.clone()).get(&workflow_id)?;
+    Ok(crate::workflow::referenced_variables(&workflow)?
+        .into_iter()
+        .collect())
+}
+
+/// Names of the profile's vault secrets; values are never returned.
+#[tauri::command]
+pub fn list_profile_secrets(state: State<'_, AppState>, profile_id: String) -> Result<Vec<String>> {
+    VaultRepo::new(state.db.clone()).names(&profile_id)
+}
+
+#[tauri::command]
+pub fn set_profile_secret(
+    state: State<'_, AppState>,
+    profile_id: String,
+    name: String,
+    value: String,
+) -> Result<()> {
+    VaultRepo::new(state.db.clone()).set(&profile_id, &name, &value)
+}
+
+#[tauri::command]
+pub fn delete_profile_secret(
+    state: State<'_, AppState>,
+    profile_id: String,
+    name: String,
+) -> Result<()> {
+    VaultRepo::new(state.db.clone()).delete(&profile_id, &name)
+}
+
+/// The country the profile appears to be in, going by its exit proxy,
+/// persona and locale.
+fn profile_geo_country(state: &AppState, profile: &Profile) -> Result<Option<String>> {
+    let exit_proxy = profile.proxy_chain.last().or(profile.proxy_id.as_ref());
+    let proxy_country = state
+        .profiles
+        .lock()
+        .unwrap()
+        .proxy_country(exit_proxy.map(String::as_str))?;
+    Ok(crate::synthetic_identity::profile_country(
+        profile,
+        proxy_country.as_deref(),
+    ))
+}
+
+/// The profile's synthetic identity for its current geo: the stored one,
+/// or a new one when there is none, the geo changed or `regenerate` is set.
+fn profile_identity(
+    state: &AppState,
+    profile: &Profile,
+    regenerate: bool,
+) -> Result<SyntheticIdentity> {
+    let country = profile_geo_country(state, profile)?.ok_or_else(|| {
+        ManifoldError::InvalidArg(format!("profile {} has no country", profile.id))
+    })?;
+    SyntheticIdentityRepo::new(state.db.clone()).ensure(profile, &country, regenerate)
+}
+
+/// Form-fill identity matching the profile's geo, as workflows see it under
+/// `{{identity.*}}`.
+#[tauri::command]
+pub fn get_synthetic_identity(
+    state: State<'_, AppState>,
+    profile_id: String,
+    regenerate: Option<bool>,
+) -> Result<SyntheticIdentity> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    profile_identity(&state, &profile, regenerate.unwrap_or(false))
+}
+
+/// Check the profile's stored identity and persona against its geo:
+/// postal code and phone formats, and countries that don't match.
+#[tauri::command]
+pub fn validate_identity(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<IdentityValidation> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let country = profile_geo_country(&state, &profile)?;
+    let identity = SyntheticIdentityRepo::new(state.db.clone()).get(&profile_id)?;
+    Ok(crate::synthetic_identity::validate(
+        &profile,
+        country.as_deref(),
+        identity.as_ref(),
+    ))
+}
+
+#[tauri::command]
+pub fn delete_synthetic_identity(state: State<'_, AppState>, profile_id: String) -> Result<()> {
+    SyntheticIdentityRepo::new(state.db.clone()).delete(&profile_id)
+}
+
+// ── Host sleep / wake ─────────────────────────────────────────────────────────
+
+/// How long a paused bridge gets to close its browser before sleep.
+const SLEEP_PAUSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(4);
+
+fn process_alive(pid: u32) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        unsafe { libc::kill(pid as i32, 0) == 0 }
+    }
+}
+
+/// React to a host power transition (see `power::spawn_watcher`).
+///
+/// Sleep: the running bridge is asked to shut down (it closes the browser,
+/// which flushes cookies and storage to the profile dir), its session is
+/// closed and the profile marked Suspended.
+///
+/// Wake: suspended profiles are relaunched when `resume_on_wake` is set —
+/// the most recently used one, since one bridge runs at a time — and set
+/// Idle otherwise.  Running profiles whose bridge didn't survive are set
+/// Idle too.
+pub fn handle_power_event(app: &tauri::AppHandle, event: PowerEvent) {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let stop_reason = |id: &str, reason: &str| {
+        state.sessions.lock().unwrap().end_open(id).ok();
+        state
+            .events
+            .lock()
+            .unwrap()
+            .record(NewEvent {
+                profile_id: Some(id.to_string()),
+                kind: EventKind::Stop,
+                severity: None,
+                domain: None,
+                detail: Some(serde_json::json!({ "reason": reason })),
+            })
+            .ok();
+    };
+
+    match event {
+        PowerEvent::Sleep => {
+            if let Some(pid) = state.bridge_pid.lock().unwrap().take() {
+                kill_process_pid(pid, true);
+                let deadline = std::time::Instant::now() + SLEEP_PAUSE_TIMEOUT;
+                while process_alive(pid) && std::time::Instant::now() < deadline {
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                }
+            }
+            release_bridge_node(&state);
+            state.chain_forwarder.lock().unwrap().take();
+            state.vpn_tunnel.lock().unwrap().take();
+            state.virtual_display.lock().unwrap().take();
+            state.ssh_tunnels.stop_all();
+            // Node connections don't survive sleep: pool launches are queued
+            // again and started on wake
+            for placement in state.node_pool.take_all() {
+                requeue_placement(&state, &placement, TransitionReason::Sleep, "host sleep");
+            }
+            let paused = state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition_all(
+                    &[ProfileStatus::Running],
+                    ProfileStatus::Suspended,
+                    TransitionReason::Sleep,
+                )
+                .unwrap_or_default();
+            for id in &paused {
+                stop_reason(id, "sleep");
+            }
+        }
+        PowerEvent::Wake => {
+            let bridge_alive = state.bridge_pid.lock().unwrap().is_some_and(process_alive);
+            if !bridge_alive {
+                let lost = state
+                    .profiles
+                    .lock()
+                    .unwrap()
+                    .transition_all(
+                        &[ProfileStatus::Running],
+                        ProfileStatus::Idle,
+                        TransitionReason::Wake,
+                    )
+                    .unwrap_or_default();
+                for id in &lost {
+                    stop_reason(id, "bridge lost during sleep");
+                }
+            }
+
+            let Ok(all) = state.profiles.lock().unwrap().list() else {
+                return;
+            };
+            let mut suspended: Vec<&Profile> = all
+                .iter()
+                .filter(|p| p.status == ProfileStatus::Suspended)
+                .collect();
+            suspended.sort_by_key(|p| std::cmp::Reverse(p.last_used));
+            let resume = state
+                .profiles
+                .lock()
+                .unwrap()
+                .power_settings()
+                .is_ok_and(|s| s.resume_on_wake);
+            for (i, p) in suspended.iter().enumerate() {
+                // Suspended → Running directly for the one that resumes
+                if resume && i == 0 && !bridge_alive {
+                    match launch_profile_blocking(app, p.id.clone(), None, None, None) {
+                        Ok(_) => continue,
+                        Err(e) => eprintln!("[power] resuming {} failed: {e}", p.id),
+                    }
+                }
+                state
+                    .profiles
+                    .lock()
+                    .unwrap()
+                    .transition(&p.id, ProfileStatus::Idle, TransitionReason::Wake)
+                    .ok();
+            }
+
+            if !state.node_pool.is_idle() {
+                let app = app.clone();
+                std::thread::spawn(move || refresh_node_pool(&app));
+            }
+        }
+    }
+}
+
+/// A profile's recent status changes, rejected attempts included.
+#[tauri::command]
+pub fn list_status_transitions(
+    state: State<'_, AppState>,
+    profile_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<StatusTransition>> {
+    state
+        .profiles
+        .lock()
+        .unwrap()
+        .status_history(&profile_id, limit.unwrap_or(50))
+}
+
+#[tauri::command]
+pub fn get_power_settings(state: State<'_, AppState>) -> Result<PowerSettings> {
+    state.profiles.lock().unwrap().power_settings()
+}
+
+#[tauri::command]
+pub fn set_power_settings(
+    state: State<'_, AppState>,
+    settings: PowerSettings,
+) -> Result<PowerSettings> {
+    let profiles = state.profiles.lock().unwrap();
+    profiles.set_power_settings(&settings)?;
+    profiles.power_settings()
+}
+
+/// Send a test alert to every configured notification sink.
+#[tauri::command]
+pub fn test_notifications(state: State<'_, AppState>) -> Result<Vec<SinkResult>> {
+    let settings = crate::notifications::load_settings(&state.db)?;
+    Ok(crate::notifications::send_test(&settings))
+}
+
+/// Start scraper WebSocket sidecar process (scripts/scraper.ts).
+#[tauri::command]
+pub fn start_scraper(state: State<'_, AppState>) -> Result<()> {
+    use crate::error::ManifoldError;
+    use std::process::{Command, Stdio};
+
+    if state.scraper_pid.lock().unwrap().is_some() {
+        return Ok(());
+    }
+
+    let workspace = std::env::current_dir().unwrap_or_else(|_| ".".into());
+    let scraper_path = workspace.join("scripts").join("scraper.ts");
+    if !scraper_path.exists() {
+        return Err(ManifoldError::Other(format!(
+            "scraper script not found: {}",
+            scraper_path.to_string_lossy()
+        )));
+    }
+
+    let child = Command::new("npx")
+        .args(["tsx", scraper_path.to_str().unwrap_or("scripts/scraper.ts")])
+        .current_dir(&workspace)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| ManifoldError::spawn("scraper", e))?;
+
+    *state.scraper_pid.lock().unwrap() = Some(child.id());
+    Ok(())
+}
+
+/// Stop scraper sidecar process.
+#[tauri::command]
+pub fn stop_scraper(state: State<'_, AppState>) -> Result<()> {
+    if let Some(pid) = state.scraper_pid.lock().unwrap().take() {
+        kill_process_pid(pid, true);
+    }
+    Ok(())
+}
+
+// ── Clipboard commands ────────────────────────────────────────────────────────
+
+/// Log a clipboard access on the profile, with the value redacted.
+fn record_clipboard_event(state: &AppState, profile_id: &str, action: &str, text: &str) {
+    let detail = serde_json::json!({
+        "action": action,
+        "value": crate::clipboard::RedactedValue::of(text),
+    });
+    state
+        .events
+        .lock()
+        .unwrap()
+        .record(NewEvent {
+            profile_id: Some(profile_id.into()),
+            kind: EventKind::Clipboard,
+            severity: None,
+            domain: None,
+            detail: Some(detail),
+        })
+        .ok();
+}
+
+/// Put `text` on the running profile's own clipboard, where the page's
+/// paste and `navigator.clipboard` find it; `paste` also types it into the
+/// focused field.  The host clipboard is never touched.
+#[tauri::command]
+pub fn clipboard_write(
+    state: State<'_, AppState>,
+    profile_id: String,
+    text: String,
+    paste: Option<bool>,
+) -> Result<()> {
+    let paste = paste.unwrap_or(false);
+    let command = ClipboardCommand::ClipboardWrite {
+        text: text.clone(),
+        paste,
+    };
+    state.clipboard.request(&profile_id, &command)?;
+    let action = if paste { "paste" } else { "write" };
+    record_clipboard_event(&state, &profile_id, action, &text);
+    Ok(())
+}
+
+/// What the running profile's clipboard holds.
+#[tauri::command]
+pub fn clipboard_read(state: State<'_, AppState>, profile_id: String) -> Result<String> {
+    let result = state
+        .clipboard
+        .request(&profile_id, &ClipboardCommand::ClipboardRead)?;
+    let text = result.text.unwrap_or_default();
+    record_clipboard_event(&state, &profile_id, "read", &text);
+    Ok(text)
+}
+
+// ── Session export commands ───────────────────────────────────────────────────
+
+/// Export the current session state as a JSON bundle containing:
+///   - HAR entries (passed from the bridge)
+///   - the recorder's event log, when passed
+///   - entropy snapshots
+///   - a header-order check of the HAR requests
+///   - profile fingerprint metadata
+///   - export timestamp
+///
+/// The bundle is written to `./profiles/<profile_id>/sessions/<ts>.json`
+/// and the path is returned to the frontend.
+#[tauri::command]
+pub fn export_session(
+    state: State<'_, AppState>,
+    profile_id: String,
+    har_json: String,
+    entropy_json: String,
+    events_json: Option<String>,
+) -> Result<String> {
+    use crate::db::profiles_dir;
+    use crate::error::ManifoldError;
+
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+
+    // Build export bundle
+    let har_val: serde_json::Value =
+        serde_json::from_str(&har_json).unwrap_or(serde_json::Value::Null);
+    let entropy_val: serde_json::Value =
+        serde_json::from_str(&entropy_json).unwrap_or(serde_json::Value::Array(vec![]));
+    let events_val: serde_json::Value = events_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or(serde_json::Value::Array(vec![]));
+
+    let header_order = crate::header_order::verify_har(
+        &crate::header_order::chrome_header_order(&profile.fingerprint),
+        &har_val,
+    );
+
+    let ts = Utc::now();
+    let bundle = serde_json::json!({
+        "version": "1.0",
+        "exported_at": ts.to_rfc3339(),
+        "profile": {
+            "id":          profile.id,
+            "name":        profile.name,
+            "seed":        profile.fingerprint.seed,
+            "user_agent":  profile.fingerprint.user_agent,
+            "platform":    profile.fingerprint.platform,
+        },
+        "har":     har_val,
+        "events":  events_val,
+        "entropy": entropy_val,
+        "header_order": header_order,
+    });
+
+    // Write to profiles/<id>/sessions/<timestamp>.json
+    let sessions_dir = profiles_dir().join(&profile_id).join("sessions");
+    std::fs::create_dir_all(&sessions_dir).context("failed to create sessions dir")?;
+
+    let filename = format!("{}.json", ts.format("%Y%m%dT%H%M%S"));
+    let out_path = sessions_dir.join(&filename);
+
+    let json_str = serde_json::to_string_pretty(&bundle).map_err(|e| ManifoldError::Json(e))?;
+
+    std::fs::write(&out_path, json_str).context("failed to write session file")?;
+
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+/// Check a HAR log's request headers against the order and casing Chrome
+/// uses for the profile's major.
+#[tauri::command]
+pub fn verify_session_headers(
+    state: State<'_, AppState>,
+    profile_id: String,
+    har_json: String,
+) -> Result<HeaderOrderReport> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let har: serde_json::Value = serde_json::from_str(&har_json)?;
+    Ok(crate::header_order::verify_har(
+        &crate::header_order::chrome_header_order(&profile.fingerprint),
+        &har,
+    ))
+}
+
+/// List previously exported session bundles for a profile, newest first.
+/// `limit` and `after` (the last filename of the previous page) page through
+/// them; bundles are never opened and only the page's files are stat'ed.
+#[tauri::command]
+pub fn list_sessions(
+    state: State<'_, AppState>,
+    profile_id: String,
+    limit: Option<u32>,
+    after: Option<String>,
+) -> Result<Vec<SessionMeta>> {
+    use crate::db::profiles_dir;
+
+    // Verify the profile exists
+    state.profiles.lock().unwrap().get(&profile_id)?;
+
+    let sessions_dir = profiles_dir().join(&profile_id).join("sessions");
+    if !sessions_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    // Names only; bundle names start with their export timestamp
+    let mut names: Vec<String> = std::fs::read_dir(&sessions_dir)
+        .context("failed to list session bundles")?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.ends_with(".json").then_some(name)
+        })
+        .collect();
+
+    // Sort newest-first
+    names.sort_by(|a, b| b.cmp(a));
+    let page = names
+        .into_iter()
+        .filter(|name| after.as_ref().is_none_or(|after| name < after))
+        .take(limit.map_or(usize::MAX, |n| n as usize));
+
+    Ok(page
+        .filter_map(|filename| {
+            let path = sessions_dir.join(&filename);
+            let size_bytes = std::fs::metadata(&path).ok()?.len();
+            Some(SessionMeta {
+                name: filename.trim_end_matches(".json").to_string(),
+                filename,
+                size_bytes,
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// An exported session bundle as an ordered timeline of navigations,
+/// actions, waits, errors and screenshots.  `session` is the bundle's name
+/// or filename from `list_sessions`.
+#[tauri::command]
+pub fn get_session_timeline(
+    state: State<'_, AppState>,
+    profile_id: String,
+    session: String,
+) -> Result<SessionTimeline> {
+    use crate::db::profiles_dir;
+
+    state.profiles.lock().unwrap().get(&profile_id)?;
+    if session.contains('/') || session.contains('\\') || session.contains("..") {
+        return Err(ManifoldError::InvalidArg("invalid session".into()));
+    }
+    let name = session.trim_end_matches(".json");
+    let path = profiles_dir()
+        .join(&profile_id)
+        .join("sessions")
+        .join(format!("{name}.json"));
+    let bundle: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read session bundle {name}"))?,
+    )?;
+    Ok(crate::timeline::build(&profile_id, name, &bundle))
+}
+
+/// Validate an externally captured HAR and store it as a session bundle of
+/// the profile, header-checked against the profile's fingerprint like an
+/// exported session.
+#[tauri::command]
+pub fn import_session_har(
+    state: State<'_, AppState>,
+    profile_id: String,
+    path: String,
+) -> Result<ImportedSession> {
+    use crate::db::profiles_dir;
+
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let size = std::fs::metadata(&path)
+        .with_context(|| format!("failed to read {path}"))?
+        .len();
+    if size > crate::har_import::MAX_HAR_BYTES {
+        return Err(ManifoldError::InvalidArg(format!(
+            "HAR is {size} bytes; at most {} can be imported",
+            crate::har_import::MAX_HAR_BYTES
+        )));
+    }
+    let raw = std::fs::read_to_string(&path).with_context(|| format!("failed to read {path}"))?;
+    let har = crate::har_import::normalize(&raw)?;
+
+    let header_order = crate::header_order::verify_har(
+        &crate::header_order::chrome_header_order(&profile.fingerprint),
+        &har.har,
+    );
+
+    let ts = Utc::now();
+    let source = Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    let bundle = serde_json::json!({
+        "version": "1.0",
+        "exported_at": ts.to_rfc3339(),
+        "imported": {
+            "creator": har.creator,
+            "file":    source,
+        },
+        "profile": {
+            "id":          profile.id,
+            "name":        profile.name,
+            "seed":        profile.fingerprint.seed,
+            "user_agent":  profile.fingerprint.user_agent,
+            "platform":    profile.fingerprint.platform,
+        },
+        "har":     har.har,
+        "events":  [],
+        "entropy": [],
+        "header_order": header_order,
+    });
+
+    let sessions_dir = profiles_dir().join(&profile_id).join("sessions");
+    std::fs::create_dir_all(&sessions_dir).context("failed to create sessions dir")?;
+    // Sorts with exports by timestamp; the suffix keeps it apart from an
+    // export made in the same second
+    let name = format!("{}-import", ts.format("%Y%m%dT%H%M%S"));
+    let out_path = sessions_dir.join(format!("{name}.json"));
+    std::fs::write(&out_path, serde_json::to_string_pretty(&bundle)?)
+        .context("failed to write session file")?;
+
+    Ok(ImportedSession {
+        session: name,
+        path: out_path.to_string_lossy().to_string(),
+        entries: har.entries,
+        creator: har.creator,
+        first_request_at: har.first_request_at,
+        header_order,
+    })
+}
+
+/// Delete a session export file.
+#[tauri::command]
+pub fn delete_session(
+    _state: State<'_, AppState>,
+    profile_id: String,
+    filename: String,
+) -> Result<()> {
+    use crate::db::profiles_dir;
+    use crate::error::ManifoldError;
+
+    // Prevent path traversal
+    if filename.contains('/') || filename.contains('\\') || filename.contains("..") {
+        return Err(ManifoldError::InvalidArg("invalid filename".into()));
+    }
+
+    let path = profiles_dir()
+        .join(&profile_id)
+        .join("sessions")
+        .join(&filename);
+    if path.exists() {
+        std::fs::remove_file(&path).context("failed to delete session bundle")?;
     }
+    Ok(())
+}
 
-    let child = Command::new("npx")
-        .args([
-            "tsx",
-            bridge_path.to_str().unwrap_or("playwright-bridge/index.ts"),
-        ])
-        .current_dir(&workspace)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .map_err(|e| ManifoldError::Other(format!("failed to spawn bridge: {e}")))?;
+#[derive(Serialize)]
+pub struct SessionMeta {
+    pub filename: String,
+    pub name: String,
+    pub size_bytes: u64,
+    pub path: String,
+}
 
-    *state.bridge_pid.lock().unwrap() = Some(child.id());
-    let port = *state.bridge_port.lock().unwrap();
-    Ok(port)
+/// Return the current bridge WebSocket URL with a one-time connection
+/// ticket; call again for every (re)connect.
+#[tauri::command]
+pub fn get_bridge_url(state: State<'_, AppState>) -> String {
+    // An exposed agent bridge is reached on the node; every other one here
+    let placed = state
+        .bridge_node
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|p| Some((p.ws_host.clone(), p.ws_port?, p.ws_tls)));
+    let bridge_port = *state.bridge_port.lock().unwrap();
+    let (host, port, tls) = placed.unwrap_or_else(|| ("localhost".into(), bridge_port, false));
+    crate::bridge_auth::bridge_url(
+        &host,
+        port,
+        tls,
+        state.bridge_secret.lock().unwrap().as_deref(),
+    )
 }
 
-/// Directly set a profile's status (used by panic shutdown).
+/// Change the bridge WebSocket port (takes effect on next launch).
 #[tauri::command]
-pub fn set_profile_status(state: State<'_, AppState>, id: String, status: String) -> Result<()> {
+pub fn set_bridge_port(state: State<'_, AppState>, port: u16) -> Result<()> {
     use crate::error::ManifoldError;
-    let ps = status
-        .parse::<ProfileStatus>()
-        .map_err(|e| ManifoldError::InvalidArg(e.to_string()))?;
-    state.profiles.lock().unwrap().set_status(&id, ps)?;
+    if port < 1024 {
+        return Err(ManifoldError::InvalidArg("port must be >= 1024".into()));
+    }
+    *state.bridge_port.lock().unwrap() = port;
     Ok(())
 }
 
-/// Emergency panic shutdown:
-///   1. Kill the bridge process
-///   2. Set ALL running profiles to Idle
-///   3. Returns a summary of affected profiles
+// ── Execution node commands ───────────────────────────────────────────────────
+
+/// Registered execution nodes with their latest health.  Agent tokens are
+/// never returned.
 #[tauri::command]
-pub fn panic_shutdown(state: State<'_, AppState>) -> Result<PanicShutdownResult> {
-    // ── 1. Kill bridge ────────────────────────────────────────────────────
-    let mut pid_guard = state.bridge_pid.lock().unwrap();
-    if let Some(pid) = pid_guard.take() {
-        #[cfg(target_os = "windows")]
-        {
-            std::process::Command::new("taskkill")
-                .args(["/PID", &pid.to_string(), "/F", "/T"])
-                .output()
-                .ok();
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            // SIGKILL for guaranteed termination
-            unsafe {
-                libc::kill(pid as i32, libc::SIGKILL);
-            }
-        }
+pub fn list_nodes(state: State<'_, AppState>) -> Result<Vec<ExecutionNode>> {
+    NodeRepo::new(state.db.clone()).list()
+}
+
+#[tauri::command]
+pub fn add_node(state: State<'_, AppState>, spec: NodeSpec) -> Result<ExecutionNode> {
+    NodeRepo::new(state.db.clone()).add(spec)
+}
+
+/// Replace a node's settings; without `agent_token` the stored one is kept.
+/// Takes effect on the next launch.
+#[tauri::command]
+pub fn update_node(
+    state: State<'_, AppState>,
+    id: String,
+    spec: NodeSpec,
+) -> Result<ExecutionNode> {
+    NodeRepo::new(state.db.clone()).update(&id, spec)
+}
+
+#[tauri::command]
+pub fn set_node_enabled(state: State<'_, AppState>, id: String, enabled: bool) -> Result<()> {
+    NodeRepo::new(state.db.clone()).set_enabled(&id, enabled)
+}
+
+/// Remove a node; refused while it runs the bridge or pool launches.
+#[tauri::command]
+pub fn delete_node(state: State<'_, AppState>, id: String) -> Result<()> {
+    let foreground = state
+        .bridge_node
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|p| p.node_id == id);
+    if foreground || state.node_pool.busy_nodes().contains(&id) {
+        return Err(ManifoldError::InvalidArg(
+            "the node is running launches; stop them first".into(),
+        ));
     }
-    drop(pid_guard);
+    NodeRepo::new(state.db.clone()).delete(&id)
+}
 
-    // Also stop scraper sidecar if running.
-    if let Some(scraper_pid) = state.scraper_pid.lock().unwrap().take() {
-        kill_process_pid(scraper_pid, true);
+/// Check a node can take launches and record the result.  The pool's
+/// launches on a node found offline are moved to other nodes.
+#[tauri::command]
+pub async fn check_node(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<NodeHealth> {
+    let repo = NodeRepo::new(state.db.clone());
+    let node = repo.get_with_token(&id)?;
+    let health = crate::nodes::check(&node).await;
+    repo.record_health(&id, &health)?;
+    if !health.online {
+        fail_over_node(&state, &id, &went_offline(&node, &health));
+    }
+    if !state.node_pool.is_idle() {
+        std::thread::spawn(move || dispatch_pool(&app));
     }
+    Ok(health)
+}
 
-    // ── 2. Mark all running profiles as idle ──────────────────────────────
-    let mut stopped_profiles: Vec<String> = Vec::new();
-    let profiles_guard = state.profiles.lock().unwrap();
-    if let Ok(all) = profiles_guard.list() {
-        for p in all {
-            if p.status == ProfileStatus::Running {
-                profiles_guard.set_status(&p.id, ProfileStatus::Idle).ok();
-                stopped_profiles.push(p.id.clone());
-            }
+// ── Node pool commands ────────────────────────────────────────────────────────
+
+/// Queue launches for the node pool (`node_pool`) and start as many as the
+/// nodes have room for; the rest start as running ones end.  Stops at the
+/// first profile already running or in the pool; those before it stay
+/// queued.
+#[tauri::command]
+pub fn enqueue_launches(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    launches: Vec<LaunchRequest>,
+) -> Result<PoolStatus> {
+    let queued = launches.into_iter().try_for_each(|request| {
+        let profile = state.profiles.lock().unwrap().get(&request.profile_id)?;
+        if profile.status == ProfileStatus::Running {
+            return Err(ManifoldError::InvalidArg(format!(
+                "profile {} is already running",
+                profile.name
+            )));
+        }
+        state.node_pool.enqueue(QueuedLaunch::new(request))
+    });
+    std::thread::spawn(move || refresh_node_pool(&app));
+    queued?;
+    get_node_pool(state)
+}
+
+/// Node loads, the pool's running launches and its queue.
+#[tauri::command]
+pub fn get_node_pool(state: State<'_, AppState>) -> Result<PoolStatus> {
+    let nodes = NodeRepo::new(state.db.clone()).list()?;
+    let foreground = state
+        .bridge_node
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|p| p.node_id.clone());
+    Ok(state.node_pool.status(&nodes, foreground.as_deref()))
+}
+
+/// WebSocket URL of a pool launch's bridge, with a one-time connection
+/// ticket; call again for every (re)connect.
+#[tauri::command]
+pub fn get_pool_bridge_url(state: State<'_, AppState>, profile_id: String) -> Result<String> {
+    let placement = state.node_pool.placement(&profile_id).ok_or_else(|| {
+        ManifoldError::InvalidArg(format!(
+            "profile {profile_id} isn't running in the node pool"
+        ))
+    })?;
+    placement
+        .bridge_url()
+        .ok_or_else(|| ManifoldError::InvalidArg(format!("profile {profile_id} is still starting")))
+}
+
+/// Run in the background: keep the pool's nodes checked, failing over the
+/// ones that go offline, and start queued launches as room appears.
+pub fn watch_node_pool(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    loop {
+        std::thread::sleep(crate::node_pool::CHECK_INTERVAL);
+        if !app.state::<AppState>().node_pool.is_idle() {
+            refresh_node_pool(app);
         }
     }
-    drop(profiles_guard);
+}
 
-    Ok(PanicShutdownResult {
-        stopped_profiles,
-        timestamp: Utc::now().to_rfc3339(),
-    })
+/// Check the nodes the pool runs on — every enabled one while launches are
+/// queued — fail over those found offline, then start what the queue can.
+fn refresh_node_pool(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    let repo = NodeRepo::new(state.db.clone());
+    let busy = state.node_pool.busy_nodes();
+    let queued = state.node_pool.has_queue();
+    let nodes: Vec<ExecutionNode> = repo
+        .list()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|n| n.enabled && (queued || busy.contains(&n.id)))
+        .filter_map(|n| repo.get_with_token(&n.id).ok())
+        .collect();
+    let checked = tauri::async_runtime::block_on(crate::node_pool::check_all(nodes));
+    for (node, health) in checked {
+        repo.record_health(&node.id, &health).ok();
+        if !health.online {
+            fail_over_node(&state, &node.id, &went_offline(&node, &health));
+        }
+    }
+    dispatch_pool(app);
 }
 
-#[derive(Serialize)]
-pub struct PanicShutdownResult {
-    pub stopped_profiles: Vec<String>,
-    pub timestamp: String,
+fn went_offline(node: &ExecutionNode, health: &NodeHealth) -> String {
+    format!(
+        "node {} went offline: {}",
+        node.spec.name,
+        health.error.as_deref().unwrap_or("health check failed")
+    )
 }
 
-/// Stop the running playwright-bridge process.
-#[tauri::command]
-pub fn stop_bridge(state: State<'_, AppState>, profile_id: Option<String>) -> Result<()> {
-    let mut pid_guard = state.bridge_pid.lock().unwrap();
+/// Start queued pool launches while a node has room.  A launch that fails
+/// to start is queued again at the back, unless it can't start on any node
+/// (its route, a conflict) or is out of attempts; then its profile is
+/// marked Error.  A requeue ends the round: the rest waits for the next
+/// refresh to check the nodes again, rather than burning the launch's
+/// attempts on the node that just failed it.
+fn dispatch_pool(app: &tauri::AppHandle) {
+    use crate::node_pool::MAX_LAUNCH_ATTEMPTS;
+    use tauri::Manager;
 
-    if let Some(pid) = pid_guard.take() {
-        kill_process_pid(pid, true);
+    let state = app.state::<AppState>();
+    let nodes = match NodeRepo::new(state.db.clone()).list() {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            eprintln!("[pool] listing nodes failed: {e}");
+            return;
+        }
+    };
+    loop {
+        let foreground = state
+            .bridge_node
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|p| p.node_id.clone());
+        let Some(placement) = state.node_pool.claim_next(&nodes, foreground.as_deref()) else {
+            break;
+        };
+        let error = if placement.attempts > MAX_LAUNCH_ATTEMPTS {
+            ManifoldError::Other(format!("gave up after {MAX_LAUNCH_ATTEMPTS} attempts"))
+        } else {
+            match start_pooled(app, &state, placement.clone()) {
+                Ok(()) => continue,
+                Err(e) => e,
+            }
+        };
+        if !state.node_pool.release(&placement) {
+            // Stopped while it started
+            continue;
+        }
+        let id = &placement.request.profile_id;
+        eprintln!("[pool] {id} on node {}: {error}", placement.node_name);
+        let retry = placement.attempts < MAX_LAUNCH_ATTEMPTS
+            && !matches!(
+                error,
+                ManifoldError::InvalidArg(_) | ManifoldError::ProfileNotFound(_)
+            );
+        if retry {
+            state
+                .node_pool
+                .requeue(placement.requeued(error.to_string()), false);
+            break;
+        } else {
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(id, ProfileStatus::Error, TransitionReason::LaunchFailed)
+                .ok();
+        }
     }
+}
 
-    // Mark profile idle
-    if let Some(id) = profile_id {
+/// Start a claimed pool launch on its node.  Returns `Ok` without a
+/// running bridge when the claim was taken back while it started.
+fn start_pooled(app: &tauri::AppHandle, state: &AppState, mut placement: Placement) -> Result<()> {
+    use std::io::Write;
+    use std::process::{Child, ChildStdin, Stdio};
+
+    // What's left to reap once the node runs the bridge
+    type Started = Option<(Child, Option<ChildStdin>)>;
+
+    let id = placement.request.profile_id.clone();
+    let node = NodeRepo::new(state.db.clone()).get_with_token(&placement.node_id)?;
+    let mut launch = prepare_pooled_launch(state, &placement.request)?;
+    launch.config.control = true;
+    crate::nodes::place(&node, placement.slot, &mut launch.config)?;
+    let config_json = launch.config.to_env_json()?;
+    placement.node = node.clone();
+    placement.ws_secret = launch.config.ws_secret.clone();
+
+    let local_port = crate::ssh_tunnel::free_local_port()?;
+    let forwarded = placement.route_ws(local_port);
+
+    // Running before anything starts on the node, so conflict checks see
+    // the profile while the node brings its bridge up and a failover from
+    // here on finds it to stop
+    state.profiles.lock().unwrap().transition(
+        &id,
+        ProfileStatus::Running,
+        TransitionReason::Launch,
+    )?;
+    let mut start = || -> Result<Started> {
+        match node.spec.kind {
+            NodeKind::Agent => {
+                tauri::async_runtime::block_on(crate::nodes::agent_launch(&node, &config_json))
+                    .with_context(|| format!("node {} failed to launch", node.spec.name))?;
+                if !forwarded {
+                    return Ok(None);
+                }
+                // The bridge keeps to the node's loopback
+                let forward = crate::nodes::start_ws_forward(&node, placement.slot, local_port)
+                    .inspect_err(|_| stop_placement(&placement))?;
+                placement.pid = Some(forward.id());
+                Ok(Some((forward, None)))
+            }
+            NodeKind::Ssh => {
+                let tls_bridge = launch
+                    .config
+                    .tls_bridge_port
+                    .map(|_| *state.tls_bridge_port.lock().unwrap());
+                let mut cmd =
+                    crate::nodes::ssh_bridge_command(&node, placement.slot, local_port, tls_bridge);
+                cmd.stdout(Stdio::null()).stderr(Stdio::inherit());
+                let mut spawned = cmd.spawn().map_err(|e| ManifoldError::spawn("ssh", e))?;
+                let mut stdin = spawned.stdin.take().expect("stdin is piped");
+                if let Err(e) = writeln!(stdin, "{config_json}") {
+                    kill_process_pid(spawned.id(), true);
+                    spawned.wait().ok();
+                    return Err(ManifoldError::Io(e))
+                        .context("failed to send the launch config to the node");
+                }
+                placement.pid = Some(spawned.id());
+                Ok(Some((spawned, Some(stdin))))
+            }
+        }
+    };
+    let child = match start() {
+        Ok(child) => child,
+        Err(e) => {
+            // Idle again for the retry, or the Error the pool settles on
+            state
+                .profiles
+                .lock()
+                .unwrap()
+                .transition(&id, ProfileStatus::Idle, TransitionReason::LaunchFailed)
+                .ok();
+            return Err(e);
+        }
+    };
+
+    let discard = |child: Started| {
+        stop_placement(&placement);
+        if let Some((mut child, _)) = child {
+            child.wait().ok();
+        }
+    };
+    if !state.node_pool.started(&placement) {
+        // Stopped or failed over while it started: the claim is gone
         state
             .profiles
             .lock()
             .unwrap()
-            .set_status(&id, ProfileStatus::Idle)
+            .transition(&id, ProfileStatus::Idle, TransitionReason::Stop)
             .ok();
+        discard(child);
+        return Ok(());
     }
 
+    state.profiles.lock().unwrap().touch_last_used(&id).ok();
+    record_launch(state, &id, &launch, Some(&node));
+    if let Some((child, stdin)) = child {
+        watch_pooled(app.clone(), child, stdin, placement);
+    }
     Ok(())
 }
 
-/// Start scraper WebSocket sidecar process (scripts/scraper.ts).
-#[tauri::command]
-pub fn start_scraper(state: State<'_, AppState>) -> Result<()> {
-    use crate::error::ManifoldError;
-    use std::process::{Command, Stdio};
-
-    if state.scraper_pid.lock().unwrap().is_some() {
-        return Ok(());
-    }
-
-    let workspace = std::env::current_dir().unwrap_or_else(|_| ".".into());
-    let scraper_path = workspace.join("scripts").join("scraper.ts");
-    if !scraper_path.exists() {
-        return Err(ManifoldError::Other(format!(
-            "scraper script not found: {}",
-            scraper_path.to_string_lossy()
+/// Resolve a pool launch's route and build its bridge config.  Unlike
+/// `prepare_launch` it leaves the running bridge alone, and it refuses
+/// routes through a tunnel on this machine (SSH proxies, chains, VPNs),
+/// which a node can't reach.
+fn prepare_pooled_launch(state: &AppState, request: &LaunchRequest) -> Result<PreparedLaunch> {
+    let id = &request.profile_id;
+    let mut profile = state.profiles.lock().unwrap().get(id)?;
+    state.conflicts.lock().unwrap().ensure_launchable(id)?;
+    let (proxy, chain_hops) =
+        resolve_route(state, &profile, &request.url, request.target_domain.clone())?;
+    let vpn = state.vpns.lock().unwrap().config(id)?;
+    let ssh = proxy
+        .as_ref()
+        .is_some_and(|p| p.proxy_type == ProxyType::Ssh);
+    if ssh || !chain_hops.is_empty() || vpn.is_some() {
+        return Err(ManifoldError::InvalidArg(format!(
+            "profile {} is routed through a local tunnel (SSH proxy, chain or VPN), \
+             which execution nodes can't reach",
+            profile.name
         )));
     }
 
-    let child = Command::new("npx")
-        .args(["tsx", scraper_path.to_str().unwrap_or("scripts/scraper.ts")])
-        .current_dir(&workspace)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .map_err(|e| ManifoldError::Other(format!("failed to spawn scraper: {e}")))?;
+    let window = session_window(state, &mut profile)?;
+    let proxy_config = proxy.as_ref().map(|p| ProxyConfig {
+        server: p.to_playwright_server(),
+        username: p.username.clone(),
+        password: p.password.clone(),
+    });
+    let mut config = build_launch_config(state, &profile, proxy_config, request.url.clone());
+    config.window = window;
+    Ok(PreparedLaunch {
+        profile,
+        proxy,
+        config,
+        // Remote browsers run headless (`nodes::place`)
+        plan: LaunchPlan {
+            mode: LaunchMode::HeadlessNew,
+            skipped: Vec::new(),
+            warnings: Vec::new(),
+        },
+    })
+}
 
-    *state.scraper_pid.lock().unwrap() = Some(child.id());
-    Ok(())
+/// Wait for a pool launch's `ssh` to end: the bridge's own on an SSH node
+/// (with its `stdin`), the WebSocket forward on an agent node.  When `ssh`
+/// lost the connection the node is taken for offline and its launches fail
+/// over; otherwise the profile stops like one whose bridge exited, and an
+/// agent is asked to stop the bridge it still runs.
+fn watch_pooled(
+    app: tauri::AppHandle,
+    mut child: std::process::Child,
+    stdin: Option<std::process::ChildStdin>,
+    mut placement: Placement,
+) {
+    use crate::node_pool::SSH_CONNECTION_LOST;
+    use tauri::Manager;
+
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        let exit = child.wait();
+        // Held open until now: the bridge shuts down when its input ends
+        drop(stdin);
+        // Reaped: stopping the launch mustn't kill whatever reuses the pid
+        placement.pid = None;
+        if !state.node_pool.release(&placement) {
+            // Stopped or failed over by someone who handles the status
+            return;
+        }
+        if exit
+            .as_ref()
+            .is_ok_and(|s| s.code() == Some(SSH_CONNECTION_LOST))
+        {
+            let why = format!("lost the ssh connection to node {}", placement.node_name);
+            let health = NodeHealth {
+                online: false,
+                latency_ms: None,
+                error: Some(why.clone()),
+                checked_at: Utc::now(),
+            };
+            NodeRepo::new(state.db.clone())
+                .record_health(&placement.node_id, &health)
+                .ok();
+            requeue_placement(&state, &placement, TransitionReason::Failover, &why);
+            fail_over_node(&state, &placement.node_id, &why);
+        } else {
+            let (to, reason) = match exit {
+                Ok(status) if status.success() => (ProfileStatus::Idle, TransitionReason::Exited),
+                _ => (ProfileStatus::Error, TransitionReason::Crashed),
+            };
+            stop_placement(&placement);
+            end_pooled(&state, &placement, to, reason, None);
+        }
+        dispatch_pool(&app);
+    });
 }
 
-/// Stop scraper sidecar process.
-#[tauri::command]
-pub fn stop_scraper(state: State<'_, AppState>) -> Result<()> {
-    if let Some(pid) = state.scraper_pid.lock().unwrap().take() {
-        kill_process_pid(pid, true);
+/// Take every pool launch off a node and queue it again for the others.
+fn fail_over_node(state: &AppState, node_id: &str, why: &str) {
+    // Each goes to the front of the queue: reversed to keep their order
+    for placement in state.node_pool.take_node(node_id).iter().rev() {
+        requeue_placement(state, placement, TransitionReason::Failover, why);
     }
-    Ok(())
 }
 
-// ── Session export commands ───────────────────────────────────────────────────
-
-/// Export the current session state as a JSON bundle containing:
-///   - HAR entries (passed from the bridge)
-///   - entropy snapshots
-///   - profile fingerprint metadata
-///   - export timestamp
-///
-/// The bundle is written to `./profiles/<profile_id>/sessions/<ts>.json`
-/// and the path is returned to the frontend.
-#[tauri::command]
-pub fn export_session(
-    state: State<'_, AppState>,
-    profile_id: String,
-    har_json: String,
-    entropy_json: String,
-) -> Result<String> {
-    use crate::db::profiles_dir;
-    use crate::error::ManifoldError;
-
-    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
-
-    // Build export bundle
-    let har_val: serde_json::Value =
-        serde_json::from_str(&har_json).unwrap_or(serde_json::Value::Null);
-    let entropy_val: serde_json::Value =
-        serde_json::from_str(&entropy_json).unwrap_or(serde_json::Value::Array(vec![]));
+/// Stop a pool launch taken out of the pool and queue it again at the
+/// front.  A launch that had started ends its session.
+fn requeue_placement(state: &AppState, placement: &Placement, reason: TransitionReason, why: &str) {
+    stop_placement(placement);
+    if placement.ws_port.is_some() {
+        end_pooled(state, placement, ProfileStatus::Idle, reason, Some(why));
+    }
+    state
+        .node_pool
+        .requeue(placement.requeued(why.to_string()), true);
+}
 
-    let ts = Utc::now();
-    let bundle = serde_json::json!({
-        "version": "1.0",
-        "exported_at": ts.to_rfc3339(),
-        "profile": {
-            "id":          profile.id,
-            "name":        profile.name,
-            "seed":        profile.fingerprint.seed,
-            "user_agent":  profile.fingerprint.user_agent,
-            "platform":    profile.fingerprint.platform,
-        },
-        "har":     har_val,
-        "entropy": entropy_val,
-    });
+/// Move a stopped pool launch's profile to `to`, close its session and log
+/// the stop.
+fn end_pooled(
+    state: &AppState,
+    placement: &Placement,
+    to: ProfileStatus,
+    reason: TransitionReason,
+    why: Option<&str>,
+) {
+    let id = &placement.request.profile_id;
+    let moved = state.profiles.lock().unwrap().transition(id, to, reason);
+    if moved.is_err() {
+        return;
+    }
+    state.sessions.lock().unwrap().end_open(id).ok();
+    state
+        .events
+        .lock()
+        .unwrap()
+        .record(NewEvent {
+            profile_id: Some(id.clone()),
+            kind: EventKind::Stop,
+            severity: (to == ProfileStatus::Error || why.is_some()).then(|| "warning".into()),
+            domain: None,
+            detail: Some(serde_json::json!({
+                "reason": reason,
+                "node": placement.node_id,
+                "error": why,
+            })),
+        })
+        .ok();
+}
 
-    // Write to profiles/<id>/sessions/<timestamp>.json
-    let sessions_dir = profiles_dir().join(&profile_id).join("sessions");
-    std::fs::create_dir_all(&sessions_dir)
-        .map_err(|e| ManifoldError::Other(format!("failed to create sessions dir: {e}")))?;
+// ── Event log / dashboard commands ────────────────────────────────────────────
 
-    let filename = format!("{}.json", ts.format("%Y%m%dT%H%M%S"));
-    let out_path = sessions_dir.join(&filename);
+/// Append an event to the log (the bridge and frontend report detections here).
+#[tauri::command]
+pub fn record_event(state: State<'_, AppState>, event: NewEvent) -> Result<Event> {
+    state.events.lock().unwrap().record(event)
+}
 
-    let json_str = serde_json::to_string_pretty(&bundle).map_err(|e| ManifoldError::Json(e))?;
+/// Most recent events, optionally for a single profile.  Pass the last id
+/// of a page as `after` for the next one.
+#[tauri::command]
+pub fn list_events(
+    state: State<'_, AppState>,
+    profile_id: Option<String>,
+    limit: Option<u32>,
+    after: Option<i64>,
+) -> Result<Vec<Event>> {
+    state
+        .events
+        .lock()
+        .unwrap()
+        .list_before(profile_id.as_deref(), limit.unwrap_or(100), after)
+}
 
-    std::fs::write(&out_path, json_str)
-        .map_err(|e| ManifoldError::Other(format!("failed to write session file: {e}")))?;
+/// Check the event log's hash chain.  Pass the `head` of an earlier
+/// verification, kept outside the database, as `anchor` to also catch a
+/// recomputed chain.
+#[tauri::command]
+pub fn verify_event_log(
+    state: State<'_, AppState>,
+    anchor: Option<ChainLink>,
+) -> Result<EventLogVerification> {
+    state.events.lock().unwrap().verify(anchor.as_ref())
+}
 
-    Ok(out_path.to_string_lossy().to_string())
+/// How long the profile should rest before its next launch against
+/// `domain` (any domain when omitted), from its recent detections.
+#[tauri::command]
+pub fn recommend_rest(
+    state: State<'_, AppState>,
+    id: String,
+    domain: Option<String>,
+) -> Result<RestRecommendation> {
+    state.profiles.lock().unwrap().get(&id)?;
+    RestRepo::new(state.db.clone()).recommend(&id, domain.as_deref())
 }
 
-/// List previously exported session bundles for a profile.
+/// Aggregated counts for the dashboard over the last `days` days (default 30).
 #[tauri::command]
-pub fn list_sessions(state: State<'_, AppState>, profile_id: String) -> Result<Vec<SessionMeta>> {
-    use crate::db::profiles_dir;
-    use crate::error::ManifoldError;
+pub fn get_dashboard_stats(
+    state: State<'_, AppState>,
+    days: Option<u32>,
+) -> Result<DashboardStats> {
+    crate::stats::dashboard_stats(&state.db, days.unwrap_or(30).max(1))
+}
 
-    // Verify the profile exists
-    state.profiles.lock().unwrap().get(&profile_id)?;
+/// Timing and argument-size statistics of every command since startup (or
+/// the last reset), slowest first.
+#[tauri::command]
+pub fn get_command_metrics() -> MetricsReport {
+    crate::metrics::report()
+}
 
-    let sessions_dir = profiles_dir().join(&profile_id).join("sessions");
-    if !sessions_dir.exists() {
-        return Ok(vec![]);
-    }
+#[tauri::command]
+pub fn reset_command_metrics() {
+    crate::metrics::reset()
+}
 
-    let mut metas: Vec<SessionMeta> = std::fs::read_dir(&sessions_dir)
-        .map_err(|e| ManifoldError::Other(format!("read_dir failed: {e}")))?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.extension()?.to_str()? != "json" {
-                return None;
-            }
-            let name = path.file_stem()?.to_str()?.to_string();
-            let meta = entry.metadata().ok()?;
-            let size = meta.len();
-            Some(SessionMeta {
-                filename: path.file_name()?.to_str()?.to_string(),
-                name,
-                size_bytes: size,
-                path: path.to_string_lossy().to_string(),
-            })
-        })
-        .collect();
+// ── Data / persistence commands ───────────────────────────────────────────────
 
-    // Sort newest-first
-    metas.sort_by(|a, b| b.name.cmp(&a.name));
-    Ok(metas)
+/// Every app setting: core app settings plus the proxy check, launch
+/// environment, power, disk quota and notification sections.
+#[tauri::command]
+pub fn get_settings(state: State<'_, AppState>) -> Result<Settings> {
+    state.settings.lock().unwrap().get()
 }
 
-/// Delete a session export file.
+/// Apply a JSON merge patch to the settings, e.g.
+/// `{ "app": { "proxy_check_concurrency": 8 } }`.  Unknown keys and invalid values
+/// are rejected without writing anything.  Port changes take effect on the
+/// next launch.
 #[tauri::command]
-pub fn delete_session(
-    _state: State<'_, AppState>,
-    profile_id: String,
-    filename: String,
-) -> Result<()> {
-    use crate::db::profiles_dir;
-    use crate::error::ManifoldError;
+pub fn update_settings(state: State<'_, AppState>, patch: serde_json::Value) -> Result<Settings> {
+    let settings = state.settings.lock().unwrap().update(&patch)?;
+    *state.bridge_port.lock().unwrap() = settings.app.bridge_port;
+    *state.tls_bridge_port.lock().unwrap() = settings.app.tls_bridge_port;
+    Ok(settings)
+}
 
-    // Prevent path traversal
-    if filename.contains('/') || filename.contains('\\') || filename.contains("..") {
-        return Err(ManifoldError::InvalidArg("invalid filename".into()));
-    }
+/// Generic key-value persistence for frontend state, stored in the DB.
+#[tauri::command]
+pub fn save_data(state: State<'_, AppState>, key: String, data: String) -> Result<()> {
+    state.settings.lock().unwrap().save_data(&key, &data)
+}
 
-    let path = profiles_dir()
-        .join(&profile_id)
-        .join("sessions")
-        .join(&filename);
-    if path.exists() {
-        std::fs::remove_file(&path)
-            .map_err(|e| ManifoldError::Other(format!("delete failed: {e}")))?;
-    }
-    Ok(())
+#[tauri::command]
+pub fn load_data(state: State<'_, AppState>, key: String) -> Result<String> {
+    state
+        .settings
+        .lock()
+        .unwrap()
+        .load_data(&key)?
+        .ok_or_else(|| ManifoldError::Other(format!("cannot read {key}: not saved")))
 }
 
-#[derive(Serialize)]
-pub struct SessionMeta {
-    pub filename: String,
-    pub name: String,
-    pub size_bytes: u64,
-    pub path: String,
+/// Read-only export of the open workspace's profiles, proxies and events:
+/// three CSV files in the directory `path`, or one JSON file at `path`.
+#[tauri::command]
+pub fn export_workspace_report(
+    state: State<'_, AppState>,
+    format: ReportFormat,
+    path: String,
+) -> Result<WorkspaceReport> {
+    crate::report::export(&state.db, format, Path::new(&path))
 }
 
-/// Return the current bridge WebSocket URL.
+// ── Workspace commands ────────────────────────────────────────────────────────
+
+/// All workspaces, the default one first.
 #[tauri::command]
-pub fn get_bridge_url(state: State<'_, AppState>) -> String {
-    let port = *state.bridge_port.lock().unwrap();
-    format!("ws://localhost:{port}")
+pub fn list_workspaces() -> Result<Vec<Workspace>> {
+    Ok(WorkspaceRegistry::load_default()?.list())
 }
 
-/// Change the bridge WebSocket port (takes effect on next launch).
+/// Register a new, empty workspace.  Its database and profiles dir are
+/// created when it is first opened.
 #[tauri::command]
-pub fn set_bridge_port(state: State<'_, AppState>, port: u16) -> Result<()> {
-    use crate::error::ManifoldError;
-    if port < 1024 {
-        return Err(ManifoldError::InvalidArg("port must be >= 1024".into()));
+pub fn create_workspace(name: String) -> Result<Workspace> {
+    WorkspaceRegistry::load_default()?.create(&name)
+}
+
+/// Close the open workspace and open `name` in its place; it also becomes
+/// the workspace opened at startup.  `master_key` falls back to the
+/// workspace's `MANIFOLD_MASTER_KEY_<NAME>` variable.
+///
+/// Refused while a bridge or scraper is running.
+#[tauri::command]
+pub fn switch_workspace(
+    state: State<'_, AppState>,
+    name: String,
+    master_key: Option<String>,
+) -> Result<Workspace> {
+    // Held until the swap is done so no launch can start in between
+    let bridge_pid = state.bridge_pid.lock().unwrap();
+    let scraper_pid = state.scraper_pid.lock().unwrap();
+    if bridge_pid.is_some() || scraper_pid.is_some() {
+        return Err(ManifoldError::InvalidArg(
+            "stop the running profile before switching workspaces".into(),
+        ));
     }
-    *state.bridge_port.lock().unwrap() = port;
-    Ok(())
+    let mut registry = WorkspaceRegistry::load_default()?;
+    let workspace = registry.get(&name)?;
+    let master_key =
+        master_key.or_else(|| std::env::var(crate::workspace::master_key_env(&name)).ok());
+    let db = crate::workspace::open(&workspace, master_key.as_deref())?;
+
+    // Nothing of the old workspace may keep running against the new one
+    state.ssh_tunnels.stop_all();
+    state.chain_forwarder.lock().unwrap().take();
+    state.vpn_tunnel.lock().unwrap().take();
+    state.virtual_display.lock().unwrap().take();
+
+    // Repositories and background threads share the handle, so they all
+    // follow the swap; only the profiles root is captured separately
+    state.db.replace_with(db)?;
+    crate::workspace::use_profiles_dir(&workspace);
+    if let Err(e) = crate::priors::restore(&state.db) {
+        eprintln!("[priors] cached dataset not used: {e}");
+    }
+    *state.profiles.lock().unwrap() = ProfileRepo::new(state.db.clone());
+    let app = state.settings.lock().unwrap().app().unwrap_or_default();
+    *state.bridge_port.lock().unwrap() = app.bridge_port;
+    *state.tls_bridge_port.lock().unwrap() = app.tls_bridge_port;
+
+    registry.set_active(&name)?;
+    let workspace = registry.get(&name)?;
+    *state.workspace.lock().unwrap() = workspace.clone();
+    *state.master_key.lock().unwrap() = master_key;
+    drop((bridge_pid, scraper_pid));
+    Ok(workspace)
 }
 
-// ── Data / persistence commands ───────────────────────────────────────────────
+// ── Master key ────────────────────────────────────────────────────────────────
 
-/// Generic key-value persistence (used for frontend settings).
+/// Event carrying a `RotationProgress` per re-encrypted field.
+pub const MASTER_KEY_ROTATION_EVENT: &str = "master-key-rotation";
+
+/// Re-encrypt the open workspace under `new_master_key` (`None` removes
+/// encryption) and return its new recovery codes.  The workspace's
+/// `MANIFOLD_MASTER_KEY…` variable, if set, must be changed to match before
+/// the next start.
 #[tauri::command]
-pub fn save_data(app: tauri::AppHandle, key: String, data: String) -> Result<()> {
-    use crate::error::ManifoldError;
-    use tauri::Manager;
+pub async fn rotate_master_key(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    new_master_key: Option<String>,
+) -> Result<Vec<String>> {
+    use tauri::Emitter;
 
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| ManifoldError::Other(e.to_string()))?;
+    if new_master_key.as_deref() == Some("") {
+        return Err(ManifoldError::InvalidArg(
+            "the new master key must not be empty".into(),
+        ));
+    }
+    let db = state.db.clone();
+    let key = new_master_key.clone();
+    let codes = tauri::async_runtime::spawn_blocking(move || {
+        crate::master_key::rotate(&db, key.as_deref(), |progress| {
+            app.emit(MASTER_KEY_ROTATION_EVENT, progress).ok();
+        })
+    })
+    .await
+    .map_err(|e| ManifoldError::Other(format!("key rotation failed: {e}")))??;
+    *state.master_key.lock().unwrap() = new_master_key;
+    Ok(codes)
+}
 
-    std::fs::create_dir_all(&dir)?;
-    let file = dir.join(format!("{key}.json"));
-    std::fs::write(&file, data)?;
-    Ok(())
+#[tauri::command]
+pub fn get_recovery_status(state: State<'_, AppState>) -> Result<RecoveryStatus> {
+    crate::master_key::status(&state.db)
 }
 
+/// Replace the open workspace's recovery codes, e.g. after losing them.
 #[tauri::command]
-pub fn load_data(app: tauri::AppHandle, key: String) -> Result<String> {
-    use crate::error::ManifoldError;
-    use tauri::Manager;
+pub fn regenerate_recovery_codes(state: State<'_, AppState>) -> Result<Vec<String>> {
+    crate::master_key::regenerate(&state.db)
+}
 
-    let dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| ManifoldError::Other(e.to_string()))?;
+/// Recovery codes issued when a workspace's master key was set up, once.
+#[tauri::command]
+pub fn take_setup_recovery_codes() -> Option<Vec<String>> {
+    crate::master_key::take_unshown()
+}
 
-    let file = dir.join(format!("{key}.json"));
-    std::fs::read_to_string(&file)
-        .map_err(|e| ManifoldError::Other(format!("cannot read {key}: {e}")))
+/// Set a new master key on workspace `name` with one of its recovery codes
+/// and return its new codes; `switch_workspace` then opens it with the new
+/// key.  The open workspace's key is changed with `rotate_master_key`.
+#[tauri::command]
+pub async fn recover_workspace(
+    state: State<'_, AppState>,
+    name: String,
+    code: String,
+    new_master_key: String,
+) -> Result<Vec<String>> {
+    if state.workspace.lock().unwrap().name == name {
+        return Err(ManifoldError::InvalidArg(
+            "the open workspace's key is changed with rotate_master_key".into(),
+        ));
+    }
+    let workspace = WorkspaceRegistry::load_default()?.get(&name)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let db = Db::open(&workspace.db_path, None)?;
+        crate::master_key::recover(&db, &code, &new_master_key)
+    })
+    .await
+    .map_err(|e| ManifoldError::Other(format!("recovery failed: {e}")))?
 }
 
 // ── App info ──────────────────────────────────────────────────────────────────
@@ -732,6 +4148,7 @@ pub fn load_data(app: tauri::AppHandle, key: String) -> Result<String> {
 pub struct AppInfo {
     pub version: &'static str,
     pub bridge_url: String,
+    pub workspace: String,
     pub db_path: String,
     pub data_dir: String,
 }
@@ -740,17 +4157,80 @@ pub struct AppInfo {
 pub fn get_app_info(app: tauri::AppHandle, state: State<'_, AppState>) -> AppInfo {
     use tauri::Manager;
     let port = *state.bridge_port.lock().unwrap();
-    let db_path = crate::db::default_db_path();
+    let workspace = state.workspace.lock().unwrap().clone();
     let data_dir = app.path().app_data_dir().unwrap_or_else(|_| ".".into());
 
     AppInfo {
         version: env!("CARGO_PKG_VERSION"),
         bridge_url: format!("ws://localhost:{port}"),
-        db_path: db_path.to_string_lossy().into_owned(),
+        db_path: workspace.db_path.to_string_lossy().into_owned(),
+        workspace: workspace.name,
         data_dir: data_dir.to_string_lossy().into_owned(),
     }
 }
 
+// ── Host audit ────────────────────────────────────────────────────────────────
+
+/// Emitted when the startup audit finds host conditions that leak through.
+pub const HOST_AUDIT_EVENT: &str = "host-audit";
+
+/// Audit the host against every profile and keep the result.
+fn run_host_audit(app: &tauri::AppHandle) -> Result<HostAudit> {
+    use tauri::Manager;
+    let state = app.state::<AppState>();
+    let scale = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|m| m.scale_factor());
+    let host = crate::host_audit::detect(scale);
+    let profiles = state.profiles.lock().unwrap().list()?;
+    let audit = crate::host_audit::audit(&host, &profiles);
+    *state.host_audit.lock().unwrap() = Some(audit.clone());
+    Ok(audit)
+}
+
+/// Run at startup: log what leaks through and tell the UI.
+pub fn startup_host_audit(app: &tauri::AppHandle) {
+    use tauri::Emitter;
+    let audit = match run_host_audit(app) {
+        Ok(audit) => audit,
+        Err(e) => {
+            eprintln!("[host] audit failed: {e}");
+            return;
+        }
+    };
+    if audit.findings.is_empty() {
+        return;
+    }
+    let counts = crate::host_audit::conflict_counts(&audit);
+    for finding in &audit.findings {
+        let profiles = counts.get(&finding.condition).copied().unwrap_or(0);
+        eprintln!(
+            "[host] {} — {profiles} profile(s) conflict",
+            finding.message
+        );
+    }
+    app.emit(HOST_AUDIT_EVENT, &audit).ok();
+}
+
+/// The host conditions known to leak through the bridge and the profiles
+/// they contradict; `refresh` audits again instead of returning the
+/// startup result.
+#[tauri::command]
+pub fn get_host_audit(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<HostAudit> {
+    if !refresh.unwrap_or(false) {
+        if let Some(audit) = state.host_audit.lock().unwrap().clone() {
+            return Ok(audit);
+        }
+    }
+    run_host_audit(&app)
+}
+
 // ── First-run bootstrap commands ───────────────────────────────────────────────
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -781,7 +4261,7 @@ fn run_bootstrap_command(workspace: &Path, cmd: &str, args: &[&str]) -> Result<S
         .args(args)
         .current_dir(workspace)
         .output()
-        .map_err(|e| ManifoldError::Other(format!("failed to run {cmd}: {e}")))?;
+        .map_err(|e| ManifoldError::spawn(cmd, e))?;
 
     let mut text = String::new();
     text.push_str(&String::from_utf8_lossy(&output.stdout));
@@ -938,18 +4418,18 @@ pub async fn analyze_url(
         .timeout(std::time::Duration::from_millis(scrape_timeout.min(30000)))
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
         .build()
-        .map_err(|e| ManifoldError::Other(format!("Failed to create HTTP client: {}", e)))?;
+        .context("failed to create HTTP client")?;
 
     let response = client
         .get(&url)
         .send()
         .await
-        .map_err(|e| ManifoldError::Other(format!("Failed to fetch URL: {}", e)))?;
+        .context("failed to fetch URL")?;
 
     let html = response
         .text()
         .await
-        .map_err(|e| ManifoldError::Other(format!("Failed to read response body: {}", e)))?;
+        .context("failed to read response body")?;
 
     // Parse HTML
     let document = scraper::Html::parse_document(&html);
@@ -1382,43 +4862,158 @@ fn detect_mfa_indicators(html: &str) -> bool {
 
 // ── Geo consistency commands ──────────────────────────────────────────────────
 
-use crate::geo_validator::{AutoCorrectResult, GeoValidator, GeoViolation};
+use crate::clock_guard::{ClockTolerance, HostClock, CLOCK_TOLERANCE_KEY, HOST_CLOCK_KEY};
+use crate::geo_validator::{
+    AutoCorrectResult, GeoOptions, GeoValidator, GeoViolation, HostFacts, VERSION_AGE_CODES,
+};
+use crate::gpu_probe::{HostGpu, HOST_GPU_KEY};
+use crate::group_run::{GroupRunOptions, GroupRunReport};
 
 /// Validate a profile's fingerprint for geo-consistency against a proxy country.
 ///
 /// Returns a list of violations (may be empty if all checks pass).
 /// `proxy_country` is an ISO-3166-1 alpha-2 code (e.g. "US"). Pass `null`/`None`
-/// to run only internal self-consistency checks.
+/// to run only internal self-consistency checks.  Once the host's GPU has been
+/// probed (`get_host_gpu`) the claimed GPU is checked against it too, and
+/// the host clock's last NTP reading (`get_host_clock`) against the clock
+/// tolerance.  Profiles pinned to a browser version get no version-age warnings.
+/// `proxy_region` defaults to the region of the profile's exit proxy when
+/// that proxy is in `proxy_country`.
 #[tauri::command]
 pub fn validate_geo_consistency(
     profile_id: String,
     proxy_country: Option<String>,
+    proxy_region: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Vec<GeoViolation>> {
     let profiles = state.profiles.lock().unwrap();
     let profile = profiles.get(&profile_id)?;
+    let proxy_region = match (proxy_region, &proxy_country) {
+        (None, Some(country)) => exit_proxy_region(&profiles, &profile, country)?,
+        (region, _) => region,
+    };
 
-    let violations = GeoValidator::validate(&profile.fingerprint, proxy_country.as_deref());
+    let host_gpu: Option<HostGpu> = crate::settings::load(&state.db, HOST_GPU_KEY)?;
+    let host_clock: Option<HostClock> = crate::settings::load(&state.db, HOST_CLOCK_KEY)?;
+    let host = HostFacts {
+        gpu: host_gpu.as_ref(),
+        clock: host_clock.as_ref(),
+        clock_tolerance: crate::settings::load(&state.db, CLOCK_TOLERANCE_KEY)?,
+    };
+    let mut violations = GeoValidator::validate_with_host(
+        &profile.fingerprint,
+        proxy_country.as_deref(),
+        proxy_region.as_deref(),
+        &host,
+    );
+    if profile.pinned_browser_version.is_some() {
+        violations.retain(|v| !VERSION_AGE_CODES.contains(&v.code.as_str()));
+    }
 
     Ok(violations)
 }
 
+/// The region of the profile's exit proxy, when that proxy is in `country`.
+fn exit_proxy_region(
+    profiles: &ProfileRepo,
+    profile: &Profile,
+    country: &str,
+) -> Result<Option<String>> {
+    let exit_proxy = profile.proxy_chain.last().or(profile.proxy_id.as_ref());
+    Ok(profiles
+        .proxy_geo(exit_proxy.map(String::as_str))?
+        .filter(|(proxy_country, _)| proxy_country.eq_ignore_ascii_case(country))
+        .and_then(|(_, region)| region))
+}
+
+/// Canonical locales, timezones and Accept-Language chains for a proxy
+/// country, for the fingerprint editor's pickers.
+#[tauri::command]
+pub fn list_geo_options(country: String) -> GeoOptions {
+    crate::geo_validator::geo_options(&country)
+}
+
+/// The host's GPU as recorded by the last probe; probes it first when it
+/// never was or `refresh` is set.
+#[tauri::command]
+pub async fn get_host_gpu(state: State<'_, AppState>, refresh: Option<bool>) -> Result<HostGpu> {
+    let db = state.db.clone();
+    if !refresh.unwrap_or(false) {
+        if let Some(recorded) = crate::settings::load::<Option<HostGpu>>(&db, HOST_GPU_KEY)? {
+            return Ok(recorded);
+        }
+    }
+    // The OS tools can take a few seconds
+    let gpu = tauri::async_runtime::spawn_blocking(crate::gpu_probe::probe)
+        .await
+        .map_err(|e| ManifoldError::Other(format!("GPU probe failed: {e}")))?;
+    crate::settings::store(&db, HOST_GPU_KEY, &Some(&gpu))?;
+    Ok(gpu)
+}
+
+/// The host clock's offset from NTP as last measured; measures it first when
+/// it never was, the reading is stale or `refresh` is set.
+#[tauri::command]
+pub async fn get_host_clock(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<HostClock> {
+    let db = state.db.clone();
+    if !refresh.unwrap_or(false) {
+        if let Some(recorded) = crate::settings::load::<Option<HostClock>>(&db, HOST_CLOCK_KEY)? {
+            if !recorded.is_stale() {
+                return Ok(recorded);
+            }
+        }
+    }
+    // Up to a few seconds per NTP server that doesn't answer
+    let clock = tauri::async_runtime::spawn_blocking(crate::clock_guard::measure_skew)
+        .await
+        .map_err(|e| ManifoldError::Other(format!("clock check failed: {e}")))??;
+    crate::settings::store(&db, HOST_CLOCK_KEY, &Some(&clock))?;
+    Ok(clock)
+}
+
+#[tauri::command]
+pub fn get_clock_tolerance(state: State<'_, AppState>) -> Result<ClockTolerance> {
+    crate::settings::load(&state.db, CLOCK_TOLERANCE_KEY)
+}
+
+/// Set how far the host clock may be off before geo validation warns
+/// (`soft_ms`) or fails (`hard_ms`).
+#[tauri::command]
+pub fn set_clock_tolerance(state: State<'_, AppState>, tolerance: ClockTolerance) -> Result<()> {
+    tolerance.validate()?;
+    crate::settings::store(&state.db, CLOCK_TOLERANCE_KEY, &tolerance)
+}
+
 /// Auto-correct a profile's fingerprint for geo-consistency and persist the result.
 ///
-/// Calls `enforce_geo()` for locale/timezone alignment, then applies screen-
-/// resolution and DPR fixes.  Returns a summary of what was fixed and any
-/// residual violations that require manual intervention.
+/// Calls `enforce_geo_region()` for locale/timezone alignment, then applies
+/// screen-resolution and DPR fixes.  Returns a summary of what was fixed and
+/// any residual violations that require manual intervention.  `proxy_region`
+/// defaults as in `validate_geo_consistency`.
 #[tauri::command]
 pub fn auto_correct_geo(
     profile_id: String,
     proxy_country: String,
+    proxy_region: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<AutoCorrectResult> {
     let profiles = state.profiles.lock().unwrap();
     let mut profile = profiles.get(&profile_id)?;
+    let proxy_region = match proxy_region {
+        Some(region) => Some(region),
+        None => exit_proxy_region(&profiles, &profile, &proxy_country)?,
+    };
 
     let seed = profile.fingerprint.seed;
-    let result = GeoValidator::auto_correct(&mut profile.fingerprint, &proxy_country, seed);
+    let result = GeoValidator::auto_correct(
+        &mut profile.fingerprint,
+        &proxy_country,
+        proxy_region.as_deref(),
+        seed,
+    );
 
     // Persist the corrected fingerprint
     let req = UpdateProfileRequest {
@@ -1430,22 +5025,208 @@ pub fn auto_correct_geo(
         tags: None,
         behavior_profile: None,
         tls_bridge: None,
+        persona: None,
     };
     profiles.update(&profile_id, req)?;
 
     Ok(result)
 }
 
+/// Store a device capture from the collection page
+/// (static/collect-fingerprint.html) as a real-device reference.
+#[tauri::command]
+pub fn ingest_reference_fingerprint(
+    state: State<'_, AppState>,
+    label: Option<String>,
+    data: serde_json::Value,
+) -> Result<ReferenceFingerprint> {
+    ReferenceRepo::new(state.db.clone()).ingest(label, data)
+}
+
+#[tauri::command]
+pub fn list_reference_fingerprints(
+    state: State<'_, AppState>,
+) -> Result<Vec<ReferenceFingerprint>> {
+    ReferenceRepo::new(state.db.clone()).list()
+}
+
+#[tauri::command]
+pub fn delete_reference_fingerprint(state: State<'_, AppState>, id: String) -> Result<()> {
+    ReferenceRepo::new(state.db.clone()).delete(&id)
+}
+
+/// Create a profile cloning the device of a reference fingerprint, with the
+/// values the capture lacks generated from a seed.
+#[tauri::command]
+pub fn create_profile_from_capture(
+    state: State<'_, AppState>,
+    request: CaptureProfileRequest,
+) -> Result<Profile> {
+    let reference = ReferenceRepo::new(state.db.clone()).get(&request.reference_id)?;
+    let profiles = state.profiles.lock().unwrap();
+    crate::reference_corpus::create_from_capture(&profiles, &reference, request)
+}
+
+/// How a profile's device values compare with the real-device references
+/// of its platform.
+#[tauri::command]
+pub fn compare_to_references(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<ReferenceComparison> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let corpus = ReferenceRepo::new(state.db.clone()).list()?;
+    Ok(crate::reference_corpus::compare(
+        &profile.fingerprint,
+        &corpus,
+    ))
+}
+
+/// Switch a profile's UA string to the reduced format real Chrome sends
+/// (`Chrome/<major>.0.0.0`), keeping the full version for the high-entropy
+/// client hints only, or back.
+#[tauri::command]
+pub fn set_profile_ua_reduction(
+    state: State<'_, AppState>,
+    id: String,
+    enabled: bool,
+) -> Result<Profile> {
+    let profiles = state.profiles.lock().unwrap();
+    let mut fingerprint = profiles.get(&id)?.fingerprint;
+    FingerprintOrchestrator::set_ua_reduction(&mut fingerprint, enabled);
+    profiles.update(
+        &id,
+        UpdateProfileRequest {
+            name: None,
+            fingerprint: Some(fingerprint),
+            human: None,
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            tls_bridge: None,
+            persona: None,
+        },
+    )
+}
+
+/// Turn per-session window size variation on or off for a profile.
+#[tauri::command]
+pub fn set_profile_window_variation(
+    state: State<'_, AppState>,
+    id: String,
+    enabled: bool,
+) -> Result<Profile> {
+    let profiles = state.profiles.lock().unwrap();
+    let mut fingerprint = profiles.get(&id)?.fingerprint;
+    fingerprint.window_variation = enabled;
+    profiles.update(
+        &id,
+        UpdateProfileRequest {
+            name: None,
+            fingerprint: Some(fingerprint),
+            human: None,
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            tls_bridge: None,
+            persona: None,
+        },
+    )
+}
+
+/// The permission presets and the state each sets, for the profile editor.
+#[tauri::command]
+pub fn get_permission_presets() -> Vec<PresetStates> {
+    crate::permissions::presets()
+}
+
+/// Set what `navigator.permissions.query()` reports for a profile: the
+/// `preset`'s states (if given), then each of `overrides` on top.
+#[tauri::command]
+pub fn set_profile_permissions(
+    state: State<'_, AppState>,
+    id: String,
+    preset: Option<PermissionPreset>,
+    overrides: Option<std::collections::HashMap<String, String>>,
+) -> Result<Profile> {
+    let profiles = state.profiles.lock().unwrap();
+    let mut fingerprint = profiles.get(&id)?.fingerprint;
+    crate::permissions::apply(&mut fingerprint, preset, &overrides.unwrap_or_default())?;
+    profiles.update(
+        &id,
+        UpdateProfileRequest {
+            name: None,
+            fingerprint: Some(fingerprint),
+            human: None,
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            tls_bridge: None,
+            persona: None,
+        },
+    )
+}
+
+/// The JA4 / JA4H strings the profile's traffic will show through the TLS
+/// bridge, to compare with target-side logs without a packet capture.
+#[tauri::command]
+pub fn compute_expected_ja4(state: State<'_, AppState>, profile_id: String) -> Result<ExpectedJa4> {
+    let profile = state.profiles.lock().unwrap().get(&profile_id)?;
+    let bridge_enabled = profile.tls_bridge.unwrap_or(false);
+    Ok(crate::tls_bridge::expected_ja4(
+        &profile.fingerprint,
+        bridge_enabled,
+    )?)
+}
+
 /// Launch TLS bridge server for JA4 fingerprinting control.
+/// With `profile_id`, outbound connections go through that profile's proxy,
+/// the ClientHello matches its fingerprint's Chrome version and the bytes
+/// exchanged are charged to the profile's bandwidth.
 /// Returns the port the bridge is listening on.
 #[tauri::command]
-pub async fn launch_tls_bridge(state: State<'_, AppState>, seed: u64) -> Result<u16> {
-    use crate::tls_bridge::TlsBridge;
+pub async fn launch_tls_bridge(
+    state: State<'_, AppState>,
+    seed: u64,
+    profile_id: Option<String>,
+) -> Result<u16> {
+    use crate::tls_bridge::{TlsBridge, TlsBridgeConfig, TlsProfile};
+
+    let mut tls = TlsProfile::default();
+    let usage = profile_id
+        .as_ref()
+        .map(|id| UsageMeter::new(state.db.clone(), id.as_str()));
+    let upstream = match profile_id {
+        Some(id) => {
+            let profile = state.profiles.lock().unwrap().get(&id)?;
+            tls = TlsProfile::for_user_agent(&profile.fingerprint.user_agent);
+            if !profile.proxy_chain.is_empty() {
+                return Err(ManifoldError::InvalidArg(
+                    "the TLS bridge can't route through a proxy chain".into(),
+                ));
+            }
+            match &profile.proxy_id {
+                Some(pid) => {
+                    let proxy = state.proxies.lock().unwrap().get(pid)?;
+                    Some(state.ssh_tunnels.resolve(&proxy)?)
+                }
+                None => None,
+            }
+        }
+        None => None,
+    };
 
     let port = *state.tls_bridge_port.lock().unwrap();
-    let bridge = TlsBridge::new(port, seed)
+    let config = TlsBridgeConfig::new(seed)
+        .with_upstream(upstream)
+        .with_tls_profile(tls)
+        .with_usage_meter(usage);
+    let bridge = TlsBridge::with_config(port, config)
         .await
-        .map_err(|e| ManifoldError::Other(format!("Failed to start TLS bridge: {}", e)))?;
+        .context("failed to start TLS bridge")?;
 
     // Start the bridge in background (we don't wait for it)
     tokio::spawn(async move {
@@ -1851,6 +5632,27 @@ mod tests {
         assert!(selector.is_none());
     }
 
+    // ── Launch Preview Tests ──────────────────────────────────────────────
+
+    #[test]
+    fn test_redact_launch_config() {
+        let mut config = serde_json::json!({
+            "profile": { "launch_env": { "API_TOKEN": "s3cret" } },
+            "proxy": { "server": "http://1.2.3.4:8080", "username": "u", "password": "p" },
+            "wsSecret": "ab12",
+        });
+        redact_launch_config(&mut config);
+        assert_eq!(config["wsSecret"], REDACTED);
+        assert_eq!(config["proxy"]["password"], REDACTED);
+        assert_eq!(config["proxy"]["username"], "u");
+        assert_eq!(config["profile"]["launch_env"]["API_TOKEN"], REDACTED);
+
+        // No password stays null rather than looking like one was set
+        let mut direct = serde_json::json!({ "proxy": { "password": null } });
+        redact_launch_config(&mut direct);
+        assert!(direct["proxy"]["password"].is_null());
+    }
+
     // ── Integration Tests ─────────────────────────────────────────────────
     #[test]
     fn test_detect_form_fields_complete_form() {
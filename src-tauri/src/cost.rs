@@ -0,0 +1,612 @@
+// ── Manifold cost tracking ────────────────────────────────────────────────────
+//
+// What running the profiles costs, from the prices the user enters:
+//   * proxies carry an optional price per GB, multiplied by the bandwidth
+//     accounted to each profile (bandwidth.rs), and an optional price per IP
+//     for 30 days, prorated to the period and split evenly between the
+//     profiles assigned to the proxy;
+//   * captcha providers, listed in the `costs` settings, carry a price per
+//     solve, multiplied by the `captcha_solve` events recorded per profile.
+//
+// Reports group the costs per profile and per tag, the way profiles are
+// grouped in the app.  A profile with several tags counts towards each of
+// them, so group totals can add up to more than the overall total.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDate, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+/// `settings` key holding `CostSettings` (JSON).
+pub const COST_SETTINGS_KEY: &str = "costs";
+
+/// Proxy bandwidth is billed in decimal gigabytes.
+const BYTES_PER_GB: f64 = 1_000_000_000.0;
+
+/// Days a price per IP pays for.
+const IP_PRICE_DAYS: f64 = 30.0;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaptchaProvider {
+    pub name: String,
+    pub price_per_solve: f64,
+}
+
+/// App-wide cost settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CostSettings {
+    /// Shown next to amounts; every price is taken to be in it.
+    pub currency: String,
+    pub captcha_providers: Vec<CaptchaProvider>,
+}
+
+impl Default for CostSettings {
+    fn default() -> Self {
+        Self {
+            currency: "USD".into(),
+            captcha_providers: Vec::new(),
+        }
+    }
+}
+
+impl CostSettings {
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for provider in &self.captcha_providers {
+            if provider.name.trim().is_empty() {
+                return Err(ManifoldError::InvalidArg(
+                    "captcha provider name is empty".into(),
+                ));
+            }
+            check_price(provider.price_per_solve)?;
+            if !seen.insert(provider.name.as_str()) {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "captcha provider {:?} is listed twice",
+                    provider.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn captcha_price(&self, provider: &str) -> Option<f64> {
+        self.captcha_providers
+            .iter()
+            .find(|p| p.name == provider)
+            .map(|p| p.price_per_solve)
+    }
+}
+
+/// A proxy's prices; `None` where the plan doesn't charge that way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProxyCost {
+    pub price_per_gb: Option<f64>,
+    /// For 30 days of the IP.
+    pub price_per_ip: Option<f64>,
+}
+
+impl ProxyCost {
+    pub fn validate(&self) -> Result<()> {
+        self.price_per_gb.map(check_price).transpose()?;
+        self.price_per_ip.map(check_price).transpose()?;
+        Ok(())
+    }
+}
+
+fn check_price(price: f64) -> Result<()> {
+    if price.is_finite() && price >= 0.0 {
+        Ok(())
+    } else {
+        Err(ManifoldError::InvalidArg(format!(
+            "price must be zero or more, got {price}"
+        )))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostPeriod {
+    /// Today (UTC).
+    Day,
+    /// The last 7 days, today included.
+    Week,
+    /// The last 30 days, today included.
+    Month,
+}
+
+impl CostPeriod {
+    pub fn days(self) -> u32 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+}
+
+/// Amounts by what they pay for.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Costs {
+    pub bandwidth: f64,
+    pub ips: f64,
+    pub captchas: f64,
+    pub total: f64,
+}
+
+impl Costs {
+    fn add(&mut self, other: &Costs) {
+        self.bandwidth += other.bandwidth;
+        self.ips += other.ips;
+        self.captchas += other.captchas;
+        self.total += other.total;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileCost {
+    pub profile_id: String,
+    /// `None` when the profile was deleted since.
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub bytes: u64,
+    pub captcha_solves: u32,
+    pub costs: Costs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupCost {
+    /// `None` for untagged profiles.
+    pub tag: Option<String>,
+    pub profiles: u32,
+    pub costs: Costs,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CostReport {
+    pub period: CostPeriod,
+    pub since: NaiveDate,
+    pub currency: String,
+    /// Everything, `unassigned_ips` included.
+    pub costs: Costs,
+    /// IP cost of priced proxies no profile is assigned to.
+    pub unassigned_ips: f64,
+    /// Most expensive first.
+    pub by_profile: Vec<ProfileCost>,
+    /// Most expensive first.
+    pub by_group: Vec<GroupCost>,
+    /// Names of proxies that carried traffic in the period without a price
+    /// per GB: their bandwidth is counted as free.
+    pub unpriced_proxies: Vec<String>,
+    /// Captcha providers with solves in the period but no price.
+    pub unpriced_providers: Vec<String>,
+}
+
+struct ProxyPrices {
+    name: String,
+    cost: ProxyCost,
+    assigned: Vec<String>,
+}
+
+/// The profile's row, created for profiles deleted since they were used.
+fn entry<'a>(by_id: &'a mut BTreeMap<String, ProfileCost>, id: &str) -> &'a mut ProfileCost {
+    by_id.entry(id.to_string()).or_insert_with(|| ProfileCost {
+        profile_id: id.to_string(),
+        name: None,
+        tags: Vec::new(),
+        bytes: 0,
+        captcha_solves: 0,
+        costs: Costs::default(),
+    })
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct CostRepo {
+    db: Db,
+}
+
+impl CostRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn settings(&self) -> Result<CostSettings> {
+        crate::settings::load(&self.db, COST_SETTINGS_KEY)
+    }
+
+    pub fn proxy_cost(&self, proxy_id: &str) -> Result<ProxyCost> {
+        self.db.with_conn(|conn| {
+            conn.query_row(
+                "SELECT price_per_gb, price_per_ip FROM proxies WHERE id = ?1",
+                params![proxy_id],
+                |r| {
+                    Ok(ProxyCost {
+                        price_per_gb: r.get(0)?,
+                        price_per_ip: r.get(1)?,
+                    })
+                },
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    ManifoldError::ProxyNotFound(proxy_id.into())
+                }
+                e => e.into(),
+            })
+        })
+    }
+
+    pub fn set_proxy_cost(&self, proxy_id: &str, cost: &ProxyCost) -> Result<()> {
+        cost.validate()?;
+        self.db.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE proxies SET price_per_gb = ?1, price_per_ip = ?2 WHERE id = ?3",
+                params![cost.price_per_gb, cost.price_per_ip, proxy_id],
+            )?;
+            if n == 0 {
+                return Err(ManifoldError::ProxyNotFound(proxy_id.into()));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn report(&self, period: CostPeriod) -> Result<CostReport> {
+        let settings = self.settings()?;
+        let days = period.days();
+        let since = Utc::now().date_naive() - Duration::days(i64::from(days) - 1);
+        let since_text = since.to_string();
+
+        let (profiles, proxies, usage, solves) = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT id, name, tags, proxy_id FROM profiles")?;
+            let profiles = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, String>(2)?,
+                        r.get::<_, Option<String>>(3)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt =
+                conn.prepare("SELECT id, name, price_per_gb, price_per_ip FROM proxies")?;
+            let proxies = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        ProxyCost {
+                            price_per_gb: r.get(2)?,
+                            price_per_ip: r.get(3)?,
+                        },
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(
+                r#"SELECT profile_id, proxy_id, SUM(bytes_up + bytes_down)
+                   FROM bandwidth_usage WHERE day >= ?1
+                   GROUP BY profile_id, proxy_id"#,
+            )?;
+            let usage = stmt
+                .query_map(params![since_text], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, i64>(2)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut stmt = conn.prepare(
+                r#"SELECT profile_id, json_extract(detail, '$.provider'), COUNT(*)
+                   FROM events
+                   WHERE kind = 'captcha_solve' AND profile_id IS NOT NULL
+                     AND created_at >= ?1
+                   GROUP BY 1, 2"#,
+            )?;
+            let solves = stmt
+                .query_map(params![format!("{since_text}T00:00:00")], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, Option<String>>(1)?,
+                        r.get::<_, u32>(2)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok((profiles, proxies, usage, solves))
+        })?;
+
+        let mut by_id: BTreeMap<String, ProfileCost> = profiles
+            .iter()
+            .map(|(id, name, tags, _)| {
+                let cost = ProfileCost {
+                    profile_id: id.clone(),
+                    name: Some(name.clone()),
+                    tags: serde_json::from_str(tags).unwrap_or_default(),
+                    bytes: 0,
+                    captcha_solves: 0,
+                    costs: Costs::default(),
+                };
+                (id.clone(), cost)
+            })
+            .collect();
+        let prices: HashMap<String, ProxyPrices> = proxies
+            .into_iter()
+            .map(|(id, name, cost)| {
+                let assigned = profiles
+                    .iter()
+                    .filter(|p| p.3.as_deref() == Some(id.as_str()))
+                    .map(|p| p.0.clone())
+                    .collect();
+                (
+                    id,
+                    ProxyPrices {
+                        name,
+                        cost,
+                        assigned,
+                    },
+                )
+            })
+            .collect();
+
+        let mut unpriced_proxies = Vec::new();
+        for (profile_id, proxy_id, bytes) in usage {
+            let bytes = bytes.max(0) as u64;
+            let per_gb = match prices.get(&proxy_id) {
+                Some(p) if p.cost.price_per_gb.is_none() => {
+                    if !unpriced_proxies.contains(&p.name) {
+                        unpriced_proxies.push(p.name.clone());
+                    }
+                    None
+                }
+                Some(p) => p.cost.price_per_gb,
+                None => None,
+            };
+            let profile = entry(&mut by_id, &profile_id);
+            profile.bytes += bytes;
+            profile.costs.bandwidth += bytes as f64 / BYTES_PER_GB * per_gb.unwrap_or(0.0);
+        }
+
+        let mut unassigned_ips = 0.0;
+        for price in prices.values() {
+            let Some(per_ip) = price.cost.price_per_ip else {
+                continue;
+            };
+            let amount = per_ip * f64::from(days) / IP_PRICE_DAYS;
+            if price.assigned.is_empty() {
+                unassigned_ips += amount;
+                continue;
+            }
+            let share = amount / price.assigned.len() as f64;
+            for id in &price.assigned {
+                entry(&mut by_id, id).costs.ips += share;
+            }
+        }
+
+        let mut unpriced_providers = Vec::new();
+        for (profile_id, provider, count) in solves {
+            let provider = provider.unwrap_or_default();
+            let per_solve = settings.captcha_price(&provider);
+            if per_solve.is_none() && !unpriced_providers.contains(&provider) {
+                unpriced_providers.push(provider);
+            }
+            let profile = entry(&mut by_id, &profile_id);
+            profile.captcha_solves += count;
+            profile.costs.captchas += f64::from(count) * per_solve.unwrap_or(0.0);
+        }
+
+        let mut costs = Costs {
+            ips: unassigned_ips,
+            total: unassigned_ips,
+            ..Costs::default()
+        };
+        let mut groups: BTreeMap<Option<String>, GroupCost> = BTreeMap::new();
+        let mut by_profile: Vec<ProfileCost> = by_id
+            .into_values()
+            .map(|mut p| {
+                p.costs.total = p.costs.bandwidth + p.costs.ips + p.costs.captchas;
+                p
+            })
+            .filter(|p| p.costs.total > 0.0 || p.bytes > 0 || p.captcha_solves > 0)
+            .collect();
+        for profile in &by_profile {
+            costs.add(&profile.costs);
+            let tags: Vec<Option<String>> = if profile.tags.is_empty() {
+                vec![None]
+            } else {
+                profile.tags.iter().cloned().map(Some).collect()
+            };
+            for tag in tags {
+                let group = groups.entry(tag.clone()).or_insert_with(|| GroupCost {
+                    tag,
+                    profiles: 0,
+                    costs: Costs::default(),
+                });
+                group.profiles += 1;
+                group.costs.add(&profile.costs);
+            }
+        }
+        let by_total = |a: &Costs, b: &Costs| b.total.total_cmp(&a.total);
+        by_profile.sort_by(|a, b| by_total(&a.costs, &b.costs));
+        let mut by_group: Vec<GroupCost> = groups.into_values().collect();
+        by_group.sort_by(|a, b| by_total(&a.costs, &b.costs));
+        unpriced_proxies.sort();
+        unpriced_providers.sort();
+
+        Ok(CostReport {
+            period,
+            since,
+            currency: settings.currency,
+            costs,
+            unassigned_ips,
+            by_profile,
+            by_group,
+            unpriced_proxies,
+            unpriced_providers,
+        })
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bandwidth::{BandwidthRepo, UsageSample};
+    use crate::events::{EventKind, EventRepo, NewEvent};
+
+    fn make_repo() -> CostRepo {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            for (id, name) in [("x1", "resi"), ("x2", "dc"), ("x3", "spare")] {
+                conn.execute(
+                    "INSERT INTO proxies (id, name, proxy_type, host, port) VALUES (?1, ?2, 'http', 'h', 1)",
+                    params![id, name],
+                )?;
+            }
+            for (id, tags, proxy) in [
+                ("p1", r#"["shop"]"#, "x1"),
+                ("p2", r#"["shop","ads"]"#, "x1"),
+                ("p3", "[]", "x2"),
+            ] {
+                conn.execute(
+                    "INSERT INTO profiles (id, name, fingerprint_json, tags, proxy_id, created_at) VALUES (?1, ?1, '{}', ?2, ?3, '2025-01-01T00:00:00+00:00')",
+                    params![id, tags, proxy],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        CostRepo::new(db)
+    }
+
+    #[test]
+    fn usage_ips_and_solves_are_priced_per_profile_and_tag() {
+        let repo = make_repo();
+        repo.set_proxy_cost(
+            "x1",
+            &ProxyCost {
+                price_per_gb: Some(4.0),
+                price_per_ip: Some(3.0),
+            },
+        )
+        .unwrap();
+        repo.set_proxy_cost(
+            "x3",
+            &ProxyCost {
+                price_per_gb: None,
+                price_per_ip: Some(30.0),
+            },
+        )
+        .unwrap();
+        crate::settings::store(
+            &repo.db,
+            COST_SETTINGS_KEY,
+            &CostSettings {
+                currency: "EUR".into(),
+                captcha_providers: vec![CaptchaProvider {
+                    name: "2captcha".into(),
+                    price_per_solve: 0.003,
+                }],
+            },
+        )
+        .unwrap();
+
+        let bandwidth = BandwidthRepo::new(repo.db.clone());
+        let gb = 1_000_000_000;
+        bandwidth
+            .record(
+                "p1",
+                UsageSample {
+                    up: 0,
+                    down: 2 * gb,
+                },
+            )
+            .unwrap();
+        bandwidth
+            .record("p3", UsageSample { up: 0, down: gb })
+            .unwrap();
+        let events = EventRepo::new(repo.db.clone());
+        for provider in ["2captcha", "2captcha", "other"] {
+            events
+                .record(NewEvent {
+                    profile_id: Some("p3".into()),
+                    kind: EventKind::CaptchaSolve,
+                    severity: None,
+                    domain: None,
+                    detail: Some(serde_json::json!({ "provider": provider })),
+                })
+                .unwrap();
+        }
+
+        let report = repo.report(CostPeriod::Day).unwrap();
+        assert_eq!(report.currency, "EUR");
+        let p1 = report
+            .by_profile
+            .iter()
+            .find(|p| p.profile_id == "p1")
+            .unwrap();
+        assert!((p1.costs.bandwidth - 8.0).abs() < 1e-9);
+        // 3.0 per 30 days, one day, split between p1 and p2
+        assert!((p1.costs.ips - 0.05).abs() < 1e-9);
+        let p3 = report
+            .by_profile
+            .iter()
+            .find(|p| p.profile_id == "p3")
+            .unwrap();
+        assert_eq!(p3.captcha_solves, 3);
+        assert!((p3.costs.captchas - 0.006).abs() < 1e-9);
+        assert_eq!(p3.costs.bandwidth, 0.0);
+        assert_eq!(report.unpriced_proxies, ["dc"]);
+        assert_eq!(report.unpriced_providers, ["other"]);
+        assert!((report.unassigned_ips - 1.0).abs() < 1e-9);
+        assert!((report.costs.total - (8.0 + 0.1 + 1.0 + 0.006)).abs() < 1e-9);
+
+        let shop = report
+            .by_group
+            .iter()
+            .find(|g| g.tag.as_deref() == Some("shop"))
+            .unwrap();
+        assert_eq!(shop.profiles, 2);
+        assert!((shop.costs.total - 8.1).abs() < 1e-9);
+        assert!(report.by_group.iter().any(|g| g.tag.is_none()));
+    }
+
+    #[test]
+    fn invalid_prices_are_rejected() {
+        let repo = make_repo();
+        let negative = ProxyCost {
+            price_per_gb: Some(-1.0),
+            price_per_ip: None,
+        };
+        assert!(repo.set_proxy_cost("x1", &negative).is_err());
+        assert!(matches!(
+            repo.set_proxy_cost("nope", &ProxyCost::default()),
+            Err(ManifoldError::ProxyNotFound(_))
+        ));
+        let twice = CostSettings {
+            captcha_providers: vec![
+                CaptchaProvider {
+                    name: "a".into(),
+                    price_per_solve: 1.0,
+                },
+                CaptchaProvider {
+                    name: "a".into(),
+                    price_per_solve: 2.0,
+                },
+            ],
+            ..CostSettings::default()
+        };
+        assert!(twice.validate().is_err());
+    }
+}
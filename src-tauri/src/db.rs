@@ -6,10 +6,12 @@
 //
 // Sensitive fields (proxy credentials, fingerprint seed) are additionally
 // encrypted at the application layer with AES-256-GCM + Argon2id key derivation
-// so they remain protected even in the plain-SQLite build.
+// so they remain protected even in the plain-SQLite build.  Changing the
+// master key re-encrypts them in one transaction (`Db::rekey`, driven by
+// `master_key`).
 
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 use aes_gcm::{
     aead::{Aead, KeyInit, OsRng},
@@ -24,7 +26,7 @@ use crate::error::{ManifoldError, Result};
 
 // ── Schema ────────────────────────────────────────────────────────────────────
 
-const SCHEMA_VERSION: u32 = 2;
+const SCHEMA_VERSION: u32 = 23;
 
 const SCHEMA_SQL: &str = r#"
 PRAGMA journal_mode = WAL;
@@ -46,6 +48,13 @@ CREATE TABLE IF NOT EXISTS profiles (
     created_at       TEXT    NOT NULL,
     last_used        TEXT,
     tls_bridge       INTEGER DEFAULT 0,     -- Enable TLS bridge for JA4 control (0=false, 1=true)
+    auto_age         INTEGER NOT NULL DEFAULT 1, -- Scheduled Chrome version aging (0=opt-out)
+    persona_json     TEXT,                  -- JSON blob (Persona), optional
+    proxy_chain      TEXT NOT NULL DEFAULT '[]', -- JSON array of proxy ids, first hop first
+    launch_env       TEXT NOT NULL DEFAULT '{}', -- JSON object of bridge env vars
+    disk_quota_mb    INTEGER,               -- Data dir size limit; NULL = app default
+    launch_policy    TEXT NOT NULL DEFAULT '{}', -- JSON LaunchPolicy (launch_mode.rs)
+    pinned_browser_version TEXT,           -- Chrome version held for legacy sites; NULL = none
     FOREIGN KEY (proxy_id) REFERENCES proxies(id) ON DELETE SET NULL
 );
 
@@ -58,6 +67,20 @@ CREATE TABLE IF NOT EXISTS proxies (
     username     TEXT,
     password_enc TEXT,                       -- AES-GCM encrypted, base64
     country      TEXT,
+    region       TEXT,                       -- state / province / city in country
+    region_measured INTEGER NOT NULL DEFAULT 0, -- region came from a leak test
+    ssh_key_path TEXT,                       -- private key file (ssh proxies)
+    pinned_ip    TEXT,                       -- resolved IP dialled instead of host
+    check_mode   TEXT    NOT NULL DEFAULT 'get', -- get | connect | both
+    supports_get     INTEGER,                -- NULL until a check tried GET
+    supports_connect INTEGER,                -- NULL until a check tried CONNECT
+    check_targets TEXT   NOT NULL DEFAULT '[]', -- JSON URLs; [] = app-wide list
+    throughput_kbps INTEGER,                 -- last measured download speed
+    anonymity    TEXT,                       -- transparent | anonymous | elite
+    price_per_gb REAL,                       -- cost.rs; NULL = not billed per GB
+    price_per_ip REAL,                       -- cost.rs; per 30 days
+    tags         TEXT    NOT NULL DEFAULT '[]',  -- JSON array of strings
+    notes        TEXT    NOT NULL DEFAULT '',
     healthy      INTEGER NOT NULL DEFAULT 0,
     latency_ms   INTEGER,
     last_checked TEXT
@@ -71,11 +94,185 @@ CREATE TABLE IF NOT EXISTS sessions (
     har_path    TEXT,
     trace_path  TEXT,
     entropy_log TEXT,                        -- JSON EntropyLog blob
+    clock_jumps INTEGER NOT NULL DEFAULT 0,  -- host clock jumps seen (clock_guard)
+    behavior_audit TEXT,                     -- JSON BehaviorAudit (behavior_audit)
+    launch_mode TEXT,                        -- headed | headless_new | virtual_display
     FOREIGN KEY (profile_id) REFERENCES profiles(id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS events (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_id  TEXT,                        -- NULL for app-wide events
+    kind        TEXT NOT NULL,               -- launch | stop | panic | detection
+    severity    TEXT NOT NULL DEFAULT 'info',
+    domain      TEXT,                        -- target domain (detections)
+    detail      TEXT NOT NULL DEFAULT '{}',  -- JSON payload
+    created_at  TEXT NOT NULL,
+    hash        TEXT                         -- BLAKE3 chain link (events.rs)
+);
+
+CREATE TABLE IF NOT EXISTS vpn_configs (
+    profile_id  TEXT PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+    kind        TEXT NOT NULL,               -- wireguard
+    config_enc  TEXT NOT NULL,               -- AES-GCM encrypted, base64
+    updated_at  TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key    TEXT PRIMARY KEY,
+    value  TEXT NOT NULL                     -- JSON
+);
+
+CREATE TABLE IF NOT EXISTS leak_tests (
+    profile_id  TEXT PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+    passed      INTEGER NOT NULL,
+    report      TEXT NOT NULL,               -- JSON LeakTestReport
+    ran_at      TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS status_transitions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    profile_id  TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+    from_status TEXT NOT NULL,
+    to_status   TEXT NOT NULL,
+    reason      TEXT NOT NULL,
+    allowed     INTEGER NOT NULL,           -- 0 = rejected (violation)
+    created_at  TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS proxy_domain_status (
+    proxy_id       TEXT NOT NULL REFERENCES proxies(id) ON DELETE CASCADE,
+    domain         TEXT NOT NULL,
+    blocks         INTEGER NOT NULL DEFAULT 0,
+    captchas       INTEGER NOT NULL DEFAULT 0,
+    last_hit_at    TEXT NOT NULL,
+    cooldown_until TEXT NOT NULL,
+    PRIMARY KEY (proxy_id, domain)
+);
+
+-- Profiles that must never be online together (launch_conflicts.rs);
+-- each pair is stored once, smaller id first
+CREATE TABLE IF NOT EXISTS profile_conflicts (
+    profile_a   TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+    profile_b   TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+    reason      TEXT NOT NULL DEFAULT '',
+    created_at  TEXT NOT NULL,
+    PRIMARY KEY (profile_a, profile_b)
+);
+
+-- Bytes through the network per session, proxy and UTC day (bandwidth.rs);
+-- '' stands for no session / a direct connection.  No foreign keys: usage
+-- still counts towards the bill after a profile or proxy is deleted
+CREATE TABLE IF NOT EXISTS bandwidth_usage (
+    profile_id  TEXT NOT NULL,
+    session_id  TEXT NOT NULL DEFAULT '',
+    proxy_id    TEXT NOT NULL DEFAULT '',
+    day         TEXT NOT NULL,               -- YYYY-MM-DD
+    bytes_up    INTEGER NOT NULL DEFAULT 0,
+    bytes_down  INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (profile_id, session_id, proxy_id, day)
+);
+
+-- Device captures from the collection page (reference_corpus.rs)
+CREATE TABLE IF NOT EXISTS reference_fingerprints (
+    id          TEXT PRIMARY KEY,
+    label       TEXT NOT NULL,
+    created_at  TEXT NOT NULL,
+    data        TEXT NOT NULL                -- DeviceCapture JSON
+);
+
+-- Stored workflows (workflow.rs)
+CREATE TABLE IF NOT EXISTS workflows (
+    id          TEXT PRIMARY KEY,
+    name        TEXT NOT NULL,
+    definition  TEXT NOT NULL,               -- steps and cleanup JSON
+    created_at  TEXT NOT NULL,
+    updated_at  TEXT NOT NULL
+);
+
+-- Values captured by workflow extraction steps (run_outputs.rs)
+CREATE TABLE IF NOT EXISTS run_outputs (
+    run_id      TEXT NOT NULL,
+    seq         INTEGER NOT NULL,            -- capture order within the run
+    workflow_id TEXT NOT NULL,
+    profile_id  TEXT NOT NULL,
+    key         TEXT NOT NULL,
+    step        INTEGER NOT NULL,
+    value       TEXT NOT NULL,               -- JSON
+    captured_at TEXT NOT NULL,
+    PRIMARY KEY (run_id, seq)
+);
+
+-- Per-profile secrets for workflow variables (vault.rs)
+CREATE TABLE IF NOT EXISTS profile_secrets (
+    profile_id  TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+    name        TEXT NOT NULL,
+    value       TEXT NOT NULL,               -- encrypted
+    updated_at  TEXT NOT NULL,
+    PRIMARY KEY (profile_id, name)
+);
+
+-- Workflow runs fanned out over a profile group (group_run.rs)
+CREATE TABLE IF NOT EXISTS workflow_group_runs (
+    id          TEXT PRIMARY KEY,
+    workflow_id TEXT NOT NULL,
+    group_id    TEXT NOT NULL,
+    report      TEXT NOT NULL,               -- GroupRunReport JSON
+    started_at  TEXT NOT NULL
+);
+
+-- One row per workflow run, pointing at its artifact archive (run_artifacts.rs)
+CREATE TABLE IF NOT EXISTS workflow_runs (
+    id           TEXT PRIMARY KEY,
+    workflow_id  TEXT NOT NULL,
+    profile_id   TEXT NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+    status       TEXT NOT NULL,              -- WorkflowRunStatus
+    failures     INTEGER NOT NULL DEFAULT 0,
+    archive_path TEXT NOT NULL,
+    started_at   TEXT NOT NULL,
+    finished_at  TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_workflow_runs_profile ON workflow_runs(profile_id, started_at);
+
+-- Generated form-fill identity per profile, encrypted JSON (synthetic_identity.rs)
+CREATE TABLE IF NOT EXISTS synthetic_identities (
+    profile_id  TEXT PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+    country     TEXT NOT NULL,
+    identity    TEXT NOT NULL,
+    created_at  TEXT NOT NULL
+);
+
+-- Remote hosts profiles can be launched on (nodes.rs)
+CREATE TABLE IF NOT EXISTS nodes (
+    id               TEXT PRIMARY KEY,
+    name             TEXT NOT NULL,
+    kind             TEXT NOT NULL,              -- ssh | agent
+    host             TEXT NOT NULL,
+    ssh_port         INTEGER NOT NULL DEFAULT 22,
+    ssh_user         TEXT,
+    ssh_key_path     TEXT,
+    agent_url        TEXT,
+    agent_token_enc  TEXT,                       -- encrypted
+    bridge_dir       TEXT NOT NULL,
+    bridge_command   TEXT NOT NULL,
+    ws_port          INTEGER NOT NULL,
+    tls_bridge_port  INTEGER NOT NULL,
+    expose_ws        INTEGER NOT NULL DEFAULT 0,
+    capacity         INTEGER NOT NULL DEFAULT 1, -- pool launches at once
+    enabled          INTEGER NOT NULL DEFAULT 1,
+    created_at       TEXT NOT NULL,
+    online           INTEGER,                    -- latest health check
+    latency_ms       INTEGER,
+    last_error       TEXT,
+    checked_at       TEXT
+);
+
 CREATE INDEX IF NOT EXISTS idx_sessions_profile ON sessions(profile_id);
 CREATE INDEX IF NOT EXISTS idx_profiles_status  ON profiles(status);
+CREATE INDEX IF NOT EXISTS idx_events_kind_time ON events(kind, created_at);
+CREATE INDEX IF NOT EXISTS idx_events_profile   ON events(profile_id);
+CREATE INDEX IF NOT EXISTS idx_status_transitions_profile ON status_transitions(profile_id, id);
+CREATE INDEX IF NOT EXISTS idx_bandwidth_usage_day ON bandwidth_usage(day);
 "#;
 
 // ── Database handle ───────────────────────────────────────────────────────────
@@ -87,6 +284,8 @@ pub struct Db {
 
 struct DbInner {
     conn: Connection,
+    /// Key `cipher` was made from, kept for wrapping it (`master_key`).
+    key: Option<[u8; 32]>,
     cipher: Option<Aes256Gcm>,
 }
 
@@ -96,7 +295,11 @@ impl Db {
     pub fn open_in_memory() -> Result<Self> {
         let conn = rusqlite::Connection::open_in_memory()?;
         let db = Self {
-            inner: Arc::new(Mutex::new(DbInner { conn, cipher: None })),
+            inner: Arc::new(Mutex::new(DbInner {
+                conn,
+                key: None,
+                cipher: None,
+            })),
         };
         db.migrate()?;
         Ok(db)
@@ -106,9 +309,13 @@ impl Db {
     #[cfg(test)]
     pub fn open_in_memory_with_key(master_key: &str) -> Result<Self> {
         let conn = rusqlite::Connection::open_in_memory()?;
-        let cipher = Some(derive_cipher(master_key));
+        let key = Some(derive_key(master_key));
         let db = Self {
-            inner: Arc::new(Mutex::new(DbInner { conn, cipher })),
+            inner: Arc::new(Mutex::new(DbInner {
+                conn,
+                key,
+                cipher: key.as_ref().map(cipher_for),
+            })),
         };
         db.migrate()?;
         Ok(db)
@@ -134,9 +341,13 @@ impl Db {
         //     ))?;
         // }
 
-        let cipher = master_key.map(|k| derive_cipher(k));
+        let key = master_key.map(derive_key);
 
-        let inner = DbInner { conn, cipher };
+        let inner = DbInner {
+            conn,
+            key,
+            cipher: key.as_ref().map(cipher_for),
+        };
         let db = Self {
             inner: Arc::new(Mutex::new(inner)),
         };
@@ -158,17 +369,181 @@ impl Db {
             })
             .unwrap_or(0);
 
+        Self::apply_migrations(&guard.conn, current).map_err(|e| ManifoldError::Migration {
+            from: current,
+            to: SCHEMA_VERSION,
+            source: Box::new(e),
+        })
+    }
+
+    /// Bring a database at schema `current` up to `SCHEMA_VERSION`.
+    fn apply_migrations(conn: &Connection, current: u32) -> Result<()> {
         if current < 2 && SCHEMA_VERSION >= 2 {
-            // Migration 1→2: add tls_bridge column to profiles table
-            guard.conn.execute(
-                "ALTER TABLE profiles ADD COLUMN tls_bridge INTEGER DEFAULT 0",
-                [],
+            // Migration 1→2: add tls_bridge column to profiles table.
+            // Fresh databases already get it from SCHEMA_SQL.
+            add_column_if_missing(conn, "profiles", "tls_bridge", "INTEGER DEFAULT 0")?;
+        }
+
+        if current < 3 {
+            // Migration 2→3: per-profile opt-out for fingerprint aging.
+            add_column_if_missing(
+                conn,
+                "profiles",
+                "auto_age",
+                "INTEGER NOT NULL DEFAULT 1",
+            )?;
+        }
+
+        if current < 4 {
+            // Migration 3→4: optional persona metadata.
+            add_column_if_missing(conn, "profiles", "persona_json", "TEXT")?;
+        }
+
+        if current < 5 {
+            // Migration 4→5: ordered proxy chains.
+            add_column_if_missing(
+                conn,
+                "profiles",
+                "proxy_chain",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )?;
+        }
+
+        if current < 6 {
+            // Migration 5→6: key-based auth for SSH tunnel proxies.
+            add_column_if_missing(conn, "proxies", "ssh_key_path", "TEXT")?;
+        }
+
+        if current < 7 {
+            // Migration 6→7: optional per-proxy IP pin.
+            add_column_if_missing(conn, "proxies", "pinned_ip", "TEXT")?;
+        }
+
+        if current < 8 {
+            // Migration 7→8: health-check mode and the modes a proxy supports.
+            add_column_if_missing(
+                conn,
+                "proxies",
+                "check_mode",
+                "TEXT NOT NULL DEFAULT 'get'",
+            )?;
+            add_column_if_missing(conn, "proxies", "supports_get", "INTEGER")?;
+            add_column_if_missing(conn, "proxies", "supports_connect", "INTEGER")?;
+        }
+
+        if current < 9 {
+            // Migration 8→9: per-proxy health-check targets.
+            add_column_if_missing(
+                conn,
+                "proxies",
+                "check_targets",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )?;
+        }
+
+        if current < 10 {
+            // Migration 9→10: last measured proxy throughput.
+            add_column_if_missing(conn, "proxies", "throughput_kbps", "INTEGER")?;
+        }
+
+        if current < 11 {
+            // Migration 10→11: detected proxy anonymity level.
+            add_column_if_missing(conn, "proxies", "anonymity", "TEXT")?;
+        }
+
+        if current < 12 {
+            // Migration 11→12: per-profile bridge environment variables.
+            add_column_if_missing(
+                conn,
+                "profiles",
+                "launch_env",
+                "TEXT NOT NULL DEFAULT '{}'",
+            )?;
+        }
+
+        if current < 13 {
+            // Migration 12→13: clock jump counter per session.
+            add_column_if_missing(
+                conn,
+                "sessions",
+                "clock_jumps",
+                "INTEGER NOT NULL DEFAULT 0",
+            )?;
+        }
+
+        if current < 14 {
+            // Migration 13→14: per-profile disk quota.
+            add_column_if_missing(conn, "profiles", "disk_quota_mb", "INTEGER")?;
+        }
+
+        if current < 15 {
+            // Migration 14→15: humanness audit of the session's input.
+            add_column_if_missing(conn, "sessions", "behavior_audit", "TEXT")?;
+        }
+
+        if current < 16 {
+            // Migration 15→16: proxy prices for cost tracking.
+            add_column_if_missing(conn, "proxies", "price_per_gb", "REAL")?;
+            add_column_if_missing(conn, "proxies", "price_per_ip", "REAL")?;
+        }
+
+        if current < 17 {
+            // Migration 16→17: proxy tags and notes.
+            add_column_if_missing(
+                conn,
+                "proxies",
+                "tags",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )?;
+            add_column_if_missing(conn, "proxies", "notes", "TEXT NOT NULL DEFAULT ''")?;
+        }
+
+        if current < 18 {
+            // Migration 17→18: launch mode policy per profile, mode per session.
+            add_column_if_missing(
+                conn,
+                "profiles",
+                "launch_policy",
+                "TEXT NOT NULL DEFAULT '{}'",
+            )?;
+            add_column_if_missing(conn, "sessions", "launch_mode", "TEXT")?;
+        }
+
+        if current < 19 {
+            // Migration 18→19: Chrome version pinned per profile.
+            add_column_if_missing(conn, "profiles", "pinned_browser_version", "TEXT")?;
+        }
+
+        if current < 20 {
+            // Migration 19→20: hash chain over the event log; existing
+            // events are chained in order.
+            add_column_if_missing(conn, "events", "hash", "TEXT")?;
+            crate::events::chain_unhashed(conn)?;
+        }
+
+        if current < 21 {
+            // Migration 20→21: sub-national region per proxy.
+            add_column_if_missing(conn, "proxies", "region", "TEXT")?;
+        }
+
+        if current < 22 {
+            // Migration 21→22: pool capacity per execution node.
+            add_column_if_missing(conn, "nodes", "capacity", "INTEGER NOT NULL DEFAULT 1")?;
+        }
+
+        if current < 23 {
+            // Migration 22→23: measured regions follow the exit; set ones stay.
+            add_column_if_missing(
+                conn,
+                "proxies",
+                "region_measured",
+                "INTEGER NOT NULL DEFAULT 0",
             )?;
         }
 
         if current < SCHEMA_VERSION {
-            guard.conn.execute("DELETE FROM schema_version", [])?;
-            guard.conn.execute(
+            conn.execute("DELETE FROM schema_version", [])?;
+            conn.execute(
                 "INSERT INTO schema_version (version) VALUES (?1)",
                 params![SCHEMA_VERSION],
             )?;
@@ -177,6 +552,17 @@ impl Db {
         Ok(())
     }
 
+    /// Point this handle — and every clone of it held by repositories and
+    /// background threads — at the database `other` was opened on.
+    pub fn replace_with(&self, other: Db) -> Result<()> {
+        let inner = Arc::try_unwrap(other.inner)
+            .map_err(|_| ManifoldError::Other("replacement database handle is shared".into()))?
+            .into_inner()
+            .unwrap();
+        *self.inner.lock().unwrap() = inner;
+        Ok(())
+    }
+
     // ── Raw connection access (for repositories) ──────────────────────────────
 
     /// Run a closure with exclusive access to the underlying `Connection`.
@@ -194,46 +580,14 @@ impl Db {
     /// Returns `plaintext` unchanged if no master key was supplied.
     pub fn encrypt_field(&self, plaintext: &str) -> Result<String> {
         let guard = self.inner.lock().unwrap();
-        match &guard.cipher {
-            None => Ok(plaintext.to_string()),
-            Some(cipher) => {
-                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-                let ciphertext = cipher
-                    .encrypt(&nonce, plaintext.as_bytes())
-                    .map_err(|e| ManifoldError::Crypto(e.to_string()))?;
-
-                // Store as base64(nonce || ciphertext)
-                let mut combined = nonce.to_vec();
-                combined.extend_from_slice(&ciphertext);
-                Ok(B64.encode(combined))
-            }
-        }
+        encrypt_with(guard.cipher.as_ref(), plaintext)
     }
 
     /// Decrypt a base64 field produced by `encrypt_field`.
     /// Returns `ciphertext` unchanged if no master key was supplied.
     pub fn decrypt_field(&self, ciphertext: &str) -> Result<String> {
         let guard = self.inner.lock().unwrap();
-        match &guard.cipher {
-            None => Ok(ciphertext.to_string()),
-            Some(cipher) => {
-                let combined = B64
-                    .decode(ciphertext)
-                    .map_err(|e| ManifoldError::Crypto(e.to_string()))?;
-
-                if combined.len() < 12 {
-                    return Err(ManifoldError::Crypto("encrypted field too short".into()));
-                }
-
-                let (nonce_bytes, ct) = combined.split_at(12);
-                let nonce = Nonce::from_slice(nonce_bytes);
-                let plaintext = cipher
-                    .decrypt(nonce, ct)
-                    .map_err(|e| ManifoldError::Crypto(e.to_string()))?;
-
-                String::from_utf8(plaintext).map_err(|e| ManifoldError::Crypto(e.to_string()))
-            }
-        }
+        decrypt_with(guard.cipher.as_ref(), ciphertext)
     }
 
     /// Convenience: encrypt only if the value is Some.
@@ -251,13 +605,131 @@ impl Db {
             Some(v) => Ok(Some(self.decrypt_field(&v)?)),
         }
     }
+
+    // ── Master key changes ────────────────────────────────────────────────────
+
+    /// The AES key sensitive fields are encrypted with, if any.
+    pub fn key(&self) -> Option<[u8; 32]> {
+        self.inner.lock().unwrap().key
+    }
+
+    /// Use `key` for sensitive fields from now on, without touching stored
+    /// data — for a handle opened without its key, e.g. during recovery.
+    pub fn unlock(&self, key: [u8; 32]) {
+        let mut guard = self.inner.lock().unwrap();
+        guard.cipher = Some(cipher_for(&key));
+        guard.key = Some(key);
+    }
+
+    /// Run `f` in one transaction with a `Rekey` from the current key to
+    /// `new_key`, and switch to `new_key` once it committed.  `None`
+    /// stores fields in plaintext.
+    pub fn rekey<F, T>(&self, new_key: Option<[u8; 32]>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection, &Rekey) -> Result<T>,
+    {
+        let mut guard = self.inner.lock().unwrap();
+        let rekey = Rekey {
+            old: guard.cipher.clone(),
+            new: new_key.as_ref().map(cipher_for),
+        };
+        let tx = guard.conn.transaction()?;
+        let out = f(&tx, &rekey)?;
+        tx.commit()?;
+        guard.key = new_key;
+        guard.cipher = rekey.new;
+        Ok(out)
+    }
+}
+
+/// Old and new cipher during `Db::rekey`.
+pub struct Rekey {
+    old: Option<Aes256Gcm>,
+    new: Option<Aes256Gcm>,
+}
+
+impl Rekey {
+    /// Decrypt a field stored under the old key and encrypt it under the
+    /// new one.
+    pub fn field(&self, value: &str) -> Result<String> {
+        encrypt_with(self.new.as_ref(), &decrypt_with(self.old.as_ref(), value)?)
+    }
+}
+
+/// `ALTER TABLE … ADD COLUMN` that is a no-op when the column already exists.
+///
+/// `SCHEMA_SQL` always describes the latest layout, so on a fresh database the
+/// column is present before any migration step runs.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let exists: bool = conn.query_row(
+        &format!("SELECT COUNT(*) FROM pragma_table_info('{table}') WHERE name = ?1"),
+        params![column],
+        |r| r.get::<_, i64>(0).map(|n| n > 0),
+    )?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// ── Encryption ────────────────────────────────────────────────────────────────
+
+/// AES-256-GCM seal of `plaintext` as base64(nonce || ciphertext).
+pub fn seal(cipher: &Aes256Gcm, plaintext: &[u8]) -> Result<String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| ManifoldError::Crypto(e.to_string()))?;
+
+    let mut combined = nonce.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(B64.encode(combined))
+}
+
+/// Open a `seal`ed value.
+pub fn unseal(cipher: &Aes256Gcm, sealed: &str) -> Result<Vec<u8>> {
+    let combined = B64
+        .decode(sealed)
+        .map_err(|e| ManifoldError::Crypto(e.to_string()))?;
+
+    if combined.len() < 12 {
+        return Err(ManifoldError::Crypto("encrypted field too short".into()));
+    }
+
+    let (nonce_bytes, ct) = combined.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+    cipher
+        .decrypt(nonce, ct)
+        .map_err(|e| ManifoldError::Crypto(e.to_string()))
+}
+
+fn encrypt_with(cipher: Option<&Aes256Gcm>, plaintext: &str) -> Result<String> {
+    match cipher {
+        None => Ok(plaintext.to_string()),
+        Some(cipher) => seal(cipher, plaintext.as_bytes()),
+    }
+}
+
+fn decrypt_with(cipher: Option<&Aes256Gcm>, ciphertext: &str) -> Result<String> {
+    match cipher {
+        None => Ok(ciphertext.to_string()),
+        Some(cipher) => String::from_utf8(unseal(cipher, ciphertext)?)
+            .map_err(|e| ManifoldError::Crypto(e.to_string())),
+    }
 }
 
 // ── Key derivation ────────────────────────────────────────────────────────────
 
-/// Derive a stable AES-256-GCM key from a master passphrase using Argon2id
+pub fn cipher_for(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+/// Derive a stable AES-256 key from a master passphrase using Argon2id
 /// with a fixed application salt (not secret — just domain separation).
-fn derive_cipher(master_key: &str) -> Aes256Gcm {
+pub fn derive_key(master_key: &str) -> [u8; 32] {
     // Fixed salt: prevents the same password hashing to the same key in
     // unrelated applications, but is deterministic so the DB can be reopened.
     const FIXED_SALT: &[u8] = b"manifold-db-v1-salt-2025";
@@ -272,13 +744,10 @@ fn derive_cipher(master_key: &str) -> Aes256Gcm {
 
     // The Argon2 output hash is 32 bytes (256 bits) — perfect for AES-256.
     let hash_bytes = hash.hash.expect("argon2 produced no hash");
-    let key_bytes: [u8; 32] = hash_bytes
+    hash_bytes
         .as_bytes()
         .try_into()
-        .expect("argon2 hash is not 32 bytes");
-
-    let key = Key::<Aes256Gcm>::from_slice(&key_bytes);
-    Aes256Gcm::new(key)
+        .expect("argon2 hash is not 32 bytes")
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -294,7 +763,13 @@ mod tests {
         let db = Db::open_in_memory().unwrap();
         // profiles, proxies, sessions tables must exist
         db.with_conn(|conn| {
-            for table in &["profiles", "proxies", "sessions", "schema_version"] {
+            for table in &[
+                "profiles",
+                "proxies",
+                "sessions",
+                "events",
+                "schema_version",
+            ] {
                 let count: i64 = conn.query_row(
                     "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?1",
                     rusqlite::params![table],
@@ -319,7 +794,7 @@ mod tests {
                 )
             })
             .unwrap();
-        assert_eq!(version, 1);
+        assert_eq!(version, SCHEMA_VERSION);
     }
 
     #[test]
@@ -340,7 +815,7 @@ mod tests {
                 )
             })
             .unwrap();
-        assert_eq!(version, 1);
+        assert_eq!(version, SCHEMA_VERSION);
     }
 
     // ── Passthrough (no master key) ───────────────────────────────────────────
@@ -448,6 +923,48 @@ mod tests {
     }
 }
 
+// ── Paging ────────────────────────────────────────────────────────────────────
+
+/// Cursor paging for list commands: at most `limit` items (all when `None`)
+/// following the item whose id is `after`, in the list's own order.  Ids
+/// rather than offsets, so rows added or removed while a client pages don't
+/// shift or repeat items.
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    pub limit: Option<u32>,
+    pub after: Option<String>,
+}
+
+impl Page {
+    pub fn new(limit: Option<u32>, after: Option<String>) -> Self {
+        Self { limit, after }
+    }
+
+    /// The `LIMIT` value; SQLite reads -1 as no limit.
+    pub fn sql_limit(&self) -> i64 {
+        self.limit.map_or(-1, i64::from)
+    }
+
+    /// Fail if the cursor names a row of `table` that no longer exists, so a
+    /// client restarts instead of silently getting an empty page.
+    pub fn check_cursor(&self, conn: &Connection, table: &str) -> Result<()> {
+        let Some(after) = &self.after else {
+            return Ok(());
+        };
+        let exists: bool = conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)"),
+            params![after],
+            |r| r.get(0),
+        )?;
+        if !exists {
+            return Err(ManifoldError::InvalidArg(format!(
+                "page cursor {after:?} no longer exists"
+            )));
+        }
+        Ok(())
+    }
+}
+
 // ── Path helpers ──────────────────────────────────────────────────────────────
 
 /// Resolve the default database path:
@@ -461,8 +978,26 @@ pub fn default_db_path() -> PathBuf {
         .join("manifold.db")
 }
 
-/// Resolve the profiles root directory (next to the binary / workspace root).
+/// Profiles root of the active workspace, when it isn't the default one.
+static PROFILES_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Make `dir` the profiles root returned by `profiles_dir` (`None` restores
+/// the default).  Used when switching workspaces.
+pub fn set_profiles_dir(dir: Option<PathBuf>) {
+    *PROFILES_DIR.write().unwrap() = dir;
+}
+
+/// Resolve the profiles root directory: the active workspace's, or the
+/// default one (next to the binary / workspace root).
 pub fn profiles_dir() -> PathBuf {
+    if let Some(dir) = PROFILES_DIR.read().unwrap().clone() {
+        return dir;
+    }
+    default_profiles_dir()
+}
+
+/// Profiles root of the default workspace.
+pub fn default_profiles_dir() -> PathBuf {
     // In dev: look for ./profiles relative to the workspace root
     let workspace = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
     let candidate = workspace.join("profiles");
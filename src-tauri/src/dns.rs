@@ -0,0 +1,195 @@
+// ── Manifold proxy address resolution ─────────────────────────────────────────
+//
+// Proxies are mostly given as hostnames, often with both A and AAAA records.
+// Resolution runs with a timeout (the system resolver can block for far
+// longer than a health check should take), and connection attempts follow
+// Happy Eyeballs (RFC 8305): address families are interleaved and attempts
+// start 250 ms apart, the first to connect wins.  A proxy may also pin one
+// resolved IP so rotating DNS can't silently move it to another exit.
+
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::proxy::Proxy;
+
+/// Upper bound on a single hostname lookup.
+pub const DNS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay between starting successive connection attempts.
+const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Resolve `host:port` on a helper thread, giving up after `timeout`.
+pub fn resolve_with_timeout(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> std::result::Result<Vec<SocketAddr>, String> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let owned = host.to_string();
+    std::thread::spawn(move || {
+        let res = (owned.as_str(), port)
+            .to_socket_addrs()
+            .map(|it| it.collect::<Vec<_>>());
+        tx.send(res).ok();
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(addrs)) if !addrs.is_empty() => Ok(addrs),
+        Ok(Ok(_)) => Err(format!("DNS returned no addresses for {host}")),
+        Ok(Err(e)) => Err(format!("DNS resolve failed for {host}: {e}")),
+        Err(_) => Err(format!(
+            "DNS resolve for {host} timed out after {}s",
+            timeout.as_secs()
+        )),
+    }
+}
+
+/// RFC 8305 ordering: alternate families, starting with the family of the
+/// first record the resolver returned.
+pub fn interleave_families(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let Some(first) = addrs.first() else {
+        return Vec::new();
+    };
+    let (mut primary, mut secondary): (Vec<_>, Vec<_>) = addrs
+        .iter()
+        .copied()
+        .partition(|a| a.is_ipv6() == first.is_ipv6());
+    primary.reverse();
+    secondary.reverse();
+
+    let mut out = Vec::with_capacity(addrs.len());
+    while !primary.is_empty() || !secondary.is_empty() {
+        out.extend(primary.pop());
+        out.extend(secondary.pop());
+    }
+    out
+}
+
+/// Race connections to `addrs` (already ordered), starting one every
+/// `ATTEMPT_DELAY`.  Returns the first stream that connects.
+pub fn happy_eyeballs_connect(
+    addrs: &[SocketAddr],
+    timeout: Duration,
+) -> std::result::Result<(TcpStream, SocketAddr), String> {
+    if addrs.is_empty() {
+        return Err("no addresses".into());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    for (i, addr) in addrs.iter().copied().enumerate() {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(ATTEMPT_DELAY * i as u32);
+            let res =
+                TcpStream::connect_timeout(&addr, timeout).map_err(|e| format!("{addr}: {e}"));
+            // The receiver is gone once another attempt has won
+            tx.send(res.map(|s| (s, addr))).ok();
+        });
+    }
+    drop(tx);
+
+    let mut errors = Vec::new();
+    let deadline = ATTEMPT_DELAY * addrs.len() as u32 + timeout;
+    while let Ok(res) = rx.recv_timeout(deadline) {
+        match res {
+            Ok(won) => return Ok(won),
+            Err(e) => errors.push(e),
+        }
+        if errors.len() == addrs.len() {
+            break;
+        }
+    }
+    Err(if errors.is_empty() {
+        "connect timed out".into()
+    } else {
+        errors.join("; ")
+    })
+}
+
+/// Addresses to dial for `proxy`: its pinned IP if it has one, otherwise
+/// its host resolved and ordered for Happy Eyeballs.
+pub fn proxy_addrs(proxy: &Proxy) -> std::result::Result<Vec<SocketAddr>, String> {
+    if let Some(ip) = proxy.pinned_ip.as_deref() {
+        let ip: IpAddr = ip
+            .parse()
+            .map_err(|_| format!("invalid pinned IP {ip:?}"))?;
+        return Ok(vec![SocketAddr::new(ip, proxy.port)]);
+    }
+    let addrs = resolve_with_timeout(&proxy.host, proxy.port, DNS_TIMEOUT)?;
+    Ok(interleave_families(&addrs))
+}
+
+/// Open a TCP connection to the proxy itself.
+pub fn connect_proxy(
+    proxy: &Proxy,
+    timeout: Duration,
+) -> std::result::Result<(TcpStream, SocketAddr), String> {
+    let addrs = proxy_addrs(proxy)?;
+    happy_eyeballs_connect(&addrs, timeout).map_err(|e| {
+        format!(
+            "TCP connect to proxy failed for {}:{} ({e})",
+            proxy.host, proxy.port
+        )
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn sa(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn interleaves_starting_with_first_family() {
+        let addrs = [
+            sa("[2001:db8::1]:80"),
+            sa("[2001:db8::2]:80"),
+            sa("192.0.2.1:80"),
+            sa("192.0.2.2:80"),
+            sa("192.0.2.3:80"),
+        ];
+        assert_eq!(
+            interleave_families(&addrs),
+            vec![
+                sa("[2001:db8::1]:80"),
+                sa("192.0.2.1:80"),
+                sa("[2001:db8::2]:80"),
+                sa("192.0.2.2:80"),
+                sa("192.0.2.3:80"),
+            ]
+        );
+        assert!(interleave_families(&[]).is_empty());
+    }
+
+    #[test]
+    fn ip_literals_skip_the_resolver() {
+        let addrs = resolve_with_timeout("2001:db8::1", 8080, DNS_TIMEOUT).unwrap();
+        assert_eq!(addrs, vec![sa("[2001:db8::1]:8080")]);
+    }
+
+    #[test]
+    fn happy_eyeballs_skips_dead_addresses() {
+        let live = TcpListener::bind("127.0.0.1:0").unwrap();
+        let live_addr = live.local_addr().unwrap();
+        // A port we just released is (almost certainly) refusing connections
+        let dead_addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let (_, won) =
+            happy_eyeballs_connect(&[dead_addr, live_addr], Duration::from_secs(2)).unwrap();
+        assert_eq!(won, live_addr);
+        assert!(happy_eyeballs_connect(&[dead_addr], Duration::from_secs(2)).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+// ── Manifold JS engine quirks ─────────────────────────────────────────────────
+//
+// Detectors tell engines and versions apart without looking at the UA: V8's
+// fdlibm port gives `Math.tan(-1e300)` a value no other engine does, V8 stack
+// traces read `Error: msg\n    at …`, each Chrome major ships a known set of
+// builtins (`Object.groupBy` from 117, `Promise.try` from 128 …), and ICU
+// picks a default calendar, numbering system and hour cycle per locale
+// (intl.rs).
+// None of it can be spoofed safely, so it has to agree with the Chrome major
+// and locale the profile claims.  This module derives those expectations
+// from the fingerprint, ships them to the bridge in the launch config, and
+// compares them with what a leak probe observed in the page.
+
+use serde::{Deserialize, Serialize};
+
+use crate::fingerprint::{chrome_version, Fingerprint};
+use crate::intl::IntlProfile;
+
+/// Oldest Chrome major whose engine values are modelled here.
+pub const MIN_MODELLED_MAJOR: u32 = 110;
+
+/// `Math` results of V8's fdlibm port, the set fingerprinting libraries
+/// probe.  Identical on every OS; Firefox and Safari differ on most.
+const V8_MATH: &[(&str, f64, f64)] = &[
+    ("tan", -1e300, -1.4214488238747245),
+    ("sin", -1e300, 0.8178819121159085),
+    ("cos", 10.000000000123, -0.8390715290095377),
+    ("acosh", 1e308, 709.889355822726),
+    ("asinh", 1.0, 0.881373587019543),
+    ("cosh", 1.0, 1.5430806348152437),
+    ("expm1", 1.0, 1.718281828459045),
+    ("log1p", 10.0, 2.3978952727983707),
+];
+
+/// Builtins and the Chrome major that shipped them.
+const BUILTINS: &[(&str, u32)] = &[
+    ("Error.captureStackTrace", 0),
+    ("Array.prototype.at", 92),
+    ("Object.hasOwn", 93),
+    ("Array.prototype.findLast", 97),
+    ("structuredClone", 98),
+    ("Array.prototype.toSorted", 110),
+    ("Object.groupBy", 117),
+    ("Promise.withResolvers", 119),
+    ("Array.fromAsync", 121),
+    ("Set.prototype.union", 122),
+    ("Promise.try", 128),
+    ("Intl.DurationFormat", 129),
+    ("Float16Array", 135),
+    ("RegExp.escape", 136),
+];
+
+/// `new Error("probe").stack` starts with this in V8.
+const V8_STACK_PREFIX: &str = "Error: probe\n    at ";
+const V8_STACK_TRACE_LIMIT: u32 = 10;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MathProbe {
+    /// `Math` function name.
+    pub func: String,
+    pub arg: f64,
+    pub value: f64,
+}
+
+/// What the page should report for the profile's Chrome major and locale.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineQuirks {
+    pub chrome_major: Option<u32>,
+    pub math: Vec<MathProbe>,
+    pub stack_prefix: String,
+    pub stack_trace_limit: u32,
+    /// Builtin paths (`Object.groupBy`) the claimed major has …
+    pub builtins_present: Vec<String>,
+    /// … and those it doesn't have yet.
+    pub builtins_absent: Vec<String>,
+    /// Calendar, numbering system and hour cycle are compared.
+    pub intl: IntlProfile,
+}
+
+/// What the bridge read back in the page, in the same terms.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EngineObservation {
+    pub math: Vec<MathProbe>,
+    pub stack: String,
+    pub stack_trace_limit: u32,
+    /// Which of the queried builtins exist.
+    pub builtins: Vec<String>,
+    pub calendar: String,
+    pub numbering_system: String,
+    pub hour_cycle: String,
+}
+
+// ── Expectations ──────────────────────────────────────────────────────────────
+
+/// Engine expectations for the Chrome major in `fp.user_agent` and
+/// `fp.locale`.  Without a parseable major every builtin is expected.
+pub fn engine_quirks(fp: &Fingerprint) -> EngineQuirks {
+    let major = chrome_major(fp);
+    let (present, absent): (Vec<_>, Vec<_>) = BUILTINS
+        .iter()
+        .partition(|(_, since)| major.is_none_or(|m| m >= *since));
+    EngineQuirks {
+        chrome_major: major,
+        math: V8_MATH
+            .iter()
+            .map(|&(func, arg, value)| MathProbe {
+                func: func.into(),
+                arg,
+                value,
+            })
+            .collect(),
+        stack_prefix: V8_STACK_PREFIX.into(),
+        stack_trace_limit: V8_STACK_TRACE_LIMIT,
+        builtins_present: present.iter().map(|(name, _)| name.to_string()).collect(),
+        builtins_absent: absent.iter().map(|(name, _)| name.to_string()).collect(),
+        intl: fp
+            .intl
+            .clone()
+            .unwrap_or_else(|| IntlProfile::for_locale(&fp.locale)),
+    }
+}
+
+pub fn chrome_major(fp: &Fingerprint) -> Option<u32> {
+    chrome_version(&fp.user_agent)?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+// ── Verification ──────────────────────────────────────────────────────────────
+
+/// Differences between the expectations and what the page reported.
+pub fn verify(expected: &EngineQuirks, observed: &EngineObservation) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    problems.extend(expected.math.iter().filter_map(|want| {
+        let got = observed
+            .math
+            .iter()
+            .find(|m| m.func == want.func && m.arg == want.arg)?;
+        (got.value != want.value).then(|| {
+            format!(
+                "Math.{}({:e}) = {}, V8 gives {}",
+                want.func, want.arg, got.value, want.value
+            )
+        })
+    }));
+
+    if !observed.stack.starts_with(&expected.stack_prefix) {
+        problems.push(format!(
+            "Error.stack is not in V8's format: {:?}",
+            observed.stack.lines().next().unwrap_or_default()
+        ));
+    }
+    if observed.stack_trace_limit != expected.stack_trace_limit {
+        problems.push(format!(
+            "Error.stackTraceLimit is {}, Chrome uses {}",
+            observed.stack_trace_limit, expected.stack_trace_limit
+        ));
+    }
+
+    let major = expected
+        .chrome_major
+        .map_or_else(|| "?".to_string(), |m| m.to_string());
+    let missing: Vec<&str> = expected
+        .builtins_present
+        .iter()
+        .filter(|b| !observed.builtins.contains(b))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        problems.push(format!(
+            "Chrome {major} has {} but the page doesn't",
+            missing.join(", ")
+        ));
+    }
+    let early: Vec<&str> = expected
+        .builtins_absent
+        .iter()
+        .filter(|b| observed.builtins.contains(b))
+        .map(String::as_str)
+        .collect();
+    if !early.is_empty() {
+        problems.push(format!(
+            "page has {}, which Chrome {major} doesn't ship yet",
+            early.join(", ")
+        ));
+    }
+
+    let intl = &expected.intl;
+    for (what, got, want) in [
+        ("calendar", &observed.calendar, &intl.calendar),
+        (
+            "numbering system",
+            &observed.numbering_system,
+            &intl.numbering_system,
+        ),
+        ("hour cycle", &observed.hour_cycle, &intl.hour_cycle),
+    ] {
+        if got != want {
+            problems.push(format!("Intl {what} is {got:?}, expected {want:?}"));
+        }
+    }
+    problems
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    /// What a matching Chrome would report.
+    fn observed(expected: &EngineQuirks) -> EngineObservation {
+        EngineObservation {
+            math: expected.math.clone(),
+            stack: format!("{}probe (<anonymous>:1:1)", expected.stack_prefix),
+            stack_trace_limit: expected.stack_trace_limit,
+            builtins: expected.builtins_present.clone(),
+            calendar: expected.intl.calendar.clone(),
+            numbering_system: expected.intl.numbering_system.clone(),
+            hour_cycle: expected.intl.hour_cycle.clone(),
+        }
+    }
+
+    #[test]
+    fn builtins_follow_the_claimed_major() {
+        let mut fp = FingerprintOrchestrator::generate(7);
+        FingerprintOrchestrator::set_chrome_major(&mut fp, 120);
+        let q = engine_quirks(&fp);
+        assert_eq!(q.chrome_major, Some(120));
+        assert!(q.builtins_present.iter().any(|b| b == "Object.groupBy"));
+        assert!(q.builtins_absent.iter().any(|b| b == "Set.prototype.union"));
+        assert!(verify(&q, &observed(&q)).is_empty());
+
+        // A newer engine behind an older UA shows its newer builtins
+        let mut newer = observed(&q);
+        newer.builtins.push("Set.prototype.union".into());
+        let problems = verify(&q, &newer);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("Set.prototype.union"), "{problems:?}");
+    }
+
+    #[test]
+    fn foreign_engine_values_are_reported() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let q = engine_quirks(&fp);
+        let mut firefox = observed(&q);
+        firefox.math[0].value = -1.4214488238747243;
+        firefox.stack = "@debugger eval code:1:1".into();
+        assert_eq!(verify(&q, &firefox).len(), 2);
+    }
+}
@@ -0,0 +1,318 @@
+// ── Manifold fingerprint entropy report ──────────────────────────────────────
+//
+// How much a fingerprint tells a tracker, field by field.  Each field's value
+// is looked up in the population priors — its share among desktop Chrome
+// users, see `priors` — and contributes its surprisal, -log2(p), in bits.  A value one user in a thousand has carries ~10 bits; one every
+// other user has carries one.
+//
+// A field is unusual when its value carries clearly more bits than the
+// field's Shannon entropy, the average a real user's value carries.  That is
+// what the report sorts by: a common GPU on a rare screen points at the
+// screen, however many bits the GPU itself carries.
+//
+// Fields are scored independently, so `total_bits` overstates correlated
+// ones: only the GPU vendor is conditioned on the platform, while locale and
+// timezone or screen and pixel ratio are counted twice over.  Read it as an
+// upper bound; the per-field excess is the useful part.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::fingerprint::{ColorGamut, ColorScheme, Fingerprint, FingerprintOrchestrator};
+use crate::fonts::{OsRelease, BASELINE};
+use crate::priors::{Distribution, Priors};
+
+/// Excess over the field's entropy from which a value counts as unusual.
+pub const UNUSUAL_EXCESS_BITS: f64 = 1.0;
+
+/// Floor for values a prior gives no mass at all.
+const MIN_PROBABILITY: f64 = 1e-6;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldEntropy {
+    pub field: String,
+    pub value: String,
+    /// Share of the population with this value.
+    pub probability: f64,
+    /// Surprisal of the value, -log2(probability).
+    pub bits: f64,
+    /// Shannon entropy of the field: the bits an average user's value
+    /// carries.
+    pub typical_bits: f64,
+    /// `bits` exceeds `typical_bits` by `UNUSUAL_EXCESS_BITS` or more.
+    pub unusual: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EntropyReport {
+    pub seed: u64,
+    /// Sum of the fields' bits; an upper bound, see the module comment.
+    pub total_bits: f64,
+    /// Sum of the fields' entropies, for comparison.
+    pub typical_bits: f64,
+    /// Most unusual first.
+    pub fields: Vec<FieldEntropy>,
+}
+
+// ── Report ────────────────────────────────────────────────────────────────────
+
+fn gpu_vendor(renderer: &str) -> &'static str {
+    let r = renderer.to_ascii_lowercase();
+    if r.contains("nvidia") || r.contains("geforce") {
+        "NVIDIA"
+    } else if r.contains("amd") || r.contains("radeon") {
+        "AMD"
+    } else if r.contains("apple") {
+        "Apple"
+    } else if r.contains("intel") {
+        "Intel"
+    } else if r.contains("llvmpipe") || r.contains("swiftshader") || r.contains("basic render") {
+        "Software"
+    } else {
+        "Other"
+    }
+}
+
+fn font_coverage(fp: &Fingerprint) -> (String, String) {
+    let release = OsRelease::from_platform(&fp.ua_platform, &fp.ua_platform_version)
+        .unwrap_or(OsRelease::Windows10);
+    let catalogue = release.catalogue();
+    let present = catalogue
+        .iter()
+        .filter(|f| fp.font_subset.iter().any(|s| s == *f))
+        .count();
+    let share = if catalogue.is_empty() {
+        1.0
+    } else {
+        present as f64 / catalogue.len() as f64
+    };
+    let bucket = match share {
+        s if s >= 0.9 => "90-100%",
+        s if s >= 0.75 => "75-90%",
+        s if s >= 0.5 => "50-75%",
+        _ => "0-50%",
+    };
+    let extra = fp
+        .font_subset
+        .iter()
+        .filter(|f| !BASELINE.contains(&f.as_str()) && !catalogue.contains(&f.as_str()))
+        .count();
+    let value = format!(
+        "{present} of {} {release:?} fonts, {extra} other",
+        catalogue.len()
+    );
+    (bucket.to_string(), value)
+}
+
+fn field(name: &str, value: String, prior: &Distribution, key: &str) -> FieldEntropy {
+    let probability = prior.share(key).max(MIN_PROBABILITY);
+    scored(name, value, probability, prior.entropy())
+}
+
+fn scored(name: &str, value: String, probability: f64, typical_bits: f64) -> FieldEntropy {
+    let bits = -probability.log2();
+    FieldEntropy {
+        field: name.to_string(),
+        value,
+        probability,
+        bits,
+        typical_bits,
+        unusual: bits - typical_bits >= UNUSUAL_EXCESS_BITS,
+    }
+}
+
+/// Per-field entropy of `fp` against `priors`, with Chrome versions
+/// measured from the stable major of `today`.
+pub fn report(fp: &Fingerprint, priors: &Priors, today: NaiveDate) -> EntropyReport {
+    let text = |v: &dyn std::fmt::Display| v.to_string();
+    let mut fields = Vec::new();
+
+    fields.push(field(
+        "platform",
+        fp.ua_platform.clone(),
+        &priors.platform,
+        &fp.ua_platform,
+    ));
+
+    if let Some(major) = crate::engine_quirks::chrome_major(fp) {
+        let stable = crate::aging::stable_chrome_major(today);
+        let lag = stable as i64 - major as i64;
+        fields.push(field(
+            "chrome_major",
+            format!("{major} (stable {stable})"),
+            &priors.chrome_lag,
+            &lag.to_string(),
+        ));
+    }
+
+    let screen = format!("{}x{}", fp.screen_width, fp.screen_height);
+    fields.push(field("screen", screen.clone(), &priors.screen, &screen));
+    let ratio = text(&fp.pixel_ratio);
+    fields.push(field(
+        "pixel_ratio",
+        ratio.clone(),
+        &priors.pixel_ratio,
+        &ratio,
+    ));
+    let depth = text(&fp.color_depth);
+    fields.push(field(
+        "color_depth",
+        depth.clone(),
+        &priors.color_depth,
+        &depth,
+    ));
+    let gamut = match fp.color_gamut {
+        ColorGamut::Srgb => "srgb",
+        ColorGamut::P3 => "p3",
+    };
+    fields.push(field(
+        "color_gamut",
+        gamut.into(),
+        &priors.color_gamut,
+        gamut,
+    ));
+    let hdr = text(&fp.hdr);
+    fields.push(field("hdr", hdr.clone(), &priors.hdr, &hdr));
+
+    let cores = text(&fp.hardware_concurrency);
+    fields.push(field(
+        "hardware_concurrency",
+        cores.clone(),
+        &priors.hardware_concurrency,
+        &cores,
+    ));
+    let memory = text(&fp.device_memory);
+    fields.push(field(
+        "device_memory",
+        memory.clone(),
+        &priors.device_memory,
+        &memory,
+    ));
+
+    let vendor = gpu_vendor(&fp.webgl_renderer);
+    let vendors = priors.gpu_vendor_on(&fp.ua_platform);
+    fields.push(field("gpu_vendor", vendor.into(), vendors, vendor));
+    let models = priors.gpu_model_count(vendor) as f64;
+    fields.push(scored(
+        "gpu_model",
+        fp.webgl_renderer.clone(),
+        1.0 / models,
+        models.log2(),
+    ));
+
+    let (bucket, fonts) = font_coverage(fp);
+    fields.push(field("fonts", fonts, &priors.font_coverage, &bucket));
+
+    fields.push(field(
+        "locale",
+        fp.locale.clone(),
+        &priors.locale,
+        &fp.locale,
+    ));
+    fields.push(field(
+        "timezone",
+        fp.timezone.clone(),
+        &priors.timezone,
+        &fp.timezone,
+    ));
+
+    let scheme = match fp.prefers_color_scheme {
+        ColorScheme::Light => "light",
+        ColorScheme::Dark => "dark",
+    };
+    fields.push(field(
+        "prefers_color_scheme",
+        scheme.into(),
+        &priors.color_scheme,
+        scheme,
+    ));
+    let motion = text(&fp.prefers_reduced_motion);
+    fields.push(field(
+        "prefers_reduced_motion",
+        motion.clone(),
+        &priors.reduced_motion,
+        &motion,
+    ));
+    let forced = text(&fp.forced_colors);
+    fields.push(field(
+        "forced_colors",
+        forced.clone(),
+        &priors.forced_colors,
+        &forced,
+    ));
+
+    fields.sort_by(|a, b| {
+        (b.bits - b.typical_bits)
+            .total_cmp(&(a.bits - a.typical_bits))
+            .then_with(|| a.field.cmp(&b.field))
+    });
+    EntropyReport {
+        seed: fp.seed,
+        total_bits: fields.iter().map(|f| f.bits).sum(),
+        typical_bits: fields.iter().map(|f| f.typical_bits).sum(),
+        fields,
+    }
+}
+
+/// `report` of the fingerprint `seed` generates, against the priors in use.
+pub fn seed_report(seed: u64, today: NaiveDate) -> EntropyReport {
+    report(
+        &FingerprintOrchestrator::generate(seed),
+        &crate::priors::current(),
+        today,
+    )
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits_of<'a>(report: &'a EntropyReport, name: &str) -> &'a FieldEntropy {
+        report.fields.iter().find(|f| f.field == name).unwrap()
+    }
+
+    #[test]
+    fn rare_values_carry_more_bits_and_sort_first() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let mut fp = FingerprintOrchestrator::generate(7);
+        fp.screen_width = 1920;
+        fp.screen_height = 1080;
+        fp.device_memory = 8.0;
+        assert_eq!(seed_report(7, today).seed, 7);
+        let priors = Priors::builtin();
+        let common = report(&fp, priors, today);
+        assert!((bits_of(&common, "screen").bits - -(0.23f64).log2()).abs() < 1e-9);
+        assert!(!bits_of(&common, "screen").unusual);
+
+        fp.screen_width = 1093;
+        fp.screen_height = 614;
+        fp.device_memory = 0.25;
+        fp.forced_colors = true;
+        let rare = report(&fp, priors, today);
+        assert!(bits_of(&rare, "screen").bits > bits_of(&common, "screen").bits + 5.0);
+        assert!(bits_of(&rare, "device_memory").unusual);
+        assert!(rare.total_bits > common.total_bits);
+        assert!(rare.fields[0].unusual);
+        let excess = |f: &FieldEntropy| f.bits - f.typical_bits;
+        assert!(rare
+            .fields
+            .windows(2)
+            .all(|w| excess(&w[0]) >= excess(&w[1])));
+
+        // Entropies are finite and no value is free of information
+        for prior in [
+            &priors.screen,
+            &priors.locale,
+            &priors.hdr,
+            &priors.chrome_lag,
+        ] {
+            assert!(prior.entropy().is_finite() && prior.entropy() > 0.0);
+        }
+        let hdr = field("hdr", "maybe".into(), &priors.hdr, "maybe");
+        assert_eq!(hdr.probability, MIN_PROBABILITY);
+    }
+}
@@ -1,4 +1,11 @@
 // ── Manifold error types ──────────────────────────────────────────────────────
+//
+// An error keeps what caused it.  Variants wrapping a lower-level error hold
+// it as their `#[source]` and show it after their own message, and
+// `Context::context` wraps any error with what was being done when it
+// happened — so a log line or a frontend toast reads "Failed to start
+// bridge: No such file or directory (os error 2)" rather than just the last
+// step, and `std::error::Error::source` walks the same chain.
 
 use serde::Serialize;
 
@@ -30,16 +37,91 @@ pub enum ManifoldError {
     #[error("Bridge not running")]
     BridgeOffline,
 
+    #[error("Bridge version mismatch: expected {expected}, found {found}")]
+    BridgeVersionMismatch { expected: String, found: String },
+
+    /// The running bridge failed a command or stopped answering.
+    #[error("Bridge error: {0}")]
+    Bridge(String),
+
+    /// A child process (bridge, scraper, Xvfb, ssh …) could not be started.
+    #[error("Failed to start {program}: {source}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
     #[error("Encryption error: {0}")]
     Crypto(String),
 
+    /// A schema upgrade failed; the database is left at `from`.
+    #[error("Database migration from version {from} to {to} failed: {source}")]
+    Migration {
+        from: u32,
+        to: u32,
+        #[source]
+        source: Box<ManifoldError>,
+    },
+
     #[error("Invalid argument: {0}")]
     InvalidArg(String),
 
+    /// What was being done when `source` happened.
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<ManifoldError>,
+    },
+
     #[error("{0}")]
     Other(String),
 }
 
+impl ManifoldError {
+    pub fn spawn(program: impl Into<String>, source: std::io::Error) -> Self {
+        Self::Spawn {
+            program: program.into(),
+            source,
+        }
+    }
+
+    /// The innermost error of the chain.
+    #[allow(dead_code)]
+    pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+        let mut err: &(dyn std::error::Error + 'static) = self;
+        while let Some(source) = err.source() {
+            err = source;
+        }
+        err
+    }
+}
+
+/// Wraps an error with what was being done, keeping it as the cause.
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// `context` with a message built only on failure.
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T>;
+}
+
+impl<T, E: Into<ManifoldError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.with_context(|| context.into())
+    }
+
+    fn with_context(self, context: impl FnOnce() -> String) -> Result<T> {
+        self.map_err(|e| ManifoldError::Context {
+            context: context(),
+            source: Box::new(e.into()),
+        })
+    }
+}
+
 /// Tauri commands must return serialisable errors.
 impl Serialize for ManifoldError {
     fn serialize<S: serde::Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
@@ -75,6 +157,18 @@ mod tests {
         assert_eq!(e.to_string(), "Bridge not running");
     }
 
+    #[test]
+    fn bridge_version_mismatch_message() {
+        let e = ManifoldError::BridgeVersionMismatch {
+            expected: "protocol 1".into(),
+            found: "protocol 2 (bridge 0.2.0)".into(),
+        };
+        assert_eq!(
+            e.to_string(),
+            "Bridge version mismatch: expected protocol 1, found protocol 2 (bridge 0.2.0)"
+        );
+    }
+
     #[test]
     fn invalid_arg_message() {
         let e = ManifoldError::InvalidArg("port cannot be 0".into());
@@ -132,6 +226,41 @@ mod tests {
         );
     }
 
+    // ── Source chains ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn context_keeps_the_cause_in_message_and_chain() {
+        use std::error::Error;
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let r: std::result::Result<(), _> = Err(io);
+        let e = r.context("failed to write session file").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "failed to write session file: IO error: no such file"
+        );
+        assert_eq!(e.source().unwrap().to_string(), "IO error: no such file");
+        assert_eq!(e.root_cause().to_string(), "no such file");
+
+        let spawn = ManifoldError::spawn(
+            "Xvfb",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+        );
+        assert_eq!(spawn.to_string(), "Failed to start Xvfb: not found");
+        assert_eq!(spawn.source().unwrap().to_string(), "not found");
+
+        let migration = ManifoldError::Migration {
+            from: 18,
+            to: 19,
+            source: Box::new(spawn),
+        };
+        assert_eq!(migration.root_cause().to_string(), "not found");
+        assert_eq!(
+            serde_json::to_string(&migration).unwrap(),
+            r#""Database migration from version 18 to 19 failed: Failed to start Xvfb: not found""#
+        );
+    }
+
     // ── Serde serialization ───────────────────────────────────────────────────
 
     #[test]
@@ -0,0 +1,515 @@
+// ── Manifold event log ────────────────────────────────────────────────────────
+//
+// Append-only record of things that happened to profiles: launches, stops,
+// panic shutdowns and detection incidents reported by the bridge / frontend.
+// Aggregations (dashboard charts, rest-period heuristics) query this table
+// directly instead of reconstructing history from profile rows.
+//
+// Each row carries a BLAKE3 hash over its own fields and the previous row's
+// hash, so editing or deleting an event breaks the chain from that row on
+// and `verify` points at it.  Anyone with write access to the database can
+// recompute the whole chain, though: to catch that, keep the `head` a
+// verification returns somewhere else and pass it back as the `anchor` of
+// the next one.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A browser session was launched for the profile.
+    Launch,
+    /// A browser session was stopped normally.
+    Stop,
+    /// Emergency shutdown stopped the profile.
+    Panic,
+    /// A block, captcha wall or other detection signal was observed.
+    Detection,
+    /// The host clock jumped during a session (clock_guard).
+    ClockJump,
+    /// A profile's data dir neared or passed its size limit (quota).
+    DiskQuota,
+    /// A captcha was sent to a solving service (cost).
+    CaptchaSolve,
+    /// The operator wrote to or read the profile's clipboard (clipboard).
+    Clipboard,
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Launch => "launch",
+            Self::Stop => "stop",
+            Self::Panic => "panic",
+            Self::Detection => "detection",
+            Self::ClockJump => "clock_jump",
+            Self::DiskQuota => "disk_quota",
+            Self::CaptchaSolve => "captcha_solve",
+            Self::Clipboard => "clipboard",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "launch" => Ok(Self::Launch),
+            "stop" => Ok(Self::Stop),
+            "panic" => Ok(Self::Panic),
+            "detection" => Ok(Self::Detection),
+            "clock_jump" => Ok(Self::ClockJump),
+            "disk_quota" => Ok(Self::DiskQuota),
+            "captcha_solve" => Ok(Self::CaptchaSolve),
+            "clipboard" => Ok(Self::Clipboard),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown event kind: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// A single event-log row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    pub profile_id: Option<String>,
+    pub kind: EventKind,
+    /// "info" | "warning" | "critical" — free-form but kept lowercase.
+    pub severity: String,
+    pub domain: Option<String>,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Payload for appending an event.
+#[derive(Debug, Deserialize)]
+pub struct NewEvent {
+    pub profile_id: Option<String>,
+    pub kind: EventKind,
+    pub severity: Option<String>,
+    pub domain: Option<String>,
+    pub detail: Option<serde_json::Value>,
+}
+
+/// A row's position in the hash chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub id: i64,
+    pub hash: String,
+}
+
+/// How the chain broke at `BrokenLink::id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainBreak {
+    /// The row has no hash.
+    Unhashed,
+    /// The row's fields or hash were changed after it was written.
+    Altered,
+    /// Rows before it were deleted.
+    Missing,
+    /// The anchor's row no longer has the anchor's hash: the chain was
+    /// recomputed after a change.
+    Rewritten,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub id: i64,
+    pub kind: ChainBreak,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogVerification {
+    /// Rows checked, up to the first break.
+    pub checked: u64,
+    /// Last row of an intact chain, to keep as the next anchor.  `None`
+    /// when the log is empty or broken.
+    pub head: Option<ChainLink>,
+    /// First break found.
+    pub broken: Option<BrokenLink>,
+}
+
+impl NewEvent {
+    /// Shorthand for an info-level event without domain or detail.
+    pub fn simple(profile_id: Option<&str>, kind: EventKind) -> Self {
+        Self {
+            profile_id: profile_id.map(str::to_string),
+            kind,
+            severity: None,
+            domain: None,
+            detail: None,
+        }
+    }
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct EventRepo {
+    db: Db,
+}
+
+impl EventRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    // ── Record ────────────────────────────────────────────────────────────────
+
+    pub fn record(&self, ev: NewEvent) -> Result<Event> {
+        let severity = ev
+            .severity
+            .map(|s| s.to_lowercase())
+            .unwrap_or_else(|| "info".into());
+        let detail = ev.detail.unwrap_or_else(|| serde_json::json!({}));
+        let detail_json = serde_json::to_string(&detail)?;
+        let now = Utc::now();
+
+        let id = self.db.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let prev: Option<Option<String>> = tx
+                .query_row(
+                    "SELECT hash FROM events ORDER BY id DESC LIMIT 1",
+                    [],
+                    |r| r.get(0),
+                )
+                .optional()?;
+            let mut row = StoredEvent {
+                id: 0,
+                profile_id: ev.profile_id.clone(),
+                kind: ev.kind.to_string(),
+                severity: severity.clone(),
+                domain: ev.domain.clone(),
+                detail: detail_json,
+                created_at: now.to_rfc3339(),
+                hash: None,
+            };
+            tx.execute(
+                r#"INSERT INTO events (profile_id, kind, severity, domain, detail, created_at)
+                   VALUES (?1,?2,?3,?4,?5,?6)"#,
+                params![
+                    row.profile_id,
+                    row.kind,
+                    row.severity,
+                    row.domain,
+                    row.detail,
+                    row.created_at,
+                ],
+            )?;
+            row.id = tx.last_insert_rowid();
+            let prev = prev.flatten().unwrap_or_else(|| GENESIS.to_string());
+            tx.execute(
+                "UPDATE events SET hash = ?1 WHERE id = ?2",
+                params![chain_hash(&prev, &row), row.id],
+            )?;
+            tx.commit()?;
+            Ok(row.id)
+        })?;
+
+        Ok(Event {
+            id,
+            profile_id: ev.profile_id,
+            kind: ev.kind,
+            severity,
+            domain: ev.domain,
+            detail,
+            created_at: now,
+        })
+    }
+
+    // ── Read ──────────────────────────────────────────────────────────────────
+
+    /// Most recent events first, optionally restricted to one profile and
+    /// continuing below event id `before` (the last id of the previous page).
+    pub fn list_before(
+        &self,
+        profile_id: Option<&str>,
+        limit: u32,
+        before: Option<i64>,
+    ) -> Result<Vec<Event>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, profile_id, kind, severity, domain, detail, created_at
+                   FROM events
+                   WHERE (?1 IS NULL OR profile_id = ?1)
+                     AND (?3 IS NULL OR id < ?3)
+                   ORDER BY id DESC
+                   LIMIT ?2"#,
+            )?;
+            let events = stmt
+                .query_map(params![profile_id, limit as i64, before], row_to_event)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(events)
+        })
+    }
+
+    // ── Hash chain ────────────────────────────────────────────────────────────
+
+    /// Recompute the chain from the first row and report the first break.
+    /// With `anchor` — the `head` of an earlier verification — its row must
+    /// still carry its hash.
+    pub fn verify(&self, anchor: Option<&ChainLink>) -> Result<EventLogVerification> {
+        self.db.with_conn(|conn| {
+            let mut prev = ChainLink {
+                id: 0,
+                hash: GENESIS.to_string(),
+            };
+            let mut checked = 0;
+            let mut anchored = anchor.is_none();
+            let broken = |id, kind, checked| EventLogVerification {
+                checked,
+                head: None,
+                broken: Some(BrokenLink { id, kind }),
+            };
+            for row in stored_events(conn)? {
+                let kind = match &row.hash {
+                    None => Some(ChainBreak::Unhashed),
+                    Some(_) if row.id != prev.id + 1 => Some(ChainBreak::Missing),
+                    Some(hash) if *hash != chain_hash(&prev.hash, &row) => {
+                        Some(ChainBreak::Altered)
+                    }
+                    Some(_) => None,
+                };
+                if let Some(kind) = kind {
+                    return Ok(broken(row.id, kind, checked));
+                }
+                prev = ChainLink {
+                    id: row.id,
+                    hash: row.hash.unwrap_or_default(),
+                };
+                if let Some(anchor) = anchor.filter(|a| a.id == prev.id) {
+                    if anchor.hash != prev.hash {
+                        return Ok(broken(anchor.id, ChainBreak::Rewritten, checked));
+                    }
+                    anchored = true;
+                }
+                checked += 1;
+            }
+            if let (false, Some(anchor)) = (anchored, anchor) {
+                // The anchor's row is gone, and every row after it with it
+                return Ok(broken(anchor.id, ChainBreak::Missing, checked));
+            }
+            Ok(EventLogVerification {
+                checked,
+                head: (prev.id > 0).then_some(prev),
+                broken: None,
+            })
+        })
+    }
+}
+
+// ── Hash chain ────────────────────────────────────────────────────────────────
+
+/// What the first row is chained to.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// An `events` row as stored, the text the hash covers.
+struct StoredEvent {
+    id: i64,
+    profile_id: Option<String>,
+    kind: String,
+    severity: String,
+    domain: Option<String>,
+    detail: String,
+    created_at: String,
+    hash: Option<String>,
+}
+
+fn stored_events(conn: &Connection) -> Result<Vec<StoredEvent>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT id, profile_id, kind, severity, domain, detail, created_at, hash
+           FROM events ORDER BY id"#,
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(StoredEvent {
+                id: r.get(0)?,
+                profile_id: r.get(1)?,
+                kind: r.get(2)?,
+                severity: r.get(3)?,
+                domain: r.get(4)?,
+                detail: r.get(5)?,
+                created_at: r.get(6)?,
+                hash: r.get(7)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows)
+}
+
+/// BLAKE3 over the previous hash and the row's fields, each length-prefixed
+/// so no two rows hash the same input.
+fn chain_hash(prev: &str, row: &StoredEvent) -> String {
+    let mut hasher = blake3::Hasher::new();
+    let mut field = |value: Option<&str>| match value {
+        None => {
+            hasher.update(&[0]);
+        }
+        Some(v) => {
+            hasher.update(&[1]);
+            hasher.update(&(v.len() as u64).to_le_bytes());
+            hasher.update(v.as_bytes());
+        }
+    };
+    field(Some(prev));
+    field(Some(&row.id.to_string()));
+    field(row.profile_id.as_deref());
+    field(Some(&row.kind));
+    field(Some(&row.severity));
+    field(row.domain.as_deref());
+    field(Some(&row.detail));
+    field(Some(&row.created_at));
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Hash the rows that have none, in order, continuing the chain — for rows
+/// written before events were chained.
+pub fn chain_unhashed(conn: &Connection) -> Result<()> {
+    let mut prev = GENESIS.to_string();
+    for row in stored_events(conn)? {
+        let hash = match row.hash.clone() {
+            Some(hash) => hash,
+            None => {
+                let hash = chain_hash(&prev, &row);
+                conn.execute(
+                    "UPDATE events SET hash = ?1 WHERE id = ?2",
+                    params![hash, row.id],
+                )?;
+                hash
+            }
+        };
+        prev = hash;
+    }
+    Ok(())
+}
+
+// ── Row mapper ────────────────────────────────────────────────────────────────
+
+fn row_to_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<Event> {
+    let kind_str: String = row.get(2)?;
+    let detail_json: String = row.get(5)?;
+    let created_str: String = row.get(6)?;
+
+    let kind = kind_str.parse().map_err(|e: ManifoldError| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(Event {
+        id: row.get(0)?,
+        profile_id: row.get(1)?,
+        kind,
+        severity: row.get(3)?,
+        domain: row.get(4)?,
+        detail: serde_json::from_str(&detail_json).unwrap_or(serde_json::Value::Null),
+        created_at: DateTime::parse_from_rfc3339(&created_str)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_repo() -> EventRepo {
+        EventRepo::new(Db::open_in_memory().unwrap())
+    }
+
+    #[test]
+    fn event_kind_roundtrip() {
+        for s in &["launch", "stop", "panic", "detection", "clock_jump"] {
+            let k: EventKind = s.parse().unwrap();
+            assert_eq!(k.to_string(), *s);
+        }
+        assert!("reboot".parse::<EventKind>().is_err());
+    }
+
+    #[test]
+    fn record_defaults_severity_and_detail() {
+        let repo = make_repo();
+        let ev = repo
+            .record(NewEvent::simple(Some("p1"), EventKind::Launch))
+            .unwrap();
+        assert_eq!(ev.severity, "info");
+        assert_eq!(ev.detail, serde_json::json!({}));
+        assert!(ev.id > 0);
+    }
+
+    #[test]
+    fn list_filters_by_profile_newest_first() {
+        let repo = make_repo();
+        repo.record(NewEvent::simple(Some("a"), EventKind::Launch))
+            .unwrap();
+        repo.record(NewEvent::simple(Some("b"), EventKind::Launch))
+            .unwrap();
+        repo.record(NewEvent::simple(Some("a"), EventKind::Stop))
+            .unwrap();
+
+        let a = repo.list_before(Some("a"), 10, None).unwrap();
+        assert_eq!(a.len(), 2);
+        assert_eq!(a[0].kind, EventKind::Stop);
+        assert_eq!(repo.list_before(None, 10, None).unwrap().len(), 3);
+        assert_eq!(repo.list_before(None, 1, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn verify_finds_altered_deleted_and_rewritten_rows() {
+        let repo = make_repo();
+        let exec = |sql: &str| {
+            repo.db
+                .with_conn(|conn| Ok(conn.execute_batch(sql)?))
+                .unwrap()
+        };
+        // Rows from before chaining get hashed in place
+        exec(
+            "INSERT INTO events (profile_id, kind, detail, created_at)
+             VALUES ('a', 'launch', '{}', '2025-01-01T00:00:00+00:00')",
+        );
+        repo.db.with_conn(chain_unhashed).unwrap();
+        for kind in [EventKind::Stop, EventKind::Launch, EventKind::Stop] {
+            repo.record(NewEvent::simple(Some("a"), kind)).unwrap();
+        }
+        let intact = repo.verify(None).unwrap();
+        assert_eq!(intact.checked, 4);
+        assert_eq!(intact.broken, None);
+        let head = intact.head.unwrap();
+        assert_eq!(head.id, 4);
+        assert!(repo.verify(Some(&head)).unwrap().broken.is_none());
+
+        let first_break = |anchor| repo.verify(anchor).unwrap().broken.unwrap();
+        exec("UPDATE events SET severity = 'critical' WHERE id = 2");
+        assert_eq!(
+            first_break(None),
+            BrokenLink {
+                id: 2,
+                kind: ChainBreak::Altered
+            }
+        );
+
+        // A rechained log verifies on its own, but not against the old head
+        exec("UPDATE events SET hash = NULL");
+        repo.db.with_conn(chain_unhashed).unwrap();
+        assert!(repo.verify(None).unwrap().broken.is_none());
+        assert_eq!(first_break(Some(&head)).kind, ChainBreak::Rewritten);
+
+        exec("DELETE FROM events WHERE id = 3");
+        assert_eq!(
+            first_break(None),
+            BrokenLink {
+                id: 4,
+                kind: ChainBreak::Missing
+            }
+        );
+    }
+}
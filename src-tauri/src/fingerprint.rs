@@ -4,24 +4,12 @@
 // using a fast xorshift64* PRNG.  The same seed always produces the same
 // browser identity; different seeds produce statistically uncorrelated ones.
 //
-// ── Quantum-Robust Enhancements ──────────────────────────────────────────────
-//
-// This module implements several quantum-resistant techniques:
-//
-// 1. BLAKE3-based seed expansion: Seeds are expanded through BLAKE3 (a hash
-//    function resistant to Grover's algorithm due to 256-bit output) before
-//    use, providing 128-bit post-quantum security.
-//
-// 2. Multi-layer entropy mixing: Noise values are derived through multiple
-//    rounds of SHA3-256 (Keccak, NIST post-quantum standard) to ensure
-//    statistical independence even under quantum analysis.
-//
-// 3. Lattice-inspired noise distribution: Canvas/WebGL/Audio noise uses
-//    discrete Gaussian sampling similar to lattice-based cryptography,
-//    providing resistance to quantum distinguishers.
-//
-// 4. Entropy health scoring: Runtime verification of entropy quality using
-//    chi-squared tests and Hamming distance analysis.
+// The xorshift stream itself is seeded through `SeedMixer`: BLAKE3 and
+// SHA3-256 over the seed and a domain label, so neighbouring seeds (1, 2, 3 …)
+// don't start neighbouring streams.  The noise levels are drawn from the
+// mixer directly, as a sum of twelve uniform bytes (roughly Gaussian) mapped
+// onto each level's range.  Nothing here is a security property; how much a
+// generated identity stands out is measured by `entropy_report`.
 
 use blake3::Hasher as Blake3Hasher;
 use rand::rngs::SmallRng;
@@ -30,6 +18,10 @@ use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_256};
 use std::collections::HashMap;
 
+use crate::error::{ManifoldError, Result};
+use crate::fonts::{OsRelease, TextRendering, BASELINE, OPTIONAL_SHARE};
+use crate::intl::IntlProfile;
+
 // ── Data types ────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +47,101 @@ impl Default for WebRtcMode {
     }
 }
 
+/// `(color-gamut: …)` media query answer: the widest gamut the display
+/// covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorGamut {
+    #[default]
+    Srgb,
+    /// Display P3 — Retina Macs and wide-gamut monitors.
+    P3,
+}
+
+/// `prefers-color-scheme` answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Profiles created before the preference was seeded all launched dark.
+fn legacy_color_scheme() -> ColorScheme {
+    ColorScheme::Dark
+}
+
+/// Timer coarsening and input-event latency applied by the timing evasion.
+///
+/// Chrome clamps `performance.now()` to 100 µs (5 µs on cross-origin isolated
+/// pages), and a real input event's `timeStamp` lies a little before its
+/// handler runs: the OS and compositor queue it for a millisecond or more.
+/// Events dispatched over CDP are stamped at dispatch, so every handler sees
+/// a near-zero, perfectly regular latency no matter how human the path is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingNoise {
+    /// `performance.now()` / `event.timeStamp` bucket, µs.
+    pub precision_us: u32,
+    /// Upper bound of the seeded delay subtracted from trusted input events'
+    /// `timeStamp`, ms.
+    pub event_jitter_ms: f64,
+}
+
+/// Where the browser's screen sits on the desktop and where its window
+/// opens.  Coordinates are desktop pixels with the primary monitor's
+/// top-left at (0, 0); a monitor left of or above the primary has negative
+/// ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenLayout {
+    /// Monitors on the desktop; above one, `screen.isExtended` is true.
+    pub monitors: u8,
+    /// `screen.availLeft` / `screen.availTop`: the top-left of the
+    /// browser's monitor.
+    pub avail_left: i32,
+    pub avail_top: i32,
+    /// Inclusive ranges each session's `window.screenX` / `screenY` are
+    /// drawn from.
+    pub window_x: (i32, i32),
+    pub window_y: (i32, i32),
+}
+
+/// Where one session's window opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    /// `window.screenX` / `screenY`.
+    pub x: i32,
+    pub y: i32,
+    /// At its monitor's origin and as large as its available area; a
+    /// restored window is the viewport plus `WINDOW_FRAME`.
+    pub maximized: bool,
+}
+
+/// What a restored Chrome window adds around its viewport (borders, tab
+/// strip and toolbar).  Mirrors the outer size in evasions/client-hints.ts.
+pub const WINDOW_FRAME: (u32, u32) = (16, 87);
+
+/// Taskbar height taken off the screen for `availHeight`.  Mirrors
+/// `buildEvasionConfig` in evasions/index.ts.
+pub const TASKBAR_HEIGHT: u32 = 40;
+
+/// Oldest Chrome a profile can be pinned to: the first with UA-CH.
+pub const MIN_PINNED_MAJOR: u32 = 89;
+
+/// Chrome's timer resolution for pages that aren't cross-origin isolated.
+pub const CHROME_TIMER_PRECISION_US: u32 = 100;
+
+/// Version of the algorithm behind `generate`.  Bump it whenever a change
+/// (a new GPU list, a new screen pool) makes a seed generate something else,
+/// and keep the previous path reachable from `generate_with`, so profiles
+/// created before the upgrade still regenerate the identity they have.
+pub const GENERATOR_VERSION: u32 = 1;
+
+/// Fingerprints saved before generators were versioned came from version 1.
+fn first_generator() -> u32 {
+    1
+}
+
 /// Full browser fingerprint configuration.
 ///
 /// Every numeric field that carries "noise" is derived from `seed` at
@@ -63,6 +150,10 @@ impl Default for WebRtcMode {
 pub struct Fingerprint {
     /// Master seed — change this to get a completely different identity.
     pub seed: u64,
+    /// Generator version the fingerprint came from; regenerating from a
+    /// seed (reseed) uses the same one.
+    #[serde(default = "first_generator")]
+    pub generator: u32,
 
     // ── Canvas 2D ────────────────────────────────────────────────────────────
     /// Per-pixel noise intensity injected into getImageData / toDataURL.
@@ -82,6 +173,11 @@ pub struct Fingerprint {
     // ── Fonts ────────────────────────────────────────────────────────────────
     /// The only font families document.fonts.has() will return `true` for.
     pub font_subset: Vec<String>,
+    /// Emoji and glyph rendering of the claimed OS build, for the canvas-text
+    /// spoof.  `None` (profiles created before it existed) leaves text
+    /// rendering native.
+    #[serde(default)]
+    pub text_rendering: Option<TextRendering>,
 
     // ── Navigator / UA ───────────────────────────────────────────────────────
     pub user_agent: String,
@@ -95,8 +191,36 @@ pub struct Fingerprint {
     pub screen_height: u32,
     pub viewport_width: u32,
     pub viewport_height: u32,
+    /// Vary the viewport slightly from session to session (toolbar height,
+    /// maximised or not) instead of reusing it pixel for pixel.
+    #[serde(default)]
+    pub window_variation: bool,
+    /// Multi-monitor desktop and window placement.  `None` keeps a
+    /// maximised window on the primary monitor.
+    #[serde(default)]
+    pub screen_layout: Option<ScreenLayout>,
     pub color_depth: u8,  // 24 | 30
     pub pixel_ratio: f64, // 1.0 | 1.25 | 1.5 | 2.0
+    #[serde(default)]
+    pub color_gamut: ColorGamut,
+    /// `(dynamic-range: high)` / `(video-dynamic-range: high)` answer.
+    #[serde(default)]
+    pub hdr: bool,
+
+    // ── Media preferences ────────────────────────────────────────────────────
+    #[serde(default = "legacy_color_scheme")]
+    pub prefers_color_scheme: ColorScheme,
+    /// `(prefers-reduced-motion: reduce)` — animations turned off in the OS.
+    #[serde(default)]
+    pub prefers_reduced_motion: bool,
+    /// `(forced-colors: active)` — a Windows contrast theme.
+    #[serde(default)]
+    pub forced_colors: bool,
+
+    // ── Timing ───────────────────────────────────────────────────────────────
+    /// `None` (profiles created before it existed) leaves timers native.
+    #[serde(default)]
+    pub timing_noise: Option<TimingNoise>,
 
     // ── WebRTC ───────────────────────────────────────────────────────────────
     pub webrtc_mode: WebRtcMode,
@@ -108,6 +232,10 @@ pub struct Fingerprint {
     // ── Timezone / locale ────────────────────────────────────────────────────
     pub timezone: String, // IANA, e.g. "America/New_York"
     pub locale: String,   // BCP-47, e.g. "en-US"
+    /// Intl data of `locale` for the Intl evasion.  `None` on profiles
+    /// created before it existed; re-derived whenever the locale changes.
+    #[serde(default)]
+    pub intl: Option<IntlProfile>,
 
     // ── UA-CH (User-Agent Client Hints) ──────────────────────────────────────
     pub ua_brands: Vec<UaBrand>,
@@ -116,24 +244,431 @@ pub struct Fingerprint {
     pub ua_platform_version: String, // "15.0.0"
     pub ua_architecture: String,     // "x86"
     pub ua_bitness: String,          // "64"
+    /// UA reduction: `user_agent` carries the frozen `<major>.0.0.0` Chrome
+    /// version and macOS token current Chrome sends, and the full version
+    /// lives in `ua_full_version` for the high-entropy hints only.
+    #[serde(default)]
+    pub ua_reduction: bool,
+    /// The full Chrome version (`"136.0.7103.114"`) while `ua_reduction` is
+    /// on; otherwise it is read from `user_agent`.
+    #[serde(default)]
+    pub ua_full_version: Option<String>,
 
     // ── Permissions API ──────────────────────────────────────────────────────
     /// Maps PermissionName → PermissionState for navigator.permissions.query().
     pub permissions: HashMap<String, String>,
 }
 
+/// Which parts of the device identity survive a reseed.  Everything not kept
+/// (noise levels, Chrome build, WebRTC hostnames, permissions …) is taken
+/// from the new seed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReseedOptions {
+    /// Platform, OS version, font list and CPU/memory.  The Chrome version
+    /// in the UA string still rotates.
+    pub keep_os: bool,
+    /// WebGL vendor/renderer.  GPUs are OS-specific, so this implies `keep_os`.
+    pub keep_gpu: bool,
+    /// Locale, Accept-Language and timezone.
+    pub keep_locale_tz: bool,
+    /// Screen, viewport, colour depth and pixel ratio.
+    pub keep_screen: bool,
+    /// Generate with the current generator instead of the one `fp` came
+    /// from.  Opting in moves a profile to the newest device pools.
+    pub latest_generator: bool,
+}
+
+// ── UA helpers ────────────────────────────────────────────────────────────────
+
+/// The full Chrome version (`"131.0.6778.85"`) embedded in a UA string.
+pub(crate) fn chrome_version(user_agent: &str) -> Option<&str> {
+    let rest = user_agent.split("Chrome/").nth(1)?;
+    rest.split_whitespace().next()
+}
+
+/// `user_agent` with its Chrome version replaced by `version`.
+pub(crate) fn with_chrome_version(user_agent: &str, version: &str) -> String {
+    match chrome_version(user_agent) {
+        Some(old) => user_agent.replacen(&format!("Chrome/{old}"), &format!("Chrome/{version}"), 1),
+        None => user_agent.to_string(),
+    }
+}
+
+/// The macOS token a reduced UA always shows, whatever the real release.
+const REDUCED_MAC_TOKEN: &str = "Intel Mac OS X 10_15_7";
+
+/// Display scale factors Windows and Linux offer; macOS only has 1 and 2.
+const SCALE_FACTORS: &[f64] = &[1.0, 1.25, 1.5, 1.75, 2.0, 2.25, 2.5, 3.0];
+
+/// `user_agent` in the reduced format: Chrome version `<major>.0.0.0` and
+/// the frozen macOS token.  Windows and Linux tokens are frozen already.
+pub(crate) fn reduced_user_agent(user_agent: &str) -> String {
+    let mut ua = match chrome_version(user_agent).and_then(|v| v.split('.').next()) {
+        Some(major) => with_chrome_version(user_agent, &format!("{major}.0.0.0")),
+        None => user_agent.to_string(),
+    };
+    if let Some(start) = ua.find("Intel Mac OS X ") {
+        let end = ua[start..].find(')').map_or(ua.len(), |i| start + i);
+        ua.replace_range(start..end, REDUCED_MAC_TOKEN);
+    }
+    ua
+}
+
+#[cfg(test)]
+impl Fingerprint {
+    /// The full Chrome version, wherever the profile keeps it.
+    fn chrome_full_version(&self) -> Option<&str> {
+        match &self.ua_full_version {
+            Some(v) if self.ua_reduction => Some(v),
+            _ => chrome_version(&self.user_agent),
+        }
+    }
+}
+
 // ── Orchestrator ──────────────────────────────────────────────────────────────
 
+/// GEO_LOCALE_MAP: the (locale, accept_language, timezone) combinations
+/// `enforce_geo` picks from for an ISO-3166-1 alpha-2 country code; empty
+/// for countries it leaves alone.  Large countries list several timezones.
+/// `enforce_geo` indexes into a country's list by seed, so a row added to it
+/// moves some seeds to another row the next time they are enforced.
+pub fn geo_locale_map(cc: &str) -> &'static [(&'static str, &'static str, &'static str)] {
+    match cc {
+        "US" => &[
+            ("en-US", "en-US,en;q=0.9", "America/New_York"),
+            ("en-US", "en-US,en;q=0.9", "America/Chicago"),
+            ("en-US", "en-US,en;q=0.9", "America/Los_Angeles"),
+            ("en-US", "en-US,en;q=0.9", "America/Denver"),
+        ],
+        "GB" => &[("en-GB", "en-GB,en;q=0.9", "Europe/London")],
+        "CA" => &[
+            ("en-CA", "en-CA,en;q=0.9,fr-CA;q=0.8", "America/Toronto"),
+            ("en-CA", "en-CA,en;q=0.9", "America/Vancouver"),
+            ("fr-CA", "fr-CA,fr;q=0.9,en-CA;q=0.8", "America/Montreal"),
+            ("en-CA", "en-CA,en;q=0.9", "America/Edmonton"),
+            ("en-CA", "en-CA,en;q=0.9", "America/Halifax"),
+        ],
+        "AU" => &[
+            ("en-AU", "en-AU,en;q=0.9", "Australia/Sydney"),
+            ("en-AU", "en-AU,en;q=0.9", "Australia/Melbourne"),
+            ("en-AU", "en-AU,en;q=0.9", "Australia/Brisbane"),
+            ("en-AU", "en-AU,en;q=0.9", "Australia/Perth"),
+            ("en-AU", "en-AU,en;q=0.9", "Australia/Adelaide"),
+        ],
+        "DE" => &[("de-DE", "de-DE,de;q=0.9,en;q=0.8", "Europe/Berlin")],
+        "FR" => &[("fr-FR", "fr-FR,fr;q=0.9,en;q=0.8", "Europe/Paris")],
+        "ES" => &[("es-ES", "es-ES,es;q=0.9,en;q=0.8", "Europe/Madrid")],
+        "IT" => &[("it-IT", "it-IT,it;q=0.9,en;q=0.8", "Europe/Rome")],
+        "NL" => &[("nl-NL", "nl-NL,nl;q=0.9,en;q=0.8", "Europe/Amsterdam")],
+        "PL" => &[("pl-PL", "pl-PL,pl;q=0.9,en;q=0.8", "Europe/Warsaw")],
+        "BR" => &[
+            ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Sao_Paulo"),
+            ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Fortaleza"),
+            ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Manaus"),
+            ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Bahia"),
+        ],
+        "JP" => &[("ja-JP", "ja-JP,ja;q=0.9,en;q=0.8", "Asia/Tokyo")],
+        "KR" => &[("ko-KR", "ko-KR,ko;q=0.9,en;q=0.8", "Asia/Seoul")],
+        "IN" => &[("en-IN", "en-IN,en;q=0.9,hi;q=0.8", "Asia/Kolkata")],
+        "SG" => &[("en-SG", "en-SG,en;q=0.9", "Asia/Singapore")],
+        "HK" => &[("zh-HK", "zh-HK,zh;q=0.9,en;q=0.8", "Asia/Hong_Kong")],
+        "SE" => &[("sv-SE", "sv-SE,sv;q=0.9,en;q=0.8", "Europe/Stockholm")],
+        "NO" => &[("nb-NO", "nb-NO,nb;q=0.9,en;q=0.8", "Europe/Oslo")],
+        "CH" => &[
+            ("de-CH", "de-CH,de;q=0.9,en;q=0.8", "Europe/Zurich"),
+            ("fr-CH", "fr-CH,fr;q=0.9,de-CH;q=0.8", "Europe/Zurich"),
+        ],
+        "MX" => &[
+            ("es-MX", "es-MX,es;q=0.9,en;q=0.8", "America/Mexico_City"),
+            ("es-MX", "es-MX,es;q=0.9,en;q=0.8", "America/Monterrey"),
+            ("es-MX", "es-MX,es;q=0.9,en;q=0.8", "America/Tijuana"),
+            ("es-MX", "es-MX,es;q=0.9,en;q=0.8", "America/Cancun"),
+        ],
+        // Europe
+        "IE" => &[("en-IE", "en-IE,en;q=0.9", "Europe/Dublin")],
+        "AT" => &[("de-AT", "de-AT,de;q=0.9,en;q=0.8", "Europe/Vienna")],
+        "BE" => &[
+            ("nl-BE", "nl-BE,nl;q=0.9,en;q=0.8", "Europe/Brussels"),
+            ("fr-BE", "fr-BE,fr;q=0.9,en;q=0.8", "Europe/Brussels"),
+        ],
+        "PT" => &[("pt-PT", "pt-PT,pt;q=0.9,en;q=0.8", "Europe/Lisbon")],
+        "DK" => &[("da-DK", "da-DK,da;q=0.9,en;q=0.8", "Europe/Copenhagen")],
+        "FI" => &[("fi-FI", "fi-FI,fi;q=0.9,en;q=0.8", "Europe/Helsinki")],
+        "CZ" => &[("cs-CZ", "cs-CZ,cs;q=0.9,en;q=0.8", "Europe/Prague")],
+        "SK" => &[("sk-SK", "sk-SK,sk;q=0.9,en;q=0.8", "Europe/Bratislava")],
+        "HU" => &[("hu-HU", "hu-HU,hu;q=0.9,en;q=0.8", "Europe/Budapest")],
+        "RO" => &[("ro-RO", "ro-RO,ro;q=0.9,en;q=0.8", "Europe/Bucharest")],
+        "BG" => &[("bg-BG", "bg-BG,bg;q=0.9,en;q=0.8", "Europe/Sofia")],
+        "HR" => &[("hr-HR", "hr-HR,hr;q=0.9,en;q=0.8", "Europe/Zagreb")],
+        "RS" => &[("sr-RS", "sr-RS,sr;q=0.9,en;q=0.8", "Europe/Belgrade")],
+        "SI" => &[("sl-SI", "sl-SI,sl;q=0.9,en;q=0.8", "Europe/Ljubljana")],
+        "GR" => &[("el-GR", "el-GR,el;q=0.9,en;q=0.8", "Europe/Athens")],
+        "LT" => &[("lt-LT", "lt-LT,lt;q=0.9,en;q=0.8", "Europe/Vilnius")],
+        "LV" => &[("lv-LV", "lv-LV,lv;q=0.9,en;q=0.8", "Europe/Riga")],
+        "EE" => &[("et-EE", "et-EE,et;q=0.9,en;q=0.8", "Europe/Tallinn")],
+        "UA" => &[("uk-UA", "uk-UA,uk;q=0.9,en;q=0.8", "Europe/Kiev")],
+        "TR" => &[("tr-TR", "tr-TR,tr;q=0.9,en;q=0.8", "Europe/Istanbul")],
+        "RU" => &[
+            ("ru-RU", "ru-RU,ru;q=0.9,en;q=0.8", "Europe/Moscow"),
+            ("ru-RU", "ru-RU,ru;q=0.9,en;q=0.8", "Europe/Samara"),
+            ("ru-RU", "ru-RU,ru;q=0.9,en;q=0.8", "Asia/Yekaterinburg"),
+            ("ru-RU", "ru-RU,ru;q=0.9,en;q=0.8", "Asia/Novosibirsk"),
+            ("ru-RU", "ru-RU,ru;q=0.9,en;q=0.8", "Asia/Vladivostok"),
+        ],
+        // Americas
+        "AR" => &[(
+            "es-AR",
+            "es-AR,es;q=0.9,en;q=0.8",
+            "America/Argentina/Buenos_Aires",
+        )],
+        "CL" => &[("es-CL", "es-CL,es;q=0.9,en;q=0.8", "America/Santiago")],
+        "CO" => &[("es-CO", "es-CO,es;q=0.9,en;q=0.8", "America/Bogota")],
+        "PE" => &[("es-PE", "es-PE,es;q=0.9,en;q=0.8", "America/Lima")],
+        "VE" => &[("es-VE", "es-VE,es;q=0.9,en;q=0.8", "America/Caracas")],
+        "EC" => &[("es-EC", "es-EC,es;q=0.9,en;q=0.8", "America/Guayaquil")],
+        "UY" => &[("es-UY", "es-UY,es;q=0.9,en;q=0.8", "America/Montevideo")],
+        // Asia / Pacific
+        "NZ" => &[("en-NZ", "en-NZ,en;q=0.9", "Pacific/Auckland")],
+        "CN" => &[("zh-CN", "zh-CN,zh;q=0.9,en;q=0.8", "Asia/Shanghai")],
+        "TW" => &[("zh-TW", "zh-TW,zh;q=0.9,en;q=0.8", "Asia/Taipei")],
+        "TH" => &[("th-TH", "th-TH,th;q=0.9,en;q=0.8", "Asia/Bangkok")],
+        "VN" => &[("vi-VN", "vi-VN,vi;q=0.9,en;q=0.8", "Asia/Ho_Chi_Minh")],
+        "ID" => &[
+            ("id-ID", "id-ID,id;q=0.9,en;q=0.8", "Asia/Jakarta"),
+            ("id-ID", "id-ID,id;q=0.9,en;q=0.8", "Asia/Makassar"),
+        ],
+        "MY" => &[
+            ("en-MY", "en-MY,en;q=0.9,ms;q=0.8", "Asia/Kuala_Lumpur"),
+            ("ms-MY", "ms-MY,ms;q=0.9,en;q=0.8", "Asia/Kuala_Lumpur"),
+        ],
+        "PH" => &[("en-PH", "en-PH,en;q=0.9,fil;q=0.8", "Asia/Manila")],
+        "PK" => &[("en-PK", "en-PK,en;q=0.9,ur;q=0.8", "Asia/Karachi")],
+        "BD" => &[("bn-BD", "bn-BD,bn;q=0.9,en;q=0.8", "Asia/Dhaka")],
+        "KZ" => &[
+            ("ru-KZ", "ru-KZ,ru;q=0.9,kk;q=0.8,en;q=0.7", "Asia/Almaty"),
+            ("ru-KZ", "ru-KZ,ru;q=0.9,kk;q=0.8,en;q=0.7", "Asia/Aqtobe"),
+        ],
+        // Middle East / Africa
+        "IL" => &[("he-IL", "he-IL,he;q=0.9,en;q=0.8", "Asia/Jerusalem")],
+        "SA" => &[("ar-SA", "ar-SA,ar;q=0.9,en;q=0.8", "Asia/Riyadh")],
+        "AE" => &[
+            ("en-AE", "en-AE,en;q=0.9,ar;q=0.8", "Asia/Dubai"),
+            ("ar-AE", "ar-AE,ar;q=0.9,en;q=0.8", "Asia/Dubai"),
+        ],
+        "EG" => &[("ar-EG", "ar-EG,ar;q=0.9,en;q=0.8", "Africa/Cairo")],
+        "MA" => &[
+            ("fr-MA", "fr-MA,fr;q=0.9,ar;q=0.8", "Africa/Casablanca"),
+            ("ar-MA", "ar-MA,ar;q=0.9,fr;q=0.8", "Africa/Casablanca"),
+        ],
+        "ZA" => &[("en-ZA", "en-ZA,en;q=0.9", "Africa/Johannesburg")],
+        "NG" => &[("en-NG", "en-NG,en;q=0.9", "Africa/Lagos")],
+        "KE" => &[("en-KE", "en-KE,en;q=0.9,sw;q=0.8", "Africa/Nairobi")],
+        _ => &[],
+    }
+}
+
+/// Regions of the countries that span several timezones:
+/// (country, region code, region name, timezone).  Codes are the ISO 3166-2
+/// suffixes ip-api reports as `region`.  Each region gets its dominant zone.
+const REGION_TIMEZONES: &[(&str, &str, &str, &str)] = &[
+    ("US", "AL", "Alabama", "America/Chicago"),
+    ("US", "AK", "Alaska", "America/Anchorage"),
+    ("US", "AZ", "Arizona", "America/Phoenix"),
+    ("US", "AR", "Arkansas", "America/Chicago"),
+    ("US", "CA", "California", "America/Los_Angeles"),
+    ("US", "CO", "Colorado", "America/Denver"),
+    ("US", "CT", "Connecticut", "America/New_York"),
+    ("US", "DE", "Delaware", "America/New_York"),
+    ("US", "DC", "District of Columbia", "America/New_York"),
+    ("US", "FL", "Florida", "America/New_York"),
+    ("US", "GA", "Georgia", "America/New_York"),
+    ("US", "HI", "Hawaii", "Pacific/Honolulu"),
+    ("US", "ID", "Idaho", "America/Boise"),
+    ("US", "IL", "Illinois", "America/Chicago"),
+    ("US", "IN", "Indiana", "America/Indiana/Indianapolis"),
+    ("US", "IA", "Iowa", "America/Chicago"),
+    ("US", "KS", "Kansas", "America/Chicago"),
+    ("US", "KY", "Kentucky", "America/New_York"),
+    ("US", "LA", "Louisiana", "America/Chicago"),
+    ("US", "ME", "Maine", "America/New_York"),
+    ("US", "MD", "Maryland", "America/New_York"),
+    ("US", "MA", "Massachusetts", "America/New_York"),
+    ("US", "MI", "Michigan", "America/Detroit"),
+    ("US", "MN", "Minnesota", "America/Chicago"),
+    ("US", "MS", "Mississippi", "America/Chicago"),
+    ("US", "MO", "Missouri", "America/Chicago"),
+    ("US", "MT", "Montana", "America/Denver"),
+    ("US", "NE", "Nebraska", "America/Chicago"),
+    ("US", "NV", "Nevada", "America/Los_Angeles"),
+    ("US", "NH", "New Hampshire", "America/New_York"),
+    ("US", "NJ", "New Jersey", "America/New_York"),
+    ("US", "NM", "New Mexico", "America/Denver"),
+    ("US", "NY", "New York", "America/New_York"),
+    ("US", "NC", "North Carolina", "America/New_York"),
+    ("US", "ND", "North Dakota", "America/Chicago"),
+    ("US", "OH", "Ohio", "America/New_York"),
+    ("US", "OK", "Oklahoma", "America/Chicago"),
+    ("US", "OR", "Oregon", "America/Los_Angeles"),
+    ("US", "PA", "Pennsylvania", "America/New_York"),
+    ("US", "RI", "Rhode Island", "America/New_York"),
+    ("US", "SC", "South Carolina", "America/New_York"),
+    ("US", "SD", "South Dakota", "America/Chicago"),
+    ("US", "TN", "Tennessee", "America/Chicago"),
+    ("US", "TX", "Texas", "America/Chicago"),
+    ("US", "UT", "Utah", "America/Denver"),
+    ("US", "VT", "Vermont", "America/New_York"),
+    ("US", "VA", "Virginia", "America/New_York"),
+    ("US", "WA", "Washington", "America/Los_Angeles"),
+    ("US", "WV", "West Virginia", "America/New_York"),
+    ("US", "WI", "Wisconsin", "America/Chicago"),
+    ("US", "WY", "Wyoming", "America/Denver"),
+    ("CA", "AB", "Alberta", "America/Edmonton"),
+    ("CA", "BC", "British Columbia", "America/Vancouver"),
+    ("CA", "MB", "Manitoba", "America/Winnipeg"),
+    ("CA", "NB", "New Brunswick", "America/Moncton"),
+    ("CA", "NL", "Newfoundland and Labrador", "America/St_Johns"),
+    ("CA", "NS", "Nova Scotia", "America/Halifax"),
+    ("CA", "NT", "Northwest Territories", "America/Yellowknife"),
+    ("CA", "NU", "Nunavut", "America/Iqaluit"),
+    ("CA", "ON", "Ontario", "America/Toronto"),
+    ("CA", "PE", "Prince Edward Island", "America/Halifax"),
+    ("CA", "QC", "Quebec", "America/Montreal"),
+    ("CA", "SK", "Saskatchewan", "America/Regina"),
+    ("CA", "YT", "Yukon", "America/Whitehorse"),
+    (
+        "AU",
+        "ACT",
+        "Australian Capital Territory",
+        "Australia/Sydney",
+    ),
+    ("AU", "NSW", "New South Wales", "Australia/Sydney"),
+    ("AU", "NT", "Northern Territory", "Australia/Darwin"),
+    ("AU", "QLD", "Queensland", "Australia/Brisbane"),
+    ("AU", "SA", "South Australia", "Australia/Adelaide"),
+    ("AU", "TAS", "Tasmania", "Australia/Hobart"),
+    ("AU", "VIC", "Victoria", "Australia/Melbourne"),
+    ("AU", "WA", "Western Australia", "Australia/Perth"),
+];
+
+/// Cities a proxy provider may name instead of a region:
+/// (country, region code, city, timezone).  A city only counts within its
+/// region, so "Portland, ME" isn't taken for Oregon's Portland.  El Paso is
+/// why cities are looked at once the region is known: it's on Mountain time
+/// unlike the rest of Texas.
+const CITY_TIMEZONES: &[(&str, &str, &str, &str)] = &[
+    ("US", "NY", "New York", "America/New_York"),
+    ("US", "CA", "Los Angeles", "America/Los_Angeles"),
+    ("US", "IL", "Chicago", "America/Chicago"),
+    ("US", "TX", "Houston", "America/Chicago"),
+    ("US", "AZ", "Phoenix", "America/Phoenix"),
+    ("US", "PA", "Philadelphia", "America/New_York"),
+    ("US", "TX", "San Antonio", "America/Chicago"),
+    ("US", "CA", "San Diego", "America/Los_Angeles"),
+    ("US", "TX", "Dallas", "America/Chicago"),
+    ("US", "CA", "San Jose", "America/Los_Angeles"),
+    ("US", "TX", "Austin", "America/Chicago"),
+    ("US", "FL", "Jacksonville", "America/New_York"),
+    ("US", "CA", "San Francisco", "America/Los_Angeles"),
+    ("US", "OH", "Columbus", "America/New_York"),
+    ("US", "IN", "Indianapolis", "America/Indiana/Indianapolis"),
+    ("US", "WA", "Seattle", "America/Los_Angeles"),
+    ("US", "CO", "Denver", "America/Denver"),
+    ("US", "MA", "Boston", "America/New_York"),
+    ("US", "TX", "El Paso", "America/Denver"),
+    ("US", "TN", "Nashville", "America/Chicago"),
+    ("US", "MI", "Detroit", "America/Detroit"),
+    ("US", "OR", "Portland", "America/Los_Angeles"),
+    ("US", "ME", "Portland", "America/New_York"),
+    ("US", "NV", "Las Vegas", "America/Los_Angeles"),
+    ("US", "GA", "Atlanta", "America/New_York"),
+    ("US", "FL", "Miami", "America/New_York"),
+    ("US", "MN", "Minneapolis", "America/Chicago"),
+    ("US", "UT", "Salt Lake City", "America/Denver"),
+    ("US", "ID", "Boise", "America/Boise"),
+    ("US", "HI", "Honolulu", "Pacific/Honolulu"),
+    ("US", "AK", "Anchorage", "America/Anchorage"),
+    ("CA", "ON", "Toronto", "America/Toronto"),
+    ("CA", "QC", "Montreal", "America/Montreal"),
+    ("CA", "BC", "Vancouver", "America/Vancouver"),
+    ("CA", "AB", "Calgary", "America/Edmonton"),
+    ("CA", "AB", "Edmonton", "America/Edmonton"),
+    ("CA", "ON", "Ottawa", "America/Toronto"),
+    ("CA", "MB", "Winnipeg", "America/Winnipeg"),
+    ("CA", "NS", "Halifax", "America/Halifax"),
+    ("AU", "NSW", "Sydney", "Australia/Sydney"),
+    ("AU", "VIC", "Melbourne", "Australia/Melbourne"),
+    ("AU", "QLD", "Brisbane", "Australia/Brisbane"),
+    ("AU", "WA", "Perth", "Australia/Perth"),
+    ("AU", "SA", "Adelaide", "Australia/Adelaide"),
+    ("AU", "ACT", "Canberra", "Australia/Sydney"),
+    ("AU", "TAS", "Hobart", "Australia/Hobart"),
+    ("AU", "NT", "Darwin", "Australia/Darwin"),
+];
+
+/// The code and timezone of the region `part` names by code ("UT",
+/// "US-UT") or name.
+fn find_region(cc: &str, part: &str) -> Option<(&'static str, &'static str)> {
+    let code = part
+        .split_once('-')
+        .filter(|(prefix, _)| prefix.eq_ignore_ascii_case(cc))
+        .map_or(part, |(_, code)| code);
+    REGION_TIMEZONES
+        .iter()
+        .find(|(c, rc, name, _)| {
+            *c == cc && (rc.eq_ignore_ascii_case(code) || name.eq_ignore_ascii_case(part))
+        })
+        .map(|&(_, rc, _, tz)| (rc, tz))
+}
+
+/// The timezone of `city`, within the region coded `region` when given.  A
+/// city in several regions on different zones ("Portland") is ambiguous
+/// without one.
+fn city_timezone(cc: &str, city: &str, region: Option<&str>) -> Option<&'static str> {
+    let mut zones = CITY_TIMEZONES
+        .iter()
+        .filter(|(c, rc, name, _)| {
+            *c == cc && name.eq_ignore_ascii_case(city) && region.is_none_or(|r| *rc == r)
+        })
+        .map(|&(_, _, _, tz)| tz);
+    let first = zones.next()?;
+    zones.all(|tz| tz == first).then_some(first)
+}
+
+/// The timezone of a proxy's region within `cc`: a region code ("UT", or
+/// "US-UT"), a region name ("Utah"), a city ("Salt Lake City") or
+/// "City, Region".  `None` for regions and countries the tables don't know.
+pub fn region_timezone(cc: &str, region: &str) -> Option<&'static str> {
+    let cc = cc.trim().to_uppercase();
+    let parts: Vec<&str> = region
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    match parts.as_slice() {
+        // The region decides; its city only where the two zones differ
+        [city, region, ..] => match find_region(&cc, region) {
+            Some((code, tz)) => Some(city_timezone(&cc, city, Some(code)).unwrap_or(tz)),
+            None => city_timezone(&cc, city, None),
+        },
+        [part] => find_region(&cc, part)
+            .map(|(_, tz)| tz)
+            .or_else(|| city_timezone(&cc, part, None)),
+        [] => None,
+    }
+}
+
 pub struct FingerprintOrchestrator;
 
-/// Quantum-robust entropy mixer using BLAKE3 and SHA3
-struct QuantumEntropy {
+/// Seed expansion for the generator's RNG and noise levels.  The domain
+/// label and hash chain are part of every seed's output: changing them
+/// changes what each seed generates.
+struct SeedMixer {
     blake3_state: Blake3Hasher,
     round: u64,
 }
 
-impl QuantumEntropy {
-    /// Create a new quantum-robust entropy source from a seed
+impl SeedMixer {
     fn new(seed: u64) -> Self {
         let mut hasher = Blake3Hasher::new();
         hasher.update(&seed.to_le_bytes());
@@ -144,7 +679,6 @@ impl QuantumEntropy {
         }
     }
 
-    /// Expand seed to 256-bit quantum-resistant key material
     fn expand_seed(&mut self, domain: &[u8]) -> [u8; 32] {
         self.blake3_state.update(domain);
         self.blake3_state.update(&self.round.to_le_bytes());
@@ -152,10 +686,8 @@ impl QuantumEntropy {
         *self.blake3_state.finalize().as_bytes()
     }
 
-    /// Generate a u64 with quantum-resistant entropy mixing
     fn next_u64(&mut self, domain: &str) -> u64 {
         let expanded = self.expand_seed(domain.as_bytes());
-        // Apply SHA3-256 for additional quantum resistance
         let mut sha3 = Sha3_256::new();
         sha3.update(&expanded);
         sha3.update(&self.round.to_le_bytes());
@@ -163,73 +695,42 @@ impl QuantumEntropy {
         u64::from_le_bytes(hash[0..8].try_into().unwrap())
     }
 
-    /// Generate noise value with lattice-inspired discrete Gaussian sampling
-    /// Provides resistance to quantum distinguishing attacks
-    fn quantum_noise(&mut self, domain: &str, min: f64, max: f64) -> f64 {
-        // Sample from a discrete approximation of Gaussian distribution
-        // This is similar to techniques used in lattice-based cryptography
+    /// A value in `[min, max]`, most likely near the middle: the sum of
+    /// twelve uniform bytes (central limit), clamped to ±3σ.
+    fn gaussian_noise(&mut self, domain: &str, min: f64, max: f64) -> f64 {
         let mut sum: i64 = 0;
         for i in 0..12 {
             let val = self.next_u64(&format!("{}-gauss-{}", domain, i));
             sum += (val % 256) as i64;
         }
-        // Central limit theorem: sum of uniform -> approximately Gaussian
         let normalized = (sum as f64 - 1536.0) / 256.0; // Mean 0, approx std 1
         let clamped = normalized.clamp(-3.0, 3.0);
-        // Map to [min, max] range
         let t = (clamped + 3.0) / 6.0; // Now in [0, 1]
         min + t * (max - min)
     }
-
-    /// Generate entropy health score (0-100)
-    /// Measures statistical quality of generated values
-    fn entropy_health_score(&mut self) -> u8 {
-        let mut samples = [0u64; 64];
-        for i in 0..64 {
-            samples[i] = self.next_u64(&format!("health-{}", i));
-        }
-
-        // Chi-squared test for uniformity
-        let mut buckets = [0u32; 16];
-        for sample in &samples {
-            let bucket = (*sample % 16) as usize;
-            buckets[bucket] += 1;
-        }
-        let expected = 4.0f64;
-        let chi_sq: f64 = buckets
-            .iter()
-            .map(|&b| (b as f64 - expected).powi(2) / expected)
-            .sum();
-
-        // Good entropy: chi_sq should be < 25 (df=15, p=0.05)
-        let uniformity_score = if chi_sq < 25.0 {
-            50
-        } else {
-            (50.0 * (1.0 - (chi_sq - 25.0) / 50.0).max(0.0)) as u8
-        };
-
-        // Hamming distance analysis
-        let mut total_hamming = 0u32;
-        for i in 0..63 {
-            total_hamming += (samples[i] ^ samples[i + 1]).count_ones();
-        }
-        let avg_hamming = total_hamming as f64 / 63.0;
-        // Ideal: ~32 bits differ on average for 64-bit values
-        let hamming_score = (50.0 * (1.0 - (avg_hamming - 32.0).abs() / 32.0)) as u8;
-
-        uniformity_score + hamming_score
-    }
 }
 
 impl FingerprintOrchestrator {
-    /// Generate a complete, internally-consistent fingerprint from `seed`.
-    /// Uses quantum-robust entropy expansion for all randomness.
+    /// Generate a complete, internally-consistent fingerprint from `seed`
+    /// with the current generator.
     pub fn generate(seed: u64) -> Fingerprint {
-        // Initialize quantum-robust entropy source
-        let mut qe = QuantumEntropy::new(seed);
+        Self::generate_v1(seed)
+    }
+
+    /// Generate from `seed` exactly as generator `version` does.
+    pub fn generate_with(seed: u64, version: u32) -> Result<Fingerprint> {
+        match version {
+            1 => Ok(Self::generate_v1(seed)),
+            _ => Err(ManifoldError::InvalidArg(format!(
+                "fingerprint generator {version} is unknown to this build (newest is {GENERATOR_VERSION})"
+            ))),
+        }
+    }
 
-        // Create RNG seeded from quantum-expanded material
-        let expanded_seed = qe.next_u64("rng-seed");
+    /// Generator 1.
+    fn generate_v1(seed: u64) -> Fingerprint {
+        let mut mixer = SeedMixer::new(seed);
+        let expanded_seed = mixer.next_u64("rng-seed");
         let mut rng = SmallRng::seed_from_u64(expanded_seed);
 
         // ── Operating system & platform ───────────────────────────────────────
@@ -248,7 +749,7 @@ impl FingerprintOrchestrator {
             Self::pick_screen(&mut rng);
 
         // ── Fonts ─────────────────────────────────────────────────────────────
-        let font_subset = Self::build_font_subset(&mut rng, &os);
+        let font_subset = Self::build_font_subset(&mut rng, &ua_platform, &ua_platform_version);
 
         // ── Locale / timezone ─────────────────────────────────────────────────
         let (locale, accept_language, timezone) = Self::pick_locale(&mut rng);
@@ -265,25 +766,46 @@ impl FingerprintOrchestrator {
             .get(rng.gen_range(0..6))
             .unwrap_or(&4.0);
 
-        // ── Noise levels (Quantum-robust) ────────────────────────────────────
-        // Use lattice-inspired discrete Gaussian sampling for noise values.
-        // This provides resistance to quantum distinguishing attacks while
-        // keeping noise subtle enough for CAPTCHAs to work.
-        let canvas_noise = qe.quantum_noise("canvas", 0.01, 0.15);
-        let webgl_noise = qe.quantum_noise("webgl", 0.01, 0.10);
-        let audio_noise = qe.quantum_noise("audio", 0.001, 0.010);
+        // ── Noise levels ─────────────────────────────────────────────────────
+        // Clustered mid-range, subtle enough for CAPTCHAs to keep working.
+        let canvas_noise = mixer.gaussian_noise("canvas", 0.01, 0.15);
+        let webgl_noise = mixer.gaussian_noise("webgl", 0.01, 0.10);
+        let audio_noise = mixer.gaussian_noise("audio", 0.001, 0.010);
 
         // ── Permissions ──────────────────────────────────────────────────────
         let permissions = Self::default_permissions(&mut rng);
 
+        // ── Text rendering ───────────────────────────────────────────────────
+        let text_rendering = Some(TextRendering::for_platform(
+            &ua_platform,
+            &ua_platform_version,
+            1.0 + rng.gen_range(-0.0005..=0.0005),
+            rng.gen_range(0.0..=0.25),
+        ));
+
+        // ── Colour gamut / HDR ───────────────────────────────────────────────
+        let (color_gamut, hdr) = Self::pick_color_caps(&mut rng, os, screen_width, pixel_ratio);
+
+        // ── Media preferences ────────────────────────────────────────────────
+        let (prefers_color_scheme, prefers_reduced_motion, forced_colors) =
+            Self::pick_media_prefs(&mut rng, os);
+
+        // ── Timing ───────────────────────────────────────────────────────────
+        let timing_noise = Some(TimingNoise {
+            precision_us: CHROME_TIMER_PRECISION_US,
+            event_jitter_ms: (rng.gen_range(0.8..=3.0_f64) * 100.0).round() / 100.0,
+        });
+
         Fingerprint {
             seed,
+            generator: 1,
             canvas_noise,
             webgl_vendor,
             webgl_renderer,
             webgl_noise,
             audio_noise,
             font_subset,
+            text_rendering,
             user_agent,
             platform,
             accept_language,
@@ -293,12 +815,21 @@ impl FingerprintOrchestrator {
             screen_height,
             viewport_width,
             viewport_height,
+            window_variation: false,
+            screen_layout: None,
             color_depth: 24,
             pixel_ratio,
+            color_gamut,
+            hdr,
+            prefers_color_scheme,
+            prefers_reduced_motion,
+            forced_colors,
+            timing_noise,
             webrtc_mode: WebRtcMode::FakeMdns,
             webrtc_fake_mdns,
             webrtc_fake_ip,
             timezone,
+            intl: Some(IntlProfile::for_locale(&locale)),
             locale,
             ua_brands,
             ua_mobile: false,
@@ -306,6 +837,8 @@ impl FingerprintOrchestrator {
             ua_platform_version,
             ua_architecture,
             ua_bitness,
+            ua_reduction: false,
+            ua_full_version: None,
             permissions,
         }
     }
@@ -317,54 +850,260 @@ impl FingerprintOrchestrator {
         Self::generate(new_seed)
     }
 
-    /// Apply small random deltas to mutable numeric fields without changing the
-    /// seed.  Uses quantum-robust entropy for mutations.
-    #[allow(dead_code)]
-    pub fn mutate(fp: &mut Fingerprint) {
-        let mut qe = QuantumEntropy::new(fp.seed.wrapping_add(1));
-        let delta_canvas = qe.quantum_noise("mutate-canvas", -0.01, 0.01);
-        let delta_webgl = qe.quantum_noise("mutate-webgl", -0.005, 0.005);
-        let delta_audio = qe.quantum_noise("mutate-audio", -0.001, 0.001);
+    /// Regenerate from `new_seed` with `fp`'s generator (or the current one
+    /// with `opts.latest_generator`), carrying over the parts of `fp` selected
+    /// by `opts` so the site-facing device identity stays the same.
+    pub fn reseed_with(
+        fp: &Fingerprint,
+        new_seed: u64,
+        opts: &ReseedOptions,
+    ) -> Result<Fingerprint> {
+        let mut next = if opts.latest_generator {
+            Self::generate(new_seed)
+        } else {
+            Self::generate_with(new_seed, fp.generator)?
+        };
 
-        fp.canvas_noise = (fp.canvas_noise + delta_canvas).clamp(0.005, 0.30);
-        fp.audio_noise = (fp.audio_noise + delta_audio).clamp(0.001, 0.05);
-        fp.webgl_noise = (fp.webgl_noise + delta_webgl).clamp(0.005, 0.15);
+        if opts.keep_os || opts.keep_gpu {
+            if let Some(version) = chrome_version(&next.user_agent) {
+                next.user_agent = with_chrome_version(&fp.user_agent, version);
+            } else {
+                next.user_agent = fp.user_agent.clone();
+            }
+            next.platform = fp.platform.clone();
+            next.ua_platform = fp.ua_platform.clone();
+            next.ua_platform_version = fp.ua_platform_version.clone();
+            next.ua_architecture = fp.ua_architecture.clone();
+            next.ua_bitness = fp.ua_bitness.clone();
+            next.ua_mobile = fp.ua_mobile;
+            next.font_subset = fp.font_subset.clone();
+            // The OS build's rendering is kept; the seeded offsets rotate
+            next.text_rendering = next.text_rendering.map(|t| {
+                TextRendering::for_platform(
+                    &fp.ua_platform,
+                    &fp.ua_platform_version,
+                    t.measure_scale,
+                    t.baseline_shift,
+                )
+            });
+            next.hardware_concurrency = fp.hardware_concurrency;
+            next.device_memory = fp.device_memory;
+            // OS accessibility and appearance settings belong to the machine
+            next.prefers_color_scheme = fp.prefers_color_scheme;
+            next.prefers_reduced_motion = fp.prefers_reduced_motion;
+            next.forced_colors = fp.forced_colors;
+        }
+        if opts.keep_gpu {
+            next.webgl_vendor = fp.webgl_vendor.clone();
+            next.webgl_renderer = fp.webgl_renderer.clone();
+        }
+        if opts.keep_locale_tz {
+            next.locale = fp.locale.clone();
+            next.intl = Some(IntlProfile::for_locale(&fp.locale));
+            next.accept_language = fp.accept_language.clone();
+            next.timezone = fp.timezone.clone();
+        }
+        if opts.keep_screen {
+            next.screen_width = fp.screen_width;
+            next.screen_height = fp.screen_height;
+            next.viewport_width = fp.viewport_width;
+            next.viewport_height = fp.viewport_height;
+            next.color_depth = fp.color_depth;
+            next.pixel_ratio = fp.pixel_ratio;
+            next.color_gamut = fp.color_gamut;
+            next.hdr = fp.hdr;
+            next.screen_layout = fp.screen_layout.clone();
+        }
+        // A format choice rather than part of the identity
+        Self::set_ua_reduction(&mut next, fp.ua_reduction);
+        Ok(next)
     }
 
-    /// Calculate entropy health score for a fingerprint (0-100)
-    /// Higher scores indicate better statistical properties and quantum resistance.
-    #[allow(dead_code)]
-    pub fn entropy_health(seed: u64) -> u8 {
-        let mut qe = QuantumEntropy::new(seed);
-        qe.entropy_health_score()
+    /// Switch the UA string between the reduced format and the full one.
+    /// The full version is kept aside while reduced and put back after; the
+    /// macOS release token stays frozen.
+    pub fn set_ua_reduction(fp: &mut Fingerprint, enabled: bool) {
+        if fp.ua_reduction == enabled {
+            return;
+        }
+        if enabled {
+            fp.ua_full_version = chrome_version(&fp.user_agent).map(str::to_string);
+            fp.user_agent = reduced_user_agent(&fp.user_agent);
+        } else if let Some(full) = fp.ua_full_version.take() {
+            fp.user_agent = with_chrome_version(&fp.user_agent, &full);
+        }
+        fp.ua_reduction = enabled;
+    }
+
+    /// Move the profile to Chrome `major`, rewriting the UA string and the
+    /// UA-CH brand list the way a browser auto-update would.  Minor/build are
+    /// derived from the seed so repeated calls are stable.
+    pub fn set_chrome_major(fp: &mut Fingerprint, major: u32) {
+        let mut mixer = SeedMixer::new(fp.seed ^ major as u64);
+        let minor = mixer.next_u64("chrome-minor") % 10_000;
+        let build = mixer.next_u64("chrome-build") % 1_000;
+        Self::set_chrome_version(fp, major, &format!("{major}.0.{minor}.{build}"));
+    }
+
+    /// Hold the profile on a Chrome version: a major (`"109"`, build from
+    /// the seed) or a full four-part version (`"109.0.5414.120"`).  The UA,
+    /// the UA-CH brands and full-version list move together, and the TLS
+    /// profile follows the UA's major.
+    pub fn pin_chrome_version(fp: &mut Fingerprint, version: &str) -> Result<()> {
+        let parts: Vec<u32> = version
+            .trim()
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()
+            .filter(|parts: &Vec<u32>| parts.len() == 1 || parts.len() == 4)
+            .ok_or_else(|| {
+                ManifoldError::InvalidArg(format!(
+                    "browser version {version:?}: expected a major or four-part version"
+                ))
+            })?;
+        let major = parts[0];
+        if major < MIN_PINNED_MAJOR {
+            return Err(ManifoldError::InvalidArg(format!(
+                "browser version {version:?}: Chrome {major} predates UA client hints \
+                 ({MIN_PINNED_MAJOR})"
+            )));
+        }
+        if parts.len() == 1 {
+            Self::set_chrome_major(fp, major);
+        } else {
+            Self::set_chrome_version(fp, major, version.trim());
+        }
+        Ok(())
     }
 
-    /// Verify that a fingerprint has sufficient entropy for security.
-    /// Returns true if entropy health >= 70 (good quality).
-    #[allow(dead_code)]
-    pub fn verify_entropy(seed: u64) -> bool {
-        Self::entropy_health(seed) >= 70
+    fn set_chrome_version(fp: &mut Fingerprint, major: u32, full: &str) {
+        if fp.ua_reduction {
+            fp.user_agent = with_chrome_version(&fp.user_agent, &format!("{major}.0.0.0"));
+            fp.ua_full_version = Some(full.to_string());
+        } else {
+            fp.user_agent = with_chrome_version(&fp.user_agent, full);
+        }
+
+        let grease_version = match major % 3 {
+            0 => 8,
+            1 => 24,
+            _ => 99,
+        };
+        for brand in &mut fp.ua_brands {
+            brand.version = if brand.brand.starts_with("Not") {
+                grease_version.to_string()
+            } else {
+                major.to_string()
+            };
+        }
     }
 
-    /// Generate a fingerprint with verified quantum-robust entropy.
-    /// Reseeds automatically if initial entropy is insufficient.
-    #[allow(dead_code)]
-    pub fn generate_verified(seed: u64) -> Fingerprint {
-        let mut current_seed = seed;
-        let mut attempts = 0;
+    /// Whether `edited` changes `current`'s screen (size or pixel ratio) or
+    /// has a viewport that no longer fits it.
+    pub fn screen_edited(current: &Fingerprint, edited: &Fingerprint) -> bool {
+        current.screen_width != edited.screen_width
+            || current.screen_height != edited.screen_height
+            || current.pixel_ratio != edited.pixel_ratio
+            || edited.viewport_width > edited.screen_width
+            || edited.viewport_height > edited.screen_height
+    }
 
-        while attempts < 10 {
-            if Self::verify_entropy(current_seed) {
-                return Self::generate(current_seed);
-            }
-            // Mix in additional entropy and retry
-            let mut qe = QuantumEntropy::new(current_seed);
-            current_seed = qe.next_u64("reseed-attempt");
-            attempts += 1;
+    /// Re-derive what follows from the screen after a manual edit: the
+    /// viewport of a maximised window (seeded toolbar and taskbar heights, as
+    /// at generation) and a pixel ratio the platform can actually report.
+    pub fn recompute_derived_fields(fp: &mut Fingerprint) {
+        let mut rng = SmallRng::seed_from_u64(fp.seed ^ 0x5c2e_e7f0_u64);
+        let chrome_h = 72u32 + rng.gen_range(0u32..25);
+        let taskbar_h = 36u32 + rng.gen_range(0u32..12);
+        fp.viewport_width = fp.screen_width;
+        fp.viewport_height = fp.screen_height.saturating_sub(chrome_h + taskbar_h);
+
+        let factors: &[f64] = if fp.ua_platform == "macOS" {
+            &[1.0, 2.0]
+        } else {
+            SCALE_FACTORS
+        };
+        let target = fp.pixel_ratio;
+        fp.pixel_ratio = factors
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+            .unwrap_or(1.0);
+        // Large Mac screens are Retina panels at their logical size
+        if fp.ua_platform == "macOS" && fp.screen_width >= 2560 {
+            fp.pixel_ratio = 2.0;
+        }
+    }
+
+    /// The viewport of one session when `window_variation` is on, from the
+    /// session's sub-seed: usually the maximised window with a few pixels
+    /// more or less browser chrome (a bookmarks bar, a different zoom of the
+    /// toolbar), sometimes a restored window a little smaller than that.
+    /// Never larger than the screen.
+    pub fn session_viewport(fp: &Fingerprint, session_seed: u64) -> (u32, u32) {
+        if !fp.window_variation {
+            return (fp.viewport_width, fp.viewport_height);
         }
+        let mut rng = SmallRng::seed_from_u64(fp.seed ^ session_seed.rotate_left(29));
+        let (width, height) = if rng.gen_bool(0.7) {
+            let chrome_delta = rng.gen_range(-6i64..=6) - if rng.gen_bool(0.25) { 28 } else { 0 };
+            (
+                fp.viewport_width as i64,
+                fp.viewport_height as i64 + chrome_delta,
+            )
+        } else {
+            (
+                (fp.viewport_width as f64 * rng.gen_range(0.80..0.97)) as i64,
+                (fp.viewport_height as f64 * rng.gen_range(0.82..0.97)) as i64,
+            )
+        };
+        (
+            width.clamp(320, fp.screen_width as i64) as u32,
+            height.clamp(240, fp.screen_height as i64) as u32,
+        )
+    }
 
-        // Fallback: use the seed anyway (should be rare)
-        Self::generate(current_seed)
+    /// Where the window of one session opens, from the session's sub-seed: a
+    /// point in the layout's ranges, moved so the window stays on the
+    /// browser's monitor.  A window at the monitor's origin is maximised.
+    /// Without a layout it is maximised on the primary monitor.
+    pub fn session_window(fp: &Fingerprint, session_seed: u64) -> WindowPlacement {
+        let Some(layout) = &fp.screen_layout else {
+            return WindowPlacement {
+                x: 0,
+                y: 0,
+                maximized: true,
+            };
+        };
+        let mut rng = SmallRng::seed_from_u64(fp.seed ^ session_seed.rotate_left(41));
+        let mut draw = |(lo, hi): (i32, i32)| rng.gen_range(lo.min(hi)..=hi.max(lo));
+        let (x, y) = (draw(layout.window_x), draw(layout.window_y));
+        let avail_height = fp.screen_height.saturating_sub(TASKBAR_HEIGHT);
+        let room_x = fp
+            .screen_width
+            .saturating_sub(fp.viewport_width + WINDOW_FRAME.0) as i32;
+        let room_y = avail_height.saturating_sub(fp.viewport_height + WINDOW_FRAME.1) as i32;
+        let x = x.clamp(layout.avail_left, layout.avail_left + room_x);
+        let y = y.clamp(layout.avail_top, layout.avail_top + room_y);
+        WindowPlacement {
+            x,
+            y,
+            maximized: (x, y) == (layout.avail_left, layout.avail_top),
+        }
+    }
+
+    /// Apply small random deltas to mutable numeric fields without changing the
+    /// seed.
+    #[allow(dead_code)]
+    pub fn mutate(fp: &mut Fingerprint) {
+        let mut mixer = SeedMixer::new(fp.seed.wrapping_add(1));
+        let delta_canvas = mixer.gaussian_noise("mutate-canvas", -0.01, 0.01);
+        let delta_webgl = mixer.gaussian_noise("mutate-webgl", -0.005, 0.005);
+        let delta_audio = mixer.gaussian_noise("mutate-audio", -0.001, 0.001);
+
+        fp.canvas_noise = (fp.canvas_noise + delta_canvas).clamp(0.005, 0.30);
+        fp.audio_noise = (fp.audio_noise + delta_audio).clamp(0.001, 0.05);
+        fp.webgl_noise = (fp.webgl_noise + delta_webgl).clamp(0.005, 0.15);
     }
 
     // ── Private helpers ───────────────────────────────────────────────────────
@@ -512,6 +1251,51 @@ impl FingerprintOrchestrator {
         }
     }
 
+    /// Gamut and HDR the picked display plausibly has.  Every Retina Mac is
+    /// P3 and the XDR panels report HDR; on Windows/Linux wide gamut comes
+    /// with high-end (QHD/4K) monitors, and HDR only with wide gamut and
+    /// only where the desktop supports it.
+    fn pick_color_caps(
+        rng: &mut SmallRng,
+        os: &str,
+        screen_width: u32,
+        pixel_ratio: f64,
+    ) -> (ColorGamut, bool) {
+        let (p3_share, hdr_share) = match os {
+            "macos" if pixel_ratio >= 2.0 => (1.0, 0.3),
+            "macos" => (0.0, 0.0),
+            "linux" if screen_width >= 3840 => (0.3, 0.0),
+            _ if screen_width >= 3840 => (0.4, 0.35),
+            _ if screen_width >= 2560 => (0.15, 0.2),
+            _ => (0.0, 0.0),
+        };
+        if !rng.gen_bool(p3_share) {
+            return (ColorGamut::Srgb, false);
+        }
+        (ColorGamut::P3, rng.gen_bool(hdr_share))
+    }
+
+    /// Appearance and accessibility settings in roughly their desktop
+    /// shares: about a third of users run a dark theme (more on macOS, where
+    /// "Auto" follows the evening), a few percent turn animations off, and
+    /// contrast themes — the only source of forced colors in Chrome — exist
+    /// only on Windows and are mostly dark.
+    fn pick_media_prefs(rng: &mut SmallRng, os: &str) -> (ColorScheme, bool, bool) {
+        let (dark_share, reduced_motion_share, forced_share) = match os {
+            "macos" => (0.45, 0.03, 0.0),
+            "linux" => (0.5, 0.02, 0.0),
+            _ => (0.35, 0.04, 0.004),
+        };
+        let forced_colors = rng.gen_bool(forced_share);
+        let dark = rng.gen_bool(if forced_colors { 0.75 } else { dark_share });
+        let scheme = if dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        };
+        (scheme, rng.gen_bool(reduced_motion_share), forced_colors)
+    }
+
     fn pick_webgl(rng: &mut SmallRng, os: &str) -> (String, String) {
         // GPU catalogue updated for 2024-2025 real-world market share.
         // Sources: Steam Hardware Survey Q1-2025, StatCounter GPU market data.
@@ -627,69 +1411,30 @@ impl FingerprintOrchestrator {
         (*sw, *sh, vw, vh, *pr)
     }
 
-    fn build_font_subset(rng: &mut SmallRng, os: &str) -> Vec<String> {
+    fn build_font_subset(
+        rng: &mut SmallRng,
+        ua_platform: &str,
+        ua_platform_version: &str,
+    ) -> Vec<String> {
         // Baseline fonts present on virtually every system
-        let mut subset: Vec<String> = vec![
-            "Arial",
-            "Arial Black",
-            "Comic Sans MS",
-            "Courier New",
-            "Georgia",
-            "Impact",
-            "Times New Roman",
-            "Trebuchet MS",
-            "Verdana",
-            "Webdings",
-        ]
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
-
-        // OS-specific common fonts
-        let extras: &[&str] = match os {
-            "macos" => &[
-                "Helvetica",
-                "Helvetica Neue",
-                "Gill Sans",
-                "Optima",
-                "Palatino",
-                "Futura",
-                "Menlo",
-                "Monaco",
-                "Apple Chancery",
-            ],
-            "linux" => &[
-                "Liberation Sans",
-                "Liberation Serif",
-                "Liberation Mono",
-                "DejaVu Sans",
-                "DejaVu Serif",
-                "Ubuntu",
-                "Cantarell",
-                "Noto Sans",
-            ],
-            _ => &[
-                "Calibri",
-                "Cambria",
-                "Candara",
-                "Consolas",
-                "Constantia",
-                "Corbel",
-                "Franklin Gothic Medium",
-                "Gabriola",
-                "Segoe UI",
-                "Tahoma",
-                "Microsoft Sans Serif",
-                "Palatino Linotype",
-            ],
-        };
-
-        // Randomly include 60–90% of the OS extras
-        for font in extras {
-            if rng.gen_bool(0.75) {
-                subset.push(font.to_string());
-            }
-        }
+        let mut subset: Vec<String> = BASELINE.iter().map(|s| s.to_string()).collect();
+
+        // Fonts of the OS release the UA claims, ~75% of them but never
+        // fewer than a stock install reports
+        let release = OsRelease::from_platform(ua_platform, ua_platform_version)
+            .unwrap_or(OsRelease::Windows10);
+        let (skipped, picked): (Vec<_>, Vec<_>) = release
+            .catalogue()
+            .into_iter()
+            .partition(|_| !rng.gen_bool(OPTIONAL_SHARE));
+        let (min_count, _) = release.font_count_range();
+        let top_up = min_count.saturating_sub(subset.len() + picked.len());
+        subset.extend(
+            picked
+                .into_iter()
+                .chain(skipped.into_iter().take(top_up))
+                .map(String::from),
+        );
 
         // Shuffle so ordering doesn't reveal the generator
         use rand::seq::SliceRandom;
@@ -750,60 +1495,38 @@ impl FingerprintOrchestrator {
     /// Constrain locale, accept_language, timezone, and screen resolution
     /// to be internally consistent with the given ISO-3166-1 alpha-2 country code.
     /// Call this after `generate()` when a proxy country is known.
+    /// Countries without a `geo_locale_map` entry are left as they are; the
+    /// validator reports them as GEO_COUNTRY_UNMAPPED.
     pub fn enforce_geo(fp: &mut Fingerprint, country_code: &str) {
+        Self::enforce_geo_region(fp, country_code, None);
+    }
+
+    /// `enforce_geo`, with the timezone pinned to the proxy's region
+    /// (`region_timezone`) when it has one the tables know: a Utah exit gets
+    /// America/Denver rather than any US zone.
+    pub fn enforce_geo_region(fp: &mut Fingerprint, country_code: &str, region: Option<&str>) {
         let cc = country_code.to_uppercase();
         let mut rng = SmallRng::seed_from_u64(fp.seed ^ 0x9e0c_0de0_u64);
 
-        // GEO_LOCALE_MAP: country → [(locale, accept_language, timezone)]
-        let options: &[(&str, &str, &str)] = match cc.as_str() {
-            "US" => &[
-                ("en-US", "en-US,en;q=0.9", "America/New_York"),
-                ("en-US", "en-US,en;q=0.9", "America/Chicago"),
-                ("en-US", "en-US,en;q=0.9", "America/Los_Angeles"),
-                ("en-US", "en-US,en;q=0.9", "America/Denver"),
-            ],
-            "GB" => &[("en-GB", "en-GB,en;q=0.9", "Europe/London")],
-            "CA" => &[
-                ("en-CA", "en-CA,en;q=0.9,fr-CA;q=0.8", "America/Toronto"),
-                ("en-CA", "en-CA,en;q=0.9", "America/Vancouver"),
-                ("fr-CA", "fr-CA,fr;q=0.9,en-CA;q=0.8", "America/Montreal"),
-            ],
-            "AU" => &[
-                ("en-AU", "en-AU,en;q=0.9", "Australia/Sydney"),
-                ("en-AU", "en-AU,en;q=0.9", "Australia/Melbourne"),
-                ("en-AU", "en-AU,en;q=0.9", "Australia/Brisbane"),
-            ],
-            "DE" => &[("de-DE", "de-DE,de;q=0.9,en;q=0.8", "Europe/Berlin")],
-            "FR" => &[("fr-FR", "fr-FR,fr;q=0.9,en;q=0.8", "Europe/Paris")],
-            "ES" => &[("es-ES", "es-ES,es;q=0.9,en;q=0.8", "Europe/Madrid")],
-            "IT" => &[("it-IT", "it-IT,it;q=0.9,en;q=0.8", "Europe/Rome")],
-            "NL" => &[("nl-NL", "nl-NL,nl;q=0.9,en;q=0.8", "Europe/Amsterdam")],
-            "PL" => &[("pl-PL", "pl-PL,pl;q=0.9,en;q=0.8", "Europe/Warsaw")],
-            "BR" => &[
-                ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Sao_Paulo"),
-                ("pt-BR", "pt-BR,pt;q=0.9,en;q=0.8", "America/Fortaleza"),
-            ],
-            "JP" => &[("ja-JP", "ja-JP,ja;q=0.9,en;q=0.8", "Asia/Tokyo")],
-            "KR" => &[("ko-KR", "ko-KR,ko;q=0.9,en;q=0.8", "Asia/Seoul")],
-            "IN" => &[("en-IN", "en-IN,en;q=0.9,hi;q=0.8", "Asia/Kolkata")],
-            "SG" => &[("en-SG", "en-SG,en;q=0.9", "Asia/Singapore")],
-            "HK" => &[("zh-HK", "zh-HK,zh;q=0.9,en;q=0.8", "Asia/Hong_Kong")],
-            "SE" => &[("sv-SE", "sv-SE,sv;q=0.9,en;q=0.8", "Europe/Stockholm")],
-            "NO" => &[("nb-NO", "nb-NO,nb;q=0.9,en;q=0.8", "Europe/Oslo")],
-            "CH" => &[
-                ("de-CH", "de-CH,de;q=0.9,en;q=0.8", "Europe/Zurich"),
-                ("fr-CH", "fr-CH,fr;q=0.9,de-CH;q=0.8", "Europe/Zurich"),
-            ],
-            "MX" => &[("es-MX", "es-MX,es;q=0.9,en;q=0.8", "America/Mexico_City")],
+        let options = geo_locale_map(&cc);
+        if options.is_empty() {
             // Fallback: keep existing locale unchanged
-            _ => return,
-        };
+            return;
+        }
 
-        let idx = rng.gen_range(0..options.len());
-        let (locale, accept_language, timezone) = options[idx];
+        let pinned = region.and_then(|r| region_timezone(&cc, r));
+        // Rows already in the region's zone keep their locale pairing (fr-CA
+        // for Montreal); a zone the map lacks keeps any row's locale
+        let in_zone: Vec<_> = options.iter().filter(|row| Some(row.2) == pinned).collect();
+        let (locale, accept_language, timezone) = if in_zone.is_empty() {
+            options[rng.gen_range(0..options.len())]
+        } else {
+            *in_zone[rng.gen_range(0..in_zone.len())]
+        };
         fp.locale = locale.to_string();
+        fp.intl = Some(IntlProfile::for_locale(locale));
         fp.accept_language = accept_language.to_string();
-        fp.timezone = timezone.to_string();
+        fp.timezone = pinned.unwrap_or(timezone).to_string();
 
         // Screen resolution: geo-gate 4K to locales where 4K desktop penetration
         // is realistic (US, JP, KR, DE, GB).  For other regions, cap at 1080p.
@@ -1355,6 +2078,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn media_preferences_vary_and_forced_colors_stay_on_windows() {
+        let fps: Vec<_> = (0u64..2000)
+            .map(FingerprintOrchestrator::generate)
+            .collect();
+        let dark = fps
+            .iter()
+            .filter(|fp| fp.prefers_color_scheme == ColorScheme::Dark)
+            .count();
+        assert!((400..1100).contains(&dark), "dark share off: {dark}/2000");
+        let reduced = fps.iter().filter(|fp| fp.prefers_reduced_motion).count();
+        assert!(
+            (10..200).contains(&reduced),
+            "reduced motion: {reduced}/2000"
+        );
+        assert!(fps
+            .iter()
+            .filter(|fp| fp.forced_colors)
+            .all(|fp| fp.ua_platform == "Windows"));
+
+        // Stored profiles from before the fields existed keep launching dark
+        let mut json = serde_json::to_value(&fps[0]).unwrap();
+        let obj = json.as_object_mut().unwrap();
+        obj.remove("prefers_color_scheme");
+        obj.remove("forced_colors");
+        let old: Fingerprint = serde_json::from_value(json).unwrap();
+        assert_eq!(old.prefers_color_scheme, ColorScheme::Dark);
+        assert!(!old.forced_colors);
+    }
+
+    #[test]
+    fn timing_noise_uses_chrome_precision_and_a_seeded_latency() {
+        let jitters: Vec<f64> = (0u64..200)
+            .map(|seed| {
+                let t = FingerprintOrchestrator::generate(seed)
+                    .timing_noise
+                    .unwrap();
+                assert_eq!(t.precision_us, CHROME_TIMER_PRECISION_US);
+                assert!((0.8..=3.0).contains(&t.event_jitter_ms), "{t:?}");
+                t.event_jitter_ms
+            })
+            .collect();
+        assert!(jitters.windows(2).any(|w| w[0] != w[1]));
+
+        let mut json = serde_json::to_value(FingerprintOrchestrator::generate(1)).unwrap();
+        json.as_object_mut().unwrap().remove("timing_noise");
+        let old: Fingerprint = serde_json::from_value(json).unwrap();
+        assert_eq!(old.timing_noise, None);
+    }
+
     // ── JSON round-trip ───────────────────────────────────────────────────────
 
     #[test]
@@ -1409,6 +2182,241 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reseed_with_keeps_selected_identity_parts() {
+        let original = FingerprintOrchestrator::generate(42);
+        let opts = ReseedOptions {
+            keep_os: true,
+            keep_gpu: true,
+            keep_locale_tz: true,
+            keep_screen: true,
+            latest_generator: false,
+        };
+        // Find a seed that lands on a different OS so the carry-over is visible
+        let other = (1..500u64)
+            .find(|s| FingerprintOrchestrator::generate(*s).platform != original.platform)
+            .unwrap();
+        let fresh = FingerprintOrchestrator::generate(other);
+        let next = FingerprintOrchestrator::reseed_with(&original, other, &opts).unwrap();
+
+        assert_eq!(next.seed, other);
+        assert_eq!(next.platform, original.platform);
+        assert_eq!(next.webgl_renderer, original.webgl_renderer);
+        assert_eq!(next.timezone, original.timezone);
+        assert_eq!(next.screen_width, original.screen_width);
+        assert_eq!(next.font_subset, original.font_subset);
+        // Noise and Chrome version come from the new seed
+        assert_eq!(next.canvas_noise, fresh.canvas_noise);
+        assert_eq!(
+            chrome_version(&next.user_agent),
+            chrome_version(&fresh.user_agent)
+        );
+        assert_eq!(
+            next.user_agent.split("Chrome/").next(),
+            original.user_agent.split("Chrome/").next()
+        );
+    }
+
+    #[test]
+    fn reseed_with_default_options_equals_generate() {
+        let original = FingerprintOrchestrator::generate(42);
+        let next =
+            FingerprintOrchestrator::reseed_with(&original, 7, &ReseedOptions::default()).unwrap();
+        let fresh = FingerprintOrchestrator::generate(7);
+        assert_eq!(next.user_agent, fresh.user_agent);
+        assert_eq!(next.timezone, fresh.timezone);
+    }
+
+    #[test]
+    fn fingerprints_saved_before_versioning_are_generator_1() {
+        let mut json = serde_json::to_value(FingerprintOrchestrator::generate(5)).unwrap();
+        json.as_object_mut().unwrap().remove("generator");
+        let fp: Fingerprint = serde_json::from_value(json).unwrap();
+        assert_eq!(fp.generator, 1);
+    }
+
+    #[test]
+    fn reseed_stays_on_the_fingerprint_generator() {
+        let fresh = FingerprintOrchestrator::generate_with(7, 1).unwrap();
+        assert_eq!(
+            fresh.user_agent,
+            FingerprintOrchestrator::generate(7).user_agent
+        );
+
+        let mut newer = FingerprintOrchestrator::generate(42);
+        newer.generator = GENERATOR_VERSION + 1;
+        let opts = ReseedOptions::default();
+        assert!(FingerprintOrchestrator::reseed_with(&newer, 7, &opts).is_err());
+        let latest = ReseedOptions {
+            latest_generator: true,
+            ..ReseedOptions::default()
+        };
+        let next = FingerprintOrchestrator::reseed_with(&newer, 7, &latest).unwrap();
+        assert_eq!(next.generator, GENERATOR_VERSION);
+    }
+
+    #[test]
+    fn set_chrome_major_updates_ua_and_brands() {
+        let mut fp = FingerprintOrchestrator::generate(42);
+        FingerprintOrchestrator::set_chrome_major(&mut fp, 140);
+        assert!(chrome_version(&fp.user_agent)
+            .unwrap()
+            .starts_with("140.0."));
+        for b in &fp.ua_brands {
+            if b.brand.starts_with("Not") {
+                assert_eq!(b.version, "99"); // 140 % 3 == 2
+            } else {
+                assert_eq!(b.version, "140");
+            }
+        }
+    }
+
+    #[test]
+    fn ua_reduction_freezes_the_ua_and_keeps_the_full_version() {
+        let mut fp = (0..)
+            .map(FingerprintOrchestrator::generate)
+            .find(|fp| fp.ua_platform == "macOS")
+            .unwrap();
+        let full = chrome_version(&fp.user_agent).unwrap().to_string();
+        let major = full.split('.').next().unwrap().to_string();
+
+        FingerprintOrchestrator::set_ua_reduction(&mut fp, true);
+        assert!(fp
+            .user_agent
+            .contains("Intel Mac OS X 10_15_7) AppleWebKit"));
+        assert_eq!(
+            chrome_version(&fp.user_agent),
+            Some(format!("{major}.0.0.0").as_str())
+        );
+        assert_eq!(fp.chrome_full_version(), Some(full.as_str()));
+
+        let reseeded =
+            FingerprintOrchestrator::reseed_with(&fp, 7, &ReseedOptions::default()).unwrap();
+        assert!(reseeded.ua_reduction);
+        assert!(chrome_version(&reseeded.user_agent)
+            .unwrap()
+            .ends_with(".0.0.0"));
+
+        FingerprintOrchestrator::set_chrome_major(&mut fp, 140);
+        assert_eq!(chrome_version(&fp.user_agent), Some("140.0.0.0"));
+        assert!(fp.chrome_full_version().unwrap().starts_with("140.0."));
+        assert_ne!(fp.chrome_full_version(), Some("140.0.0.0"));
+
+        let full = fp.chrome_full_version().unwrap().to_string();
+        FingerprintOrchestrator::set_ua_reduction(&mut fp, false);
+        assert_eq!(chrome_version(&fp.user_agent), Some(full.as_str()));
+        assert!(fp.ua_full_version.is_none());
+    }
+
+    #[test]
+    fn pinning_moves_ua_and_brands_to_the_version() {
+        let mut fp = FingerprintOrchestrator::generate(11);
+        FingerprintOrchestrator::pin_chrome_version(&mut fp, "109.0.5414.120").unwrap();
+        assert_eq!(chrome_version(&fp.user_agent), Some("109.0.5414.120"));
+        assert!(fp
+            .ua_brands
+            .iter()
+            .filter(|b| !b.brand.starts_with("Not"))
+            .all(|b| b.version == "109"));
+
+        // A bare major gets the seeded build; reduced UAs keep it aside
+        FingerprintOrchestrator::set_ua_reduction(&mut fp, true);
+        FingerprintOrchestrator::pin_chrome_version(&mut fp, "112").unwrap();
+        assert_eq!(chrome_version(&fp.user_agent), Some("112.0.0.0"));
+        assert!(fp.chrome_full_version().unwrap().starts_with("112.0."));
+
+        for bad in ["", "109.0", "latest", "60"] {
+            assert!(
+                FingerprintOrchestrator::pin_chrome_version(&mut fp, bad).is_err(),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn recompute_derived_fields_follows_a_screen_edit() {
+        let fp = FingerprintOrchestrator::generate(42);
+        let mut edited = fp.clone();
+        edited.screen_width = 1366;
+        edited.screen_height = 768;
+        edited.pixel_ratio = 1.3;
+        assert!(FingerprintOrchestrator::screen_edited(&fp, &edited));
+        FingerprintOrchestrator::recompute_derived_fields(&mut edited);
+        assert_eq!(edited.viewport_width, 1366);
+        assert!((768 - 143..=768 - 108).contains(&edited.viewport_height));
+        assert_eq!(
+            edited.pixel_ratio,
+            if fp.ua_platform == "macOS" { 1.0 } else { 1.25 }
+        );
+        assert!(!FingerprintOrchestrator::screen_edited(
+            &edited,
+            &edited.clone()
+        ));
+
+        let mut mac = (0..)
+            .map(FingerprintOrchestrator::generate)
+            .find(|fp| fp.ua_platform == "macOS")
+            .unwrap();
+        mac.screen_width = 2880;
+        mac.screen_height = 1800;
+        mac.pixel_ratio = 1.25;
+        FingerprintOrchestrator::recompute_derived_fields(&mut mac);
+        assert_eq!(mac.pixel_ratio, 2.0);
+    }
+
+    #[test]
+    fn session_viewport_varies_per_session_within_the_screen() {
+        let mut fp = FingerprintOrchestrator::generate(42);
+        let stored = (fp.viewport_width, fp.viewport_height);
+        assert_eq!(FingerprintOrchestrator::session_viewport(&fp, 3), stored);
+
+        fp.window_variation = true;
+        let sizes: Vec<(u32, u32)> = (0..50)
+            .map(|n| FingerprintOrchestrator::session_viewport(&fp, n))
+            .collect();
+        assert_eq!(sizes[7], FingerprintOrchestrator::session_viewport(&fp, 7));
+        assert!(sizes.iter().any(|&s| s != sizes[0]));
+        assert!(sizes.iter().any(|&(w, _)| w == stored.0));
+        assert!(sizes.iter().any(|&(w, _)| w < stored.0));
+        for (w, h) in sizes {
+            assert!(w <= fp.screen_width && h <= fp.screen_height);
+            assert!(h + 40 >= stored.1 * 4 / 5, "{h} vs {}", stored.1);
+        }
+    }
+
+    #[test]
+    fn session_window_stays_on_the_browsers_monitor() {
+        let mut fp = FingerprintOrchestrator::generate(42);
+        let maximised = FingerprintOrchestrator::session_window(&fp, 1);
+        assert_eq!((maximised.x, maximised.y), (0, 0));
+        assert!(maximised.maximized);
+
+        // Browser on a monitor left of the primary
+        fp.screen_layout = Some(ScreenLayout {
+            monitors: 2,
+            avail_left: -(fp.screen_width as i32),
+            avail_top: 0,
+            window_x: (-(fp.screen_width as i32) + 40, 400),
+            window_y: (0, 120),
+        });
+        fp.viewport_width = fp.screen_width / 2;
+        fp.viewport_height = fp.screen_height / 2;
+        let windows: Vec<WindowPlacement> = (0..40)
+            .map(|n| FingerprintOrchestrator::session_window(&fp, n))
+            .collect();
+        assert_eq!(windows[3], FingerprintOrchestrator::session_window(&fp, 3));
+        assert!(windows.iter().any(|w| w.x != windows[0].x));
+        let right_edge = fp.screen_width as i32 / 2 + WINDOW_FRAME.0 as i32;
+        for w in &windows {
+            assert!(
+                w.x >= -(fp.screen_width as i32) && w.x <= -right_edge,
+                "{w:?}"
+            );
+            assert!((0..=120).contains(&w.y));
+            assert!(!w.maximized || (w.x, w.y) == (-(fp.screen_width as i32), 0));
+        }
+    }
+
     #[test]
     fn mutate_changes_at_least_one_noise_field() {
         let mut fp = FingerprintOrchestrator::generate(100);
@@ -0,0 +1,145 @@
+// ── Manifold fingerprint diff ─────────────────────────────────────────────────
+//
+// Field-by-field comparison of two fingerprints, so the editor can show what
+// a reseed or a geo auto-correct actually changes before it is saved.
+//
+// Fields are compared through their serialized form: nested structs
+// (`text_rendering`, `intl`, `timing_noise`) and the permissions map are
+// walked key by key and reported with dotted paths, lists are compared
+// whole.  The noise levels alone don't say how much of the rendered output
+// moves — a new seed with the same levels changes nearly every probe value —
+// so the diff also carries the probe Hamming distances from `hash_preview`.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::fingerprint::Fingerprint;
+use crate::hash_preview::{noise_distance, NoiseDistance};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Dotted path, e.g. `screen_width` or `intl.calendar`.
+    pub field: String,
+    /// `null` when the field is new in `b`.
+    pub before: Value,
+    /// `null` when the field is gone from `b`.
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FingerprintDiff {
+    /// Changed fields, sorted by path.
+    pub changes: Vec<FieldChange>,
+    /// Leaf fields equal on both sides.
+    pub unchanged: usize,
+    pub noise: NoiseDistance,
+}
+
+/// Compare `a` (current) with `b` (proposed).
+pub fn diff(a: &Fingerprint, b: &Fingerprint) -> serde_json::Result<FingerprintDiff> {
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    walk(
+        "",
+        &serde_json::to_value(a)?,
+        &serde_json::to_value(b)?,
+        &mut changes,
+        &mut unchanged,
+    );
+    changes.sort_by(|x, y| x.field.cmp(&y.field));
+    Ok(FingerprintDiff {
+        changes,
+        unchanged,
+        noise: noise_distance(a, b),
+    })
+}
+
+/// The changed fields between two serialized fingerprints, sorted by path.
+/// For fingerprints stored by another Manifold version, which may no longer
+/// deserialize.
+pub fn changed_fields(a: &Value, b: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    walk("", a, b, &mut changes, &mut 0);
+    changes.sort_by(|x, y| x.field.cmp(&y.field));
+    changes
+}
+
+fn walk(path: &str, a: &Value, b: &Value, changes: &mut Vec<FieldChange>, unchanged: &mut usize) {
+    if let (Value::Object(a), Value::Object(b)) = (a, b) {
+        let keys = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k)));
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            let null = Value::Null;
+            walk(
+                &child,
+                a.get(key).unwrap_or(&null),
+                b.get(key).unwrap_or(&null),
+                changes,
+                unchanged,
+            );
+        }
+    } else if a == b {
+        *unchanged += 1;
+    } else {
+        changes.push(FieldChange {
+            field: path.to_string(),
+            before: a.clone(),
+            after: b.clone(),
+        });
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::{FingerprintOrchestrator, ReseedOptions};
+
+    #[test]
+    fn identical_fingerprints_have_no_changes() {
+        let fp = FingerprintOrchestrator::generate(11);
+        let d = diff(&fp, &fp.clone()).unwrap();
+        assert!(d.changes.is_empty());
+        assert!(d.unchanged > 20);
+        assert_eq!(d.noise.audio.differing, 0);
+    }
+
+    #[test]
+    fn nested_and_map_fields_use_dotted_paths() {
+        let a = FingerprintOrchestrator::generate(11);
+        let mut b = a.clone();
+        b.screen_width += 1;
+        b.permissions.insert("x-new".into(), "denied".into());
+        let d = diff(&a, &b).unwrap();
+        let fields: Vec<&str> = d.changes.iter().map(|c| c.field.as_str()).collect();
+        assert!(fields.contains(&"screen_width"));
+        assert!(fields.contains(&"permissions.x-new"));
+        let added = d
+            .changes
+            .iter()
+            .find(|c| c.field == "permissions.x-new")
+            .unwrap();
+        assert_eq!(
+            (&added.before, &added.after),
+            (&Value::Null, &Value::from("denied"))
+        );
+    }
+
+    #[test]
+    fn reseed_keeping_the_screen_leaves_it_out_of_the_diff() {
+        let a = FingerprintOrchestrator::generate(11);
+        let opts = ReseedOptions {
+            keep_screen: true,
+            ..ReseedOptions::default()
+        };
+        let b = FingerprintOrchestrator::reseed_with(&a, 12, &opts).unwrap();
+        let d = diff(&a, &b).unwrap();
+        assert!(d.changes.iter().any(|c| c.field == "seed"));
+        assert!(!d.changes.iter().any(|c| c.field.starts_with("screen_")));
+    }
+}
@@ -0,0 +1,178 @@
+// ── Manifold fingerprint test vectors ─────────────────────────────────────────
+//
+// A profile's identity is its seed: the fingerprint is regenerated from it
+// whenever it is reseeded, duplicated or shared.  If an upgrade changes what
+// a seed generates (a new GPU table, a reordered font list), every identity
+// derived afterwards silently differs from the one the sites already know.
+//
+// `export` writes the fingerprints a list of seeds generates now to a JSON
+// "golden file"; `verify` regenerates them with the running build and lists
+// every field that no longer matches.  Exported before an upgrade and
+// verified after it, the pair shows exactly which identities would move.
+//
+// Vectors are stored as plain JSON rather than `Fingerprint`, so a file
+// written by an older build still compares field by field after the struct
+// gained or lost fields.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::FingerprintOrchestrator;
+use crate::fingerprint_diff::{changed_fields, FieldChange};
+
+/// Golden file layout version.
+pub const VECTORS_FORMAT: u32 = 1;
+
+/// Seeds always exported: small, large and past the 2^53 JS precision limit.
+pub const DEFAULT_SEEDS: &[u64] = &[
+    0,
+    1,
+    42,
+    1337,
+    65_535,
+    4_294_967_295,
+    9_007_199_254_740_993,
+    u64::MAX,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFile {
+    pub format: u32,
+    /// Manifold version that generated the vectors.
+    pub generator: String,
+    pub created_at: DateTime<Utc>,
+    pub vectors: Vec<Vector>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vector {
+    pub seed: u64,
+    pub fingerprint: Value,
+}
+
+/// A seed whose fingerprint no longer matches its golden vector.
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorDrift {
+    pub seed: u64,
+    /// `before` is the golden value, `after` the current one.
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorReport {
+    /// Manifold version that wrote the file.
+    pub generator: String,
+    pub current: String,
+    pub checked: usize,
+    /// Empty when every seed still generates its golden fingerprint.
+    pub drifted: Vec<VectorDrift>,
+}
+
+/// The fingerprint `seed` generates, as read back from a golden file.
+/// serde_json doesn't parse every float to the exact value it printed, so
+/// both sides of a comparison go through the same text form.
+fn generate(seed: u64) -> Result<Value> {
+    let text = serde_json::to_string(&FingerprintOrchestrator::generate(seed))?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Write the fingerprints `seeds` generate to `path`.  Duplicate seeds are
+/// written once, in ascending order.
+pub fn export(path: &Path, seeds: &[u64]) -> Result<VectorFile> {
+    let mut seeds = seeds.to_vec();
+    seeds.sort_unstable();
+    seeds.dedup();
+    let file = VectorFile {
+        format: VECTORS_FORMAT,
+        generator: env!("CARGO_PKG_VERSION").into(),
+        created_at: Utc::now(),
+        vectors: seeds
+            .into_iter()
+            .map(|seed| {
+                Ok(Vector {
+                    seed,
+                    fingerprint: generate(seed)?,
+                })
+            })
+            .collect::<Result<_>>()?,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+    Ok(file)
+}
+
+/// Regenerate every vector in the golden file at `path` and report the
+/// seeds whose fingerprint changed.
+pub fn verify(path: &Path) -> Result<VectorReport> {
+    let file: VectorFile = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if file.format != VECTORS_FORMAT {
+        return Err(ManifoldError::InvalidArg(format!(
+            "unsupported test vector format {}",
+            file.format
+        )));
+    }
+    let mut drifted = Vec::new();
+    for vector in &file.vectors {
+        let changes = changed_fields(&vector.fingerprint, &generate(vector.seed)?);
+        if !changes.is_empty() {
+            drifted.push(VectorDrift {
+                seed: vector.seed,
+                changes,
+            });
+        }
+    }
+    Ok(VectorReport {
+        generator: file.generator,
+        current: env!("CARGO_PKG_VERSION").into(),
+        checked: file.vectors.len(),
+        drifted,
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_vectors_verify_clean() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("golden/fingerprints.json");
+        let file = export(&path, &[42, 7, 42]).unwrap();
+        let seeds: Vec<u64> = file.vectors.iter().map(|v| v.seed).collect();
+        assert_eq!(seeds, [7, 42]);
+
+        let report = verify(&path).unwrap();
+        assert_eq!(report.checked, 2);
+        assert!(report.drifted.is_empty());
+    }
+
+    #[test]
+    fn changed_generation_is_reported_per_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fingerprints.json");
+        let mut file = export(&path, DEFAULT_SEEDS).unwrap();
+        // What an older build would have written for seed 1337
+        let old = &mut file.vectors[3].fingerprint;
+        old["screen_width"] = Value::from(1);
+        old.as_object_mut().unwrap().remove("locale");
+        std::fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+        let report = verify(&path).unwrap();
+        assert_eq!(report.drifted.len(), 1);
+        assert_eq!(report.drifted[0].seed, 1337);
+        let fields: Vec<&str> = report.drifted[0]
+            .changes
+            .iter()
+            .map(|c| c.field.as_str())
+            .collect();
+        assert_eq!(fields, ["locale", "screen_width"]);
+    }
+}
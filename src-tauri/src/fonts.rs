@@ -0,0 +1,349 @@
+// ── Manifold font catalogues ──────────────────────────────────────────────────
+//
+// `document.fonts.check()` probing gives sites the installed font list, and
+// font lists track OS releases: Windows 11 ships Segoe UI Variable and Segoe
+// Fluent Icons, which no Windows 10 install has; newer macOS releases expose
+// SF Pro variants that older ones don't.  A "Windows 10" profile reporting
+// Segoe UI Variable is therefore inconsistent.
+//
+// Catalogues are cumulative per OS family: each release lists only the fonts
+// it added, and a release has everything its predecessors had.  The release
+// is read from the UA-CH platform and platform version so the fonts always
+// match what the profile claims to run.
+//
+// The same goes for how text is drawn: canvas text fingerprints render emoji,
+// so the emoji font and the Unicode Emoji version it covers give away the OS
+// build, and glyph rasterisation (ClearType vs grayscale antialiasing) the
+// OS family.  `TextRendering` carries those for the canvas-text spoof.
+
+use serde::{Deserialize, Serialize};
+
+/// Present on virtually every desktop system (bundled or pulled in by
+/// common installs).
+pub const BASELINE: &[&str] = &[
+    "Arial",
+    "Arial Black",
+    "Comic Sans MS",
+    "Courier New",
+    "Georgia",
+    "Impact",
+    "Times New Roman",
+    "Trebuchet MS",
+    "Verdana",
+    "Webdings",
+];
+
+/// Share of a release's optional fonts a generated profile includes.
+pub const OPTIONAL_SHARE: f64 = 0.75;
+
+/// Windows builds from this number on are Windows 11.
+const WIN11_FIRST_BUILD: u32 = 22000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsRelease {
+    Windows10,
+    Windows11,
+    /// macOS 13
+    MacVentura,
+    /// macOS 14
+    MacSonoma,
+    /// macOS 15
+    MacSequoia,
+    Linux,
+}
+
+impl OsRelease {
+    /// The release a fingerprint claims from its UA-CH platform and platform
+    /// version.  Windows versions are read both as `0.0.<build>` and as
+    /// Chrome's `<major>.0.0` (13 and up is Windows 11).
+    pub fn from_platform(ua_platform: &str, ua_platform_version: &str) -> Option<Self> {
+        let parts: Vec<u32> = ua_platform_version
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let major = parts.first().copied().unwrap_or(0);
+        match ua_platform {
+            "Windows" => {
+                let win11 = if major == 0 {
+                    parts.get(2).copied().unwrap_or(0) >= WIN11_FIRST_BUILD
+                } else {
+                    major >= 13
+                };
+                Some(if win11 {
+                    Self::Windows11
+                } else {
+                    Self::Windows10
+                })
+            }
+            "macOS" => Some(match major {
+                0..=13 => Self::MacVentura,
+                14 => Self::MacSonoma,
+                _ => Self::MacSequoia,
+            }),
+            "Linux" => Some(Self::Linux),
+            _ => None,
+        }
+    }
+
+    /// Releases of the same OS family, oldest first.
+    fn family(self) -> &'static [OsRelease] {
+        match self {
+            Self::Windows10 | Self::Windows11 => &[Self::Windows10, Self::Windows11],
+            Self::MacVentura | Self::MacSonoma | Self::MacSequoia => {
+                &[Self::MacVentura, Self::MacSonoma, Self::MacSequoia]
+            }
+            Self::Linux => &[Self::Linux],
+        }
+    }
+
+    /// Fonts this release added over its predecessor.
+    fn added_fonts(self) -> &'static [&'static str] {
+        match self {
+            Self::Windows10 => &[
+                "Bahnschrift",
+                "Calibri",
+                "Cambria",
+                "Candara",
+                "Consolas",
+                "Constantia",
+                "Corbel",
+                "Franklin Gothic Medium",
+                "Gabriola",
+                "Ink Free",
+                "Microsoft Sans Serif",
+                "Palatino Linotype",
+                "Segoe MDL2 Assets",
+                "Segoe UI",
+                "Segoe UI Emoji",
+                "Segoe UI Symbol",
+                "Tahoma",
+            ],
+            Self::Windows11 => &[
+                "Segoe Fluent Icons",
+                "Segoe UI Variable Display",
+                "Segoe UI Variable Small",
+                "Segoe UI Variable Text",
+            ],
+            Self::MacVentura => &[
+                "American Typewriter",
+                "Apple Chancery",
+                "Avenir",
+                "Avenir Next",
+                "Baskerville",
+                "Didot",
+                "Futura",
+                "Gill Sans",
+                "Helvetica",
+                "Helvetica Neue",
+                "Hoefler Text",
+                "Menlo",
+                "Monaco",
+                "Optima",
+                "Palatino",
+            ],
+            Self::MacSonoma => &["SF Mono", "SF Pro"],
+            Self::MacSequoia => &["New York", "SF Compact", "SF Pro Rounded"],
+            Self::Linux => &[
+                "Cantarell",
+                "DejaVu Sans",
+                "DejaVu Serif",
+                "Liberation Mono",
+                "Liberation Sans",
+                "Liberation Serif",
+                "Noto Sans",
+                "Ubuntu",
+            ],
+        }
+    }
+
+    /// Every OS font the release ships, beyond `BASELINE`.
+    pub fn catalogue(self) -> Vec<&'static str> {
+        self.family()
+            .iter()
+            .take_while(|r| **r <= self)
+            .flat_map(|r| r.added_fonts())
+            .copied()
+            .collect()
+    }
+
+    /// Plausible number of fonts (baseline included) for a profile of this
+    /// release: at least half the optional fonts, at most twice the
+    /// catalogue (users install fonts; they rarely install hundreds).
+    pub fn font_count_range(self) -> (usize, usize) {
+        let catalogue = self.catalogue().len();
+        (
+            BASELINE.len() + catalogue / 2,
+            (BASELINE.len() + catalogue) * 2,
+        )
+    }
+}
+
+/// Where a font that isn't in a release's catalogue comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignFont {
+    /// Ships with a later release of the same OS.
+    NewerRelease(OsRelease),
+    /// Ships with a different OS.
+    OtherOs(OsRelease),
+}
+
+/// Classify `font` against `release`.  `None` when it ships with the
+/// release, or isn't in any catalogue (user-installed fonts are fine).
+pub fn foreign_font(release: OsRelease, font: &str) -> Option<ForeignFont> {
+    if BASELINE.contains(&font) || release.catalogue().contains(&font) {
+        return None;
+    }
+    let all = [
+        OsRelease::Windows10,
+        OsRelease::Windows11,
+        OsRelease::MacVentura,
+        OsRelease::MacSonoma,
+        OsRelease::MacSequoia,
+        OsRelease::Linux,
+    ];
+    let owner = all.into_iter().find(|r| r.added_fonts().contains(&font))?;
+    Some(if release.family().contains(&owner) {
+        ForeignFont::NewerRelease(owner)
+    } else {
+        ForeignFont::OtherOs(owner)
+    })
+}
+
+// ── Text rendering ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAntialiasing {
+    /// ClearType-style LCD antialiasing (coloured glyph fringes).
+    Subpixel,
+    Grayscale,
+}
+
+/// Text and emoji rendering of the claimed OS build.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRendering {
+    /// Font emoji are drawn with.
+    pub emoji_font: String,
+    /// Newest Unicode Emoji version the emoji font covers, e.g. "15.0";
+    /// newer emoji render as tofu.
+    pub emoji_version: String,
+    pub antialiasing: TextAntialiasing,
+    /// Seeded multiplier (within ±0.05 %) applied to `measureText` widths,
+    /// standing in for per-install hinting differences.
+    pub measure_scale: f64,
+    /// Seeded vertical glyph offset in px (0 … 0.25).
+    pub baseline_shift: f64,
+}
+
+impl TextRendering {
+    /// Rendering for a UA-CH platform and platform version, with the seeded
+    /// parts given.
+    pub fn for_platform(
+        ua_platform: &str,
+        ua_platform_version: &str,
+        measure_scale: f64,
+        baseline_shift: f64,
+    ) -> Self {
+        let release = OsRelease::from_platform(ua_platform, ua_platform_version)
+            .unwrap_or(OsRelease::Windows10);
+        let (emoji_font, antialiasing) = match release {
+            OsRelease::Windows10 | OsRelease::Windows11 => {
+                ("Segoe UI Emoji", TextAntialiasing::Subpixel)
+            }
+            OsRelease::MacVentura | OsRelease::MacSonoma | OsRelease::MacSequoia => {
+                ("Apple Color Emoji", TextAntialiasing::Grayscale)
+            }
+            OsRelease::Linux => ("Noto Color Emoji", TextAntialiasing::Grayscale),
+        };
+        Self {
+            emoji_font: emoji_font.into(),
+            emoji_version: emoji_version(release, ua_platform_version).into(),
+            antialiasing,
+            measure_scale,
+            baseline_shift,
+        }
+    }
+}
+
+/// Unicode Emoji version shipped by an OS build.  Point releases bring new
+/// emoji (macOS 13.3, 14.4, 15.4; Windows 11 23H2 and 24H2).
+pub fn emoji_version(release: OsRelease, ua_platform_version: &str) -> &'static str {
+    let parts: Vec<u32> = ua_platform_version
+        .split('.')
+        .map(|p| p.parse().unwrap_or(0))
+        .collect();
+    let minor = parts.get(1).copied().unwrap_or(0);
+    let build = parts.get(2).copied().unwrap_or(0);
+    match release {
+        // Windows 10 stopped receiving emoji updates at 12.0
+        OsRelease::Windows10 => "12.0",
+        OsRelease::Windows11 if build >= 26100 => "15.1",
+        OsRelease::Windows11 if build >= 22631 => "15.0",
+        OsRelease::Windows11 => "14.0",
+        OsRelease::MacVentura if minor >= 3 => "15.0",
+        OsRelease::MacVentura => "14.0",
+        OsRelease::MacSonoma if minor >= 4 => "15.1",
+        OsRelease::MacSonoma => "15.0",
+        OsRelease::MacSequoia if minor >= 4 => "16.0",
+        OsRelease::MacSequoia => "15.1",
+        OsRelease::Linux => "15.0",
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_follows_platform_version() {
+        let r = |p, v| OsRelease::from_platform(p, v).unwrap();
+        assert_eq!(r("Windows", "0.0.19045"), OsRelease::Windows10);
+        assert_eq!(r("Windows", "0.0.26100"), OsRelease::Windows11);
+        assert_eq!(r("Windows", "10.0.0"), OsRelease::Windows10);
+        assert_eq!(r("Windows", "15.0.0"), OsRelease::Windows11);
+        assert_eq!(r("macOS", "13.6.1"), OsRelease::MacVentura);
+        assert_eq!(r("macOS", "15.3.0"), OsRelease::MacSequoia);
+        assert!(OsRelease::from_platform("Android", "14").is_none());
+    }
+
+    #[test]
+    fn catalogues_are_cumulative() {
+        let win10 = OsRelease::Windows10.catalogue();
+        let win11 = OsRelease::Windows11.catalogue();
+        assert!(win10.iter().all(|f| win11.contains(f)));
+        assert!(!win10.contains(&"Segoe UI Variable Text"));
+        assert!(OsRelease::MacSequoia.catalogue().contains(&"SF Pro"));
+        assert!(!OsRelease::MacVentura.catalogue().contains(&"SF Pro"));
+    }
+
+    #[test]
+    fn classifies_foreign_fonts() {
+        assert_eq!(
+            foreign_font(OsRelease::Windows10, "Segoe Fluent Icons"),
+            Some(ForeignFont::NewerRelease(OsRelease::Windows11))
+        );
+        assert_eq!(
+            foreign_font(OsRelease::Windows11, "Menlo"),
+            Some(ForeignFont::OtherOs(OsRelease::MacVentura))
+        );
+        assert_eq!(foreign_font(OsRelease::Windows11, "Segoe UI"), None);
+        assert_eq!(foreign_font(OsRelease::Linux, "Fira Code"), None);
+    }
+
+    #[test]
+    fn emoji_follow_the_os_build() {
+        let win10 = TextRendering::for_platform("Windows", "0.0.19045", 1.0, 0.0);
+        assert_eq!(win10.emoji_font, "Segoe UI Emoji");
+        assert_eq!(win10.emoji_version, "12.0");
+        assert_eq!(win10.antialiasing, TextAntialiasing::Subpixel);
+        let win11 = TextRendering::for_platform("Windows", "0.0.26100", 1.0, 0.0);
+        assert_eq!(win11.emoji_version, "15.1");
+        let mac = TextRendering::for_platform("macOS", "13.2.1", 1.0, 0.0);
+        assert_eq!(mac.emoji_font, "Apple Color Emoji");
+        assert_eq!(mac.emoji_version, "14.0");
+        assert_eq!(mac.antialiasing, TextAntialiasing::Grayscale);
+    }
+}
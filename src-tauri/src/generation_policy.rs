@@ -0,0 +1,404 @@
+// ── Manifold generation policies ──────────────────────────────────────────────
+//
+// The generator draws OS and locale from global web traffic shares: ~70 %
+// Windows, a mix of US and European locales.  An operator creating 200
+// profiles for German and Polish proxies wants those profiles to look
+// German and Polish, and may want more Macs than the world average.
+//
+// A policy gives relative weights per OS and per country.  A batch is split
+// by largest remainder, so 200 profiles at 60/30/10 are exactly 120/60/20,
+// then shuffled.  The OS comes from the seed — seeds are drawn until one
+// generates the wanted OS, so it survives regenerating the fingerprint from
+// the seed — and the locale and timezone are then aligned to the country.
+// Country weights can instead follow the proxy pool, one share per proxy.
+
+use std::collections::BTreeMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::{Fingerprint, FingerprintOrchestrator};
+use crate::profile::{CreateProfileRequest, Profile, ProfileRepo, UpdateProfileRequest};
+use crate::proxy::Proxy;
+
+/// OS names accepted in `GenerationPolicy::os`, with the `ua_platform` the
+/// generator gives them.
+pub const POLICY_OSES: &[(&str, &str)] = &[
+    ("windows", "Windows"),
+    ("macos", "macOS"),
+    ("linux", "Linux"),
+];
+
+/// Most profiles one bulk creation makes.
+pub const MAX_BULK_PROFILES: u32 = 1000;
+
+/// Seeds tried per profile before giving up on its OS.  Linux, the rarest,
+/// comes up for about one seed in twenty.
+const MAX_SEED_ATTEMPTS: u32 = 10_000;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenerationPolicy {
+    /// Relative weight per OS (`windows`, `macos`, `linux`).  Empty keeps
+    /// the generator's own shares.
+    pub os: BTreeMap<String, f64>,
+    /// Relative weight per ISO 3166-1 alpha-2 country.  Empty keeps the
+    /// generator's own locale; countries without a locale table do too.
+    pub countries: BTreeMap<String, f64>,
+    /// Weigh countries by the proxy pool instead of `countries`.
+    pub match_proxy_pool: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkCreateRequest {
+    pub count: u32,
+    /// Profiles are named `<prefix>-001`, `<prefix>-002` …
+    pub name_prefix: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub behavior_profile: Option<String>,
+    #[serde(default)]
+    pub policy: GenerationPolicy,
+    /// Give each profile a proxy in its country, least used first.
+    #[serde(default)]
+    pub assign_proxies: bool,
+}
+
+/// What one profile of a batch is generated as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub os: Option<String>,
+    pub country: Option<String>,
+}
+
+impl GenerationPolicy {
+    pub fn validate(&self) -> Result<()> {
+        for os in self.os.keys() {
+            if !POLICY_OSES.iter().any(|(name, _)| name == os) {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "unknown OS {os:?} (expected windows, macos or linux)"
+                )));
+            }
+        }
+        for cc in self.countries.keys() {
+            if cc.len() != 2 || !cc.bytes().all(|b| b.is_ascii_alphabetic()) {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "{cc:?} is not a two-letter country code"
+                )));
+            }
+        }
+        check_weights("OS", &self.os)?;
+        check_weights("country", &self.countries)
+    }
+
+    /// The country weights for a batch: the policy's, or one share per
+    /// proxy with a known country.
+    pub fn country_weights(&self, pool: &[Proxy]) -> Result<BTreeMap<String, f64>> {
+        if !self.match_proxy_pool {
+            return Ok(self
+                .countries
+                .iter()
+                .map(|(cc, w)| (cc.to_ascii_uppercase(), *w))
+                .collect());
+        }
+        let mut weights = BTreeMap::new();
+        for cc in pool.iter().filter_map(|p| p.country.as_deref()) {
+            *weights.entry(cc.to_ascii_uppercase()).or_insert(0.0) += 1.0;
+        }
+        if weights.is_empty() {
+            return Err(ManifoldError::InvalidArg(
+                "no proxy in the pool has a country to match".into(),
+            ));
+        }
+        Ok(weights)
+    }
+}
+
+fn check_weights(what: &str, weights: &BTreeMap<String, f64>) -> Result<()> {
+    if weights.values().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(ManifoldError::InvalidArg(format!(
+            "{what} weights must be zero or more"
+        )));
+    }
+    if !weights.is_empty() && weights.values().sum::<f64>() <= 0.0 {
+        return Err(ManifoldError::InvalidArg(format!(
+            "{what} weights add up to zero"
+        )));
+    }
+    Ok(())
+}
+
+/// Split `count` by `weights` with the largest-remainder method: every
+/// share gets its whole part, the leftover goes to the largest fractions.
+pub fn apportion(weights: &BTreeMap<String, f64>, count: u32) -> Vec<(String, u32)> {
+    let total: f64 = weights.values().sum();
+    if weights.is_empty() || total <= 0.0 {
+        return Vec::new();
+    }
+    let mut shares: Vec<(String, u32, f64)> = weights
+        .iter()
+        .map(|(key, w)| {
+            let exact = f64::from(count) * w / total;
+            (key.clone(), exact.floor() as u32, exact.fract())
+        })
+        .collect();
+    let assigned: u32 = shares.iter().map(|s| s.1).sum();
+    let mut by_fraction: Vec<usize> = (0..shares.len()).collect();
+    by_fraction.sort_by(|&a, &b| shares[b].2.total_cmp(&shares[a].2));
+    for &i in by_fraction.iter().take((count - assigned) as usize) {
+        shares[i].1 += 1;
+    }
+    shares.into_iter().map(|(key, n, _)| (key, n)).collect()
+}
+
+/// The OS and country of each of `count` profiles, in random order.
+pub fn plan<R: Rng>(
+    os: &BTreeMap<String, f64>,
+    countries: &BTreeMap<String, f64>,
+    count: u32,
+    rng: &mut R,
+) -> Vec<Slot> {
+    let expand = |weights: &BTreeMap<String, f64>, rng: &mut R| -> Vec<Option<String>> {
+        let mut values: Vec<Option<String>> = apportion(weights, count)
+            .into_iter()
+            .flat_map(|(key, n)| std::iter::repeat_n(Some(key), n as usize))
+            .collect();
+        values.resize(count as usize, None);
+        values.shuffle(rng);
+        values
+    };
+    let oses = expand(os, rng);
+    let countries = expand(countries, rng);
+    oses.into_iter()
+        .zip(countries)
+        .map(|(os, country)| Slot { os, country })
+        .collect()
+}
+
+/// A random seed whose fingerprint runs on `os`, with that fingerprint.
+pub fn seed_for_os<R: Rng>(os: Option<&str>, rng: &mut R) -> Result<(u64, Fingerprint)> {
+    let platform = match os {
+        None => {
+            let seed = rng.gen();
+            return Ok((seed, FingerprintOrchestrator::generate(seed)));
+        }
+        Some(os) => POLICY_OSES
+            .iter()
+            .find(|(name, _)| *name == os)
+            .map(|(_, platform)| *platform)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("unknown OS {os:?}")))?,
+    };
+    for _ in 0..MAX_SEED_ATTEMPTS {
+        let seed = rng.gen();
+        let fp = FingerprintOrchestrator::generate(seed);
+        if fp.ua_platform == platform {
+            return Ok((seed, fp));
+        }
+    }
+    Err(ManifoldError::Other(format!(
+        "no {platform} fingerprint in {MAX_SEED_ATTEMPTS} seeds"
+    )))
+}
+
+// ── Bulk creation ─────────────────────────────────────────────────────────────
+
+/// Create `req.count` profiles following `req.policy`.  `pool` is every
+/// proxy; `assigned` counts the profiles already using each of them.  On
+/// failure the profiles created so far are deleted again.
+pub fn bulk_create(
+    profiles: &ProfileRepo,
+    pool: &[Proxy],
+    mut assigned: BTreeMap<String, u32>,
+    req: BulkCreateRequest,
+) -> Result<Vec<Profile>> {
+    if req.count == 0 || req.count > MAX_BULK_PROFILES {
+        return Err(ManifoldError::InvalidArg(format!(
+            "count must be between 1 and {MAX_BULK_PROFILES}"
+        )));
+    }
+    if req.name_prefix.trim().is_empty() {
+        return Err(ManifoldError::InvalidArg("name prefix is empty".into()));
+    }
+    req.policy.validate()?;
+    let countries = req.policy.country_weights(pool)?;
+
+    let mut rng = rand::thread_rng();
+    let slots = plan(&req.policy.os, &countries, req.count, &mut rng);
+    let width = req.count.to_string().len().max(3);
+    let mut created: Vec<Profile> = Vec::with_capacity(slots.len());
+    for (i, slot) in slots.into_iter().enumerate() {
+        let proxy = if req.assign_proxies {
+            least_used(pool, &assigned, slot.country.as_deref())
+        } else {
+            None
+        };
+        let result = create_one(
+            profiles,
+            &req,
+            format!("{}-{:0width$}", req.name_prefix, i + 1),
+            &slot,
+            proxy,
+            &mut rng,
+        );
+        match result {
+            Ok(profile) => {
+                if let Some(p) = proxy {
+                    *assigned.entry(p.id.clone()).or_insert(0) += 1;
+                }
+                created.push(profile);
+            }
+            Err(e) => {
+                for profile in &created {
+                    profiles.delete(&profile.id).ok();
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(created)
+}
+
+fn create_one<R: Rng>(
+    profiles: &ProfileRepo,
+    req: &BulkCreateRequest,
+    name: String,
+    slot: &Slot,
+    proxy: Option<&Proxy>,
+    rng: &mut R,
+) -> Result<Profile> {
+    let (seed, mut fingerprint) = seed_for_os(slot.os.as_deref(), rng)?;
+    // `create` already aligns the fingerprint to the proxy's country; only a
+    // planned country differing from it needs enforcing afterwards
+    let country = slot
+        .country
+        .clone()
+        .filter(|c| proxy.and_then(|p| p.country.as_deref()) != Some(c.as_str()));
+    let created = profiles.create(CreateProfileRequest {
+        name,
+        seed: Some(seed),
+        proxy_id: proxy.map(|p| p.id.clone()),
+        notes: None,
+        tags: Some(req.tags.clone()),
+        behavior_profile: req.behavior_profile.clone(),
+        persona: None,
+    })?;
+    let Some(country) = country else {
+        return Ok(created);
+    };
+    FingerprintOrchestrator::enforce_geo(&mut fingerprint, &country);
+    let updated = profiles.update(
+        &created.id,
+        UpdateProfileRequest {
+            name: None,
+            fingerprint: Some(fingerprint),
+            human: None,
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            tls_bridge: None,
+            persona: None,
+        },
+    );
+    if updated.is_err() {
+        profiles.delete(&created.id).ok();
+    }
+    updated
+}
+
+/// The proxy in `country` (any country when `None`) with the fewest
+/// profiles on it.
+fn least_used<'a>(
+    pool: &'a [Proxy],
+    assigned: &BTreeMap<String, u32>,
+    country: Option<&str>,
+) -> Option<&'a Proxy> {
+    pool.iter()
+        .filter(|p| {
+            country.is_none_or(|cc| {
+                p.country
+                    .as_deref()
+                    .is_some_and(|pc| pc.eq_ignore_ascii_case(cc))
+            })
+        })
+        .min_by_key(|p| (assigned.get(&p.id).copied().unwrap_or(0), p.name.clone()))
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    fn weights(pairs: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        pairs.iter().map(|(k, w)| (k.to_string(), *w)).collect()
+    }
+
+    #[test]
+    fn batches_match_the_policy_shares_exactly() {
+        let os = weights(&[("windows", 60.0), ("macos", 30.0), ("linux", 10.0)]);
+        let shares = apportion(&os, 200);
+        assert_eq!(
+            shares,
+            [
+                ("linux".to_string(), 20),
+                ("macos".to_string(), 60),
+                ("windows".to_string(), 120)
+            ]
+        );
+        // Leftovers go to the largest fractions
+        let thirds = apportion(&weights(&[("a", 1.0), ("b", 1.0), ("c", 1.0)]), 10);
+        assert_eq!(thirds.iter().map(|s| s.1).sum::<u32>(), 10);
+
+        let mut rng = SmallRng::seed_from_u64(1);
+        let slots = plan(&os, &weights(&[("DE", 3.0), ("PL", 1.0)]), 8, &mut rng);
+        assert_eq!(slots.len(), 8);
+        let german = slots
+            .iter()
+            .filter(|s| s.country.as_deref() == Some("DE"))
+            .count();
+        assert_eq!(german, 6);
+        assert!(plan(&BTreeMap::new(), &BTreeMap::new(), 3, &mut rng)
+            .iter()
+            .all(|s| s.os.is_none() && s.country.is_none()));
+    }
+
+    #[test]
+    fn seeds_are_drawn_for_the_wanted_os() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let (seed, fp) = seed_for_os(Some("linux"), &mut rng).unwrap();
+        assert_eq!(fp.ua_platform, "Linux");
+        // The profile is reproducible from its seed alone
+        assert_eq!(
+            FingerprintOrchestrator::generate(seed).user_agent,
+            fp.user_agent
+        );
+        assert!(seed_for_os(Some("beos"), &mut rng).is_err());
+    }
+
+    #[test]
+    fn policy_validation_rejects_bad_keys_and_weights() {
+        let mut policy = GenerationPolicy::default();
+        assert!(policy.validate().is_ok());
+        policy.os = weights(&[("windows", 0.0)]);
+        assert!(policy.validate().is_err());
+        policy.os = weights(&[("amiga", 1.0)]);
+        assert!(policy.validate().is_err());
+        policy.os = BTreeMap::new();
+        policy.countries = weights(&[("GER", 1.0)]);
+        assert!(policy.validate().is_err());
+        policy.countries = weights(&[("de", 1.0)]);
+        assert_eq!(
+            policy.country_weights(&[]).unwrap(),
+            weights(&[("DE", 1.0)])
+        );
+        policy.match_proxy_pool = true;
+        assert!(policy.country_weights(&[]).is_err());
+    }
+}
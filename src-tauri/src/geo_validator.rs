@@ -14,7 +14,27 @@
 //   5. Locale ↔ timezone cross-check (e.g. de-DE must not be in Asia/*)
 //   6. Platform ↔ screen DPI realism (macOS retina pixel_ratio gate)
 //   7. ua_platform ↔ platform string consistency
+//   8. Font list ↔ OS release (fonts of another OS or a newer release,
+//      implausible font counts)
+//   9. Colour gamut / HDR ↔ screen and OS
+//  10. JS engine expectations ↔ claimed Chrome major and locale (UA and
+//      UA-CH majors agree, the major is one whose builtins are modelled,
+//      the locale is a tag ICU resolves as-is)
+//  11. Intl data (calendar, numbering, week, hour cycle, currency) ↔ locale
+//  12. Claimed WebGL renderer ↔ the host's GPU (tier, MAX_TEXTURE_SIZE and
+//      extensions pass through ANGLE), with `validate_with_host`
+//  13. Screen layout ↔ screen (a lone monitor has no offset, the window
+//      ranges reach the browser's monitor)
+//  14. Proxy country ↔ GEO_LOCALE_MAP (a country `enforce_geo` has no
+//      choices for is reported rather than silently left as is)
+//  15. Proxy region (US state, Canadian province, Australian state or a
+//      city) ↔ the timezone, so a Utah exit claims America/Denver
+//  16. Host clock ↔ NTP, against the tolerance windows (`clock_guard`),
+//      with `validate_with_host`
 //
+// `geo_options` lists the locale / Accept-Language / timezone combinations
+// `enforce_geo` assigns for a country, for editors to offer instead of free
+// text.
 // Usage:
 //
 //   let violations = GeoValidator::validate(&fingerprint, "US");
@@ -23,11 +43,23 @@
 //   }
 //
 //   // Auto-correct (mutates fingerprint in-place):
-//   GeoValidator::auto_correct(&mut fingerprint, "US", seed);
+//   GeoValidator::auto_correct(&mut fingerprint, "US", Some("UT"), seed);
 
 use serde::{Deserialize, Serialize};
 
-use crate::fingerprint::{Fingerprint, FingerprintOrchestrator};
+use crate::clock_guard::{ClockTolerance, HostClock};
+use crate::engine_quirks::{chrome_major, MIN_MODELLED_MAJOR};
+use crate::fingerprint::{
+    geo_locale_map, region_timezone, ColorGamut, Fingerprint, FingerprintOrchestrator,
+    TASKBAR_HEIGHT,
+};
+use crate::fonts::{foreign_font, ForeignFont, OsRelease};
+use crate::gpu_probe::{tier_capabilities, tier_of, HostGpu};
+use crate::intl::IntlProfile;
+
+/// Codes about the claimed Chrome being old.  Profiles pinned to a browser
+/// version for a legacy site are old on purpose and don't get them.
+pub const VERSION_AGE_CODES: &[&str] = &["ENGINE_MAJOR_UNMODELLED"];
 
 // ── Violation severity ────────────────────────────────────────────────────────
 
@@ -127,6 +159,11 @@ fn allowed_locale_prefixes(cc: &str) -> &'static [&'static str] {
         "MX" => &["es-MX", "es-"],
         "AR" => &["es-AR", "es-"],
         "CO" => &["es-CO", "es-"],
+        "CL" => &["es-CL", "es-"],
+        "PE" => &["es-PE", "es-"],
+        "VE" => &["es-VE", "es-"],
+        "EC" => &["es-EC", "es-"],
+        "UY" => &["es-UY", "es-"],
         "IT" => &["it-IT", "it-"],
         "PT" => &["pt-PT", "pt-"],
         "BR" => &["pt-BR", "pt-"],
@@ -141,10 +178,16 @@ fn allowed_locale_prefixes(cc: &str) -> &'static [&'static str] {
         "RO" => &["ro-RO", "ro-"],
         "BG" => &["bg-BG", "bg-"],
         "HR" => &["hr-HR", "hr-"],
+        "RS" => &["sr-RS", "sr-"],
+        "SI" => &["sl-SI", "sl-"],
+        "LT" => &["lt-LT", "lt-"],
+        "LV" => &["lv-LV", "lv-"],
+        "EE" => &["et-EE", "et-"],
         "GR" => &["el-GR", "el-"],
         "TR" => &["tr-TR", "tr-"],
         "UA" => &["uk-UA", "uk-", "ru-"],
         "RU" => &["ru-RU", "ru-"],
+        "KZ" => &["ru-KZ", "kk-KZ", "ru-", "kk-"],
         "JP" => &["ja-JP", "ja-"],
         "KR" => &["ko-KR", "ko-"],
         "CN" => &["zh-CN", "zh-Hans", "zh-"],
@@ -152,6 +195,8 @@ fn allowed_locale_prefixes(cc: &str) -> &'static [&'static str] {
         "HK" => &["zh-HK", "zh-", "en-HK", "en-"],
         "SG" => &["en-SG", "zh-SG", "ms-SG", "en-", "zh-"],
         "IN" => &["en-IN", "hi-IN", "en-", "hi-"],
+        "PK" => &["en-PK", "ur-PK", "en-", "ur-"],
+        "BD" => &["bn-BD", "bn-", "en-"],
         "ID" => &["id-ID", "id-"],
         "MY" => &["ms-MY", "en-MY", "ms-", "en-"],
         "TH" => &["th-TH", "th-"],
@@ -160,6 +205,8 @@ fn allowed_locale_prefixes(cc: &str) -> &'static [&'static str] {
         "SA" => &["ar-SA", "ar-"],
         "AE" => &["ar-AE", "ar-", "en-"],
         "IL" => &["he-IL", "he-", "en-"],
+        "EG" => &["ar-EG", "ar-", "en-"],
+        "MA" => &["fr-MA", "ar-MA", "fr-", "ar-"],
         "ZA" => &["en-ZA", "af-ZA", "en-", "af-"],
         "NG" => &["en-NG", "en-"],
         "KE" => &["sw-KE", "en-KE", "en-"],
@@ -170,19 +217,22 @@ fn allowed_locale_prefixes(cc: &str) -> &'static [&'static str] {
 /// Allowed IANA timezone prefixes for a given country code.
 fn allowed_tz_prefixes(cc: &str) -> &'static [&'static str] {
     match cc {
-        "US" | "CA" | "MX" | "AR" | "CO" | "BR" | "CL" | "PE" | "VE" | "EC" | "BO" | "PY"
-        | "UY" => &["America/"],
+        // Hawaii is the one US state off the American continent
+        "US" => &["America/", "Pacific/Honolulu"],
+        "CA" | "MX" | "AR" | "CO" | "BR" | "CL" | "PE" | "VE" | "EC" | "BO" | "PY" | "UY" => {
+            &["America/"]
+        }
         "GB" | "IE" => &["Europe/London", "Europe/Dublin"],
         "DE" | "AT" | "CH" | "FR" | "BE" | "NL" | "ES" | "IT" | "PT" | "PL" | "SE" | "NO"
-        | "DK" | "FI" | "CZ" | "SK" | "HU" | "RO" | "BG" | "HR" | "GR" | "TR" | "UA" | "RU" => {
-            &["Europe/"]
-        }
+        | "DK" | "FI" | "CZ" | "SK" | "HU" | "RO" | "BG" | "HR" | "GR" | "TR" | "UA" | "RS"
+        | "SI" | "LT" | "LV" | "EE" => &["Europe/"],
+        "RU" => &["Europe/", "Asia/"],
         "AU" | "NZ" | "PG" | "FJ" => &["Australia/", "Pacific/"],
         "JP" => &["Asia/Tokyo"],
         "KR" => &["Asia/Seoul"],
         "CN" | "TW" | "HK" | "SG" | "MY" | "ID" | "PH" | "TH" | "VN" | "IN" | "SA" | "AE"
-        | "IL" => &["Asia/"],
-        "ZA" | "NG" | "KE" | "ET" | "GH" | "TZ" | "UG" => &["Africa/"],
+        | "IL" | "PK" | "BD" | "KZ" => &["Asia/"],
+        "ZA" | "NG" | "KE" | "ET" | "GH" | "TZ" | "UG" | "EG" | "MA" => &["Africa/"],
         _ => &[], // Unknown: no restriction
     }
 }
@@ -192,46 +242,26 @@ fn is_4k_or_above(width: u32, height: u32) -> bool {
     width >= 3840 && height >= 2160
 }
 
-/// Countries where 4K desktop penetration is realistic (>5% of desktop users).
-/// Source: Steam Hardware Survey Q1-2025 + StatCounter by country.
+/// 4K desktops are common enough in `cc` (over 5 %), per the priors in use.
 fn allows_4k(cc: &str) -> bool {
-    matches!(
-        cc,
-        "US" | "CA"
-            | "GB"
-            | "AU"
-            | "DE"
-            | "FR"
-            | "NL"
-            | "SE"
-            | "NO"
-            | "DK"
-            | "FI"
-            | "CH"
-            | "AT"
-            | "JP"
-            | "KR"
-            | "SG"
-            | "HK"
-            | "TW"
-            | "NZ"
-            | "IE"
-            | "BE"
-            | "IT"
-            | "ES"
-            | "PT"
-            | "PL"
-    )
+    crate::priors::current().allows_uhd(cc)
 }
 
-/// Countries where high pixel-ratio (2.0+) screens are common on desktops/laptops.
+/// High pixel-ratio (2.0+) screens are common enough in `cc`: everywhere but
+/// the markets with very low consumer electronics spend the priors list.
 fn allows_high_dpr(cc: &str) -> bool {
-    // Apple devices are prevalent globally; OLED/Retina laptops growing everywhere.
-    // Only flag DPR=2 on countries with very low consumer electronics spend.
-    !matches!(
-        cc,
-        "NG" | "ET" | "UG" | "TZ" | "GH" | "KE" | "BD" | "KH" | "LA" | "MM"
-    )
+    crate::priors::current().allows_high_dpr(cc)
+}
+
+// ── Host facts ────────────────────────────────────────────────────────────────
+
+/// What is known about the host the profile runs on, for the rules that
+/// compare the fingerprint with it.  Each check is skipped without its fact.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HostFacts<'a> {
+    pub gpu: Option<&'a HostGpu>,
+    pub clock: Option<&'a HostClock>,
+    pub clock_tolerance: ClockTolerance,
 }
 
 // ── GeoValidator ─────────────────────────────────────────────────────────────
@@ -248,6 +278,18 @@ impl GeoValidator {
     /// Pass `None` to run only the internal self-consistency checks
     /// (locale ↔ timezone, platform ↔ ua_platform, etc.).
     pub fn validate(fp: &Fingerprint, proxy_country: Option<&str>) -> Vec<GeoViolation> {
+        Self::validate_with_host(fp, proxy_country, None, &HostFacts::default())
+    }
+
+    /// `validate`, plus the claimed GPU and the clock against the host's
+    /// recorded ones and the timezone against the proxy's region (a US
+    /// state, a city) if known.
+    pub fn validate_with_host(
+        fp: &Fingerprint,
+        proxy_country: Option<&str>,
+        proxy_region: Option<&str>,
+        host: &HostFacts,
+    ) -> Vec<GeoViolation> {
         let mut violations: Vec<GeoViolation> = Vec::new();
 
         // ── Internal consistency checks (no proxy needed) ─────────────────
@@ -264,22 +306,55 @@ impl GeoValidator {
         // 4. macOS retina pixel-ratio gate
         Self::check_macos_dpr(fp, &mut violations);
 
+        // 5. Font list vs the OS release the UA claims
+        Self::check_fonts(fp, &mut violations);
+
+        // 6. Colour gamut / HDR vs display and OS
+        Self::check_color_caps(fp, &mut violations);
+
+        // 7. Engine expectations vs the claimed Chrome major and locale
+        Self::check_engine(fp, &mut violations);
+
+        // 8. Intl data vs locale
+        Self::check_intl(fp, &mut violations);
+
+        // 9. Multi-monitor layout vs the screen
+        Self::check_screen_layout(fp, &mut violations);
+
+        // 10. Claimed GPU vs what the host's GPU passes through
+        if let Some(gpu) = host.gpu {
+            Self::check_host_gpu(fp, gpu, &mut violations);
+        }
+
+        // 11. Host clock vs NTP, within the tolerance windows
+        if let Some(clock) = host.clock.filter(|c| !c.is_stale()) {
+            Self::check_clock_skew(fp, clock, host.clock_tolerance, &mut violations);
+        }
+
         // ── Proxy-country-specific checks ─────────────────────────────────
         if let Some(cc) = proxy_country {
             let cc = cc.to_uppercase();
             let cc = cc.as_str();
 
-            // 5. Locale vs proxy country
+            // 12. Locale vs proxy country
             Self::check_locale_vs_country(fp, cc, &mut violations);
 
-            // 6. Timezone vs proxy country
+            // 13. Timezone vs proxy country
             Self::check_tz_vs_country(fp, cc, &mut violations);
 
-            // 7. 4K screen gating
+            // 14. 4K screen gating
             Self::check_4k_vs_country(fp, cc, &mut violations);
 
-            // 8. High DPR gating
+            // 15. High DPR gating
             Self::check_dpr_vs_country(fp, cc, &mut violations);
+
+            // 16. Countries enforce_geo can't align to
+            Self::check_country_mapped(cc, &mut violations);
+
+            // 17. Timezone vs the proxy's region
+            if let Some(region) = proxy_region {
+                Self::check_tz_vs_region(fp, cc, region, &mut violations);
+            }
         }
 
         // Sort: Hard first, then Soft, then Info
@@ -292,15 +367,24 @@ impl GeoValidator {
         violations
     }
 
-    /// Auto-correct a fingerprint for a given proxy country.
+    /// Auto-correct a fingerprint for a given proxy country, and region when
+    /// the proxy has one.
     ///
-    /// Calls `enforce_geo` to align locale/timezone, then re-checks and
+    /// Calls `enforce_geo_region` to align locale/timezone, then re-checks and
     /// applies any remaining resolvable violations (screen resolution gating).
     ///
     /// Returns the list of violations that were automatically fixed and any
     /// residual violations that require manual intervention.
-    pub fn auto_correct(fp: &mut Fingerprint, proxy_country: &str, seed: u64) -> AutoCorrectResult {
-        let before = Self::validate(fp, Some(proxy_country));
+    pub fn auto_correct(
+        fp: &mut Fingerprint,
+        proxy_country: &str,
+        proxy_region: Option<&str>,
+        seed: u64,
+    ) -> AutoCorrectResult {
+        let validate = |fp: &Fingerprint| {
+            Self::validate_with_host(fp, Some(proxy_country), proxy_region, &HostFacts::default())
+        };
+        let before = validate(fp);
         if before.is_empty() {
             return AutoCorrectResult {
                 fixed: vec![],
@@ -309,7 +393,7 @@ impl GeoValidator {
         }
 
         // Apply geo enforcement (aligns locale, accept-language, timezone)
-        FingerprintOrchestrator::enforce_geo(fp, proxy_country);
+        FingerprintOrchestrator::enforce_geo_region(fp, proxy_country, proxy_region);
 
         let cc = proxy_country.to_uppercase();
         let cc = cc.as_str();
@@ -330,7 +414,7 @@ impl GeoValidator {
             fp.pixel_ratio = 1.0;
         }
 
-        let after = Self::validate(fp, Some(proxy_country));
+        let after = validate(fp);
 
         // Violations that disappeared = fixed
         let fixed: Vec<GeoViolation> = before
@@ -402,7 +486,8 @@ impl GeoValidator {
             "KR" => fp.timezone != "Asia/Seoul",
             "AU" => tz_continent != "australia",
             "BR" => tz_continent != "america",
-            "US" | "CA" => tz_continent != "america",
+            "US" => tz_continent != "america" && fp.timezone != "Pacific/Honolulu",
+            "CA" => tz_continent != "america",
             _ => false,
         };
 
@@ -516,6 +601,338 @@ impl GeoValidator {
         }
     }
 
+    fn check_fonts(fp: &Fingerprint, out: &mut Vec<GeoViolation>) {
+        let Some(release) = OsRelease::from_platform(&fp.ua_platform, &fp.ua_platform_version)
+        else {
+            return;
+        };
+
+        let mut other_os = Vec::new();
+        let mut newer = Vec::new();
+        for font in &fp.font_subset {
+            match foreign_font(release, font) {
+                Some(ForeignFont::OtherOs(_)) => other_os.push(font.as_str()),
+                Some(ForeignFont::NewerRelease(_)) => newer.push(font.as_str()),
+                None => {}
+            }
+        }
+        if !other_os.is_empty() {
+            out.push(GeoViolation::hard(
+                "FONT_OS_MISMATCH",
+                format!(
+                    "Fonts {} do not ship with {} ({release:?})",
+                    other_os.join(", "),
+                    fp.ua_platform
+                ),
+                vec!["font_subset", "ua_platform"],
+                "Remove fonts of other operating systems or regenerate the font list",
+            ));
+        }
+        if !newer.is_empty() {
+            out.push(GeoViolation::hard(
+                "FONT_RELEASE_MISMATCH",
+                format!(
+                    "Fonts {} only ship with releases newer than {} {} ({release:?})",
+                    newer.join(", "),
+                    fp.ua_platform,
+                    fp.ua_platform_version
+                ),
+                vec!["font_subset", "ua_platform_version"],
+                "Remove the newer fonts or raise ua_platform_version",
+            ));
+        }
+
+        let (min, max) = release.font_count_range();
+        let count = fp.font_subset.len();
+        if count < min {
+            out.push(GeoViolation::soft(
+                "FONT_COUNT_LOW",
+                format!("{count} fonts is fewer than a stock {release:?} install reports (≥{min})"),
+                vec!["font_subset"],
+                "Regenerate the font list for the profile's OS release",
+            ));
+        } else if count > max {
+            out.push(GeoViolation::info(
+                "FONT_COUNT_HIGH",
+                format!("{count} fonts is unusually many for {release:?} (typically ≤{max})"),
+                vec!["font_subset"],
+                "Trim user-installed fonts from the font list",
+            ));
+        }
+    }
+
+    fn check_host_gpu(fp: &Fingerprint, host: &HostGpu, out: &mut Vec<GeoViolation>) {
+        let Some(claimed) = tier_of(&fp.webgl_renderer) else {
+            return;
+        };
+        let host_name = host.renderer.as_deref().unwrap_or("unknown GPU");
+        if let Some(host_tier) = host.tier.filter(|t| *t < claimed) {
+            out.push(GeoViolation::soft(
+                "GPU_ABOVE_HOST_TIER",
+                format!(
+                    "Claims a {claimed:?} GPU ({}) on a host with a {host_tier:?} one \
+                     ({host_name}); WebGL speed and precision follow the host",
+                    fp.webgl_renderer
+                ),
+                vec!["webgl_renderer"],
+                "Pick a renderer of the host's tier or run the profile on a stronger host",
+            ));
+        }
+        let Some(actual) = host.capabilities() else {
+            return;
+        };
+        let expected = tier_capabilities(claimed, Some(&fp.webgl_renderer));
+        if actual.max_texture_size != expected.max_texture_size {
+            out.push(GeoViolation::hard(
+                "GPU_TEXTURE_SIZE_MISMATCH",
+                format!(
+                    "MAX_TEXTURE_SIZE passes through as {} from {host_name}; {} reports {}",
+                    actual.max_texture_size, fp.webgl_renderer, expected.max_texture_size
+                ),
+                vec!["webgl_renderer"],
+                "Pick a renderer whose limits match the host's GPU",
+            ));
+        }
+        // Unknown host extensions (probed by name only) can't be compared
+        if host.max_texture_size.is_some() {
+            let missing: Vec<&str> = expected
+                .extensions
+                .iter()
+                .filter(|e| !actual.extensions.contains(e))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                out.push(GeoViolation::soft(
+                    "GPU_EXTENSIONS_MISSING",
+                    format!(
+                        "{} exposes {}, which {host_name} can't provide",
+                        fp.webgl_renderer,
+                        missing.join(", ")
+                    ),
+                    vec!["webgl_renderer"],
+                    "Pick a renderer whose extensions the host's GPU supports",
+                ));
+            }
+        }
+    }
+
+    fn check_clock_skew(
+        fp: &Fingerprint,
+        clock: &HostClock,
+        tolerance: ClockTolerance,
+        out: &mut Vec<GeoViolation>,
+    ) {
+        let off = clock.skew_ms.unsigned_abs();
+        if off <= tolerance.soft_ms {
+            return;
+        }
+        let direction = if clock.skew_ms > 0 {
+            "ahead of"
+        } else {
+            "behind"
+        };
+        let description = format!(
+            "Host clock is {:.1} s {direction} {}; the page's time in {} is off \
+             by as much, which breaks one-time codes and time-checked requests",
+            off as f64 / 1000.0,
+            clock.server,
+            fp.timezone
+        );
+        let fields = vec!["host_clock"];
+        let suggestion = "Sync the host clock (NTP) and re-measure it";
+        out.push(if off > tolerance.hard_ms {
+            GeoViolation::hard("CLOCK_SKEW", description, fields, suggestion)
+        } else {
+            GeoViolation::soft("CLOCK_SKEW", description, fields, suggestion)
+        });
+    }
+
+    fn check_color_caps(fp: &Fingerprint, out: &mut Vec<GeoViolation>) {
+        if fp.hdr && fp.color_gamut == ColorGamut::Srgb {
+            out.push(GeoViolation::hard(
+                "HDR_WITHOUT_WIDE_GAMUT",
+                "HDR display reporting an sRGB-only colour gamut; HDR panels are wide gamut",
+                vec!["hdr", "color_gamut"],
+                "Set color_gamut to p3 or turn hdr off",
+            ));
+        }
+        if fp.ua_platform == "macOS" && fp.pixel_ratio >= 2.0 && fp.color_gamut == ColorGamut::Srgb
+        {
+            out.push(GeoViolation::soft(
+                "RETINA_SRGB_GAMUT",
+                "Retina Mac reporting an sRGB-only colour gamut; every Retina panel is P3",
+                vec!["ua_platform", "pixel_ratio", "color_gamut"],
+                "Set color_gamut to p3",
+            ));
+        }
+        let retina = fp.ua_platform == "macOS" && fp.pixel_ratio >= 2.0;
+        if fp.color_gamut == ColorGamut::P3 && !retina && fp.screen_width < 2560 {
+            out.push(GeoViolation::soft(
+                "P3_ON_BASIC_PANEL",
+                format!(
+                    "P3 colour gamut on a {}×{} non-Retina display; wide gamut is \
+                     rare below QHD",
+                    fp.screen_width, fp.screen_height
+                ),
+                vec!["color_gamut", "screen_width", "pixel_ratio"],
+                "Set color_gamut to srgb or use a higher-end screen",
+            ));
+        }
+        if fp.hdr && fp.ua_platform == "Linux" {
+            out.push(GeoViolation::info(
+                "HDR_ON_LINUX",
+                "HDR reported on Linux, where Chrome rarely enables it",
+                vec!["hdr", "ua_platform"],
+                "Turn hdr off for Linux profiles",
+            ));
+        }
+    }
+
+    fn check_screen_layout(fp: &Fingerprint, out: &mut Vec<GeoViolation>) {
+        let Some(layout) = &fp.screen_layout else {
+            return;
+        };
+        if layout.monitors <= 1 && (layout.avail_left, layout.avail_top) != (0, 0) {
+            out.push(GeoViolation::hard(
+                "SCREEN_OFFSET_SINGLE_MONITOR",
+                format!(
+                    "availLeft/availTop ({}, {}) on a single-monitor desktop; only a \
+                     secondary monitor is offset from the origin",
+                    layout.avail_left, layout.avail_top
+                ),
+                vec!["screen_layout.monitors", "screen_layout.avail_left"],
+                "Set monitors to 2 or more, or move the screen to (0, 0)",
+            ));
+        }
+        let right = layout.avail_left + fp.screen_width as i32;
+        let bottom = layout.avail_top + fp.screen_height.saturating_sub(TASKBAR_HEIGHT) as i32;
+        let reaches =
+            |(lo, hi): (i32, i32), start: i32, end: i32| lo <= hi && hi >= start && lo < end;
+        if !reaches(layout.window_x, layout.avail_left, right)
+            || !reaches(layout.window_y, layout.avail_top, bottom)
+        {
+            out.push(GeoViolation::soft(
+                "WINDOW_RANGE_OFF_SCREEN",
+                format!(
+                    "window screenX {:?} / screenY {:?} never land on the browser's monitor \
+                     ({}..{}, {}..{}); every window is pinned to its edge",
+                    layout.window_x,
+                    layout.window_y,
+                    layout.avail_left,
+                    right,
+                    layout.avail_top,
+                    bottom
+                ),
+                vec!["screen_layout.window_x", "screen_layout.window_y"],
+                "Pick window ranges inside the monitor's available area",
+            ));
+        }
+    }
+
+    fn check_engine(fp: &Fingerprint, out: &mut Vec<GeoViolation>) {
+        let ua_major = chrome_major(fp);
+        let brand_major = fp
+            .ua_brands
+            .iter()
+            .find(|b| b.brand == "Google Chrome")
+            .and_then(|b| b.version.split('.').next()?.parse::<u32>().ok());
+        if brand_major.is_some() && brand_major != ua_major {
+            out.push(GeoViolation::hard(
+                "CHROME_MAJOR_MISMATCH",
+                format!(
+                    "UA claims Chrome {ua_major:?} but UA-CH brands claim {brand_major:?}; \
+                     builtin probes can only agree with one of them"
+                ),
+                vec!["user_agent", "ua_brands"],
+                "Use set_chrome_major() to move UA and brands together",
+            ));
+        }
+        if ua_major.is_some_and(|m| m < MIN_MODELLED_MAJOR) {
+            out.push(GeoViolation::info(
+                "ENGINE_MAJOR_UNMODELLED",
+                format!(
+                    "Chrome {} predates the modelled engine values (≥{MIN_MODELLED_MAJOR}); \
+                     builtin checks are approximate",
+                    ua_major.unwrap_or(0)
+                ),
+                vec!["user_agent"],
+                "Move the profile to a current Chrome major",
+            ));
+        }
+
+        // ICU falls back to the default locale for tags it can't parse, so
+        // Intl output stops matching navigator.language
+        let mut subtags = fp.locale.split('-');
+        let lang_ok = subtags.next().is_some_and(|l| {
+            (2..=3).contains(&l.len()) && l.bytes().all(|b| b.is_ascii_lowercase())
+        });
+        let rest_ok = subtags
+            .all(|t| (2..=8).contains(&t.len()) && t.bytes().all(|b| b.is_ascii_alphanumeric()));
+        if !lang_ok || !rest_ok {
+            out.push(GeoViolation::hard(
+                "LOCALE_NOT_BCP47",
+                format!(
+                    "Locale '{}' is not a BCP-47 tag; Intl resolves the default locale instead",
+                    fp.locale
+                ),
+                vec!["locale"],
+                "Use a tag like en-US (hyphen, lowercase language, uppercase region)",
+            ));
+        }
+    }
+
+    fn check_intl(fp: &Fingerprint, out: &mut Vec<GeoViolation>) {
+        let Some(intl) = &fp.intl else {
+            return;
+        };
+        let expected = IntlProfile::for_locale(&fp.locale);
+        let mut stale = Vec::new();
+        if intl.calendar != expected.calendar {
+            stale.push("calendar");
+        }
+        if intl.numbering_system != expected.numbering_system {
+            stale.push("numbering system");
+        }
+        if intl.hour_cycle != expected.hour_cycle {
+            stale.push("hour cycle");
+        }
+        if intl.first_day_of_week != expected.first_day_of_week
+            || intl.weekend != expected.weekend
+            || intl.minimal_days != expected.minimal_days
+        {
+            stale.push("week info");
+        }
+        if intl.currency != expected.currency || intl.currency_sample != expected.currency_sample {
+            stale.push("currency");
+        }
+        if !stale.is_empty() {
+            out.push(GeoViolation::hard(
+                "INTL_LOCALE_MISMATCH",
+                format!(
+                    "Intl {} do not belong to locale '{}'",
+                    stale.join(", "),
+                    fp.locale
+                ),
+                vec!["intl", "locale"],
+                "Re-derive intl from the locale (auto_correct does this)",
+            ));
+        }
+    }
+
+    fn check_country_mapped(cc: &str, out: &mut Vec<GeoViolation>) {
+        if geo_locale_map(cc).is_empty() {
+            out.push(GeoViolation::info(
+                "GEO_COUNTRY_UNMAPPED",
+                format!(
+                    "Proxy country '{cc}' has no canonical locale and timezone; \
+                     auto-correct leaves them as they are"
+                ),
+                vec!["locale", "timezone", "proxy_country"],
+                "Set locale, accept_language and timezone for this country by hand",
+            ));
+        }
+    }
+
     fn check_locale_vs_country(fp: &Fingerprint, cc: &str, out: &mut Vec<GeoViolation>) {
         let allowed = allowed_locale_prefixes(cc);
         if allowed.is_empty() {
@@ -577,6 +994,23 @@ impl GeoValidator {
         }
     }
 
+    fn check_tz_vs_region(fp: &Fingerprint, cc: &str, region: &str, out: &mut Vec<GeoViolation>) {
+        let Some(expected) = region_timezone(cc, region) else {
+            return;
+        };
+        if fp.timezone != expected {
+            out.push(GeoViolation::soft(
+                "TIMEZONE_REGION_MISMATCH",
+                format!(
+                    "Timezone '{}' is not the zone of proxy region '{region}' ({expected})",
+                    fp.timezone
+                ),
+                vec!["timezone", "proxy_region"],
+                format!("Run auto_correct() or set timezone to {expected}"),
+            ));
+        }
+    }
+
     fn check_4k_vs_country(fp: &Fingerprint, cc: &str, out: &mut Vec<GeoViolation>) {
         if is_4k_or_above(fp.screen_width, fp.screen_height) && !allows_4k(cc) {
             out.push(GeoViolation::soft(
@@ -608,6 +1042,60 @@ impl GeoValidator {
     }
 }
 
+// ── Geo options ───────────────────────────────────────────────────────────────
+
+/// A locale with the Accept-Language chain and timezone it is assigned with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GeoChoice {
+    pub locale: String,
+    pub accept_language: String,
+    pub timezone: String,
+}
+
+/// What a fingerprint editor offers for a country.  All lists are empty for
+/// countries without canonical choices, which keep free-text fields.
+#[derive(Debug, Clone, Serialize)]
+pub struct GeoOptions {
+    pub country: String,
+    /// Most typical first.
+    pub locales: Vec<String>,
+    pub timezones: Vec<String>,
+    pub accept_languages: Vec<String>,
+    /// The combinations the lists are drawn from; picking a locale should
+    /// narrow the other two to its rows.
+    pub choices: Vec<GeoChoice>,
+}
+
+/// The canonical choices for ISO-3166-1 alpha-2 `country`, as `enforce_geo`
+/// assigns them.
+pub fn geo_options(country: &str) -> GeoOptions {
+    let cc = country.to_uppercase();
+    let choices: Vec<GeoChoice> = geo_locale_map(&cc)
+        .iter()
+        .map(|&(locale, accept_language, timezone)| GeoChoice {
+            locale: locale.into(),
+            accept_language: accept_language.into(),
+            timezone: timezone.into(),
+        })
+        .collect();
+    let distinct = |field: fn(&GeoChoice) -> &String| {
+        let mut values: Vec<String> = Vec::new();
+        for choice in &choices {
+            if !values.contains(field(choice)) {
+                values.push(field(choice).clone());
+            }
+        }
+        values
+    };
+    GeoOptions {
+        locales: distinct(|c| &c.locale),
+        timezones: distinct(|c| &c.timezone),
+        accept_languages: distinct(|c| &c.accept_language),
+        country: cc,
+        choices,
+    }
+}
+
 // ── AutoCorrectResult ─────────────────────────────────────────────────────────
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -691,7 +1179,7 @@ mod tests {
         let mut fp = gen(3);
         FingerprintOrchestrator::enforce_geo(&mut fp, "JP");
         // Pretend proxy is US
-        let result = GeoValidator::auto_correct(&mut fp, "US", 3);
+        let result = GeoValidator::auto_correct(&mut fp, "US", None, 3);
         // After auto-correct, US locale+tz should be set
         let residual_hard: Vec<_> = result
             .residual
@@ -705,6 +1193,152 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proxy_region_pins_the_timezone() {
+        assert_eq!(region_timezone("US", "UT"), Some("America/Denver"));
+        assert_eq!(region_timezone("us", "US-ut"), Some("America/Denver"));
+        assert_eq!(region_timezone("US", "texas"), Some("America/Chicago"));
+        assert_eq!(region_timezone("US", "El Paso, TX"), Some("America/Denver"));
+        assert_eq!(
+            region_timezone("US", "Portland, ME"),
+            Some("America/New_York")
+        );
+        assert_eq!(
+            region_timezone("US", "Portland, Oregon"),
+            Some("America/Los_Angeles")
+        );
+        assert_eq!(region_timezone("US", "Portland"), None);
+        assert_eq!(
+            region_timezone("US", "Boston, MA"),
+            Some("America/New_York")
+        );
+        assert_eq!(
+            region_timezone("US", "Bangor, ME"),
+            Some("America/New_York")
+        );
+        assert_eq!(
+            region_timezone("US", "Seattle"),
+            Some("America/Los_Angeles")
+        );
+        assert_eq!(region_timezone("CA", "QC"), Some("America/Montreal"));
+        assert_eq!(region_timezone("DE", "BY"), None);
+
+        for seed in 0..20 {
+            let mut fp = gen(seed);
+            FingerprintOrchestrator::enforce_geo_region(&mut fp, "US", Some("Utah"));
+            assert_eq!(fp.timezone, "America/Denver");
+            let mut fp = gen(seed);
+            FingerprintOrchestrator::enforce_geo_region(&mut fp, "US", Some("Hawaii"));
+            assert_eq!(fp.timezone, "Pacific/Honolulu");
+            assert!(GeoValidator::validate_with_host(
+                &fp,
+                Some("US"),
+                Some("HI"),
+                &HostFacts::default()
+            )
+            .iter()
+            .all(|v| v.severity != Severity::Hard));
+            let mut fp = gen(seed);
+            FingerprintOrchestrator::enforce_geo_region(&mut fp, "CA", Some("QC"));
+            assert_eq!(fp.locale, "fr-CA");
+        }
+
+        let mut fp = gen(3);
+        FingerprintOrchestrator::enforce_geo_region(&mut fp, "US", Some("NY"));
+        let mismatch = |fp: &Fingerprint| {
+            GeoValidator::validate_with_host(fp, Some("US"), Some("UT"), &HostFacts::default())
+                .iter()
+                .any(|v| v.code == "TIMEZONE_REGION_MISMATCH")
+        };
+        assert!(mismatch(&fp));
+        let result = GeoValidator::auto_correct(&mut fp, "US", Some("UT"), 3);
+        assert!(result
+            .fixed
+            .iter()
+            .any(|v| v.code == "TIMEZONE_REGION_MISMATCH"));
+        assert!(!mismatch(&fp));
+    }
+
+    #[test]
+    fn clock_skew_is_judged_by_the_tolerance_windows() {
+        let fp = gen(4);
+        let mut clock = HostClock {
+            server: "time.cloudflare.com".into(),
+            skew_ms: 12_000,
+            round_trip_ms: 20,
+            measured_at: chrono::Utc::now(),
+        };
+        let severity = |clock: &HostClock, tolerance: ClockTolerance| {
+            let host = HostFacts {
+                clock: Some(clock),
+                clock_tolerance: tolerance,
+                ..HostFacts::default()
+            };
+            GeoValidator::validate_with_host(&fp, None, None, &host)
+                .into_iter()
+                .find(|v| v.code == "CLOCK_SKEW")
+                .map(|v| v.severity)
+        };
+        let default = ClockTolerance::default();
+        assert_eq!(severity(&clock, default), None);
+        clock.skew_ms = -45_000;
+        assert_eq!(severity(&clock, default), Some(Severity::Soft));
+        clock.skew_ms = 600_000;
+        assert_eq!(severity(&clock, default), Some(Severity::Hard));
+        let wide = ClockTolerance {
+            soft_ms: 900_000,
+            hard_ms: 3_600_000,
+        };
+        assert_eq!(severity(&clock, wide), None);
+        clock.measured_at -= chrono::Duration::days(2);
+        assert_eq!(
+            severity(&clock, default),
+            None,
+            "stale readings are ignored"
+        );
+    }
+
+    #[test]
+    fn geo_options_pass_the_geo_rules() {
+        let us = geo_options("us");
+        assert_eq!(us.country, "US");
+        assert_eq!(us.locales, ["en-US"]);
+        assert_eq!(us.timezones.len(), 4);
+        assert_eq!(geo_options("CA").accept_languages.len(), 3);
+        assert!(geo_options("XX").choices.is_empty());
+        assert!(GeoValidator::validate(&gen(5), Some("XX"))
+            .iter()
+            .any(|v| v.code == "GEO_COUNTRY_UNMAPPED" && v.severity == Severity::Info));
+
+        let geo_fields = ["locale", "timezone", "accept_language", "intl"];
+        let letters = b'A'..=b'Z';
+        let mapped: Vec<String> = letters
+            .clone()
+            .flat_map(|a| {
+                letters
+                    .clone()
+                    .map(move |b| String::from_utf8(vec![a, b]).unwrap())
+            })
+            .filter(|cc| !geo_locale_map(cc).is_empty())
+            .collect();
+        assert!(mapped.len() >= 60, "{} countries mapped", mapped.len());
+        for cc in &mapped {
+            let cc = cc.as_str();
+            for choice in geo_options(cc).choices {
+                let mut fp = gen(5);
+                fp.locale = choice.locale.clone();
+                fp.intl = Some(IntlProfile::for_locale(&choice.locale));
+                fp.accept_language = choice.accept_language.clone();
+                fp.timezone = choice.timezone.clone();
+                let violations: Vec<_> = GeoValidator::validate(&fp, Some(cc))
+                    .into_iter()
+                    .filter(|v| v.fields.iter().any(|f| geo_fields.contains(&f.as_str())))
+                    .collect();
+                assert!(violations.is_empty(), "{cc} {choice:?}: {violations:?}");
+            }
+        }
+    }
+
     #[test]
     fn consistency_score_is_1_for_clean_profile() {
         let mut fp = gen(10);
@@ -747,6 +1381,203 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fonts_must_match_the_claimed_os_release() {
+        for seed in 0..30 {
+            let fp = gen(seed);
+            let v = GeoValidator::validate(&fp, None);
+            assert!(
+                !v.iter().any(|x| x.code.starts_with("FONT_")),
+                "generated font list flagged for seed={seed}: {v:?}"
+            );
+        }
+
+        let mut fp = gen(4);
+        fp.ua_platform = "Windows".into();
+        fp.platform = "Win32".into();
+        fp.ua_platform_version = "0.0.19045".into();
+        fp.font_subset = OsRelease::Windows11
+            .catalogue()
+            .iter()
+            .chain(["Menlo"].iter())
+            .map(|f| f.to_string())
+            .collect();
+        let codes: Vec<_> = GeoValidator::validate(&fp, None)
+            .into_iter()
+            .map(|x| x.code)
+            .collect();
+        assert!(codes.contains(&"FONT_OS_MISMATCH".to_string()), "{codes:?}");
+        assert!(
+            codes.contains(&"FONT_RELEASE_MISMATCH".to_string()),
+            "{codes:?}"
+        );
+
+        fp.font_subset = vec!["Arial".into(), "Segoe UI".into()];
+        assert!(GeoValidator::validate(&fp, None)
+            .iter()
+            .any(|x| x.code == "FONT_COUNT_LOW"));
+    }
+
+    #[test]
+    fn color_caps_follow_screen_and_os() {
+        for seed in 0..60 {
+            let fp = gen(seed);
+            let v = GeoValidator::validate(&fp, None);
+            assert!(
+                !v.iter()
+                    .any(|x| x.fields.iter().any(|f| f == "color_gamut")),
+                "generated gamut flagged for seed={seed}: {v:?}"
+            );
+        }
+
+        let mut fp = gen(5);
+        fp.ua_platform = "Windows".into();
+        fp.platform = "Win32".into();
+        fp.screen_width = 1366;
+        fp.screen_height = 768;
+        fp.pixel_ratio = 1.0;
+        fp.color_gamut = ColorGamut::Srgb;
+        fp.hdr = true;
+        let codes: Vec<_> = GeoValidator::validate(&fp, None)
+            .into_iter()
+            .map(|x| x.code)
+            .collect();
+        assert!(codes.contains(&"HDR_WITHOUT_WIDE_GAMUT".to_string()));
+        fp.color_gamut = ColorGamut::P3;
+        assert!(GeoValidator::validate(&fp, None)
+            .iter()
+            .any(|x| x.code == "P3_ON_BASIC_PANEL"));
+    }
+
+    #[test]
+    fn engine_expectations_need_one_major_and_a_bcp47_locale() {
+        for seed in 0..60 {
+            let v = GeoValidator::validate(&gen(seed), None);
+            assert!(
+                !v.iter()
+                    .any(|x| x.code == "CHROME_MAJOR_MISMATCH" || x.code == "LOCALE_NOT_BCP47"),
+                "generated engine values flagged for seed={seed}: {v:?}"
+            );
+        }
+
+        let mut fp = gen(3);
+        FingerprintOrchestrator::set_chrome_major(&mut fp, 100);
+        fp.locale = "en_US".into();
+        let mut codes: Vec<_> = GeoValidator::validate(&fp, None)
+            .into_iter()
+            .map(|x| x.code)
+            .collect();
+        codes.retain(|c| c == "ENGINE_MAJOR_UNMODELLED" || c == "LOCALE_NOT_BCP47");
+        assert_eq!(codes, ["LOCALE_NOT_BCP47", "ENGINE_MAJOR_UNMODELLED"]);
+
+        fp.ua_brands
+            .iter_mut()
+            .for_each(|b| b.version = "131".into());
+        assert!(GeoValidator::validate(&fp, None)
+            .iter()
+            .any(|x| x.code == "CHROME_MAJOR_MISMATCH"));
+    }
+
+    #[test]
+    fn intl_data_must_belong_to_the_locale() {
+        let mut fp = gen(4);
+        fp.locale = "de-DE".into();
+        fp.accept_language = "de-DE,de;q=0.9".into();
+        fp.timezone = "Europe/Berlin".into();
+        fp.intl = Some(IntlProfile::for_locale("en-US"));
+        let v = GeoValidator::validate(&fp, None);
+        let x = v.iter().find(|x| x.code == "INTL_LOCALE_MISMATCH").unwrap();
+        assert!(x.description.contains("hour cycle, week info, currency"));
+
+        let result = GeoValidator::auto_correct(&mut fp, "DE", None, 4);
+        assert!(result
+            .fixed
+            .iter()
+            .any(|x| x.code == "INTL_LOCALE_MISMATCH"));
+        assert_eq!(fp.intl.as_ref().unwrap().currency, "EUR");
+
+        // Profiles without Intl data are not flagged
+        fp.intl = None;
+        fp.locale = "ja-JP".into();
+        assert!(!GeoValidator::validate(&fp, None)
+            .iter()
+            .any(|x| x.code == "INTL_LOCALE_MISMATCH"));
+    }
+
+    #[test]
+    fn screen_layout_must_fit_the_screen() {
+        use crate::fingerprint::ScreenLayout;
+
+        let mut fp = gen(8);
+        let codes = |fp: &Fingerprint| -> Vec<String> {
+            GeoValidator::validate(fp, None)
+                .into_iter()
+                .map(|v| v.code)
+                .filter(|c| c.starts_with("SCREEN_") || c.starts_with("WINDOW_"))
+                .collect()
+        };
+        let width = fp.screen_width as i32;
+        fp.screen_layout = Some(ScreenLayout {
+            monitors: 2,
+            avail_left: width,
+            avail_top: 0,
+            window_x: (width, width + 300),
+            window_y: (0, 80),
+        });
+        assert!(codes(&fp).is_empty(), "{:?}", codes(&fp));
+
+        // One monitor can't be offset, and these windows open on the primary
+        let layout = fp.screen_layout.as_mut().unwrap();
+        layout.monitors = 1;
+        layout.window_x = (0, 300);
+        assert_eq!(
+            codes(&fp),
+            ["SCREEN_OFFSET_SINGLE_MONITOR", "WINDOW_RANGE_OFF_SCREEN"]
+        );
+    }
+
+    #[test]
+    fn claimed_gpu_must_fit_the_host() {
+        let mut fp = gen(5);
+        fp.webgl_renderer =
+            "ANGLE (NVIDIA, NVIDIA GeForce RTX 4070 Direct3D11 vs_5_0 ps_5_0, D3D11)".into();
+        let codes = |host: &HostGpu| -> Vec<String> {
+            let host = HostFacts {
+                gpu: Some(host),
+                ..HostFacts::default()
+            };
+            GeoValidator::validate_with_host(&fp, None, None, &host)
+                .into_iter()
+                .filter(|v| v.code.starts_with("GPU_"))
+                .map(|v| v.code)
+                .collect()
+        };
+
+        let software = crate::gpu_probe::parse_glxinfo(
+            "OpenGL renderer string: llvmpipe (LLVM 15.0.7)\n    GL_MAX_TEXTURE_SIZE = 8192\n",
+        );
+        assert_eq!(
+            codes(&software),
+            [
+                "GPU_TEXTURE_SIZE_MISMATCH",
+                "GPU_ABOVE_HOST_TIER",
+                "GPU_EXTENSIONS_MISSING"
+            ]
+        );
+
+        let nvidia = crate::gpu_probe::parse_glxinfo(
+            "OpenGL renderer string: NVIDIA GeForce RTX 3080/PCIe/SSE2\n\
+             GL_MAX_TEXTURE_SIZE = 16384\n\
+             GL_EXT_texture_filter_anisotropic GL_EXT_texture_compression_s3tc \
+             GL_ARB_texture_compression_rgtc GL_ARB_texture_compression_bptc\n",
+        );
+        assert!(codes(&nvidia).is_empty());
+        // Without a host GPU the rule doesn't run
+        assert!(!GeoValidator::validate(&fp, None)
+            .iter()
+            .any(|v| v.code.starts_with("GPU_")));
+    }
+
     #[test]
     fn score_decreases_with_violations() {
         let mut fp = gen(9);
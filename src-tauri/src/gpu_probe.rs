@@ -0,0 +1,318 @@
+// ── Manifold host GPU probe ───────────────────────────────────────────────────
+//
+// The WebGL evasion swaps the vendor and renderer strings and trims the
+// extension list, but every other `getParameter` value comes from the host's
+// GPU through ANGLE.  A profile claiming an RTX 4070 on a host whose GL tops
+// out at 8192-pixel textures reports `MAX_TEXTURE_SIZE` 8192 next to that
+// renderer string, a combination no real RTX 4070 produces.
+//
+// The probe records what the host's GPU is and can do:
+//
+//   Linux    `glxinfo -l`: renderer, GL_MAX_TEXTURE_SIZE, extensions
+//   macOS    `system_profiler SPDisplaysDataType`: chipset model
+//   Windows  Win32_VideoController: adapter name
+//
+// Where only a name is available the capabilities are those of its tier.
+// The result is kept in `settings` (`host_gpu`) and feeds the host GPU rule
+// of the geo validator (`GeoValidator::validate_with_host`).
+
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// `settings` key holding the recorded `HostGpu` (JSON).
+pub const HOST_GPU_KEY: &str = "host_gpu";
+
+/// GL extensions and the WebGL extensions ANGLE builds on them.
+const WEBGL_EXTENSIONS: &[(&str, &str)] = &[
+    (
+        "GL_EXT_texture_filter_anisotropic",
+        "EXT_texture_filter_anisotropic",
+    ),
+    (
+        "GL_ARB_texture_filter_anisotropic",
+        "EXT_texture_filter_anisotropic",
+    ),
+    (
+        "GL_EXT_texture_compression_s3tc",
+        "WEBGL_compressed_texture_s3tc",
+    ),
+    (
+        "GL_ARB_texture_compression_rgtc",
+        "EXT_texture_compression_rgtc",
+    ),
+    (
+        "GL_EXT_texture_compression_rgtc",
+        "EXT_texture_compression_rgtc",
+    ),
+    (
+        "GL_ARB_texture_compression_bptc",
+        "EXT_texture_compression_bptc",
+    ),
+    (
+        "GL_EXT_texture_compression_bptc",
+        "EXT_texture_compression_bptc",
+    ),
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// Rough capability class of a GPU, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuTier {
+    /// SwiftShader, llvmpipe and other CPU renderers.
+    Software,
+    Integrated,
+    Discrete,
+}
+
+/// What Chrome's WebGL reports on a GPU of some tier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GpuCapabilities {
+    pub max_texture_size: u32,
+    /// WebGL extension names.
+    pub extensions: Vec<String>,
+}
+
+/// The host's GPU as last probed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostGpu {
+    pub vendor: Option<String>,
+    pub renderer: Option<String>,
+    pub tier: Option<GpuTier>,
+    /// GL_MAX_TEXTURE_SIZE, when the host's GL could be queried.
+    pub max_texture_size: Option<u32>,
+    /// The host's GL extensions by their WebGL names; empty when unknown.
+    pub webgl_extensions: Vec<String>,
+    /// `glxinfo`, `system_profiler`, `Win32_VideoController` or `none`.
+    pub source: String,
+    pub probed_at: DateTime<Utc>,
+}
+
+impl HostGpu {
+    /// The host's capabilities: measured where the probe could, else its
+    /// tier's.
+    pub fn capabilities(&self) -> Option<GpuCapabilities> {
+        let expected = self
+            .tier
+            .map(|tier| tier_capabilities(tier, self.renderer.as_deref()));
+        match (self.max_texture_size, expected) {
+            (Some(max_texture_size), _) => Some(GpuCapabilities {
+                max_texture_size,
+                extensions: self.webgl_extensions.clone(),
+            }),
+            (None, expected) => expected,
+        }
+    }
+}
+
+// ── Classification ────────────────────────────────────────────────────────────
+
+/// The tier a renderer string (a WebGL renderer or an adapter name) names.
+pub fn tier_of(renderer: &str) -> Option<GpuTier> {
+    let r = renderer.to_ascii_lowercase();
+    let any = |needles: &[&str]| needles.iter().any(|n| r.contains(n));
+    if any(&[
+        "swiftshader",
+        "llvmpipe",
+        "softpipe",
+        "software",
+        "basic render",
+    ]) {
+        Some(GpuTier::Software)
+    } else if any(&[
+        "geforce",
+        "quadro",
+        "radeon rx",
+        "radeon pro",
+        "arc(tm)",
+        "intel arc",
+    ]) {
+        Some(GpuTier::Discrete)
+    } else if any(&["intel", "radeon", "vega", "apple", "mali", "adreno"]) {
+        Some(GpuTier::Integrated)
+    } else {
+        None
+    }
+}
+
+/// What Chrome's WebGL shows on a `tier` GPU.  ANGLE's D3D11 and Metal
+/// backends cap textures at 16384; SwiftShader at 8192.
+pub fn tier_capabilities(tier: GpuTier, renderer: Option<&str>) -> GpuCapabilities {
+    let apple = renderer.is_some_and(|r| r.to_ascii_lowercase().contains("apple"));
+    let extensions: &[&str] = match tier {
+        GpuTier::Software => &[],
+        _ if apple => &[
+            "EXT_texture_filter_anisotropic",
+            "WEBGL_compressed_texture_s3tc",
+        ],
+        _ => &[
+            "EXT_texture_filter_anisotropic",
+            "WEBGL_compressed_texture_s3tc",
+            "EXT_texture_compression_rgtc",
+            "EXT_texture_compression_bptc",
+        ],
+    };
+    GpuCapabilities {
+        max_texture_size: if tier == GpuTier::Software {
+            8192
+        } else {
+            16384
+        },
+        extensions: extensions.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+/// The WebGL extensions a list of GL extensions provides, sorted.
+pub fn webgl_extensions<'a>(gl: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut out: Vec<String> = gl
+        .into_iter()
+        .filter_map(|ext| WEBGL_EXTENSIONS.iter().find(|(g, _)| *g == ext))
+        .map(|(_, webgl)| webgl.to_string())
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+// ── Probe ─────────────────────────────────────────────────────────────────────
+
+/// Read `glxinfo -l` output.
+pub fn parse_glxinfo(text: &str) -> HostGpu {
+    let field = |label: &str| {
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(label))
+            .map(|v| v.trim().to_string())
+    };
+    let renderer = field("OpenGL renderer string:");
+    let max_texture_size = text.lines().find_map(|l| {
+        let (name, value) = l.split_once('=')?;
+        (name.trim() == "GL_MAX_TEXTURE_SIZE")
+            .then(|| value.trim().parse().ok())
+            .flatten()
+    });
+    let tokens = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| t.starts_with("GL_"));
+    HostGpu {
+        vendor: field("OpenGL vendor string:"),
+        tier: renderer.as_deref().and_then(tier_of),
+        renderer,
+        max_texture_size,
+        webgl_extensions: webgl_extensions(tokens),
+        source: "glxinfo".into(),
+        probed_at: Utc::now(),
+    }
+}
+
+/// A host GPU known only by its adapter name.
+fn named(name: Option<String>, source: &str) -> HostGpu {
+    HostGpu {
+        vendor: None,
+        tier: name.as_deref().and_then(tier_of),
+        renderer: name,
+        max_texture_size: None,
+        webgl_extensions: Vec::new(),
+        source: source.into(),
+        probed_at: Utc::now(),
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Probe the host's GPU.  Blocks while the OS tool runs.
+pub fn probe() -> HostGpu {
+    #[cfg(target_os = "linux")]
+    {
+        match command_output("glxinfo", &["-l"]) {
+            Some(text) => parse_glxinfo(&text),
+            None => named(None, "none"),
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let chipset = command_output("system_profiler", &["SPDisplaysDataType"]).and_then(|t| {
+            t.lines()
+                .find_map(|l| l.trim().strip_prefix("Chipset Model:"))
+                .map(|v| v.trim().to_string())
+        });
+        named(chipset, "system_profiler")
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let adapter = command_output(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "(Get-CimInstance Win32_VideoController | Select-Object -First 1).Name",
+            ],
+        )
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+        named(adapter, "Win32_VideoController")
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        named(None, "none")
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_glxinfo_and_classifies_renderers() {
+        let text = "\
+name of display: :0
+OpenGL vendor string: Mesa
+OpenGL renderer string: llvmpipe (LLVM 15.0.7, 256 bits)
+OpenGL core profile limits:
+    GL_MAX_TEXTURE_SIZE = 8192
+    GL_MAX_3D_TEXTURE_SIZE = 2048
+OpenGL extensions:
+    GL_ARB_texture_compression_rgtc, GL_EXT_texture_filter_anisotropic,
+    GL_ARB_texture_filter_anisotropic, GL_KHR_debug
+";
+        let gpu = parse_glxinfo(text);
+        assert_eq!(gpu.vendor.as_deref(), Some("Mesa"));
+        assert_eq!(gpu.tier, Some(GpuTier::Software));
+        assert_eq!(gpu.max_texture_size, Some(8192));
+        assert_eq!(
+            gpu.webgl_extensions,
+            [
+                "EXT_texture_compression_rgtc",
+                "EXT_texture_filter_anisotropic"
+            ]
+        );
+        assert_eq!(gpu.capabilities().unwrap().max_texture_size, 8192);
+
+        assert_eq!(
+            tier_of("ANGLE (NVIDIA, NVIDIA GeForce RTX 4070 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+            Some(GpuTier::Discrete)
+        );
+        assert_eq!(
+            tier_of("ANGLE (Intel, Intel(R) UHD Graphics 630 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+            Some(GpuTier::Integrated)
+        );
+        assert_eq!(tier_of("Google SwiftShader"), Some(GpuTier::Software));
+        assert_eq!(tier_of("Matrox G200eW"), None);
+
+        // Known only by name: the tier's capabilities stand in
+        let named = named(Some("Apple M2".into()), "system_profiler");
+        let caps = named.capabilities().unwrap();
+        assert_eq!(caps.max_texture_size, 16384);
+        assert_eq!(caps.extensions.len(), 2);
+    }
+}
@@ -0,0 +1,256 @@
+// ── Manifold group workflow runs ──────────────────────────────────────────────
+//
+// One workflow fanned out over a profile group — the profiles carrying a
+// tag, the way profiles are grouped in the app.  The backend drives one
+// bridge at a time, so the group's profiles are queued and each gets its
+// own run in turn; a profile that can't start (conflict, missing variable,
+// no bridge) or whose run fails doesn't hold up the rest unless
+// `stop_on_failure` is set.
+//
+// The group report is stored.  Passing its id as `resume_from` re-runs only
+// the profiles that didn't succeed there, and the new report carries the
+// earlier successes over, so it always describes the whole group.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::profile::Profile;
+use crate::workflow::WorkflowRunStatus;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GroupRunOptions {
+    /// Run parameters for every profile; the vault fills in the rest.
+    pub params: BTreeMap<String, String>,
+    /// Leave the remaining profiles unrun after the first failure.
+    pub stop_on_failure: bool,
+    /// A previous group run of the same workflow and group: only the
+    /// profiles that didn't succeed there are run.
+    pub resume_from: Option<String>,
+}
+
+/// How one profile of the group fared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRunResult {
+    pub profile_id: String,
+    pub profile_name: String,
+    /// `None` when the run never started.
+    pub run_id: Option<String>,
+    pub status: Option<WorkflowRunStatus>,
+    /// Why the run didn't start, or the failing step's error.
+    pub error: Option<String>,
+}
+
+impl ProfileRunResult {
+    pub fn succeeded(&self) -> bool {
+        self.status == Some(WorkflowRunStatus::Succeeded)
+    }
+
+    /// A profile left unrun because an earlier one failed.
+    pub fn not_run(profile: &Profile) -> Self {
+        Self {
+            profile_id: profile.id.clone(),
+            profile_name: profile.name.clone(),
+            run_id: None,
+            status: None,
+            error: Some("not run: an earlier profile failed".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRunReport {
+    pub id: String,
+    pub workflow_id: String,
+    pub group_id: String,
+    pub resumed_from: Option<String>,
+    /// One entry per profile of the group: successes carried over from
+    /// `resumed_from` first, then the profiles in run order.
+    pub results: Vec<ProfileRunResult>,
+    pub succeeded: u32,
+    pub failed: u32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl GroupRunReport {
+    pub fn new(
+        id: String,
+        workflow_id: &str,
+        group_id: &str,
+        resumed_from: Option<String>,
+        results: Vec<ProfileRunResult>,
+        started_at: DateTime<Utc>,
+    ) -> Self {
+        let succeeded = results.iter().filter(|r| r.succeeded()).count() as u32;
+        Self {
+            id,
+            workflow_id: workflow_id.into(),
+            group_id: group_id.into(),
+            resumed_from,
+            failed: results.len() as u32 - succeeded,
+            succeeded,
+            results,
+            started_at,
+            finished_at: Utc::now(),
+        }
+    }
+}
+
+// ── Planning ──────────────────────────────────────────────────────────────────
+
+/// Split the group into the profiles to run now and the results carried
+/// over from `previous`: its successes, for profiles still in the group.
+pub fn plan(
+    group: Vec<Profile>,
+    previous: Option<&GroupRunReport>,
+) -> (Vec<Profile>, Vec<ProfileRunResult>) {
+    let Some(previous) = previous else {
+        return (group, Vec::new());
+    };
+    let mut carried = Vec::new();
+    let mut queue = Vec::new();
+    for profile in group {
+        match previous
+            .results
+            .iter()
+            .find(|r| r.profile_id == profile.id && r.succeeded())
+        {
+            Some(done) => carried.push(done.clone()),
+            None => queue.push(profile),
+        }
+    }
+    (queue, carried)
+}
+
+/// Check that `previous` is a run of the same workflow on the same group.
+pub fn check_resumable(previous: &GroupRunReport, workflow_id: &str, group_id: &str) -> Result<()> {
+    if previous.workflow_id != workflow_id || previous.group_id != group_id {
+        return Err(ManifoldError::InvalidArg(format!(
+            "group run {:?} was for another workflow or group",
+            previous.id
+        )));
+    }
+    Ok(())
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct GroupRunRepo {
+    db: Db,
+}
+
+impl GroupRunRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn save(&self, report: &GroupRunReport) -> Result<()> {
+        let json = serde_json::to_string(report)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO workflow_group_runs (id, workflow_id, group_id, report, started_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5)"#,
+                params![
+                    report.id,
+                    report.workflow_id,
+                    report.group_id,
+                    json,
+                    report.started_at.to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Result<GroupRunReport> {
+        let json: Option<String> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT report FROM workflow_group_runs WHERE id = ?1",
+                    params![id],
+                    |r| r.get(0),
+                )
+                .optional()?)
+        })?;
+        let json = json.ok_or_else(|| ManifoldError::InvalidArg(format!("no group run {id:?}")))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+
+    fn result(profile: &Profile, status: Option<WorkflowRunStatus>) -> ProfileRunResult {
+        ProfileRunResult {
+            profile_id: profile.id.clone(),
+            profile_name: profile.name.clone(),
+            run_id: status.map(|_| format!("run-{}", profile.name)),
+            status,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn resume_reruns_only_the_profiles_that_did_not_succeed() {
+        let db = Db::open_in_memory().unwrap();
+        let profiles = ProfileRepo::new(db.clone());
+        let group: Vec<Profile> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| {
+                profiles
+                    .create(CreateProfileRequest {
+                        name: name.into(),
+                        seed: Some(3),
+                        proxy_id: None,
+                        notes: None,
+                        tags: Some(vec!["shop".into()]),
+                        behavior_profile: None,
+                        persona: None,
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        let (queue, carried) = plan(group.clone(), None);
+        assert_eq!(queue.len(), 3);
+        assert!(carried.is_empty());
+
+        let first = GroupRunReport::new(
+            "g1".into(),
+            "wf",
+            "shop",
+            None,
+            vec![
+                result(&group[0], Some(WorkflowRunStatus::Succeeded)),
+                result(&group[1], Some(WorkflowRunStatus::Aborted)),
+                ProfileRunResult::not_run(&group[2]),
+            ],
+            Utc::now(),
+        );
+        assert_eq!((first.succeeded, first.failed), (1, 2));
+        let repo = GroupRunRepo::new(db);
+        repo.save(&first).unwrap();
+        let stored = repo.get("g1").unwrap();
+        assert_eq!(stored.results, first.results);
+
+        check_resumable(&stored, "wf", "shop").unwrap();
+        assert!(check_resumable(&stored, "other", "shop").is_err());
+        let (queue, carried) = plan(group.clone(), Some(&stored));
+        let names: Vec<_> = queue.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["b", "c"]);
+        assert_eq!(carried, [first.results[0].clone()]);
+        assert!(repo.get("missing").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+// ── Manifold HAR import ───────────────────────────────────────────────────────
+//
+// HAR files captured by other tooling (DevTools, mitmproxy, Charles, other
+// automation stacks) are brought into the session store so they can be
+// diffed and header-checked against a Manifold profile the same way an
+// exported session is.
+//
+// A HAR is validated as a whole — a file with one malformed entry is
+// rejected rather than imported with holes — and then rewritten in the shape
+// the bridge exports: entries sorted by start time, timestamps in UTC,
+// headers as name/value pairs.  Response bodies, cookies, POST data and
+// tool-specific `_` fields are dropped; Manifold's bundles never hold them.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::{ManifoldError, Result};
+use crate::header_order::HeaderOrderReport;
+
+/// Largest HAR accepted, before bodies are dropped.
+pub const MAX_HAR_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The result of `import_session_har`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedSession {
+    /// The new bundle's name, as `list_sessions` reports it.
+    pub session: String,
+    pub path: String,
+    pub entries: usize,
+    /// The HAR's `log.creator.name`, e.g. "WebInspector".
+    pub creator: Option<String>,
+    pub first_request_at: Option<DateTime<Utc>>,
+    pub header_order: HeaderOrderReport,
+}
+
+/// An external HAR's creator and its log, rewritten in Manifold's shape.
+#[derive(Debug, Clone)]
+pub struct NormalizedHar {
+    pub creator: Option<String>,
+    pub har: Value,
+    pub entries: usize,
+    pub first_request_at: Option<DateTime<Utc>>,
+}
+
+// ── Validation ────────────────────────────────────────────────────────────────
+
+/// Validate an external HAR and rewrite it in Manifold's export shape.
+pub fn normalize(raw: &str) -> Result<NormalizedHar> {
+    let har: Value = serde_json::from_str(raw)
+        .map_err(|e| ManifoldError::InvalidArg(format!("not a HAR file: {e}")))?;
+    let entries = har["log"]["entries"]
+        .as_array()
+        .ok_or_else(|| ManifoldError::InvalidArg("not a HAR file: no log.entries".into()))?;
+
+    let mut timed = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            normalize_entry(entry)
+                .map_err(|why| ManifoldError::InvalidArg(format!("HAR entry {i}: {why}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    // Stable: entries started in the same millisecond keep their order
+    timed.sort_by_key(|(at, _)| *at);
+
+    let first_request_at = timed.first().map(|(at, _)| *at);
+    let entries: Vec<Value> = timed.into_iter().map(|(_, entry)| entry).collect();
+    Ok(NormalizedHar {
+        creator: har["log"]["creator"]["name"].as_str().map(str::to_string),
+        entries: entries.len(),
+        first_request_at,
+        har: json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "Manifold", "version": env!("CARGO_PKG_VERSION") },
+                "entries": entries,
+            }
+        }),
+    })
+}
+
+fn normalize_entry(entry: &Value) -> std::result::Result<(DateTime<Utc>, Value), String> {
+    let started = entry["startedDateTime"]
+        .as_str()
+        .ok_or("missing startedDateTime")?;
+    let at = DateTime::parse_from_rfc3339(started)
+        .map_err(|e| format!("bad startedDateTime {started:?}: {e}"))?
+        .with_timezone(&Utc);
+
+    let request = &entry["request"];
+    let method = request["method"].as_str().ok_or("missing request.method")?;
+    let url = request["url"].as_str().ok_or("missing request.url")?;
+    url::Url::parse(url).map_err(|e| format!("bad request.url {url:?}: {e}"))?;
+
+    let response = &entry["response"];
+    // Blocked and aborted requests are recorded with status 0
+    let status = response["status"]
+        .as_u64()
+        .filter(|s| *s < 1000)
+        .ok_or("missing response.status")?;
+    let time = entry["time"].as_f64().filter(|t| *t >= 0.0).unwrap_or(0.0);
+    let body_size = response["content"]["size"]
+        .as_i64()
+        .or_else(|| response["bodySize"].as_i64())
+        .unwrap_or(-1);
+
+    Ok((
+        at,
+        json!({
+            "startedDateTime": at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "time": time,
+            "request": {
+                "method": method,
+                "url": url,
+                "headers": headers(&request["headers"])?,
+                "headersSize": -1,
+                "bodySize": -1,
+            },
+            "response": {
+                "status": status,
+                "statusText": response["statusText"].as_str().unwrap_or_default(),
+                "headers": headers(&response["headers"])?,
+                "content": {
+                    "size": body_size,
+                    "mimeType": response["content"]["mimeType"].as_str().unwrap_or_default(),
+                },
+                "headersSize": -1,
+                "bodySize": body_size,
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": time, "receive": 0 },
+        }),
+    ))
+}
+
+/// A HAR header list as name/value pairs, in the order it was captured.
+fn headers(list: &Value) -> std::result::Result<Vec<Value>, String> {
+    let Some(list) = list.as_array() else {
+        return Ok(Vec::new());
+    };
+    list.iter()
+        .map(|h| -> std::result::Result<Value, String> {
+            let name = h["name"].as_str().ok_or("header without a name")?;
+            let value = h["value"].as_str().unwrap_or_default();
+            Ok(json!({ "name": name, "value": value }))
+        })
+        .collect()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(started: &str, url: &str) -> Value {
+        json!({
+            "startedDateTime": started,
+            "time": 42.5,
+            "request": {
+                "method": "GET",
+                "url": url,
+                "headers": [{ "name": "User-Agent", "value": "x" }, { "name": "Accept", "value": "*/*" }],
+                "cookies": [{ "name": "sid", "value": "secret" }],
+            },
+            "response": {
+                "status": 200,
+                "statusText": "OK",
+                "headers": [],
+                "content": { "size": 10, "mimeType": "text/html", "text": "<html></html>" },
+            },
+            "_initiator": { "type": "parser" },
+        })
+    }
+
+    #[test]
+    fn external_har_is_sorted_and_rewritten_in_manifold_shape() {
+        let raw = json!({ "log": {
+            "creator": { "name": "WebInspector", "version": "537.36" },
+            "entries": [
+                entry("2026-01-01T02:00:01.000+02:00", "https://a.test/late"),
+                entry("2026-01-01T00:00:00.500Z", "https://a.test/early"),
+            ],
+        }})
+        .to_string();
+        let har = normalize(&raw).unwrap();
+        assert_eq!(har.creator.as_deref(), Some("WebInspector"));
+        assert_eq!(har.entries, 2);
+        assert_eq!(
+            har.first_request_at.unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00.500+00:00"
+        );
+
+        let entries = har.har["log"]["entries"].as_array().unwrap();
+        assert_eq!(entries[0]["request"]["url"], "https://a.test/early");
+        assert_eq!(entries[1]["startedDateTime"], "2026-01-01T00:00:01.000Z");
+        assert_eq!(entries[0]["request"]["headers"][0]["name"], "User-Agent");
+        assert!(entries[0]["request"]["cookies"].is_null());
+        assert!(entries[0]["response"]["content"]["text"].is_null());
+        assert!(entries[0]["_initiator"].is_null());
+    }
+
+    #[test]
+    fn malformed_hars_are_rejected_whole() {
+        assert!(normalize("{}").is_err());
+        assert!(normalize("not json").is_err());
+
+        let mut bad = entry("2026-01-01T00:00:00Z", "https://a.test/");
+        bad["request"]["url"] = json!("not a url");
+        let raw = json!({ "log": { "entries": [
+            entry("2026-01-01T00:00:00Z", "https://a.test/"),
+            bad,
+        ] } })
+        .to_string();
+        let err = normalize(&raw).unwrap_err().to_string();
+        assert!(err.contains("HAR entry 1"), "{err}");
+
+        let empty = json!({ "log": { "entries": [] } }).to_string();
+        assert_eq!(normalize(&empty).unwrap().entries, 0);
+    }
+}
@@ -0,0 +1,277 @@
+// ── Manifold fingerprint hash preview ─────────────────────────────────────────
+//
+// Re-implements the position-keyed noise of the canvas, WebGL and audio
+// evasions (evasions/canvas.ts, webgl.ts, audio.ts) bit-for-bit so the editor
+// can show what a checker site will see without launching a browser.
+//
+// A checker hashes "device render + our noise".  The device render is the
+// same for every profile on a machine, so each hash here is computed over a
+// fixed probe (flat mid-grey canvas / framebuffer, fixed sine buffer) with
+// the profile's noise applied.  Two profiles collide on a checker exactly
+// when their preview hashes collide, and a hash changes exactly when the
+// site-visible one would.
+
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+
+use crate::fingerprint::Fingerprint;
+
+/// Probe canvas size — the 2D text canvas most checkers draw is 240×60.
+const CANVAS_PROBE: (u32, u32) = (240, 60);
+/// Probe framebuffer for WebGL readPixels.
+const WEBGL_PROBE: (u32, u32) = (256, 128);
+/// Samples read by the classic OfflineAudioContext fingerprint.
+const AUDIO_PROBE_LEN: u32 = 5000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FingerprintHashes {
+    pub seed: u64,
+    /// SHA3-256 hex of the noised canvas probe; `None` when noise is off.
+    pub canvas_hash: Option<String>,
+    pub canvas_max_delta: u32,
+    pub webgl_hash: Option<String>,
+    pub webgl_max_delta: u32,
+    pub audio_hash: Option<String>,
+    pub audio_max_amplitude: f64,
+}
+
+// ── Shared primitives (mirror the injected JS) ────────────────────────────────
+
+/// `${seed} >>> 0` — the seed reaches JS as an f64, so large seeds lose
+/// precision before being truncated to 32 bits.
+fn js_seed32(seed: u64) -> u32 {
+    (seed as f64) as u128 as u32
+}
+
+/// Wang hash over (a·k1 ⊕ b·k2 ⊕ c·k3 ⊕ seed), identical to `_delta`.
+fn wang(a: u32, b: u32, c: u32, keys: (u32, u32, u32), seed32: u32) -> u32 {
+    let mut h = a.wrapping_mul(keys.0) ^ b.wrapping_mul(keys.1) ^ c.wrapping_mul(keys.2) ^ seed32;
+    h = (h ^ (h >> 16)).wrapping_mul(0x45d9f3b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^ (h >> 16)
+}
+
+const PIXEL_KEYS: (u32, u32, u32) = (374761393, 668265263, 2147483647);
+
+fn pixel_delta(x: u32, y: u32, ch: u32, seed32: u32, max_delta: u32) -> i32 {
+    let range = max_delta * 2 + 1;
+    (wang(x, y, ch, PIXEL_KEYS, seed32) % range) as i32 - max_delta as i32
+}
+
+/// One colour channel of the flat mid-grey probe after noising.
+fn noised_channel(x: u32, y: u32, ch: u32, seed32: u32, max_delta: u32) -> u8 {
+    (128 + pixel_delta(x, y, ch, seed32, max_delta)).clamp(0, 255) as u8
+}
+
+/// Noise a flat mid-grey RGBA probe and hash it (alpha is never touched).
+fn noised_probe_hash(size: (u32, u32), seed32: u32, max_delta: u32) -> String {
+    let mut sha = Sha3_256::new();
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            let mut px = [128u8, 128, 128, 255];
+            for (ch, v) in px.iter_mut().take(3).enumerate() {
+                *v = noised_channel(x, y, ch as u32, seed32, max_delta);
+            }
+            sha.update(px);
+        }
+    }
+    hex::encode(sha.finalize())
+}
+
+// ── Per-surface previews ──────────────────────────────────────────────────────
+
+/// `Math.max(0, Math.min(4, Math.round(noise * 4)))` from canvas.ts.
+pub fn canvas_max_delta(noise: f64) -> u32 {
+    (noise * 4.0).round().clamp(0.0, 4.0) as u32
+}
+
+/// `Math.max(0, Math.min(3, Math.round(noise * 3)))` from webgl.ts.
+pub fn webgl_max_delta(noise: f64) -> u32 {
+    (noise * 3.0).round().clamp(0.0, 3.0) as u32
+}
+
+/// `Math.max(0, Math.min(1.2e-4, noise * 1.2e-4))` from audio.ts.
+pub fn audio_max_amplitude(noise: f64) -> f64 {
+    (noise * 1.2e-4).clamp(0.0, 1.2e-4)
+}
+
+/// Sample `i` of the audio probe after noising.
+fn noised_sample(i: u32, seed32: u32, max_amp: f64) -> f32 {
+    // First AudioBuffer of the page gets id 1; channel 0.
+    let buf_id = 1u32;
+    let base = (i as f64 * 0.05).sin() as f32;
+    let h = wang(i, 0, buf_id, PIXEL_KEYS, seed32);
+    let delta = ((h as f64 / 4_294_967_296.0) - 0.5) * 2.0 * max_amp;
+    // Float32Array store rounds to f32
+    ((base as f64 + delta).clamp(-1.0, 1.0)) as f32
+}
+
+fn audio_hash(seed32: u32, max_amp: f64) -> String {
+    let mut sha = Sha3_256::new();
+    for i in 0..AUDIO_PROBE_LEN {
+        sha.update(noised_sample(i, seed32, max_amp).to_le_bytes());
+    }
+    hex::encode(sha.finalize())
+}
+
+/// Compute the preview hashes for a fingerprint.
+pub fn preview_hashes(fp: &Fingerprint) -> FingerprintHashes {
+    let seed32 = js_seed32(fp.seed);
+    let canvas_d = canvas_max_delta(fp.canvas_noise);
+    let webgl_d = webgl_max_delta(fp.webgl_noise);
+    let audio_a = audio_max_amplitude(fp.audio_noise);
+
+    FingerprintHashes {
+        seed: fp.seed,
+        canvas_hash: (canvas_d > 0).then(|| noised_probe_hash(CANVAS_PROBE, seed32, canvas_d)),
+        canvas_max_delta: canvas_d,
+        webgl_hash: (webgl_d > 0).then(|| noised_probe_hash(WEBGL_PROBE, seed32, webgl_d)),
+        webgl_max_delta: webgl_d,
+        audio_hash: (audio_a > 0.0).then(|| audio_hash(seed32, audio_a)),
+        audio_max_amplitude: audio_a,
+    }
+}
+
+// ── Noise distance ────────────────────────────────────────────────────────────
+
+/// Hamming distance between two noised probes: how many of the values a
+/// checker reads differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ProbeDistance {
+    pub differing: u32,
+    /// Values in the probe (colour channels, or audio samples).
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoiseDistance {
+    pub canvas: ProbeDistance,
+    pub webgl: ProbeDistance,
+    pub audio: ProbeDistance,
+}
+
+fn probe_distance(size: (u32, u32), a: (u32, u32), b: (u32, u32)) -> ProbeDistance {
+    let mut differing = 0;
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            for ch in 0..3 {
+                if noised_channel(x, y, ch, a.0, a.1) != noised_channel(x, y, ch, b.0, b.1) {
+                    differing += 1;
+                }
+            }
+        }
+    }
+    ProbeDistance {
+        differing,
+        total: size.0 * size.1 * 3,
+    }
+}
+
+/// Compare the canvas, WebGL and audio probes `a` and `b` produce.
+pub fn noise_distance(a: &Fingerprint, b: &Fingerprint) -> NoiseDistance {
+    let (sa, sb) = (js_seed32(a.seed), js_seed32(b.seed));
+    let canvas = probe_distance(
+        CANVAS_PROBE,
+        (sa, canvas_max_delta(a.canvas_noise)),
+        (sb, canvas_max_delta(b.canvas_noise)),
+    );
+    let webgl = probe_distance(
+        WEBGL_PROBE,
+        (sa, webgl_max_delta(a.webgl_noise)),
+        (sb, webgl_max_delta(b.webgl_noise)),
+    );
+    let (aa, ab) = (
+        audio_max_amplitude(a.audio_noise),
+        audio_max_amplitude(b.audio_noise),
+    );
+    let audio = ProbeDistance {
+        differing: (0..AUDIO_PROBE_LEN)
+            .filter(|&i| noised_sample(i, sa, aa) != noised_sample(i, sb, ab))
+            .count() as u32,
+        total: AUDIO_PROBE_LEN,
+    };
+    NoiseDistance {
+        canvas,
+        webgl,
+        audio,
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    #[test]
+    fn wang_matches_js_reference() {
+        // Computed in node with the _delta body from evasions/canvas.ts:
+        //   _SEED=12345, _MAX_DELTA=2 → _delta(10, 20, 1) === 2,
+        //   _delta(0, 0, 0) === -1, _delta(239, 59, 2) === -1
+        let seed32 = js_seed32(12345);
+        assert_eq!(pixel_delta(10, 20, 1, seed32, 2), 2);
+        assert_eq!(pixel_delta(0, 0, 0, seed32, 2), -1);
+        assert_eq!(pixel_delta(239, 59, 2, seed32, 2), -1);
+    }
+
+    #[test]
+    fn js_seed32_matches_unsigned_shift() {
+        assert_eq!(js_seed32(12345), 12345);
+        assert_eq!(js_seed32(u32::MAX as u64 + 2), 1);
+        // 2^53 + 1 is not representable; JS sees 2^53, whose low 32 bits are 0
+        assert_eq!(js_seed32((1u64 << 53) + 1), 0);
+    }
+
+    #[test]
+    fn max_delta_mapping_matches_scripts() {
+        assert_eq!(canvas_max_delta(0.0), 0);
+        assert_eq!(canvas_max_delta(0.1), 0);
+        assert_eq!(canvas_max_delta(0.13), 1);
+        assert_eq!(canvas_max_delta(5.0), 4);
+        assert_eq!(webgl_max_delta(0.2), 1);
+        assert!((audio_max_amplitude(0.5) - 6e-5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hashes_are_deterministic_and_seed_specific() {
+        let mut a = FingerprintOrchestrator::generate(1);
+        let mut b = FingerprintOrchestrator::generate(2);
+        for fp in [&mut a, &mut b] {
+            fp.canvas_noise = 0.5;
+            fp.webgl_noise = 0.5;
+        }
+        let ha = preview_hashes(&a);
+        assert_eq!(ha.canvas_hash, preview_hashes(&a).canvas_hash);
+        assert_ne!(ha.canvas_hash, preview_hashes(&b).canvas_hash);
+        assert_ne!(ha.audio_hash, preview_hashes(&b).audio_hash);
+        assert_eq!(ha.canvas_hash.as_ref().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn noise_distance_is_zero_for_the_same_noise() {
+        let mut a = FingerprintOrchestrator::generate(1);
+        a.canvas_noise = 0.5;
+        let d = noise_distance(&a, &a.clone());
+        assert_eq!(d.canvas.differing, 0);
+        assert_eq!(d.canvas.total, 240 * 60 * 3);
+
+        let mut b = FingerprintOrchestrator::generate(2);
+        b.canvas_noise = 0.5;
+        assert!(noise_distance(&a, &b).canvas.differing > 0);
+        b.canvas_noise = 0.0;
+        a.canvas_noise = 0.0;
+        assert_eq!(noise_distance(&a, &b).canvas.differing, 0);
+    }
+
+    #[test]
+    fn disabled_noise_has_no_hash() {
+        let mut fp = FingerprintOrchestrator::generate(3);
+        fp.canvas_noise = 0.0;
+        fp.audio_noise = 0.0;
+        let h = preview_hashes(&fp);
+        assert!(h.canvas_hash.is_none());
+        assert!(h.audio_hash.is_none());
+    }
+}
@@ -0,0 +1,414 @@
+// ── Manifold request header order ─────────────────────────────────────────────
+//
+// Header order (and, over HTTP/1.1, header casing) is part of Akamai's
+// request fingerprint: Chrome emits the same headers in the same order for a
+// given kind of request, so a stack that reorders them stands out even with a
+// perfect UA.  This module generates the order Chrome uses for the profile's
+// major, ships it to the bridge in the launch config, and checks recorded
+// traffic (HAR entries) against it for session diagnostics.
+
+use serde::{Deserialize, Serialize};
+
+use crate::fingerprint::{chrome_version, Fingerprint};
+
+/// First Chrome major that sends the RFC 9218 `priority` header.
+const PRIORITY_HEADER_SINCE: u32 = 124;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    /// Top-level or frame navigation (`sec-fetch-mode: navigate`).
+    Navigation,
+    /// `fetch()` / XHR.
+    Fetch,
+    /// Scripts, styles, images and other subresources.
+    Subresource,
+}
+
+/// Chrome's header order for each request kind.  `http2` names are the
+/// lowercase wire form; `http1` carries Chrome's HTTP/1.1 casing.  Headers a
+/// request doesn't need (cookie, referer, origin, …) are simply skipped —
+/// the relative order of the rest is what matters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderOrderProfile {
+    pub chrome_major: Option<u32>,
+    /// HTTP/2 pseudo-header order (`m,a,s,p` for Chrome).
+    pub pseudo_headers: Vec<String>,
+    pub navigation: HeaderOrder,
+    pub fetch: HeaderOrder,
+    pub subresource: HeaderOrder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderOrder {
+    pub http2: Vec<String>,
+    pub http1: Vec<String>,
+}
+
+impl HeaderOrderProfile {
+    pub fn for_kind(&self, kind: RequestKind) -> &HeaderOrder {
+        match kind {
+            RequestKind::Navigation => &self.navigation,
+            RequestKind::Fetch => &self.fetch,
+            RequestKind::Subresource => &self.subresource,
+        }
+    }
+}
+
+/// Result of comparing one observed request against the profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HeaderOrderCheck {
+    /// Headers present in both lists appear in the expected relative order.
+    pub in_order: bool,
+    /// First observed header that broke the order.
+    pub first_out_of_order: Option<String>,
+    /// Observed headers Chrome never sends for this kind of request.
+    pub unexpected: Vec<String>,
+    /// HTTP/1.1 headers whose casing differs from Chrome's.
+    pub miscased: Vec<String>,
+}
+
+/// One HAR entry checked for session diagnostics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarHeaderCheck {
+    pub url: String,
+    pub kind: RequestKind,
+    pub check: HeaderOrderCheck,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderOrderReport {
+    pub checked: usize,
+    pub in_order: usize,
+    /// Entries that failed, in HAR order.
+    pub failures: Vec<HarHeaderCheck>,
+}
+
+// ── Generation ────────────────────────────────────────────────────────────────
+
+/// Header order of the Chrome major in `fp.user_agent`.
+pub fn chrome_header_order(fp: &Fingerprint) -> HeaderOrderProfile {
+    let major =
+        chrome_version(&fp.user_agent).and_then(|v| v.split('.').next()?.parse::<u32>().ok());
+    let priority = major.is_some_and(|m| m >= PRIORITY_HEADER_SINCE);
+
+    let order = |names: &[&str]| {
+        let http2: Vec<String> = names
+            .iter()
+            .filter(|n| priority || **n != "priority")
+            .map(|n| n.to_string())
+            .collect();
+        let http1 = ["Host", "Connection"]
+            .into_iter()
+            .map(String::from)
+            .chain(
+                http2
+                    .iter()
+                    // HTTP/1.1 has no priority header; Chrome uses
+                    // connection-level prioritisation there
+                    .filter(|n| *n != "priority")
+                    .map(|n| chrome_h1_case(n)),
+            )
+            .collect();
+        HeaderOrder { http2, http1 }
+    };
+
+    HeaderOrderProfile {
+        chrome_major: major,
+        pseudo_headers: [":method", ":authority", ":scheme", ":path"]
+            .map(String::from)
+            .to_vec(),
+        navigation: order(&[
+            "cache-control",
+            "sec-ch-ua",
+            "sec-ch-ua-mobile",
+            "sec-ch-ua-platform",
+            "upgrade-insecure-requests",
+            "user-agent",
+            "accept",
+            "sec-fetch-site",
+            "sec-fetch-mode",
+            "sec-fetch-user",
+            "sec-fetch-dest",
+            "referer",
+            "accept-encoding",
+            "accept-language",
+            "cookie",
+            "priority",
+        ]),
+        fetch: order(&[
+            "content-length",
+            "sec-ch-ua-platform",
+            "user-agent",
+            "sec-ch-ua",
+            "content-type",
+            "sec-ch-ua-mobile",
+            "accept",
+            "origin",
+            "sec-fetch-site",
+            "sec-fetch-mode",
+            "sec-fetch-dest",
+            "referer",
+            "accept-encoding",
+            "accept-language",
+            "cookie",
+            "priority",
+        ]),
+        subresource: order(&[
+            "sec-ch-ua-platform",
+            "user-agent",
+            "sec-ch-ua",
+            "sec-ch-ua-mobile",
+            "accept",
+            "origin",
+            "sec-fetch-site",
+            "sec-fetch-mode",
+            "sec-fetch-dest",
+            "referer",
+            "accept-encoding",
+            "accept-language",
+            "cookie",
+            "priority",
+        ]),
+    }
+}
+
+/// Chrome's HTTP/1.1 casing: client hints stay lowercase, everything else
+/// is Title-Case (`Sec-Fetch-Site`, `User-Agent`).
+fn chrome_h1_case(name: &str) -> String {
+    if name.starts_with("sec-ch-") {
+        return name.to_string();
+    }
+    name.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// ── Verification ──────────────────────────────────────────────────────────────
+
+/// Compare observed header names with `expected`.  Pass `check_case` for
+/// HTTP/1.1 traffic, where casing is on the wire.
+pub fn verify_order(
+    expected: &[String],
+    observed: &[String],
+    check_case: bool,
+) -> HeaderOrderCheck {
+    let mut last_index = None;
+    let mut first_out_of_order = None;
+    let mut unexpected = Vec::new();
+    let mut miscased = Vec::new();
+
+    for name in observed {
+        // Pseudo-header order is fixed by the browser's HTTP/2 stack
+        if name.starts_with(':') {
+            continue;
+        }
+        match expected.iter().position(|e| e.eq_ignore_ascii_case(name)) {
+            Some(i) => {
+                if check_case && expected[i] != *name {
+                    miscased.push(name.clone());
+                }
+                if last_index.is_some_and(|last| i < last) && first_out_of_order.is_none() {
+                    first_out_of_order = Some(name.clone());
+                }
+                last_index = Some(i);
+            }
+            None => unexpected.push(name.clone()),
+        }
+    }
+
+    HeaderOrderCheck {
+        in_order: first_out_of_order.is_none(),
+        first_out_of_order,
+        unexpected,
+        miscased,
+    }
+}
+
+/// Classify a request from its fetch metadata headers.
+pub fn request_kind(headers: &[(String, String)]) -> RequestKind {
+    let get = |key: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    };
+    match (get("sec-fetch-mode"), get("sec-fetch-dest")) {
+        (Some("navigate"), _) => RequestKind::Navigation,
+        (_, Some("empty")) => RequestKind::Fetch,
+        _ => RequestKind::Subresource,
+    }
+}
+
+/// Check every request in a HAR log against `profile`.
+pub fn verify_har(profile: &HeaderOrderProfile, har: &serde_json::Value) -> HeaderOrderReport {
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(|e| e.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut report = HeaderOrderReport {
+        checked: 0,
+        in_order: 0,
+        failures: Vec::new(),
+    };
+    for entry in &entries {
+        let Some(request) = entry.get("request") else {
+            continue;
+        };
+        let headers: Vec<(String, String)> = request
+            .get("headers")
+            .and_then(|h| h.as_array())
+            .map(|hs| {
+                hs.iter()
+                    .filter_map(|h| {
+                        Some((
+                            h.get("name")?.as_str()?.to_string(),
+                            h.get("value")?.as_str().unwrap_or_default().to_string(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if headers.is_empty() {
+            continue;
+        }
+
+        let kind = request_kind(&headers);
+        let http1 = request
+            .get("httpVersion")
+            .and_then(|v| v.as_str())
+            .is_some_and(|v| v.eq_ignore_ascii_case("HTTP/1.1"));
+        let order = profile.for_kind(kind);
+        let expected = if http1 { &order.http1 } else { &order.http2 };
+        let names: Vec<String> = headers.into_iter().map(|(k, _)| k).collect();
+        let check = verify_order(expected, &names, http1);
+
+        report.checked += 1;
+        if check.in_order && check.unexpected.is_empty() && check.miscased.is_empty() {
+            report.in_order += 1;
+        } else {
+            report.failures.push(HarHeaderCheck {
+                url: request
+                    .get("url")
+                    .and_then(|u| u.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                kind,
+                check,
+            });
+        }
+    }
+    report
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn fp_with_major(major: u32) -> Fingerprint {
+        let mut fp = FingerprintOrchestrator::generate(7);
+        fp.user_agent = format!(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{major}.0.0.0 Safari/537.36"
+        );
+        fp
+    }
+
+    #[test]
+    fn priority_header_follows_chrome_major() {
+        let new = chrome_header_order(&fp_with_major(133));
+        assert_eq!(new.chrome_major, Some(133));
+        assert_eq!(new.navigation.http2.last().unwrap(), "priority");
+        assert!(!new.navigation.http1.iter().any(|h| h == "priority"));
+
+        let old = chrome_header_order(&fp_with_major(120));
+        assert!(!old.fetch.http2.iter().any(|h| h == "priority"));
+    }
+
+    #[test]
+    fn http1_uses_chrome_casing() {
+        let profile = chrome_header_order(&fp_with_major(133));
+        let h1 = &profile.navigation.http1;
+        assert_eq!(&h1[..3], ["Host", "Connection", "Cache-Control"]);
+        assert!(h1.contains(&"sec-ch-ua-platform".to_string()));
+        assert!(h1.contains(&"Sec-Fetch-Site".to_string()));
+        assert!(h1.contains(&"Upgrade-Insecure-Requests".to_string()));
+    }
+
+    #[test]
+    fn verify_flags_reordering_unknown_and_casing() {
+        let expected = names(&["Host", "User-Agent", "Accept", "Accept-Language"]);
+
+        let ok = verify_order(
+            &expected,
+            &names(&["Host", "User-Agent", "Accept-Language"]),
+            true,
+        );
+        assert!(ok.in_order && ok.unexpected.is_empty() && ok.miscased.is_empty());
+
+        let bad = verify_order(
+            &expected,
+            &names(&["Host", "Accept", "user-agent", "X-Requested-With"]),
+            true,
+        );
+        assert!(!bad.in_order);
+        assert_eq!(bad.first_out_of_order.as_deref(), Some("user-agent"));
+        assert_eq!(bad.unexpected, vec!["X-Requested-With"]);
+        assert_eq!(bad.miscased, vec!["user-agent"]);
+    }
+
+    #[test]
+    fn har_entries_are_classified_and_checked() {
+        let profile = chrome_header_order(&fp_with_major(133));
+        let har = serde_json::json!({ "log": { "entries": [
+            { "request": {
+                "url": "https://example.com/",
+                "httpVersion": "h2",
+                "headers": [
+                    { "name": ":method", "value": "GET" },
+                    { "name": "sec-ch-ua", "value": "x" },
+                    { "name": "user-agent", "value": "x" },
+                    { "name": "sec-fetch-mode", "value": "navigate" },
+                    { "name": "accept-language", "value": "en" }
+                ]
+            }},
+            { "request": {
+                "url": "https://example.com/api",
+                "httpVersion": "h2",
+                "headers": [
+                    { "name": "accept", "value": "*/*" },
+                    { "name": "user-agent", "value": "x" },
+                    { "name": "sec-fetch-mode", "value": "cors" },
+                    { "name": "sec-fetch-dest", "value": "empty" }
+                ]
+            }}
+        ]}});
+
+        let report = verify_har(&profile, &har);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.in_order, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].kind, RequestKind::Fetch);
+        assert_eq!(
+            report.failures[0].check.first_out_of_order.as_deref(),
+            Some("user-agent")
+        );
+    }
+}
@@ -0,0 +1,517 @@
+// ── Manifold host contamination audit ─────────────────────────────────────────
+//
+// The bridge spoofs what a page can ask the browser, but some of what the
+// host is shows through regardless: a virtual machine or a remote-desktop
+// session leaves Chromium without a GPU, so WebGL draws in software whatever
+// renderer the profile names; the system locale picks the language of native
+// validation messages and dialogs; an odd display scale changes how text is
+// hinted and rasterised.  None of that can be fully masked from inside the
+// page.
+//
+// The audit looks at the host once at startup (and on `get_host_audit`), lists
+// the conditions it found and, for each, the profiles whose fingerprint
+// contradicts the host in a way the bridge can't paper over.  Detection is
+// best-effort and read-only: environment variables, DMI strings, the CPU's
+// hypervisor flag and the OS's own tools, with anything unreadable treated
+// as not found.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::profile::Profile;
+
+/// Display scales the major OSes offer in their settings.
+const STANDARD_SCALES: &[f64] = &[1.0, 1.25, 1.5, 1.75, 2.0, 2.25, 2.5, 3.0];
+
+/// Vendor and model strings of common hypervisors, lowercased, with the
+/// name reported for them.
+const VM_SIGNATURES: &[(&str, &str)] = &[
+    ("vmware", "VMware"),
+    ("virtualbox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("qemu", "QEMU"),
+    ("kvm", "KVM"),
+    ("xen", "Xen"),
+    ("parallels", "Parallels"),
+    ("virtual machine", "Hyper-V"),
+    ("bochs", "Bochs"),
+    ("virtualmac", "Apple Virtualization"),
+];
+
+/// Renderer strings of software WebGL, lowercased.
+const SOFTWARE_RENDERERS: &[&str] = &["swiftshader", "llvmpipe", "software", "basic render"];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What the audit could find out about the host.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostEnvironment {
+    pub os: String,
+    /// BCP-47 (`en-US`), or `C` / `POSIX`; `None` when unset.
+    pub locale: Option<String>,
+    /// What gave a virtual machine away, e.g. `VMware (sys_vendor)`.
+    pub virtual_machine: Option<String>,
+    /// The kind of remote session the app runs in.
+    pub remote_session: Option<String>,
+    /// Scale factor of the primary display.
+    pub display_scale: Option<f64>,
+    pub cpu_cores: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostCondition {
+    Locale,
+    VirtualMachine,
+    RemoteDesktop,
+    DisplayScaling,
+}
+
+/// A host condition known to leak through the bridge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostFinding {
+    pub condition: HostCondition,
+    pub message: String,
+}
+
+/// A profile whose fingerprint the host contradicts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileConflict {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub condition: HostCondition,
+    /// The fingerprint field in conflict.
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostAudit {
+    pub host: HostEnvironment,
+    pub findings: Vec<HostFinding>,
+    pub conflicts: Vec<ProfileConflict>,
+    pub audited_at: DateTime<Utc>,
+}
+
+// ── Classification ────────────────────────────────────────────────────────────
+
+/// `en_US.UTF-8` → `en-US`; `C.UTF-8` → `C`; empty → `None`.
+pub fn normalize_locale(raw: &str) -> Option<String> {
+    let name = raw.trim().split(['.', '@']).next().unwrap_or("");
+    match name {
+        "" => None,
+        "C" | "POSIX" => Some(name.into()),
+        _ => Some(name.replace('_', "-")),
+    }
+}
+
+/// The primary language subtag, lowercased; `None` for `C` / `POSIX`.
+pub fn locale_language(locale: &str) -> Option<String> {
+    if matches!(locale, "C" | "POSIX") {
+        return None;
+    }
+    let lang = locale.split(['-', '_']).next()?.to_ascii_lowercase();
+    (!lang.is_empty()).then_some(lang)
+}
+
+/// The hypervisor a vendor or model string names, if any.
+pub fn vm_signature(text: &str) -> Option<&'static str> {
+    let text = text.to_ascii_lowercase();
+    VM_SIGNATURES
+        .iter()
+        .find(|(needle, _)| text.contains(needle))
+        .map(|(_, name)| *name)
+}
+
+/// The remote session the environment variables point at.
+pub fn remote_session(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if var("SESSIONNAME").is_some_and(|s| s.to_ascii_uppercase().starts_with("RDP-")) {
+        return Some("Remote Desktop".into());
+    }
+    if var("XRDP_SESSION").is_some() {
+        return Some("xrdp".into());
+    }
+    if var("CHROME_REMOTE_DESKTOP_DEFAULT_DESKTOP_SIZES").is_some() {
+        return Some("Chrome Remote Desktop".into());
+    }
+    if var("SSH_CONNECTION").is_some() || var("SSH_CLIENT").is_some() {
+        return Some("SSH".into());
+    }
+    None
+}
+
+pub fn is_standard_scale(scale: f64) -> bool {
+    STANDARD_SCALES.iter().any(|s| (s - scale).abs() < 0.01)
+}
+
+fn is_software_renderer(renderer: &str) -> bool {
+    let renderer = renderer.to_ascii_lowercase();
+    SOFTWARE_RENDERERS.iter().any(|s| renderer.contains(s))
+}
+
+// ── Audit ─────────────────────────────────────────────────────────────────────
+
+/// The host's conditions and the profiles they conflict with.
+pub fn audit(host: &HostEnvironment, profiles: &[Profile]) -> HostAudit {
+    let mut findings = Vec::new();
+    let host_language = host.locale.as_deref().and_then(locale_language);
+    match host.locale.as_deref() {
+        None => findings.push(HostFinding {
+            condition: HostCondition::Locale,
+            message: "the system locale is unset; native messages fall back to English".into(),
+        }),
+        Some(locale) if host_language.is_none() => findings.push(HostFinding {
+            condition: HostCondition::Locale,
+            message: format!("the system locale is {locale}, as on a server or container"),
+        }),
+        Some(_) => {}
+    }
+    // Either one leaves Chromium rendering WebGL in software
+    let no_gpu = match (&host.virtual_machine, &host.remote_session) {
+        (Some(vm), _) => {
+            findings.push(HostFinding {
+                condition: HostCondition::VirtualMachine,
+                message: format!("running in a virtual machine ({vm})"),
+            });
+            Some(HostCondition::VirtualMachine)
+        }
+        (None, Some(session)) if session != "SSH" => Some(HostCondition::RemoteDesktop),
+        _ => None,
+    };
+    if let Some(session) = &host.remote_session {
+        findings.push(HostFinding {
+            condition: HostCondition::RemoteDesktop,
+            message: format!("running in a {session} session"),
+        });
+    }
+    let odd_scale = host.display_scale.filter(|s| !is_standard_scale(*s));
+    if let Some(scale) = odd_scale {
+        findings.push(HostFinding {
+            condition: HostCondition::DisplayScaling,
+            message: format!("the display is scaled to a non-standard {scale}x"),
+        });
+    }
+
+    let mut conflicts = Vec::new();
+    for profile in profiles {
+        let fp = &profile.fingerprint;
+        let mut conflict = |condition, field: &str, message: String| {
+            conflicts.push(ProfileConflict {
+                profile_id: profile.id.clone(),
+                profile_name: profile.name.clone(),
+                condition,
+                field: field.into(),
+                message,
+            })
+        };
+        if let (Some(host_lang), Some(lang)) = (&host_language, locale_language(&fp.locale)) {
+            if *host_lang != lang {
+                conflict(
+                    HostCondition::Locale,
+                    "locale",
+                    format!(
+                        "native validation messages and dialogs follow the host's {} locale, not {}",
+                        host.locale.as_deref().unwrap_or_default(),
+                        fp.locale
+                    ),
+                );
+            }
+        }
+        if let Some(condition) = no_gpu {
+            if !is_software_renderer(&fp.webgl_renderer) {
+                conflict(
+                    condition,
+                    "webgl_renderer",
+                    format!(
+                        "WebGL renders in software here, so its output won't match {}",
+                        fp.webgl_renderer
+                    ),
+                );
+            }
+        }
+        if host.virtual_machine.is_some() {
+            if let Some(cores) = host
+                .cpu_cores
+                .filter(|c| u32::from(fp.hardware_concurrency) > *c)
+            {
+                conflict(
+                    HostCondition::VirtualMachine,
+                    "hardware_concurrency",
+                    format!(
+                        "claims {} cores but workers run on the VM's {cores}",
+                        fp.hardware_concurrency
+                    ),
+                );
+            }
+        }
+        if host.remote_session.is_some() && (fp.color_depth > 24 || fp.hdr) {
+            conflict(
+                HostCondition::RemoteDesktop,
+                "color_depth",
+                "remote sessions carry 24-bit colour without HDR".into(),
+            );
+        }
+        if let Some(scale) = odd_scale {
+            if (fp.pixel_ratio - scale).abs() >= 0.01 {
+                conflict(
+                    HostCondition::DisplayScaling,
+                    "pixel_ratio",
+                    format!(
+                        "text is hinted at the host's {scale}x, not the profile's {}x",
+                        fp.pixel_ratio
+                    ),
+                );
+            }
+        }
+    }
+
+    HostAudit {
+        host: host.clone(),
+        findings,
+        conflicts,
+        audited_at: Utc::now(),
+    }
+}
+
+/// How many profiles each condition affects, for the startup log.
+pub fn conflict_counts(audit: &HostAudit) -> HashMap<HostCondition, usize> {
+    let mut profiles: HashMap<HostCondition, Vec<&str>> = HashMap::new();
+    for c in &audit.conflicts {
+        let ids = profiles.entry(c.condition).or_default();
+        if !ids.contains(&c.profile_id.as_str()) {
+            ids.push(&c.profile_id);
+        }
+    }
+    profiles.into_iter().map(|(k, v)| (k, v.len())).collect()
+}
+
+// ── Detection ─────────────────────────────────────────────────────────────────
+
+/// Look at the host.  `display_scale` comes from the window system.
+pub fn detect(display_scale: Option<f64>) -> HostEnvironment {
+    HostEnvironment {
+        os: std::env::consts::OS.into(),
+        locale: system_locale().as_deref().and_then(normalize_locale),
+        virtual_machine: virtual_machine(),
+        remote_session: remote_session(|name| std::env::var(name).ok().filter(|v| !v.is_empty())),
+        display_scale,
+        cpu_cores: std::thread::available_parallelism()
+            .ok()
+            .map(|n| n.get() as u32),
+    }
+}
+
+/// Trimmed stdout of a command that succeeded.  Linux reads what it needs
+/// from files.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn system_locale() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        command_output(
+            "powershell",
+            &["-NoProfile", "-Command", "(Get-Culture).Name"],
+        )
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()));
+        #[cfg(target_os = "macos")]
+        let env = env.or_else(|| command_output("defaults", &["read", "-g", "AppleLocale"]));
+        env
+    }
+}
+
+fn virtual_machine() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        for file in ["sys_vendor", "product_name"] {
+            let text = std::fs::read_to_string(format!("/sys/class/dmi/id/{file}"));
+            if let Some(name) = text.ok().as_deref().and_then(vm_signature) {
+                return Some(format!("{name} ({file})"));
+            }
+        }
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let flagged = cpuinfo
+            .lines()
+            .filter(|l| l.starts_with("flags"))
+            .any(|l| l.split_whitespace().any(|f| f == "hypervisor"));
+        flagged.then(|| "hypervisor CPU flag".into())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        if command_output("sysctl", &["-n", "kern.hv_vmm_present"]).as_deref() == Some("1") {
+            return Some("hypervisor (kern.hv_vmm_present)".into());
+        }
+        command_output("sysctl", &["-n", "hw.model"])
+            .as_deref()
+            .and_then(vm_signature)
+            .map(|name| format!("{name} (hw.model)"))
+    }
+    #[cfg(target_os = "windows")]
+    {
+        command_output(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_ComputerSystem | ForEach-Object { $_.Manufacturer + ' ' + $_.Model }",
+            ],
+        )
+        .as_deref()
+        .and_then(vm_signature)
+        .map(|name| format!("{name} (Win32_ComputerSystem)"))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        None
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+
+    fn profile(repo: &ProfileRepo, name: &str) -> Profile {
+        repo.create(CreateProfileRequest {
+            name: name.into(),
+            seed: Some(1),
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            persona: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn classifies_host_strings() {
+        assert_eq!(normalize_locale("en_US.UTF-8").as_deref(), Some("en-US"));
+        assert_eq!(normalize_locale("C.UTF-8").as_deref(), Some("C"));
+        assert_eq!(normalize_locale(" "), None);
+        assert_eq!(locale_language("de-AT").as_deref(), Some("de"));
+        assert_eq!(locale_language("POSIX"), None);
+
+        assert_eq!(vm_signature("innotek GmbH\n"), Some("VirtualBox"));
+        assert_eq!(
+            vm_signature("Microsoft Corporation Virtual Machine"),
+            Some("Hyper-V")
+        );
+        assert_eq!(vm_signature("Dell Inc."), None);
+
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(
+            remote_session(env(&[("SESSIONNAME", "RDP-Tcp#3")])).as_deref(),
+            Some("Remote Desktop")
+        );
+        assert_eq!(remote_session(env(&[("SESSIONNAME", "Console")])), None);
+        assert!(is_standard_scale(1.75) && !is_standard_scale(1.1));
+    }
+
+    #[test]
+    fn flags_profiles_the_host_contradicts() {
+        let repo = ProfileRepo::new(Db::open_in_memory().unwrap());
+        let mut gpu = profile(&repo, "gpu");
+        gpu.fingerprint.locale = "fr-FR".into();
+        gpu.fingerprint.webgl_renderer = "ANGLE (NVIDIA GeForce RTX 3060)".into();
+        gpu.fingerprint.hardware_concurrency = 16;
+        gpu.fingerprint.pixel_ratio = 1.0;
+        let mut soft = profile(&repo, "soft");
+        soft.fingerprint.locale = "en-GB".into();
+        soft.fingerprint.webgl_renderer = "Google SwiftShader".into();
+        soft.fingerprint.hardware_concurrency = 4;
+        soft.fingerprint.pixel_ratio = 1.1;
+        soft.fingerprint.color_depth = 24;
+        soft.fingerprint.hdr = false;
+
+        let clean = HostEnvironment {
+            os: "linux".into(),
+            locale: Some("en-US".into()),
+            display_scale: Some(1.0),
+            cpu_cores: Some(8),
+            ..Default::default()
+        };
+        let report = audit(&clean, &[soft.clone()]);
+        assert!(report.findings.is_empty() && report.conflicts.is_empty());
+
+        let vm = HostEnvironment {
+            locale: Some("C".into()),
+            virtual_machine: Some("VMware (sys_vendor)".into()),
+            display_scale: Some(1.1),
+            ..clean
+        };
+        let report = audit(&vm, &[gpu.clone(), soft]);
+        let conditions: Vec<_> = report.findings.iter().map(|f| f.condition).collect();
+        assert_eq!(
+            conditions,
+            [
+                HostCondition::Locale,
+                HostCondition::VirtualMachine,
+                HostCondition::DisplayScaling
+            ]
+        );
+        // Only the hardware-GPU profile conflicts; "C" has no language to compare
+        let fields: Vec<_> = report
+            .conflicts
+            .iter()
+            .map(|c| (c.profile_name.as_str(), c.field.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("gpu", "webgl_renderer"),
+                ("gpu", "hardware_concurrency"),
+                ("gpu", "pixel_ratio")
+            ]
+        );
+        assert_eq!(conflict_counts(&report)[&HostCondition::VirtualMachine], 1);
+
+        let rdp = HostEnvironment {
+            os: "windows".into(),
+            locale: Some("en-US".into()),
+            remote_session: Some("Remote Desktop".into()),
+            ..Default::default()
+        };
+        gpu.fingerprint.color_depth = 30;
+        let report = audit(&rdp, &[gpu]);
+        let fields: Vec<_> = report
+            .conflicts
+            .iter()
+            .map(|c| (c.condition, c.field.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (HostCondition::Locale, "locale"),
+                (HostCondition::RemoteDesktop, "webgl_renderer"),
+                (HostCondition::RemoteDesktop, "color_depth")
+            ]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+// ── Manifold referential integrity ────────────────────────────────────────────
+//
+// Profiles point at proxies in two ways: `profiles.proxy_id`, a declared
+// foreign key, and `profiles.proxy_chain`, a JSON array of proxy ids that
+// SQLite can't constrain.  Deleting a proxy used to null the first and leave
+// the second dangling, and databases created before the foreign keys were
+// declared can hold dangling ids in both — plus rows in per-profile tables
+// whose profile is long gone.
+//
+// Deleting a proxy now takes an `OnDelete` choice: `Nullify` detaches it
+// from every profile (direct use and chain hops) before removing it,
+// `Block` refuses while anything still references it.  `check` lists broken
+// references of every kind and `repair` fixes them the same way: dangling
+// proxy ids are cleared, dangling chain hops dropped and orphaned rows
+// deleted.
+
+use std::collections::HashSet;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What deleting a still-referenced proxy does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDelete {
+    /// Detach it from every profile, then delete.
+    #[default]
+    Nullify,
+    /// Refuse while any profile uses it.
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// `profiles.proxy_id` names a missing proxy.
+    DanglingProxy,
+    /// `profiles.proxy_chain` contains a missing proxy.
+    DanglingChainHop,
+    /// A row whose declared foreign key points at a missing parent.
+    OrphanRow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IntegrityIssue {
+    pub kind: IssueKind,
+    pub table: String,
+    /// Id of the row (rowid for tables keyed otherwise).
+    pub row: String,
+    /// The table the missing row belongs to.
+    pub parent: String,
+    /// The missing id, when known.
+    pub missing: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    /// Issues fixed by `repair`; 0 for `check`.
+    pub repaired: usize,
+}
+
+// ── Proxy references ──────────────────────────────────────────────────────────
+
+/// Names of the profiles using `proxy_id` directly or as a chain hop.
+pub fn proxy_users(conn: &Connection, proxy_id: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT name FROM profiles
+           WHERE proxy_id = ?1
+              OR EXISTS (SELECT 1 FROM json_each(profiles.proxy_chain) WHERE value = ?1)
+           ORDER BY name"#,
+    )?;
+    let names = stmt
+        .query_map(params![proxy_id], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(names)
+}
+
+/// Fail with `Block`, or detach `proxy_id` from every profile with
+/// `Nullify`.  Run right before the proxy row is deleted.
+pub fn release_proxy(conn: &Connection, proxy_id: &str, on_delete: OnDelete) -> Result<()> {
+    match on_delete {
+        OnDelete::Block => {
+            let users = proxy_users(conn, proxy_id)?;
+            if !users.is_empty() {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "proxy is still used by {}",
+                    users.join(", ")
+                )));
+            }
+        }
+        OnDelete::Nullify => {
+            conn.execute(
+                "UPDATE profiles SET proxy_id = NULL WHERE proxy_id = ?1",
+                params![proxy_id],
+            )?;
+            let gone = HashSet::from([proxy_id.to_string()]);
+            strip_chains(conn, |hop| gone.contains(hop))?;
+        }
+    }
+    Ok(())
+}
+
+/// Drop the chain hops `remove` matches from every profile's chain.
+/// Returns how many were dropped.
+fn strip_chains(conn: &Connection, remove: impl Fn(&str) -> bool) -> Result<usize> {
+    let chains: Vec<(String, String)> = {
+        let mut stmt =
+            conn.prepare("SELECT id, proxy_chain FROM profiles WHERE proxy_chain <> '[]'")?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows
+    };
+    let mut removed = 0;
+    for (profile_id, json) in chains {
+        let chain: Vec<String> = serde_json::from_str(&json).unwrap_or_default();
+        let (gone, kept): (Vec<String>, Vec<String>) =
+            chain.into_iter().partition(|hop| remove(hop));
+        if gone.is_empty() {
+            continue;
+        }
+        conn.execute(
+            "UPDATE profiles SET proxy_chain = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&kept)?, profile_id],
+        )?;
+        removed += gone.len();
+    }
+    Ok(removed)
+}
+
+// ── Check / repair ────────────────────────────────────────────────────────────
+
+/// Every broken reference in the database.
+pub fn check(db: &Db) -> Result<IntegrityReport> {
+    let issues = db.with_conn(find_issues)?;
+    Ok(IntegrityReport {
+        issues,
+        repaired: 0,
+    })
+}
+
+/// Fix every broken reference: clear dangling proxy ids, drop dangling
+/// chain hops and delete orphaned rows, in one transaction.
+pub fn repair(db: &Db) -> Result<IntegrityReport> {
+    db.with_conn(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        let issues = find_issues(&tx)?;
+        let mut repaired = 0;
+        for issue in &issues {
+            match issue.kind {
+                IssueKind::DanglingProxy => {
+                    repaired += tx.execute(
+                        "UPDATE profiles SET proxy_id = NULL WHERE id = ?1",
+                        params![issue.row],
+                    )?;
+                }
+                IssueKind::OrphanRow => {
+                    // Table names come from sqlite_master via foreign_key_check
+                    repaired += tx.execute(
+                        &format!("DELETE FROM \"{}\" WHERE rowid = ?1", issue.table),
+                        params![issue.row],
+                    )?;
+                }
+                IssueKind::DanglingChainHop => {}
+            }
+        }
+        let known = proxy_ids(&tx)?;
+        repaired += strip_chains(&tx, |hop| !known.contains(hop))?;
+        tx.commit()?;
+        Ok(IntegrityReport { issues, repaired })
+    })
+}
+
+fn proxy_ids(conn: &Connection) -> rusqlite::Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT id FROM proxies")?;
+    let ids = stmt
+        .query_map([], |r| r.get(0))?
+        .collect::<rusqlite::Result<HashSet<_>>>()?;
+    Ok(ids)
+}
+
+fn find_issues(conn: &Connection) -> Result<Vec<IntegrityIssue>> {
+    let mut issues = Vec::new();
+
+    // Checked by hand: this database's table may predate the foreign key
+    {
+        let mut stmt = conn.prepare(
+            r#"SELECT id, proxy_id FROM profiles
+               WHERE proxy_id IS NOT NULL
+                 AND NOT EXISTS (SELECT 1 FROM proxies WHERE proxies.id = profiles.proxy_id)
+               ORDER BY id"#,
+        )?;
+        let rows = stmt.query_map([], |r| {
+            Ok(IntegrityIssue {
+                kind: IssueKind::DanglingProxy,
+                table: "profiles".into(),
+                row: r.get(0)?,
+                parent: "proxies".into(),
+                missing: Some(r.get(1)?),
+            })
+        })?;
+        for row in rows {
+            issues.push(row?);
+        }
+    }
+
+    let known = proxy_ids(conn)?;
+    let mut stmt =
+        conn.prepare("SELECT id, proxy_chain FROM profiles WHERE proxy_chain <> '[]' ORDER BY id")?;
+    let chains = stmt
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (profile_id, json) in chains {
+        let chain: Vec<String> = serde_json::from_str(&json).unwrap_or_default();
+        for hop in chain.into_iter().filter(|hop| !known.contains(hop)) {
+            issues.push(IntegrityIssue {
+                kind: IssueKind::DanglingChainHop,
+                table: "profiles".into(),
+                row: profile_id.clone(),
+                parent: "proxies".into(),
+                missing: Some(hop),
+            });
+        }
+    }
+
+    // Every other declared foreign key
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let orphans = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, Option<i64>>(1)?,
+                r.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (table, rowid, parent) in orphans {
+        let Some(rowid) = rowid else { continue };
+        if table == "profiles" && parent == "proxies" {
+            continue; // DanglingProxy above
+        }
+        issues.push(IntegrityIssue {
+            kind: IssueKind::OrphanRow,
+            table,
+            row: rowid.to_string(),
+            parent,
+            missing: None,
+        });
+    }
+
+    Ok(issues)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two proxies and a profile using one directly and both as a chain.
+    fn seeded() -> Db {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO proxies (id, name, proxy_type, host, port) VALUES
+                       ('x1', 'one', 'http', 'a', 1), ('x2', 'two', 'http', 'b', 2);
+                   INSERT INTO profiles (id, name, fingerprint_json, proxy_id, proxy_chain, created_at)
+                   VALUES ('p1', 'shop', '{}', 'x1', '["x1","x2"]', '2025-01-01T00:00:00+00:00');"#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        db
+    }
+
+    fn profile_refs(db: &Db) -> (Option<String>, String) {
+        db.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT proxy_id, proxy_chain FROM profiles WHERE id = 'p1'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )?)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn block_refuses_and_nullify_detaches_everywhere() {
+        let db = seeded();
+        db.with_conn(|conn| {
+            assert!(release_proxy(conn, "x1", OnDelete::Block).is_err());
+            release_proxy(conn, "x1", OnDelete::Nullify)?;
+            assert!(release_proxy(conn, "x1", OnDelete::Block).is_ok());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(profile_refs(&db), (None, r#"["x2"]"#.to_string()));
+    }
+
+    #[test]
+    fn check_finds_and_repair_fixes_broken_references() {
+        let db = seeded();
+        db.with_conn(|conn| {
+            conn.execute_batch(
+                r#"PRAGMA foreign_keys = OFF;
+                   DELETE FROM proxies WHERE id = 'x2';
+                   UPDATE profiles SET proxy_id = 'ghost';
+                   INSERT INTO sessions (id, profile_id, started_at) VALUES ('s1', 'gone', 'now');
+                   PRAGMA foreign_keys = ON;"#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let report = check(&db).unwrap();
+        let kinds: Vec<IssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                IssueKind::DanglingProxy,
+                IssueKind::DanglingChainHop,
+                IssueKind::OrphanRow
+            ]
+        );
+        assert_eq!(report.issues[1].missing.as_deref(), Some("x2"));
+        assert_eq!(report.issues[2].table, "sessions");
+
+        assert_eq!(repair(&db).unwrap().repaired, 3);
+        assert!(check(&db).unwrap().issues.is_empty());
+        assert_eq!(profile_refs(&db), (None, r#"["x1"]"#.to_string()));
+    }
+}
@@ -0,0 +1,285 @@
+// ── Manifold Intl locale data ─────────────────────────────────────────────────
+//
+// What `Intl.*` reports follows from the locale: ICU picks the calendar,
+// numbering system and hour cycle, `Intl.Locale#getWeekInfo` the first day
+// of the week and the weekend, and `Intl.NumberFormat` the currency layout.
+// Scripts read these next to `navigator.language` and the timezone, so they
+// have to be the values of the profile's locale rather than of the machine
+// the bridge runs on.  `IntlProfile` is that data for one locale: stored in
+// the fingerprint, handed to the Intl evasion and checked by GeoValidator.
+//
+// Chrome ignores the OS region overrides (24-hour clock on an en-US system,
+// custom first weekday), so the data is a pure function of the locale.
+
+use serde::{Deserialize, Serialize};
+
+/// Amount formatted for `IntlProfile::currency_sample`.
+const SAMPLE_AMOUNT: f64 = 1234.56;
+
+/// Regions whose locales default to a 12-hour clock.
+const TWELVE_HOUR_REGIONS: &[&str] = &[
+    "US", "CA", "MX", "AU", "NZ", "IN", "PK", "PH", "HK", "TW", "SG", "EG", "SA",
+];
+
+const NBSP: &str = "\u{a0}";
+const NNBSP: &str = "\u{202f}";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntlProfile {
+    /// `Intl.DateTimeFormat().resolvedOptions().calendar`
+    pub calendar: String,
+    /// `Intl.NumberFormat().resolvedOptions().numberingSystem`
+    pub numbering_system: String,
+    /// `hourCycle` of a format with `hour: "numeric"` (`h12` | `h23`).
+    pub hour_cycle: String,
+    /// `getWeekInfo().firstDay`: 1 = Monday … 7 = Sunday.
+    pub first_day_of_week: u8,
+    /// `getWeekInfo().weekend`
+    pub weekend: Vec<u8>,
+    /// `getWeekInfo().minimalDays`
+    pub minimal_days: u8,
+    /// ISO 4217 code of the region's currency.
+    pub currency: String,
+    /// `new Intl.NumberFormat(locale, { style: "currency", currency })`
+    /// applied to 1234.56.
+    pub currency_sample: String,
+}
+
+impl IntlProfile {
+    /// ICU's data for a BCP-47 `locale` (`th-TH` counts in the Buddhist era,
+    /// `fa-IR` in Persian digits, `en-US` on a 12-hour clock from Sunday).
+    pub fn for_locale(locale: &str) -> Self {
+        let mut parts = locale.split('-');
+        let lang = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts
+            .find(|p| p.len() == 2)
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let (lang, region) = (lang.as_str(), region.as_str());
+
+        let calendar = match (lang, region) {
+            ("th", _) => "buddhist",
+            ("fa", _) => "persian",
+            ("ar", "SA") => "islamic-umalqura",
+            _ => "gregory",
+        };
+        let numbering_system = match (lang, region) {
+            ("fa", _) => "arabext",
+            ("ar", "EG" | "SA") => "arab",
+            ("bn", _) => "beng",
+            ("mr" | "ne", _) => "deva",
+            ("my", _) => "mymr",
+            _ => "latn",
+        };
+        let twelve_hour = match lang {
+            "ar" | "ko" => true,
+            "fr" => false,
+            _ => TWELVE_HOUR_REGIONS.contains(&region),
+        };
+        let first_day_of_week = match region {
+            "US" | "CA" | "JP" | "KR" | "BR" | "IN" | "MX" | "IL" | "PH" | "HK" | "TW" | "ZA"
+            | "SG" | "TH" | "ID" | "SA" | "PT" => 7,
+            "AE" | "EG" => 6,
+            _ => 1,
+        };
+        let weekend = match region {
+            "AE" | "SA" | "EG" | "IL" => vec![5, 6],
+            "IN" => vec![7],
+            _ => vec![6, 7],
+        };
+        // ISO 8601 weeks (the first has four days) across most of Europe
+        let minimal_days = match region {
+            "DE" | "AT" | "CH" | "FR" | "BE" | "NL" | "ES" | "IT" | "GB" | "IE" | "SE" | "NO"
+            | "DK" | "FI" | "PL" | "CZ" | "SK" | "HU" | "RU" | "PT" => 4,
+            _ => 1,
+        };
+        let currency = region_currency(region);
+
+        Self {
+            calendar: calendar.into(),
+            numbering_system: numbering_system.into(),
+            hour_cycle: if twelve_hour { "h12" } else { "h23" }.into(),
+            first_day_of_week,
+            weekend,
+            minimal_days,
+            currency: currency.into(),
+            currency_sample: format_currency(lang, region, currency, SAMPLE_AMOUNT),
+        }
+    }
+}
+
+fn region_currency(region: &str) -> &'static str {
+    match region {
+        "US" => "USD",
+        "GB" => "GBP",
+        "CA" => "CAD",
+        "AU" => "AUD",
+        "NZ" => "NZD",
+        "DE" | "AT" | "FR" | "BE" | "NL" | "ES" | "IT" | "PT" | "IE" | "FI" | "GR" | "SK"
+        | "HR" => "EUR",
+        "CH" => "CHF",
+        "PL" => "PLN",
+        "SE" => "SEK",
+        "NO" => "NOK",
+        "DK" => "DKK",
+        "CZ" => "CZK",
+        "HU" => "HUF",
+        "RO" => "RON",
+        "TR" => "TRY",
+        "UA" => "UAH",
+        "RU" => "RUB",
+        "BR" => "BRL",
+        "MX" => "MXN",
+        "JP" => "JPY",
+        "KR" => "KRW",
+        "CN" => "CNY",
+        "TW" => "TWD",
+        "HK" => "HKD",
+        "SG" => "SGD",
+        "IN" => "INR",
+        "TH" => "THB",
+        "IL" => "ILS",
+        "ZA" => "ZAR",
+        _ => "USD",
+    }
+}
+
+/// The currency's symbol in its home locale; the ISO code where CLDR has
+/// none.
+fn currency_symbol(lang: &str, currency: &str) -> &'static str {
+    match currency {
+        "USD" | "CAD" | "AUD" | "NZD" | "SGD" | "MXN" | "TWD" => "$",
+        "HKD" => "HK$",
+        "GBP" => "£",
+        "EUR" => "€",
+        "JPY" if lang == "ja" => "￥",
+        "JPY" | "CNY" => "¥",
+        "KRW" => "₩",
+        "INR" => "₹",
+        "BRL" => "R$",
+        "PLN" => "zł",
+        "SEK" | "NOK" => "kr",
+        "DKK" => "kr.",
+        "CZK" => "Kč",
+        "TRY" => "₺",
+        "ILS" => "₪",
+        "RUB" => "₽",
+        "UAH" => "₴",
+        "THB" => "฿",
+        "CHF" => "CHF",
+        "HUF" => "Ft",
+        "RON" => "RON",
+        "ZAR" => "R",
+        _ => "USD",
+    }
+}
+
+/// How Chrome lays out `amount` in `currency` for the locale.
+fn format_currency(lang: &str, region: &str, currency: &str, amount: f64) -> String {
+    let (group, decimal) = match (lang, region) {
+        ("de", "CH") => ("'", "."),
+        ("es", "MX") => (",", "."),
+        ("fr", "CA") => (NBSP, ","),
+        ("fr", "CH") => (NNBSP, "."),
+        ("fr", _) => (NNBSP, ","),
+        ("pl" | "cs" | "sk" | "ru" | "uk" | "nb" | "sv" | "fi" | "hu" | "bg", _) => (NBSP, ","),
+        ("de" | "es" | "it" | "nl" | "pt" | "tr" | "id" | "da" | "ro" | "el" | "hr" | "vi", _) => {
+            (".", ",")
+        }
+        _ => (",", "."),
+    };
+    // Some languages only group from five digits on
+    let min_grouping = match (lang, region) {
+        ("es", "MX") => 1,
+        ("es" | "pl" | "it" | "pt" | "hu", _) if region != "BR" => 2,
+        _ => 1,
+    };
+    let fraction_digits = match (lang, currency) {
+        (_, "JPY" | "KRW") | ("hu", "HUF") => 0,
+        _ => 2,
+    };
+
+    let fixed = format!("{amount:.fraction_digits$}");
+    let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let grouped = if int.len() >= 4 + (min_grouping - 1) {
+        let mut out = String::new();
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                out.push_str(group);
+            }
+            out.push(c);
+        }
+        out
+    } else {
+        int.to_string()
+    };
+    let number = if frac.is_empty() {
+        grouped
+    } else {
+        format!("{grouped}{decimal}{frac}")
+    };
+
+    let symbol = currency_symbol(lang, currency);
+    match (lang, region) {
+        ("nl", _) | ("pt", "BR") | ("de", "CH" | "AT") => format!("{symbol}{NBSP}{number}"),
+        // Right-to-left marks keep the digits left of the symbol
+        ("he", _) => format!("\u{200f}{number}{NBSP}\u{200f}{symbol}"),
+        (
+            "de" | "fr" | "es" | "it" | "pl" | "cs" | "sk" | "ru" | "uk" | "nb" | "sv" | "fi"
+            | "hu" | "da" | "ro" | "el" | "hr" | "pt" | "bg" | "vi",
+            _,
+        ) if region != "MX" => format!("{number}{NBSP}{symbol}"),
+        _ => format!("{symbol}{number}"),
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_numbering_and_clock_follow_the_locale() {
+        assert_eq!(IntlProfile::for_locale("en-US").hour_cycle, "h12");
+        assert_eq!(IntlProfile::for_locale("en-GB").hour_cycle, "h23");
+        assert_eq!(IntlProfile::for_locale("fr-CA").hour_cycle, "h23");
+        let th = IntlProfile::for_locale("th-TH");
+        assert_eq!(
+            (th.calendar.as_str(), th.numbering_system.as_str()),
+            ("buddhist", "latn")
+        );
+        let fa = IntlProfile::for_locale("fa-IR");
+        assert_eq!(
+            (fa.calendar.as_str(), fa.numbering_system.as_str()),
+            ("persian", "arabext")
+        );
+    }
+
+    #[test]
+    fn week_info_follows_the_region() {
+        let us = IntlProfile::for_locale("en-US");
+        assert_eq!((us.first_day_of_week, us.minimal_days), (7, 1));
+        let de = IntlProfile::for_locale("de-DE");
+        assert_eq!((de.first_day_of_week, de.minimal_days), (1, 4));
+        assert_eq!(IntlProfile::for_locale("ar-AE").weekend, [5, 6]);
+        assert_eq!(IntlProfile::for_locale("en-IN").weekend, [7]);
+    }
+
+    #[test]
+    fn currency_samples_match_chrome() {
+        let sample = |l: &str| IntlProfile::for_locale(l).currency_sample;
+        assert_eq!(sample("en-US"), "$1,234.56");
+        assert_eq!(sample("en-GB"), "£1,234.56");
+        assert_eq!(sample("de-DE"), "1.234,56\u{a0}€");
+        assert_eq!(sample("fr-FR"), "1\u{202f}234,56\u{a0}€");
+        assert_eq!(sample("es-ES"), "1234,56\u{a0}€");
+        assert_eq!(sample("nl-NL"), "€\u{a0}1.234,56");
+        assert_eq!(sample("pt-BR"), "R$\u{a0}1.234,56");
+        assert_eq!(sample("ja-JP"), "￥1,235");
+        assert_eq!(sample("de-CH"), "CHF\u{a0}1'234.56");
+        assert_eq!(sample("fr-CA"), "1\u{a0}234,56\u{a0}$");
+        assert_eq!(sample("it-IT"), "1234,56\u{a0}€");
+        assert_eq!(IntlProfile::for_locale("ja-JP").currency, "JPY");
+    }
+}
@@ -0,0 +1,263 @@
+// ── Manifold bridge launch config ─────────────────────────────────────────────
+//
+// The JSON a bridge receives in `MANIFOLD_LAUNCH_CONFIG`.  It used to be built
+// ad hoc with `json!` and read on the other side with a bare `JSON.parse`, so
+// a misspelt or renamed field simply went missing.  The shape is now defined
+// here once, mirrored by `LaunchConfig` in playwright-bridge/types.ts, and
+// carries the bridge protocol revision it was written for.  Configs are
+// checked before spawn, and the bridge rejects keys it doesn't know.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bridge_locator::BRIDGE_PROTOCOL;
+use crate::engine_quirks::EngineQuirks;
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::WindowPlacement;
+use crate::header_order::HeaderOrderProfile;
+use crate::launch_mode::LaunchMode;
+use crate::preflight::PreflightPlan;
+use crate::profile::Profile;
+use crate::request_headers::RequestHeaderProfile;
+
+/// Playwright `proxy` launch option.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyConfig {
+    pub server: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    /// A local endpoint (VPN tunnel, chain forwarder) without credentials.
+    pub fn local(server: String) -> Self {
+        Self {
+            server,
+            username: None,
+            password: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LaunchConfig {
+    /// `BRIDGE_PROTOCOL` of the backend that wrote the config.
+    pub protocol: u32,
+    pub profile: Profile,
+    pub proxy: Option<ProxyConfig>,
+    pub url: String,
+    pub ws_port: u16,
+    /// Address the WebSocket listens on; loopback when absent.  Only agent
+    /// nodes that expose their bridge set it (`nodes`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_host: Option<String>,
+    /// Serve wss:// with the certificate the node provides; required when
+    /// `ws_host` is off loopback.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ws_tls: bool,
+    /// This launch's WebSocket secret; clients connect with tickets signed
+    /// by it (`bridge_auth`).  Absent only for dev runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_secret: Option<String>,
+    /// TLS bridge port when `profile.tls_bridge` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_bridge_port: Option<u16>,
+    /// Additional Chromium flags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+    /// Chrome's request header order/casing for the profile's major.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_order: Option<HeaderOrderProfile>,
+    /// Chrome's Accept / Accept-Encoding / fetch metadata values for the
+    /// profile's major.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_headers: Option<RequestHeaderProfile>,
+    /// Engine values the page should show for the profile's major and locale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine_quirks: Option<EngineQuirks>,
+    /// Probe for leaks, print a `LEAK_PROBE` line and exit.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub leak_probe: bool,
+    /// Run the leak probe, check `plan` on its page, print a `PREFLIGHT`
+    /// line and exit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight: Option<PreflightPlan>,
+    /// Take workflow steps on stdin, answer each with a `STEP_RESULT` line
+    /// and exit when stdin closes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub workflow: bool,
+    /// Take control commands (the profile's clipboard) on stdin, each
+    /// answered by a result line.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub control: bool,
+    /// How the browser is shown, as planned for this launch.
+    #[serde(default)]
+    pub launch_mode: LaunchMode,
+    /// The X display a `virtual_display` browser opens on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    /// Where the window opens this session (profiles with a screen layout).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowPlacement>,
+}
+
+impl LaunchConfig {
+    /// Check the config is one this build's bridge can run.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(ManifoldError::InvalidArg(format!("launch config: {msg}")));
+        if self.protocol != BRIDGE_PROTOCOL {
+            return invalid(format!(
+                "protocol {} (this build speaks {BRIDGE_PROTOCOL})",
+                self.protocol
+            ));
+        }
+        if self.url != "about:blank"
+            && !self.url.starts_with("http://")
+            && !self.url.starts_with("https://")
+        {
+            return invalid(format!("unsupported start url {:?}", self.url));
+        }
+        if self.ws_port == 0 {
+            return invalid("wsPort is 0".into());
+        }
+        if let Some(host) = &self.ws_host {
+            let Ok(ip) = host.parse::<std::net::IpAddr>() else {
+                return invalid(format!("wsHost {host:?} is not an IP address"));
+            };
+            // The ticket and the session would cross the network in the clear
+            if !ip.is_loopback() && !self.ws_tls {
+                return invalid(format!("wsHost {host} needs wsTls"));
+            }
+        }
+        if let Some(secret) = &self.ws_secret {
+            if !crate::bridge_auth::is_valid_secret(secret) {
+                return invalid("wsSecret is not a launch secret".into());
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            let scheme = proxy.server.split("://").next().unwrap_or_default();
+            if !matches!(scheme, "http" | "https" | "socks5") || !proxy.server.contains("://") {
+                return invalid(format!("unsupported proxy server {:?}", proxy.server));
+            }
+        }
+        if let Some(arg) = self.extra_args.iter().find(|a| !a.starts_with("--")) {
+            return invalid(format!("extra arg {arg:?} is not a flag"));
+        }
+        if self.profile.tls_bridge.unwrap_or(false) != self.tls_bridge_port.is_some() {
+            return invalid("tlsBridgePort must be set exactly when tls_bridge is on".into());
+        }
+        Ok(())
+    }
+
+    /// Validate and serialise for `MANIFOLD_LAUNCH_CONFIG`.
+    pub fn to_env_json(&self) -> Result<String> {
+        self.validate()?;
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+
+    fn config() -> LaunchConfig {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = ProfileRepo::new_with_root(Db::open_in_memory().unwrap(), dir.path().into());
+        let profile = repo
+            .create(CreateProfileRequest {
+                name: "cfg".into(),
+                seed: Some(3),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                persona: None,
+            })
+            .unwrap();
+        LaunchConfig {
+            protocol: BRIDGE_PROTOCOL,
+            header_order: Some(crate::header_order::chrome_header_order(
+                &profile.fingerprint,
+            )),
+            request_headers: Some(crate::request_headers::request_header_profile(
+                &profile.fingerprint,
+            )),
+            engine_quirks: Some(crate::engine_quirks::engine_quirks(&profile.fingerprint)),
+            profile,
+            proxy: Some(ProxyConfig {
+                server: "socks5://127.0.0.1:1080".into(),
+                username: None,
+                password: None,
+            }),
+            url: "https://example.com".into(),
+            ws_port: 8766,
+            ws_host: None,
+            ws_tls: false,
+            ws_secret: Some(crate::bridge_auth::new_secret()),
+            tls_bridge_port: None,
+            extra_args: Vec::new(),
+            leak_probe: false,
+            preflight: None,
+            workflow: false,
+            control: false,
+            launch_mode: LaunchMode::HeadlessNew,
+            display: None,
+            window: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_camel_case_json() {
+        let json = config().to_env_json().unwrap();
+        assert!(json.contains("\"wsPort\":8766"));
+        assert!(json.contains("\"headerOrder\""));
+        assert!(json.contains("\"requestHeaders\""));
+        assert!(json.contains("\"engineQuirks\""));
+        assert!(json.contains("\"wsSecret\""));
+        assert!(!json.contains("leakProbe"));
+        let back: LaunchConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.proxy, config().proxy);
+    }
+
+    #[test]
+    fn unknown_keys_and_protocol_mismatch_fail() {
+        let mut value = serde_json::to_value(config()).unwrap();
+        value["wsPrt"] = 8766.into();
+        let err = serde_json::from_value::<LaunchConfig>(value).unwrap_err();
+        assert!(err.to_string().contains("wsPrt"), "{err}");
+
+        let mut stale = config();
+        stale.protocol = BRIDGE_PROTOCOL + 1;
+        assert!(stale.validate().is_err());
+
+        let mut guessable = config();
+        guessable.ws_secret = Some("secret".into());
+        assert!(guessable.validate().is_err());
+
+        let mut exposed = config();
+        exposed.ws_host = Some("0.0.0.0".into());
+        assert!(exposed.validate().is_err(), "plain ws:// off loopback");
+        exposed.ws_tls = true;
+        assert!(exposed.validate().is_ok());
+        assert!(exposed.to_env_json().unwrap().contains("\"wsTls\":true"));
+    }
+
+    #[test]
+    fn tls_bridge_port_follows_profile_flag() {
+        let mut c = config();
+        c.profile.tls_bridge = Some(true);
+        assert!(c.validate().is_err());
+        c.tls_bridge_port = Some(8443);
+        c.extra_args = vec!["--disable-quic".into()];
+        assert!(c.validate().is_ok());
+        c.extra_args.push("disable-http2".into());
+        assert!(c.validate().is_err());
+    }
+}
@@ -0,0 +1,268 @@
+// ── Manifold launch conflicts ─────────────────────────────────────────────────
+//
+// Some profiles must never be online together: two accounts on the same
+// target site that share a household persona, or a main account and its
+// recovery account.  Sites link accounts that are active at the same time
+// from related devices, and switching straight from one to the other
+// without a stop in between is the same signal.
+//
+// A conflict is a symmetric rule between two profiles.  Launching a profile
+// is refused while any profile it conflicts with is Running or Suspended
+// (a suspended bridge comes back on wake); the other profile has to be
+// stopped first, explicitly, rather than being replaced by the launch.
+
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::profile::ProfileStatus;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// A stored rule, seen from one of its two profiles.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictRule {
+    pub profile_id: String,
+    pub other_id: String,
+    pub other_name: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A rule that blocks a launch right now.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchConflict {
+    pub other_id: String,
+    pub other_name: String,
+    pub other_status: ProfileStatus,
+    pub reason: String,
+}
+
+/// A rule joined with the other profile's current row.
+struct RuleRow {
+    other_id: String,
+    other_name: String,
+    other_status: String,
+    reason: String,
+    created_at: String,
+}
+
+/// Rules are stored once, with the smaller id first.
+fn ordered<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct ConflictRepo {
+    db: Db,
+}
+
+impl ConflictRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Forbid `a` and `b` from being online together.  Adding an existing
+    /// rule updates its reason.
+    pub fn add(&self, a: &str, b: &str, reason: &str) -> Result<()> {
+        if a == b {
+            return Err(ManifoldError::InvalidArg(
+                "a profile can't conflict with itself".into(),
+            ));
+        }
+        let (first, second) = ordered(a, b);
+        self.db.with_conn(|conn| {
+            for id in [first, second] {
+                let exists: bool = conn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM profiles WHERE id = ?1)",
+                    params![id],
+                    |r| r.get(0),
+                )?;
+                if !exists {
+                    return Err(ManifoldError::ProfileNotFound(id.into()));
+                }
+            }
+            conn.execute(
+                r#"INSERT INTO profile_conflicts (profile_a, profile_b, reason, created_at)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT(profile_a, profile_b) DO UPDATE SET reason = excluded.reason"#,
+                params![first, second, reason, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Drop the rule between `a` and `b`.  Returns whether there was one.
+    pub fn remove(&self, a: &str, b: &str) -> Result<bool> {
+        let (first, second) = ordered(a, b);
+        self.db.with_conn(|conn| {
+            let n = conn.execute(
+                "DELETE FROM profile_conflicts WHERE profile_a = ?1 AND profile_b = ?2",
+                params![first, second],
+            )?;
+            Ok(n > 0)
+        })
+    }
+
+    /// Every rule involving `profile_id`, by the other profile's name.
+    pub fn rules_for(&self, profile_id: &str) -> Result<Vec<ConflictRule>> {
+        Ok(self
+            .query(profile_id)?
+            .into_iter()
+            .map(|row| ConflictRule {
+                profile_id: profile_id.into(),
+                other_id: row.other_id,
+                other_name: row.other_name,
+                reason: row.reason,
+                created_at: DateTime::parse_from_rfc3339(&row.created_at)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            })
+            .collect())
+    }
+
+    /// The rules that forbid launching `profile_id` now: those whose other
+    /// profile is Running or Suspended.
+    pub fn check(&self, profile_id: &str) -> Result<Vec<LaunchConflict>> {
+        Ok(self
+            .query(profile_id)?
+            .into_iter()
+            .filter_map(|row| {
+                let other_status: ProfileStatus = row.other_status.parse().ok()?;
+                matches!(
+                    other_status,
+                    ProfileStatus::Running | ProfileStatus::Suspended
+                )
+                .then_some(LaunchConflict {
+                    other_id: row.other_id,
+                    other_name: row.other_name,
+                    other_status,
+                    reason: row.reason,
+                })
+            })
+            .collect())
+    }
+
+    /// Fail with the blocking rules if `profile_id` can't be launched now.
+    pub fn ensure_launchable(&self, profile_id: &str) -> Result<()> {
+        let conflicts = self.check(profile_id)?;
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<String> = conflicts
+            .iter()
+            .map(|c| format!("{} ({})", c.other_name, c.other_status))
+            .collect();
+        Err(ManifoldError::InvalidArg(format!(
+            "profile must not run at the same time as {}",
+            names.join(", ")
+        )))
+    }
+
+    fn query(&self, profile_id: &str) -> Result<Vec<RuleRow>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT p.id, p.name, p.status, c.reason, c.created_at
+                   FROM profile_conflicts c
+                   JOIN profiles p ON p.id = CASE WHEN c.profile_a = ?1
+                                                  THEN c.profile_b ELSE c.profile_a END
+                   WHERE c.profile_a = ?1 OR c.profile_b = ?1
+                   ORDER BY p.name"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id], |r| {
+                    Ok(RuleRow {
+                        other_id: r.get(0)?,
+                        other_name: r.get(1)?,
+                        other_status: r.get(2)?,
+                        reason: r.get(3)?,
+                        created_at: r.get(4)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_repo() -> ConflictRepo {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            for (id, name) in [("p1", "shop-a"), ("p2", "shop-b"), ("p3", "other")] {
+                conn.execute(
+                    "INSERT INTO profiles (id, name, fingerprint_json, created_at) VALUES (?1, ?2, '{}', '2025-01-01T00:00:00+00:00')",
+                    params![id, name],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        ConflictRepo::new(db)
+    }
+
+    fn set_status(repo: &ConflictRepo, id: &str, status: &str) {
+        repo.db
+            .with_conn(|conn| {
+                conn.execute(
+                    "UPDATE profiles SET status = ?1 WHERE id = ?2",
+                    params![status, id],
+                )?;
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn rules_are_symmetric_and_block_while_the_other_is_online() {
+        let repo = make_repo();
+        repo.add("p2", "p1", "same household, same shop").unwrap();
+        assert_eq!(repo.rules_for("p1").unwrap()[0].other_name, "shop-b");
+        assert_eq!(repo.rules_for("p2").unwrap()[0].other_id, "p1");
+        assert!(repo.rules_for("p3").unwrap().is_empty());
+
+        assert!(repo.check("p1").unwrap().is_empty());
+        set_status(&repo, "p2", "running");
+        let blocking = repo.check("p1").unwrap();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].other_status, ProfileStatus::Running);
+        assert!(repo.ensure_launchable("p1").is_err());
+        assert!(repo.ensure_launchable("p3").is_ok());
+
+        set_status(&repo, "p2", "suspended");
+        assert!(repo.ensure_launchable("p1").is_err());
+        set_status(&repo, "p2", "idle");
+        assert!(repo.ensure_launchable("p1").is_ok());
+    }
+
+    #[test]
+    fn add_validates_and_remove_drops_the_rule() {
+        let repo = make_repo();
+        assert!(repo.add("p1", "p1", "").is_err());
+        assert!(matches!(
+            repo.add("p1", "nope", ""),
+            Err(ManifoldError::ProfileNotFound(_))
+        ));
+        repo.add("p1", "p2", "first").unwrap();
+        repo.add("p2", "p1", "updated").unwrap();
+        let rules = repo.rules_for("p1").unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].reason, "updated");
+
+        assert!(repo.remove("p2", "p1").unwrap());
+        assert!(!repo.remove("p1", "p2").unwrap());
+        assert!(repo.rules_for("p1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+// ── Manifold bridge launch environment ────────────────────────────────────────
+//
+// A profile's bridge used to inherit Manifold's whole environment and working
+// directory, so a launch behaved differently depending on the shell Manifold
+// was started from (proxy variables, NODE_OPTIONS, a stray TZ …).  Launches
+// now start from an empty environment: only an allowlist of variables Node and
+// Chromium need is copied from the host, the profile's own variables are laid
+// on top, and PATH is rebuilt around a resolved Node install.  The bridge runs
+// in a working directory of its own inside the profile directory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+
+/// `settings` key holding the app-wide `LaunchEnvSettings` (JSON).
+pub const LAUNCH_ENV_KEY: &str = "launch_env";
+
+/// Working directory of the bridge, inside the profile directory.
+pub const WORK_DIR_NAME: &str = "bridge-cwd";
+
+/// Host variables every launch keeps: user/home and temp dirs (Playwright
+/// finds its browsers through them), the display server, and the Windows
+/// system locations Node and Chromium can't start without.
+pub const BASE_ALLOWLIST: &[&str] = &[
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "TMPDIR",
+    "DISPLAY",
+    "WAYLAND_DISPLAY",
+    "XAUTHORITY",
+    "XDG_RUNTIME_DIR",
+    "DBUS_SESSION_BUS_ADDRESS",
+    "PLAYWRIGHT_BROWSERS_PATH",
+    "SystemRoot",
+    "SystemDrive",
+    "windir",
+    "ComSpec",
+    "PATHEXT",
+    "TEMP",
+    "TMP",
+    "USERNAME",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "ProgramData",
+    "ProgramFiles",
+    "ProgramFiles(x86)",
+];
+
+/// Directories kept on PATH after the Node install, so `npx` can still
+/// find the shell it spawns.
+#[cfg(windows)]
+const SYSTEM_PATH: &[&str] = &[r"C:\Windows\System32", r"C:\Windows"];
+#[cfg(not(windows))]
+const SYSTEM_PATH: &[&str] = &["/usr/local/bin", "/usr/bin", "/bin"];
+
+#[cfg(windows)]
+const NPX: &str = "npx.cmd";
+#[cfg(not(windows))]
+const NPX: &str = "npx";
+
+/// App-wide launch environment settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LaunchEnvSettings {
+    /// Host variables to pass through on top of `BASE_ALLOWLIST`.
+    pub allowlist: Vec<String>,
+    /// Directory holding `node` / `npx`.  `None` searches the host PATH.
+    pub node_dir: Option<String>,
+}
+
+impl LaunchEnvSettings {
+    pub fn validate(&self) -> Result<()> {
+        for key in &self.allowlist {
+            if key.is_empty() || key.contains('=') {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "invalid variable name {key:?}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The Node install to launch with: the configured directory, or the
+    /// first host PATH entry that has `npx`.
+    pub fn resolve_node_dir(&self) -> Option<PathBuf> {
+        match &self.node_dir {
+            Some(dir) => Some(PathBuf::from(dir)).filter(|d| d.join(NPX).is_file()),
+            None => std::env::var_os("PATH")
+                .and_then(|path| std::env::split_paths(&path).find(|d| d.join(NPX).is_file())),
+        }
+    }
+}
+
+/// `npx` inside `node_dir`.
+pub fn npx_path(node_dir: &Path) -> PathBuf {
+    node_dir.join(NPX)
+}
+
+/// The environment a bridge starts with: allowlisted `host` variables, PATH
+/// rebuilt as `node_dir` + system dirs, then the profile's own variables
+/// (which may override anything, PATH included).
+pub fn build_env(
+    host: impl IntoIterator<Item = (String, String)>,
+    settings: &LaunchEnvSettings,
+    node_dir: Option<&Path>,
+    profile_env: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    // Windows variable names are case-insensitive
+    let allowed = |key: &str| {
+        BASE_ALLOWLIST
+            .iter()
+            .copied()
+            .chain(settings.allowlist.iter().map(String::as_str))
+            .any(|a| {
+                if cfg!(windows) {
+                    a.eq_ignore_ascii_case(key)
+                } else {
+                    a == key
+                }
+            })
+    };
+    let mut env: BTreeMap<String, String> = host.into_iter().filter(|(k, _)| allowed(k)).collect();
+
+    let path_dirs = node_dir
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(SYSTEM_PATH.iter().map(PathBuf::from));
+    if let Ok(path) = std::env::join_paths(path_dirs) {
+        env.insert("PATH".into(), path.to_string_lossy().into_owned());
+    }
+
+    env.extend(profile_env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    env
+}
+
+/// Reject variable names a process environment can't hold.
+pub fn validate_env(env: &BTreeMap<String, String>) -> Result<()> {
+    for (key, value) in env {
+        if key.is_empty() || key.contains('=') || key.contains('\0') || value.contains('\0') {
+            return Err(ManifoldError::InvalidArg(format!(
+                "invalid environment variable {key:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> Vec<(String, String)> {
+        [
+            ("HOME", "/home/u"),
+            ("HTTPS_PROXY", "http://corp:3128"),
+            ("NODE_OPTIONS", "--inspect"),
+            ("PATH", "/opt/junk/bin"),
+            ("TZ", "Asia/Tokyo"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn scrubs_everything_off_the_allowlist() {
+        let env = build_env(
+            host(),
+            &LaunchEnvSettings::default(),
+            None,
+            &BTreeMap::new(),
+        );
+        assert_eq!(env.get("HOME").map(String::as_str), Some("/home/u"));
+        assert!(!env.contains_key("HTTPS_PROXY"));
+        assert!(!env.contains_key("NODE_OPTIONS"));
+        assert!(!env.contains_key("TZ"));
+        assert!(!env["PATH"].contains("junk"));
+    }
+
+    #[test]
+    fn path_starts_with_node_dir_and_profile_env_wins() {
+        let settings = LaunchEnvSettings {
+            allowlist: vec!["HTTPS_PROXY".into()],
+            node_dir: None,
+        };
+        let profile_env = BTreeMap::from([("HOME".to_string(), "/srv/p1".to_string())]);
+        let node = PathBuf::from("/opt/node/bin");
+        let env = build_env(host(), &settings, Some(&node), &profile_env);
+        assert!(env["PATH"].starts_with("/opt/node/bin"));
+        assert_eq!(env["HTTPS_PROXY"], "http://corp:3128");
+        assert_eq!(env["HOME"], "/srv/p1");
+    }
+
+    #[test]
+    fn configured_node_dir_without_npx_is_not_used() {
+        let settings = LaunchEnvSettings {
+            allowlist: Vec::new(),
+            node_dir: Some("/definitely/not/node".into()),
+        };
+        assert!(settings.resolve_node_dir().is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_variable_names() {
+        let bad = BTreeMap::from([("A=B".to_string(), "x".to_string())]);
+        assert!(validate_env(&bad).is_err());
+        let ok = BTreeMap::from([("LANG".to_string(), "en_US.UTF-8".to_string())]);
+        assert!(validate_env(&ok).is_ok());
+    }
+}
@@ -0,0 +1,393 @@
+// ── Manifold launch modes ─────────────────────────────────────────────────────
+//
+// How a profile's browser is shown decides part of what it gives away:
+//
+//   headed           a normal window on the host's display; the most real,
+//                    but it needs a display and takes over the screen
+//   headless_new     Chrome's new headless mode (the full browser without a
+//                    window); no display needed, but no GPU either and the
+//                    window geometry is synthetic
+//   virtual_display  a headed browser on an Xvfb display sized to the
+//                    profile's screen (Linux); real window management, no GPU
+//
+// Each profile has a `LaunchPolicy`: the mode it wants and the modes to fall
+// back to, in order, when the host can't provide it.  The plan is made at
+// launch from what the host offers, carries the detection risks of the mode
+// it settles on and goes to the bridge in the launch config; the mode is
+// recorded on the session.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::Fingerprint;
+use crate::gpu_probe::{tier_of, GpuTier};
+
+/// First X display number tried for a virtual display.
+const FIRST_VIRTUAL_DISPLAY: u32 = 99;
+/// Display numbers tried before giving up.
+const VIRTUAL_DISPLAY_SLOTS: u32 = 100;
+/// How long Xvfb gets to open its socket.
+const XVFB_READY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMode {
+    Headed,
+    #[default]
+    HeadlessNew,
+    VirtualDisplay,
+}
+
+impl LaunchMode {
+    /// What a detector can still see in this mode.
+    pub fn risk(self) -> Option<&'static str> {
+        match self {
+            LaunchMode::Headed => None,
+            LaunchMode::HeadlessNew => Some(
+                "headless: window and screen geometry are synthetic and there is no GPU, \
+                 so outer window size and rendering timings can give it away",
+            ),
+            LaunchMode::VirtualDisplay => Some(
+                "virtual display: Xvfb has no GPU, and its fixed screen shows no taskbar \
+                 or window decorations of the claimed OS",
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for LaunchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LaunchMode::Headed => "headed",
+            LaunchMode::HeadlessNew => "headless_new",
+            LaunchMode::VirtualDisplay => "virtual_display",
+        })
+    }
+}
+
+impl std::str::FromStr for LaunchMode {
+    type Err = ManifoldError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "headed" => Ok(LaunchMode::Headed),
+            "headless_new" => Ok(LaunchMode::HeadlessNew),
+            "virtual_display" => Ok(LaunchMode::VirtualDisplay),
+            _ => Err(ManifoldError::InvalidArg(format!(
+                "unknown launch mode {s:?}"
+            ))),
+        }
+    }
+}
+
+/// The mode a profile wants and what to fall back to, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LaunchPolicy {
+    pub mode: LaunchMode,
+    pub fallback: Vec<LaunchMode>,
+}
+
+impl LaunchPolicy {
+    pub fn validate(&self) -> Result<()> {
+        let order = self.order();
+        if order.len() != self.fallback.len() + 1 {
+            return Err(ManifoldError::InvalidArg(
+                "a launch mode appears more than once in the policy".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The modes to try, preferred first.
+    pub fn order(&self) -> Vec<LaunchMode> {
+        let mut order = vec![self.mode];
+        for mode in &self.fallback {
+            if !order.contains(mode) {
+                order.push(*mode);
+            }
+        }
+        order
+    }
+}
+
+/// What the host offers for showing a browser.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostDisplay {
+    pub os: String,
+    /// The display headed browsers open on (`DISPLAY` / `WAYLAND_DISPLAY`
+    /// on Linux; the desktop elsewhere).
+    pub display: Option<String>,
+    /// The Xvfb binary, when installed.
+    pub xvfb: Option<PathBuf>,
+}
+
+impl HostDisplay {
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let os = std::env::consts::OS;
+        let display = if os == "linux" {
+            var("DISPLAY").or_else(|| var("WAYLAND_DISPLAY"))
+        } else {
+            Some("desktop".into())
+        };
+        let xvfb = (os == "linux").then(|| find_in_path("Xvfb")).flatten();
+        Self {
+            os: os.into(),
+            display,
+            xvfb,
+        }
+    }
+
+    /// Why the host can't run `mode`, or `None` when it can.
+    pub fn unavailable(&self, mode: LaunchMode) -> Option<String> {
+        match mode {
+            LaunchMode::HeadlessNew => None,
+            LaunchMode::Headed if self.display.is_none() => {
+                Some("no display (DISPLAY / WAYLAND_DISPLAY unset)".into())
+            }
+            LaunchMode::Headed => None,
+            LaunchMode::VirtualDisplay if self.os != "linux" => {
+                Some("virtual displays need Linux".into())
+            }
+            LaunchMode::VirtualDisplay if self.xvfb.is_none() => {
+                Some("Xvfb is not installed".into())
+            }
+            LaunchMode::VirtualDisplay => None,
+        }
+    }
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+/// A mode passed over, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedMode {
+    pub mode: LaunchMode,
+    pub reason: String,
+}
+
+/// The mode a launch settled on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchPlan {
+    pub mode: LaunchMode,
+    /// Preferred modes the host couldn't provide, in policy order.
+    pub skipped: Vec<SkippedMode>,
+    /// Detection risks of the chosen mode for this profile.
+    pub warnings: Vec<String>,
+}
+
+// ── Planning ──────────────────────────────────────────────────────────────────
+
+/// Pick the first mode of `policy` the host can run.  A virtual display is
+/// started through `start_display` and, if that fails, passed over like a
+/// mode the host lacks.
+pub fn plan<D>(
+    policy: &LaunchPolicy,
+    host: &HostDisplay,
+    fp: &Fingerprint,
+    mut start_display: impl FnMut() -> Result<D>,
+) -> Result<(LaunchPlan, Option<D>)> {
+    let mut skipped = Vec::new();
+    for mode in policy.order() {
+        if let Some(reason) = host.unavailable(mode) {
+            skipped.push(SkippedMode { mode, reason });
+            continue;
+        }
+        let display = if mode == LaunchMode::VirtualDisplay {
+            match start_display() {
+                Ok(display) => Some(display),
+                Err(e) => {
+                    skipped.push(SkippedMode {
+                        mode,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+        let plan = LaunchPlan {
+            mode,
+            warnings: warnings(mode, fp),
+            skipped,
+        };
+        return Ok((plan, display));
+    }
+    let reasons: Vec<String> = skipped
+        .iter()
+        .map(|s| format!("{} ({})", s.mode, s.reason))
+        .collect();
+    Err(ManifoldError::InvalidArg(format!(
+        "no launch mode of the profile's policy can run here: {}",
+        reasons.join(", ")
+    )))
+}
+
+fn warnings(mode: LaunchMode, fp: &Fingerprint) -> Vec<String> {
+    let mut warnings: Vec<String> = mode.risk().into_iter().map(String::from).collect();
+    let hardware_gpu = tier_of(&fp.webgl_renderer).is_some_and(|t| t != GpuTier::Software);
+    if mode != LaunchMode::Headed && hardware_gpu {
+        warnings.push(format!(
+            "WebGL renders in software in this mode, unlike the claimed {}",
+            fp.webgl_renderer
+        ));
+    }
+    warnings
+}
+
+// ── Virtual display ───────────────────────────────────────────────────────────
+
+/// An Xvfb server, stopped on drop.
+pub struct VirtualDisplay {
+    child: Child,
+    display: String,
+}
+
+impl VirtualDisplay {
+    /// Start Xvfb on the first free display number with a screen of
+    /// `width`×`height`.
+    pub fn start(xvfb: &Path, width: u32, height: u32) -> Result<Self> {
+        let number = (FIRST_VIRTUAL_DISPLAY..FIRST_VIRTUAL_DISPLAY + VIRTUAL_DISPLAY_SLOTS)
+            .find(|n| !Path::new(&format!("/tmp/.X{n}-lock")).exists())
+            .ok_or_else(|| ManifoldError::Other("no free X display number".into()))?;
+        let display = format!(":{number}");
+        let mut child = Command::new(xvfb)
+            .arg(&display)
+            .args(["-screen", "0", &format!("{width}x{height}x24")])
+            .args(["-nolisten", "tcp"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ManifoldError::spawn("Xvfb", e))?;
+
+        let socket = PathBuf::from(format!("/tmp/.X11-unix/X{number}"));
+        let deadline = Instant::now() + XVFB_READY_TIMEOUT;
+        while !socket.exists() {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(ManifoldError::Other(format!("Xvfb exited: {status}")));
+            }
+            if Instant::now() >= deadline {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(ManifoldError::Other("Xvfb did not start in time".into()));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Ok(Self { child, display })
+    }
+
+    /// The `DISPLAY` value, e.g. `:99`.
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+}
+
+impl Drop for VirtualDisplay {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    fn fingerprint(renderer: &str) -> Fingerprint {
+        let mut fp = FingerprintOrchestrator::generate(3);
+        fp.webgl_renderer = renderer.into();
+        fp
+    }
+
+    #[test]
+    fn falls_back_in_policy_order() {
+        let policy = LaunchPolicy {
+            mode: LaunchMode::Headed,
+            fallback: vec![LaunchMode::VirtualDisplay, LaunchMode::HeadlessNew],
+        };
+        policy.validate().unwrap();
+        let server = HostDisplay {
+            os: "linux".into(),
+            display: None,
+            xvfb: Some("/usr/bin/Xvfb".into()),
+        };
+        let fp =
+            fingerprint("ANGLE (NVIDIA, NVIDIA GeForce RTX 3060 Direct3D11 vs_5_0 ps_5_0, D3D11)");
+
+        // Xvfb is there but fails to start: on to headless
+        let (plan, display) = super::plan(&policy, &server, &fp, || -> Result<()> {
+            Err(ManifoldError::Other("Xvfb exited: 1".into()))
+        })
+        .unwrap();
+        assert_eq!(plan.mode, LaunchMode::HeadlessNew);
+        assert!(display.is_none());
+        let skipped: Vec<_> = plan.skipped.iter().map(|s| s.mode).collect();
+        assert_eq!(skipped, [LaunchMode::Headed, LaunchMode::VirtualDisplay]);
+        assert_eq!(plan.warnings.len(), 2);
+
+        let (plan, display) = super::plan(&policy, &server, &fp, || Ok(":99")).unwrap();
+        assert_eq!(
+            (plan.mode, display),
+            (LaunchMode::VirtualDisplay, Some(":99"))
+        );
+
+        // A desktop runs the preferred mode without warnings
+        let desktop = HostDisplay {
+            os: "windows".into(),
+            display: Some("desktop".into()),
+            xvfb: None,
+        };
+        let (plan, _) = super::plan(&policy, &desktop, &fp, || Ok(())).unwrap();
+        assert_eq!(plan.mode, LaunchMode::Headed);
+        assert!(plan.skipped.is_empty() && plan.warnings.is_empty());
+    }
+
+    #[test]
+    fn refuses_when_nothing_can_run() {
+        let policy = LaunchPolicy {
+            mode: LaunchMode::VirtualDisplay,
+            fallback: vec![LaunchMode::Headed],
+        };
+        let host = HostDisplay {
+            os: "macos".into(),
+            display: None,
+            xvfb: None,
+        };
+        let err = super::plan(
+            &policy,
+            &host,
+            &fingerprint("Google SwiftShader"),
+            || Ok(()),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("virtual_display (virtual displays need Linux)"));
+
+        let repeated = LaunchPolicy {
+            mode: LaunchMode::Headed,
+            fallback: vec![LaunchMode::Headed],
+        };
+        assert!(repeated.validate().is_err());
+        assert_eq!(
+            "headless_new".parse::<LaunchMode>().unwrap(),
+            LaunchMode::HeadlessNew
+        );
+    }
+}
@@ -0,0 +1,587 @@
+// ── Manifold profile leak test ────────────────────────────────────────────────
+//
+// Launches a profile exactly as `launch_profile` would, but with the bridge in
+// probe mode: instead of serving the live view it reads back what the page can
+// see (WebRTC candidates, exit IP and its geo, the DNS resolver's geo, the
+// effective timezone, UA and UA-CH, JS engine values) and exits.  The observations are checked
+// against the profile and its proxy here, and the resulting pass/fail report
+// is stored with the profile — one report per profile, the latest run.
+// The exit's measured region and city also become the proxy's region
+// (`measured_region`) unless the user set one, which pins the timezone geo
+// enforcement picks.  A rotating exit moves it with each run.
+//
+// Probe-mode output is a single stdout line: `LEAK_PROBE {json}`.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::engine_quirks::{engine_quirks, EngineObservation};
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::{chrome_version, region_timezone, Fingerprint, UaBrand};
+use crate::proxy::Ipv6LeakReport;
+
+/// Prefix of the stdout line carrying the bridge's observations.
+pub const PROBE_LINE_PREFIX: &str = "LEAK_PROBE ";
+
+/// How long the bridge gets to launch, probe and report.
+pub const PROBE_TIMEOUT_SECS: u64 = 90;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What the page saw, as reported by the bridge in probe mode.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LeakProbe {
+    /// Addresses found in gathered ICE candidates (mDNS names excluded).
+    pub webrtc_ips: Vec<String>,
+    /// Public IP the page's traffic leaves from, and its geo.
+    pub exit_ip: Option<String>,
+    pub exit_country: Option<String>,
+    pub exit_country_code: Option<String>,
+    /// UTC offset of the exit IP's timezone, in seconds east of UTC.
+    pub exit_utc_offset_secs: Option<i32>,
+    /// Region code ("UT"), region name and city of the exit IP.
+    pub exit_region: Option<String>,
+    pub exit_region_name: Option<String>,
+    pub exit_city: Option<String>,
+    /// Resolver that looked up the page's hostnames, and its country name.
+    pub resolver_ip: Option<String>,
+    pub resolver_country: Option<String>,
+    /// `Intl.DateTimeFormat().resolvedOptions().timeZone`
+    pub timezone: String,
+    /// `-new Date().getTimezoneOffset() * 60`
+    pub utc_offset_secs: i32,
+    pub user_agent: String,
+    pub ua_brands: Vec<UaBrand>,
+    pub ua_platform: String,
+    /// `browser.version()` — the Chromium build actually running.
+    pub browser_version: String,
+    /// Math, stack, builtin and Intl values read in the page.  `None` from
+    /// bridges that predate the engine check.
+    pub engine: Option<EngineObservation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// The probe couldn't observe what the check needs.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakCheck {
+    /// webrtc | dns | ipv6 | timezone | ua_consistency | engine
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl LeakCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakTestReport {
+    pub profile_id: String,
+    /// No check failed.  Skipped checks don't fail the report.
+    pub passed: bool,
+    pub exit_ip: Option<String>,
+    pub exit_country_code: Option<String>,
+    pub checks: Vec<LeakCheck>,
+    pub ran_at: DateTime<Utc>,
+}
+
+// ── Evaluation ────────────────────────────────────────────────────────────────
+
+/// Pull the probe out of the bridge's stdout line, if this is that line.
+pub fn parse_probe_line(line: &str) -> Option<Result<LeakProbe>> {
+    let json = line.trim().strip_prefix(PROBE_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).map_err(ManifoldError::from))
+}
+
+/// Check the probe against the profile.  `ipv6` is the proxy's IPv6 leak
+/// report (`None` without a single proxy); `tls_bridge` says whether the
+/// ClientHello comes from the bridge rather than Chromium itself.
+pub fn evaluate(
+    profile_id: &str,
+    fp: &Fingerprint,
+    probe: &LeakProbe,
+    ipv6: Option<&Ipv6LeakReport>,
+    tls_bridge: bool,
+) -> LeakTestReport {
+    let checks = vec![
+        check_webrtc(probe),
+        check_dns(probe),
+        check_ipv6(ipv6),
+        check_timezone(fp, probe),
+        check_ua_consistency(fp, probe, tls_bridge),
+        check_engine(fp, probe),
+    ];
+    LeakTestReport {
+        profile_id: profile_id.into(),
+        passed: checks.iter().all(|c| c.status != CheckStatus::Fail),
+        exit_ip: probe.exit_ip.clone(),
+        exit_country_code: probe.exit_country_code.clone(),
+        checks,
+        ran_at: Utc::now(),
+    }
+}
+
+/// The exit's region as a proxy region ("El Paso, TX"), when the exit is in
+/// `country` and the city or region pins a timezone there.
+pub fn measured_region(probe: &LeakProbe, country: &str) -> Option<String> {
+    if !probe
+        .exit_country_code
+        .as_deref()
+        .is_some_and(|cc| cc.eq_ignore_ascii_case(country))
+    {
+        return None;
+    }
+    let parts: Vec<&str> = [&probe.exit_city, &probe.exit_region]
+        .into_iter()
+        .filter_map(|p| p.as_deref().map(str::trim).filter(|p| !p.is_empty()))
+        .collect();
+    let region = parts.join(", ");
+    region_timezone(country, &region).map(|_| region)
+}
+
+/// Any routable candidate address other than the exit IP is a leak.
+fn check_webrtc(probe: &LeakProbe) -> LeakCheck {
+    let public: Vec<&str> = probe
+        .webrtc_ips
+        .iter()
+        .filter(|ip| ip.parse::<IpAddr>().is_ok_and(is_public))
+        .map(String::as_str)
+        .collect();
+    let Some(exit) = &probe.exit_ip else {
+        return if public.is_empty() {
+            LeakCheck::new("webrtc", CheckStatus::Pass, "no public ICE candidates")
+        } else {
+            LeakCheck::new(
+                "webrtc",
+                CheckStatus::Skipped,
+                format!("exit IP unknown; candidates expose {}", public.join(", ")),
+            )
+        };
+    };
+    let leaked: Vec<&str> = public.into_iter().filter(|ip| ip != exit).collect();
+    if leaked.is_empty() {
+        LeakCheck::new(
+            "webrtc",
+            CheckStatus::Pass,
+            "ICE candidates show only the exit IP",
+        )
+    } else {
+        LeakCheck::new(
+            "webrtc",
+            CheckStatus::Fail,
+            format!(
+                "ICE candidates expose {} (exit is {exit})",
+                leaked.join(", ")
+            ),
+        )
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                // 100.64.0.0/10 carrier-grade NAT
+                || (v4.octets()[0] == 100 && (v4.octets()[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => {
+            let seg = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || (seg & 0xfe00) == 0xfc00 // unique local
+                || (seg & 0xffc0) == 0xfe80) // link local
+        }
+    }
+}
+
+/// A resolver in another country than the exit means lookups bypass the proxy.
+fn check_dns(probe: &LeakProbe) -> LeakCheck {
+    match (&probe.resolver_country, &probe.exit_country) {
+        (Some(resolver), Some(exit)) if resolver.eq_ignore_ascii_case(exit) => LeakCheck::new(
+            "dns",
+            CheckStatus::Pass,
+            format!("resolver is in {resolver}, like the exit"),
+        ),
+        (Some(resolver), Some(exit)) => LeakCheck::new(
+            "dns",
+            CheckStatus::Fail,
+            format!(
+                "resolver {} is in {resolver}, exit is in {exit}",
+                probe.resolver_ip.as_deref().unwrap_or("?")
+            ),
+        ),
+        _ => LeakCheck::new(
+            "dns",
+            CheckStatus::Skipped,
+            "resolver or exit geo unavailable",
+        ),
+    }
+}
+
+fn check_ipv6(report: Option<&Ipv6LeakReport>) -> LeakCheck {
+    match report {
+        None => LeakCheck::new("ipv6", CheckStatus::Skipped, "no single proxy to check"),
+        Some(r) if r.leak_risk => LeakCheck::new(
+            "ipv6",
+            CheckStatus::Fail,
+            "host has IPv6 but the proxy can't carry it",
+        ),
+        Some(r) if r.host_ipv6 => LeakCheck::new("ipv6", CheckStatus::Pass, "proxy carries IPv6"),
+        Some(_) => LeakCheck::new("ipv6", CheckStatus::Pass, "host has no IPv6 route"),
+    }
+}
+
+/// The spoofed timezone must be in effect and match the exit IP's offset.
+fn check_timezone(fp: &Fingerprint, probe: &LeakProbe) -> LeakCheck {
+    if probe.timezone != fp.timezone {
+        return LeakCheck::new(
+            "timezone",
+            CheckStatus::Fail,
+            format!(
+                "page reports {}, profile is {}",
+                probe.timezone, fp.timezone
+            ),
+        );
+    }
+    match probe.exit_utc_offset_secs {
+        Some(exit) if exit == probe.utc_offset_secs => LeakCheck::new(
+            "timezone",
+            CheckStatus::Pass,
+            format!("{} matches the exit's UTC offset", fp.timezone),
+        ),
+        Some(exit) => LeakCheck::new(
+            "timezone",
+            CheckStatus::Fail,
+            format!(
+                "{} is UTC{}, exit IP is UTC{}",
+                fp.timezone,
+                fmt_offset(probe.utc_offset_secs),
+                fmt_offset(exit)
+            ),
+        ),
+        None => LeakCheck::new(
+            "timezone",
+            CheckStatus::Skipped,
+            "exit timezone unavailable",
+        ),
+    }
+}
+
+fn fmt_offset(secs: i32) -> String {
+    let sign = if secs < 0 { '-' } else { '+' };
+    let m = secs.unsigned_abs() / 60;
+    format!("{sign}{:02}:{:02}", m / 60, m % 60)
+}
+
+fn major(version: &str) -> Option<u32> {
+    version.split('.').next()?.parse().ok()
+}
+
+/// UA, UA-CH and the TLS stack must all claim the same Chrome.
+fn check_ua_consistency(fp: &Fingerprint, probe: &LeakProbe, tls_bridge: bool) -> LeakCheck {
+    let mut problems = Vec::new();
+    if probe.user_agent != fp.user_agent {
+        problems.push(format!("navigator.userAgent is {:?}", probe.user_agent));
+    }
+    let ua_major = chrome_version(&probe.user_agent).and_then(major);
+    let ch_major = probe
+        .ua_brands
+        .iter()
+        .find(|b| b.brand == "Google Chrome" || b.brand == "Chromium")
+        .and_then(|b| major(&b.version));
+    if ch_major != ua_major {
+        problems.push(format!(
+            "UA-CH brand major {ch_major:?} differs from UA major {ua_major:?}"
+        ));
+    }
+    if probe.ua_platform != fp.ua_platform {
+        problems.push(format!(
+            "UA-CH platform {:?}, profile is {:?}",
+            probe.ua_platform, fp.ua_platform
+        ));
+    }
+    // Without the bridge the ClientHello is Chromium's own, so the running
+    // build has to be the one the UA claims
+    let engine_major = major(&probe.browser_version);
+    if !tls_bridge && engine_major.is_some() && engine_major != ua_major {
+        problems.push(format!(
+            "TLS comes from Chromium {} but the UA claims {ua_major:?}",
+            probe.browser_version
+        ));
+    }
+    if problems.is_empty() {
+        let tls = if tls_bridge {
+            "TLS bridge"
+        } else {
+            "native TLS"
+        };
+        LeakCheck::new(
+            "ua_consistency",
+            CheckStatus::Pass,
+            format!(
+                "UA, UA-CH and {tls} agree on Chrome {}",
+                ua_major.unwrap_or(0)
+            ),
+        )
+    } else {
+        LeakCheck::new("ua_consistency", CheckStatus::Fail, problems.join("; "))
+    }
+}
+
+/// Engine values must be those of the Chrome major and locale the profile
+/// claims.
+fn check_engine(fp: &Fingerprint, probe: &LeakProbe) -> LeakCheck {
+    let Some(observed) = &probe.engine else {
+        return LeakCheck::new(
+            "engine",
+            CheckStatus::Skipped,
+            "bridge reported no engine values",
+        );
+    };
+    let expected = engine_quirks(fp);
+    let problems = crate::engine_quirks::verify(&expected, observed);
+    if problems.is_empty() {
+        LeakCheck::new(
+            "engine",
+            CheckStatus::Pass,
+            format!(
+                "Math, Error.stack, builtins and Intl match Chrome {} in {}",
+                expected.chrome_major.unwrap_or(0),
+                fp.locale
+            ),
+        )
+    } else {
+        LeakCheck::new("engine", CheckStatus::Fail, problems.join("; "))
+    }
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct LeakTestRepo {
+    db: Db,
+}
+
+impl LeakTestRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Store `report` as the profile's latest leak test.
+    pub fn save(&self, report: &LeakTestReport) -> Result<()> {
+        let json = serde_json::to_string(report)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO leak_tests (profile_id, passed, report, ran_at)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT(profile_id) DO UPDATE SET
+                       passed = excluded.passed,
+                       report = excluded.report,
+                       ran_at = excluded.ran_at"#,
+                params![
+                    report.profile_id,
+                    report.passed,
+                    json,
+                    report.ran_at.to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn latest(&self, profile_id: &str) -> Result<Option<LeakTestReport>> {
+        let json: Option<String> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT report FROM leak_tests WHERE profile_id = ?1",
+                    params![profile_id],
+                    |r| r.get(0),
+                )
+                .optional()?)
+        })?;
+        json.map(|j| serde_json::from_str(&j).map_err(ManifoldError::from))
+            .transpose()
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    fn clean_probe(fp: &Fingerprint) -> LeakProbe {
+        let version = chrome_version(&fp.user_agent).unwrap().to_string();
+        LeakProbe {
+            webrtc_ips: vec!["192.168.1.20".into(), "203.0.113.7".into()],
+            exit_ip: Some("203.0.113.7".into()),
+            exit_country: Some("United States".into()),
+            exit_country_code: Some("US".into()),
+            exit_utc_offset_secs: Some(-5 * 3600),
+            exit_region: Some("NY".into()),
+            exit_region_name: Some("New York".into()),
+            exit_city: Some("New York".into()),
+            resolver_ip: Some("198.51.100.53".into()),
+            resolver_country: Some("United States".into()),
+            timezone: fp.timezone.clone(),
+            utc_offset_secs: -5 * 3600,
+            user_agent: fp.user_agent.clone(),
+            ua_brands: fp.ua_brands.clone(),
+            ua_platform: fp.ua_platform.clone(),
+            browser_version: version,
+            engine: Some(matching_engine(fp)),
+        }
+    }
+
+    fn matching_engine(fp: &Fingerprint) -> EngineObservation {
+        let q = engine_quirks(fp);
+        EngineObservation {
+            math: q.math,
+            stack: format!("{}<anonymous>:1:7", q.stack_prefix),
+            stack_trace_limit: q.stack_trace_limit,
+            builtins: q.builtins_present,
+            calendar: q.intl.calendar,
+            numbering_system: q.intl.numbering_system,
+            hour_cycle: q.intl.hour_cycle,
+        }
+    }
+
+    fn status(report: &LeakTestReport, name: &str) -> CheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn clean_probe_passes() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let report = evaluate("p", &fp, &clean_probe(&fp), None, false);
+        assert!(report.passed, "{:?}", report.checks);
+        assert_eq!(status(&report, "ipv6"), CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn measured_region_needs_a_known_zone_in_the_country() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let mut probe = clean_probe(&fp);
+        assert_eq!(
+            measured_region(&probe, "us").as_deref(),
+            Some("New York, NY")
+        );
+        assert_eq!(measured_region(&probe, "DE"), None);
+
+        probe.exit_city = Some("Provo".into());
+        probe.exit_region = Some("UT".into());
+        assert_eq!(measured_region(&probe, "US").as_deref(), Some("Provo, UT"));
+        probe.exit_city = None;
+        assert_eq!(measured_region(&probe, "US").as_deref(), Some("UT"));
+        probe.exit_region = Some("ZZ".into());
+        assert_eq!(measured_region(&probe, "US"), None);
+    }
+
+    #[test]
+    fn webrtc_and_dns_leaks_fail() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let mut probe = clean_probe(&fp);
+        probe.webrtc_ips.push("198.51.100.9".into());
+        probe.resolver_country = Some("Germany".into());
+        let report = evaluate("p", &fp, &probe, None, false);
+        assert!(!report.passed);
+        assert_eq!(status(&report, "webrtc"), CheckStatus::Fail);
+        assert_eq!(status(&report, "dns"), CheckStatus::Fail);
+    }
+
+    #[test]
+    fn timezone_offset_must_match_exit() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let mut probe = clean_probe(&fp);
+        probe.exit_utc_offset_secs = Some(3600);
+        let report = evaluate("p", &fp, &probe, None, false);
+        assert_eq!(status(&report, "timezone"), CheckStatus::Fail);
+        assert!(report.checks[3].detail.contains("UTC+01:00"));
+    }
+
+    #[test]
+    fn native_tls_must_match_ua_major() {
+        let fp = FingerprintOrchestrator::generate(7);
+        let mut probe = clean_probe(&fp);
+        probe.browser_version = "99.0.4844.51".into();
+        let report = evaluate("p", &fp, &probe, None, false);
+        assert_eq!(status(&report, "ua_consistency"), CheckStatus::Fail);
+        // The TLS bridge speaks for the UA, not the engine
+        let report = evaluate("p", &fp, &probe, None, true);
+        assert_eq!(status(&report, "ua_consistency"), CheckStatus::Pass);
+    }
+
+    #[test]
+    fn engine_values_must_match_claimed_chrome_and_locale() {
+        let mut fp = FingerprintOrchestrator::generate(7);
+        FingerprintOrchestrator::set_chrome_major(&mut fp, 118);
+        let mut probe = clean_probe(&fp);
+        probe
+            .engine
+            .as_mut()
+            .unwrap()
+            .builtins
+            .push("Promise.try".into());
+        let report = evaluate("p", &fp, &probe, None, false);
+        assert_eq!(status(&report, "engine"), CheckStatus::Fail);
+
+        probe.engine = None;
+        let report = evaluate("p", &fp, &probe, None, false);
+        assert_eq!(status(&report, "engine"), CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn parses_probe_line() {
+        let line = r#"LEAK_PROBE {"webrtcIps":["10.0.0.2"],"timezone":"UTC"}"#;
+        let probe = parse_probe_line(line).unwrap().unwrap();
+        assert_eq!(probe.webrtc_ips, ["10.0.0.2"]);
+        assert!(parse_probe_line("BRIDGE_READY").is_none());
+    }
+
+    #[test]
+    fn report_round_trips_through_repo() {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, created_at)
+                 VALUES ('p', 'p', '{}', '2025-01-01T00:00:00+00:00')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        let repo = LeakTestRepo::new(db);
+        assert!(repo.latest("p").unwrap().is_none());
+        let fp = FingerprintOrchestrator::generate(7);
+        let report = evaluate("p", &fp, &clean_probe(&fp), None, false);
+        repo.save(&report).unwrap();
+        repo.save(&report).unwrap();
+        assert!(repo.latest("p").unwrap().unwrap().passed);
+    }
+}
@@ -1,50 +1,210 @@
 // ── Manifold — Tauri application root ────────────────────────────────────────
 
+mod aging;
+mod bandwidth;
+mod behavior_audit;
+mod bridge_auth;
+mod bridge_locator;
+mod cache_prewarm;
+mod chain;
+mod clipboard;
+mod clock_guard;
 mod commands;
+mod cost;
 mod db;
+mod dns;
+mod engine_quirks;
+mod entropy_report;
 mod error;
+mod events;
 mod fingerprint;
+mod fingerprint_diff;
+mod fingerprint_vectors;
+mod fonts;
+mod generation_policy;
 mod geo_validator;
+mod gpu_probe;
+mod group_run;
+mod har_import;
+mod hash_preview;
+mod header_order;
+mod host_audit;
 mod human;
+mod integrity;
+mod intl;
+mod launch_config;
+mod launch_conflicts;
+mod launch_env;
+mod launch_mode;
+mod leak_test;
+mod master_key;
+mod metrics;
+mod motion;
+mod node_pool;
+mod nodes;
+mod notifications;
+mod permissions;
+mod persona;
+mod power;
+mod preflight;
+mod priors;
 mod profile;
 mod proxy;
+mod proxy_matching;
+mod proxy_transfer;
+mod quota;
+mod reference_corpus;
+mod report;
+mod request_headers;
+mod rest;
+mod run_artifacts;
+mod run_outputs;
+mod session;
+mod settings;
+mod share;
+mod ssh_tunnel;
+mod stats;
+mod synthetic_identity;
+mod timeline;
 mod tls_bridge;
+mod vault;
+mod vpn;
+mod workflow;
+mod workspace;
 
 use commands::AppState;
-use db::{default_db_path, Db};
+use workspace::WorkspaceRegistry;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // ── Database ──────────────────────────────────────────────────────────────
+    // The active workspace (or the one named by MANIFOLD_WORKSPACE) is opened.
     // In a production build you'd derive the master key from a user-supplied
     // passphrase or the OS keychain.  For development we skip encryption.
-    let master_key: Option<String> = std::env::var("MANIFOLD_MASTER_KEY").ok();
+    let registry = WorkspaceRegistry::load_default().expect("failed to read workspace registry");
+    let name = std::env::var(workspace::WORKSPACE_ENV)
+        .unwrap_or_else(|_| registry.active().to_string());
+    let active = registry.get(&name).expect("unknown workspace");
+    let master_key: Option<String> = std::env::var(workspace::master_key_env(&name)).ok();
 
-    let db_path = default_db_path();
-    let db = Db::open(&db_path, master_key.as_deref()).expect("failed to open Manifold database");
+    workspace::use_profiles_dir(&active);
+    let db = workspace::open(&active, master_key.as_deref())
+        .expect("failed to open Manifold database");
 
-    let app_state = AppState::new(db, master_key);
+    // Use the last fetched population priors, if they still verify
+    if let Err(e) = priors::restore(&db) {
+        eprintln!("[priors] cached dataset not used: {e}");
+    }
+
+    // Keep Chrome versions current for profiles that opted in
+    aging::spawn_scheduler(db.clone());
+
+    // Measure profile data dirs against their disk quotas
+    quota::spawn_scheduler(db.clone());
+
+    // Alert when the database volume runs low on space
+    notifications::spawn_db_space_watch(db.clone());
+
+    // Report data dirs left behind by failed deletions; cleaning them is
+    // left to the user (`gc_orphaned_data`)
+    let gc_db = db.clone();
+    std::thread::spawn(move || {
+        match profile::ProfileRepo::new(gc_db).gc_orphaned_data(true) {
+            Ok(report) if !report.orphans.is_empty() => {
+                let bytes: u64 = report.orphans.iter().map(|o| o.bytes).sum();
+                eprintln!(
+                    "[gc] {} orphaned profile dir(s), {bytes} bytes — run gc_orphaned_data to remove",
+                    report.orphans.len()
+                );
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("[gc] orphan scan failed: {e}"),
+        }
+    });
+
+    let app_state = AppState::new(db, active, master_key);
 
     // ── Tauri builder ─────────────────────────────────────────────────────────
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .manage(app_state)
-        .invoke_handler(tauri::generate_handler![
+        .setup(|app| {
+            // Pause running profiles across host sleep
+            let handle = app.handle().clone();
+            power::spawn_watcher(move |event| commands::handle_power_event(&handle, event));
+
+            // Warn about host conditions the bridge can't mask
+            let handle = app.handle().clone();
+            std::thread::spawn(move || commands::startup_host_audit(&handle));
+
+            // Keep the node pool's nodes checked and its queue moving
+            let handle = app.handle().clone();
+            std::thread::spawn(move || commands::watch_node_pool(&handle));
+
+            // Pull `save_data` key files from older builds into the DB
+            if let Ok(dir) = app.path().app_data_dir() {
+                let state = app.state::<AppState>();
+                let migrated = state.settings.lock().unwrap().migrate_key_files(&dir);
+                match migrated {
+                    Ok(0) => {}
+                    Ok(n) => eprintln!("[settings] imported {n} key file(s) into the database"),
+                    Err(e) => eprintln!("[settings] key file migration failed: {e}"),
+                }
+            }
+            Ok(())
+        })
+        // Every command is timed for `get_command_metrics`
+        .invoke_handler(metrics::instrument(tauri::generate_handler![
             // ── Profile ───────────────────────────────────────────────────────
             commands::list_profiles,
             commands::get_profile,
             commands::create_profile,
+            commands::bulk_create_profiles,
             commands::update_profile,
             commands::delete_profile,
+            commands::gc_orphaned_data,
+            commands::check_integrity,
+            commands::repair_integrity,
+            commands::set_profile_disk_quota,
+            commands::get_disk_usage,
+            commands::clear_profile_cache,
+            commands::get_quota_settings,
+            commands::set_quota_settings,
+            commands::get_cache_prewarm_settings,
+            commands::set_cache_prewarm_settings,
+            commands::harvest_shared_cache,
+            commands::get_shared_cache,
+            commands::clear_shared_cache,
             commands::reseed_profile,
             commands::duplicate_profile,
+            commands::share_profile,
+            commands::import_shared_profile,
+            commands::batch_update_profiles,
+            commands::set_profile_auto_age,
+            commands::get_warmup_plan,
             commands::set_profile_status,
+            commands::list_status_transitions,
             // ── Fingerprint ───────────────────────────────────────────────────
             commands::generate_fingerprint,
             commands::reseed_fingerprint,
+            commands::run_fingerprint_aging,
+            commands::preview_fingerprint_hashes,
+            commands::get_entropy_report,
+            commands::get_priors_status,
+            commands::get_priors,
+            commands::get_priors_source,
+            commands::set_priors_source,
+            commands::update_priors,
+            commands::diff_fingerprints,
+            commands::export_fingerprint_vectors,
+            commands::verify_fingerprint_vectors,
             // ── Human behavior ────────────────────────────────────────────────
             commands::get_human_defaults,
+            commands::generate_mouse_path,
+            commands::export_mouse_trace,
+            commands::generate_scroll_pattern,
             // ── Proxy ─────────────────────────────────────────────────────────
             commands::list_proxies,
             commands::get_proxy,
@@ -53,35 +213,152 @@ pub fn run() {
             commands::delete_proxy,
             commands::check_proxy,
             commands::check_all_proxies,
+            commands::check_ipv6_leak,
+            commands::pin_proxy_ip,
+            commands::unpin_proxy_ip,
+            commands::set_proxy_check_mode,
+            commands::get_proxy_check_targets,
+            commands::set_proxy_check_targets,
+            commands::set_proxy_own_check_targets,
+            commands::compare_proxy_check_targets,
+            commands::measure_proxy_speed,
+            commands::set_profile_proxy_chain,
+            commands::set_profile_launch_env,
+            commands::set_profile_launch_policy,
+            commands::set_profile_pinned_version,
+            commands::check_launch_mode,
+            commands::list_profile_sessions,
+            commands::check_proxy_chain,
+            commands::set_profile_vpn,
+            commands::get_profile_vpn,
+            commands::remove_profile_vpn,
+            commands::record_proxy_domain_hit,
+            commands::list_proxy_domain_status,
+            commands::clear_proxy_domain_status,
+            commands::export_proxies,
+            commands::import_proxies,
+            commands::suggest_proxy,
+            commands::auto_assign_proxies,
             // ── Bridge / launcher ─────────────────────────────────────────────
             commands::start_bridge,
+            commands::get_bridge_info,
             commands::launch_profile,
+            commands::preview_launch_config,
+            commands::add_launch_conflict,
+            commands::remove_launch_conflict,
+            commands::list_launch_conflicts,
+            commands::check_launch_conflicts,
+            commands::run_leak_test,
+            commands::preflight_profile,
+            commands::list_workflows,
+            commands::get_workflow,
+            commands::create_workflow,
+            commands::update_workflow,
+            commands::delete_workflow,
+            commands::run_workflow,
+            commands::resume_workflow,
+            commands::run_workflow_on_group,
+            commands::get_group_run,
+            commands::list_workflow_runs,
+            commands::get_workflow_run_archive,
+            commands::list_interventions,
+            commands::list_run_outputs,
+            commands::export_run_outputs,
+            commands::workflow_variables,
+            commands::list_profile_secrets,
+            commands::set_profile_secret,
+            commands::delete_profile_secret,
+            commands::get_synthetic_identity,
+            commands::validate_identity,
+            commands::delete_synthetic_identity,
+            commands::get_leak_test,
+            commands::get_launch_env_settings,
+            commands::set_launch_env_settings,
             commands::stop_bridge,
             commands::get_bridge_url,
             commands::set_bridge_port,
+            commands::list_nodes,
+            commands::add_node,
+            commands::update_node,
+            commands::set_node_enabled,
+            commands::delete_node,
+            commands::check_node,
+            commands::enqueue_launches,
+            commands::get_node_pool,
+            commands::get_pool_bridge_url,
             // ── Scraper sidecar ───────────────────────────────────────────────
             commands::start_scraper,
             commands::stop_scraper,
             // ── TLS Bridge ────────────────────────────────────────────────────
+            commands::compute_expected_ja4,
             commands::launch_tls_bridge,
             // ── Emergency ─────────────────────────────────────────────────────
             commands::panic_shutdown,
+            commands::get_power_settings,
+            commands::set_power_settings,
+            commands::test_notifications,
             // ── Session export / replay ───────────────────────────────────────
             commands::export_session,
+            commands::verify_session_headers,
             commands::list_sessions,
+            commands::get_session_timeline,
+            commands::import_session_har,
+            commands::get_bandwidth_report,
+            commands::get_proxy_cost,
+            commands::set_proxy_cost,
+            commands::record_captcha_solve,
+            commands::get_cost_report,
+            commands::list_clock_flagged_sessions,
+            commands::list_behavior_audits,
             commands::delete_session,
+            commands::clipboard_write,
+            commands::clipboard_read,
             // ── Data / settings ───────────────────────────────────────────────
+            commands::get_settings,
+            commands::update_settings,
             commands::save_data,
             commands::load_data,
+            commands::export_workspace_report,
+            commands::list_workspaces,
+            commands::create_workspace,
+            commands::switch_workspace,
+            commands::rotate_master_key,
+            commands::get_recovery_status,
+            commands::regenerate_recovery_codes,
+            commands::take_setup_recovery_codes,
+            commands::recover_workspace,
             commands::get_app_info,
+            commands::get_host_audit,
             commands::bootstrap_check,
             commands::run_bootstrap_step,
             // ── URL Analysis ──────────────────────────────────────────────────
             commands::analyze_url,
             // ── Geo consistency ───────────────────────────────────────────────
             commands::validate_geo_consistency,
+            commands::list_geo_options,
             commands::auto_correct_geo,
-        ])
+            commands::get_host_gpu,
+            commands::get_host_clock,
+            commands::get_clock_tolerance,
+            commands::set_clock_tolerance,
+            commands::ingest_reference_fingerprint,
+            commands::list_reference_fingerprints,
+            commands::delete_reference_fingerprint,
+            commands::compare_to_references,
+            commands::create_profile_from_capture,
+            commands::set_profile_ua_reduction,
+            commands::set_profile_window_variation,
+            commands::get_permission_presets,
+            commands::set_profile_permissions,
+            // ── Event log / dashboard ─────────────────────────────────────────
+            commands::record_event,
+            commands::list_events,
+            commands::verify_event_log,
+            commands::recommend_rest,
+            commands::get_dashboard_stats,
+            commands::get_command_metrics,
+            commands::reset_command_metrics,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running Manifold");
 }
@@ -0,0 +1,424 @@
+// ── Manifold master key rotation and recovery codes ──────────────────────────
+//
+// Sensitive fields are encrypted under a key derived from the workspace's
+// master passphrase (`db::derive_key`).  Rotating the passphrase decrypts
+// every such field and encrypts it again under the new key, all in one
+// transaction: a rotation that fails half way leaves the old key working,
+// never a mix of both.
+//
+// Forgetting the passphrase would otherwise lose every proxy password, VPN
+// config, vault secret and synthetic identity for good.  So when a key is
+// first set up, and on every rotation, the workspace gets a set of recovery
+// codes.  Each one seals the current key under a key of its own, stored in
+// `settings`; any one of them unlocks the workspace once, to rotate it to a
+// new passphrase — which replaces the whole set, as the codes left over
+// seal the key just retired.
+//
+// A code is 100 random bits, far past brute force, so its key is a plain
+// SHA-256 of salt and code rather than a deliberately slow derivation.
+
+use std::sync::Mutex;
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Db, Rekey};
+use crate::error::{ManifoldError, Result};
+
+/// `settings` key holding the sealed recovery codes.
+pub const RECOVERY_CODES_KEY: &str = "recovery_codes";
+
+/// Codes issued per set.
+pub const RECOVERY_CODE_COUNT: usize = 8;
+
+/// Crockford base32: no I, L, O or U to misread.
+const CODE_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_GROUPS: usize = 4;
+const CODE_GROUP_LEN: usize = 5;
+
+/// Encrypted columns, as (table, column).
+const ENCRYPTED_COLUMNS: &[(&str, &str)] = &[
+    ("proxies", "password_enc"),
+    ("vpn_configs", "config_enc"),
+    ("profile_secrets", "value"),
+    ("synthetic_identities", "identity"),
+    ("nodes", "agent_token_enc"),
+];
+
+/// Encrypted strings inside `settings` values, as (key, JSON pointer).
+const ENCRYPTED_SETTINGS: &[(&str, &str)] = &[
+    (crate::workspace::KEY_CHECK_KEY, ""),
+    (
+        crate::notifications::NOTIFICATION_SETTINGS_KEY,
+        "/telegram/bot_token",
+    ),
+];
+
+/// Codes generated when a workspace's key was set up, until the frontend
+/// takes them to show.
+static UNSHOWN: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// One code's seal of the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedKey {
+    salt: String,
+    sealed: String,
+}
+
+/// Emitted while a rotation works through the encrypted fields.
+#[derive(Debug, Clone, Serialize)]
+pub struct RotationProgress {
+    /// Table (or `settings`) being re-encrypted.
+    pub table: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryStatus {
+    /// The workspace has a master key.
+    pub encrypted: bool,
+    /// Recovery codes still unused.
+    pub codes_left: usize,
+}
+
+// ── Recovery codes ────────────────────────────────────────────────────────────
+
+fn new_code() -> String {
+    let mut bytes = [0u8; CODE_GROUPS * CODE_GROUP_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    let chars: Vec<char> = bytes
+        .iter()
+        .map(|b| CODE_ALPHABET[(b % 32) as usize] as char)
+        .collect();
+    chars
+        .chunks(CODE_GROUP_LEN)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Uppercase, without separators, with the letters base32 reads as digits
+/// mapped to them.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        })
+        .collect()
+}
+
+fn code_key(salt: &[u8], code: &str) -> [u8; 32] {
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    ctx.update(b"manifold-recovery-v1");
+    ctx.update(salt);
+    ctx.update(normalize(code).as_bytes());
+    ctx.finish()
+        .as_ref()
+        .try_into()
+        .expect("SHA-256 digest is 32 bytes")
+}
+
+fn seal_key(key: &[u8; 32], code: &str) -> Result<SealedKey> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = crate::db::cipher_for(&code_key(&salt, code));
+    Ok(SealedKey {
+        salt: B64.encode(salt),
+        sealed: crate::db::seal(&cipher, key)?,
+    })
+}
+
+fn unseal_key(sealed: &SealedKey, code: &str) -> Option<[u8; 32]> {
+    let salt = B64.decode(&sealed.salt).ok()?;
+    let cipher = crate::db::cipher_for(&code_key(&salt, code));
+    crate::db::unseal(&cipher, &sealed.sealed)
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// A fresh set of codes sealing `key`, stored in place of any previous set.
+/// No key clears the set.
+fn issue(conn: &Connection, key: Option<&[u8; 32]>) -> Result<Vec<String>> {
+    let Some(key) = key else {
+        conn.execute(
+            "DELETE FROM settings WHERE key = ?1",
+            params![RECOVERY_CODES_KEY],
+        )?;
+        return Ok(Vec::new());
+    };
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| new_code()).collect();
+    let sealed = codes
+        .iter()
+        .map(|code| seal_key(key, code))
+        .collect::<Result<Vec<_>>>()?;
+    store_sealed(conn, &sealed)?;
+    Ok(codes)
+}
+
+fn load_sealed(conn: &Connection) -> Result<Vec<SealedKey>> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![RECOVERY_CODES_KEY],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn store_sealed(conn: &Connection, sealed: &[SealedKey]) -> Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![RECOVERY_CODES_KEY, serde_json::to_string(sealed)?],
+    )?;
+    Ok(())
+}
+
+/// Replace the workspace's recovery codes with a new set.
+pub fn regenerate(db: &Db) -> Result<Vec<String>> {
+    let key = db.key().ok_or_else(|| {
+        ManifoldError::InvalidArg("this workspace has no master key to recover".into())
+    })?;
+    db.with_conn(|conn| issue(conn, Some(&key)))
+}
+
+/// Issue the first set of codes for a workspace whose key was just set up,
+/// held until `take_unshown`.
+pub fn issue_on_setup(db: &Db) -> Result<()> {
+    if db.key().is_some() {
+        *UNSHOWN.lock().unwrap() = Some(regenerate(db)?);
+    }
+    Ok(())
+}
+
+/// Codes issued on setup that were not shown yet; `None` once taken.
+pub fn take_unshown() -> Option<Vec<String>> {
+    UNSHOWN.lock().unwrap().take()
+}
+
+pub fn status(db: &Db) -> Result<RecoveryStatus> {
+    Ok(RecoveryStatus {
+        encrypted: db.key().is_some(),
+        codes_left: db.with_conn(load_sealed)?.len(),
+    })
+}
+
+// ── Rotation ──────────────────────────────────────────────────────────────────
+
+fn reencrypt_column(
+    conn: &Connection,
+    rekey: &Rekey,
+    table: &str,
+    column: &str,
+    mut step: impl FnMut(),
+) -> Result<()> {
+    let rows: Vec<(i64, String)> = conn
+        .prepare(&format!(
+            "SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL"
+        ))?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (rowid, value) in rows {
+        conn.execute(
+            &format!("UPDATE {table} SET {column} = ?1 WHERE rowid = ?2"),
+            params![rekey.field(&value)?, rowid],
+        )?;
+        step();
+    }
+    Ok(())
+}
+
+fn reencrypt_setting(conn: &Connection, rekey: &Rekey, key: &str, pointer: &str) -> Result<()> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![key],
+            |r| r.get(0),
+        )
+        .optional()?;
+    let Some(json) = json else {
+        return Ok(());
+    };
+    let mut value: serde_json::Value = serde_json::from_str(&json)?;
+    if let Some(field) = value.pointer_mut(pointer) {
+        if let Some(text) = field.as_str() {
+            *field = rekey.field(text)?.into();
+            conn.execute(
+                "UPDATE settings SET value = ?1 WHERE key = ?2",
+                params![value.to_string(), key],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn count_encrypted(conn: &Connection) -> Result<usize> {
+    let mut total = ENCRYPTED_SETTINGS.len();
+    for (table, column) in ENCRYPTED_COLUMNS {
+        let n: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM {table} WHERE {column} IS NOT NULL"),
+            [],
+            |r| r.get(0),
+        )?;
+        total += n as usize;
+    }
+    Ok(total)
+}
+
+/// Re-encrypt every sensitive field under `new_master_key` (`None` to
+/// store them in plaintext) and return the workspace's new recovery codes.
+/// `progress` is called after each field.
+pub fn rotate(
+    db: &Db,
+    new_master_key: Option<&str>,
+    mut progress: impl FnMut(&RotationProgress),
+) -> Result<Vec<String>> {
+    let new_key = new_master_key.map(crate::db::derive_key);
+    db.rekey(new_key, |conn, rekey| {
+        let total = count_encrypted(conn)?;
+        let mut done = 0;
+        for (table, column) in ENCRYPTED_COLUMNS {
+            reencrypt_column(conn, rekey, table, column, || {
+                done += 1;
+                progress(&RotationProgress {
+                    table: table.to_string(),
+                    done,
+                    total,
+                });
+            })?;
+        }
+        for (key, pointer) in ENCRYPTED_SETTINGS {
+            reencrypt_setting(conn, rekey, key, pointer)?;
+            done += 1;
+            progress(&RotationProgress {
+                table: "settings".into(),
+                done,
+                total,
+            });
+        }
+        issue(conn, new_key.as_ref())
+    })
+}
+
+/// Unlock `db`, opened without its key, with a recovery code and rotate it
+/// to `new_master_key`.  Returns the new recovery codes; the one used, like
+/// the rest of the old set, no longer works.
+pub fn recover(db: &Db, code: &str, new_master_key: &str) -> Result<Vec<String>> {
+    if new_master_key.is_empty() {
+        return Err(ManifoldError::InvalidArg(
+            "the new master key must not be empty".into(),
+        ));
+    }
+    let key = db
+        .with_conn(load_sealed)?
+        .iter()
+        .find_map(|sealed| unseal_key(sealed, code))
+        .ok_or_else(|| ManifoldError::Crypto("recovery code does not match".into()))?;
+    db.unlock(key);
+    crate::workspace::check_master_key(db)?;
+    rotate(db, Some(new_master_key), |_| {})
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::{NotificationSettings, TelegramSettings};
+
+    fn seed(db: &Db) {
+        crate::workspace::check_master_key(db).unwrap();
+        let password = db.encrypt_field("hunter2").unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO proxies (id, name, proxy_type, host, port, password_enc)
+                 VALUES ('p1', 'p1', 'http', 'example.com', 8080, ?1)",
+                params![password],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        let settings = NotificationSettings {
+            telegram: Some(TelegramSettings {
+                bot_token: "123:abc".into(),
+                chat_id: "42".into(),
+            }),
+            ..Default::default()
+        };
+        crate::notifications::store_settings(db, &settings).unwrap();
+    }
+
+    fn password(db: &Db) -> Result<String> {
+        let enc: String = db.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT password_enc FROM proxies WHERE id = 'p1'",
+                [],
+                |r| r.get(0),
+            )?)
+        })?;
+        db.decrypt_field(&enc)
+    }
+
+    #[test]
+    fn rotation_reencrypts_every_field_and_reissues_codes() {
+        let db = Db::open_in_memory_with_key("old passphrase").unwrap();
+        seed(&db);
+        let mut last = None;
+        let codes = rotate(&db, Some("new passphrase"), |p| last = Some(p.clone())).unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        let last = last.unwrap();
+        assert_eq!(last.done, last.total);
+        assert_eq!(last.total, 1 + ENCRYPTED_SETTINGS.len());
+
+        // Only the new key reads the stored fields
+        assert_eq!(db.key(), Some(crate::db::derive_key("new passphrase")));
+        assert_eq!(password(&db).unwrap(), "hunter2");
+        let tg = crate::notifications::load_settings(&db).unwrap().telegram;
+        assert_eq!(tg.unwrap().bot_token, "123:abc");
+        crate::workspace::check_master_key(&db).unwrap();
+        db.unlock(crate::db::derive_key("old passphrase"));
+        assert!(password(&db).is_err());
+        assert!(crate::workspace::check_master_key(&db).is_err());
+    }
+
+    #[test]
+    fn a_recovery_code_unlocks_once_and_sets_a_new_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("manifold.db");
+        let db = Db::open(&path, Some("forgotten")).unwrap();
+        seed(&db);
+        let codes = regenerate(&db).unwrap();
+        assert_eq!(status(&db).unwrap().codes_left, RECOVERY_CODE_COUNT);
+        drop(db);
+
+        let locked = Db::open(&path, None).unwrap();
+        assert!(recover(&locked, "0000-0000-0000-0000-0000", "fresh").is_err());
+        let typed = codes[3].to_lowercase().replace('-', " ");
+        let new_codes = recover(&locked, &typed, "fresh").unwrap();
+        drop(locked);
+
+        let db = Db::open(&path, Some("fresh")).unwrap();
+        crate::workspace::check_master_key(&db).unwrap();
+        assert_eq!(password(&db).unwrap(), "hunter2");
+        drop(db);
+
+        let locked = Db::open(&path, None).unwrap();
+        assert!(recover(&locked, &codes[3], "again").is_err());
+        assert!(recover(&locked, &codes[0], "again").is_err());
+        recover(&locked, &new_codes[0], "again").unwrap();
+    }
+}
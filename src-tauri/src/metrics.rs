@@ -0,0 +1,286 @@
+// ── Manifold command metrics ──────────────────────────────────────────────────
+//
+// Every Tauri command is timed as it goes through the invoke handler, along
+// with the size of its JSON arguments.  Each command keeps its last `WINDOW`
+// durations for percentiles plus lifetime totals, and calls slower than
+// `SLOW_MS` are logged and kept in a short list, so a report like
+// "list_profiles takes 4 s with 800 profiles" comes with numbers and it is
+// clear which paths need paging or a background task.
+//
+// Async commands return to the handler as soon as they are spawned, so the
+// handler can't time them; they are left out rather than recorded as
+// near-instant.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::Runtime;
+
+/// Durations kept per command for percentiles.
+const WINDOW: usize = 500;
+
+/// Calls at least this slow are logged.
+pub const SLOW_MS: f64 = 1000.0;
+
+/// Slow calls kept for `get_command_metrics`.
+const SLOW_KEPT: usize = 50;
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+/// The `async` commands in commands.rs, which `instrument` doesn't record.
+const ASYNC_COMMANDS: [&str; 12] = [
+    "update_priors",
+    "launch_profile",
+    "run_leak_test",
+    "run_workflow",
+    "run_workflow_on_group",
+    "check_node",
+    "rotate_master_key",
+    "recover_workspace",
+    "analyze_url",
+    "get_host_gpu",
+    "get_host_clock",
+    "launch_tls_bridge",
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandMetric {
+    pub command: String,
+    pub calls: u64,
+    pub mean_ms: f64,
+    /// Percentiles over the last `WINDOW` calls.
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub mean_arg_bytes: u64,
+    pub max_arg_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowCall {
+    pub command: String,
+    pub duration_ms: f64,
+    pub arg_bytes: u64,
+    pub at: DateTime<Utc>,
+}
+
+/// `get_command_metrics`: commands slowest (p95) first, newest slow calls
+/// first.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsReport {
+    pub commands: Vec<CommandMetric>,
+    pub slow: Vec<SlowCall>,
+}
+
+#[derive(Default)]
+struct CommandStats {
+    calls: u64,
+    total_ms: f64,
+    max_ms: f64,
+    total_arg_bytes: u64,
+    max_arg_bytes: u64,
+    recent: VecDeque<f64>,
+}
+
+struct Registry {
+    commands: BTreeMap<String, CommandStats>,
+    slow: VecDeque<SlowCall>,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Self {
+            commands: BTreeMap::new(),
+            slow: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, command: &str, duration: Duration, arg_bytes: u64) {
+        let ms = duration.as_secs_f64() * 1000.0;
+        let stats = self.commands.entry(command.to_string()).or_default();
+        stats.calls += 1;
+        stats.total_ms += ms;
+        stats.max_ms = stats.max_ms.max(ms);
+        stats.total_arg_bytes += arg_bytes;
+        stats.max_arg_bytes = stats.max_arg_bytes.max(arg_bytes);
+        if stats.recent.len() == WINDOW {
+            stats.recent.pop_front();
+        }
+        stats.recent.push_back(ms);
+
+        if ms >= SLOW_MS {
+            eprintln!("[metrics] slow command {command}: {ms:.0} ms, {arg_bytes} B of arguments");
+            if self.slow.len() == SLOW_KEPT {
+                self.slow.pop_back();
+            }
+            self.slow.push_front(SlowCall {
+                command: command.to_string(),
+                duration_ms: ms,
+                arg_bytes,
+                at: Utc::now(),
+            });
+        }
+    }
+
+    fn report(&self) -> MetricsReport {
+        let mut commands: Vec<CommandMetric> = self
+            .commands
+            .iter()
+            .map(|(command, s)| {
+                let mut sorted: Vec<f64> = s.recent.iter().copied().collect();
+                sorted.sort_by(f64::total_cmp);
+                CommandMetric {
+                    command: command.clone(),
+                    calls: s.calls,
+                    mean_ms: s.total_ms / s.calls as f64,
+                    p50_ms: percentile(&sorted, 0.50),
+                    p95_ms: percentile(&sorted, 0.95),
+                    p99_ms: percentile(&sorted, 0.99),
+                    max_ms: s.max_ms,
+                    mean_arg_bytes: s.total_arg_bytes / s.calls,
+                    max_arg_bytes: s.max_arg_bytes,
+                }
+            })
+            .collect();
+        commands.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+        MetricsReport {
+            commands,
+            slow: self.slow.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice; 0 when empty.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+// ── Recording ─────────────────────────────────────────────────────────────────
+
+pub fn record(command: &str, duration: Duration, arg_bytes: u64) {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .record(command, duration, arg_bytes);
+}
+
+pub fn report() -> MetricsReport {
+    REGISTRY.lock().unwrap().report()
+}
+
+pub fn reset() {
+    *REGISTRY.lock().unwrap() = Registry::new();
+}
+
+/// Wrap the app's invoke handler so every synchronous command it runs is
+/// recorded.
+pub fn instrument<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        if ASYNC_COMMANDS.contains(&command.as_str()) {
+            return handler(invoke);
+        }
+        let arg_bytes = payload_size(invoke.message.payload());
+        let started = Instant::now();
+        let handled = handler(invoke);
+        record(&command, started.elapsed(), arg_bytes);
+        handled
+    }
+}
+
+/// Serialized size of the arguments, counted without buffering them.
+fn payload_size(body: &InvokeBody) -> u64 {
+    struct Counter(u64);
+    impl Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len() as u64;
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    match body {
+        InvokeBody::Json(value) => {
+            let mut counter = Counter(0);
+            serde_json::to_writer(&mut counter, value).ok();
+            counter.0
+        }
+        InvokeBody::Raw(bytes) => bytes.len() as u64,
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_cover_the_recent_window() {
+        let mut registry = Registry::new();
+        for ms in 1..=100 {
+            registry.record("list_profiles", Duration::from_millis(ms), 10);
+        }
+        registry.record("get_profile", Duration::from_millis(2), 50);
+
+        let report = registry.report();
+        let list = &report.commands[0];
+        assert_eq!(list.command, "list_profiles");
+        assert_eq!(list.calls, 100);
+        assert_eq!((list.p50_ms, list.p95_ms, list.max_ms), (50.0, 95.0, 100.0));
+        assert_eq!(report.commands[1].max_arg_bytes, 50);
+        assert!(report.slow.is_empty());
+
+        for _ in 0..WINDOW {
+            registry.record("list_profiles", Duration::from_millis(5), 10);
+        }
+        let list = &registry.report().commands[0];
+        assert_eq!((list.p99_ms, list.max_ms), (5.0, 100.0));
+    }
+
+    #[test]
+    fn slow_calls_are_kept_newest_first() {
+        let mut registry = Registry::new();
+        registry.record("launch_profile", Duration::from_millis(1500), 0);
+        registry.record("check_all_proxies", Duration::from_secs(4), 0);
+        let slow = registry.report().slow;
+        assert_eq!(slow.len(), 2);
+        assert_eq!(slow[0].command, "check_all_proxies");
+    }
+
+    #[test]
+    fn every_async_command_is_left_out() {
+        let mut declared: Vec<&str> = include_str!("commands.rs")
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub async fn "))
+            .filter_map(|rest| rest.split('(').next())
+            .collect();
+        let mut listed = ASYNC_COMMANDS.to_vec();
+        declared.sort_unstable();
+        listed.sort_unstable();
+        assert_eq!(declared, listed);
+    }
+
+    #[test]
+    fn payload_size_counts_serialized_json() {
+        let body = InvokeBody::Json(serde_json::json!({ "id": "abc" }));
+        assert_eq!(payload_size(&body), r#"{"id":"abc"}"#.len() as u64);
+        assert_eq!(payload_size(&InvokeBody::Raw(vec![0; 7])), 7);
+    }
+}
@@ -0,0 +1,609 @@
+// ── Manifold mouse motion ─────────────────────────────────────────────────────
+//
+// Cubic-Bézier mouse paths, generated in Rust so the geometry and timing of
+// a move are a pure function of (start, end, MouseConfig, seed): the same
+// inputs give the same path on every run, which the replay engine relies on
+// and which makes the shape testable.  The bridge walks the points it is
+// sent (`move_path`) instead of drawing its own.
+//
+// The model follows HumanMouse in human/index.ts:
+//   • speed from a cruise / burst / hesitation mixture around the base speed
+//   • moves over 120 px split into 2–4 Bézier segments through perturbed
+//     waypoints, control points offset by a log-normal share of the distance
+//   • ~60 Hz steps with log-normal spacing and self-exciting micro-pauses
+//   • optional micro-jitter and overshoot-then-correct
+// HumanMouse also widens every draw by its live entropy multiplier; a
+// generated path is drawn at multiplier 1.
+//
+// `MousePath::trace` is the same move as a `MouseTraceSegment`
+// (human/trace/types.ts), the format the trace calibrator consumes.
+//
+// Scrolls are generated the same way (`scroll_pattern`): a scroll is a run
+// of wheel flicks, each a momentum-decaying burst of ticks, with reading
+// pauses after roughly every viewport of content and the odd upward
+// correction after a flick that went too far.  How long a person's flicks
+// are and how fast they read is a habit, so those come from the seed
+// alone (`ScrollSignature`); the ticks of one scroll come from the seed and
+// the scroll's sequence number.
+
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::human::{MouseConfig, ScrollConfig};
+
+/// Moves shorter than this are a single point.
+const MIN_MOVE_PX: f64 = 1.0;
+/// Mouse events per second while moving.
+const EVENT_RATE_HZ: f64 = 60.0;
+const MIN_STEPS: usize = 8;
+const MAX_STEPS: usize = 200;
+/// Baseline and ceiling of the per-step micro-pause probability.
+const PAUSE_PROB_BASE: f64 = 0.12;
+const PAUSE_PROB_MAX: f64 = 0.55;
+/// Chance of stopping to read once a viewport of content has gone by.
+const READING_PAUSE_PROB: f64 = 0.7;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PathPoint {
+    pub x: f64,
+    pub y: f64,
+    /// Milliseconds since the move started.
+    pub t: f64,
+}
+
+/// A generated move: the start position at `t = 0`, then every
+/// `mousemove` up to and including the target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MousePath {
+    pub points: Vec<PathPoint>,
+    /// Whether the path passes the target and comes back to it.
+    pub overshoot: bool,
+}
+
+/// Parallel arrays, one entry per point (`MouseTraceSegment`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MouseTrace {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub t: Vec<f64>,
+    /// px/s over the step ending at the point; 0 at the start.
+    pub velocity: Vec<f64>,
+    /// Turn (radians) between the steps into and out of the point; 0 at
+    /// both ends.
+    pub curvature: Vec<f64>,
+}
+
+impl MousePath {
+    pub fn trace(&self) -> MouseTrace {
+        let pts = &self.points;
+        let velocity = (0..pts.len())
+            .map(|i| {
+                if i == 0 {
+                    return 0.0;
+                }
+                let (a, b) = (pts[i - 1], pts[i]);
+                let dt = b.t - a.t;
+                if dt > 0.0 {
+                    (b.x - a.x).hypot(b.y - a.y) / dt * 1000.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let curvature = (0..pts.len())
+            .map(|i| {
+                if i == 0 || i + 1 == pts.len() {
+                    return 0.0;
+                }
+                let (a, b, c) = (pts[i - 1], pts[i], pts[i + 1]);
+                turn_angle((b.x - a.x, b.y - a.y), (c.x - b.x, c.y - b.y))
+            })
+            .collect();
+        MouseTrace {
+            x: pts.iter().map(|p| p.x).collect(),
+            y: pts.iter().map(|p| p.y).collect(),
+            t: pts.iter().map(|p| p.t).collect(),
+            velocity,
+            curvature,
+        }
+    }
+}
+
+// ── Generation ────────────────────────────────────────────────────────────────
+
+/// The timestamped path from `from` to `to` for `cfg`, deterministic in
+/// `seed`.
+pub fn generate_mouse_path(from: Point, to: Point, cfg: &MouseConfig, seed: u64) -> MousePath {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut points = vec![PathPoint {
+        x: from.x,
+        y: from.y,
+        t: 0.0,
+    }];
+    let d = dist(from, to);
+    if d < MIN_MOVE_PX {
+        return MousePath {
+            points,
+            overshoot: false,
+        };
+    }
+
+    let speed = velocity_mixture(&mut rng, cfg.base_speed_px_per_sec, cfg.speed_jitter);
+    let travel_sec = d / speed.max(1.0);
+    let steps = ((travel_sec * EVENT_RATE_HZ).round() as usize).clamp(MIN_STEPS, MAX_STEPS);
+    let curve = segmented_path(&mut rng, from, to, cfg.curve_scatter, steps);
+
+    let overshoot = (chance(&mut rng, cfg.overshoot_prob) && d > 20.0).then(|| {
+        let past = log_normal(&mut rng, cfg.overshoot_max_px * 0.4, 0.5);
+        Point {
+            x: to.x + (to.x - from.x) / d * past,
+            y: to.y + (to.y - from.y) / d * past,
+        }
+    });
+
+    let base_step_ms = travel_sec * 1000.0 / curve.len() as f64;
+    let mut pause_prob = PAUSE_PROB_BASE;
+    let mut t = 0.0;
+    for (i, p) in curve.iter().enumerate().skip(1) {
+        let last = i + 1 == curve.len();
+        let (mut x, mut y) = (p.x, p.y);
+        if !last && chance(&mut rng, cfg.micro_jitter_prob) {
+            let amp = log_normal(&mut rng, 1.0, 0.6);
+            let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+            x += amp * angle.cos();
+            y += amp * angle.sin();
+        }
+        t += log_normal(&mut rng, base_step_ms, 0.40);
+        points.push(PathPoint { x, y, t });
+
+        // Clustered micro-pauses: each one makes the next more likely
+        if !last && chance(&mut rng, pause_prob) {
+            t += heavy_tail_pause(&mut rng, 95.0, 0.55, 0.06);
+            pause_prob = (pause_prob * 2.8).min(PAUSE_PROB_MAX);
+        } else {
+            pause_prob = (pause_prob * 0.82).max(PAUSE_PROB_BASE);
+        }
+    }
+
+    if let Some(past) = overshoot {
+        t += log_normal(&mut rng, base_step_ms, 0.40);
+        points.push(PathPoint {
+            x: past.x,
+            y: past.y,
+            t,
+        });
+        t += log_normal(&mut rng, 70.0, 0.45);
+        points.push(PathPoint {
+            x: to.x,
+            y: to.y,
+            t,
+        });
+    }
+
+    MousePath {
+        points,
+        overshoot: overshoot.is_some(),
+    }
+}
+
+/// Split long moves into 2–4 Bézier segments through waypoints pushed off
+/// the straight line, giving the several inflections of a real hand move.
+fn segmented_path(
+    rng: &mut SmallRng,
+    from: Point,
+    to: Point,
+    scatter: f64,
+    steps: usize,
+) -> Vec<Point> {
+    let d = dist(from, to);
+    let segments = if d > 400.0 {
+        rng.gen_range(3..=4)
+    } else if d > 120.0 {
+        rng.gen_range(2..=3)
+    } else {
+        1
+    };
+    if segments == 1 {
+        let (c1, c2) = control_points(rng, from, to, scatter);
+        return sample_curve(from, c1, c2, to, steps.max(MIN_STEPS));
+    }
+
+    let (dir_x, dir_y) = ((to.x - from.x) / d, (to.y - from.y) / d);
+    let mut waypoints = vec![from];
+    for i in 1..segments {
+        let f = i as f64 / segments as f64;
+        let off = log_normal(rng, d * 0.04, 0.5) * sign(rng);
+        waypoints.push(Point {
+            x: lerp(from.x, to.x, f) - dir_y * off,
+            y: lerp(from.y, to.y, f) + dir_x * off,
+        });
+    }
+    waypoints.push(to);
+
+    let mut out = Vec::new();
+    for (i, pair) in waypoints.windows(2).enumerate() {
+        let (a, b) = (pair[0], pair[1]);
+        let seg_steps = ((dist(a, b) / d * steps as f64).round() as usize).max(6);
+        let (c1, c2) = control_points(rng, a, b, scatter);
+        let pts = sample_curve(a, c1, c2, b, seg_steps);
+        // Each segment starts where the previous one ended
+        out.extend(pts.into_iter().skip(usize::from(i > 0)));
+    }
+    out
+}
+
+/// Control points offset perpendicular to the chord by a log-normal share of
+/// its length, at log-normal positions along it, so the curvature histogram
+/// isn't centred on the midpoint.
+fn control_points(rng: &mut SmallRng, a: Point, b: Point, scatter: f64) -> (Point, Point) {
+    let d = dist(a, b);
+    let len = if d > 0.0 { d } else { 1.0 };
+    let (px, py) = (-(b.y - a.y) / len, (b.x - a.x) / len);
+
+    let amp1 = log_normal(rng, 0.3 * scatter * d, 0.55) * sign(rng);
+    let amp2 = log_normal(rng, 0.3 * scatter * d, 0.55) * sign(rng);
+    let t1 = log_normal(rng, 0.32, 0.25).clamp(0.12, 0.48);
+    let t2 = log_normal(rng, 0.65, 0.20).clamp(0.52, 0.88);
+    (
+        Point {
+            x: lerp(a.x, b.x, t1) + px * amp1,
+            y: lerp(a.y, b.y, t1) + py * amp1,
+        },
+        Point {
+            x: lerp(a.x, b.x, t2) + px * amp2,
+            y: lerp(a.y, b.y, t2) + py * amp2,
+        },
+    )
+}
+
+/// `steps + 1` points of the curve, both ends included.
+fn sample_curve(p0: Point, p1: Point, p2: Point, p3: Point, steps: usize) -> Vec<Point> {
+    (0..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let mt = 1.0 - t;
+            let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+            Point {
+                x: a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+                y: a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+            }
+        })
+        .collect()
+}
+
+// ── Scroll ────────────────────────────────────────────────────────────────────
+
+/// One wheel event, followed by `delay_ms` of nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScrollTick {
+    pub delta_y: f64,
+    pub delay_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScrollPattern {
+    pub ticks: Vec<ScrollTick>,
+    /// Flicks that overshot and were scrolled back.
+    pub corrections: u32,
+    pub reading_pauses: u32,
+}
+
+#[cfg(test)]
+impl ScrollPattern {
+    /// Net distance scrolled.
+    fn total_delta_y(&self) -> f64 {
+        self.ticks.iter().map(|t| t.delta_y).sum()
+    }
+}
+
+/// The scrolling habits of one seed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScrollSignature {
+    /// Typical flick length as a share of the viewport height.
+    pub flick_share: f64,
+    /// Reading pause per pixel of viewport height, ms.
+    pub reading_ms_per_px: f64,
+    /// Multiplier on the config's correction probability.
+    pub correction_bias: f64,
+}
+
+impl ScrollSignature {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed ^ 0x5c_0011);
+        Self {
+            flick_share: rng.gen_range(0.25..0.6),
+            reading_ms_per_px: rng.gen_range(1.0..3.5),
+            correction_bias: rng.gen_range(0.5..1.5),
+        }
+    }
+}
+
+/// Wheel ticks scrolling `total_delta_y` px (negative = up) over a viewport
+/// `viewport_height` px tall, for the `nth` scroll of `seed`.  The ticks add
+/// up to exactly `total_delta_y`.  A `viewport_height` of 0 leaves reading
+/// pauses out.
+pub fn scroll_pattern(
+    total_delta_y: f64,
+    viewport_height: f64,
+    cfg: &ScrollConfig,
+    seed: u64,
+    nth: u64,
+) -> ScrollPattern {
+    let sig = ScrollSignature::from_seed(seed);
+    let mut rng = SmallRng::seed_from_u64(seed ^ nth.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    let mut pattern = ScrollPattern {
+        ticks: Vec::new(),
+        corrections: 0,
+        reading_pauses: 0,
+    };
+    let direction = total_delta_y.signum();
+    let mut remaining = total_delta_y.abs();
+    let flick_mean = if viewport_height > 0.0 {
+        viewport_height * sig.flick_share
+    } else {
+        cfg.initial_velocity_px * 4.0
+    };
+    let mut since_pause = 0.0;
+
+    while remaining > 0.0 {
+        let flick = log_normal(&mut rng, flick_mean, 0.35).min(remaining);
+        let overshoot = (chance(&mut rng, cfg.overshoot_prob * sig.correction_bias)
+            && cfg.overshoot_max_px > 10.0)
+            .then(|| rng.gen_range(10.0..cfg.overshoot_max_px));
+
+        let start = cfg.initial_velocity_px * (1.0 + 0.2 * gauss(&mut rng));
+        push_momentum(
+            &mut pattern.ticks,
+            &mut rng,
+            cfg,
+            flick + overshoot.unwrap_or(0.0),
+            direction,
+            start,
+        );
+        if let Some(back) = overshoot {
+            if let Some(last) = pattern.ticks.last_mut() {
+                last.delay_ms += rng.gen_range(80.0..200.0);
+            }
+            let start = rng.gen_range(cfg.min_velocity_px * 2.0..=cfg.initial_velocity_px * 0.5);
+            push_momentum(&mut pattern.ticks, &mut rng, cfg, back, -direction, start);
+            pattern.corrections += 1;
+        }
+        remaining -= flick;
+        since_pause += flick;
+
+        let Some(last) = pattern.ticks.last_mut() else {
+            break;
+        };
+        if remaining <= 0.0 {
+            break;
+        }
+        if viewport_height > 0.0
+            && since_pause >= viewport_height * rng.gen_range(0.6..1.0)
+            && chance(&mut rng, READING_PAUSE_PROB)
+        {
+            last.delay_ms += log_normal(&mut rng, viewport_height * sig.reading_ms_per_px, 0.5);
+            pattern.reading_pauses += 1;
+            since_pause = 0.0;
+        } else {
+            // Finger lifted and back on the wheel
+            last.delay_ms += log_normal(&mut rng, 220.0, 0.4);
+        }
+    }
+    pattern
+}
+
+/// Ticks covering `distance` px, starting at `velocity` px per tick and
+/// decaying by the config's momentum factor.
+fn push_momentum(
+    ticks: &mut Vec<ScrollTick>,
+    rng: &mut SmallRng,
+    cfg: &ScrollConfig,
+    distance: f64,
+    direction: f64,
+    velocity: f64,
+) {
+    let mut left = distance;
+    let mut velocity = velocity.max(cfg.min_velocity_px);
+    while left > 0.0 {
+        let tick = velocity.min(left);
+        left -= tick;
+        velocity = (velocity * cfg.momentum_decay).max(cfg.min_velocity_px);
+        let delay_ms = if cfg.tick_max_ms > cfg.tick_min_ms {
+            rng.gen_range(cfg.tick_min_ms as f64..cfg.tick_max_ms as f64)
+        } else {
+            cfg.tick_min_ms as f64
+        };
+        ticks.push(ScrollTick {
+            delta_y: tick * direction,
+            delay_ms,
+        });
+    }
+}
+
+// ── Sampling helpers ──────────────────────────────────────────────────────────
+
+/// 70 % cruise around the base speed, 20 % focused burst, 10 % hesitation.
+fn velocity_mixture(rng: &mut SmallRng, base: f64, jitter: f64) -> f64 {
+    let r: f64 = rng.gen();
+    if r < 0.70 {
+        log_normal(rng, base, jitter * 0.8)
+    } else if r < 0.90 {
+        log_normal(rng, base * 1.55, jitter * 0.35)
+    } else {
+        log_normal(rng, base * 0.42, jitter * 1.4)
+    }
+}
+
+/// Log-normal with arithmetic mean `mean`.
+fn log_normal(rng: &mut SmallRng, mean: f64, sigma: f64) -> f64 {
+    let mu = mean.ln() - sigma * sigma / 2.0;
+    (mu + sigma * gauss(rng)).exp()
+}
+
+/// Mostly log-normal; with probability `tail` a Pareto(1.5) draw instead.
+fn heavy_tail_pause(rng: &mut SmallRng, mean: f64, sigma: f64, tail: f64) -> f64 {
+    if chance(rng, tail) {
+        let u: f64 = rng.gen::<f64>().max(0.001);
+        mean * u.powf(-1.0 / 1.5)
+    } else {
+        log_normal(rng, mean, sigma)
+    }
+}
+
+/// Standard normal via Box–Muller.
+fn gauss(rng: &mut SmallRng) -> f64 {
+    let u = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+}
+
+fn chance(rng: &mut SmallRng, p: f64) -> bool {
+    rng.gen::<f64>() < p
+}
+
+fn sign(rng: &mut SmallRng) -> f64 {
+    if chance(rng, 0.5) {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+fn dist(a: Point, b: Point) -> f64 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+fn turn_angle(u: (f64, f64), v: (f64, f64)) -> f64 {
+    let (mu, mv) = (u.0.hypot(u.1), v.0.hypot(v.1));
+    if mu == 0.0 || mv == 0.0 {
+        return 0.0;
+    }
+    let cos = (u.0 * v.0 + u.1 * v.1) / (mu * mv);
+    cos.clamp(-1.0, 1.0).acos()
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::human::BehaviorProfile;
+
+    const FROM: Point = Point { x: 100.0, y: 700.0 };
+    const TO: Point = Point { x: 900.0, y: 150.0 };
+
+    #[test]
+    fn paths_are_deterministic_per_seed_and_end_on_target() {
+        let cfg = MouseConfig::for_profile(BehaviorProfile::Normal);
+        let a = generate_mouse_path(FROM, TO, &cfg, 42);
+        assert_eq!(a, generate_mouse_path(FROM, TO, &cfg, 42));
+        assert_ne!(a, generate_mouse_path(FROM, TO, &cfg, 43));
+
+        let first = a.points[0];
+        let last = a.points.last().unwrap();
+        assert_eq!((first.x, first.y, first.t), (FROM.x, FROM.y, 0.0));
+        assert!((last.x - TO.x).hypot(last.y - TO.y) < 1e-9);
+        assert!(a.points.windows(2).all(|w| w[1].t > w[0].t));
+        assert!((MIN_STEPS..=MAX_STEPS * 2).contains(&a.points.len()));
+    }
+
+    #[test]
+    fn bot_profile_moves_short_distances_in_a_straight_line() {
+        let cfg = MouseConfig::for_profile(BehaviorProfile::Bot);
+        let to = Point { x: 180.0, y: 640.0 };
+        let path = generate_mouse_path(FROM, to, &cfg, 7);
+        assert!(!path.overshoot);
+        // Short moves are one segment, and without scatter it is the chord
+        let (dx, dy) = (to.x - FROM.x, to.y - FROM.y);
+        for p in &path.points {
+            let cross = (p.x - FROM.x) * dy - (p.y - FROM.y) * dx;
+            assert!(cross.abs() / dist(FROM, to) < 1e-6, "{p:?}");
+        }
+    }
+
+    #[test]
+    fn curved_profiles_bend_and_some_overshoot() {
+        let cfg = MouseConfig::for_profile(BehaviorProfile::Cautious);
+        let paths: Vec<_> = (0..200)
+            .map(|seed| generate_mouse_path(FROM, TO, &cfg, seed))
+            .collect();
+        let overshoots = paths.iter().filter(|p| p.overshoot).count();
+        assert!((20..100).contains(&overshoots), "{overshoots}/200");
+        assert!(paths
+            .iter()
+            .all(|p| p.trace().curvature.iter().any(|&c| c > 0.01)));
+    }
+
+    #[test]
+    fn trace_has_one_entry_per_point() {
+        let cfg = MouseConfig::for_profile(BehaviorProfile::Fast);
+        let path = generate_mouse_path(FROM, TO, &cfg, 3);
+        let trace = path.trace();
+        let n = path.points.len();
+        for len in [
+            trace.x.len(),
+            trace.y.len(),
+            trace.t.len(),
+            trace.velocity.len(),
+            trace.curvature.len(),
+        ] {
+            assert_eq!(len, n);
+        }
+        assert_eq!((trace.velocity[0], trace.curvature[n - 1]), (0.0, 0.0));
+        assert!(trace.velocity[1..].iter().all(|&v| v > 0.0));
+
+        let still = generate_mouse_path(FROM, FROM, &cfg, 3);
+        assert_eq!(still.points.len(), 1);
+        assert_eq!(still.trace().velocity, [0.0]);
+    }
+
+    #[test]
+    fn scroll_ticks_add_up_and_follow_the_seed() {
+        let cfg = ScrollConfig::for_profile(BehaviorProfile::Normal);
+        let a = scroll_pattern(3_000.0, 900.0, &cfg, 11, 0);
+        assert_eq!(a, scroll_pattern(3_000.0, 900.0, &cfg, 11, 0));
+        assert_ne!(a, scroll_pattern(3_000.0, 900.0, &cfg, 11, 1));
+        assert!((a.total_delta_y() - 3_000.0).abs() < 1e-6);
+        assert!(a.ticks.iter().all(|t| t.delay_ms > 0.0));
+        assert!(a.reading_pauses >= 1, "{a:?}");
+
+        let up = scroll_pattern(-1_200.0, 900.0, &cfg, 11, 2);
+        assert!((up.total_delta_y() + 1_200.0).abs() < 1e-6);
+        assert!(up.ticks.iter().filter(|t| t.delta_y < 0.0).count() > up.corrections as usize);
+
+        // Without a viewport nobody stops to read
+        let skim = scroll_pattern(3_000.0, 0.0, &cfg, 11, 0);
+        assert_eq!(skim.reading_pauses, 0);
+    }
+
+    #[test]
+    fn corrections_scroll_back_and_signatures_differ() {
+        let cfg = ScrollConfig::for_profile(BehaviorProfile::Cautious);
+        let patterns: Vec<_> = (0..100)
+            .map(|nth| scroll_pattern(2_000.0, 800.0, &cfg, 5, nth))
+            .collect();
+        let corrections: u32 = patterns.iter().map(|p| p.corrections).sum();
+        assert!(corrections > 0);
+        assert!(patterns
+            .iter()
+            .filter(|p| p.corrections > 0)
+            .all(|p| p.ticks.iter().any(|t| t.delta_y < 0.0)));
+
+        let bot = ScrollConfig::for_profile(BehaviorProfile::Bot);
+        assert_eq!(scroll_pattern(2_000.0, 800.0, &bot, 5, 0).corrections, 0);
+        assert_ne!(ScrollSignature::from_seed(5), ScrollSignature::from_seed(6));
+    }
+}
@@ -0,0 +1,592 @@
+// ── Manifold execution node pool ──────────────────────────────────────────────
+//
+// launch_profile drives one bridge at a time, here or on a chosen node.  The
+// pool runs further launches on the registered execution nodes alongside
+// it.  Each queued launch goes to the enabled node that was online at its
+// latest check and has the most room: the fewest running bridges per bridge
+// it can hold (`capacity` pool slots, plus the launch_profile bridge when
+// that runs there too), then the lowest latency.  A launch waits in the
+// queue while every node is full or offline, and starts when one frees up.
+//
+// The pool knows which node runs which profile.  When a node drops out —
+// its health check fails, or the `ssh` carrying a launch loses the
+// connection — its launches are stopped and put back at the front of the
+// queue for the other nodes, up to MAX_LAUNCH_ATTEMPTS starts in all.
+//
+// Pool launches run headless and, like agent launches, record no clock
+// samples, behavior audit or bandwidth.  Placements and the queue live in
+// memory, like the launch_profile bridge's pid.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::nodes::{ExecutionNode, NodeHealth, NodeKind};
+
+/// How often nodes running or waiting for pool launches are checked.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Starts a launch gets, failovers included, before it is given up.
+pub const MAX_LAUNCH_ATTEMPTS: u32 = 3;
+/// `ssh`'s exit status for its own errors, a dropped connection among them.
+pub const SSH_CONNECTION_LOST: i32 = 255;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// A profile to launch, as `launch_profile` takes it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    pub profile_id: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub target_domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedLaunch {
+    #[serde(flatten)]
+    pub request: LaunchRequest,
+    pub queued_at: DateTime<Utc>,
+    /// Starts so far.
+    pub attempts: u32,
+    /// Why the previous start ended, for a launch queued again.
+    pub last_error: Option<String>,
+}
+
+impl QueuedLaunch {
+    pub fn new(request: LaunchRequest) -> Self {
+        Self {
+            request,
+            queued_at: Utc::now(),
+            attempts: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// A launch running, or starting, on a node.
+#[derive(Debug, Clone, Serialize)]
+pub struct Placement {
+    #[serde(flatten)]
+    pub request: LaunchRequest,
+    pub node_id: String,
+    pub node_name: String,
+    /// The node's port slot (`nodes`); 0 is the launch_profile bridge's.
+    pub slot: u16,
+    /// Where the bridge's WebSocket is reached from here; `None` while the
+    /// launch is starting.
+    pub ws_host: String,
+    pub ws_port: Option<u16>,
+    /// The bridge serves wss:// (agent nodes with `expose_ws`).
+    pub ws_tls: bool,
+    pub started_at: DateTime<Utc>,
+    /// Starts so far, this one included.
+    pub attempts: u32,
+    /// The node, with its agent token.
+    #[serde(skip)]
+    pub node: ExecutionNode,
+    /// The `ssh` process of a launch: the bridge's on an SSH node, the
+    /// WebSocket forward's on an agent node.
+    #[serde(skip)]
+    pub pid: Option<u32>,
+    #[serde(skip)]
+    pub ws_secret: Option<String>,
+    /// Tells this start from a later one of the same profile.
+    #[serde(skip)]
+    claim: u64,
+}
+
+impl Placement {
+    pub fn new(request: LaunchRequest, node: ExecutionNode, slot: u16) -> Self {
+        Self {
+            request,
+            node_id: node.id.clone(),
+            node_name: node.spec.name.clone(),
+            slot,
+            ws_host: "localhost".into(),
+            ws_port: None,
+            ws_tls: false,
+            started_at: Utc::now(),
+            attempts: 1,
+            node,
+            pid: None,
+            ws_secret: None,
+            claim: 0,
+        }
+    }
+
+    /// Point `ws_host` and `ws_port` where this machine reaches the bridge:
+    /// an exposed agent bridge on the node over TLS, every other one on
+    /// `local_ws_port` here.  Returns whether that takes an SSH forward
+    /// (`nodes::start_ws_forward`), as an agent bridge on the node's
+    /// loopback does; an SSH node's `ssh` forwards its own.
+    pub fn route_ws(&mut self, local_ws_port: u16) -> bool {
+        let spec = &self.node.spec;
+        let agent = spec.kind == NodeKind::Agent;
+        self.ws_tls = agent && spec.expose_ws;
+        if self.ws_tls {
+            self.ws_host = spec.host.clone();
+            self.ws_port = Some(crate::nodes::slot_ports(spec, self.slot).0);
+            return false;
+        }
+        self.ws_host = "localhost".into();
+        self.ws_port = Some(local_ws_port);
+        agent
+    }
+
+    /// The bridge's WebSocket URL with a one-time connection ticket; `None`
+    /// while the launch is starting.
+    pub fn bridge_url(&self) -> Option<String> {
+        Some(crate::bridge_auth::bridge_url(
+            &self.ws_host,
+            self.ws_port?,
+            self.ws_tls,
+            self.ws_secret.as_deref(),
+        ))
+    }
+
+    /// The launch put back in the queue after `why`.
+    pub fn requeued(&self, why: String) -> QueuedLaunch {
+        QueuedLaunch {
+            request: self.request.clone(),
+            queued_at: Utc::now(),
+            attempts: self.attempts,
+            last_error: Some(why),
+        }
+    }
+}
+
+/// How busy one node is.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeLoad {
+    pub node_id: String,
+    pub name: String,
+    /// Pool launches on the node, starting ones included.
+    pub running: u16,
+    pub capacity: u16,
+    /// Whether the launch_profile bridge runs there too.
+    pub foreground: bool,
+    /// Enabled and online at its latest check.
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolStatus {
+    pub nodes: Vec<NodeLoad>,
+    pub placements: Vec<Placement>,
+    pub queue: Vec<QueuedLaunch>,
+}
+
+// ── Scheduling ────────────────────────────────────────────────────────────────
+
+fn available(node: &ExecutionNode) -> bool {
+    node.enabled && node.health.as_ref().is_some_and(|h| h.online)
+}
+
+/// The node the next launch should go to and its free slot, or `None` when
+/// every available node is full.  `foreground_node` is where the
+/// launch_profile bridge runs, if on a node.
+pub fn pick<'a>(
+    nodes: &'a [ExecutionNode],
+    placements: &[&Placement],
+    foreground_node: Option<&str>,
+) -> Option<(&'a ExecutionNode, u16)> {
+    nodes
+        .iter()
+        .filter(|n| available(n))
+        .filter_map(|n| {
+            let used: BTreeSet<u16> = placements
+                .iter()
+                .filter(|p| p.node_id == n.id)
+                .map(|p| p.slot)
+                .collect();
+            let slot = (1..=n.spec.capacity).find(|s| !used.contains(s))?;
+            let load = used.len() as u64 + u64::from(foreground_node == Some(n.id.as_str()));
+            Some((n, slot, load, u64::from(n.spec.capacity) + 1))
+        })
+        // load_a / slots_a against load_b / slots_b, without division
+        .min_by(|(a, _, load_a, slots_a), (b, _, load_b, slots_b)| {
+            (load_a * slots_b)
+                .cmp(&(load_b * slots_a))
+                .then_with(|| latency(a).cmp(&latency(b)))
+                .then_with(|| a.spec.name.cmp(&b.spec.name))
+        })
+        .map(|(n, slot, _, _)| (n, slot))
+}
+
+fn latency(node: &ExecutionNode) -> u64 {
+    node.health
+        .as_ref()
+        .and_then(|h| h.latency_ms)
+        .unwrap_or(u64::MAX)
+}
+
+/// Check `nodes` concurrently.
+pub async fn check_all(nodes: Vec<ExecutionNode>) -> Vec<(ExecutionNode, NodeHealth)> {
+    let mut checks = tokio::task::JoinSet::new();
+    for node in nodes {
+        checks.spawn(async move {
+            let health = crate::nodes::check(&node).await;
+            (node, health)
+        });
+    }
+    let mut results = Vec::new();
+    while let Some(result) = checks.join_next().await {
+        if let Ok(result) = result {
+            results.push(result);
+        }
+    }
+    results
+}
+
+// ── Pool ──────────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct PoolState {
+    /// By profile id.
+    placements: BTreeMap<String, Placement>,
+    queue: VecDeque<QueuedLaunch>,
+    next_claim: u64,
+}
+
+/// The queue and placements of pool launches.
+#[derive(Default)]
+pub struct NodePool {
+    state: Mutex<PoolState>,
+}
+
+impl NodePool {
+    /// Queue a launch; a profile already queued or placed is refused.
+    pub fn enqueue(&self, launch: QueuedLaunch) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let id = &launch.request.profile_id;
+        if state.placements.contains_key(id)
+            || state.queue.iter().any(|q| &q.request.profile_id == id)
+        {
+            return Err(ManifoldError::InvalidArg(format!(
+                "profile {id} is already in the node pool"
+            )));
+        }
+        state.queue.push_back(launch);
+        Ok(())
+    }
+
+    /// Put a launch back: at the front after a failover, so it doesn't wait
+    /// behind launches that never started, at the back after a failed start.
+    pub fn requeue(&self, launch: QueuedLaunch, front: bool) {
+        let mut state = self.state.lock().unwrap();
+        if front {
+            state.queue.push_front(launch);
+        } else {
+            state.queue.push_back(launch);
+        }
+    }
+
+    /// Drop a queued launch; false when the profile isn't queued.
+    pub fn cancel(&self, profile_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(|q| q.request.profile_id != profile_id);
+        state.queue.len() != before
+    }
+
+    /// Whether the profile is queued or placed.
+    pub fn contains(&self, profile_id: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state.placements.contains_key(profile_id)
+            || state
+                .queue
+                .iter()
+                .any(|q| q.request.profile_id == profile_id)
+    }
+
+    pub fn placement(&self, profile_id: &str) -> Option<Placement> {
+        self.state
+            .lock()
+            .unwrap()
+            .placements
+            .get(profile_id)
+            .cloned()
+    }
+
+    /// Take the first queued launch and reserve a slot for it on the node
+    /// `pick` chooses.  `None` when the queue is empty or every node full.
+    pub fn claim_next(
+        &self,
+        nodes: &[ExecutionNode],
+        foreground_node: Option<&str>,
+    ) -> Option<Placement> {
+        let mut state = self.state.lock().unwrap();
+        let launch = state.queue.front()?;
+        let placed: Vec<&Placement> = state.placements.values().collect();
+        let (node, slot) = pick(nodes, &placed, foreground_node)?;
+        let mut placement = Placement::new(launch.request.clone(), node.clone(), slot);
+        placement.attempts = launch.attempts + 1;
+        state.queue.pop_front();
+        state.next_claim += 1;
+        placement.claim = state.next_claim;
+        state
+            .placements
+            .insert(placement.request.profile_id.clone(), placement.clone());
+        Some(placement)
+    }
+
+    /// Record a claimed launch as started.  False when its claim was taken
+    /// back meanwhile (stopped or failed over); the caller stops the bridge.
+    pub fn started(&self, placement: &Placement) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.placements.get_mut(&placement.request.profile_id) {
+            Some(held) if held.claim == placement.claim => {
+                *held = placement.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Take a profile's placement out of the pool.
+    pub fn remove(&self, profile_id: &str) -> Option<Placement> {
+        self.state.lock().unwrap().placements.remove(profile_id)
+    }
+
+    /// Take `placement` out of the pool if it still holds its claim.
+    pub fn release(&self, placement: &Placement) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let id = &placement.request.profile_id;
+        if state.placements.get(id).map(|p| p.claim) != Some(placement.claim) {
+            return false;
+        }
+        state.placements.remove(id);
+        true
+    }
+
+    /// Take every placement on `node_id` out of the pool.
+    pub fn take_node(&self, node_id: &str) -> Vec<Placement> {
+        let mut state = self.state.lock().unwrap();
+        let ids: Vec<String> = state
+            .placements
+            .values()
+            .filter(|p| p.node_id == node_id)
+            .map(|p| p.request.profile_id.clone())
+            .collect();
+        ids.iter()
+            .filter_map(|id| state.placements.remove(id))
+            .collect()
+    }
+
+    /// Take every placement out of the pool, leaving the queue.
+    pub fn take_all(&self) -> Vec<Placement> {
+        std::mem::take(&mut self.state.lock().unwrap().placements)
+            .into_values()
+            .collect()
+    }
+
+    pub fn clear_queue(&self) {
+        self.state.lock().unwrap().queue.clear();
+    }
+
+    /// Nothing queued or placed.
+    pub fn is_idle(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.placements.is_empty() && state.queue.is_empty()
+    }
+
+    pub fn has_queue(&self) -> bool {
+        !self.state.lock().unwrap().queue.is_empty()
+    }
+
+    /// Ids of the nodes running pool launches.
+    pub fn busy_nodes(&self) -> BTreeSet<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .placements
+            .values()
+            .map(|p| p.node_id.clone())
+            .collect()
+    }
+
+    pub fn status(&self, nodes: &[ExecutionNode], foreground_node: Option<&str>) -> PoolStatus {
+        let state = self.state.lock().unwrap();
+        PoolStatus {
+            nodes: nodes
+                .iter()
+                .map(|n| NodeLoad {
+                    node_id: n.id.clone(),
+                    name: n.spec.name.clone(),
+                    running: state
+                        .placements
+                        .values()
+                        .filter(|p| p.node_id == n.id)
+                        .count() as u16,
+                    capacity: n.spec.capacity,
+                    foreground: foreground_node == Some(n.id.as_str()),
+                    available: available(n),
+                })
+                .collect(),
+            placements: state.placements.values().cloned().collect(),
+            queue: state.queue.iter().cloned().collect(),
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::{NodeKind, NodeSpec};
+
+    fn node(id: &str, capacity: u16, latency_ms: u64) -> ExecutionNode {
+        let spec: NodeSpec = serde_json::from_value(serde_json::json!({
+            "name": id,
+            "kind": "ssh",
+            "host": "10.0.0.5",
+            "capacity": capacity,
+        }))
+        .unwrap();
+        assert_eq!(spec.kind, NodeKind::Ssh);
+        ExecutionNode {
+            id: id.into(),
+            spec,
+            enabled: true,
+            health: Some(NodeHealth {
+                online: true,
+                latency_ms: Some(latency_ms),
+                error: None,
+                checked_at: Utc::now(),
+            }),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn launch(profile_id: &str) -> QueuedLaunch {
+        QueuedLaunch::new(LaunchRequest {
+            profile_id: profile_id.into(),
+            url: None,
+            target_domain: None,
+        })
+    }
+
+    #[test]
+    fn launches_go_to_the_node_with_the_most_room() {
+        let nodes = vec![node("big", 3, 80), node("small", 1, 20)];
+        let pool = NodePool::default();
+        for id in ["p1", "p2", "p3", "p4", "p5"] {
+            pool.enqueue(launch(id)).unwrap();
+        }
+
+        // Both idle: the faster one first, then "big" while it has less
+        // load per slot
+        let claimed: Vec<(String, u16)> = std::iter::from_fn(|| pool.claim_next(&nodes, None))
+            .map(|p| (p.node_id, p.slot))
+            .collect();
+        assert_eq!(
+            claimed,
+            vec![
+                ("small".to_string(), 1),
+                ("big".to_string(), 1),
+                ("big".to_string(), 2),
+                ("big".to_string(), 3),
+            ]
+        );
+        // Every slot is taken; p5 waits
+        assert!(pool.has_queue());
+        assert_eq!(pool.status(&nodes, None).nodes[0].running, 3);
+
+        // A freed slot is handed out again
+        let p2 = pool.remove("p2").unwrap();
+        let next = pool.claim_next(&nodes, None).unwrap();
+        assert_eq!((next.node_id.as_str(), next.slot), ("big", p2.slot));
+        assert_eq!(next.request.profile_id, "p5");
+    }
+
+    #[test]
+    fn offline_disabled_and_foreground_nodes_are_weighed() {
+        let mut nodes = vec![node("a", 1, 10), node("b", 1, 50)];
+        assert_eq!(pick(&nodes, &[], None).unwrap().0.id, "a");
+        // The launch_profile bridge on "a" leaves "b" with more room
+        assert_eq!(pick(&nodes, &[], Some("a")).unwrap().0.id, "b");
+
+        nodes[1].health.as_mut().unwrap().online = false;
+        assert_eq!(pick(&nodes, &[], Some("a")).unwrap().0.id, "a");
+        nodes[0].enabled = false;
+        assert!(pick(&nodes, &[], None).is_none());
+        nodes[0].enabled = true;
+        nodes[0].health = None;
+        assert!(pick(&nodes, &[], None).is_none(), "never checked");
+    }
+
+    #[test]
+    fn failed_over_launches_go_back_to_the_front_of_the_queue() {
+        let nodes = vec![node("a", 2, 10)];
+        let pool = NodePool::default();
+        for id in ["p1", "p2", "p3"] {
+            pool.enqueue(launch(id)).unwrap();
+        }
+        assert!(pool.enqueue(launch("p1")).is_err());
+        let first = pool.claim_next(&nodes, None).unwrap();
+        pool.claim_next(&nodes, None).unwrap();
+        assert!(pool.contains("p1") && pool.contains("p3"));
+        assert_eq!(pool.busy_nodes().len(), 1);
+
+        let moved = pool.take_node("a");
+        assert_eq!(moved.len(), 2);
+        for placement in moved.iter().rev() {
+            pool.requeue(placement.requeued("node a went offline".into()), true);
+        }
+        let queue = pool.status(&nodes, None).queue;
+        let order: Vec<&str> = queue
+            .iter()
+            .map(|q| q.request.profile_id.as_str())
+            .collect();
+        assert_eq!(order, ["p1", "p2", "p3"]);
+        assert_eq!(queue[0].attempts, 1);
+
+        // The first start's claim is gone: it can't register or release
+        assert!(!pool.started(&first));
+        let again = pool.claim_next(&nodes, None).unwrap();
+        assert_eq!(again.attempts, 2);
+        assert!(!pool.release(&first));
+        assert!(pool.started(&again));
+        assert!(pool.release(&again));
+
+        assert!(pool.cancel("p3"));
+        assert!(!pool.cancel("p3"));
+    }
+    #[test]
+    fn pool_urls_reach_agent_bridges() {
+        let mut agent = node("a1", 2, 10);
+        agent.spec.kind = NodeKind::Agent;
+        agent.spec.agent_url = Some("https://10.0.0.5:7000".into());
+        assert!(agent.spec.validate().is_ok());
+        let mut placement = Placement::new(launch("p1").request, agent, 2);
+        placement.ws_secret = Some(crate::bridge_auth::new_secret());
+        assert!(placement.bridge_url().is_none(), "still starting");
+
+        // By default the bridge keeps to the node's loopback and is reached
+        // through the SSH forward, which the listener stands in for
+        let forward = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let local_port = forward.local_addr().unwrap().port();
+        assert!(placement.route_ws(local_port));
+        let url = url::Url::parse(&placement.bridge_url().unwrap()).unwrap();
+        assert_eq!(url.scheme(), "ws");
+        assert!(url.query().unwrap().starts_with("nonce="));
+        std::net::TcpStream::connect((url.host_str().unwrap(), url.port().unwrap())).unwrap();
+
+        // An exposed bridge is reached on the node, over TLS only
+        placement.node.spec.expose_ws = true;
+        assert!(!placement.route_ws(local_port));
+        let url = placement.bridge_url().unwrap();
+        assert!(url.starts_with("wss://10.0.0.5:8768/?nonce="), "{url}");
+
+        // An SSH node's own ssh forwards its bridge
+        let mut ssh = Placement::new(launch("p2").request, node("s1", 1, 10), 1);
+        assert!(!ssh.route_ws(local_port));
+        assert!(ssh.bridge_url().unwrap().starts_with("ws://localhost:"));
+    }
+}
@@ -0,0 +1,941 @@
+// ── Manifold execution nodes ──────────────────────────────────────────────────
+//
+// Browsers normally run on this machine.  An execution node is another host
+// a profile can be launched on instead, of one of two kinds:
+//
+//   ssh     Manifold starts the bridge itself: `ssh` runs `bridge_command`
+//           in `bridge_dir` on the node and the launch config is sent as the
+//           first line of its stdin, so it never shows on a command line.
+//           The bridge's WebSocket stays on the node's loopback and is
+//           forwarded to the local bridge port; the TLS bridge, when the
+//           profile uses it, is forwarded back to `tls_bridge_port` on the
+//           node.  The frontend can't tell it from a local launch.
+//
+//   agent   A pre-provisioned agent starts bridges on request over HTTPS:
+//
+//             GET  {agent_url}/health   2xx while the agent can launch
+//             POST {agent_url}/launch   body: the launch config; 2xx once the
+//                                       bridge printed BRIDGE_READY
+//             POST {agent_url}/stop     body: {"profileId": ...}; stop the
+//                                       bridge it launched for that profile
+//
+//           each with `Authorization: Bearer <agent_token>`.  The bridge
+//           listens on the config's `wsPort` on the node's loopback, reached
+//           through an `ssh -N -L` forward with the node's SSH login.  With
+//           `expose_ws` it listens on every interface instead and serves
+//           wss:// (`wsTls`) with the certificate the agent passes in
+//           MANIFOLD_WS_TLS_CERT / MANIFOLD_WS_TLS_KEY; a ticket never
+//           crosses the network in plain ws://.  The node provides its own
+//           TLS bridge on the config's `tlsBridgePort`.  There's no stdout to
+//           read, so agent launches record no clock samples, behavior audit
+//           or bandwidth.
+//
+// A node runs up to 1 + `capacity` bridges at once, each in its own port
+// slot: slot 0 (`ws_port`, `tls_bridge_port`) is the launch_profile bridge,
+// slots 1..=capacity take the pool's launches (node_pool.rs) on the ports
+// right after.
+//
+// Either way a remote browser can't reach tunnels that only listen on this
+// machine (SSH proxies, chains, VPNs); profiles routed through one are
+// refused, and there's no display to show a headed browser on, so remote
+// browsers run headless.
+
+use std::net::{IpAddr, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::launch_config::LaunchConfig;
+use crate::launch_mode::LaunchMode;
+
+/// How long a health check may take.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long an agent gets to bring a bridge up.
+const AGENT_LAUNCH_TIMEOUT: Duration = Duration::from_secs(90);
+/// How long an agent bridge's WebSocket forward may take to come up.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(15);
+/// Most pool launches one node may take.
+const MAX_CAPACITY: u16 = 64;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Ssh,
+    Agent,
+}
+
+impl std::fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ssh => write!(f, "ssh"),
+            Self::Agent => write!(f, "agent"),
+        }
+    }
+}
+
+impl std::str::FromStr for NodeKind {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ssh" => Ok(Self::Ssh),
+            "agent" => Ok(Self::Agent),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown node kind: {other:?}"
+            ))),
+        }
+    }
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_bridge_dir() -> String {
+    "manifold".into()
+}
+
+fn default_bridge_command() -> String {
+    "npx tsx playwright-bridge/index.ts".into()
+}
+
+fn default_ws_port() -> u16 {
+    8766
+}
+
+fn default_tls_bridge_port() -> u16 {
+    8443
+}
+
+fn default_capacity() -> u16 {
+    1
+}
+
+/// How to reach a node and run bridges on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeSpec {
+    pub name: String,
+    pub kind: NodeKind,
+    pub host: String,
+    #[serde(default = "default_ssh_port")]
+    pub ssh_port: u16,
+    #[serde(default)]
+    pub ssh_user: Option<String>,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Base URL of an agent node's API.
+    #[serde(default)]
+    pub agent_url: Option<String>,
+    /// Bearer token of an agent node; stored encrypted, never sent back.
+    #[serde(default, skip_serializing)]
+    pub agent_token: Option<String>,
+    /// Where an SSH node's bridge runs, relative to the login's home.
+    #[serde(default = "default_bridge_dir")]
+    pub bridge_dir: String,
+    /// Shell command starting the bridge in `bridge_dir` (SSH nodes).
+    #[serde(default = "default_bridge_command")]
+    pub bridge_command: String,
+    /// The bridge's WebSocket port on the node.
+    #[serde(default = "default_ws_port")]
+    pub ws_port: u16,
+    /// Where browsers on the node reach the TLS bridge.
+    #[serde(default = "default_tls_bridge_port")]
+    pub tls_bridge_port: u16,
+    /// Let an agent node's bridge listen on every interface rather than
+    /// loopback only.
+    #[serde(default)]
+    pub expose_ws: bool,
+    /// Pool launches the node takes at once, besides the launch_profile
+    /// bridge.
+    #[serde(default = "default_capacity")]
+    pub capacity: u16,
+}
+
+/// The outcome of the latest health check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeHealth {
+    pub online: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionNode {
+    pub id: String,
+    #[serde(flatten)]
+    pub spec: NodeSpec,
+    /// Disabled nodes are kept but not launched on.
+    pub enabled: bool,
+    /// `None` until the node is first checked.
+    pub health: Option<NodeHealth>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ── Validation ────────────────────────────────────────────────────────────────
+
+/// A host or user name that can't be taken for an `ssh` option.
+fn is_plain_name(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('-')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':'))
+}
+
+/// `localhost` or a loopback address, bracketed IPv6 included.
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+impl NodeSpec {
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: String| Err(ManifoldError::InvalidArg(format!("node: {msg}")));
+        if self.name.trim().is_empty() {
+            return invalid("name is empty".into());
+        }
+        if !is_plain_name(&self.host) {
+            return invalid(format!("invalid host {:?}", self.host));
+        }
+        if let Some(user) = self.ssh_user.as_ref().filter(|u| !is_plain_name(u)) {
+            return invalid(format!("invalid ssh user {user:?}"));
+        }
+        if self.ssh_port == 0 || self.ws_port == 0 || self.tls_bridge_port == 0 {
+            return invalid("ports must be non-zero".into());
+        }
+        if !(1..=MAX_CAPACITY).contains(&self.capacity) {
+            return invalid(format!("capacity must be 1..={MAX_CAPACITY}"));
+        }
+        // Every slot's ports must exist and not collide with the other range
+        let (ws, tls, cap) = (
+            u32::from(self.ws_port),
+            u32::from(self.tls_bridge_port),
+            u32::from(self.capacity),
+        );
+        if ws + cap > u32::from(u16::MAX) || tls + cap > u32::from(u16::MAX) {
+            return invalid("port slots run past 65535".into());
+        }
+        if ws <= tls + cap && tls <= ws + cap {
+            return invalid("ws_port and tls_bridge_port slots overlap".into());
+        }
+        match self.kind {
+            NodeKind::Ssh => {
+                if self.bridge_command.trim().is_empty() {
+                    return invalid("bridge_command is empty".into());
+                }
+                if self.expose_ws {
+                    return invalid(
+                        "SSH nodes forward the WebSocket; expose_ws is for agents".into(),
+                    );
+                }
+            }
+            NodeKind::Agent => {
+                let Some(raw) = &self.agent_url else {
+                    return invalid("agent nodes need an agent_url".into());
+                };
+                let url = url::Url::parse(raw)
+                    .map_err(|e| ManifoldError::InvalidArg(format!("node: agent_url: {e}")))?;
+                // Launch configs hold proxy credentials
+                let loopback = url.host_str().is_some_and(is_loopback_host);
+                if url.scheme() != "https" && !(url.scheme() == "http" && loopback) {
+                    return invalid("agent_url must be https".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+const COLUMNS: &str = "id, name, kind, host, ssh_port, ssh_user, ssh_key_path, agent_url, \
+     bridge_dir, bridge_command, ws_port, tls_bridge_port, expose_ws, capacity, enabled, \
+     created_at, online, latency_ms, last_error, checked_at";
+
+pub struct NodeRepo {
+    db: Db,
+}
+
+impl NodeRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn add(&self, spec: NodeSpec) -> Result<ExecutionNode> {
+        spec.validate()?;
+        let id = Uuid::new_v4().to_string();
+        let token = self.db.encrypt_opt(spec.agent_token.as_deref())?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO nodes (id, name, kind, host, ssh_port, ssh_user, ssh_key_path,
+                       agent_url, agent_token_enc, bridge_dir, bridge_command, ws_port,
+                       tls_bridge_port, expose_ws, capacity, enabled, created_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, 1,
+                           ?16)"#,
+                params![
+                    id,
+                    spec.name.trim(),
+                    spec.kind.to_string(),
+                    spec.host,
+                    spec.ssh_port,
+                    spec.ssh_user,
+                    spec.ssh_key_path,
+                    spec.agent_url,
+                    token,
+                    spec.bridge_dir,
+                    spec.bridge_command,
+                    spec.ws_port,
+                    spec.tls_bridge_port,
+                    spec.expose_ws,
+                    spec.capacity,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })?;
+        self.get(&id)
+    }
+
+    /// Replace a node's spec; an absent `agent_token` keeps the stored one.
+    pub fn update(&self, id: &str, spec: NodeSpec) -> Result<ExecutionNode> {
+        spec.validate()?;
+        let token = self.db.encrypt_opt(spec.agent_token.as_deref())?;
+        let changed = self.db.with_conn(|conn| {
+            Ok(conn.execute(
+                r#"UPDATE nodes SET name = ?2, kind = ?3, host = ?4, ssh_port = ?5,
+                       ssh_user = ?6, ssh_key_path = ?7, agent_url = ?8,
+                       agent_token_enc = COALESCE(?9, agent_token_enc), bridge_dir = ?10,
+                       bridge_command = ?11, ws_port = ?12, tls_bridge_port = ?13,
+                       expose_ws = ?14, capacity = ?15
+                   WHERE id = ?1"#,
+                params![
+                    id,
+                    spec.name.trim(),
+                    spec.kind.to_string(),
+                    spec.host,
+                    spec.ssh_port,
+                    spec.ssh_user,
+                    spec.ssh_key_path,
+                    spec.agent_url,
+                    token,
+                    spec.bridge_dir,
+                    spec.bridge_command,
+                    spec.ws_port,
+                    spec.tls_bridge_port,
+                    spec.expose_ws,
+                    spec.capacity,
+                ],
+            )?)
+        })?;
+        if changed == 0 {
+            return Err(not_found(id));
+        }
+        self.get(id)
+    }
+
+    pub fn list(&self) -> Result<Vec<ExecutionNode>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(&format!("SELECT {COLUMNS} FROM nodes ORDER BY name"))?;
+            let nodes = stmt
+                .query_map([], from_row)?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(nodes)
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Result<ExecutionNode> {
+        let node = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    &format!("SELECT {COLUMNS} FROM nodes WHERE id = ?1"),
+                    params![id],
+                    from_row,
+                )
+                .optional()?)
+        })?;
+        node.ok_or_else(|| not_found(id))
+    }
+
+    /// The node with its decrypted agent token, for launching.
+    pub fn get_with_token(&self, id: &str) -> Result<ExecutionNode> {
+        let mut node = self.get(id)?;
+        let token: Option<String> = self.db.with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT agent_token_enc FROM nodes WHERE id = ?1",
+                params![id],
+                |r| r.get(0),
+            )?)
+        })?;
+        node.spec.agent_token = token.map(|t| self.db.decrypt_field(&t)).transpose()?;
+        Ok(node)
+    }
+
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<()> {
+        let changed = self.db.with_conn(|conn| {
+            Ok(conn.execute(
+                "UPDATE nodes SET enabled = ?2 WHERE id = ?1",
+                params![id, enabled],
+            )?)
+        })?;
+        if changed == 0 {
+            return Err(not_found(id));
+        }
+        Ok(())
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute("DELETE FROM nodes WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+
+    pub fn record_health(&self, id: &str, health: &NodeHealth) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"UPDATE nodes SET online = ?2, latency_ms = ?3, last_error = ?4, checked_at = ?5
+                   WHERE id = ?1"#,
+                params![
+                    id,
+                    health.online,
+                    health.latency_ms.map(|ms| ms as i64),
+                    health.error,
+                    health.checked_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+fn not_found(id: &str) -> ManifoldError {
+    ManifoldError::InvalidArg(format!("no execution node {id:?}"))
+}
+
+fn parse_time(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+fn from_row(r: &Row) -> rusqlite::Result<ExecutionNode> {
+    let kind: String = r.get("kind")?;
+    let checked_at: Option<String> = r.get("checked_at")?;
+    Ok(ExecutionNode {
+        id: r.get("id")?,
+        spec: NodeSpec {
+            name: r.get("name")?,
+            kind: kind.parse().unwrap_or(NodeKind::Ssh),
+            host: r.get("host")?,
+            ssh_port: r.get("ssh_port")?,
+            ssh_user: r.get("ssh_user")?,
+            ssh_key_path: r.get("ssh_key_path")?,
+            agent_url: r.get("agent_url")?,
+            agent_token: None,
+            bridge_dir: r.get("bridge_dir")?,
+            bridge_command: r.get("bridge_command")?,
+            ws_port: r.get("ws_port")?,
+            tls_bridge_port: r.get("tls_bridge_port")?,
+            expose_ws: r.get("expose_ws")?,
+            capacity: r.get("capacity")?,
+        },
+        enabled: r.get("enabled")?,
+        health: checked_at
+            .map(|at| -> rusqlite::Result<NodeHealth> {
+                Ok(NodeHealth {
+                    online: r.get::<_, Option<bool>>("online")?.unwrap_or(false),
+                    latency_ms: r.get::<_, Option<i64>>("latency_ms")?.map(|ms| ms as u64),
+                    error: r.get("last_error")?,
+                    checked_at: parse_time(&at),
+                })
+            })
+            .transpose()?,
+        created_at: parse_time(&r.get::<_, String>("created_at")?),
+    })
+}
+
+// ── Launch placement ──────────────────────────────────────────────────────────
+
+/// The node's WebSocket and TLS bridge ports in port slot `slot`.
+pub fn slot_ports(spec: &NodeSpec, slot: u16) -> (u16, u16) {
+    (spec.ws_port + slot, spec.tls_bridge_port + slot)
+}
+
+/// Point a launch config at port slot `slot` of `node`: its ports, loopback
+/// or exposed TLS WebSocket, and a headless browser.  Fails for routes
+/// through tunnels that only listen on this machine.
+pub fn place(node: &ExecutionNode, slot: u16, config: &mut LaunchConfig) -> Result<()> {
+    if !node.enabled {
+        return Err(ManifoldError::InvalidArg(format!(
+            "node {} is disabled",
+            node.spec.name
+        )));
+    }
+    if let Some(proxy) = &config.proxy {
+        let local = url::Url::parse(&proxy.server)
+            .ok()
+            .is_some_and(|u| u.host_str().is_some_and(is_loopback_host));
+        if local {
+            return Err(ManifoldError::InvalidArg(format!(
+                "the profile's route runs through a local tunnel (SSH proxy, chain or VPN), \
+                 which node {} can't reach",
+                node.spec.name
+            )));
+        }
+    }
+    if slot > node.spec.capacity {
+        return Err(ManifoldError::InvalidArg(format!(
+            "node {} has no port slot {slot}",
+            node.spec.name
+        )));
+    }
+    let (ws_port, tls_bridge_port) = slot_ports(&node.spec, slot);
+    config.ws_port = ws_port;
+    config.ws_host = node.spec.expose_ws.then(|| "0.0.0.0".to_string());
+    config.ws_tls = node.spec.expose_ws;
+    if config.tls_bridge_port.is_some() {
+        config.tls_bridge_port = Some(tls_bridge_port);
+    }
+    config.launch_mode = LaunchMode::HeadlessNew;
+    config.display = None;
+    Ok(())
+}
+
+// ── SSH transport ─────────────────────────────────────────────────────────────
+
+/// `s` as one single-quoted POSIX shell word.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn ssh_base_args(spec: &NodeSpec) -> Vec<String> {
+    let mut args = vec!["-p".to_string(), spec.ssh_port.to_string()];
+    for opt in [
+        "BatchMode=yes",
+        "ExitOnForwardFailure=yes",
+        "ServerAliveInterval=15",
+        "ServerAliveCountMax=3",
+        "StrictHostKeyChecking=accept-new",
+        "ConnectTimeout=10",
+    ] {
+        args.extend(["-o".into(), opt.into()]);
+    }
+    if let Some(key) = &spec.ssh_key_path {
+        args.extend(["-i".into(), key.clone()]);
+    }
+    args
+}
+
+fn ssh_target(spec: &NodeSpec) -> String {
+    match &spec.ssh_user {
+        Some(user) => format!("{user}@{}", spec.host),
+        None => spec.host.clone(),
+    }
+}
+
+/// `ssh` running the bridge in port slot `slot` of `node`, its WebSocket
+/// forwarded to `local_ws_port` and, when given, the local TLS bridge
+/// forwarded to the slot's TLS bridge port.  Write the launch config as the
+/// first stdin line.
+pub fn ssh_bridge_command(
+    node: &ExecutionNode,
+    slot: u16,
+    local_ws_port: u16,
+    local_tls_bridge_port: Option<u16>,
+) -> Command {
+    let spec = &node.spec;
+    let (ws_port, tls_bridge_port) = slot_ports(spec, slot);
+    let mut args = ssh_base_args(spec);
+    args.extend([
+        "-L".into(),
+        format!("127.0.0.1:{local_ws_port}:127.0.0.1:{ws_port}"),
+    ]);
+    if let Some(local) = local_tls_bridge_port {
+        args.extend([
+            "-R".into(),
+            format!("127.0.0.1:{tls_bridge_port}:127.0.0.1:{local}"),
+        ]);
+    }
+    args.push(ssh_target(spec));
+    // `read` takes exactly one line; the rest of stdin is the bridge's
+    args.push(format!(
+        "cd {} && IFS= read -r MANIFOLD_LAUNCH_CONFIG && export MANIFOLD_LAUNCH_CONFIG && exec {}",
+        shell_quote(&spec.bridge_dir),
+        spec.bridge_command
+    ));
+    let mut cmd = Command::new("ssh");
+    cmd.args(args).stdin(Stdio::piped());
+    cmd
+}
+
+/// `ssh` forwarding `local_ws_port` here to the WebSocket of port slot
+/// `slot`, which an agent node's bridge keeps on the node's loopback.
+pub fn ssh_forward_command(node: &ExecutionNode, slot: u16, local_ws_port: u16) -> Command {
+    let spec = &node.spec;
+    let (ws_port, _) = slot_ports(spec, slot);
+    let mut args = ssh_base_args(spec);
+    args.extend([
+        "-N".into(),
+        "-L".into(),
+        format!("127.0.0.1:{local_ws_port}:127.0.0.1:{ws_port}"),
+        ssh_target(spec),
+    ]);
+    let mut cmd = Command::new("ssh");
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit());
+    cmd
+}
+
+/// Start `ssh_forward_command` and wait until the forward takes
+/// connections.
+pub fn start_ws_forward(node: &ExecutionNode, slot: u16, local_ws_port: u16) -> Result<Child> {
+    let mut child = ssh_forward_command(node, slot, local_ws_port)
+        .spawn()
+        .map_err(|e| ManifoldError::spawn("ssh", e))?;
+    let deadline = Instant::now() + FORWARD_TIMEOUT;
+    while TcpStream::connect(("127.0.0.1", local_ws_port)).is_err() {
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(ManifoldError::Other(format!(
+                "WebSocket forward to node {} exited ({status})",
+                node.spec.name
+            )));
+        }
+        if Instant::now() > deadline {
+            child.kill().ok();
+            child.wait().ok();
+            return Err(ManifoldError::Other(format!(
+                "WebSocket forward to node {} did not come up",
+                node.spec.name
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Ok(child)
+}
+
+// ── Agent transport ───────────────────────────────────────────────────────────
+
+fn agent_request(
+    node: &ExecutionNode,
+    method: reqwest::Method,
+    path: &str,
+    timeout: Duration,
+) -> Result<reqwest::RequestBuilder> {
+    let base = node.spec.agent_url.as_deref().ok_or_else(|| {
+        ManifoldError::InvalidArg(format!("node {} has no agent_url", node.spec.name))
+    })?;
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let mut request = client.request(method, format!("{}{path}", base.trim_end_matches('/')));
+    if let Some(token) = &node.spec.agent_token {
+        request = request.bearer_auth(token);
+    }
+    Ok(request)
+}
+
+/// Have `node`'s agent start a bridge with `config_json`.
+pub async fn agent_launch(node: &ExecutionNode, config_json: &str) -> Result<()> {
+    agent_request(node, reqwest::Method::POST, "/launch", AGENT_LAUNCH_TIMEOUT)?
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(config_json.to_string())
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Have `node`'s agent stop the bridge it launched for `profile_id`.
+pub async fn agent_stop(node: &ExecutionNode, profile_id: &str) -> Result<()> {
+    agent_request(node, reqwest::Method::POST, "/stop", CHECK_TIMEOUT)?
+        .json(&serde_json::json!({ "profileId": profile_id }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+// ── Health checks ─────────────────────────────────────────────────────────────
+
+/// Check that `node` can take launches: an SSH node accepts the login and
+/// has its bridge directory and Node; an agent answers its health endpoint.
+pub async fn check(node: &ExecutionNode) -> NodeHealth {
+    let started = Instant::now();
+    let outcome = match node.spec.kind {
+        NodeKind::Ssh => check_ssh(&node.spec).await,
+        NodeKind::Agent => check_agent(node).await,
+    };
+    NodeHealth {
+        online: outcome.is_ok(),
+        latency_ms: outcome
+            .is_ok()
+            .then(|| started.elapsed().as_millis() as u64),
+        error: outcome.err().map(|e| e.to_string()),
+        checked_at: Utc::now(),
+    }
+}
+
+async fn check_ssh(spec: &NodeSpec) -> Result<()> {
+    let mut args = ssh_base_args(spec);
+    args.push(ssh_target(spec));
+    args.push(format!(
+        "cd {} && command -v node >/dev/null",
+        shell_quote(&spec.bridge_dir)
+    ));
+    let output = tokio::time::timeout(
+        CHECK_TIMEOUT,
+        tokio::process::Command::new("ssh")
+            .args(args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| ManifoldError::Other("ssh timed out".into()))?
+    .map_err(|e| ManifoldError::spawn("ssh", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(ManifoldError::Other(match stderr.trim() {
+        "" => format!("bridge_dir or node missing ({})", output.status),
+        reason => reason.lines().last().unwrap_or(reason).to_string(),
+    }))
+}
+
+async fn check_agent(node: &ExecutionNode) -> Result<()> {
+    agent_request(node, reqwest::Method::GET, "/health", CHECK_TIMEOUT)?
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssh_spec() -> NodeSpec {
+        serde_json::from_value(serde_json::json!({
+            "name": "render-1",
+            "kind": "ssh",
+            "host": "10.0.0.5",
+            "ssh_user": "manifold",
+            "ssh_key_path": "/keys/id_ed25519",
+            "bridge_dir": "opt/manifold's",
+        }))
+        .unwrap()
+    }
+
+    fn config(proxy: Option<&str>) -> LaunchConfig {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = crate::profile::ProfileRepo::new_with_root(
+            Db::open_in_memory().unwrap(),
+            dir.path().into(),
+        );
+        let profile = repo
+            .create(crate::profile::CreateProfileRequest {
+                name: "placed".into(),
+                seed: Some(3),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                persona: None,
+            })
+            .unwrap();
+        serde_json::from_value(serde_json::json!({
+            "protocol": crate::bridge_locator::BRIDGE_PROTOCOL,
+            "profile": profile,
+            "proxy": proxy.map(|server| serde_json::json!({ "server": server })),
+            "url": "about:blank",
+            "wsPort": 8766,
+            "wsSecret": crate::bridge_auth::new_secret(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn specs_are_validated_per_kind() {
+        assert!(ssh_spec().validate().is_ok());
+
+        let mut option_host = ssh_spec();
+        option_host.host = "-oProxyCommand=x".into();
+        assert!(option_host.validate().is_err());
+
+        let mut exposed = ssh_spec();
+        exposed.expose_ws = true;
+        assert!(exposed.validate().is_err());
+
+        // Slots 8766..=8770 run into the TLS bridge range 8770..=8774
+        let mut crowded = ssh_spec();
+        crowded.capacity = 4;
+        crowded.tls_bridge_port = 8770;
+        assert!(crowded.validate().is_err());
+        crowded.tls_bridge_port = 8771;
+        assert!(crowded.validate().is_ok());
+        crowded.capacity = 0;
+        assert!(crowded.validate().is_err());
+
+        let mut agent = ssh_spec();
+        agent.kind = NodeKind::Agent;
+        assert!(agent.validate().is_err(), "agents need a URL");
+        agent.agent_url = Some("http://10.0.0.5:7000".into());
+        assert!(agent.validate().is_err(), "plain http off loopback");
+        agent.agent_url = Some("https://10.0.0.5:7000".into());
+        assert!(agent.validate().is_ok());
+        for local in [
+            "http://127.0.0.2:7000",
+            "http://[::1]:7000",
+            "http://LOCALHOST:7000",
+        ] {
+            agent.agent_url = Some(local.into());
+            assert!(agent.validate().is_ok(), "{local}");
+        }
+    }
+
+    #[test]
+    fn loopback_proxies_are_not_sent_to_nodes() {
+        let node = ExecutionNode {
+            id: "n1".into(),
+            spec: ssh_spec(),
+            enabled: true,
+            health: None,
+            created_at: Utc::now(),
+        };
+        let route = |server: &str| place(&node, 0, &mut config(Some(server)));
+        for local in [
+            "socks5://127.0.0.1:1080",
+            "socks5://127.8.0.1:1080",
+            "socks5://localhost:1080",
+            "http://[::1]:8080",
+            "http://[0:0:0:0:0:0:0:1]:8080",
+        ] {
+            assert!(route(local).is_err(), "{local}");
+        }
+        assert!(route("socks5://203.0.113.7:1080").is_ok());
+        assert!(route("http://[2001:db8::1]:8080").is_ok());
+        assert!(!is_loopback_host("localhost.example"));
+    }
+
+    #[test]
+    fn repo_keeps_tokens_out_of_listings() {
+        let repo = NodeRepo::new(Db::open_in_memory_with_key("k").unwrap());
+        let mut spec = ssh_spec();
+        spec.kind = NodeKind::Agent;
+        spec.agent_url = Some("https://agent.test".into());
+        spec.agent_token = Some("t0ken".into());
+        let node = repo.add(spec.clone()).unwrap();
+        assert!(node.health.is_none());
+        assert!(node.spec.agent_token.is_none());
+        assert!(!serde_json::to_string(&node).unwrap().contains("t0ken"));
+        assert_eq!(
+            repo.get_with_token(&node.id)
+                .unwrap()
+                .spec
+                .agent_token
+                .as_deref(),
+            Some("t0ken")
+        );
+
+        // Updating without a token keeps the stored one
+        spec.agent_token = None;
+        spec.name = "render-2".into();
+        repo.update(&node.id, spec).unwrap();
+        assert_eq!(
+            repo.get_with_token(&node.id)
+                .unwrap()
+                .spec
+                .agent_token
+                .as_deref(),
+            Some("t0ken")
+        );
+
+        let health = NodeHealth {
+            online: true,
+            latency_ms: Some(42),
+            error: None,
+            checked_at: Utc::now(),
+        };
+        repo.record_health(&node.id, &health).unwrap();
+        repo.set_enabled(&node.id, false).unwrap();
+        let listed = repo.list().unwrap();
+        assert_eq!(listed[0].spec.name, "render-2");
+        assert_eq!(listed[0].health.as_ref().unwrap().latency_ms, Some(42));
+        assert!(!listed[0].enabled);
+
+        repo.delete(&node.id).unwrap();
+        assert!(repo.get(&node.id).is_err());
+    }
+
+    #[test]
+    fn ssh_command_forwards_ports_and_keeps_config_off_the_command_line() {
+        let node = ExecutionNode {
+            id: "n1".into(),
+            spec: ssh_spec(),
+            enabled: true,
+            health: None,
+            created_at: Utc::now(),
+        };
+        let cmd = ssh_bridge_command(&node, 1, 9766, Some(9443));
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        let joined = args.join(" ");
+        assert!(joined.contains("-L 127.0.0.1:9766:127.0.0.1:8767"));
+        assert!(joined.contains("-R 127.0.0.1:8444:127.0.0.1:9443"));
+        assert!(joined.contains("BatchMode=yes"));
+        assert!(args.contains(&"manifold@10.0.0.5".to_string()));
+        let remote = args.last().unwrap();
+        assert!(
+            remote.starts_with(r"cd 'opt/manifold'\''s' && IFS= read -r MANIFOLD_LAUNCH_CONFIG")
+        );
+        assert!(remote.ends_with("exec npx tsx playwright-bridge/index.ts"));
+    }
+
+    #[test]
+    fn agent_websockets_are_forwarded_or_served_over_tls() {
+        let mut spec = ssh_spec();
+        spec.kind = NodeKind::Agent;
+        spec.agent_url = Some("https://10.0.0.5:7000".into());
+        let mut node = ExecutionNode {
+            id: "n1".into(),
+            spec,
+            enabled: true,
+            health: None,
+            created_at: Utc::now(),
+        };
+
+        // Default: loopback on the node, forwarded over SSH
+        let mut config = config(None);
+        place(&node, 2, &mut config).unwrap();
+        assert_eq!(config.ws_port, 8768);
+        assert!(config.ws_host.is_none() && !config.ws_tls);
+        let args: Vec<String> = ssh_forward_command(&node, 2, 9766)
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        let joined = args.join(" ");
+        assert!(joined.ends_with("-N -L 127.0.0.1:9766:127.0.0.1:8768 manifold@10.0.0.5"));
+
+        node.spec.expose_ws = true;
+        place(&node, 2, &mut config).unwrap();
+        assert_eq!(config.ws_host.as_deref(), Some("0.0.0.0"));
+        assert!(config.ws_tls);
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,540 @@
+// ── Manifold notifications ────────────────────────────────────────────────────
+//
+// Most problems only show up in the event log, which nobody reads while a
+// batch of profiles runs unattended.  The few that need a human right away
+// — a proxy burned on a target site, an emergency shutdown, the database
+// volume running out of space, a workflow waiting for the operator — are
+// also pushed out as alerts.
+//
+// An alert goes to the sinks its kind is routed to in `NotificationSettings`:
+//
+//   native    a desktop notification through the OS's own tool
+//             (notify-send, osascript, a PowerShell balloon tip)
+//   telegram  a message from a Telegram bot to one chat
+//
+// Sinks are sent to on a background thread; a sink that fails is logged and
+// never blocks the command that raised the alert.  The bot token is stored
+// encrypted with the workspace master key, like proxy credentials.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{Context, ManifoldError, Result};
+use crate::proxy::{DomainHit, ProxyDomainStatus};
+use crate::workflow::Intervention;
+
+/// `settings` key holding `NotificationSettings` (JSON).
+pub const NOTIFICATION_SETTINGS_KEY: &str = "notifications";
+
+/// Blocks on one domain after which a proxy counts as burned for it.
+pub const BURNED_BLOCKS: u32 = 3;
+
+/// How often the database volume's free space is checked.
+const DB_SPACE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const TELEGRAM_API: &str = "https://api.telegram.org";
+const TELEGRAM_TIMEOUT: Duration = Duration::from_secs(15);
+
+const MB: u64 = 1024 * 1024;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// A proxy reached `BURNED_BLOCKS` blocks on a target domain.
+    ProxyBurned,
+    /// `panic_shutdown` was triggered.
+    PanicShutdown,
+    /// The database volume is below `db_min_free_mb` of free space.
+    DbNearlyFull,
+    /// A workflow run paused on a `manual_intervention` step.
+    WorkflowIntervention,
+}
+
+/// One alert, ready to be sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub title: String,
+    pub body: String,
+}
+
+impl Alert {
+    /// The alert for a recorded domain hit, if it is the block that burns
+    /// the proxy for that domain.  Later blocks don't alert again.
+    pub fn for_domain_hit(
+        proxy_name: &str,
+        status: &ProxyDomainStatus,
+        hit: DomainHit,
+    ) -> Option<Self> {
+        (hit == DomainHit::Block && status.blocks == BURNED_BLOCKS).then(|| Self {
+            kind: AlertKind::ProxyBurned,
+            title: "Proxy burned".into(),
+            body: format!(
+                "{proxy_name} was blocked {} times on {}; it is skipped there until {}.",
+                status.blocks,
+                status.domain,
+                status.cooldown_until.format("%Y-%m-%d %H:%M UTC"),
+            ),
+        })
+    }
+
+    pub fn panic_shutdown(stopped_profiles: usize) -> Self {
+        Self {
+            kind: AlertKind::PanicShutdown,
+            title: "Emergency shutdown".into(),
+            body: format!("Panic shutdown stopped {stopped_profiles} profile(s) and all tunnels."),
+        }
+    }
+
+    pub fn db_nearly_full(db_path: &Path, free_bytes: u64) -> Self {
+        Self {
+            kind: AlertKind::DbNearlyFull,
+            title: "Database disk nearly full".into(),
+            body: format!(
+                "Only {} MB left on the volume holding {}.",
+                free_bytes / MB,
+                db_path.display()
+            ),
+        }
+    }
+
+    pub fn workflow_intervention(intervention: &Intervention) -> Self {
+        Self {
+            kind: AlertKind::WorkflowIntervention,
+            title: "Workflow waiting for you".into(),
+            body: format!(
+                "{} (profile {}, run {}). The step gives up at {}.",
+                intervention.prompt,
+                intervention.profile_id,
+                intervention.run_id,
+                intervention.expires_at.format("%Y-%m-%d %H:%M UTC"),
+            ),
+        }
+    }
+}
+
+/// The sinks one alert kind is sent to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertRoute {
+    pub native: bool,
+    pub telegram: bool,
+}
+
+impl Default for AlertRoute {
+    fn default() -> Self {
+        Self {
+            native: true,
+            telegram: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelegramSettings {
+    /// Token from @BotFather.
+    pub bot_token: String,
+    /// Chat (user, group or channel) the bot posts to.
+    pub chat_id: String,
+}
+
+/// App-wide notification settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationSettings {
+    /// `None` disables the Telegram sink for every alert kind.
+    pub telegram: Option<TelegramSettings>,
+    pub proxy_burned: AlertRoute,
+    pub panic_shutdown: AlertRoute,
+    pub db_nearly_full: AlertRoute,
+    pub workflow_intervention: AlertRoute,
+    /// Free space below which the database volume counts as nearly full.
+    pub db_min_free_mb: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            telegram: None,
+            proxy_burned: AlertRoute::default(),
+            panic_shutdown: AlertRoute::default(),
+            db_nearly_full: AlertRoute::default(),
+            workflow_intervention: AlertRoute::default(),
+            db_min_free_mb: 1024,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(tg) = &self.telegram {
+            if tg.bot_token.trim().is_empty() || tg.chat_id.trim().is_empty() {
+                return Err(ManifoldError::InvalidArg(
+                    "telegram needs both a bot token and a chat id".into(),
+                ));
+            }
+        }
+        if self.db_min_free_mb == 0 {
+            return Err(ManifoldError::InvalidArg(
+                "db_min_free_mb must be at least 1".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn route(&self, kind: AlertKind) -> &AlertRoute {
+        match kind {
+            AlertKind::ProxyBurned => &self.proxy_burned,
+            AlertKind::PanicShutdown => &self.panic_shutdown,
+            AlertKind::DbNearlyFull => &self.db_nearly_full,
+            AlertKind::WorkflowIntervention => &self.workflow_intervention,
+        }
+    }
+
+    /// The configured sinks `kind` is routed to.
+    pub fn sinks_for(&self, kind: AlertKind) -> Vec<Box<dyn NotificationSink>> {
+        let route = self.route(kind);
+        let mut sinks: Vec<Box<dyn NotificationSink>> = Vec::new();
+        if route.native {
+            sinks.push(Box::new(NativeSink));
+        }
+        if let (true, Some(tg)) = (route.telegram, &self.telegram) {
+            sinks.push(Box::new(TelegramSink::new(tg)));
+        }
+        sinks
+    }
+}
+
+/// Settings with the bot token decrypted.
+pub fn load_settings(db: &Db) -> Result<NotificationSettings> {
+    let mut settings: NotificationSettings = crate::settings::load(db, NOTIFICATION_SETTINGS_KEY)?;
+    if let Some(tg) = settings.telegram.as_mut() {
+        tg.bot_token = db.decrypt_field(&tg.bot_token)?;
+    }
+    Ok(settings)
+}
+
+/// Store settings, encrypting the bot token.
+pub fn store_settings(db: &Db, settings: &NotificationSettings) -> Result<()> {
+    let mut stored = settings.clone();
+    if let Some(tg) = stored.telegram.as_mut() {
+        tg.bot_token = db.encrypt_field(&tg.bot_token)?;
+    }
+    crate::settings::store(db, NOTIFICATION_SETTINGS_KEY, &stored)
+}
+
+// ── Sinks ─────────────────────────────────────────────────────────────────────
+
+pub trait NotificationSink: Send {
+    fn name(&self) -> &'static str;
+    fn send(&self, alert: &Alert) -> Result<()>;
+}
+
+/// Desktop notification through the OS's notification tool.  Title and
+/// body are handed over in environment variables, so nothing needs quoting.
+pub struct NativeSink;
+
+impl NotificationSink for NativeSink {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    fn send(&self, alert: &Alert) -> Result<()> {
+        let mut cmd = native_command();
+        cmd.env("MANIFOLD_ALERT_TITLE", &alert.title)
+            .env("MANIFOLD_ALERT_BODY", &alert.body);
+        let status = cmd
+            .status()
+            .map_err(|e| ManifoldError::spawn("notification tool", e))?;
+        if !status.success() {
+            return Err(ManifoldError::Other(format!(
+                "notification tool exited with {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn native_command() -> std::process::Command {
+    let mut cmd = std::process::Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"exec notify-send -u critical -a Manifold "$MANIFOLD_ALERT_TITLE" "$MANIFOLD_ALERT_BODY""#,
+    ]);
+    cmd
+}
+
+#[cfg(target_os = "macos")]
+fn native_command() -> std::process::Command {
+    let mut cmd = std::process::Command::new("osascript");
+    cmd.args([
+        "-e",
+        r#"display notification (system attribute "MANIFOLD_ALERT_BODY") with title (system attribute "MANIFOLD_ALERT_TITLE")"#,
+    ]);
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn native_command() -> std::process::Command {
+    let mut cmd = std::process::Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Warning; $n.Visible = $true; \
+         $n.ShowBalloonTip(10000, $env:MANIFOLD_ALERT_TITLE, $env:MANIFOLD_ALERT_BODY, 'Warning'); \
+         Start-Sleep -Seconds 10; $n.Dispose()",
+    ]);
+    cmd
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn native_command() -> std::process::Command {
+    std::process::Command::new("false")
+}
+
+/// `sendMessage` through the Telegram Bot API.
+pub struct TelegramSink {
+    url: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    pub fn new(settings: &TelegramSettings) -> Self {
+        Self {
+            url: format!(
+                "{TELEGRAM_API}/bot{}/sendMessage",
+                settings.bot_token.trim()
+            ),
+            chat_id: settings.chat_id.trim().to_string(),
+        }
+    }
+}
+
+impl NotificationSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn send(&self, alert: &Alert) -> Result<()> {
+        let body = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": format!("⚠ {}\n{}", alert.title, alert.body),
+            "disable_web_page_preview": true,
+        });
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        rt.block_on(async {
+            let resp = reqwest::Client::builder()
+                .timeout(TELEGRAM_TIMEOUT)
+                .build()
+                .context("telegram client")?
+                .post(&self.url)
+                .json(&body)
+                .send()
+                .await
+                // The error text would carry the URL, and with it the token
+                .map_err(|e| e.without_url())
+                .context("telegram")?;
+            if !resp.status().is_success() {
+                return Err(ManifoldError::Other(format!(
+                    "telegram: HTTP {}",
+                    resp.status()
+                )));
+            }
+            Ok(())
+        })
+    }
+}
+
+// ── Dispatch ──────────────────────────────────────────────────────────────────
+
+/// Send `alert` to the sinks its kind is routed to, on a background thread.
+pub fn notify(db: &Db, alert: Alert) {
+    let sinks = match load_settings(db) {
+        Ok(settings) => settings.sinks_for(alert.kind),
+        Err(e) => {
+            eprintln!("[notify] settings unreadable: {e}");
+            return;
+        }
+    };
+    if sinks.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for sink in sinks {
+            if let Err(e) = sink.send(&alert) {
+                eprintln!("[notify] {} sink failed: {e}", sink.name());
+            }
+        }
+    });
+}
+
+/// Outcome of a test message for one sink.
+#[derive(Debug, Clone, Serialize)]
+pub struct SinkResult {
+    pub sink: String,
+    /// `None` when the message was sent.
+    pub error: Option<String>,
+}
+
+/// Send a test alert to every configured sink and wait for the results.
+pub fn send_test(settings: &NotificationSettings) -> Vec<SinkResult> {
+    let alert = Alert {
+        kind: AlertKind::PanicShutdown,
+        title: "Manifold test alert".into(),
+        body: "Notifications are set up.".into(),
+    };
+    let mut sinks: Vec<Box<dyn NotificationSink>> = vec![Box::new(NativeSink)];
+    if let Some(tg) = &settings.telegram {
+        sinks.push(Box::new(TelegramSink::new(tg)));
+    }
+    sinks
+        .iter()
+        .map(|sink| SinkResult {
+            sink: sink.name().into(),
+            error: sink.send(&alert).err().map(|e| e.to_string()),
+        })
+        .collect()
+}
+
+// ── Database free space ───────────────────────────────────────────────────────
+
+/// File behind the connection's main database (`None` for in-memory ones).
+fn db_file(db: &Db) -> Option<PathBuf> {
+    let file: String = db
+        .with_conn(|conn| {
+            Ok(conn.query_row(
+                "SELECT file FROM pragma_database_list WHERE name = 'main'",
+                [],
+                |r| r.get(0),
+            )?)
+        })
+        .ok()?;
+    (!file.is_empty()).then(|| PathBuf::from(file))
+}
+
+/// Bytes available to unprivileged users on the volume holding `path`.
+#[cfg(unix)]
+fn free_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Not measured on this platform; the check never fires.
+#[cfg(not(unix))]
+fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Check the database volume every `DB_SPACE_INTERVAL` on a background
+/// thread and alert once each time free space drops below the threshold.
+pub fn spawn_db_space_watch(db: Db) {
+    std::thread::spawn(move || {
+        let mut low = false;
+        loop {
+            if let Some(path) = db_file(&db) {
+                let free = path.parent().and_then(free_space);
+                let min = load_settings(&db)
+                    .map(|s| s.db_min_free_mb)
+                    .unwrap_or(NotificationSettings::default().db_min_free_mb);
+                if let Some(free) = free {
+                    let now_low = free < min * MB;
+                    if now_low && !low {
+                        notify(&db, Alert::db_nearly_full(&path, free));
+                    }
+                    low = now_low;
+                }
+            }
+            std::thread::sleep(DB_SPACE_INTERVAL);
+        }
+    });
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn status(blocks: u32) -> ProxyDomainStatus {
+        ProxyDomainStatus {
+            proxy_id: "px".into(),
+            domain: "shop.example".into(),
+            blocks,
+            captchas: 0,
+            last_hit_at: Utc::now(),
+            cooldown_until: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn only_the_burning_block_alerts() {
+        assert!(Alert::for_domain_hit("res-1", &status(2), DomainHit::Block).is_none());
+        let alert = Alert::for_domain_hit("res-1", &status(3), DomainHit::Block).unwrap();
+        assert_eq!(alert.kind, AlertKind::ProxyBurned);
+        assert!(alert.body.contains("res-1") && alert.body.contains("shop.example"));
+        assert!(Alert::for_domain_hit("res-1", &status(3), DomainHit::Captcha).is_none());
+        assert!(Alert::for_domain_hit("res-1", &status(4), DomainHit::Block).is_none());
+    }
+
+    #[test]
+    fn routes_pick_the_configured_sinks() {
+        let mut settings = NotificationSettings::default();
+        let names = |s: &NotificationSettings, kind| {
+            s.sinks_for(kind)
+                .iter()
+                .map(|k| k.name())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&settings, AlertKind::PanicShutdown), ["native"]);
+
+        settings.telegram = Some(TelegramSettings {
+            bot_token: "123:abc".into(),
+            chat_id: "42".into(),
+        });
+        settings.db_nearly_full.native = false;
+        assert_eq!(
+            names(&settings, AlertKind::PanicShutdown),
+            ["native", "telegram"]
+        );
+        assert_eq!(names(&settings, AlertKind::DbNearlyFull), ["telegram"]);
+
+        settings.telegram.as_mut().unwrap().chat_id = " ".into();
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn bot_token_is_encrypted_at_rest() {
+        let db = Db::open_in_memory_with_key("hunter2").unwrap();
+        let settings = NotificationSettings {
+            telegram: Some(TelegramSettings {
+                bot_token: "123:secret".into(),
+                chat_id: "42".into(),
+            }),
+            ..Default::default()
+        };
+        store_settings(&db, &settings).unwrap();
+
+        let raw: NotificationSettings =
+            crate::settings::load(&db, NOTIFICATION_SETTINGS_KEY).unwrap();
+        assert_ne!(raw.telegram.unwrap().bot_token, "123:secret");
+        assert_eq!(load_settings(&db).unwrap(), settings);
+    }
+}
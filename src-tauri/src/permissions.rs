@@ -0,0 +1,214 @@
+// ── Manifold permission presets ───────────────────────────────────────────────
+//
+// `navigator.permissions.query()` answers from the fingerprint's permissions
+// map, which generation seeds with what a fresh Chrome install reports.  A
+// long-lived profile rarely looks like that: a cautious user has blocked
+// location and notifications everywhere, someone who video-calls has
+// granted camera and microphone.  The presets here are those habits as
+// whole maps; single permissions can be overridden on top.
+//
+// Only the names generation seeds are accepted, so a typo can't add a
+// permission the evasion script never answers for, and only the three
+// `PermissionState` values.  Chrome derives `push` from `notifications`, so
+// the presets keep the two equal.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::Fingerprint;
+
+/// `PermissionState` values.
+pub const PERMISSION_STATES: &[&str] = &["granted", "denied", "prompt"];
+
+/// The `PermissionName`s a fingerprint carries.
+pub const PERMISSION_NAMES: &[&str] = &[
+    "geolocation",
+    "notifications",
+    "camera",
+    "microphone",
+    "clipboard-read",
+    "clipboard-write",
+    "payment-handler",
+    "accelerometer",
+    "gyroscope",
+    "magnetometer",
+    "push",
+    "midi",
+    "storage-access",
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PermissionPreset {
+    /// Blocks location, notifications and media devices.
+    PrivacyConscious,
+    /// Chrome's out-of-the-box answers: everything sensitive prompts.
+    DefaultUser,
+    /// Has allowed location, notifications, media devices and clipboard.
+    PowerUser,
+}
+
+impl fmt::Display for PermissionPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::PrivacyConscious => "privacy-conscious",
+            Self::DefaultUser => "default-user",
+            Self::PowerUser => "power-user",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for PermissionPreset {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "privacy-conscious" => Ok(Self::PrivacyConscious),
+            "default-user" => Ok(Self::DefaultUser),
+            "power-user" => Ok(Self::PowerUser),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown PermissionPreset: {other:?}"
+            ))),
+        }
+    }
+}
+
+impl PermissionPreset {
+    pub const ALL: [PermissionPreset; 3] = [
+        PermissionPreset::PrivacyConscious,
+        PermissionPreset::DefaultUser,
+        PermissionPreset::PowerUser,
+    ];
+
+    /// The state of every permission in `PERMISSION_NAMES` under this preset.
+    pub fn states(self) -> BTreeMap<String, String> {
+        PERMISSION_NAMES
+            .iter()
+            .map(|&name| (name.to_string(), self.state(name).to_string()))
+            .collect()
+    }
+
+    fn state(self, name: &str) -> &'static str {
+        match (self, name) {
+            // Granted without asking in every Chrome profile
+            (_, "clipboard-write" | "accelerometer" | "gyroscope" | "magnetometer") => "granted",
+            (
+                Self::PrivacyConscious,
+                "geolocation" | "notifications" | "push" | "camera" | "microphone",
+            ) => "denied",
+            (
+                Self::PowerUser,
+                "geolocation" | "notifications" | "push" | "camera" | "microphone"
+                | "clipboard-read",
+            ) => "granted",
+            _ => "prompt",
+        }
+    }
+}
+
+/// A preset and what it sets, for the profile editor.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetStates {
+    pub preset: PermissionPreset,
+    pub permissions: BTreeMap<String, String>,
+}
+
+pub fn presets() -> Vec<PresetStates> {
+    PermissionPreset::ALL
+        .iter()
+        .map(|&preset| PresetStates {
+            preset,
+            permissions: preset.states(),
+        })
+        .collect()
+}
+
+/// Check that `name` is a known permission and `state` a `PermissionState`.
+pub fn validate(name: &str, state: &str) -> Result<()> {
+    if !PERMISSION_NAMES.contains(&name) {
+        return Err(ManifoldError::InvalidArg(format!(
+            "unknown permission {name:?}"
+        )));
+    }
+    if !PERMISSION_STATES.contains(&state) {
+        return Err(ManifoldError::InvalidArg(format!(
+            "permission {name:?}: state must be one of {}, got {state:?}",
+            PERMISSION_STATES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Replace `fp`'s permissions with `preset` (if any), then apply
+/// `overrides`.  Nothing changes unless every override is valid.
+pub fn apply(
+    fp: &mut Fingerprint,
+    preset: Option<PermissionPreset>,
+    overrides: &HashMap<String, String>,
+) -> Result<()> {
+    for (name, state) in overrides {
+        validate(name, state)?;
+    }
+    if let Some(preset) = preset {
+        fp.permissions = preset.states().into_iter().collect();
+    }
+    fp.permissions.extend(overrides.clone());
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    #[test]
+    fn presets_cover_every_permission_with_valid_states() {
+        for preset in PermissionPreset::ALL {
+            let states = preset.states();
+            assert_eq!(states.len(), PERMISSION_NAMES.len());
+            for (name, state) in &states {
+                validate(name, state).unwrap();
+            }
+            assert_eq!(states["push"], states["notifications"], "{preset}");
+            assert_eq!(
+                preset.to_string().parse::<PermissionPreset>().unwrap(),
+                preset
+            );
+        }
+        // Generation seeds exactly the names presets can set
+        let fp = FingerprintOrchestrator::generate(7);
+        let mut seeded: Vec<&str> = fp.permissions.keys().map(String::as_str).collect();
+        let mut known = PERMISSION_NAMES.to_vec();
+        seeded.sort_unstable();
+        known.sort_unstable();
+        assert_eq!(seeded, known);
+    }
+
+    #[test]
+    fn apply_sets_preset_then_overrides_and_rejects_bad_input() {
+        let mut fp = FingerprintOrchestrator::generate(7);
+        let overrides = HashMap::from([("camera".to_string(), "granted".to_string())]);
+        apply(
+            &mut fp,
+            Some(PermissionPreset::PrivacyConscious),
+            &overrides,
+        )
+        .unwrap();
+        assert_eq!(fp.permissions["geolocation"], "denied");
+        assert_eq!(fp.permissions["camera"], "granted");
+
+        let before = fp.permissions.clone();
+        for (name, state) in [("camera", "allowed"), ("bluetooth", "granted")] {
+            let bad = HashMap::from([(name.to_string(), state.to_string())]);
+            let err = apply(&mut fp, Some(PermissionPreset::PowerUser), &bad);
+            assert!(matches!(err, Err(ManifoldError::InvalidArg(_))));
+        }
+        assert_eq!(fp.permissions, before);
+    }
+}
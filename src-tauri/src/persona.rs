@@ -0,0 +1,589 @@
+// ── Manifold profile personas ─────────────────────────────────────────────────
+//
+// A persona is the human behind a profile: who they are, what they browse and
+// when they are online.  It is optional metadata on `Profile`, but when
+// present it drives the warm-up plan (which sites to visit first, at what
+// hours) and the locale/timezone choice so the identity and the technical
+// fingerprint tell the same story.
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::Fingerprint;
+use crate::intl::IntlProfile;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AgeBracket {
+    #[serde(rename = "18-24")]
+    Age18To24,
+    #[default]
+    #[serde(rename = "25-34")]
+    Age25To34,
+    #[serde(rename = "35-44")]
+    Age35To44,
+    #[serde(rename = "45-54")]
+    Age45To54,
+    #[serde(rename = "55+")]
+    Age55Plus,
+}
+
+impl std::fmt::Display for AgeBracket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Age18To24 => "18-24",
+            Self::Age25To34 => "25-34",
+            Self::Age35To44 => "35-44",
+            Self::Age45To54 => "45-54",
+            Self::Age55Plus => "55+",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for AgeBracket {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "18-24" => Ok(Self::Age18To24),
+            "25-34" => Ok(Self::Age25To34),
+            "35-44" => Ok(Self::Age35To44),
+            "45-54" => Ok(Self::Age45To54),
+            "55+" => Ok(Self::Age55Plus),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown AgeBracket: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// Identity metadata attached to a profile.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Persona {
+    pub name: String,
+    #[serde(default)]
+    pub age_bracket: AgeBracket,
+    /// Free-form interest keywords; known ones (see `INTEREST_SITES`) steer
+    /// warm-up site selection.
+    #[serde(default)]
+    pub interests: Vec<String>,
+    /// City name, e.g. "Chicago".  Known cities pin locale and timezone.
+    #[serde(default)]
+    pub home_city: Option<String>,
+}
+
+/// Local hours (inclusive start, exclusive end, may wrap past midnight)
+/// during which the persona is typically online.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+/// Locale settings implied by a persona's home city.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CityLocale {
+    pub country: &'static str,
+    pub locale: &'static str,
+    pub accept_language: &'static str,
+    pub timezone: &'static str,
+}
+
+/// What a warm-up run should do for a profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupPlan {
+    pub sites: Vec<String>,
+    pub locale: String,
+    pub timezone: String,
+    pub active_hours: ActiveHours,
+}
+
+// ── Catalogues ────────────────────────────────────────────────────────────────
+
+/// city (lowercase) → (country, locale, accept-language, timezone)
+const CITIES: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "new york",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9",
+        "America/New_York",
+    ),
+    (
+        "boston",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9",
+        "America/New_York",
+    ),
+    (
+        "miami",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9,es;q=0.8",
+        "America/New_York",
+    ),
+    (
+        "chicago",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9",
+        "America/Chicago",
+    ),
+    (
+        "houston",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9,es;q=0.8",
+        "America/Chicago",
+    ),
+    ("denver", "US", "en-US", "en-US,en;q=0.9", "America/Denver"),
+    (
+        "los angeles",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9",
+        "America/Los_Angeles",
+    ),
+    (
+        "seattle",
+        "US",
+        "en-US",
+        "en-US,en;q=0.9",
+        "America/Los_Angeles",
+    ),
+    (
+        "toronto",
+        "CA",
+        "en-CA",
+        "en-CA,en;q=0.9,fr-CA;q=0.8",
+        "America/Toronto",
+    ),
+    (
+        "montreal",
+        "CA",
+        "fr-CA",
+        "fr-CA,fr;q=0.9,en-CA;q=0.8",
+        "America/Montreal",
+    ),
+    (
+        "vancouver",
+        "CA",
+        "en-CA",
+        "en-CA,en;q=0.9",
+        "America/Vancouver",
+    ),
+    ("london", "GB", "en-GB", "en-GB,en;q=0.9", "Europe/London"),
+    (
+        "manchester",
+        "GB",
+        "en-GB",
+        "en-GB,en;q=0.9",
+        "Europe/London",
+    ),
+    (
+        "berlin",
+        "DE",
+        "de-DE",
+        "de-DE,de;q=0.9,en;q=0.8",
+        "Europe/Berlin",
+    ),
+    (
+        "munich",
+        "DE",
+        "de-DE",
+        "de-DE,de;q=0.9,en;q=0.8",
+        "Europe/Berlin",
+    ),
+    (
+        "paris",
+        "FR",
+        "fr-FR",
+        "fr-FR,fr;q=0.9,en;q=0.8",
+        "Europe/Paris",
+    ),
+    (
+        "madrid",
+        "ES",
+        "es-ES",
+        "es-ES,es;q=0.9,en;q=0.8",
+        "Europe/Madrid",
+    ),
+    (
+        "rome",
+        "IT",
+        "it-IT",
+        "it-IT,it;q=0.9,en;q=0.8",
+        "Europe/Rome",
+    ),
+    (
+        "amsterdam",
+        "NL",
+        "nl-NL",
+        "nl-NL,nl;q=0.9,en;q=0.8",
+        "Europe/Amsterdam",
+    ),
+    (
+        "warsaw",
+        "PL",
+        "pl-PL",
+        "pl-PL,pl;q=0.9,en;q=0.8",
+        "Europe/Warsaw",
+    ),
+    (
+        "stockholm",
+        "SE",
+        "sv-SE",
+        "sv-SE,sv;q=0.9,en;q=0.8",
+        "Europe/Stockholm",
+    ),
+    (
+        "zurich",
+        "CH",
+        "de-CH",
+        "de-CH,de;q=0.9,en;q=0.8",
+        "Europe/Zurich",
+    ),
+    (
+        "sydney",
+        "AU",
+        "en-AU",
+        "en-AU,en;q=0.9",
+        "Australia/Sydney",
+    ),
+    (
+        "melbourne",
+        "AU",
+        "en-AU",
+        "en-AU,en;q=0.9",
+        "Australia/Melbourne",
+    ),
+    (
+        "tokyo",
+        "JP",
+        "ja-JP",
+        "ja-JP,ja;q=0.9,en;q=0.8",
+        "Asia/Tokyo",
+    ),
+    (
+        "seoul",
+        "KR",
+        "ko-KR",
+        "ko-KR,ko;q=0.9,en;q=0.8",
+        "Asia/Seoul",
+    ),
+    (
+        "singapore",
+        "SG",
+        "en-SG",
+        "en-SG,en;q=0.9",
+        "Asia/Singapore",
+    ),
+    (
+        "mumbai",
+        "IN",
+        "en-IN",
+        "en-IN,en;q=0.9,hi;q=0.8",
+        "Asia/Kolkata",
+    ),
+    (
+        "sao paulo",
+        "BR",
+        "pt-BR",
+        "pt-BR,pt;q=0.9,en;q=0.8",
+        "America/Sao_Paulo",
+    ),
+    (
+        "mexico city",
+        "MX",
+        "es-MX",
+        "es-MX,es;q=0.9,en;q=0.8",
+        "America/Mexico_City",
+    ),
+];
+
+/// interest keyword → sites a person with that interest plausibly visits.
+const INTEREST_SITES: &[(&str, &[&str])] = &[
+    (
+        "news",
+        &[
+            "https://www.reuters.com",
+            "https://apnews.com",
+            "https://www.bbc.com/news",
+        ],
+    ),
+    (
+        "tech",
+        &[
+            "https://news.ycombinator.com",
+            "https://www.theverge.com",
+            "https://arstechnica.com",
+        ],
+    ),
+    (
+        "sports",
+        &[
+            "https://www.espn.com",
+            "https://www.skysports.com",
+            "https://www.nba.com",
+        ],
+    ),
+    (
+        "cooking",
+        &[
+            "https://www.allrecipes.com",
+            "https://www.seriouseats.com",
+            "https://www.bbcgoodfood.com",
+        ],
+    ),
+    (
+        "travel",
+        &[
+            "https://www.tripadvisor.com",
+            "https://www.lonelyplanet.com",
+            "https://www.booking.com",
+        ],
+    ),
+    (
+        "finance",
+        &[
+            "https://finance.yahoo.com",
+            "https://www.investopedia.com",
+            "https://www.marketwatch.com",
+        ],
+    ),
+    (
+        "gaming",
+        &[
+            "https://www.ign.com",
+            "https://store.steampowered.com",
+            "https://www.pcgamer.com",
+        ],
+    ),
+    (
+        "fashion",
+        &[
+            "https://www.vogue.com",
+            "https://www.zara.com",
+            "https://www.asos.com",
+        ],
+    ),
+    (
+        "music",
+        &[
+            "https://pitchfork.com",
+            "https://www.billboard.com",
+            "https://bandcamp.com",
+        ],
+    ),
+    (
+        "movies",
+        &[
+            "https://www.imdb.com",
+            "https://www.rottentomatoes.com",
+            "https://letterboxd.com",
+        ],
+    ),
+    (
+        "health",
+        &[
+            "https://www.healthline.com",
+            "https://www.mayoclinic.org",
+            "https://www.webmd.com",
+        ],
+    ),
+    (
+        "diy",
+        &[
+            "https://www.instructables.com",
+            "https://www.familyhandyman.com",
+            "https://www.homedepot.com",
+        ],
+    ),
+];
+
+/// Generic sites everybody visits; used to pad the plan.
+const COMMON_SITES: &[&str] = &[
+    "https://www.google.com",
+    "https://www.youtube.com",
+    "https://www.wikipedia.org",
+    "https://www.amazon.com",
+    "https://www.reddit.com",
+    "https://weather.com",
+];
+
+// ── Persona logic ─────────────────────────────────────────────────────────────
+
+impl Persona {
+    /// Locale implied by `home_city`, if the city is known.
+    pub fn city_locale(&self) -> Option<CityLocale> {
+        let city = self.home_city.as_deref()?.trim().to_lowercase();
+        CITIES.iter().find(|(name, ..)| *name == city).map(
+            |&(_, country, locale, accept_language, timezone)| CityLocale {
+                country,
+                locale,
+                accept_language,
+                timezone,
+            },
+        )
+    }
+
+    /// Default online window by age bracket — younger personas skew late.
+    pub fn active_hours(&self) -> ActiveHours {
+        let (start_hour, end_hour) = match self.age_bracket {
+            AgeBracket::Age18To24 => (11, 2),
+            AgeBracket::Age25To34 => (8, 24),
+            AgeBracket::Age35To44 => (7, 23),
+            AgeBracket::Age45To54 => (7, 22),
+            AgeBracket::Age55Plus => (6, 21),
+        };
+        ActiveHours {
+            start_hour,
+            end_hour,
+        }
+    }
+
+    /// Up to `count` warm-up sites: interest sites first, padded with common
+    /// ones.  Order is shuffled deterministically from `seed`.
+    pub fn warmup_sites(&self, seed: u64, count: usize) -> Vec<String> {
+        let mut rng = SmallRng::seed_from_u64(seed ^ 0x7e75_0a11);
+        let mut sites: Vec<&str> = Vec::new();
+        for interest in &self.interests {
+            let key = interest.trim().to_lowercase();
+            if let Some((_, list)) = INTEREST_SITES.iter().find(|(k, _)| *k == key) {
+                sites.extend(list.iter().copied());
+            }
+        }
+        sites.sort_unstable();
+        sites.dedup();
+        sites.shuffle(&mut rng);
+        sites.truncate(count);
+
+        let mut common: Vec<&str> = COMMON_SITES
+            .iter()
+            .copied()
+            .filter(|s| !sites.contains(s))
+            .collect();
+        common.shuffle(&mut rng);
+        let missing = count.saturating_sub(sites.len());
+        sites.extend(common.into_iter().take(missing));
+        sites.into_iter().map(str::to_string).collect()
+    }
+
+    /// Overwrite locale/timezone of `fp` with the home city's, if known.
+    /// Returns whether anything was applied.
+    pub fn apply_locale(&self, fp: &mut Fingerprint) -> bool {
+        match self.city_locale() {
+            Some(c) => {
+                fp.locale = c.locale.into();
+                fp.intl = Some(IntlProfile::for_locale(c.locale));
+                fp.accept_language = c.accept_language.into();
+                fp.timezone = c.timezone.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Warm-up plan for a profile with this persona and fingerprint.
+    pub fn warmup_plan(&self, fp: &Fingerprint, site_count: usize) -> WarmupPlan {
+        let (locale, timezone) = match self.city_locale() {
+            Some(c) => (c.locale.to_string(), c.timezone.to_string()),
+            None => (fp.locale.clone(), fp.timezone.clone()),
+        };
+        WarmupPlan {
+            sites: self.warmup_sites(fp.seed, site_count),
+            locale,
+            timezone,
+            active_hours: self.active_hours(),
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    fn persona() -> Persona {
+        Persona {
+            name: "Dana".into(),
+            age_bracket: AgeBracket::Age18To24,
+            interests: vec!["Gaming".into(), "music".into()],
+            home_city: Some("Chicago".into()),
+        }
+    }
+
+    #[test]
+    fn age_bracket_roundtrip() {
+        for s in &["18-24", "25-34", "35-44", "45-54", "55+"] {
+            let b: AgeBracket = s.parse().unwrap();
+            assert_eq!(b.to_string(), *s);
+            assert_eq!(serde_json::to_string(&b).unwrap(), format!("\"{s}\""));
+        }
+        assert!("teen".parse::<AgeBracket>().is_err());
+    }
+
+    #[test]
+    fn known_city_pins_locale_and_timezone() {
+        let mut fp = FingerprintOrchestrator::generate(1);
+        assert!(persona().apply_locale(&mut fp));
+        assert_eq!(fp.timezone, "America/Chicago");
+        assert_eq!(fp.locale, "en-US");
+
+        let unknown = Persona {
+            home_city: Some("Atlantis".into()),
+            ..persona()
+        };
+        assert!(unknown.city_locale().is_none());
+    }
+
+    #[test]
+    fn warmup_sites_prefer_interests_and_are_deterministic() {
+        let p = persona();
+        let sites = p.warmup_sites(42, 8);
+        assert_eq!(sites.len(), 8);
+        assert_eq!(sites, p.warmup_sites(42, 8));
+        let interest_hits = sites
+            .iter()
+            .filter(|s| {
+                s.contains("ign.com") || s.contains("pitchfork") || s.contains("steampowered")
+            })
+            .count();
+        assert!(interest_hits >= 2);
+    }
+
+    #[test]
+    fn warmup_sites_skip_repeated_interests() {
+        let p = Persona {
+            interests: vec!["news".into(), "News".into(), "news ".into()],
+            ..persona()
+        };
+        let sites = p.warmup_sites(7, 6);
+        let mut unique = sites.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(sites.len(), 6);
+        assert_eq!(unique.len(), 6);
+    }
+
+    #[test]
+    fn warmup_plan_uses_persona_hours() {
+        let fp = FingerprintOrchestrator::generate(5);
+        let plan = persona().warmup_plan(&fp, 5);
+        assert_eq!(
+            plan.active_hours,
+            ActiveHours {
+                start_hour: 11,
+                end_hour: 2
+            }
+        );
+        assert_eq!(plan.timezone, "America/Chicago");
+    }
+}
@@ -0,0 +1,219 @@
+// ── Manifold host power events ────────────────────────────────────────────────
+//
+// A host that sleeps with a profile open leaves a browser whose proxy
+// connections are dead and whose clock is about to jump, and — if the bridge
+// doesn't survive — a profile stuck at Running.  The watcher here turns host
+// power transitions into `PowerEvent`s so running sessions can be paused
+// before sleep and resumed or stopped on wake.
+//
+// On Linux the watcher follows logind's `PrepareForSleep` signal through
+// `dbus-monitor` and holds a delay inhibitor (`systemd-inhibit`) so the pause
+// can finish before the machine goes down.  Elsewhere, or without those
+// tools, sleep can only be noticed after the fact: a heartbeat thread sees
+// the wall clock leap past the monotonic clock and reports a wake.
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// `settings` key holding `PowerSettings` (JSON).
+pub const POWER_SETTINGS_KEY: &str = "power";
+
+/// Heartbeat of the wake detector.
+const HEARTBEAT: Duration = Duration::from_secs(5);
+/// Wall time beyond the monotonic time between heartbeats that counts as a
+/// sleep (monotonic clocks stop while the host is suspended).
+const WAKE_GAP: Duration = Duration::from_secs(30);
+
+#[cfg(target_os = "linux")]
+const LOGIND_MATCH: &str =
+    "type='signal',interface='org.freedesktop.login1.Manager',member='PrepareForSleep'";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerEvent {
+    /// The host is about to sleep.
+    Sleep,
+    /// The host woke up.
+    Wake,
+}
+
+/// What to do with suspended profiles on wake.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerSettings {
+    /// Relaunch profiles paused for sleep; otherwise they are stopped.
+    pub resume_on_wake: bool,
+}
+
+// ── logind signal parsing ─────────────────────────────────────────────────────
+
+/// Line-by-line parser for `dbus-monitor` output: the signal header line
+/// is followed by the `boolean true|false` argument line.
+#[derive(Debug, Default)]
+pub struct LogindParser {
+    in_signal: bool,
+}
+
+impl LogindParser {
+    pub fn feed(&mut self, line: &str) -> Option<PowerEvent> {
+        let line = line.trim();
+        if line.starts_with("signal ") {
+            self.in_signal = line.contains("member=PrepareForSleep");
+            return None;
+        }
+        if !self.in_signal {
+            return None;
+        }
+        self.in_signal = false;
+        match line {
+            "boolean true" => Some(PowerEvent::Sleep),
+            "boolean false" => Some(PowerEvent::Wake),
+            _ => None,
+        }
+    }
+}
+
+/// Did the host sleep between two heartbeats?  `wall` and `mono` are the
+/// elapsed wall-clock and monotonic times.
+pub fn slept_between(wall: chrono::Duration, mono: Duration) -> bool {
+    wall.to_std()
+        .is_ok_and(|wall| wall.saturating_sub(mono) >= WAKE_GAP)
+}
+
+// ── Watcher ───────────────────────────────────────────────────────────────────
+
+/// Run `on_event` for every host power transition, on a background thread
+/// for the lifetime of the app.  `on_event` returns once its work is done;
+/// on Linux the sleep is held off until then.
+pub fn spawn_watcher<F>(on_event: F)
+where
+    F: Fn(PowerEvent) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        #[cfg(target_os = "linux")]
+        if let Some(monitor) = spawn_logind_monitor() {
+            watch_logind(monitor, &on_event);
+        }
+        watch_heartbeat(&on_event);
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_logind_monitor() -> Option<std::process::Child> {
+    use std::process::{Command, Stdio};
+
+    Command::new("dbus-monitor")
+        .args(["--system", LOGIND_MATCH])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()
+}
+
+/// Delay lock that keeps logind waiting (up to its `InhibitDelayMaxSec`)
+/// after announcing sleep.  Released when dropped.
+#[cfg(target_os = "linux")]
+struct SleepInhibitor(std::process::Child);
+
+#[cfg(target_os = "linux")]
+impl SleepInhibitor {
+    fn take() -> Option<Self> {
+        use std::process::{Command, Stdio};
+
+        Command::new("systemd-inhibit")
+            .args([
+                "--what=sleep",
+                "--mode=delay",
+                "--who=Manifold",
+                "--why=Pausing running browser profiles",
+                "sleep",
+                "infinity",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()
+            .map(Self)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        self.0.kill().ok();
+        self.0.wait().ok();
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn watch_logind<F: Fn(PowerEvent)>(mut monitor: std::process::Child, on_event: &F) {
+    use std::io::{BufRead, BufReader};
+
+    let Some(stdout) = monitor.stdout.take() else {
+        return;
+    };
+    let mut inhibitor = SleepInhibitor::take();
+    let mut parser = LogindParser::default();
+    for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+        match parser.feed(&line) {
+            Some(PowerEvent::Sleep) => {
+                on_event(PowerEvent::Sleep);
+                // Paused — let the machine go down
+                drop(inhibitor.take());
+            }
+            Some(PowerEvent::Wake) => {
+                inhibitor = inhibitor.or_else(SleepInhibitor::take);
+                on_event(PowerEvent::Wake);
+            }
+            None => {}
+        }
+    }
+    // dbus-monitor went away; the heartbeat takes over
+    monitor.kill().ok();
+    monitor.wait().ok();
+}
+
+fn watch_heartbeat<F: Fn(PowerEvent)>(on_event: &F) {
+    let mut last = (Utc::now(), Instant::now());
+    loop {
+        std::thread::sleep(HEARTBEAT);
+        let now = (Utc::now(), Instant::now());
+        if slept_between(now.0 - last.0, now.1 - last.1) {
+            on_event(PowerEvent::Wake);
+        }
+        last = now;
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prepare_for_sleep_signals() {
+        let out = "\
+signal time=1700000000.1 sender=:1.4 -> destination=(null destination) serial=9 path=/org/freedesktop/login1; interface=org.freedesktop.login1.Manager; member=PrepareForSleep
+   boolean true
+signal time=1700000900.2 sender=:1.4 -> destination=(null destination) serial=10 path=/org/freedesktop/login1; interface=org.freedesktop.login1.Manager; member=PrepareForShutdown
+   boolean true
+signal time=1700000960.3 sender=:1.4 -> destination=(null destination) serial=11 path=/org/freedesktop/login1; interface=org.freedesktop.login1.Manager; member=PrepareForSleep
+   boolean false";
+        let mut parser = LogindParser::default();
+        let events: Vec<_> = out.lines().filter_map(|l| parser.feed(l)).collect();
+        assert_eq!(events, [PowerEvent::Sleep, PowerEvent::Wake]);
+    }
+
+    #[test]
+    fn wall_gap_beyond_monotonic_means_sleep() {
+        let beat = Duration::from_secs(5);
+        assert!(!slept_between(chrono::Duration::seconds(6), beat));
+        assert!(slept_between(chrono::Duration::minutes(20), beat));
+        // Clock set back is not a sleep
+        assert!(!slept_between(chrono::Duration::seconds(-3600), beat));
+    }
+}
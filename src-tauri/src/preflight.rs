@@ -0,0 +1,213 @@
+// ── Manifold launch preflight ─────────────────────────────────────────────────
+//
+// A dry run of a profile before it is trusted with real work, typically
+// ahead of a large scheduled batch.  The bridge is started exactly as a
+// launch would start it (same proxy route, evasions and headless browser)
+// but never serves the live view: it runs the leak probe, opens the
+// workflow's start URL, counts what each of the workflow's selectors
+// matches there and exits.  No session is recorded and the profile's
+// status doesn't change.
+//
+// Preflight-mode output is a single stdout line: `PREFLIGHT {json}`.  The
+// leak probe inside it is judged like a leak test's (and stored as the
+// profile's latest one); the profile is ready when that passed, the page
+// loaded and every selector matched something.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::leak_test::{CheckStatus, LeakProbe, LeakTestReport};
+
+/// Prefix of the stdout line carrying the bridge's preflight results.
+pub const PREFLIGHT_LINE_PREFIX: &str = "PREFLIGHT ";
+
+/// How long the bridge gets for the leak probe plus the page visit.
+pub const PREFLIGHT_TIMEOUT_SECS: u64 = 150;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What the preflight visits: the workflow's start page and the selectors
+/// the workflow expects on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightPlan {
+    pub url: String,
+    #[serde(default)]
+    pub selectors: Vec<String>,
+}
+
+impl PreflightPlan {
+    pub fn validate(&self) -> Result<()> {
+        let url = url::Url::parse(&self.url)
+            .map_err(|e| ManifoldError::InvalidArg(format!("preflight url: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ManifoldError::InvalidArg(
+                "preflight url must be http(s)".into(),
+            ));
+        }
+        if self.selectors.iter().any(|s| s.trim().is_empty()) {
+            return Err(ManifoldError::InvalidArg(
+                "preflight selectors cannot be empty".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How many elements a selector matched on the page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectorCheck {
+    pub selector: String,
+    pub count: u32,
+    /// Set when the selector couldn't be evaluated (invalid syntax).
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// What the bridge reports in preflight mode.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreflightProbe {
+    pub leak: LeakProbe,
+    /// HTTP status of the start page; `None` when navigation failed.
+    pub status: Option<u16>,
+    pub final_url: Option<String>,
+    /// Why navigation failed.
+    pub error: Option<String>,
+    pub selectors: Vec<SelectorCheck>,
+}
+
+/// The verdict on one profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightReport {
+    pub profile_id: String,
+    /// The leak test passed, the page loaded and every selector matched.
+    pub ready: bool,
+    pub leak_test: LeakTestReport,
+    pub url: String,
+    pub status: Option<u16>,
+    pub final_url: Option<String>,
+    pub selectors: Vec<SelectorCheck>,
+    /// Why the profile isn't ready, one line per problem.
+    pub issues: Vec<String>,
+    pub ran_at: DateTime<Utc>,
+}
+
+// ── Evaluation ────────────────────────────────────────────────────────────────
+
+/// Pull the results out of the bridge's stdout line, if this is that line.
+pub fn parse_preflight_line(line: &str) -> Option<Result<PreflightProbe>> {
+    let json = line.trim().strip_prefix(PREFLIGHT_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).map_err(ManifoldError::from))
+}
+
+/// Combine the judged leak probe with the page visit into the report.
+pub fn evaluate(
+    plan: &PreflightPlan,
+    probe: PreflightProbe,
+    leak_test: LeakTestReport,
+) -> PreflightReport {
+    let mut issues: Vec<String> = leak_test
+        .checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .map(|c| format!("leak test ({}): {}", c.name, c.detail))
+        .collect();
+    let load_issue = match (probe.status, &probe.error) {
+        (_, Some(error)) => Some(format!("page did not load: {error}")),
+        (None, None) => Some("page did not load".into()),
+        (Some(status), None) if status >= 400 => Some(format!("page answered HTTP {status}")),
+        _ => None,
+    };
+    if let Some(issue) = load_issue {
+        // Selectors on a page that never loaded say nothing more
+        issues.push(issue);
+    } else {
+        for check in &probe.selectors {
+            match &check.error {
+                Some(error) => issues.push(format!("selector {:?}: {error}", check.selector)),
+                None if check.count == 0 => {
+                    issues.push(format!("selector {:?} matched nothing", check.selector))
+                }
+                None => {}
+            }
+        }
+        // A bridge that skipped a selector must not pass it
+        for selector in &plan.selectors {
+            if !probe.selectors.iter().any(|c| &c.selector == selector) {
+                issues.push(format!("selector {selector:?} was not checked"));
+            }
+        }
+    }
+    PreflightReport {
+        profile_id: leak_test.profile_id.clone(),
+        ready: issues.is_empty(),
+        leak_test,
+        url: plan.url.clone(),
+        status: probe.status,
+        final_url: probe.final_url,
+        selectors: probe.selectors,
+        issues,
+        ran_at: Utc::now(),
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passed_leak_test() -> LeakTestReport {
+        LeakTestReport {
+            profile_id: "p1".into(),
+            passed: true,
+            exit_ip: None,
+            exit_country_code: None,
+            checks: Vec::new(),
+            ran_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn ready_needs_the_page_and_every_selector() {
+        let plan = PreflightPlan {
+            url: "https://shop.example.com/login".into(),
+            selectors: vec!["#user".into(), "#pass".into()],
+        };
+        plan.validate().unwrap();
+        let line = r##"PREFLIGHT {"leak":{"timezone":"UTC"},"status":200,"selectors":[{"selector":"#user","count":1},{"selector":"#pass","count":1}]}"##;
+        let probe = parse_preflight_line(line).unwrap().unwrap();
+        let report = evaluate(&plan, probe.clone(), passed_leak_test());
+        assert!(report.ready, "{:?}", report.issues);
+
+        let mut missing = probe.clone();
+        missing.selectors[1].count = 0;
+        missing.selectors.remove(0);
+        let report = evaluate(&plan, missing, passed_leak_test());
+        assert!(!report.ready);
+        assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+
+        let mut blocked = probe;
+        blocked.status = Some(403);
+        assert!(!evaluate(&plan, blocked, passed_leak_test()).ready);
+        assert!(parse_preflight_line("LEAK_PROBE {}").is_none());
+    }
+
+    #[test]
+    fn plan_rejects_non_http_urls_and_blank_selectors() {
+        for (url, selectors) in [
+            ("file:///etc/passwd", vec![]),
+            ("not a url", vec![]),
+            ("https://example.com", vec![" ".to_string()]),
+        ] {
+            let plan = PreflightPlan {
+                url: url.into(),
+                selectors,
+            };
+            assert!(matches!(plan.validate(), Err(ManifoldError::InvalidArg(_))));
+        }
+    }
+}
@@ -0,0 +1,717 @@
+// ── Manifold population priors ────────────────────────────────────────────────
+//
+// Market shares of the values a fingerprint exposes — screen resolutions,
+// GPU vendors, locales, timezones, how far behind stable Chrome installs
+// are — for the code that judges how common a fingerprint is: the entropy
+// report, the geo gates (countries where 4K screens or high pixel ratios are
+// common) and the aging task's adoption curve.  Shares drift quarter by
+// quarter, so they are a dataset rather than constants: a built-in copy
+// ships with the app and a newer one can be fetched from a configured URL.
+//
+// A fetched dataset is only accepted signed.  The document carries the
+// dataset JSON and an Ed25519 signature over it, checked against the public
+// key configured with the URL, and its version must be newer than the one
+// in use, so an older dataset can't be replayed.  The accepted document is
+// cached in `settings` and verified again on every start.
+//
+// The generator's pools don't follow the dataset: they are frozen per
+// generator version (`GENERATOR_VERSION`), since new weights would change
+// what existing seeds regenerate.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chrono::{DateTime, NaiveDate, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{Context, ManifoldError, Result};
+use crate::settings::{load, store};
+
+/// `settings` key holding `PriorsSource` (JSON).
+pub const PRIORS_SOURCE_KEY: &str = "priors_source";
+
+/// `settings` key holding the last accepted `CachedPriors`.
+const PRIORS_CACHE_KEY: &str = "priors_cache";
+
+/// Version of the built-in dataset; a fetched one must be newer.
+const BUILTIN_VERSION: u32 = 1;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Dataset in use when it isn't the built-in one.
+static ACTIVE: RwLock<Option<Arc<Priors>>> = RwLock::new(None);
+
+/// The built-in dataset, built on first use.
+static BUILTIN: OnceLock<Arc<Priors>> = OnceLock::new();
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// Population shares of one field's values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Distribution {
+    /// Value → share of the population, 0–1.
+    pub shares: BTreeMap<String, f64>,
+    /// Values not listed; they split the remaining share evenly.
+    #[serde(default)]
+    pub unlisted: u32,
+}
+
+impl Distribution {
+    fn of(shares: &[(&str, f64)], unlisted: u32) -> Self {
+        Self {
+            shares: shares.iter().map(|(v, p)| (v.to_string(), *p)).collect(),
+            unlisted,
+        }
+    }
+
+    /// Share not covered by the listed values.
+    pub fn other_share(&self) -> f64 {
+        (1.0 - self.shares.values().sum::<f64>()).max(0.0)
+    }
+
+    /// Share of `value`: its listed one, or its part of the rest.
+    pub fn share(&self, value: &str) -> f64 {
+        match self.shares.get(value) {
+            Some(p) => *p,
+            None if self.unlisted > 0 => self.other_share() / self.unlisted as f64,
+            None => 0.0,
+        }
+    }
+
+    /// Shannon entropy in bits: what the average user's value tells.
+    pub fn entropy(&self) -> f64 {
+        let listed: f64 = self
+            .shares
+            .values()
+            .filter(|p| **p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum();
+        let other = self.other_share();
+        if other <= 0.0 || self.unlisted == 0 {
+            return listed;
+        }
+        listed + other * (self.unlisted as f64 / other).log2()
+    }
+
+    fn validate(&self, field: &str) -> Result<()> {
+        let valid = self.shares.values().all(|p| (0.0..=1.0).contains(p))
+            && self.shares.values().sum::<f64>() <= 1.0 + 1e-6;
+        if !valid {
+            return Err(ManifoldError::InvalidArg(format!(
+                "priors {field}: shares must lie in 0–1 and sum to at most 1"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The population dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Priors {
+    /// Increases with every published dataset.
+    pub version: u32,
+    /// Date the shares were measured.
+    pub published: NaiveDate,
+    /// By `ua_platform`.
+    pub platform: Distribution,
+    /// Chrome majors behind the current stable; negative ones are ahead of
+    /// it (Beta, Dev).
+    pub chrome_lag: Distribution,
+    /// `"<width>x<height>"`.
+    pub screen: Distribution,
+    pub pixel_ratio: Distribution,
+    pub hardware_concurrency: Distribution,
+    pub device_memory: Distribution,
+    pub color_depth: Distribution,
+    pub color_gamut: Distribution,
+    pub hdr: Distribution,
+    pub color_scheme: Distribution,
+    pub reduced_motion: Distribution,
+    pub forced_colors: Distribution,
+    /// GPU vendor family by `ua_platform`; `"Windows"` stands in for
+    /// platforms not listed.
+    pub gpu_vendor: BTreeMap<String, Distribution>,
+    /// Models in use per vendor family, taken as equally likely; `"Other"`
+    /// covers families not listed.
+    pub gpu_models: BTreeMap<String, u32>,
+    pub timezone: Distribution,
+    pub locale: Distribution,
+    /// Share of the OS release's stock fonts an install has, by band.
+    pub font_coverage: Distribution,
+    /// Countries where 4K desktop screens are common (over 5 %).
+    pub uhd_countries: Vec<String>,
+    /// Countries where pixel ratios of 2 and above are rare.
+    pub low_dpr_countries: Vec<String>,
+}
+
+/// Where newer datasets come from.  Both or neither are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriorsSource {
+    /// https URL of the signed dataset document.
+    pub url: Option<String>,
+    /// Base64 Ed25519 public key the dataset must be signed with.
+    pub public_key: Option<String>,
+}
+
+/// A dataset as published: the `Priors` JSON and its signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedPriors {
+    /// Base64 of the dataset JSON.
+    pub payload: String,
+    /// Base64 Ed25519 signature over the decoded payload.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedPriors {
+    document: SignedPriors,
+    fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriorsStatus {
+    pub version: u32,
+    pub published: NaiveDate,
+    /// A fetched dataset is in use rather than the built-in one.
+    pub downloaded: bool,
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl PriorsSource {
+    pub fn validate(&self) -> Result<()> {
+        match (&self.url, &self.public_key) {
+            (None, None) => Ok(()),
+            (Some(url), Some(key)) => {
+                if !url.starts_with("https://") || url::Url::parse(url).is_err() {
+                    return Err(ManifoldError::InvalidArg(format!(
+                        "priors url {url:?}: expected an https URL"
+                    )));
+                }
+                public_key_bytes(key).map(|_| ())
+            }
+            _ => Err(ManifoldError::InvalidArg(
+                "priors source needs both a URL and a public key".into(),
+            )),
+        }
+    }
+}
+
+fn public_key_bytes(key: &str) -> Result<Vec<u8>> {
+    match B64.decode(key.trim()) {
+        Ok(bytes) if bytes.len() == 32 => Ok(bytes),
+        _ => Err(ManifoldError::InvalidArg(
+            "priors public key: expected 32 bytes of base64".into(),
+        )),
+    }
+}
+
+// ── Dataset ───────────────────────────────────────────────────────────────────
+
+impl Priors {
+    pub fn validate(&self) -> Result<()> {
+        let fields = [
+            ("platform", &self.platform),
+            ("chrome_lag", &self.chrome_lag),
+            ("screen", &self.screen),
+            ("pixel_ratio", &self.pixel_ratio),
+            ("hardware_concurrency", &self.hardware_concurrency),
+            ("device_memory", &self.device_memory),
+            ("color_depth", &self.color_depth),
+            ("color_gamut", &self.color_gamut),
+            ("hdr", &self.hdr),
+            ("color_scheme", &self.color_scheme),
+            ("reduced_motion", &self.reduced_motion),
+            ("forced_colors", &self.forced_colors),
+            ("timezone", &self.timezone),
+            ("locale", &self.locale),
+            ("font_coverage", &self.font_coverage),
+        ];
+        for (field, dist) in fields {
+            dist.validate(field)?;
+        }
+        for (platform, dist) in &self.gpu_vendor {
+            dist.validate(&format!("gpu_vendor.{platform}"))?;
+        }
+        if !self.gpu_vendor.contains_key("Windows") || !self.gpu_models.contains_key("Other") {
+            return Err(ManifoldError::InvalidArg(
+                "priors: gpu_vendor needs \"Windows\" and gpu_models \"Other\"".into(),
+            ));
+        }
+        let countries = self.uhd_countries.iter().chain(&self.low_dpr_countries);
+        if let Some(cc) = countries
+            .into_iter()
+            .find(|cc| cc.len() != 2 || !cc.bytes().all(|b| b.is_ascii_uppercase()))
+        {
+            return Err(ManifoldError::InvalidArg(format!(
+                "priors: {cc:?} is not an ISO-3166 alpha-2 country code"
+            )));
+        }
+        Ok(())
+    }
+
+    /// GPU vendor shares on `ua_platform`.
+    pub fn gpu_vendor_on(&self, ua_platform: &str) -> &Distribution {
+        self.gpu_vendor
+            .get(ua_platform)
+            .or_else(|| self.gpu_vendor.get("Windows"))
+            .expect("validated priors have Windows GPU shares")
+    }
+
+    /// Models in use of a GPU vendor family.
+    pub fn gpu_model_count(&self, vendor: &str) -> u32 {
+        let count = self
+            .gpu_models
+            .get(vendor)
+            .or_else(|| self.gpu_models.get("Other"));
+        count.copied().unwrap_or(1).max(1)
+    }
+
+    /// 4K desktop screens are common in country `cc`.
+    pub fn allows_uhd(&self, cc: &str) -> bool {
+        self.uhd_countries.iter().any(|c| c == cc)
+    }
+
+    /// Pixel ratios of 2 and above are common enough in country `cc`.
+    pub fn allows_high_dpr(&self, cc: &str) -> bool {
+        !self.low_dpr_countries.iter().any(|c| c == cc)
+    }
+
+    /// The dataset shipped with the app.
+    #[cfg(test)]
+    pub(crate) fn builtin() -> &'static Priors {
+        builtin_arc()
+    }
+}
+
+fn builtin_arc() -> &'static Arc<Priors> {
+    BUILTIN.get_or_init(|| Arc::new(builtin_priors()))
+}
+
+/// StatCounter desktop, the Steam hardware survey and Chrome release data,
+/// Q1 2025.
+fn builtin_priors() -> Priors {
+    let d = Distribution::of;
+    let countries = |ccs: &[&str]| ccs.iter().map(|c| c.to_string()).collect();
+    Priors {
+        version: BUILTIN_VERSION,
+        published: NaiveDate::from_ymd_opt(2025, 4, 1).expect("valid date"),
+        platform: d(&[("Windows", 0.72), ("macOS", 0.17), ("Linux", 0.05)], 3),
+        chrome_lag: d(
+            &[
+                ("-2", 0.003),
+                ("-1", 0.01),
+                ("0", 0.55),
+                ("1", 0.21),
+                ("2", 0.085),
+                ("3", 0.05),
+                ("4", 0.03),
+                ("5", 0.02),
+            ],
+            40,
+        ),
+        screen: d(
+            &[
+                ("1920x1080", 0.23),
+                ("1536x864", 0.10),
+                ("1366x768", 0.09),
+                ("2560x1440", 0.08),
+                ("1440x900", 0.06),
+                ("1280x720", 0.04),
+                ("1470x956", 0.03),
+                ("1680x1050", 0.03),
+                ("1600x900", 0.03),
+                ("1280x800", 0.03),
+                ("1512x982", 0.02),
+                ("1280x1024", 0.02),
+                ("2560x1600", 0.02),
+                ("3840x2160", 0.02),
+                ("2880x1800", 0.01),
+            ],
+            150,
+        ),
+        pixel_ratio: d(
+            &[
+                ("1", 0.52),
+                ("1.25", 0.15),
+                ("1.5", 0.10),
+                ("1.75", 0.01),
+                ("2", 0.17),
+                ("2.25", 0.005),
+                ("2.5", 0.01),
+                ("3", 0.01),
+            ],
+            20,
+        ),
+        hardware_concurrency: d(
+            &[
+                ("2", 0.04),
+                ("4", 0.20),
+                ("6", 0.07),
+                ("8", 0.30),
+                ("10", 0.02),
+                ("12", 0.13),
+                ("16", 0.15),
+                ("20", 0.03),
+                ("24", 0.03),
+                ("32", 0.02),
+            ],
+            20,
+        ),
+        // Chrome clamps deviceMemory to 8 and rounds down to a power
+        // of two, so nearly every desktop reports 8 or 4
+        device_memory: d(
+            &[
+                ("8", 0.72),
+                ("4", 0.20),
+                ("2", 0.05),
+                ("1", 0.015),
+                ("0.5", 0.008),
+                ("0.25", 0.002),
+            ],
+            1,
+        ),
+        color_depth: d(&[("24", 0.96), ("30", 0.035)], 2),
+        color_gamut: d(&[("srgb", 0.70), ("p3", 0.29)], 1),
+        hdr: d(&[("false", 0.90), ("true", 0.10)], 0),
+        color_scheme: d(&[("light", 0.65), ("dark", 0.35)], 0),
+        reduced_motion: d(&[("false", 0.965), ("true", 0.035)], 0),
+        forced_colors: d(&[("false", 0.997), ("true", 0.003)], 0),
+        gpu_vendor: [
+            (
+                "Windows",
+                d(
+                    &[
+                        ("Intel", 0.42),
+                        ("NVIDIA", 0.36),
+                        ("AMD", 0.18),
+                        ("Software", 0.02),
+                    ],
+                    3,
+                ),
+            ),
+            (
+                "macOS",
+                d(&[("Apple", 0.82), ("Intel", 0.15), ("AMD", 0.02)], 2),
+            ),
+            (
+                "Linux",
+                d(
+                    &[
+                        ("Intel", 0.45),
+                        ("AMD", 0.22),
+                        ("NVIDIA", 0.20),
+                        ("Software", 0.08),
+                    ],
+                    3,
+                ),
+            ),
+        ]
+        .into_iter()
+        .map(|(p, dist)| (p.to_string(), dist))
+        .collect(),
+        gpu_models: [
+            ("NVIDIA", 80),
+            ("AMD", 60),
+            ("Intel", 40),
+            ("Apple", 15),
+            ("Software", 3),
+            ("Other", 20),
+        ]
+        .into_iter()
+        .map(|(v, n)| (v.to_string(), n))
+        .collect(),
+        timezone: d(
+            &[
+                ("America/New_York", 0.09),
+                ("America/Chicago", 0.05),
+                ("America/Los_Angeles", 0.05),
+                ("America/Denver", 0.015),
+                ("America/Sao_Paulo", 0.03),
+                ("America/Toronto", 0.01),
+                ("America/Mexico_City", 0.015),
+                ("Europe/London", 0.04),
+                ("Europe/Berlin", 0.04),
+                ("Europe/Paris", 0.03),
+                ("Europe/Madrid", 0.015),
+                ("Europe/Rome", 0.015),
+                ("Europe/Amsterdam", 0.008),
+                ("Europe/Warsaw", 0.01),
+                ("Europe/Moscow", 0.03),
+                ("Asia/Kolkata", 0.07),
+                ("Asia/Shanghai", 0.06),
+                ("Asia/Tokyo", 0.03),
+                ("Asia/Seoul", 0.015),
+                ("Asia/Jakarta", 0.02),
+                ("Australia/Sydney", 0.01),
+            ],
+            300,
+        ),
+        locale: d(
+            &[
+                ("en-US", 0.35),
+                ("en-GB", 0.04),
+                ("en-IN", 0.02),
+                ("en-CA", 0.01),
+                ("en-AU", 0.01),
+                ("de-DE", 0.05),
+                ("fr-FR", 0.04),
+                ("es-ES", 0.03),
+                ("pt-BR", 0.04),
+                ("ru-RU", 0.04),
+                ("it-IT", 0.02),
+                ("pl-PL", 0.015),
+                ("nl-NL", 0.01),
+                ("ja-JP", 0.03),
+                ("ko-KR", 0.02),
+                ("zh-CN", 0.06),
+            ],
+            150,
+        ),
+        // A real install keeps nearly all its stock fonts
+        font_coverage: d(
+            &[
+                ("90-100%", 0.70),
+                ("75-90%", 0.15),
+                ("50-75%", 0.10),
+                ("0-50%", 0.05),
+            ],
+            0,
+        ),
+        uhd_countries: countries(&[
+            "US", "CA", "GB", "AU", "DE", "FR", "NL", "SE", "NO", "DK", "FI", "CH", "AT", "JP",
+            "KR", "SG", "HK", "TW", "NZ", "IE", "BE", "IT", "ES", "PT", "PL",
+        ]),
+        low_dpr_countries: countries(&["NG", "ET", "UG", "TZ", "GH", "KE", "BD", "KH", "LA", "MM"]),
+    }
+}
+
+/// The dataset in use: the last accepted one, or the built-in one.
+pub fn current() -> Arc<Priors> {
+    match ACTIVE.read().unwrap().clone() {
+        Some(active) => active,
+        None => builtin_arc().clone(),
+    }
+}
+
+/// Make `priors` the dataset `current` returns (`None` restores the
+/// built-in one).
+fn install(priors: Option<Priors>) {
+    *ACTIVE.write().unwrap() = priors.map(Arc::new);
+}
+
+// ── Signed documents ──────────────────────────────────────────────────────────
+
+/// The dataset in `document` if it is signed with `public_key` and valid.
+pub fn verify(document: &SignedPriors, public_key: &str) -> Result<Priors> {
+    let key = public_key_bytes(public_key)?;
+    let decode = |field: &str, b64: &str| {
+        B64.decode(b64.trim()).map_err(|_| {
+            ManifoldError::InvalidArg(format!("priors document: {field} is not base64"))
+        })
+    };
+    let payload = decode("payload", &document.payload)?;
+    let signature = decode("signature", &document.signature)?;
+    UnparsedPublicKey::new(&ED25519, &key)
+        .verify(&payload, &signature)
+        .map_err(|_| {
+            ManifoldError::InvalidArg("priors document: signature does not match the key".into())
+        })?;
+    let priors: Priors = serde_json::from_slice(&payload)
+        .map_err(|e| ManifoldError::InvalidArg(format!("priors document: {e}")))?;
+    priors.validate()?;
+    Ok(priors)
+}
+
+/// Download the signed document at `url`.
+pub async fn fetch(url: &str) -> Result<SignedPriors> {
+    let resp = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .context("priors client")?
+        .get(url)
+        .send()
+        .await
+        .context("priors fetch")?;
+    if !resp.status().is_success() {
+        return Err(ManifoldError::Other(format!(
+            "priors fetch: HTTP {}",
+            resp.status()
+        )));
+    }
+    resp.json()
+        .await
+        .map_err(|e| ManifoldError::InvalidArg(format!("priors document: {e}")))
+}
+
+// ── Cache ─────────────────────────────────────────────────────────────────────
+
+pub fn source(db: &Db) -> Result<PriorsSource> {
+    load(db, PRIORS_SOURCE_KEY)
+}
+
+/// Store a new source.  The cached dataset stays only if the new key
+/// verifies it; otherwise the built-in dataset takes over.
+pub fn set_source(db: &Db, source: &PriorsSource) -> Result<PriorsStatus> {
+    source.validate()?;
+    if *source == self::source(db)? {
+        return status(db);
+    }
+    store(db, PRIORS_SOURCE_KEY, source)?;
+    if restore(db).is_err() {
+        store(db, PRIORS_CACHE_KEY, &None::<CachedPriors>)?;
+        install(None);
+    }
+    status(db)
+}
+
+/// The cached dataset, verified with the configured key.
+fn cached(db: &Db) -> Result<Option<(Priors, DateTime<Utc>)>> {
+    let Some(cache) = load::<Option<CachedPriors>>(db, PRIORS_CACHE_KEY)? else {
+        return Ok(None);
+    };
+    let Some(key) = source(db)?.public_key else {
+        return Err(ManifoldError::InvalidArg(
+            "cached priors dataset but no public key to verify it".into(),
+        ));
+    };
+    Ok(Some((verify(&cache.document, &key)?, cache.fetched_at)))
+}
+
+/// Put the cached dataset in use, at startup or after switching
+/// workspaces.  The built-in one stays in use when there is none or it
+/// doesn't verify.
+pub fn restore(db: &Db) -> Result<PriorsStatus> {
+    install(None);
+    if let Some((priors, _)) = cached(db)? {
+        install(Some(priors));
+    }
+    status(db)
+}
+
+/// Verify `document` with the configured key and, if it is newer than the
+/// dataset in use, cache it and put it in use.
+pub fn accept(db: &Db, document: SignedPriors, now: DateTime<Utc>) -> Result<PriorsStatus> {
+    let Some(key) = source(db)?.public_key else {
+        return Err(ManifoldError::InvalidArg(
+            "no priors source configured".into(),
+        ));
+    };
+    let priors = verify(&document, &key)?;
+    let in_use = cached(db)
+        .ok()
+        .flatten()
+        .map_or(BUILTIN_VERSION, |(p, _)| p.version);
+    if priors.version <= in_use {
+        return Err(ManifoldError::InvalidArg(format!(
+            "priors dataset {} is not newer than {in_use}, the one in use",
+            priors.version
+        )));
+    }
+    store(
+        db,
+        PRIORS_CACHE_KEY,
+        &Some(CachedPriors {
+            document,
+            fetched_at: now,
+        }),
+    )?;
+    install(Some(priors));
+    status(db)
+}
+
+pub fn status(db: &Db) -> Result<PriorsStatus> {
+    let fetched_at = load::<Option<CachedPriors>>(db, PRIORS_CACHE_KEY)?.map(|c| c.fetched_at);
+    let priors = current();
+    let downloaded = ACTIVE.read().unwrap().is_some();
+    Ok(PriorsStatus {
+        version: priors.version,
+        published: priors.published,
+        downloaded,
+        fetched_at: fetched_at.filter(|_| downloaded),
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn signed(priors: &Priors, pair: &Ed25519KeyPair) -> SignedPriors {
+        let payload = serde_json::to_vec(priors).unwrap();
+        SignedPriors {
+            payload: B64.encode(&payload),
+            signature: B64.encode(pair.sign(&payload).as_ref()),
+        }
+    }
+
+    #[test]
+    fn only_newer_datasets_signed_with_the_key_are_accepted() {
+        let builtin = Priors::builtin();
+        builtin.validate().unwrap();
+        assert!(builtin.allows_uhd("DE") && !builtin.allows_uhd("NG"));
+        assert!((builtin.chrome_lag.share("0") - 0.55).abs() < 1e-12);
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let key = B64.encode(pair.public_key().as_ref());
+
+        let db = Db::open_in_memory().unwrap();
+        let now = Utc::now();
+        let mut next = builtin.clone();
+        next.version = BUILTIN_VERSION + 1;
+        // Nothing is fetched until a source is configured
+        assert!(accept(&db, signed(&next, &pair), now).is_err());
+        let src = PriorsSource {
+            url: Some("https://priors.example/latest.json".into()),
+            public_key: Some(key),
+        };
+        assert!(PriorsSource {
+            url: Some("http://priors.example/".into()),
+            ..src.clone()
+        }
+        .validate()
+        .is_err());
+        store(&db, PRIORS_SOURCE_KEY, &src).unwrap();
+
+        // Tampered payload, foreign key
+        let mut tampered = signed(&next, &pair);
+        tampered.payload = B64.encode(serde_json::to_vec(builtin).unwrap());
+        assert!(accept(&db, tampered, now).is_err());
+        let other =
+            Ed25519KeyPair::from_pkcs8(Ed25519KeyPair::generate_pkcs8(&rng).unwrap().as_ref())
+                .unwrap();
+        assert!(accept(&db, signed(&next, &other), now).is_err());
+        // Not newer than the built-in dataset
+        assert!(accept(&db, signed(builtin, &pair), now).is_err());
+
+        // The shares are the built-in ones, so swapping datasets doesn't
+        // disturb tests running alongside
+        let status = accept(&db, signed(&next, &pair), now).unwrap();
+        assert_eq!(status.version, BUILTIN_VERSION + 1);
+        assert!(status.downloaded);
+        assert!(accept(&db, signed(&next, &pair), now).is_err(), "replay");
+        assert_eq!(restore(&db).unwrap().version, BUILTIN_VERSION + 1);
+
+        // A new key that doesn't verify the cache drops it
+        let other_key = B64.encode(other.public_key().as_ref());
+        let status = set_source(
+            &db,
+            &PriorsSource {
+                public_key: Some(other_key),
+                ..src
+            },
+        )
+        .unwrap();
+        assert!(!status.downloaded);
+        assert_eq!(status.version, BUILTIN_VERSION);
+    }
+}
@@ -2,24 +2,64 @@
 
 use chrono::{DateTime, Utc};
 use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 
-use crate::db::Db;
+use crate::cache_prewarm::{
+    CachePrewarmSettings, HarvestReport, SharedCache, CACHE_PREWARM_KEY, SHARED_CACHE_DIR,
+};
+use crate::db::{Db, Page};
 use crate::error::{ManifoldError, Result};
-use crate::fingerprint::Fingerprint;
+use crate::fingerprint::{Fingerprint, FingerprintOrchestrator, ReseedOptions};
+use crate::geo_validator::GeoValidator;
 use crate::human::{BehaviorProfile, HumanBehavior};
+use crate::launch_env::{validate_env, LaunchEnvSettings, LAUNCH_ENV_KEY};
+use crate::launch_mode::LaunchPolicy;
+use crate::persona::Persona;
+use crate::power::{PowerSettings, POWER_SETTINGS_KEY};
+use crate::quota::{QuotaSettings, QUOTA_SETTINGS_KEY};
+
+/// Entries of a Chromium user-data dir that are tied to the running instance
+/// or the physical machine and must not be carried over when forking a
+/// profile's browser data.
+const DEVICE_BOUND_ENTRIES: &[&str] = &[
+    "SingletonLock",
+    "SingletonCookie",
+    "SingletonSocket",
+    "DevToolsActivePort",
+    "lockfile",
+    "Crashpad",
+    "GPUCache",
+    "ShaderCache",
+    "GrShaderCache",
+    "DawnCache",
+];
+
+/// Top-level `Local State` keys identifying the installation (metrics client
+/// id, variations seed).  `os_crypt` is kept so copied cookies stay readable.
+const LOCAL_STATE_SCRUB_KEYS: &[&str] = &[
+    "user_experience_metrics",
+    "variations_compressed_seed",
+    "variations_seed_signature",
+    "variations_permanent_consistency_country",
+    "uninstall_metrics",
+    "stability",
+];
 
 // ── Types ─────────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ProfileStatus {
     #[default]
     Idle,
     Running,
     Error,
+    /// Paused because the host went to sleep; resumed or stopped on wake.
+    Suspended,
 }
 
 impl std::fmt::Display for ProfileStatus {
@@ -28,6 +68,7 @@ impl std::fmt::Display for ProfileStatus {
             Self::Idle => "idle",
             Self::Running => "running",
             Self::Error => "error",
+            Self::Suspended => "suspended",
         };
         write!(f, "{s}")
     }
@@ -40,6 +81,7 @@ impl std::str::FromStr for ProfileStatus {
             "idle" => Ok(Self::Idle),
             "running" => Ok(Self::Running),
             "error" => Ok(Self::Error),
+            "suspended" => Ok(Self::Suspended),
             other => Err(ManifoldError::InvalidArg(format!(
                 "unknown ProfileStatus: {other:?}"
             ))),
@@ -47,6 +89,106 @@ impl std::str::FromStr for ProfileStatus {
     }
 }
 
+impl ProfileStatus {
+    /// Whether a profile may move from `self` to `next`.  Stopping is
+    /// idempotent (Idle → Idle); every other self-transition — a second
+    /// launch of a running profile in particular — is rejected.
+    pub fn can_transition_to(self, next: ProfileStatus) -> bool {
+        use ProfileStatus::*;
+        matches!(
+            (self, next),
+            (_, Idle)
+                | (Idle | Error | Suspended, Running)
+                | (Running, Suspended)
+                | (Idle | Running | Suspended, Error)
+        )
+    }
+}
+
+/// Why a profile's status changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionReason {
+    /// A bridge was started for the profile.
+    Launch,
+    /// The bridge failed to start.
+    LaunchFailed,
+    /// Stopped by the user.
+    Stop,
+    /// The bridge exited on its own (browser closed).
+    Exited,
+    /// The bridge exited with an error.
+    Crashed,
+    /// Another launch took over the bridge.
+    Replaced,
+    /// The execution node went offline; the launch was requeued.
+    Failover,
+    /// Emergency shutdown.
+    Panic,
+    /// Host sleep / wake.
+    Sleep,
+    Wake,
+    /// Left over from a previous run of the app.
+    Startup,
+    /// Set explicitly through `set_profile_status`.
+    Manual,
+}
+
+impl std::fmt::Display for TransitionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Launch => "launch",
+            Self::LaunchFailed => "launch_failed",
+            Self::Stop => "stop",
+            Self::Exited => "exited",
+            Self::Crashed => "crashed",
+            Self::Replaced => "replaced",
+            Self::Failover => "failover",
+            Self::Panic => "panic",
+            Self::Sleep => "sleep",
+            Self::Wake => "wake",
+            Self::Startup => "startup",
+            Self::Manual => "manual",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for TransitionReason {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "launch" => Ok(Self::Launch),
+            "launch_failed" => Ok(Self::LaunchFailed),
+            "stop" => Ok(Self::Stop),
+            "exited" => Ok(Self::Exited),
+            "crashed" => Ok(Self::Crashed),
+            "replaced" => Ok(Self::Replaced),
+            "failover" => Ok(Self::Failover),
+            "panic" => Ok(Self::Panic),
+            "sleep" => Ok(Self::Sleep),
+            "wake" => Ok(Self::Wake),
+            "startup" => Ok(Self::Startup),
+            "manual" => Ok(Self::Manual),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown TransitionReason: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// One attempted status change, kept in `status_transitions`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusTransition {
+    pub profile_id: String,
+    pub from: ProfileStatus,
+    pub to: ProfileStatus,
+    pub reason: TransitionReason,
+    /// False when the change was rejected by the state machine.
+    pub allowed: bool,
+    pub at: DateTime<Utc>,
+}
+
 /// Full profile record returned to the frontend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -64,6 +206,29 @@ pub struct Profile {
     pub data_dir: String,
     /// Enable TLS bridge for JA4 fingerprinting control.
     pub tls_bridge: Option<bool>,
+    /// Let the aging task bump the Chrome version as real browsers update.
+    pub auto_age: bool,
+    /// Who the profile is supposed to be; drives warm-up and locale choices.
+    #[serde(default)]
+    pub persona: Option<Persona>,
+    /// Proxy ids routed in order (first hop first).  When non-empty it takes
+    /// precedence over `proxy_id` at launch.
+    #[serde(default)]
+    pub proxy_chain: Vec<String>,
+    /// Extra environment variables for the profile's bridge, applied over
+    /// the scrubbed host environment.
+    #[serde(default)]
+    pub launch_env: BTreeMap<String, String>,
+    /// Data dir size limit in MiB; `None` uses the app-wide default.
+    #[serde(default)]
+    pub disk_quota_mb: Option<u64>,
+    /// How the browser is shown, and what to fall back to.
+    #[serde(default)]
+    pub launch_policy: LaunchPolicy,
+    /// Chrome version held for legacy targets (`"109"` or a full version).
+    /// Aging leaves the profile alone and version-age warnings are dropped.
+    #[serde(default)]
+    pub pinned_browser_version: Option<String>,
 }
 
 /// Payload for creating a new profile.
@@ -76,6 +241,8 @@ pub struct CreateProfileRequest {
     pub notes: Option<String>,
     pub tags: Option<Vec<String>>,
     pub behavior_profile: Option<String>,
+    /// A persona with a known home city also pins locale and timezone.
+    pub persona: Option<Persona>,
 }
 
 /// Payload for updating an existing profile.
@@ -89,6 +256,91 @@ pub struct UpdateProfileRequest {
     pub tags: Option<Vec<String>>,
     pub behavior_profile: Option<String>,
     pub tls_bridge: Option<bool>,
+    /// `Some(None)` clears the persona.  A changed persona re-derives
+    /// locale and timezone.
+    pub persona: Option<Option<Persona>>,
+}
+
+/// Which profiles a batch operation applies to.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSelection {
+    /// Explicit list of profile ids.
+    Ids(Vec<String>),
+    /// Every profile matching all of the set filter fields.
+    Filter(ProfileFilter),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ProfileFilter {
+    pub tag: Option<String>,
+    pub status: Option<ProfileStatus>,
+    pub proxy_id: Option<String>,
+    /// Case-insensitive substring of the profile name.
+    pub name_contains: Option<String>,
+}
+
+impl ProfileFilter {
+    pub fn matches(&self, p: &Profile) -> bool {
+        self.tag.as_ref().is_none_or(|t| p.tags.contains(t))
+            && self.status.as_ref().is_none_or(|s| &p.status == s)
+            && self
+                .proxy_id
+                .as_ref()
+                .is_none_or(|id| p.proxy_id.as_ref() == Some(id))
+            && self
+                .name_contains
+                .as_ref()
+                .is_none_or(|n| p.name.to_lowercase().contains(&n.to_lowercase()))
+    }
+}
+
+/// Changes applied to every profile of a batch selection.
+#[derive(Debug, Default, Deserialize)]
+pub struct BatchProfilePatch {
+    /// Proxy ids assigned round-robin across the selection.  An empty list
+    /// clears the proxy of every selected profile.
+    pub proxy_pool: Option<Vec<String>>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    pub behavior_profile: Option<String>,
+    pub tls_bridge: Option<bool>,
+}
+
+/// Outcome for a single profile of a batch operation.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    pub id: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Result of a batch operation.  The batch is all-or-nothing: when any item
+/// fails, `applied` is false and no profile was changed.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchUpdateResult {
+    pub applied: bool,
+    pub results: Vec<BatchItemResult>,
+}
+
+/// A directory under the profiles root that no profile owns.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedDir {
+    pub name: String,
+    pub path: String,
+    pub bytes: u64,
+    /// Whether it was deleted (always false on a dry run).
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcReport {
+    pub dry_run: bool,
+    pub orphans: Vec<OrphanedDir>,
+    /// Bytes actually freed.
+    pub reclaimed_bytes: u64,
 }
 
 // ── Repository ────────────────────────────────────────────────────────────────
@@ -104,7 +356,7 @@ impl ProfileRepo {
         Self { db, profiles_root }
     }
 
-    /// Create a repo with an explicit root directory (used in tests).
+    /// Create a repo with an explicit root directory.
     #[allow(dead_code)]
     pub fn new_with_root(db: Db, profiles_root: PathBuf) -> Self {
         Self { db, profiles_root }
@@ -113,7 +365,6 @@ impl ProfileRepo {
     // ── Create ────────────────────────────────────────────────────────────────
 
     pub fn create(&self, req: CreateProfileRequest) -> Result<Profile> {
-        use crate::fingerprint::FingerprintOrchestrator;
         use rand::Rng;
 
         let id = Uuid::new_v4().to_string();
@@ -121,7 +372,15 @@ impl ProfileRepo {
         // Derive seed
         let seed = req.seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
 
-        let fingerprint = FingerprintOrchestrator::generate(seed);
+        let mut fingerprint = FingerprintOrchestrator::generate(seed);
+        // Born consistent with the proxy's exit country rather than left for
+        // a later geo auto-correct; a persona's home city still wins
+        if let Some((country, region)) = self.proxy_geo(req.proxy_id.as_deref())? {
+            GeoValidator::auto_correct(&mut fingerprint, &country, region.as_deref(), seed);
+        }
+        if let Some(persona) = &req.persona {
+            persona.apply_locale(&mut fingerprint);
+        }
 
         // Parse behavior profile
         let bp: BehaviorProfile = req
@@ -139,17 +398,27 @@ impl ProfileRepo {
         let fp_json = serde_json::to_string(&fingerprint)?;
         let human_json = serde_json::to_string(&human)?;
         let tags_json = serde_json::to_string(&tags)?;
+        let persona_json = req
+            .persona
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
 
         // Create the browser data directory
         let data_dir = self.profile_data_dir(&id);
         std::fs::create_dir_all(&data_dir)?;
+        if self.cache_prewarm_settings()?.enabled {
+            if let Err(e) = crate::cache_prewarm::prewarm(&self.shared_cache_dir(), &data_dir) {
+                eprintln!("[cache] prewarming {id} failed: {e}");
+            }
+        }
 
         self.db.with_conn(|conn| {
             conn.execute(
                 r#"INSERT INTO profiles
                    (id, name, fingerprint_json, human_json, proxy_id,
-                    notes, tags, status, created_at, last_used, tls_bridge)
-                   VALUES (?1,?2,?3,?4,?5,?6,?7,'idle',?8,NULL,0)"#,
+                    notes, tags, status, created_at, last_used, tls_bridge, persona_json)
+                   VALUES (?1,?2,?3,?4,?5,?6,?7,'idle',?8,NULL,0,?9)"#,
                 params![
                     id,
                     req.name,
@@ -159,6 +428,7 @@ impl ProfileRepo {
                     notes,
                     tags_json,
                     now.to_rfc3339(),
+                    persona_json,
                 ],
             )?;
             Ok(())
@@ -177,6 +447,68 @@ impl ProfileRepo {
             last_used: None,
             data_dir: data_dir.to_string_lossy().into_owned(),
             tls_bridge: Some(false),
+            auto_age: true,
+            persona: req.persona,
+            proxy_chain: Vec::new(),
+            launch_env: BTreeMap::new(),
+            disk_quota_mb: None,
+            launch_policy: LaunchPolicy::default(),
+            pinned_browser_version: None,
+        })
+    }
+
+    /// The country of proxy `proxy_id`, if it has one.
+    pub(crate) fn proxy_country(&self, proxy_id: Option<&str>) -> Result<Option<String>> {
+        Ok(self.proxy_geo(proxy_id)?.map(|(country, _)| country))
+    }
+
+    /// Reset locale, accept-language and timezone of `fp` to what `create`
+    /// would pick: the seed's, aligned with the proxy's exit country, then
+    /// pinned to the persona's home city.
+    fn derive_locale(
+        &self,
+        fp: &mut Fingerprint,
+        proxy_id: Option<&str>,
+        persona: Option<&Persona>,
+    ) -> Result<()> {
+        let mut derived = FingerprintOrchestrator::generate_with(fp.seed, fp.generator)?;
+        if let Some((country, region)) = self.proxy_geo(proxy_id)? {
+            GeoValidator::auto_correct(&mut derived, &country, region.as_deref(), fp.seed);
+        }
+        if let Some(persona) = persona {
+            persona.apply_locale(&mut derived);
+        }
+        fp.locale = derived.locale;
+        fp.intl = derived.intl;
+        fp.accept_language = derived.accept_language;
+        fp.timezone = derived.timezone;
+        Ok(())
+    }
+
+    /// The country of proxy `proxy_id` and its region within it, if it has
+    /// a country.
+    pub(crate) fn proxy_geo(
+        &self,
+        proxy_id: Option<&str>,
+    ) -> Result<Option<(String, Option<String>)>> {
+        let Some(proxy_id) = proxy_id else {
+            return Ok(None);
+        };
+        let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+        self.db.with_conn(|conn| {
+            let geo: Option<(Option<String>, Option<String>)> = conn
+                .query_row(
+                    "SELECT country, region FROM proxies WHERE id = ?1",
+                    params![proxy_id],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .optional()?;
+            match geo {
+                Some((country, region)) => {
+                    Ok(non_empty(country).map(|country| (country, non_empty(region))))
+                }
+                None => Err(ManifoldError::ProxyNotFound(proxy_id.into())),
+            }
         })
     }
 
@@ -188,7 +520,9 @@ impl ProfileRepo {
                 let row = conn
                     .query_row(
                         r#"SELECT id, name, fingerprint_json, human_json, proxy_id,
-                          notes, tags, status, created_at, last_used, tls_bridge
+                          notes, tags, status, created_at, last_used, tls_bridge,
+                          auto_age, persona_json, proxy_chain, launch_env, disk_quota_mb,
+                          launch_policy, pinned_browser_version
                    FROM profiles WHERE id = ?1"#,
                         params![id],
                         row_to_profile,
@@ -204,17 +538,28 @@ impl ProfileRepo {
     }
 
     pub fn list(&self) -> Result<Vec<Profile>> {
+        self.list_page(&Page::default())
+    }
+
+    /// Newest first, one page at a time.
+    pub fn list_page(&self, page: &Page) -> Result<Vec<Profile>> {
         self.db
             .with_conn(|conn| {
+                page.check_cursor(conn, "profiles")?;
                 let mut stmt = conn.prepare(
                     r#"SELECT id, name, fingerprint_json, human_json, proxy_id,
-                          notes, tags, status, created_at, last_used, tls_bridge
+                          notes, tags, status, created_at, last_used, tls_bridge,
+                          auto_age, persona_json, proxy_chain, launch_env, disk_quota_mb,
+                          launch_policy, pinned_browser_version
                    FROM profiles
-                   ORDER BY created_at DESC"#,
+                   WHERE ?1 IS NULL
+                      OR (created_at, id) < (SELECT created_at, id FROM profiles WHERE id = ?1)
+                   ORDER BY created_at DESC, id DESC
+                   LIMIT ?2"#,
                 )?;
 
                 let profiles = stmt
-                    .query_map([], row_to_profile)?
+                    .query_map(params![page.after, page.sql_limit()], row_to_profile)?
                     .collect::<rusqlite::Result<Vec<_>>>()?;
 
                 Ok(profiles)
@@ -238,6 +583,10 @@ impl ProfileRepo {
         }
         if let Some(fp) = req.fingerprint {
             profile.fingerprint = fp;
+            // An edited fingerprint stays on the pinned version
+            if let Some(version) = &profile.pinned_browser_version {
+                FingerprintOrchestrator::pin_chrome_version(&mut profile.fingerprint, version)?;
+            }
         }
         if let Some(human) = req.human {
             profile.human = human;
@@ -255,17 +604,32 @@ impl ProfileRepo {
         if let Some(tags) = req.tags {
             profile.tags = tags;
         }
+        if let Some(persona) = req.persona {
+            if persona != profile.persona {
+                profile.persona = persona;
+                self.derive_locale(
+                    &mut profile.fingerprint,
+                    profile.proxy_id.as_deref(),
+                    profile.persona.as_ref(),
+                )?;
+            }
+        }
         // tls_bridge is handled via separate update endpoint
 
         let fp_json = serde_json::to_string(&profile.fingerprint)?;
         let human_json = serde_json::to_string(&profile.human)?;
         let tags_json = serde_json::to_string(&profile.tags)?;
+        let persona_json = profile
+            .persona
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
 
         self.db.with_conn(|conn| {
             let updated = conn.execute(
                 r#"UPDATE profiles
                    SET name = ?1, fingerprint_json = ?2, human_json = ?3,
-                       proxy_id = ?4, notes = ?5, tags = ?6
+                       proxy_id = ?4, notes = ?5, tags = ?6, persona_json = ?8
                    WHERE id = ?7"#,
                 params![
                     profile.name,
@@ -275,6 +639,7 @@ impl ProfileRepo {
                     profile.notes,
                     tags_json,
                     id,
+                    persona_json,
                 ],
             )?;
 
@@ -287,11 +652,137 @@ impl ProfileRepo {
         Ok(profile)
     }
 
+    /// Manual status change; see `transition`.
     pub fn set_status(&self, id: &str, status: ProfileStatus) -> Result<()> {
+        self.transition(id, status, TransitionReason::Manual)
+            .map(|_| ())
+    }
+
+    // ── Status state machine ──────────────────────────────────────────────────
+
+    /// Move a profile to `to`.  The check and the update happen under the
+    /// DB lock, so two callers racing on one profile can't both succeed.
+    /// Every attempt is recorded; a transition the state machine doesn't
+    /// allow is logged and returned as `InvalidArg`, leaving the status as
+    /// it was.
+    pub fn transition(
+        &self,
+        id: &str,
+        to: ProfileStatus,
+        reason: TransitionReason,
+    ) -> Result<StatusTransition> {
+        let t = self.db.with_conn(|conn| {
+            let from: String = conn
+                .query_row(
+                    "SELECT status FROM profiles WHERE id = ?1",
+                    params![id],
+                    |r| r.get(0),
+                )
+                .optional()?
+                .ok_or_else(|| ManifoldError::ProfileNotFound(id.into()))?;
+            let from: ProfileStatus = from.parse().unwrap_or_default();
+            let t = StatusTransition {
+                profile_id: id.to_string(),
+                from,
+                to,
+                reason,
+                allowed: from.can_transition_to(to),
+                at: Utc::now(),
+            };
+            // Repeated stops are no-ops, not history
+            if from == to && t.allowed {
+                return Ok(t);
+            }
+            if t.allowed {
+                conn.execute(
+                    "UPDATE profiles SET status = ?1 WHERE id = ?2",
+                    params![to.to_string(), id],
+                )?;
+            }
+            conn.execute(
+                "INSERT INTO status_transitions
+                   (profile_id, from_status, to_status, reason, allowed, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    id,
+                    from.to_string(),
+                    to.to_string(),
+                    reason.to_string(),
+                    t.allowed,
+                    t.at.to_rfc3339(),
+                ],
+            )?;
+            Ok(t)
+        })?;
+
+        if !t.allowed {
+            eprintln!(
+                "[status] rejected {id}: {} → {} ({})",
+                t.from, t.to, t.reason
+            );
+            return Err(ManifoldError::InvalidArg(format!(
+                "profile {id} can't go from {} to {} ({})",
+                t.from, t.to, t.reason
+            )));
+        }
+        Ok(t)
+    }
+
+    /// Move every profile in one of `from` to `to`; returns the ids moved.
+    pub fn transition_all(
+        &self,
+        from: &[ProfileStatus],
+        to: ProfileStatus,
+        reason: TransitionReason,
+    ) -> Result<Vec<String>> {
+        let ids: Vec<String> = self
+            .list()?
+            .into_iter()
+            .filter(|p| from.contains(&p.status))
+            .map(|p| p.id)
+            .collect();
+        Ok(ids
+            .into_iter()
+            .filter(|id| self.transition(id, to, reason).is_ok())
+            .collect())
+    }
+
+    /// Most recent status changes of a profile, newest first, rejected
+    /// attempts included.
+    pub fn status_history(&self, id: &str, limit: u32) -> Result<Vec<StatusTransition>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT profile_id, from_status, to_status, reason, allowed, created_at
+                 FROM status_transitions WHERE profile_id = ?1
+                 ORDER BY id DESC LIMIT ?2",
+            )?;
+            let rows = stmt
+                .query_map(params![id, limit], |r| {
+                    let at: String = r.get(5)?;
+                    Ok(StatusTransition {
+                        profile_id: r.get(0)?,
+                        from: r.get::<_, String>(1)?.parse().unwrap_or_default(),
+                        to: r.get::<_, String>(2)?.parse().unwrap_or_default(),
+                        reason: r
+                            .get::<_, String>(3)?
+                            .parse()
+                            .unwrap_or(TransitionReason::Manual),
+                        allowed: r.get(4)?,
+                        at: DateTime::parse_from_rfc3339(&at)
+                            .map(|dt| dt.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+    }
+
+    pub fn set_auto_age(&self, id: &str, enabled: bool) -> Result<()> {
         self.db.with_conn(|conn| {
             let updated = conn.execute(
-                "UPDATE profiles SET status = ?1 WHERE id = ?2",
-                params![status.to_string(), id],
+                "UPDATE profiles SET auto_age = ?1 WHERE id = ?2",
+                params![enabled, id],
             )?;
             if updated == 0 {
                 return Err(ManifoldError::ProfileNotFound(id.into()));
@@ -300,6 +791,135 @@ impl ProfileRepo {
         })
     }
 
+    /// Replace the profile's proxy chain; an empty list removes it.
+    pub fn set_proxy_chain(&self, id: &str, proxy_ids: &[String]) -> Result<()> {
+        let chain_json = serde_json::to_string(proxy_ids)?;
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE profiles SET proxy_chain = ?1 WHERE id = ?2",
+                params![chain_json, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProfileNotFound(id.into()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Replace the profile's launch environment variables.
+    pub fn set_launch_env(&self, id: &str, env: &BTreeMap<String, String>) -> Result<()> {
+        validate_env(env)?;
+        let env_json = serde_json::to_string(env)?;
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE profiles SET launch_env = ?1 WHERE id = ?2",
+                params![env_json, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProfileNotFound(id.into()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Set the profile's data dir size limit (`None` = app-wide default).
+    pub fn set_disk_quota(&self, id: &str, quota_mb: Option<u64>) -> Result<()> {
+        let quota_mb = quota_mb.map(|mb| mb.min(i64::MAX as u64) as i64);
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE profiles SET disk_quota_mb = ?1 WHERE id = ?2",
+                params![quota_mb, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProfileNotFound(id.into()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Set how the profile's browser is shown.
+    pub fn set_launch_policy(&self, id: &str, policy: &LaunchPolicy) -> Result<()> {
+        policy.validate()?;
+        let policy_json = serde_json::to_string(policy)?;
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE profiles SET launch_policy = ?1 WHERE id = ?2",
+                params![policy_json, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProfileNotFound(id.into()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Pin the profile's Chrome version (moving its fingerprint there), or
+    /// release the pin with `None`; the fingerprint then stays where it is
+    /// until aging moves it.
+    pub fn set_pinned_browser_version(&self, id: &str, version: Option<&str>) -> Result<Profile> {
+        let mut profile = self.get(id)?;
+        let version = version.map(str::trim).filter(|v| !v.is_empty());
+        if let Some(version) = version {
+            FingerprintOrchestrator::pin_chrome_version(&mut profile.fingerprint, version)?;
+        }
+        profile.pinned_browser_version = version.map(str::to_string);
+        let fp_json = serde_json::to_string(&profile.fingerprint)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE profiles SET fingerprint_json = ?1, pinned_browser_version = ?2 \
+                 WHERE id = ?3",
+                params![fp_json, profile.pinned_browser_version, id],
+            )?;
+            Ok(())
+        })?;
+        Ok(profile)
+    }
+
+    /// App-wide launch environment settings (defaults until changed).
+    pub fn launch_env_settings(&self) -> Result<LaunchEnvSettings> {
+        self.setting(LAUNCH_ENV_KEY)
+    }
+
+    pub fn set_launch_env_settings(&self, settings: &LaunchEnvSettings) -> Result<()> {
+        self.set_setting(LAUNCH_ENV_KEY, settings)
+    }
+
+    /// What happens to profiles paused for host sleep.
+    pub fn power_settings(&self) -> Result<PowerSettings> {
+        self.setting(POWER_SETTINGS_KEY)
+    }
+
+    pub fn set_power_settings(&self, settings: &PowerSettings) -> Result<()> {
+        self.set_setting(POWER_SETTINGS_KEY, settings)
+    }
+
+    /// App-wide disk quota settings.
+    pub fn quota_settings(&self) -> Result<QuotaSettings> {
+        self.setting(QUOTA_SETTINGS_KEY)
+    }
+
+    pub fn set_quota_settings(&self, settings: &QuotaSettings) -> Result<()> {
+        self.set_setting(QUOTA_SETTINGS_KEY, settings)
+    }
+
+    /// App-wide cache prewarming settings.
+    pub fn cache_prewarm_settings(&self) -> Result<CachePrewarmSettings> {
+        self.setting(CACHE_PREWARM_KEY)
+    }
+
+    pub fn set_cache_prewarm_settings(&self, settings: &CachePrewarmSettings) -> Result<()> {
+        settings.validate()?;
+        self.set_setting(CACHE_PREWARM_KEY, settings)
+    }
+
+    fn setting<T: DeserializeOwned + Default>(&self, key: &str) -> Result<T> {
+        crate::settings::load(&self.db, key)
+    }
+
+    fn set_setting<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        crate::settings::store(&self.db, key, value)
+    }
+
     pub fn touch_last_used(&self, id: &str) -> Result<()> {
         let now = Utc::now().to_rfc3339();
         self.db.with_conn(|conn| {
@@ -329,16 +949,105 @@ impl ProfileRepo {
         })
     }
 
+    // ── Batch ─────────────────────────────────────────────────────────────────
+
+    /// Apply `patch` to every selected profile inside one transaction.
+    pub fn batch_update(
+        &self,
+        selection: ProfileSelection,
+        patch: BatchProfilePatch,
+    ) -> Result<BatchUpdateResult> {
+        let human = match &patch.behavior_profile {
+            Some(bp) => Some(serde_json::to_string(&HumanBehavior::from_profile(
+                bp.parse::<BehaviorProfile>()?,
+            ))?),
+            None => None,
+        };
+
+        // Resolve the selection up front; unknown ids become failed items.
+        let mut targets: Vec<std::result::Result<Profile, ManifoldError>> = Vec::new();
+        let mut target_ids: Vec<String> = Vec::new();
+        match selection {
+            ProfileSelection::Ids(ids) => {
+                for id in ids {
+                    targets.push(self.get(&id));
+                    target_ids.push(id);
+                }
+            }
+            ProfileSelection::Filter(filter) => {
+                for p in self.list()?.into_iter().filter(|p| filter.matches(p)) {
+                    target_ids.push(p.id.clone());
+                    targets.push(Ok(p));
+                }
+            }
+        }
+
+        self.db.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut results = Vec::with_capacity(targets.len());
+
+            for (i, (target, id)) in targets.into_iter().zip(target_ids).enumerate() {
+                let outcome = target.and_then(|mut p| {
+                    if let Some(pool) = &patch.proxy_pool {
+                        p.proxy_id = if pool.is_empty() {
+                            None
+                        } else {
+                            Some(pool[i % pool.len()].clone())
+                        };
+                    }
+                    p.tags.retain(|t| !patch.remove_tags.contains(t));
+                    for tag in &patch.add_tags {
+                        if !p.tags.contains(tag) {
+                            p.tags.push(tag.clone());
+                        }
+                    }
+                    let tags_json = serde_json::to_string(&p.tags)?;
+
+                    tx.execute(
+                        r#"UPDATE profiles
+                           SET proxy_id = ?1, tags = ?2,
+                               human_json = COALESCE(?3, human_json),
+                               tls_bridge = COALESCE(?4, tls_bridge)
+                           WHERE id = ?5"#,
+                        params![p.proxy_id, tags_json, human, patch.tls_bridge, id],
+                    )?;
+                    Ok(())
+                });
+
+                results.push(BatchItemResult {
+                    id,
+                    ok: outcome.is_ok(),
+                    error: outcome.err().map(|e| e.to_string()),
+                });
+            }
+
+            let applied = results.iter().all(|r| r.ok);
+            if applied {
+                tx.commit()?;
+            }
+            // Dropping an uncommitted transaction rolls it back.
+            Ok(BatchUpdateResult { applied, results })
+        })
+    }
+
     // ── Fingerprint helpers ───────────────────────────────────────────────────
 
-    /// Regenerate fingerprint from the same seed (deterministic refresh).
-    pub fn reseed_fingerprint(&self, id: &str, new_seed: Option<u64>) -> Result<Profile> {
-        use crate::fingerprint::FingerprintOrchestrator;
+    /// Regenerate fingerprint, keeping the identity parts selected by `opts`.
+    pub fn reseed_fingerprint(
+        &self,
+        id: &str,
+        new_seed: Option<u64>,
+        opts: &ReseedOptions,
+    ) -> Result<Profile> {
         use rand::Rng;
 
         let mut profile = self.get(id)?;
         let seed = new_seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>());
-        profile.fingerprint = FingerprintOrchestrator::generate(seed);
+        profile.fingerprint =
+            FingerprintOrchestrator::reseed_with(&profile.fingerprint, seed, opts)?;
+        if let Some(version) = &profile.pinned_browser_version {
+            FingerprintOrchestrator::pin_chrome_version(&mut profile.fingerprint, version)?;
+        }
 
         let fp_json = serde_json::to_string(&profile.fingerprint)?;
         self.db.with_conn(|conn| {
@@ -352,11 +1061,172 @@ impl ProfileRepo {
         Ok(profile)
     }
 
+    // ── Browser data ──────────────────────────────────────────────────────────
+
+    /// Copy the browser user-data directory of `from_id` into `to_id`'s,
+    /// dropping device-bound files so the fork looks like a separate install.
+    /// Exported session bundles stay with the source profile.  Refused while
+    /// either profile is running: a live browser's databases are mid-write.
+    pub fn copy_browser_data(&self, from_id: &str, to_id: &str) -> Result<()> {
+        for id in [from_id, to_id] {
+            if self.get(id)?.status == ProfileStatus::Running {
+                return Err(ManifoldError::InvalidArg(
+                    "stop the profile before copying its browser data".into(),
+                ));
+            }
+        }
+        let src = self.profile_data_dir(from_id);
+        let dst = self.profile_data_dir(to_id);
+        if !src.exists() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&dst)?;
+
+        for entry in std::fs::read_dir(&src)? {
+            let entry = entry?;
+            if entry.file_name() == "sessions" {
+                continue;
+            }
+            copy_scrubbed(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+
+        let local_state = dst.join("Local State");
+        if local_state.exists() {
+            scrub_local_state(&local_state)?;
+        }
+        Ok(())
+    }
+
+    /// Add the CDN entries of a stopped profile's cache to the shared layer.
+    pub fn harvest_shared_cache(&self, id: &str) -> Result<HarvestReport> {
+        let profile = self.get(id)?;
+        if profile.status == ProfileStatus::Running {
+            return Err(ManifoldError::InvalidArg(
+                "stop the profile before harvesting its cache".into(),
+            ));
+        }
+        let settings = self.cache_prewarm_settings()?;
+        crate::cache_prewarm::harvest(
+            &self.profile_data_dir(id),
+            &self.shared_cache_dir(),
+            &settings.hosts,
+            &settings.top_frame_sites,
+        )
+    }
+
+    pub fn shared_cache(&self) -> Result<SharedCache> {
+        crate::cache_prewarm::summary(&self.shared_cache_dir())
+    }
+
+    /// Delete the shared cache layer.  Returns the bytes freed.
+    pub fn clear_shared_cache(&self) -> Result<u64> {
+        crate::cache_prewarm::clear(&self.shared_cache_dir())
+    }
+
+    /// Find directories under the profiles root that don't belong to any
+    /// profile — left by a deletion that failed half-way or by older builds —
+    /// and delete them unless `dry_run`.
+    pub fn gc_orphaned_data(&self, dry_run: bool) -> Result<GcReport> {
+        let ids: std::collections::HashSet<String> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM profiles")?;
+            let ids = stmt
+                .query_map([], |r| r.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(ids)
+        })?;
+
+        let mut report = GcReport {
+            dry_run,
+            orphans: Vec::new(),
+            reclaimed_bytes: 0,
+        };
+        let entries = match std::fs::read_dir(&self.profiles_root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !entry.file_type()?.is_dir() || ids.contains(&name) || name == SHARED_CACHE_DIR {
+                continue;
+            }
+            let path = entry.path();
+            let bytes = dir_size(&path);
+            let removed = !dry_run && std::fs::remove_dir_all(&path).is_ok();
+            if removed {
+                report.reclaimed_bytes += bytes;
+            }
+            report.orphans.push(OrphanedDir {
+                name,
+                path: path.to_string_lossy().into_owned(),
+                bytes,
+                removed,
+            });
+        }
+        report.orphans.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(report)
+    }
+
     // ── Private ───────────────────────────────────────────────────────────────
 
     fn profile_data_dir(&self, id: &str) -> PathBuf {
         self.profiles_root.join(id)
     }
+
+    fn shared_cache_dir(&self) -> PathBuf {
+        crate::cache_prewarm::shared_cache_dir(&self.profiles_root)
+    }
+}
+
+/// Total size of the files under `path`; unreadable entries count as empty.
+pub fn dir_size(path: &std::path::Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| dir_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Recursively copy `src` to `dst`, skipping `DEVICE_BOUND_ENTRIES` and
+/// symlinks (Chromium's singleton files are symlinks on Unix).
+fn copy_scrubbed(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
+    let name = src.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if DEVICE_BOUND_ENTRIES.contains(&name) {
+        return Ok(());
+    }
+
+    let meta = std::fs::symlink_metadata(src)?;
+    if meta.is_dir() {
+        std::fs::create_dir_all(dst)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_scrubbed(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+    } else if meta.is_file() {
+        std::fs::copy(src, dst)?;
+    }
+    Ok(())
+}
+
+fn scrub_local_state(path: &std::path::Path) -> Result<()> {
+    let raw = std::fs::read_to_string(path)?;
+    let mut state: serde_json::Value = match serde_json::from_str(&raw) {
+        Ok(v) => v,
+        // Unparseable state is useless to the fork; Chromium recreates it.
+        Err(_) => return Ok(std::fs::remove_file(path)?),
+    };
+    if let Some(obj) = state.as_object_mut() {
+        for key in LOCAL_STATE_SCRUB_KEYS {
+            obj.remove(*key);
+        }
+    }
+    std::fs::write(path, serde_json::to_string(&state)?)?;
+    Ok(())
 }
 
 // ── Row mapper ────────────────────────────────────────────────────────────────
@@ -384,6 +1254,7 @@ mod tests {
             notes: None,
             tags: None,
             behavior_profile: None,
+            persona: None,
         }
     }
 
@@ -445,6 +1316,7 @@ mod tests {
             notes: None,
             tags: None,
             behavior_profile: None,
+            persona: None,
         };
         let p = repo.create(req).unwrap();
         assert!(p.fingerprint.seed > 0);
@@ -474,6 +1346,7 @@ mod tests {
             notes: Some("a note".into()),
             tags: Some(vec!["vpn".into(), "test".into()]),
             behavior_profile: None,
+            persona: None,
         };
         let p = repo.create(req).unwrap();
         assert_eq!(p.notes, "a note");
@@ -498,11 +1371,38 @@ mod tests {
             notes: None,
             tags: None,
             behavior_profile: None,
+            persona: None,
         };
         let p = repo.create(req).unwrap();
         assert_eq!(p.proxy_id, Some("px1".into()));
     }
 
+    #[test]
+    fn create_with_proxy_matches_its_country() {
+        let (repo, _dir) = make_repo();
+        repo.db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO proxies (id, name, proxy_type, host, port, healthy, country) VALUES ('px1','de','http','h',1,0,'DE')",
+                [],
+            )?;
+            Ok(())
+        }).unwrap();
+        let p = repo
+            .create(CreateProfileRequest {
+                proxy_id: Some("px1".into()),
+                ..default_create("Berlin")
+            })
+            .unwrap();
+        assert_eq!(p.fingerprint.locale, "de-DE");
+        assert_eq!(p.fingerprint.timezone, "Europe/Berlin");
+
+        let missing = repo.create(CreateProfileRequest {
+            proxy_id: Some("nope".into()),
+            ..default_create("Missing")
+        });
+        assert!(matches!(missing, Err(ManifoldError::ProxyNotFound(_))));
+    }
+
     #[test]
     fn create_data_dir_is_created_on_disk() {
         let (repo, _dir) = make_repo();
@@ -530,11 +1430,67 @@ mod tests {
             notes: None,
             tags: None,
             behavior_profile: Some("cautious".into()),
+            persona: None,
         };
         let p = repo.create(req).unwrap();
         assert_eq!(p.human.profile.to_string(), "cautious");
     }
 
+    #[test]
+    fn create_with_persona_pins_locale_and_round_trips() {
+        let (repo, _dir) = make_repo();
+        let persona = Persona {
+            name: "Jo".into(),
+            home_city: Some("Berlin".into()),
+            ..Default::default()
+        };
+        let req = CreateProfileRequest {
+            persona: Some(persona.clone()),
+            ..default_create("Persona")
+        };
+        let p = repo.create(req).unwrap();
+        assert_eq!(p.fingerprint.timezone, "Europe/Berlin");
+        assert_eq!(repo.get(&p.id).unwrap().persona, Some(persona));
+    }
+
+    #[test]
+    fn proxy_chain_round_trips_and_clears() {
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("Chained")).unwrap();
+        assert!(p.proxy_chain.is_empty());
+
+        let chain = vec!["a".to_string(), "b".to_string()];
+        repo.set_proxy_chain(&p.id, &chain).unwrap();
+        assert_eq!(repo.get(&p.id).unwrap().proxy_chain, chain);
+
+        repo.set_proxy_chain(&p.id, &[]).unwrap();
+        assert!(repo.get(&p.id).unwrap().proxy_chain.is_empty());
+        assert!(repo.set_proxy_chain("missing", &[]).is_err());
+    }
+
+    #[test]
+    fn launch_env_round_trips_and_settings_default() {
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("Env")).unwrap();
+        assert!(p.launch_env.is_empty());
+
+        let env = BTreeMap::from([("LANG".to_string(), "de_DE.UTF-8".to_string())]);
+        repo.set_launch_env(&p.id, &env).unwrap();
+        assert_eq!(repo.get(&p.id).unwrap().launch_env, env);
+        assert!(repo.set_launch_env("missing", &env).is_err());
+
+        assert_eq!(
+            repo.launch_env_settings().unwrap(),
+            LaunchEnvSettings::default()
+        );
+        let settings = LaunchEnvSettings {
+            allowlist: vec!["LANG".into()],
+            node_dir: Some("/opt/node/bin".into()),
+        };
+        repo.set_launch_env_settings(&settings).unwrap();
+        assert_eq!(repo.launch_env_settings().unwrap(), settings);
+    }
+
     // ── Get ───────────────────────────────────────────────────────────────────
 
     #[test]
@@ -599,6 +1555,31 @@ mod tests {
         assert!(ids.contains(&b.id.as_str()));
     }
 
+    #[test]
+    fn list_page_walks_every_profile_once() {
+        let (repo, _dir) = make_repo();
+        for name in ["A", "B", "C", "D", "E"] {
+            repo.create(default_create(name)).unwrap();
+        }
+        let all: Vec<String> = repo.list().unwrap().into_iter().map(|p| p.id).collect();
+
+        let mut paged = Vec::new();
+        let mut page = Page::new(Some(2), None);
+        loop {
+            let batch = repo.list_page(&page).unwrap();
+            let Some(last) = batch.last() else { break };
+            page.after = Some(last.id.clone());
+            paged.extend(batch.into_iter().map(|p| p.id));
+        }
+        assert_eq!(paged, all);
+
+        page.after = Some("gone".into());
+        assert!(matches!(
+            repo.list_page(&page),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+    }
+
     // ── Update ────────────────────────────────────────────────────────────────
 
     #[test]
@@ -617,6 +1598,7 @@ mod tests {
                     tags: None,
                     behavior_profile: None,
                     tls_bridge: None,
+                    persona: None,
                 },
             )
             .unwrap();
@@ -639,6 +1621,7 @@ mod tests {
                 tags: Some(vec!["new_tag".into()]),
                 behavior_profile: None,
                 tls_bridge: None,
+                persona: None,
             },
         )
         .unwrap();
@@ -664,6 +1647,7 @@ mod tests {
                 tags: None,
                 behavior_profile: None,
                 tls_bridge: None,
+                persona: None,
             },
         )
         .unwrap();
@@ -686,6 +1670,7 @@ mod tests {
                     tags: None,
                     behavior_profile: None,
                     tls_bridge: None,
+                    persona: None,
                 },
             )
             .unwrap_err();
@@ -707,6 +1692,7 @@ mod tests {
                 tags: None,
                 behavior_profile: Some("bot".into()),
                 tls_bridge: None,
+                persona: None,
             },
         )
         .unwrap();
@@ -714,6 +1700,59 @@ mod tests {
         assert_eq!(fetched.human.profile.to_string(), "bot");
     }
 
+    #[test]
+    fn update_persona_rederives_locale_and_clears() {
+        let (repo, _dir) = make_repo();
+        let berlin = Persona {
+            name: "Jo".into(),
+            home_city: Some("Berlin".into()),
+            ..Default::default()
+        };
+        let p = repo
+            .create(CreateProfileRequest {
+                persona: Some(berlin),
+                ..default_create("Mover")
+            })
+            .unwrap();
+        let set_persona = |persona: Option<Persona>| {
+            repo.update(
+                &p.id,
+                UpdateProfileRequest {
+                    name: None,
+                    fingerprint: None,
+                    human: None,
+                    proxy_id: None,
+                    notes: None,
+                    tags: None,
+                    behavior_profile: None,
+                    tls_bridge: None,
+                    persona: Some(persona),
+                },
+            )
+            .unwrap()
+        };
+
+        let new_york = Persona {
+            name: "Jo".into(),
+            home_city: Some("New York".into()),
+            ..Default::default()
+        };
+        let moved = set_persona(Some(new_york.clone()));
+        assert_eq!(moved.fingerprint.timezone, "America/New_York");
+        assert_eq!(moved.fingerprint.locale, "en-US");
+        let fetched = repo.get(&p.id).unwrap();
+        assert_eq!(fetched.persona, Some(new_york));
+        assert_eq!(fetched.fingerprint.timezone, "America/New_York");
+
+        let cleared = set_persona(None);
+        let generated = FingerprintOrchestrator::generate(p.fingerprint.seed);
+        assert_eq!(cleared.fingerprint.timezone, generated.timezone);
+        assert_eq!(cleared.fingerprint.locale, generated.locale);
+        let fetched = repo.get(&p.id).unwrap();
+        assert!(fetched.persona.is_none());
+        assert_eq!(fetched.fingerprint.timezone, generated.timezone);
+    }
+
     // ── Set status / touch ────────────────────────────────────────────────────
 
     #[test]
@@ -772,13 +1811,121 @@ mod tests {
         assert!(!dir.exists());
     }
 
+    #[test]
+    fn gc_finds_and_removes_orphaned_dirs_only() {
+        let (repo, dir) = make_repo();
+        let p = repo.create(default_create("Kept")).unwrap();
+        let stray = dir.path().join("stale-profile");
+        std::fs::create_dir_all(stray.join("Default")).unwrap();
+        std::fs::write(stray.join("Default").join("Cookies"), [0u8; 64]).unwrap();
+        std::fs::write(dir.path().join("README.txt"), "not a profile").unwrap();
+
+        let report = repo.gc_orphaned_data(true).unwrap();
+        assert_eq!(report.orphans.len(), 1);
+        assert_eq!(report.orphans[0].name, "stale-profile");
+        assert_eq!(report.orphans[0].bytes, 64);
+        assert!(!report.orphans[0].removed);
+        assert!(stray.exists());
+
+        let report = repo.gc_orphaned_data(false).unwrap();
+        assert_eq!(report.reclaimed_bytes, 64);
+        assert!(!stray.exists());
+        assert!(std::path::Path::new(&p.data_dir).exists());
+    }
+
+    #[test]
+    fn new_profiles_are_prewarmed_only_when_enabled() {
+        let (repo, dir) = make_repo();
+        let shared = dir.path().join(SHARED_CACHE_DIR);
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::write(shared.join("0123456789abcdef_0"), b"entry").unwrap();
+        let cached = |p: &Profile| {
+            std::path::Path::new(&p.data_dir)
+                .join("Default/Cache/Cache_Data/0123456789abcdef_0")
+                .exists()
+        };
+
+        assert!(!cached(&repo.create(default_create("Cold")).unwrap()));
+        repo.set_cache_prewarm_settings(&CachePrewarmSettings {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(cached(&repo.create(default_create("Warm")).unwrap()));
+
+        // The shared layer is not an orphaned profile dir
+        assert!(repo.gc_orphaned_data(false).unwrap().orphans.is_empty());
+        assert_eq!(repo.shared_cache().unwrap().entries, 1);
+    }
+
+    // ── Status state machine ──────────────────────────────────────────────────
+
+    #[test]
+    fn double_launch_is_rejected_and_recorded() {
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("Twice")).unwrap();
+        repo.transition(&p.id, ProfileStatus::Running, TransitionReason::Launch)
+            .unwrap();
+        let err = repo
+            .transition(&p.id, ProfileStatus::Running, TransitionReason::Launch)
+            .unwrap_err();
+        assert!(matches!(err, ManifoldError::InvalidArg(_)));
+        assert_eq!(repo.get(&p.id).unwrap().status, ProfileStatus::Running);
+
+        let history = repo.status_history(&p.id, 10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(!history[0].allowed);
+        assert!(history[1].allowed);
+        assert_eq!(history[1].reason, TransitionReason::Launch);
+    }
+
+    #[test]
+    fn transition_table_matches_lifecycle() {
+        use ProfileStatus::*;
+        assert!(Running.can_transition_to(Suspended));
+        assert!(Suspended.can_transition_to(Running));
+        assert!(Error.can_transition_to(Running));
+        assert!(Idle.can_transition_to(Idle));
+        assert!(!Idle.can_transition_to(Suspended));
+        assert!(!Suspended.can_transition_to(Suspended));
+        assert!(!Error.can_transition_to(Error));
+    }
+
+    #[test]
+    fn repeated_stop_is_a_silent_no_op() {
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("Stopped")).unwrap();
+        repo.transition(&p.id, ProfileStatus::Idle, TransitionReason::Stop)
+            .unwrap();
+        assert!(repo.status_history(&p.id, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn transition_all_moves_only_matching_profiles() {
+        let (repo, _dir) = make_repo();
+        let a = repo.create(default_create("A")).unwrap();
+        let b = repo.create(default_create("B")).unwrap();
+        repo.set_status(&a.id, ProfileStatus::Running).unwrap();
+        let moved = repo
+            .transition_all(
+                &[ProfileStatus::Running, ProfileStatus::Suspended],
+                ProfileStatus::Idle,
+                TransitionReason::Startup,
+            )
+            .unwrap();
+        assert_eq!(moved, vec![a.id.clone()]);
+        assert_eq!(repo.get(&b.id).unwrap().status, ProfileStatus::Idle);
+    }
+
     // ── Reseed fingerprint ────────────────────────────────────────────────────
 
     #[test]
     fn reseed_with_explicit_seed() {
         let (repo, _dir) = make_repo();
         let p = repo.create(default_create("Reseed")).unwrap();
-        let reseeded = repo.reseed_fingerprint(&p.id, Some(777)).unwrap();
+        let reseeded = repo
+            .reseed_fingerprint(&p.id, Some(777), &ReseedOptions::default())
+            .unwrap();
         assert_eq!(reseeded.fingerprint.seed, 777);
         // Persisted
         assert_eq!(repo.get(&p.id).unwrap().fingerprint.seed, 777);
@@ -789,7 +1936,9 @@ mod tests {
         let (repo, _dir) = make_repo();
         let p = repo.create(default_create("ReseedRandom")).unwrap();
         let original_seed = p.fingerprint.seed;
-        let reseeded = repo.reseed_fingerprint(&p.id, None).unwrap();
+        let reseeded = repo
+            .reseed_fingerprint(&p.id, None, &ReseedOptions::default())
+            .unwrap();
         // Extremely unlikely to collide, but not impossible — just check it's set
         let _ = reseeded.fingerprint.seed;
         // The new fingerprint must be persisted
@@ -801,10 +1950,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reseed_with_options_keeps_locale() {
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("KeepTz")).unwrap();
+        let opts = ReseedOptions {
+            keep_locale_tz: true,
+            ..Default::default()
+        };
+        let reseeded = repo.reseed_fingerprint(&p.id, Some(1234), &opts).unwrap();
+        assert_eq!(reseeded.fingerprint.seed, 1234);
+        let fetched = repo.get(&p.id).unwrap();
+        assert_eq!(fetched.fingerprint.timezone, p.fingerprint.timezone);
+        assert_eq!(fetched.fingerprint.locale, p.fingerprint.locale);
+    }
+
+    #[test]
+    fn pinned_version_survives_reseed_until_released() {
+        use crate::fingerprint::chrome_version;
+
+        let (repo, _dir) = make_repo();
+        let p = repo.create(default_create("Legacy")).unwrap();
+        let pinned = repo
+            .set_pinned_browser_version(&p.id, Some("109.0.5414.120"))
+            .unwrap();
+        assert_eq!(
+            pinned.pinned_browser_version.as_deref(),
+            Some("109.0.5414.120")
+        );
+        let fetched = repo.get(&p.id).unwrap();
+        assert_eq!(
+            chrome_version(&fetched.fingerprint.user_agent),
+            Some("109.0.5414.120")
+        );
+
+        let reseeded = repo
+            .reseed_fingerprint(&p.id, Some(99), &ReseedOptions::default())
+            .unwrap();
+        assert_eq!(
+            chrome_version(&reseeded.fingerprint.user_agent),
+            Some("109.0.5414.120")
+        );
+
+        assert!(repo.set_pinned_browser_version(&p.id, Some("1.2")).is_err());
+        let released = repo.set_pinned_browser_version(&p.id, None).unwrap();
+        assert_eq!(released.pinned_browser_version, None);
+        assert_eq!(
+            chrome_version(&released.fingerprint.user_agent),
+            Some("109.0.5414.120")
+        );
+    }
+
     #[test]
     fn reseed_nonexistent_returns_not_found() {
         let (repo, _dir) = make_repo();
-        let err = repo.reseed_fingerprint("ghost", Some(1)).unwrap_err();
+        let err = repo
+            .reseed_fingerprint("ghost", Some(1), &ReseedOptions::default())
+            .unwrap_err();
         assert!(matches!(err, ManifoldError::ProfileNotFound(_)));
     }
 
@@ -821,6 +2023,7 @@ mod tests {
                 notes: None,
                 tags: None,
                 behavior_profile: None,
+                persona: None,
             })
             .unwrap();
         let b = repo
@@ -831,6 +2034,7 @@ mod tests {
                 notes: None,
                 tags: None,
                 behavior_profile: None,
+                persona: None,
             })
             .unwrap();
         assert_ne!(a.id, b.id);
@@ -847,6 +2051,7 @@ mod tests {
                 tags: None,
                 behavior_profile: None,
                 tls_bridge: None,
+                persona: None,
             },
         )
         .unwrap();
@@ -854,6 +2059,194 @@ mod tests {
         let b_check = repo.get(&b.id).unwrap();
         assert_eq!(b_check.name, "Beta", "updating A must not affect B");
     }
+
+    // ── Browser data copy ─────────────────────────────────────────────────────
+
+    #[test]
+    fn copy_browser_data_scrubs_device_bound_files() {
+        let (repo, _dir) = make_repo();
+        let a = repo.create(default_create("Src")).unwrap();
+        let b = repo.create(default_create("Dst")).unwrap();
+
+        let src = std::path::PathBuf::from(&a.data_dir);
+        std::fs::create_dir_all(src.join("Default").join("GPUCache")).unwrap();
+        std::fs::create_dir_all(src.join("sessions")).unwrap();
+        std::fs::write(src.join("Default").join("Cookies"), b"cookies").unwrap();
+        std::fs::write(src.join("Default").join("GPUCache").join("data_0"), b"gpu").unwrap();
+        std::fs::write(src.join("DevToolsActivePort"), b"9222").unwrap();
+        std::fs::write(
+            src.join("Local State"),
+            r#"{"os_crypt":{"encrypted_key":"k"},"user_experience_metrics":{"client_id":"c"}}"#,
+        )
+        .unwrap();
+
+        repo.copy_browser_data(&a.id, &b.id).unwrap();
+
+        let dst = std::path::PathBuf::from(&b.data_dir);
+        assert_eq!(
+            std::fs::read(dst.join("Default").join("Cookies")).unwrap(),
+            b"cookies"
+        );
+        assert!(!dst.join("Default").join("GPUCache").exists());
+        assert!(!dst.join("DevToolsActivePort").exists());
+        assert!(!dst.join("sessions").exists());
+
+        let state: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dst.join("Local State")).unwrap())
+                .unwrap();
+        assert!(state.get("os_crypt").is_some());
+        assert!(state.get("user_experience_metrics").is_none());
+    }
+
+    #[test]
+    fn copy_browser_data_refuses_running_profiles() {
+        let (repo, _dir) = make_repo();
+        let a = repo.create(default_create("Src")).unwrap();
+        let b = repo.create(default_create("Dst")).unwrap();
+        let src = std::path::PathBuf::from(&a.data_dir);
+        std::fs::create_dir_all(src.join("Default")).unwrap();
+        std::fs::write(src.join("Default").join("Cookies"), b"cookies").unwrap();
+
+        repo.set_status(&a.id, ProfileStatus::Running).unwrap();
+        assert!(matches!(
+            repo.copy_browser_data(&a.id, &b.id),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+        repo.set_status(&a.id, ProfileStatus::Idle).unwrap();
+        repo.set_status(&b.id, ProfileStatus::Running).unwrap();
+        assert!(matches!(
+            repo.copy_browser_data(&a.id, &b.id),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+        let dst = std::path::PathBuf::from(&b.data_dir);
+        assert!(!dst.join("Default").join("Cookies").exists());
+    }
+
+    // ── Batch update ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn batch_update_by_ids_applies_tags_and_tls_bridge() {
+        let (repo, _dir) = make_repo();
+        let a = repo.create(default_create("A")).unwrap();
+        let b = repo.create(default_create("B")).unwrap();
+        repo.update(
+            &a.id,
+            UpdateProfileRequest {
+                name: None,
+                fingerprint: None,
+                human: None,
+                proxy_id: None,
+                notes: None,
+                tags: Some(vec!["old".into()]),
+                behavior_profile: None,
+                tls_bridge: None,
+                persona: None,
+            },
+        )
+        .unwrap();
+
+        let res = repo
+            .batch_update(
+                ProfileSelection::Ids(vec![a.id.clone(), b.id.clone()]),
+                BatchProfilePatch {
+                    add_tags: vec!["farm".into()],
+                    remove_tags: vec!["old".into()],
+                    behavior_profile: Some("cautious".into()),
+                    tls_bridge: Some(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(res.applied);
+        assert_eq!(res.results.len(), 2);
+
+        let a = repo.get(&a.id).unwrap();
+        assert_eq!(a.tags, vec!["farm"]);
+        assert_eq!(a.tls_bridge, Some(true));
+        assert_eq!(a.human.profile.to_string(), "cautious");
+    }
+
+    #[test]
+    fn batch_update_by_filter_round_robins_proxy_pool() {
+        let (repo, _dir) = make_repo();
+        repo.db.with_conn(|conn| {
+            conn.execute_batch(
+                "INSERT INTO proxies (id, name, proxy_type, host, port, healthy) VALUES ('px1','a','http','h',1,0), ('px2','b','http','h',1,0)",
+            )?;
+            Ok(())
+        }).unwrap();
+        for name in ["x1", "x2", "x3", "other"] {
+            repo.create(default_create(name)).unwrap();
+        }
+
+        let res = repo
+            .batch_update(
+                ProfileSelection::Filter(ProfileFilter {
+                    name_contains: Some("X".into()),
+                    ..Default::default()
+                }),
+                BatchProfilePatch {
+                    proxy_pool: Some(vec!["px1".into(), "px2".into()]),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(res.applied);
+        assert_eq!(res.results.len(), 3);
+
+        let all = repo.list().unwrap();
+        let with_px1 = all
+            .iter()
+            .filter(|p| p.proxy_id.as_deref() == Some("px1"))
+            .count();
+        let with_px2 = all
+            .iter()
+            .filter(|p| p.proxy_id.as_deref() == Some("px2"))
+            .count();
+        assert_eq!(with_px1 + with_px2, 3);
+        assert!(with_px1 >= 1 && with_px2 >= 1);
+        assert!(all
+            .iter()
+            .find(|p| p.name == "other")
+            .unwrap()
+            .proxy_id
+            .is_none());
+    }
+
+    #[test]
+    fn batch_update_rolls_back_when_any_item_fails() {
+        let (repo, _dir) = make_repo();
+        let a = repo.create(default_create("A")).unwrap();
+
+        let res = repo
+            .batch_update(
+                ProfileSelection::Ids(vec![a.id.clone(), "ghost".into()]),
+                BatchProfilePatch {
+                    add_tags: vec!["x".into()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(!res.applied);
+        assert!(res.results[0].ok);
+        assert!(!res.results[1].ok);
+        assert!(repo.get(&a.id).unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn batch_update_rejects_unknown_behavior_profile() {
+        let (repo, _dir) = make_repo();
+        let err = repo
+            .batch_update(
+                ProfileSelection::Ids(vec![]),
+                BatchProfilePatch {
+                    behavior_profile: Some("reckless".into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, ManifoldError::InvalidArg(_)));
+    }
 }
 
 fn row_to_profile(row: &rusqlite::Row<'_>) -> rusqlite::Result<Profile> {
@@ -868,6 +2261,13 @@ fn row_to_profile(row: &rusqlite::Row<'_>) -> rusqlite::Result<Profile> {
     let created_str: String = row.get(8)?;
     let last_str: Option<String> = row.get(9)?;
     let tls_bridge: Option<i32> = row.get(10)?;
+    let auto_age: bool = row.get(11)?;
+    let persona_json: Option<String> = row.get(12)?;
+    let chain_json: String = row.get(13)?;
+    let env_json: String = row.get(14)?;
+    let disk_quota_mb: Option<i64> = row.get(15)?;
+    let policy_json: String = row.get(16)?;
+    let pinned_browser_version: Option<String> = row.get(17)?;
 
     let fingerprint: Fingerprint = serde_json::from_str(&fp_json).map_err(|e| {
         rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
@@ -904,5 +2304,12 @@ fn row_to_profile(row: &rusqlite::Row<'_>) -> rusqlite::Result<Profile> {
         last_used,
         data_dir: String::new(), // filled by repo after construction
         tls_bridge: tls_bridge.map(|v| v != 0),
+        auto_age,
+        persona: persona_json.and_then(|s| serde_json::from_str(&s).ok()),
+        proxy_chain: serde_json::from_str(&chain_json).unwrap_or_default(),
+        launch_env: serde_json::from_str(&env_json).unwrap_or_default(),
+        disk_quota_mb: disk_quota_mb.map(|mb| mb.max(0) as u64),
+        launch_policy: serde_json::from_str(&policy_json).unwrap_or_default(),
+        pinned_browser_version,
     })
 }
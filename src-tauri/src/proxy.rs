@@ -3,11 +3,13 @@
 use chrono::{DateTime, Utc};
 use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use crate::db::Db;
+use crate::db::{Db, Page};
 use crate::error::{ManifoldError, Result};
+use crate::integrity::OnDelete;
 
 // ── Types ─────────────────────────────────────────────────────────────────────
 
@@ -18,6 +20,8 @@ pub enum ProxyType {
     Http,
     Https,
     Socks5,
+    /// SSH server used through a Manifold-owned `ssh -D` tunnel.
+    Ssh,
 }
 
 impl std::fmt::Display for ProxyType {
@@ -26,6 +30,7 @@ impl std::fmt::Display for ProxyType {
             Self::Http => write!(f, "http"),
             Self::Https => write!(f, "https"),
             Self::Socks5 => write!(f, "socks5"),
+            Self::Ssh => write!(f, "ssh"),
         }
     }
 }
@@ -37,6 +42,7 @@ impl std::str::FromStr for ProxyType {
             "http" => Ok(Self::Http),
             "https" => Ok(Self::Https),
             "socks5" => Ok(Self::Socks5),
+            "ssh" => Ok(Self::Ssh),
             other => Err(ManifoldError::InvalidArg(format!(
                 "unknown proxy type: {other:?}"
             ))),
@@ -44,6 +50,147 @@ impl std::str::FromStr for ProxyType {
     }
 }
 
+/// How health checks talk to an HTTP(S) proxy.  Many datacenter proxies
+/// reject absolute-URI GETs and only tunnel, while some residential pools
+/// are the other way round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckMode {
+    /// Plain `GET` of the target URL, over http, through the proxy.
+    #[default]
+    Get,
+    /// `CONNECT` to the target, then a real TLS handshake for https
+    /// targets or a GET through the tunnel for http ones.
+    Connect,
+    /// Try both and record which the proxy supports.
+    Both,
+}
+
+impl CheckMode {
+    fn runs_get(self) -> bool {
+        self != Self::Connect
+    }
+
+    fn runs_connect(self) -> bool {
+        self != Self::Get
+    }
+}
+
+impl std::fmt::Display for CheckMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Get => write!(f, "get"),
+            Self::Connect => write!(f, "connect"),
+            Self::Both => write!(f, "both"),
+        }
+    }
+}
+
+impl std::str::FromStr for CheckMode {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "get" => Ok(Self::Get),
+            "connect" => Ok(Self::Connect),
+            "both" => Ok(Self::Both),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown check mode: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// What a proxy reveals to the sites it fetches from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnonymityLevel {
+    /// Forwards the client's real IP (`X-Forwarded-For` and friends) —
+    /// unusable for stealth.
+    Transparent,
+    /// Hides the client IP but announces itself (`Via`, `Forwarded`, …).
+    Anonymous,
+    /// Adds nothing a site could tell apart from a direct visit.
+    Elite,
+}
+
+impl std::fmt::Display for AnonymityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transparent => write!(f, "transparent"),
+            Self::Anonymous => write!(f, "anonymous"),
+            Self::Elite => write!(f, "elite"),
+        }
+    }
+}
+
+impl std::str::FromStr for AnonymityLevel {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "transparent" => Ok(Self::Transparent),
+            "anonymous" => Ok(Self::Anonymous),
+            "elite" => Ok(Self::Elite),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown anonymity level: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// Request headers that only a proxy adds.
+const PROXY_HEADERS: [&str; 8] = [
+    "via",
+    "forwarded",
+    "x-forwarded-for",
+    "x-real-ip",
+    "client-ip",
+    "x-client-ip",
+    "x-proxy-id",
+    "proxy-connection",
+];
+
+/// Classify the headers a reflecting endpoint saw.  `real_ip` is this
+/// machine's direct public IP; when it couldn't be determined, forwarded-for
+/// headers alone only prove the proxy is anonymous at best.
+pub fn classify_anonymity(headers: &[(String, String)], real_ip: Option<&str>) -> AnonymityLevel {
+    let real_ip = real_ip.and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+    let mut announced = false;
+    for (name, value) in headers {
+        if !PROXY_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+            continue;
+        }
+        if real_ip.is_some_and(|real| header_ips(value).any(|ip| ip == real)) {
+            return AnonymityLevel::Transparent;
+        }
+        announced = true;
+    }
+    if announced {
+        AnonymityLevel::Anonymous
+    } else {
+        AnonymityLevel::Elite
+    }
+}
+
+/// IP addresses named in a proxy header value: the elements of
+/// `X-Forwarded-For: a, b`, the `for=`/`by=` nodes of `Forwarded` (quoted,
+/// bracketed or with a port) and the received-by hosts of `Via`.
+fn header_ips(value: &str) -> impl Iterator<Item = IpAddr> + '_ {
+    value
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter_map(|token| {
+            let token = token.split_once('=').map_or(token, |(_, v)| v);
+            let token = token.trim_matches('"');
+            if let Some(rest) = token.strip_prefix('[') {
+                return rest.split(']').next()?.parse().ok();
+            }
+            token.parse().ok().or_else(|| {
+                let (host, port) = token.rsplit_once(':')?;
+                port.parse::<u16>().ok()?;
+                host.parse::<Ipv4Addr>().ok().map(IpAddr::V4)
+            })
+        })
+}
+
 /// Full proxy record returned to the frontend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Proxy {
@@ -58,6 +205,39 @@ pub struct Proxy {
     #[serde(skip_serializing)]
     pub password: Option<String>,
     pub country: Option<String>,
+    /// State, province or city within `country` ("UT", "Utah", "Salt Lake
+    /// City"), pinning the timezone `enforce_geo_region` picks.  Filled from
+    /// the measured exit by a leak test unless set by the user.
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Private key file for `Ssh` proxies; password auth is used without one.
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Resolved IP the proxy is pinned to; dialled instead of `host`.
+    #[serde(default)]
+    pub pinned_ip: Option<String>,
+    #[serde(default)]
+    pub check_mode: CheckMode,
+    /// Whether the last check that tried each mode succeeded with it
+    /// (`None` until a check has tried it; HTTP(S) proxies only).
+    #[serde(default)]
+    pub supports_get: Option<bool>,
+    #[serde(default)]
+    pub supports_connect: Option<bool>,
+    /// URLs this proxy's health checks use instead of the app-wide list.
+    #[serde(default)]
+    pub check_targets: Vec<String>,
+    /// Download speed from the last `measure_speed`, in KB/s.
+    #[serde(default)]
+    pub throughput_kbps: Option<u32>,
+    /// From the last successful health check; `None` until detected.
+    #[serde(default)]
+    pub anonymity: Option<AnonymityLevel>,
+    /// Free-form labels ("client A only", "mobile 4G").
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
     pub healthy: bool,
     pub latency_ms: Option<u32>,
     pub last_checked: Option<DateTime<Utc>>,
@@ -70,30 +250,88 @@ impl Proxy {
     #[allow(dead_code)]
     pub fn to_playwright_url(&self) -> String {
         let scheme = match self.proxy_type {
-            ProxyType::Socks5 => "socks5",
+            ProxyType::Socks5 | ProxyType::Ssh => "socks5",
             _ => "http",
         };
         match (&self.username, &self.password) {
             (Some(u), Some(p)) => {
-                format!("{scheme}://{}:{}@{}:{}", u, p, self.host, self.port)
+                format!("{scheme}://{}:{}@{}:{}", u, p, self.url_host(), self.port)
             }
             (Some(u), None) => {
-                format!("{scheme}://{}@{}:{}", u, self.host, self.port)
+                format!("{scheme}://{}@{}:{}", u, self.url_host(), self.port)
             }
-            _ => format!("{scheme}://{}:{}", self.host, self.port),
+            _ => format!("{scheme}://{}:{}", self.url_host(), self.port),
         }
     }
 
     /// Build the Playwright `server` field (no credentials — they go separately).
     pub fn to_playwright_server(&self) -> String {
         let scheme = match self.proxy_type {
-            ProxyType::Socks5 => "socks5",
+            ProxyType::Socks5 | ProxyType::Ssh => "socks5",
             _ => "http",
         };
-        format!("{scheme}://{}:{}", self.host, self.port)
+        format!("{scheme}://{}:{}", self.url_host(), self.port)
+    }
+
+    /// Host as it must appear in a URL (the pinned IP when set): IPv6
+    /// literals are bracketed.
+    pub fn url_host(&self) -> String {
+        let host = self.pinned_ip.as_deref().unwrap_or(&self.host);
+        if host.parse::<Ipv6Addr>().is_ok() {
+            format!("[{host}]")
+        } else {
+            host.to_string()
+        }
+    }
+
+    /// What checks have recorded about this proxy.
+    pub fn health_snapshot(&self) -> HealthSnapshot {
+        HealthSnapshot {
+            healthy: self.healthy,
+            latency_ms: self.latency_ms,
+            last_checked: self.last_checked,
+            supports_get: self.supports_get,
+            supports_connect: self.supports_connect,
+            throughput_kbps: self.throughput_kbps,
+            anonymity: self.anonymity,
+        }
     }
 }
 
+/// Canonical stored form of a proxy host: trimmed, IPv6 literals without
+/// brackets (so `(host, port)` resolves directly).  Rejects malformed
+/// literals and `host:port` strings pasted into the host field.
+pub fn normalize_host(host: &str) -> Result<String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(ManifoldError::InvalidArg("host cannot be empty".into()));
+    }
+    let bare = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+    let bracketed = bare.len() != host.len();
+    if (bracketed || bare.contains(':')) && bare.parse::<Ipv6Addr>().is_err() {
+        return Err(ManifoldError::InvalidArg(format!(
+            "invalid host {host:?} (put the port in the port field)"
+        )));
+    }
+    Ok(bare.to_string())
+}
+
+/// Whether the proxy exit and this machine can reach IPv6 destinations.
+/// When the host has IPv6 but the proxy does not, anything that bypasses the
+/// proxy (WebRTC host candidates, a misconfigured launch) reveals the real
+/// IPv6 address — no proxied traffic could have produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ipv6LeakReport {
+    pub proxy_id: String,
+    pub proxy_ipv6: bool,
+    pub host_ipv6: bool,
+    pub leak_risk: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
 /// Subset of Proxy fields needed for health check results.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyHealth {
@@ -101,9 +339,36 @@ pub struct ProxyHealth {
     pub healthy: bool,
     pub latency_ms: Option<u32>,
     pub error: Option<String>,
+    pub supports_get: Option<bool>,
+    pub supports_connect: Option<bool>,
+    /// Only set when the check included a throughput test.
+    pub throughput_kbps: Option<u32>,
+    pub anonymity: Option<AnonymityLevel>,
     pub checked_at: DateTime<Utc>,
 }
 
+/// What health checks have learned about a proxy: its track record as a
+/// proxy list export carries it between instances.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthSnapshot {
+    pub healthy: bool,
+    pub latency_ms: Option<u32>,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub supports_get: Option<bool>,
+    pub supports_connect: Option<bool>,
+    pub throughput_kbps: Option<u32>,
+    pub anonymity: Option<AnonymityLevel>,
+}
+
+/// Latency of one health-check target through a proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetLatency {
+    pub target: String,
+    pub latency_ms: Option<u32>,
+    pub error: Option<String>,
+}
+
 /// Payload for adding a new proxy.
 #[derive(Debug, Deserialize)]
 pub struct AddProxyRequest {
@@ -114,10 +379,18 @@ pub struct AddProxyRequest {
     pub username: Option<String>,
     pub password: Option<String>,
     pub country: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub notes: Option<String>,
 }
 
 /// Payload for updating a proxy.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct UpdateProxyRequest {
     pub name: Option<String>,
     pub proxy_type: Option<String>,
@@ -126,8 +399,81 @@ pub struct UpdateProxyRequest {
     pub username: Option<String>,
     pub password: Option<String>,
     pub country: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// Narrows `list_page_filtered`; every set field must match.
+#[derive(Debug, Default, Deserialize)]
+pub struct ProxyFilter {
+    pub tag: Option<String>,
+    /// Case-insensitive substring of the name or notes.
+    pub search: Option<String>,
 }
 
+/// Trimmed, non-empty tags, each once, in the order given.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !out.iter().any(|t| t == tag) {
+            out.push(tag.to_string());
+        }
+    }
+    out
+}
+
+/// A negative signal from a target site observed through a proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainHit {
+    Block,
+    Captcha,
+}
+
+impl DomainHit {
+    /// Cooldown after the first hit; doubles with every further hit.
+    fn base_cooldown(self) -> chrono::Duration {
+        match self {
+            Self::Block => chrono::Duration::hours(1),
+            Self::Captcha => chrono::Duration::minutes(15),
+        }
+    }
+}
+
+impl std::fmt::Display for DomainHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Block => "block",
+            Self::Captcha => "captcha",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Blocks/captchas seen for one (proxy, domain) pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyDomainStatus {
+    pub proxy_id: String,
+    pub domain: String,
+    pub blocks: u32,
+    pub captchas: u32,
+    pub last_hit_at: DateTime<Utc>,
+    /// The proxy is skipped for this domain until then.
+    pub cooldown_until: DateTime<Utc>,
+}
+
+/// Host that only publishes AAAA records, used to probe IPv6 reachability.
+const IPV6_PROBE_HOST: &str = "ipv6.google.com";
+
+/// Longest cooldown a repeatedly flagged proxy can accumulate.
+const MAX_DOMAIN_COOLDOWN_HOURS: i64 = 24;
+
 // ── Repository ────────────────────────────────────────────────────────────────
 
 pub struct ProxyRepo {
@@ -145,13 +491,14 @@ impl ProxyRepo {
         let id = Uuid::new_v4().to_string();
         let proxy_type: ProxyType = req.proxy_type.parse()?;
 
-        if req.host.trim().is_empty() {
-            return Err(ManifoldError::InvalidArg("host cannot be empty".into()));
-        }
+        let host = normalize_host(&req.host)?;
         if req.port == 0 {
             return Err(ManifoldError::InvalidArg("port cannot be 0".into()));
         }
 
+        let tags = normalize_tags(&req.tags.unwrap_or_default());
+        let notes = req.notes.unwrap_or_default();
+
         // Encrypt the password if supplied
         let password_enc = self.db.encrypt_opt(req.password.as_deref())?;
 
@@ -159,17 +506,22 @@ impl ProxyRepo {
             conn.execute(
                 r#"INSERT INTO proxies
                    (id, name, proxy_type, host, port,
-                    username, password_enc, country, healthy)
-                   VALUES (?1,?2,?3,?4,?5,?6,?7,?8,0)"#,
+                    username, password_enc, country, ssh_key_path, tags, notes, healthy,
+                    region)
+                   VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,0,?12)"#,
                 params![
                     id,
                     req.name,
                     proxy_type.to_string(),
-                    req.host,
+                    host,
                     req.port as i64,
                     req.username,
                     password_enc,
                     req.country,
+                    req.ssh_key_path,
+                    serde_json::to_string(&tags)?,
+                    notes,
+                    req.region,
                 ],
             )?;
             Ok(())
@@ -179,11 +531,22 @@ impl ProxyRepo {
             id,
             name: req.name,
             proxy_type,
-            host: req.host,
+            host,
             port: req.port,
             username: req.username,
             password: req.password,
             country: req.country,
+            region: req.region,
+            ssh_key_path: req.ssh_key_path,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags,
+            notes,
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -199,7 +562,9 @@ impl ProxyRepo {
                     .query_row(
                         r#"SELECT id, name, proxy_type, host, port,
                                   username, password_enc, country,
-                                  healthy, latency_ms, last_checked
+                                  healthy, latency_ms, last_checked, ssh_key_path, pinned_ip,
+                              check_mode, supports_get, supports_connect, check_targets,
+                              throughput_kbps, anonymity, tags, notes, region
                            FROM proxies WHERE id = ?1"#,
                         params![id],
                         |r| row_to_proxy_raw(r),
@@ -212,16 +577,46 @@ impl ProxyRepo {
     }
 
     pub fn list(&self) -> Result<Vec<Proxy>> {
+        self.list_page(&Page::default())
+    }
+
+    /// By name, one page at a time.
+    pub fn list_page(&self, page: &Page) -> Result<Vec<Proxy>> {
+        self.list_page_filtered(page, &ProxyFilter::default())
+    }
+
+    /// `list_page` of the proxies matching `filter`.
+    pub fn list_page_filtered(&self, page: &Page, filter: &ProxyFilter) -> Result<Vec<Proxy>> {
+        let search = filter
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", escape_like(s)));
         self.db
             .with_conn(|conn| {
+                page.check_cursor(conn, "proxies")?;
                 let mut stmt = conn.prepare(
                     r#"SELECT id, name, proxy_type, host, port,
                               username, password_enc, country,
-                              healthy, latency_ms, last_checked
-                       FROM proxies ORDER BY name ASC"#,
+                              healthy, latency_ms, last_checked, ssh_key_path, pinned_ip,
+                              check_mode, supports_get, supports_connect, check_targets,
+                              throughput_kbps, anonymity, tags, notes, region
+                       FROM proxies
+                       WHERE (?1 IS NULL
+                              OR (name, id) > (SELECT name, id FROM proxies WHERE id = ?1))
+                         AND (?3 IS NULL
+                              OR EXISTS (SELECT 1 FROM json_each(proxies.tags) WHERE value = ?3))
+                         AND (?4 IS NULL
+                              OR name LIKE ?4 ESCAPE '\' OR notes LIKE ?4 ESCAPE '\')
+                       ORDER BY name ASC, id ASC
+                       LIMIT ?2"#,
                 )?;
                 let raws = stmt
-                    .query_map([], |r| row_to_proxy_raw(r))?
+                    .query_map(
+                        params![page.after, page.sql_limit(), filter.tag, search],
+                        row_to_proxy_raw,
+                    )?
                     .collect::<rusqlite::Result<Vec<_>>>()?;
                 Ok(raws)
             })
@@ -236,6 +631,8 @@ impl ProxyRepo {
 
     pub fn update(&self, id: &str, req: UpdateProxyRequest) -> Result<Proxy> {
         let mut proxy = self.get(id)?;
+        // A region given here is the user's; leak tests leave it alone
+        let region_set = req.region.is_some();
 
         if let Some(name) = req.name {
             proxy.name = name;
@@ -244,7 +641,7 @@ impl ProxyRepo {
             proxy.proxy_type = pt.parse()?;
         }
         if let Some(host) = req.host {
-            proxy.host = host;
+            proxy.host = normalize_host(&host)?;
         }
         if let Some(port) = req.port {
             proxy.port = port;
@@ -258,6 +655,18 @@ impl ProxyRepo {
         if req.country.is_some() {
             proxy.country = req.country;
         }
+        if req.region.is_some() {
+            proxy.region = req.region;
+        }
+        if req.ssh_key_path.is_some() {
+            proxy.ssh_key_path = req.ssh_key_path;
+        }
+        if let Some(tags) = req.tags {
+            proxy.tags = normalize_tags(&tags);
+        }
+        if let Some(notes) = req.notes {
+            proxy.notes = notes;
+        }
 
         let password_enc = self.db.encrypt_opt(proxy.password.as_deref())?;
 
@@ -265,7 +674,9 @@ impl ProxyRepo {
             let updated = conn.execute(
                 r#"UPDATE proxies
                    SET name = ?1, proxy_type = ?2, host = ?3, port = ?4,
-                       username = ?5, password_enc = ?6, country = ?7
+                       username = ?5, password_enc = ?6, country = ?7,
+                       ssh_key_path = ?9, tags = ?10, notes = ?11, region = ?12,
+                       region_measured = region_measured AND NOT ?13
                    WHERE id = ?8"#,
                 params![
                     proxy.name,
@@ -276,6 +687,11 @@ impl ProxyRepo {
                     password_enc,
                     proxy.country,
                     id,
+                    proxy.ssh_key_path,
+                    serde_json::to_string(&proxy.tags)?,
+                    proxy.notes,
+                    proxy.region,
+                    region_set,
                 ],
             )?;
             if updated == 0 {
@@ -290,21 +706,372 @@ impl ProxyRepo {
     // ── Delete ────────────────────────────────────────────────────────────────
 
     pub fn delete(&self, id: &str) -> Result<()> {
+        self.delete_with(id, OnDelete::Nullify)
+    }
+
+    /// Delete a proxy, detaching it from or refusing for the profiles that
+    /// still use it, as `on_delete` says.
+    pub fn delete_with(&self, id: &str, on_delete: OnDelete) -> Result<()> {
         self.db.with_conn(|conn| {
-            let deleted = conn.execute("DELETE FROM proxies WHERE id = ?1", params![id])?;
+            let tx = conn.unchecked_transaction()?;
+            crate::integrity::release_proxy(&tx, id, on_delete)?;
+            let deleted = tx.execute("DELETE FROM proxies WHERE id = ?1", params![id])?;
             if deleted == 0 {
                 return Err(ManifoldError::ProxyNotFound(id.into()));
             }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    // ── IP pinning ────────────────────────────────────────────────────────────
+
+    /// Pin the proxy to `ip`, or with `None` to whichever resolved address
+    /// answers first right now.  Returns the pinned IP.
+    pub fn pin_ip(&self, id: &str, ip: Option<&str>) -> Result<String> {
+        let mut proxy = self.get(id)?;
+        let ip = match ip {
+            Some(ip) => ip
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<std::net::IpAddr>()
+                .map_err(|_| ManifoldError::InvalidArg(format!("invalid IP address: {ip:?}")))?,
+            None => {
+                proxy.pinned_ip = None;
+                crate::dns::connect_proxy(&proxy, Duration::from_secs(10))
+                    .map_err(ManifoldError::ProxyCheck)?
+                    .1
+                    .ip()
+            }
+        };
+        self.set_pinned_ip(id, Some(&ip.to_string()))?;
+        Ok(ip.to_string())
+    }
+
+    pub fn unpin_ip(&self, id: &str) -> Result<()> {
+        self.set_pinned_ip(id, None)
+    }
+
+    fn set_pinned_ip(&self, id: &str, ip: Option<&str>) -> Result<()> {
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE proxies SET pinned_ip = ?1 WHERE id = ?2",
+                params![ip, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProxyNotFound(id.into()));
+            }
             Ok(())
         })
     }
 
+    /// Record the region a leak test measured, replacing an earlier
+    /// measurement but never a region the user set.  Returns whether it
+    /// changed.
+    pub fn fill_region(&self, id: &str, region: &str) -> Result<bool> {
+        self.db.with_conn(|conn| {
+            Ok(conn.execute(
+                "UPDATE proxies SET region = ?1, region_measured = 1
+                 WHERE id = ?2 AND region IS NOT ?1
+                   AND (region IS NULL OR trim(region) = '' OR region_measured)",
+                params![region, id],
+            )? > 0)
+        })
+    }
+
+    /// Choose how health checks talk to this proxy.
+    pub fn set_check_mode(&self, id: &str, mode: CheckMode) -> Result<Proxy> {
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE proxies SET check_mode = ?1 WHERE id = ?2",
+                params![mode.to_string(), id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProxyNotFound(id.into()));
+            }
+            Ok(())
+        })?;
+        self.get(id)
+    }
+
+    // ── Check targets ─────────────────────────────────────────────────────────
+
+    /// App-wide health-check targets (the built-in list until changed).
+    pub fn default_check_targets(&self) -> Result<Vec<String>> {
+        let stored: Option<String> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT value FROM settings WHERE key = ?1",
+                    params![CHECK_TARGETS_KEY],
+                    |r| r.get(0),
+                )
+                .optional()?)
+        })?;
+        Ok(stored
+            .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+            .filter(|targets| !targets.is_empty())
+            .unwrap_or_else(|| CHECK_URLS.iter().map(|u| u.to_string()).collect()))
+    }
+
+    /// Replace the app-wide targets; an empty list restores the built-ins.
+    pub fn set_default_check_targets(&self, targets: &[String]) -> Result<Vec<String>> {
+        let targets = normalize_check_targets(targets)?;
+        let json = serde_json::to_string(&targets)?;
+        self.db.with_conn(|conn| {
+            if targets.is_empty() {
+                conn.execute(
+                    "DELETE FROM settings WHERE key = ?1",
+                    params![CHECK_TARGETS_KEY],
+                )?;
+            } else {
+                conn.execute(
+                    "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                     ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                    params![CHECK_TARGETS_KEY, json],
+                )?;
+            }
+            Ok(())
+        })?;
+        self.default_check_targets()
+    }
+
+    /// Set per-proxy targets; an empty list falls back to the app-wide list.
+    pub fn set_check_targets(&self, id: &str, targets: &[String]) -> Result<Proxy> {
+        let json = serde_json::to_string(&normalize_check_targets(targets)?)?;
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                "UPDATE proxies SET check_targets = ?1 WHERE id = ?2",
+                params![json, id],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProxyNotFound(id.into()));
+            }
+            Ok(())
+        })?;
+        self.get(id)
+    }
+
+    /// Targets a check of `proxy` uses: its own, else the app-wide list.
+    pub fn effective_check_targets(&self, proxy: &Proxy) -> Result<Vec<String>> {
+        if proxy.check_targets.is_empty() {
+            self.default_check_targets()
+        } else {
+            Ok(proxy.check_targets.clone())
+        }
+    }
+
+    /// Check every target through the proxy (rather than stopping at the
+    /// first that works), fastest first, to pick region-local endpoints.
+    /// HTTP(S) proxies use CONNECT when that is their only check mode.
+    pub fn compare_check_targets(&self, id: &str) -> Result<Vec<TargetLatency>> {
+        let proxy = self.get(id)?;
+        let targets = self.effective_check_targets(&proxy)?;
+        let addrs = crate::dns::proxy_addrs(&proxy).map_err(ManifoldError::ProxyCheck)?;
+        let check = Self::primary_check(&proxy);
+
+        let mut results: Vec<TargetLatency> = targets
+            .iter()
+            .map(|target| {
+                match Self::check_targets(&proxy, &addrs, std::slice::from_ref(target), check) {
+                    Ok(ms) => TargetLatency {
+                        target: target.clone(),
+                        latency_ms: Some(ms),
+                        error: None,
+                    },
+                    Err(e) => TargetLatency {
+                        target: target.clone(),
+                        latency_ms: None,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect();
+        results.sort_by_key(|r| r.latency_ms.unwrap_or(u32::MAX));
+        Ok(results)
+    }
+
+    // ── Domain cooldowns ──────────────────────────────────────────────────────
+
+    /// Record a block or captcha for `domain` seen through `proxy_id` and
+    /// (re)start its cooldown.  Each hit doubles the previous cooldown, capped
+    /// at `MAX_DOMAIN_COOLDOWN_HOURS`.
+    pub fn record_domain_hit(
+        &self,
+        proxy_id: &str,
+        domain: &str,
+        hit: DomainHit,
+    ) -> Result<ProxyDomainStatus> {
+        self.get(proxy_id)?;
+        let domain = normalize_domain(domain)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("invalid domain: {domain:?}")))?;
+        let now = Utc::now();
+
+        self.db.with_conn(|conn| {
+            let (mut blocks, mut captchas): (u32, u32) = conn
+                .query_row(
+                    "SELECT blocks, captchas FROM proxy_domain_status WHERE proxy_id = ?1 AND domain = ?2",
+                    params![proxy_id, domain],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .optional()?
+                .unwrap_or((0, 0));
+            match hit {
+                DomainHit::Block => blocks += 1,
+                DomainHit::Captcha => captchas += 1,
+            }
+
+            let doublings = (blocks + captchas - 1).min(16);
+            let cooldown = (hit.base_cooldown() * (1 << doublings))
+                .min(chrono::Duration::hours(MAX_DOMAIN_COOLDOWN_HOURS));
+            let cooldown_until = now + cooldown;
+
+            conn.execute(
+                r#"INSERT INTO proxy_domain_status
+                   (proxy_id, domain, blocks, captchas, last_hit_at, cooldown_until)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                   ON CONFLICT(proxy_id, domain) DO UPDATE SET
+                       blocks = excluded.blocks,
+                       captchas = excluded.captchas,
+                       last_hit_at = excluded.last_hit_at,
+                       cooldown_until = excluded.cooldown_until"#,
+                params![
+                    proxy_id,
+                    domain,
+                    blocks,
+                    captchas,
+                    now.to_rfc3339(),
+                    cooldown_until.to_rfc3339(),
+                ],
+            )?;
+
+            Ok(ProxyDomainStatus {
+                proxy_id: proxy_id.to_string(),
+                domain,
+                blocks,
+                captchas,
+                last_hit_at: now,
+                cooldown_until,
+            })
+        })
+    }
+
+    /// Per-domain status rows, optionally for a single proxy.
+    pub fn list_domain_status(&self, proxy_id: Option<&str>) -> Result<Vec<ProxyDomainStatus>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT proxy_id, domain, blocks, captchas, last_hit_at, cooldown_until
+                   FROM proxy_domain_status
+                   WHERE ?1 IS NULL OR proxy_id = ?1
+                   ORDER BY cooldown_until DESC"#,
+            )?;
+            let rows = stmt
+                .query_map(params![proxy_id], row_to_domain_status)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })
+    }
+
+    /// Write a (proxy, domain) history recorded elsewhere, replacing any
+    /// this proxy has for the domain.
+    pub fn restore_domain_status(&self, status: &ProxyDomainStatus) -> Result<()> {
+        self.get(&status.proxy_id)?;
+        let domain = normalize_domain(&status.domain).ok_or_else(|| {
+            ManifoldError::InvalidArg(format!("invalid domain: {:?}", status.domain))
+        })?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO proxy_domain_status
+                   (proxy_id, domain, blocks, captchas, last_hit_at, cooldown_until)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                   ON CONFLICT(proxy_id, domain) DO UPDATE SET
+                       blocks = excluded.blocks,
+                       captchas = excluded.captchas,
+                       last_hit_at = excluded.last_hit_at,
+                       cooldown_until = excluded.cooldown_until"#,
+                params![
+                    status.proxy_id,
+                    domain,
+                    status.blocks,
+                    status.captchas,
+                    status.last_hit_at.to_rfc3339(),
+                    status.cooldown_until.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Forget the history for a (proxy, domain) pair, ending any cooldown.
+    pub fn clear_domain_status(&self, proxy_id: &str, domain: &str) -> Result<()> {
+        let domain = normalize_domain(domain).unwrap_or_default();
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM proxy_domain_status WHERE proxy_id = ?1 AND domain = ?2",
+                params![proxy_id, domain],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Whether `proxy_id` is cooling down for `domain` at `now`.
+    pub fn is_cooling_down(
+        &self,
+        proxy_id: &str,
+        domain: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let Some(domain) = normalize_domain(domain) else {
+            return Ok(false);
+        };
+        self.db.with_conn(|conn| {
+            let until: Option<String> = conn
+                .query_row(
+                    "SELECT cooldown_until FROM proxy_domain_status WHERE proxy_id = ?1 AND domain = ?2",
+                    params![proxy_id, domain],
+                    |r| r.get(0),
+                )
+                .optional()?;
+            Ok(until.is_some_and(|u| u > now.to_rfc3339()))
+        })
+    }
+
+    /// Pick a proxy for `domain`: `preferred` if it is not cooling down,
+    /// otherwise the best other proxy from the same pool (same country as
+    /// `preferred`), healthy and lowest-latency first.  Returns `None` when
+    /// every candidate is cooling down.
+    pub fn select_for_domain(&self, preferred: &str, domain: &str) -> Result<Option<Proxy>> {
+        let now = Utc::now();
+        let preferred = self.get(preferred)?;
+        if !self.is_cooling_down(&preferred.id, domain, now)? {
+            return Ok(Some(preferred));
+        }
+
+        let mut pool: Vec<Proxy> = self
+            .list()?
+            .into_iter()
+            .filter(|p| {
+                p.id != preferred.id
+                    && p.country == preferred.country
+                    && p.anonymity != Some(AnonymityLevel::Transparent)
+            })
+            .collect();
+        pool.sort_by_key(|p| (!p.healthy, p.latency_ms.unwrap_or(u32::MAX)));
+
+        for candidate in pool {
+            if !self.is_cooling_down(&candidate.id, domain, now)? {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
     // ── Health check ──────────────────────────────────────────────────────────
 
     /// Perform a synchronous HTTP health check against the proxy.
     ///
-    /// Connects through the proxy to `https://checkip.amazonaws.com` (or any
-    /// fast endpoint) and records latency and the returned exit IP.
+    /// Connects through the proxy to the proxy's check targets (or the
+    /// app-wide ones) until one answers, and records latency.
     ///
     /// This is intentionally synchronous so it can be called from a Tauri
     /// command via `std::thread::spawn` or `tauri::async_runtime::spawn_blocking`.
@@ -312,23 +1079,36 @@ impl ProxyRepo {
         let proxy = self.get(id)?;
         let checked_at = Utc::now();
 
-        let result = Self::do_http_check(&proxy);
+        let targets = self.effective_check_targets(&proxy)?;
+        let outcome = Self::do_http_check(&proxy, &targets);
 
-        let (healthy, latency_ms, error) = match result {
+        let (healthy, latency_ms, error) = match outcome.result {
             Ok(ms) => (true, Some(ms), None),
             Err(e) => (false, None, Some(e)),
         };
+        // Keep what earlier checks learned about modes this one didn't try
+        let supports_get = outcome.supports_get.or(proxy.supports_get);
+        let supports_connect = outcome.supports_connect.or(proxy.supports_connect);
+        let anonymity = if healthy {
+            Self::detect_anonymity(&proxy, supports_get).or(proxy.anonymity)
+        } else {
+            proxy.anonymity
+        };
 
         // Persist the result
         self.db.with_conn(|conn| {
             conn.execute(
                 r#"UPDATE proxies
-                   SET healthy = ?1, latency_ms = ?2, last_checked = ?3
-                   WHERE id = ?4"#,
+                   SET healthy = ?1, latency_ms = ?2, last_checked = ?3,
+                       supports_get = ?4, supports_connect = ?5, anonymity = ?6
+                   WHERE id = ?7"#,
                 params![
                     healthy as i64,
                     latency_ms.map(|ms| ms as i64),
                     checked_at.to_rfc3339(),
+                    supports_get,
+                    supports_connect,
+                    anonymity.map(|a| a.to_string()),
                     id,
                 ],
             )?;
@@ -340,100 +1120,410 @@ impl ProxyRepo {
             healthy,
             latency_ms,
             error,
+            supports_get,
+            supports_connect,
+            throughput_kbps: None,
+            anonymity,
             checked_at,
         })
     }
 
-    /// Check all proxies in sequence and return results.
-    pub fn check_all(&self) -> Result<Vec<ProxyHealth>> {
+    /// Overwrite the proxy's health fields with a snapshot taken elsewhere
+    /// (a proxy list import), as if that check had run here.
+    pub fn restore_health(&self, id: &str, health: &HealthSnapshot) -> Result<Proxy> {
+        self.db.with_conn(|conn| {
+            let updated = conn.execute(
+                r#"UPDATE proxies
+                   SET healthy = ?1, latency_ms = ?2, last_checked = ?3,
+                       supports_get = ?4, supports_connect = ?5,
+                       throughput_kbps = ?6, anonymity = ?7
+                   WHERE id = ?8"#,
+                params![
+                    health.healthy as i64,
+                    health.latency_ms.map(|ms| ms as i64),
+                    health.last_checked.map(|t| t.to_rfc3339()),
+                    health.supports_get,
+                    health.supports_connect,
+                    health.throughput_kbps.map(|kbps| kbps as i64),
+                    health.anonymity.map(|a| a.to_string()),
+                    id,
+                ],
+            )?;
+            if updated == 0 {
+                return Err(ManifoldError::ProxyNotFound(id.into()));
+            }
+            Ok(())
+        })?;
+        self.get(id)
+    }
+
+    /// Anonymity of a reachable proxy.  Tunnels (SOCKS5, SSH, CONNECT-only
+    /// HTTP proxies) never see request headers, so they are elite by
+    /// construction; plain-HTTP proxies are probed with a header-reflecting
+    /// endpoint.  `None` when the probe fails.
+    fn detect_anonymity(proxy: &Proxy, supports_get: Option<bool>) -> Option<AnonymityLevel> {
+        match proxy.proxy_type {
+            ProxyType::Socks5 | ProxyType::Ssh => Some(AnonymityLevel::Elite),
+            ProxyType::Http | ProxyType::Https if supports_get == Some(false) => {
+                Some(AnonymityLevel::Elite)
+            }
+            ProxyType::Http | ProxyType::Https => {
+                let headers = Self::reflected_headers(proxy)
+                    .map_err(|e| eprintln!("[proxy] anonymity probe failed: {e}"))
+                    .ok()?;
+                Some(classify_anonymity(&headers, direct_public_ip().as_deref()))
+            }
+        }
+    }
+
+    /// Headers `HEADER_ECHO_HOST` received for a plain GET through `proxy`.
+    fn reflected_headers(proxy: &Proxy) -> std::result::Result<Vec<(String, String)>, String> {
+        use std::io::{Read, Write};
+
+        let (mut stream, _) =
+            crate::dns::connect_proxy(proxy, Duration::from_secs(CHECK_TIMEOUT_SECS))?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(CHECK_TIMEOUT_SECS)))
+            .ok();
+        let req = format!(
+            "GET http://{HEADER_ECHO_HOST}/headers HTTP/1.1\r\nHost: {HEADER_ECHO_HOST}\r\nAccept: application/json\r\nConnection: close\r\n{}\r\n",
+            Self::proxy_auth_header(proxy)
+        );
+        stream
+            .write_all(req.as_bytes())
+            .map_err(|e| format!("write header probe: {e}"))?;
+        let mut response = String::new();
+        stream
+            .take(64 * 1024)
+            .read_to_string(&mut response)
+            .map_err(|e| format!("read header probe: {e}"))?;
+
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body)
+            .ok_or("truncated header probe response")?;
+        let echoed: serde_json::Value =
+            serde_json::from_str(body).map_err(|e| format!("header probe body: {e}"))?;
+        let headers = echoed
+            .get("headers")
+            .and_then(|h| h.as_object())
+            .ok_or("header probe body has no headers object")?;
+        Ok(headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+            .collect())
+    }
+
+    /// Health check plus a throughput test: download `size_kb` KB (default
+    /// `DEFAULT_SPEED_TEST_KB`) through `dial` — the proxy as the launcher
+    /// reaches it, i.e. SSH proxies resolved to their local tunnel — and
+    /// record the speed.  The download is skipped when the check fails.
+    pub fn measure_speed(
+        &self,
+        id: &str,
+        dial: &Proxy,
+        size_kb: Option<u32>,
+    ) -> Result<ProxyHealth> {
+        let size_kb = size_kb.unwrap_or(DEFAULT_SPEED_TEST_KB);
+        if size_kb == 0 || size_kb > MAX_SPEED_TEST_KB {
+            return Err(ManifoldError::InvalidArg(format!(
+                "speed test size must be 1–{MAX_SPEED_TEST_KB} KB"
+            )));
+        }
+
+        let mut health = self.check_health(id)?;
+        if !health.healthy {
+            return Ok(health);
+        }
+        match measure_throughput(dial, size_kb) {
+            Ok(kbps) => {
+                self.db.with_conn(|conn| {
+                    conn.execute(
+                        "UPDATE proxies SET throughput_kbps = ?1 WHERE id = ?2",
+                        params![kbps as i64, id],
+                    )?;
+                    Ok(())
+                })?;
+                health.throughput_kbps = Some(kbps);
+            }
+            Err(e) => health.error = Some(format!("speed test failed: {e}")),
+        }
+        Ok(health)
+    }
+
+    /// Check all proxies, `concurrency` at a time, and return results in
+    /// list order.
+    pub fn check_all(&self, concurrency: usize) -> Result<Vec<ProxyHealth>> {
         let proxies = self.list()?;
         let mut results = Vec::with_capacity(proxies.len());
-        for proxy in &proxies {
-            let health = self.check_health(&proxy.id)?;
-            results.push(health);
+        for batch in proxies.chunks(concurrency.max(1)) {
+            let checked: Vec<Result<ProxyHealth>> = std::thread::scope(|s| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|proxy| s.spawn(|| self.check_health(&proxy.id)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().expect("proxy check panicked"))
+                    .collect()
+            });
+            for health in checked {
+                results.push(health?);
+            }
         }
         Ok(results)
     }
 
-    // ── Internal HTTP check ───────────────────────────────────────────────────
+    /// Check for an IPv6 leak path: can this machine reach IPv6 targets
+    /// directly while the proxy exit cannot?  SSH proxies are skipped (their
+    /// exit is whatever the SSH server has) and report `proxy_ipv6 = true`.
+    pub fn ipv6_leak_check(&self, id: &str) -> Result<Ipv6LeakReport> {
+        let proxy = self.get(id)?;
+        let proxy_ipv6 = proxy.proxy_type == ProxyType::Ssh || Self::proxy_reaches_ipv6(&proxy);
+        let host_ipv6 = Self::host_reaches_ipv6();
+        Ok(Ipv6LeakReport {
+            proxy_id: proxy.id,
+            proxy_ipv6,
+            host_ipv6,
+            leak_risk: host_ipv6 && !proxy_ipv6,
+            checked_at: Utc::now(),
+        })
+    }
 
-    fn do_http_check(proxy: &Proxy) -> std::result::Result<u32, String> {
+    /// Direct TCP connect to the AAAA records of the IPv6-only probe host.
+    fn host_reaches_ipv6() -> bool {
         use std::net::{TcpStream, ToSocketAddrs};
 
-        // Some residential/mobile pools can block or throttle specific health hosts.
-        // Try several lightweight endpoints before declaring the proxy unhealthy.
-        const CHECK_HOSTS: [&str; 3] = ["checkip.amazonaws.com", "api.ipify.org", "example.com"];
-        const TIMEOUT_SECS: u64 = 10;
+        let Ok(addrs) = (IPV6_PROBE_HOST, 80).to_socket_addrs() else {
+            return false;
+        };
+        addrs
+            .filter(|a| a.is_ipv6())
+            .any(|a| TcpStream::connect_timeout(&a, Duration::from_secs(5)).is_ok())
+    }
+
+    /// Ask the proxy to fetch the IPv6-only probe host.
+    fn proxy_reaches_ipv6(proxy: &Proxy) -> bool {
+        use std::io::{BufRead, BufReader, Write};
+
+        let Ok((stream, _)) = crate::dns::connect_proxy(proxy, Duration::from_secs(10)) else {
+            return false;
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(10))).ok();
+
+        match proxy.proxy_type {
+            ProxyType::Socks5 => {
+                Self::socks5_check(stream, proxy, IPV6_PROBE_HOST, Instant::now()).is_ok()
+            }
+            _ => {
+                // Unlike the health check, the status matters here: a proxy
+                // without IPv6 answers 502/504 for an AAAA-only host.
+                let mut stream = stream;
+                let mut req = format!(
+                    "GET http://{IPV6_PROBE_HOST}/ HTTP/1.1\r\nHost: {IPV6_PROBE_HOST}\r\nConnection: close\r\n"
+                );
+                if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+                    use base64::Engine;
+                    let creds =
+                        base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+                    req.push_str(&format!("Proxy-Authorization: Basic {creds}\r\n"));
+                }
+                req.push_str("\r\n");
+                if stream.write_all(req.as_bytes()).is_err() {
+                    return false;
+                }
+                let mut line = String::new();
+                BufReader::new(&stream).read_line(&mut line).ok();
+                matches!(line.split_whitespace().nth(1), Some(code) if code.starts_with('2') || code.starts_with('3'))
+            }
+        }
+    }
+
+    // ── Internal HTTP check ───────────────────────────────────────────────────
+
+    fn do_http_check(proxy: &Proxy, targets: &[String]) -> CheckOutcome {
+        let addrs = match crate::dns::proxy_addrs(proxy) {
+            Ok(addrs) => addrs,
+            Err(e) => return CheckOutcome::failed(e),
+        };
+
+        match proxy.proxy_type {
+            ProxyType::Http | ProxyType::Https => {
+                let mode = proxy.check_mode;
+                let get = mode
+                    .runs_get()
+                    .then(|| Self::check_targets(proxy, &addrs, targets, Self::http_connect_check));
+                let connect = mode
+                    .runs_connect()
+                    .then(|| Self::check_targets(proxy, &addrs, targets, Self::connect_tls_check));
+
+                let result = match (&get, &connect) {
+                    (Some(Ok(a)), Some(Ok(b))) => Ok((*a).min(*b)),
+                    (Some(Ok(ms)), _) | (_, Some(Ok(ms))) => Ok(*ms),
+                    (Some(Err(e)), None) | (None, Some(Err(e))) => Err(e.clone()),
+                    (Some(Err(g)), Some(Err(c))) => Err(format!("GET: {g}; CONNECT: {c}")),
+                    (None, None) => unreachable!("every check mode runs GET or CONNECT"),
+                };
+                CheckOutcome {
+                    result,
+                    supports_get: get.map(|r| r.is_ok()),
+                    supports_connect: connect.map(|r| r.is_ok()),
+                }
+            }
+            ProxyType::Socks5 | ProxyType::Ssh => CheckOutcome {
+                result: Self::check_targets(proxy, &addrs, targets, Self::primary_check(proxy)),
+                supports_get: None,
+                supports_connect: None,
+            },
+        }
+    }
+
+    /// The single check used for `proxy` when modes aren't being compared.
+    fn primary_check(proxy: &Proxy) -> CheckFn {
+        match proxy.proxy_type {
+            ProxyType::Http | ProxyType::Https if proxy.check_mode == CheckMode::Connect => {
+                Self::connect_tls_check
+            }
+            ProxyType::Http | ProxyType::Https => Self::http_connect_check,
+            ProxyType::Socks5 => Self::socks5_check,
+            // The tunnel only exists while a profile uses it; check that
+            // the server answers with an SSH banner.
+            ProxyType::Ssh => |stream, _, _, t0| Self::ssh_banner_check(stream, t0),
+        }
+    }
+
+    /// Run `check` against each health target in turn, on a fresh
+    /// connection to the proxy, until one succeeds.
+    fn check_targets(
+        proxy: &Proxy,
+        addrs: &[std::net::SocketAddr],
+        targets: &[String],
+        check: CheckFn,
+    ) -> std::result::Result<u32, String> {
+        let mut errors = Vec::with_capacity(targets.len());
+
+        for target in targets {
+            let t0 = Instant::now();
+
+            // Open a fresh TCP connection to the proxy for each target check.
+            let (stream, _) =
+                crate::dns::happy_eyeballs_connect(addrs, Duration::from_secs(CHECK_TIMEOUT_SECS))
+                    .map_err(|e| {
+                        format!(
+                            "TCP connect to proxy failed for {}:{} ({e})",
+                            proxy.host, proxy.port
+                        )
+                    })?;
+
+            stream
+                .set_read_timeout(Some(Duration::from_secs(CHECK_TIMEOUT_SECS)))
+                .ok();
+            stream
+                .set_write_timeout(Some(Duration::from_secs(CHECK_TIMEOUT_SECS)))
+                .ok();
+
+            match check(stream, proxy, target, t0) {
+                Ok(ms) => return Ok(ms),
+                Err(e) => {
+                    // Authentication errors are deterministic; no need to retry other targets.
+                    if e.contains("authentication failed") || e.contains("407") {
+                        return Err(e);
+                    }
+                    errors.push(format!("{target}: {e}"));
+                }
+            }
+        }
+
+        Err(format!(
+            "all health checks failed ({})",
+            errors.join(" | ")
+        ))
+    }
+
+    /// `Proxy-Authorization` header line (with CRLF) when the proxy has creds.
+    fn proxy_auth_header(proxy: &Proxy) -> String {
+        match (&proxy.username, &proxy.password) {
+            (Some(user), Some(pass)) => {
+                use base64::Engine;
+                let creds =
+                    base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+                format!("Proxy-Authorization: Basic {creds}\r\n")
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Tunnel check: `CONNECT` to the target, then a full TLS handshake
+    /// with an https target or a GET of an http one through the tunnel.  A
+    /// 200 alone isn't enough — some proxies acknowledge CONNECT and then
+    /// drop or intercept the tunnel.
+    fn connect_tls_check(
+        mut stream: std::net::TcpStream,
+        proxy: &Proxy,
+        target: &str,
+        t0: Instant,
+    ) -> std::result::Result<u32, String> {
+        use std::io::{BufRead, BufReader, Write};
+
+        let target = CheckTarget::parse(target)?;
+        let authority = target.authority();
+        let req = format!(
+            "CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n{}\r\n",
+            Self::proxy_auth_header(proxy)
+        );
+        stream
+            .write_all(req.as_bytes())
+            .map_err(|e| format!("write CONNECT request: {e}"))?;
 
-        // NOTE: Many proxies are provided as hostnames (e.g. proxy.example.com),
-        // not raw IPs. We must resolve DNS before using connect_timeout.
-        let addrs = (proxy.host.as_str(), proxy.port)
-            .to_socket_addrs()
-            .map_err(|e| format!("DNS resolve failed for {}:{}: {e}", proxy.host, proxy.port))?
-            .collect::<Vec<_>>();
-        if addrs.is_empty() {
-            return Err(format!(
-                "DNS resolve returned no addresses for {}:{}",
-                proxy.host, proxy.port
-            ));
+        // The proxy sends nothing after the header block until we speak TLS,
+        // so the reader can't buffer any tunnel bytes.
+        let mut reader = BufReader::new(&stream);
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| format!("read CONNECT response: {e}"))?;
+        if line.contains("407") {
+            return Err("Proxy authentication failed (407)".into());
+        }
+        if line.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("CONNECT rejected: {}", line.trim()));
+        }
+        loop {
+            let mut h = String::new();
+            if reader.read_line(&mut h).unwrap_or(0) == 0 || h == "\r\n" {
+                break;
+            }
         }
 
-        let mut errors = Vec::with_capacity(CHECK_HOSTS.len());
-
-        for target in CHECK_HOSTS {
-            let t0 = Instant::now();
-
-            // Open a fresh TCP connection to the proxy for each target check.
-            let mut last_err: Option<String> = None;
-            let stream = addrs
-                .iter()
-                .find_map(|addr| match TcpStream::connect_timeout(addr, Duration::from_secs(TIMEOUT_SECS))
-                {
-                    Ok(s) => Some(s),
-                    Err(e) => {
-                        last_err = Some(format!("{addr}: {e}"));
-                        None
-                    }
-                })
-                .ok_or_else(|| {
-                    format!(
-                        "TCP connect to proxy failed for {}:{} ({})",
-                        proxy.host,
-                        proxy.port,
-                        last_err.unwrap_or_else(|| "no addresses".into())
-                    )
-                })?;
-
-            stream
-                .set_read_timeout(Some(Duration::from_secs(TIMEOUT_SECS)))
-                .ok();
+        if !target.tls {
+            let req = format!(
+                "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                target.path,
+                target.host_header()
+            );
             stream
-                .set_write_timeout(Some(Duration::from_secs(TIMEOUT_SECS)))
-                .ok();
-
-            let result = match proxy.proxy_type {
-                ProxyType::Http | ProxyType::Https => {
-                    Self::http_connect_check(stream, proxy, target, t0)
-                }
-                ProxyType::Socks5 => Self::socks5_check(stream, proxy, target, t0),
-            };
-
-            match result {
-                Ok(ms) => return Ok(ms),
-                Err(e) => {
-                    // Authentication errors are deterministic; no need to retry other targets.
-                    if e.contains("authentication failed") || e.contains("407") {
-                        return Err(e);
-                    }
-                    errors.push(format!("{target}: {e}"));
-                }
+                .write_all(req.as_bytes())
+                .map_err(|e| format!("write GET through tunnel: {e}"))?;
+            let mut line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut line)
+                .map_err(|e| format!("read GET through tunnel: {e}"))?;
+            if !line.starts_with("HTTP/") {
+                return Err(format!("GET through tunnel failed: {}", line.trim()));
             }
+            return Ok(t0.elapsed().as_millis() as u32);
         }
 
-        Err(format!(
-            "all health checks failed ({})",
-            errors.join(" | ")
-        ))
+        let name = rustls::pki_types::ServerName::try_from(target.host.clone())
+            .map_err(|e| format!("bad TLS server name {:?}: {e}", target.host))?;
+        let mut tls = rustls::ClientConnection::new(check_tls_config(), name)
+            .map_err(|e| format!("TLS setup: {e}"))?;
+        while tls.is_handshaking() {
+            tls.complete_io(&mut stream)
+                .map_err(|e| format!("TLS handshake through tunnel: {e}"))?;
+        }
+
+        Ok(t0.elapsed().as_millis() as u32)
     }
 
     /// HTTP proxy check using standard GET request (not CONNECT tunnel).
@@ -448,14 +1538,11 @@ impl ProxyRepo {
 
         // Use standard HTTP GET through proxy (absolute URI form)
         // This is more compatible with residential/mobile proxies than CONNECT
-        let mut req =
-            format!("GET http://{target}/ HTTP/1.1\r\nHost: {target}\r\nConnection: close\r\n");
-        if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
-            use base64::Engine;
-            let creds = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
-            req.push_str(&format!("Proxy-Authorization: Basic {creds}\r\n"));
-        }
-        req.push_str("\r\n");
+        let (url, host) = CheckTarget::parse(target)?.http_request();
+        let req = format!(
+            "GET {url} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n{}\r\n",
+            Self::proxy_auth_header(proxy)
+        );
 
         stream
             .write_all(req.as_bytes())
@@ -491,6 +1578,23 @@ impl ProxyRepo {
         Ok(t0.elapsed().as_millis() as u32)
     }
 
+    /// SSH reachability check: the server speaks first with `SSH-2.0-…`.
+    fn ssh_banner_check(
+        stream: std::net::TcpStream,
+        t0: Instant,
+    ) -> std::result::Result<u32, String> {
+        use std::io::{BufRead, BufReader};
+
+        let mut line = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut line)
+            .map_err(|e| format!("read SSH banner: {e}"))?;
+        if !line.starts_with("SSH-") {
+            return Err(format!("not an SSH server: {}", line.trim()));
+        }
+        Ok(t0.elapsed().as_millis() as u32)
+    }
+
     /// SOCKS5 handshake check (no-auth or user/pass auth).
     fn socks5_check(
         mut stream: std::net::TcpStream,
@@ -545,11 +1649,12 @@ impl ProxyRepo {
             m => return Err(format!("SOCKS5 unexpected auth method: 0x{m:02x}")),
         }
 
-        // CONNECT request to target:80
+        // CONNECT request to the target's host and port
+        let target = CheckTarget::parse(target)?;
         let mut req = vec![0x05u8, 0x01, 0x00, 0x03];
-        req.push(target.len() as u8);
-        req.extend_from_slice(target.as_bytes());
-        req.extend_from_slice(&80u16.to_be_bytes());
+        req.push(target.host.len() as u8);
+        req.extend_from_slice(target.host.as_bytes());
+        req.extend_from_slice(&target.port.to_be_bytes());
         stream
             .write_all(&req)
             .map_err(|e| format!("socks5 connect req: {e}"))?;
@@ -607,6 +1712,10 @@ impl ProxyRepo {
             )),
             password: Some(password.to_string()),
             country: None,
+            region: None,
+            ssh_key_path: None,
+            tags: None,
+            notes: None,
         }
     }
 
@@ -627,6 +1736,10 @@ impl ProxyRepo {
             username: Some(format!("lum-customer-{customer}-zone-{zone}")),
             password: Some(password.to_string()),
             country: None,
+            region: None,
+            ssh_key_path: None,
+            tags: None,
+            notes: None,
         }
     }
 
@@ -643,6 +1756,17 @@ impl ProxyRepo {
             username: raw.username,
             password: raw.password,
             country: raw.country,
+            region: raw.region,
+            ssh_key_path: raw.ssh_key_path,
+            pinned_ip: raw.pinned_ip,
+            check_mode: raw.check_mode.parse().unwrap_or_default(),
+            supports_get: raw.supports_get,
+            supports_connect: raw.supports_connect,
+            check_targets: serde_json::from_str(&raw.check_targets).unwrap_or_default(),
+            throughput_kbps: raw.throughput_kbps,
+            anonymity: raw.anonymity.and_then(|a| a.parse().ok()),
+            tags: serde_json::from_str(&raw.tags).unwrap_or_default(),
+            notes: raw.notes,
             healthy: raw.healthy,
             latency_ms: raw.latency_ms,
             last_checked: raw.last_checked,
@@ -650,6 +1774,269 @@ impl ProxyRepo {
     }
 }
 
+// ── Check helpers ─────────────────────────────────────────────────────────────
+
+/// Some residential/mobile pools can block or throttle specific health hosts.
+/// Try several lightweight endpoints before declaring the proxy unhealthy.
+/// Used until the app-wide list is changed.
+pub const CHECK_URLS: [&str; 3] = [
+    "http://checkip.amazonaws.com/",
+    "http://api.ipify.org/",
+    "http://example.com/",
+];
+const CHECK_TIMEOUT_SECS: u64 = 10;
+/// `settings` key holding the app-wide check targets (JSON array).
+const CHECK_TARGETS_KEY: &str = "proxy_check_targets";
+
+/// Endpoint that echoes request headers as `{"headers": {...}}`.
+const HEADER_ECHO_HOST: &str = "httpbin.org";
+/// Plain-text "what is my IP" endpoint, fetched without a proxy.
+const DIRECT_IP_HOST: &str = "api.ipify.org";
+/// How long the direct public IP is reused between checks.
+const DIRECT_IP_TTL: Duration = Duration::from_secs(300);
+
+/// This machine's public IP as seen without a proxy (cached briefly, since
+/// `check_all` would otherwise fetch it once per proxy).
+fn direct_public_ip() -> Option<String> {
+    use std::io::{Read, Write};
+
+    static CACHE: std::sync::Mutex<Option<(Instant, String)>> = std::sync::Mutex::new(None);
+    if let Some((at, ip)) = CACHE.lock().unwrap().as_ref() {
+        if at.elapsed() < DIRECT_IP_TTL {
+            return Some(ip.clone());
+        }
+    }
+
+    let addrs =
+        crate::dns::resolve_with_timeout(DIRECT_IP_HOST, 80, crate::dns::DNS_TIMEOUT).ok()?;
+    let (mut stream, _) = crate::dns::happy_eyeballs_connect(
+        &crate::dns::interleave_families(&addrs),
+        Duration::from_secs(CHECK_TIMEOUT_SECS),
+    )
+    .ok()?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(CHECK_TIMEOUT_SECS)))
+        .ok();
+    write!(
+        stream,
+        "GET / HTTP/1.1\r\nHost: {DIRECT_IP_HOST}\r\nConnection: close\r\n\r\n"
+    )
+    .ok()?;
+    let mut response = String::new();
+    stream.take(4096).read_to_string(&mut response).ok()?;
+    let ip = response.split_once("\r\n\r\n")?.1.trim();
+    let ip = ip.parse::<std::net::IpAddr>().ok()?.to_string();
+
+    *CACHE.lock().unwrap() = Some((Instant::now(), ip.clone()));
+    Some(ip)
+}
+
+/// Host serving arbitrary-size downloads for throughput tests.
+const SPEED_TEST_HOST: &str = "speed.cloudflare.com";
+pub const DEFAULT_SPEED_TEST_KB: u32 = 1024;
+pub const MAX_SPEED_TEST_KB: u32 = 50 * 1024;
+
+/// Download `size_kb` KB over HTTPS through `proxy` and return KB/s.  Timing
+/// starts once the tunnel and TLS session are up, so it reflects transfer
+/// speed rather than connection latency.
+pub fn measure_throughput(proxy: &Proxy, size_kb: u32) -> std::result::Result<u32, String> {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let tcp = crate::chain::open_chain(std::slice::from_ref(proxy), SPEED_TEST_HOST, 443)
+        .map_err(|e| format!("tunnel to {SPEED_TEST_HOST}: {e}"))?;
+    tcp.set_read_timeout(Some(Duration::from_secs(30))).ok();
+    let name =
+        rustls::pki_types::ServerName::try_from(SPEED_TEST_HOST).map_err(|e| e.to_string())?;
+    let conn =
+        rustls::ClientConnection::new(check_tls_config(), name).map_err(|e| e.to_string())?;
+    let mut tls = rustls::StreamOwned::new(conn, tcp);
+
+    let bytes = size_kb as u64 * 1024;
+    write!(
+        tls,
+        "GET /__down?bytes={bytes} HTTP/1.1\r\nHost: {SPEED_TEST_HOST}\r\nConnection: close\r\n\r\n"
+    )
+    .map_err(|e| format!("TLS request: {e}"))?;
+    let t0 = Instant::now();
+
+    let mut reader = BufReader::new(tls);
+    let mut status = String::new();
+    reader
+        .read_line(&mut status)
+        .map_err(|e| format!("read response: {e}"))?;
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("download rejected: {}", status.trim()));
+    }
+    loop {
+        let mut h = String::new();
+        if reader.read_line(&mut h).unwrap_or(0) == 0 || h == "\r\n" {
+            break;
+        }
+    }
+
+    // Servers often skip close_notify; whatever arrived before the
+    // connection ended still counts.
+    let mut body = reader.take(bytes);
+    let mut received = 0u64;
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        match body.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => received += n as u64,
+            Err(e) if received > 0 && e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("download: {e}")),
+        }
+    }
+    if received < bytes {
+        return Err(format!("download ended after {received} of {bytes} bytes"));
+    }
+
+    let secs = t0.elapsed().as_secs_f64().max(0.001);
+    Ok(((received as f64 / 1024.0) / secs).round() as u32)
+}
+
+/// One check of a proxy against one target, returning latency in ms.
+type CheckFn = fn(std::net::TcpStream, &Proxy, &str, Instant) -> std::result::Result<u32, String>;
+
+/// Reduce check targets to canonical http(s) URLs, dropping duplicates.
+/// Bare hosts become `http://host/`; scheme, port, path and query are kept.
+pub fn normalize_check_targets(targets: &[String]) -> Result<Vec<String>> {
+    let mut out: Vec<String> = Vec::with_capacity(targets.len());
+    for raw in targets {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        let url = check_url(raw)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("invalid check target: {raw:?}")))?;
+        if !out.contains(&url) {
+            out.push(url);
+        }
+    }
+    Ok(out)
+}
+
+/// `raw` as a canonical http(s) URL without fragment, if it is one.
+fn check_url(raw: &str) -> Option<String> {
+    let with_scheme = if raw.contains("://") {
+        raw.to_string()
+    } else {
+        format!("http://{raw}")
+    };
+    let mut url = url::Url::parse(&with_scheme).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none_or(str::is_empty) {
+        return None;
+    }
+    url.set_fragment(None);
+    Some(url.to_string())
+}
+
+/// A health-check target URL, split into what the checks send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckTarget {
+    /// Host name or IP literal, without IPv6 brackets.
+    pub host: String,
+    pub port: u16,
+    /// Path and query, at least `/`.
+    pub path: String,
+    pub tls: bool,
+}
+
+impl CheckTarget {
+    /// Parse a stored target.  Lists saved before targets were URLs hold
+    /// bare hosts, which mean `http://host/`.
+    pub(crate) fn parse(raw: &str) -> std::result::Result<Self, String> {
+        let url = check_url(raw.trim())
+            .and_then(|u| url::Url::parse(&u).ok())
+            .ok_or_else(|| format!("invalid check target: {raw:?}"))?;
+        let host = match url.host() {
+            Some(url::Host::Ipv6(ip)) => ip.to_string(),
+            Some(host) => host.to_string(),
+            None => return Err(format!("invalid check target: {raw:?}")),
+        };
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        Ok(Self {
+            host,
+            port: url.port_or_known_default().unwrap_or(80),
+            path,
+            tls: url.scheme() == "https",
+        })
+    }
+
+    /// `host:port`, bracketing IPv6 literals.
+    pub(crate) fn authority(&self) -> String {
+        match self.host.parse::<std::net::IpAddr>() {
+            Ok(std::net::IpAddr::V6(_)) => format!("[{}]:{}", self.host, self.port),
+            _ => format!("{}:{}", self.host, self.port),
+        }
+    }
+
+    /// `Host` header value: the authority, without the scheme's default port.
+    pub(crate) fn host_header(&self) -> String {
+        let authority = self.authority();
+        let default_port = if self.tls { ":443" } else { ":80" };
+        match authority.strip_suffix(default_port) {
+            Some(host) => host.to_string(),
+            None => authority,
+        }
+    }
+
+    /// The target as plain http, which is all a proxied GET can fetch: the
+    /// URL itself for http targets, the same host and path on port 80 for
+    /// https ones.  Returns the URL and its `Host` header.
+    fn http_request(&self) -> (String, String) {
+        let host = if self.tls {
+            Self {
+                port: 80,
+                tls: false,
+                ..self.clone()
+            }
+            .host_header()
+        } else {
+            self.host_header()
+        };
+        (format!("http://{host}{}", self.path), host)
+    }
+}
+
+/// Result of one health check plus what it learned about check modes.
+struct CheckOutcome {
+    result: std::result::Result<u32, String>,
+    supports_get: Option<bool>,
+    supports_connect: Option<bool>,
+}
+
+impl CheckOutcome {
+    fn failed(error: String) -> Self {
+        Self {
+            result: Err(error),
+            supports_get: None,
+            supports_connect: None,
+        }
+    }
+}
+
+/// Client config for handshakes through CONNECT tunnels (webpki roots).
+pub(crate) fn check_tls_config() -> std::sync::Arc<rustls::ClientConfig> {
+    static CONFIG: std::sync::OnceLock<std::sync::Arc<rustls::ClientConfig>> =
+        std::sync::OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            std::sync::Arc::new(
+                rustls::ClientConfig::builder()
+                    .with_root_certificates(roots)
+                    .with_no_client_auth(),
+            )
+        })
+        .clone()
+}
+
 // ── Row types ─────────────────────────────────────────────────────────────────
 
 /// Intermediate struct holding raw (possibly encrypted) DB values.
@@ -662,6 +2049,17 @@ struct ProxyRaw {
     username: Option<String>,
     password: Option<String>, // encrypted or plaintext depending on build
     country: Option<String>,
+    region: Option<String>,
+    ssh_key_path: Option<String>,
+    pinned_ip: Option<String>,
+    check_mode: String,
+    supports_get: Option<bool>,
+    supports_connect: Option<bool>,
+    check_targets: String,
+    throughput_kbps: Option<u32>,
+    anonymity: Option<String>,
+    tags: String,
+    notes: String,
     healthy: bool,
     latency_ms: Option<u32>,
     last_checked: Option<DateTime<Utc>>,
@@ -679,6 +2077,17 @@ fn row_to_proxy_raw(row: &rusqlite::Row<'_>) -> rusqlite::Result<ProxyRaw> {
     let healthy: i64 = row.get(8)?;
     let latency_ms: Option<i64> = row.get(9)?;
     let last_checked: Option<String> = row.get(10)?;
+    let ssh_key_path: Option<String> = row.get(11)?;
+    let pinned_ip: Option<String> = row.get(12)?;
+    let check_mode: String = row.get(13)?;
+    let supports_get: Option<i64> = row.get(14)?;
+    let supports_connect: Option<i64> = row.get(15)?;
+    let check_targets: String = row.get(16)?;
+    let throughput_kbps: Option<i64> = row.get(17)?;
+    let anonymity: Option<String> = row.get(18)?;
+    let tags: String = row.get(19)?;
+    let notes: String = row.get(20)?;
+    let region: Option<String> = row.get(21)?;
 
     let last_checked = last_checked.and_then(|s| {
         DateTime::parse_from_rfc3339(&s)
@@ -695,12 +2104,66 @@ fn row_to_proxy_raw(row: &rusqlite::Row<'_>) -> rusqlite::Result<ProxyRaw> {
         username,
         password,
         country,
+        region,
+        ssh_key_path,
+        pinned_ip,
+        check_mode,
+        supports_get: supports_get.map(|v| v != 0),
+        supports_connect: supports_connect.map(|v| v != 0),
+        check_targets,
+        throughput_kbps: throughput_kbps.map(|v| v as u32),
+        anonymity,
+        tags,
+        notes,
         healthy: healthy != 0,
         latency_ms: latency_ms.map(|ms| ms as u32),
         last_checked,
     })
 }
 
+/// `s` with the `LIKE` wildcards (and the `\` escaping them) taken literally.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn row_to_domain_status(row: &rusqlite::Row<'_>) -> rusqlite::Result<ProxyDomainStatus> {
+    let parse = |s: String| {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_default()
+    };
+    Ok(ProxyDomainStatus {
+        proxy_id: row.get(0)?,
+        domain: row.get(1)?,
+        blocks: row.get(2)?,
+        captchas: row.get(3)?,
+        last_hit_at: parse(row.get(4)?),
+        cooldown_until: parse(row.get(5)?),
+    })
+}
+
+/// Reduce a URL or host to the registrable-ish domain used as cooldown key:
+/// lowercase host without scheme, port, path or leading `www.`.
+pub fn normalize_domain(input: &str) -> Option<String> {
+    let input = input.trim();
+    let host = match url::Url::parse(input) {
+        Ok(u) if u.host_str().is_some() => u.host_str()?.to_string(),
+        _ => input
+            .split(['/', '?', '#'])
+            .next()?
+            .rsplit('@')
+            .next()?
+            .split(':')
+            .next()?
+            .to_string(),
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -721,6 +2184,10 @@ mod tests {
             username: None,
             password: None,
             country: None,
+            region: None,
+            ssh_key_path: None,
+            tags: None,
+            notes: None,
         }
     }
 
@@ -735,7 +2202,7 @@ mod tests {
 
     #[test]
     fn proxy_type_from_str_roundtrip() {
-        for s in &["http", "https", "socks5"] {
+        for s in &["http", "https", "socks5", "ssh"] {
             let pt: ProxyType = s.parse().unwrap();
             assert_eq!(pt.to_string(), *s);
         }
@@ -760,6 +2227,17 @@ mod tests {
             username: None,
             password: None,
             country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -778,6 +2256,17 @@ mod tests {
             username: Some("user".into()),
             password: Some("pass".into()),
             country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -799,6 +2288,17 @@ mod tests {
             username: Some("user".into()),
             password: None,
             country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -820,6 +2320,17 @@ mod tests {
             username: None,
             password: None,
             country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -838,6 +2349,17 @@ mod tests {
             username: Some("user".into()),
             password: Some("secret".into()),
             country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: CheckMode::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
             healthy: false,
             latency_ms: None,
             last_checked: None,
@@ -913,6 +2435,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap_err();
         assert!(matches!(err, ManifoldError::InvalidArg(_)));
@@ -930,6 +2456,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap_err();
         assert!(matches!(err, ManifoldError::InvalidArg(_)));
@@ -947,6 +2477,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap_err();
         assert!(matches!(err, ManifoldError::InvalidArg(_)));
@@ -1018,6 +2552,58 @@ mod tests {
         assert_eq!(names, vec!["Alpha", "Mango", "Zeta"]);
     }
 
+    #[test]
+    fn tags_and_notes_filter_the_list() {
+        let repo = make_repo();
+        let mobile = repo
+            .add(AddProxyRequest {
+                tags: Some(vec![
+                    " mobile 4G ".into(),
+                    "client A only".into(),
+                    "".into(),
+                ]),
+                notes: Some("Burned on 100%_sure.example".into()),
+                ..default_add("Mobile")
+            })
+            .unwrap();
+        assert_eq!(mobile.tags, vec!["mobile 4G", "client A only"]);
+        repo.add(default_add("Datacenter")).unwrap();
+
+        let names = |filter: ProxyFilter| -> Vec<String> {
+            repo.list_page_filtered(&Page::default(), &filter)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+        let tag = |t: &str| ProxyFilter {
+            tag: Some(t.into()),
+            search: None,
+        };
+        let search = |q: &str| ProxyFilter {
+            tag: None,
+            search: Some(q.into()),
+        };
+        assert_eq!(names(tag("mobile 4G")), vec!["Mobile"]);
+        assert!(names(tag("mobile")).is_empty());
+        assert_eq!(names(search("BURNED")), vec!["Mobile"]);
+        assert_eq!(names(search("100%_")), vec!["Mobile"]);
+        assert!(names(search("1000")).is_empty());
+        assert_eq!(names(ProxyFilter::default()).len(), 2);
+
+        let updated = repo
+            .update(
+                &mobile.id,
+                UpdateProxyRequest {
+                    tags: Some(vec!["burned on amazon".into()]),
+                    ..UpdateProxyRequest::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.notes, "Burned on 100%_sure.example");
+        assert_eq!(repo.get(&mobile.id).unwrap().tags, vec!["burned on amazon"]);
+    }
+
     #[test]
     fn update_name_and_host() {
         let repo = make_repo();
@@ -1032,6 +2618,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             },
         )
         .unwrap();
@@ -1054,6 +2644,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             },
         )
         .unwrap();
@@ -1075,6 +2669,10 @@ mod tests {
                     username: None,
                     password: None,
                     country: None,
+                    region: None,
+                    ssh_key_path: None,
+                    tags: None,
+                    notes: None,
                 },
             )
             .unwrap_err();
@@ -1122,6 +2720,10 @@ mod tests {
                 username: Some("user1".into()),
                 password: Some("pw1".into()),
                 country: Some("US".into()),
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap();
         assert_eq!(px.username, Some("user1".into()));
@@ -1130,6 +2732,37 @@ mod tests {
         assert_eq!(px.password, Some("pw1".into()));
     }
 
+    #[test]
+    fn measured_region_follows_the_exit_until_the_user_sets_one() {
+        let repo = make_repo();
+        let px = repo.add(default_add("Utah")).unwrap();
+        assert_eq!(repo.get(&px.id).unwrap().region, None);
+
+        let region = |repo: &ProxyRepo| repo.get(&px.id).unwrap().region;
+        assert!(repo.fill_region(&px.id, "Provo, UT").unwrap());
+        assert!(!repo.fill_region(&px.id, "Provo, UT").unwrap());
+        // The exit moved: a measured region is replaced
+        assert!(repo.fill_region(&px.id, "CO").unwrap());
+        assert_eq!(region(&repo).as_deref(), Some("CO"));
+
+        let req = UpdateProxyRequest {
+            region: Some("Salt Lake City".into()),
+            ..Default::default()
+        };
+        repo.update(&px.id, req).unwrap();
+        assert!(!repo.fill_region(&px.id, "CO").unwrap());
+        let listed = repo.list().unwrap();
+        assert_eq!(listed[0].region.as_deref(), Some("Salt Lake City"));
+
+        // Other edits keep the region the user's
+        let req = UpdateProxyRequest {
+            notes: Some("sticky".into()),
+            ..Default::default()
+        };
+        repo.update(&px.id, req).unwrap();
+        assert!(!repo.fill_region(&px.id, "CO").unwrap());
+    }
+
     #[test]
     fn password_survives_update_roundtrip() {
         let repo = make_repo();
@@ -1142,6 +2775,10 @@ mod tests {
                 username: Some("u".into()),
                 password: Some("original_pw".into()),
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap();
         // Update without touching password
@@ -1155,6 +2792,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             },
         )
         .unwrap();
@@ -1176,6 +2817,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: Some("DE".into()),
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             })
             .unwrap();
         assert_eq!(px.proxy_type, ProxyType::Socks5);
@@ -1201,6 +2846,10 @@ mod tests {
                 username: None,
                 password: None,
                 country: None,
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
             },
         )
         .unwrap();
@@ -1208,4 +2857,364 @@ mod tests {
         let b_check = repo.get(&b.id).unwrap();
         assert_eq!(b_check.name, "ProxyB", "updating A must not affect B");
     }
+    // ── Check modes ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn check_mode_round_trips_and_persists() {
+        for mode in [CheckMode::Get, CheckMode::Connect, CheckMode::Both] {
+            assert_eq!(mode.to_string().parse::<CheckMode>().unwrap(), mode);
+        }
+        assert!("head".parse::<CheckMode>().is_err());
+
+        let repo = make_repo();
+        let proxy = repo.add(default_add("modes")).unwrap();
+        assert_eq!(proxy.check_mode, CheckMode::Get);
+        let updated = repo.set_check_mode(&proxy.id, CheckMode::Both).unwrap();
+        assert_eq!(updated.check_mode, CheckMode::Both);
+        assert!(updated.supports_connect.is_none());
+        assert!(repo.set_check_mode("missing", CheckMode::Get).is_err());
+    }
+
+    #[test]
+    fn connect_check_rejects_refused_tunnel() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(&conn).read_line(&mut line).unwrap();
+            conn.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n").unwrap();
+            line
+        });
+
+        let proxy = Proxy {
+            host: "127.0.0.1".into(),
+            port: addr.port(),
+            ..repo_proxy()
+        };
+        let err = ProxyRepo::connect_tls_check(
+            TcpStream::connect(addr).unwrap(),
+            &proxy,
+            "example.com",
+            Instant::now(),
+        )
+        .unwrap_err();
+        assert!(err.contains("CONNECT rejected"), "{err}");
+        assert_eq!(
+            server.join().unwrap(),
+            "CONNECT example.com:443 HTTP/1.1\r\n"
+        );
+    }
+
+    // ── Check targets ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn check_targets_normalize_to_urls() {
+        let targets = normalize_check_targets(&[
+            "https://IPInfo.io/ip".into(),
+            "ipinfo.io".into(),
+            " http://ifconfig.me:8080/all?fmt=json#top ".into(),
+            "https://ipinfo.io:443/ip".into(),
+            "".into(),
+        ])
+        .unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                "https://ipinfo.io/ip",
+                "http://ipinfo.io/",
+                "http://ifconfig.me:8080/all?fmt=json",
+            ]
+        );
+        assert!(normalize_check_targets(&["http://".into()]).is_err());
+        assert!(normalize_check_targets(&["ftp://files.example.com".into()]).is_err());
+    }
+
+    #[test]
+    fn check_targets_keep_port_path_and_scheme() {
+        let t = CheckTarget::parse("https://eu.example.net:8443/ip?v=4").unwrap();
+        assert_eq!(
+            t,
+            CheckTarget {
+                host: "eu.example.net".into(),
+                port: 8443,
+                path: "/ip?v=4".into(),
+                tls: true,
+            }
+        );
+        assert_eq!(t.authority(), "eu.example.net:8443");
+        assert_eq!(t.host_header(), "eu.example.net:8443");
+        assert_eq!(
+            t.http_request(),
+            (
+                "http://eu.example.net/ip?v=4".into(),
+                "eu.example.net".into()
+            )
+        );
+
+        // Lists stored before targets were URLs hold bare hosts
+        let bare = CheckTarget::parse("checkip.amazonaws.com").unwrap();
+        assert_eq!((bare.port, bare.path.as_str(), bare.tls), (80, "/", false));
+
+        let v6 = CheckTarget::parse("http://[2001:db8::1]:8080/").unwrap();
+        assert_eq!(v6.host, "2001:db8::1");
+        assert_eq!(v6.authority(), "[2001:db8::1]:8080");
+        assert_eq!(v6.http_request().0, "http://[2001:db8::1]:8080/");
+    }
+
+    #[test]
+    fn proxy_targets_override_app_targets() {
+        let repo = make_repo();
+        assert_eq!(repo.default_check_targets().unwrap(), CHECK_URLS.to_vec());
+
+        repo.set_default_check_targets(&["https://eu.example.net/ip".into()])
+            .unwrap();
+        let proxy = repo.add(default_add("targets")).unwrap();
+        assert_eq!(
+            repo.effective_check_targets(&proxy).unwrap(),
+            vec!["https://eu.example.net/ip"]
+        );
+
+        let proxy = repo
+            .set_check_targets(&proxy.id, &["local.example.org".into()])
+            .unwrap();
+        assert_eq!(
+            repo.effective_check_targets(&proxy).unwrap(),
+            vec!["http://local.example.org/"]
+        );
+
+        repo.set_default_check_targets(&[]).unwrap();
+        assert_eq!(repo.default_check_targets().unwrap(), CHECK_URLS.to_vec());
+    }
+
+    // ── Anonymity ─────────────────────────────────────────────────────────────
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn anonymity_levels_from_reflected_headers() {
+        let real = Some("203.0.113.9");
+        let clean = headers(&[("Host", "httpbin.org"), ("Accept", "application/json")]);
+        assert_eq!(classify_anonymity(&clean, real), AnonymityLevel::Elite);
+
+        let via = headers(&[("Via", "1.1 squid"), ("X-Forwarded-For", "198.51.100.4")]);
+        assert_eq!(classify_anonymity(&via, real), AnonymityLevel::Anonymous);
+
+        let leaky = headers(&[("x-forwarded-for", "203.0.113.9, 198.51.100.4")]);
+        assert_eq!(
+            classify_anonymity(&leaky, real),
+            AnonymityLevel::Transparent
+        );
+        // Without the real IP a forwarded-for header can't prove a leak
+        assert_eq!(classify_anonymity(&leaky, None), AnonymityLevel::Anonymous);
+
+        // Addresses are compared whole, not as substrings
+        let lookalike = headers(&[("X-Forwarded-For", "203.0.113.99, 1203.0.113.9")]);
+        assert_eq!(
+            classify_anonymity(&lookalike, real),
+            AnonymityLevel::Anonymous
+        );
+        let forwarded = headers(&[("Forwarded", "for=\"203.0.113.9:4711\";proto=https")]);
+        assert_eq!(
+            classify_anonymity(&forwarded, real),
+            AnonymityLevel::Transparent
+        );
+        let v6 = headers(&[("Forwarded", "for=\"[2001:DB8::1]:4711\", for=198.51.100.4")]);
+        assert_eq!(
+            classify_anonymity(&v6, Some("2001:db8::1")),
+            AnonymityLevel::Transparent
+        );
+        let via = headers(&[("Via", "1.1 203.0.113.9 (squid/5.7)")]);
+        assert_eq!(classify_anonymity(&via, real), AnonymityLevel::Transparent);
+
+        for level in [
+            AnonymityLevel::Transparent,
+            AnonymityLevel::Anonymous,
+            AnonymityLevel::Elite,
+        ] {
+            assert_eq!(level.to_string().parse::<AnonymityLevel>().unwrap(), level);
+        }
+    }
+
+    // ── Throughput ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn speed_test_validates_size_and_skips_dead_proxies() {
+        let repo = make_repo();
+        // A port we just released is (almost certainly) refusing connections
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut req = default_add("dead");
+        req.host = "127.0.0.1".into();
+        req.port = port;
+        let proxy = repo.add(req).unwrap();
+
+        assert!(repo.measure_speed(&proxy.id, &proxy, Some(0)).is_err());
+        assert!(repo
+            .measure_speed(&proxy.id, &proxy, Some(MAX_SPEED_TEST_KB + 1))
+            .is_err());
+
+        let health = repo.measure_speed(&proxy.id, &proxy, None).unwrap();
+        assert!(!health.healthy);
+        assert!(health.throughput_kbps.is_none());
+        assert!(repo.get(&proxy.id).unwrap().throughput_kbps.is_none());
+    }
+
+    fn repo_proxy() -> Proxy {
+        make_repo().add(default_add("tunnel")).unwrap()
+    }
+
+    // ── IP pinning ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn pinned_ip_is_dialled_and_clearable() {
+        let repo = make_repo();
+        let mut req = default_add("pinned");
+        req.host = "proxy.example.com".into();
+        let proxy = repo.add(req).unwrap();
+
+        assert_eq!(
+            repo.pin_ip(&proxy.id, Some("[2001:db8::7]")).unwrap(),
+            "2001:db8::7"
+        );
+        let pinned = repo.get(&proxy.id).unwrap();
+        assert_eq!(pinned.pinned_ip.as_deref(), Some("2001:db8::7"));
+        assert_eq!(pinned.to_playwright_server(), "http://[2001:db8::7]:8080");
+        assert!(repo.pin_ip(&proxy.id, Some("nope")).is_err());
+
+        repo.unpin_ip(&proxy.id).unwrap();
+        assert!(repo.get(&proxy.id).unwrap().pinned_ip.is_none());
+    }
+
+    // ── IPv6 ──────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn normalize_host_accepts_ipv6_literals() {
+        assert_eq!(normalize_host(" [2001:db8::1] ").unwrap(), "2001:db8::1");
+        assert_eq!(normalize_host("2001:db8::1").unwrap(), "2001:db8::1");
+        assert_eq!(
+            normalize_host("proxy.example.com").unwrap(),
+            "proxy.example.com"
+        );
+        assert!(normalize_host("proxy.example.com:8080").is_err());
+        assert!(normalize_host("[not-an-ip]").is_err());
+    }
+
+    #[test]
+    fn ipv6_proxy_urls_are_bracketed() {
+        let repo = make_repo();
+        let mut req = default_add("v6");
+        req.host = "[2001:db8::1]".into();
+        let proxy = repo.add(req).unwrap();
+        assert_eq!(repo.get(&proxy.id).unwrap().host, "2001:db8::1");
+        assert_eq!(proxy.to_playwright_server(), "http://[2001:db8::1]:8080");
+        assert_eq!(proxy.to_playwright_url(), "http://[2001:db8::1]:8080");
+    }
+
+    // ── Domain cooldowns ──────────────────────────────────────────────────────
+
+    #[test]
+    fn normalize_domain_strips_scheme_path_and_www() {
+        assert_eq!(
+            normalize_domain("https://www.Example.com:8443/login?x=1").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            normalize_domain("shop.example.com/cart").as_deref(),
+            Some("shop.example.com")
+        );
+        assert_eq!(normalize_domain("  "), None);
+    }
+
+    #[test]
+    fn domain_hits_escalate_cooldown() {
+        let repo = make_repo();
+        let px = repo.add(default_add("P")).unwrap();
+
+        let first = repo
+            .record_domain_hit(&px.id, "https://example.com/", DomainHit::Captcha)
+            .unwrap();
+        let second = repo
+            .record_domain_hit(&px.id, "example.com", DomainHit::Block)
+            .unwrap();
+        assert_eq!((second.blocks, second.captchas), (1, 1));
+        assert!(
+            second.cooldown_until - second.last_hit_at > first.cooldown_until - first.last_hit_at
+        );
+
+        assert!(repo
+            .is_cooling_down(&px.id, "www.example.com", Utc::now())
+            .unwrap());
+        assert!(!repo
+            .is_cooling_down(&px.id, "other.com", Utc::now())
+            .unwrap());
+
+        repo.clear_domain_status(&px.id, "example.com").unwrap();
+        assert!(!repo
+            .is_cooling_down(&px.id, "example.com", Utc::now())
+            .unwrap());
+    }
+
+    #[test]
+    fn select_for_domain_skips_cooling_proxies_in_pool() {
+        let repo = make_repo();
+        let add = |name: &str, country: &str| {
+            let mut req = default_add(name);
+            req.country = Some(country.into());
+            repo.add(req).unwrap()
+        };
+        let a = add("A", "US");
+        let b = add("B", "US");
+        let _c = add("C", "DE");
+
+        let picked = repo
+            .select_for_domain(&a.id, "example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(picked.id, a.id, "preferred proxy is used when not cooling");
+
+        repo.record_domain_hit(&a.id, "example.com", DomainHit::Block)
+            .unwrap();
+        let picked = repo
+            .select_for_domain(&a.id, "example.com")
+            .unwrap()
+            .unwrap();
+        assert_eq!(picked.id, b.id, "falls back within the same country pool");
+
+        repo.record_domain_hit(&b.id, "example.com", DomainHit::Captcha)
+            .unwrap();
+        assert!(repo
+            .select_for_domain(&a.id, "example.com")
+            .unwrap()
+            .is_none());
+        // Other domains are unaffected
+        assert_eq!(
+            repo.select_for_domain(&a.id, "other.com")
+                .unwrap()
+                .unwrap()
+                .id,
+            a.id
+        );
+    }
+
+    #[test]
+    fn deleting_proxy_drops_domain_status() {
+        let repo = make_repo();
+        let px = repo.add(default_add("P")).unwrap();
+        repo.record_domain_hit(&px.id, "example.com", DomainHit::Block)
+            .unwrap();
+        repo.delete(&px.id).unwrap();
+        assert!(repo.list_domain_status(None).unwrap().is_empty());
+    }
 }
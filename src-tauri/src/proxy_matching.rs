@@ -0,0 +1,345 @@
+// ── Manifold proxy matching ───────────────────────────────────────────────────
+//
+// Picks a proxy for a profile that has none.  Every usable proxy is scored
+// on four things and the highest total wins:
+//
+//   * country fit (half the score): how much of the fingerprint's geo
+//     consistency survives the proxy's country — locale, timezone, screen
+//     and DPR as GeoValidator judges them.  A proxy with no country is
+//     neither a match nor a mismatch.
+//   * latency from the last health check; unchecked proxies score low.
+//   * load: profiles already routed through the proxy.  Sharing an exit
+//     links the profiles on it, so emptier proxies are preferred.
+//   * cooldowns: domains currently blocking or captcha-ing the proxy.
+//
+// Proxies whose last check failed and transparent proxies (refused at
+// launch) are never candidates.  Auto-assignment counts each assignment
+// towards the load of the next profile, so a batch spreads over the pool
+// instead of piling onto the single best exit.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::fingerprint::Fingerprint;
+use crate::geo_validator::GeoValidator;
+use crate::profile::{Profile, ProfileFilter, ProfileRepo, UpdateProfileRequest};
+use crate::proxy::{AnonymityLevel, Proxy, ProxyRepo};
+
+const COUNTRY_WEIGHT: f64 = 0.5;
+const LATENCY_WEIGHT: f64 = 0.2;
+const LOAD_WEIGHT: f64 = 0.2;
+const COOLDOWN_WEIGHT: f64 = 0.1;
+
+/// Latency at and above which a proxy gets no latency score.
+const SLOW_LATENCY_MS: u32 = 2_000;
+
+/// Country fit of a proxy without a country.
+const UNKNOWN_COUNTRY_FIT: f64 = 0.5;
+
+/// Latency score of a proxy never checked.
+const UNCHECKED_LATENCY_SCORE: f64 = 0.25;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// One candidate proxy for a profile and how it scored.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyCandidate {
+    pub proxy_id: String,
+    pub proxy_name: String,
+    pub country: Option<String>,
+    /// 0–1, higher is better.
+    pub score: f64,
+    /// 0–1: share of the fingerprint's geo consistency kept in this country.
+    pub country_fit: f64,
+    pub latency_ms: Option<u32>,
+    /// Profiles already using the proxy.
+    pub load: u32,
+    /// Domains the proxy is cooling down for.
+    pub cooldowns: u32,
+}
+
+/// A proxy given to a profile by `auto_assign`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyAssignment {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub proxy: ProxyCandidate,
+}
+
+/// What `auto_assign` did.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoAssignResult {
+    pub assigned: Vec<ProxyAssignment>,
+    /// Unassigned profiles left so because no proxy was usable.
+    pub unmatched: Vec<String>,
+}
+
+// ── Scoring ───────────────────────────────────────────────────────────────────
+
+/// Whether `proxy` may be given to a profile at all.
+fn usable(proxy: &Proxy) -> bool {
+    let failed_check = !proxy.healthy && proxy.last_checked.is_some();
+    !failed_check && proxy.anonymity != Some(AnonymityLevel::Transparent)
+}
+
+/// Share of `fp`'s consistency (GeoValidator's score without a proxy)
+/// that remains once `country` is checked as well.
+pub fn country_fit(fp: &Fingerprint, country: Option<&str>) -> f64 {
+    let Some(cc) = country.filter(|cc| !cc.is_empty()) else {
+        return UNKNOWN_COUNTRY_FIT;
+    };
+    let base = GeoValidator::consistency_score(fp, None);
+    if base <= 0.0 {
+        return UNKNOWN_COUNTRY_FIT;
+    }
+    (GeoValidator::consistency_score(fp, Some(cc)) / base).clamp(0.0, 1.0)
+}
+
+/// Usable `proxies` scored for `fp`, best first.  `load` and `cooldowns`
+/// are keyed by proxy id; missing ids count as zero.
+pub fn rank(
+    fp: &Fingerprint,
+    proxies: &[Proxy],
+    load: &BTreeMap<String, u32>,
+    cooldowns: &BTreeMap<String, u32>,
+) -> Vec<ProxyCandidate> {
+    let mut candidates: Vec<ProxyCandidate> = proxies
+        .iter()
+        .filter(|p| usable(p))
+        .map(|p| {
+            let country_fit = country_fit(fp, p.country.as_deref());
+            let latency_score = match p.latency_ms {
+                Some(ms) => 1.0 - ms.min(SLOW_LATENCY_MS) as f64 / SLOW_LATENCY_MS as f64,
+                None => UNCHECKED_LATENCY_SCORE,
+            };
+            let load = load.get(&p.id).copied().unwrap_or(0);
+            let cooling = cooldowns.get(&p.id).copied().unwrap_or(0);
+            let score = COUNTRY_WEIGHT * country_fit
+                + LATENCY_WEIGHT * latency_score
+                + LOAD_WEIGHT / (1.0 + load as f64)
+                + COOLDOWN_WEIGHT / (1.0 + cooling as f64);
+            ProxyCandidate {
+                proxy_id: p.id.clone(),
+                proxy_name: p.name.clone(),
+                country: p.country.clone(),
+                score,
+                country_fit,
+                latency_ms: p.latency_ms,
+                load,
+                cooldowns: cooling,
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.proxy_name.cmp(&b.proxy_name))
+    });
+    candidates
+}
+
+// ── Repository-backed matching ────────────────────────────────────────────────
+
+/// Profiles per proxy id.
+fn load_of(profiles: &[Profile]) -> BTreeMap<String, u32> {
+    let mut load = BTreeMap::new();
+    for p in profiles {
+        for id in p.proxy_id.iter().chain(&p.proxy_chain) {
+            *load.entry(id.clone()).or_insert(0) += 1;
+        }
+    }
+    load
+}
+
+/// Domains each proxy is cooling down for right now.
+fn cooldowns_of(proxies: &ProxyRepo) -> Result<BTreeMap<String, u32>> {
+    let now = Utc::now();
+    let mut cooling = BTreeMap::new();
+    for status in proxies.list_domain_status(None)? {
+        if status.cooldown_until > now {
+            *cooling.entry(status.proxy_id).or_insert(0) += 1;
+        }
+    }
+    Ok(cooling)
+}
+
+/// Every usable proxy scored for the profile, best first.
+pub fn suggest(
+    profiles: &ProfileRepo,
+    proxies: &ProxyRepo,
+    profile_id: &str,
+) -> Result<Vec<ProxyCandidate>> {
+    let profile = profiles.get(profile_id)?;
+    let mut load = load_of(&profiles.list()?);
+    // The profile's own proxy doesn't count against itself
+    for id in profile.proxy_id.iter().chain(&profile.proxy_chain) {
+        if let Some(n) = load.get_mut(id) {
+            *n = n.saturating_sub(1);
+        }
+    }
+    Ok(rank(
+        &profile.fingerprint,
+        &proxies.list()?,
+        &load,
+        &cooldowns_of(proxies)?,
+    ))
+}
+
+/// Give every profile matching `filter` that has neither a proxy nor a
+/// chain its best proxy.
+pub fn auto_assign(
+    profiles: &ProfileRepo,
+    proxies: &ProxyRepo,
+    filter: &ProfileFilter,
+) -> Result<AutoAssignResult> {
+    let all = profiles.list()?;
+    let mut load = load_of(&all);
+    let pool = proxies.list()?;
+    let cooldowns = cooldowns_of(proxies)?;
+
+    let mut result = AutoAssignResult {
+        assigned: Vec::new(),
+        unmatched: Vec::new(),
+    };
+    let unassigned = all
+        .into_iter()
+        .filter(|p| p.proxy_id.is_none() && p.proxy_chain.is_empty() && filter.matches(p));
+    for profile in unassigned {
+        let Some(best) = rank(&profile.fingerprint, &pool, &load, &cooldowns)
+            .into_iter()
+            .next()
+        else {
+            result.unmatched.push(profile.id);
+            continue;
+        };
+        profiles.update(
+            &profile.id,
+            UpdateProfileRequest {
+                name: None,
+                fingerprint: None,
+                human: None,
+                proxy_id: Some(best.proxy_id.clone()),
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                tls_bridge: None,
+                persona: None,
+            },
+        )?;
+        *load.entry(best.proxy_id.clone()).or_insert(0) += 1;
+        result.assigned.push(ProxyAssignment {
+            profile_id: profile.id,
+            profile_name: profile.name,
+            proxy: best,
+        });
+    }
+    Ok(result)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+    use crate::fingerprint::FingerprintOrchestrator;
+    use crate::profile::CreateProfileRequest;
+    use crate::proxy::{AddProxyRequest, HealthSnapshot};
+
+    fn add_proxy(repo: &ProxyRepo, name: &str, country: &str, latency_ms: u32) -> Proxy {
+        let proxy = repo
+            .add(AddProxyRequest {
+                name: name.into(),
+                proxy_type: "http".into(),
+                host: format!("{name}.example.net"),
+                port: 8080,
+                username: None,
+                password: None,
+                country: Some(country.into()),
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
+            })
+            .unwrap();
+        repo.restore_health(
+            &proxy.id,
+            &HealthSnapshot {
+                healthy: true,
+                latency_ms: Some(latency_ms),
+                last_checked: Some(Utc::now()),
+                ..HealthSnapshot::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rank_prefers_the_profiles_country_and_skips_failed_proxies() {
+        let mut fp = FingerprintOrchestrator::generate(11);
+        FingerprintOrchestrator::enforce_geo(&mut fp, "DE");
+        let repo = ProxyRepo::new(Db::open_in_memory().unwrap());
+        let de = add_proxy(&repo, "de", "DE", 900);
+        let jp = add_proxy(&repo, "jp", "JP", 40);
+        let mut dead = add_proxy(&repo, "dead", "DE", 10);
+        dead.healthy = false;
+
+        let pool = [de.clone(), jp, dead];
+        let ranked = rank(&fp, &pool, &BTreeMap::new(), &BTreeMap::new());
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].proxy_id, de.id);
+        assert_eq!(ranked[0].country_fit, 1.0);
+        assert!(ranked[1].country_fit < 1.0);
+
+        // A crowded, cooling-down exit loses to an idle one in the same country
+        let de2 = add_proxy(&repo, "de2", "DE", 900);
+        let pool = [de.clone(), de2.clone()];
+        let busy = BTreeMap::from([(de.id.clone(), 5)]);
+        let ranked = rank(&fp, &pool, &busy, &busy);
+        assert_eq!(ranked[0].proxy_id, de2.id);
+        assert_eq!(ranked[1].load, 5);
+    }
+
+    #[test]
+    fn auto_assign_spreads_unassigned_profiles_over_the_pool() {
+        let db = Db::open_in_memory().unwrap();
+        let profiles = ProfileRepo::new(db.clone());
+        let proxies = ProxyRepo::new(db);
+        let a = add_proxy(&proxies, "a", "US", 100);
+        let b = add_proxy(&proxies, "b", "US", 100);
+        let create = |name: &str, proxy_id: Option<String>| {
+            profiles
+                .create(CreateProfileRequest {
+                    name: name.into(),
+                    seed: Some(5),
+                    proxy_id,
+                    notes: None,
+                    tags: None,
+                    behavior_profile: None,
+                    persona: None,
+                })
+                .unwrap()
+        };
+        create("p1", None);
+        create("p2", None);
+        let kept = create("kept", Some(a.id.clone()));
+
+        let suggested = suggest(&profiles, &proxies, &kept.id).unwrap();
+        assert_eq!(suggested.len(), 2);
+
+        let result = auto_assign(&profiles, &proxies, &ProfileFilter::default()).unwrap();
+        assert_eq!(result.assigned.len(), 2);
+        assert!(result.unmatched.is_empty());
+        let on_b = profiles
+            .list()
+            .unwrap()
+            .iter()
+            .filter(|p| p.proxy_id.as_deref() == Some(&b.id))
+            .count();
+        assert_eq!(on_b, 1, "one goes to b, then both carry one more");
+        assert_eq!(profiles.get(&kept.id).unwrap().proxy_id, Some(a.id));
+    }
+}
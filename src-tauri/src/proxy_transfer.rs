@@ -0,0 +1,528 @@
+// ── Manifold proxy list import / export ───────────────────────────────────────
+//
+// Moves a proxy inventory between Manifold instances together with its track
+// record: what the last health check found (latency, modes, throughput,
+// anonymity, when it ran) and the per-domain block/captcha history that
+// drives cooldowns.  A proxy that arrives with its history doesn't have to be
+// burned on a target again before the new instance knows to avoid it.
+//
+// Two layouts: a JSON document, and a CSV with one proxy per row whose
+// tags, check targets and domain history are JSON cells, for editing in a
+// spreadsheet.  Credentials are only written when asked for.  SSH key paths
+// are specific to the exporting machine and never leave it.
+//
+// Import validates every row before writing anything.  A proxy the
+// receiver already has (same type, host and port) is skipped rather than
+// duplicated or overwritten.
+
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::proxy::{
+    normalize_check_targets, normalize_domain, normalize_host, AddProxyRequest, CheckMode,
+    HealthSnapshot, Proxy, ProxyDomainStatus, ProxyRepo, ProxyType,
+};
+use crate::report::{csv_cell, ReportFormat};
+
+/// JSON layout version; bumped on incompatible changes.
+pub const PROXY_LIST_FORMAT: u32 = 1;
+
+/// CSV header, in column order.
+const CSV_COLUMNS: &[&str] = &[
+    "name",
+    "proxy_type",
+    "host",
+    "port",
+    "username",
+    "password",
+    "country",
+    "region",
+    "tags",
+    "notes",
+    "check_mode",
+    "check_targets",
+    "healthy",
+    "latency_ms",
+    "last_checked",
+    "supports_get",
+    "supports_connect",
+    "throughput_kbps",
+    "anonymity",
+    "domains",
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// Block/captcha history of one target domain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainRecord {
+    pub domain: String,
+    pub blocks: u32,
+    pub captchas: u32,
+    pub last_hit_at: DateTime<Utc>,
+    pub cooldown_until: DateTime<Utc>,
+}
+
+/// One proxy as it travels between instances.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedProxy {
+    pub name: String,
+    pub proxy_type: ProxyType,
+    pub host: String,
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub check_mode: CheckMode,
+    #[serde(default)]
+    pub check_targets: Vec<String>,
+    #[serde(flatten)]
+    pub health: HealthSnapshot,
+    #[serde(default)]
+    pub domains: Vec<DomainRecord>,
+}
+
+/// The JSON export document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyList {
+    pub format: u32,
+    pub exported_at: DateTime<Utc>,
+    pub proxies: Vec<ExportedProxy>,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyImport {
+    pub added: Vec<Proxy>,
+    /// Names of rows matching a proxy this instance already has.
+    pub skipped: Vec<String>,
+}
+
+// ── Export ────────────────────────────────────────────────────────────────────
+
+/// Every proxy with its health and domain history, as `format` text.
+pub fn export(
+    proxies: &ProxyRepo,
+    format: ReportFormat,
+    include_credentials: bool,
+) -> Result<String> {
+    let mut records = Vec::new();
+    for proxy in proxies.list()? {
+        let domains = proxies
+            .list_domain_status(Some(&proxy.id))?
+            .into_iter()
+            .map(|s| DomainRecord {
+                domain: s.domain,
+                blocks: s.blocks,
+                captchas: s.captchas,
+                last_hit_at: s.last_hit_at,
+                cooldown_until: s.cooldown_until,
+            })
+            .collect();
+        let (username, password) = if include_credentials {
+            (proxy.username.clone(), proxy.password.clone())
+        } else {
+            (None, None)
+        };
+        records.push(ExportedProxy {
+            health: proxy.health_snapshot(),
+            name: proxy.name,
+            proxy_type: proxy.proxy_type,
+            host: proxy.host,
+            port: proxy.port,
+            username,
+            password,
+            country: proxy.country,
+            region: proxy.region,
+            tags: proxy.tags,
+            notes: proxy.notes,
+            check_mode: proxy.check_mode,
+            check_targets: proxy.check_targets,
+            domains,
+        });
+    }
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(&ProxyList {
+            format: PROXY_LIST_FORMAT,
+            exported_at: Utc::now(),
+            proxies: records,
+        })?),
+        ReportFormat::Csv => to_csv(&records),
+    }
+}
+
+fn to_csv(records: &[ExportedProxy]) -> Result<String> {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+    for r in records {
+        let text = |v: Option<&str>| v.map_or(Value::Null, |s| Value::Text(s.to_string()));
+        let int = |v: Option<i64>| v.map_or(Value::Null, Value::Integer);
+        let flag = |v: Option<bool>| int(v.map(i64::from));
+        let h = &r.health;
+        let row = [
+            text(Some(&r.name)),
+            text(Some(&r.proxy_type.to_string())),
+            text(Some(&r.host)),
+            Value::Integer(r.port.into()),
+            text(r.username.as_deref()),
+            text(r.password.as_deref()),
+            text(r.country.as_deref()),
+            text(r.region.as_deref()),
+            text(Some(&serde_json::to_string(&r.tags)?)),
+            text(Some(&r.notes)),
+            text(Some(&r.check_mode.to_string())),
+            text(Some(&serde_json::to_string(&r.check_targets)?)),
+            Value::Integer(h.healthy.into()),
+            int(h.latency_ms.map(i64::from)),
+            text(h.last_checked.map(|t| t.to_rfc3339()).as_deref()),
+            flag(h.supports_get),
+            flag(h.supports_connect),
+            int(h.throughput_kbps.map(i64::from)),
+            text(h.anonymity.map(|a| a.to_string()).as_deref()),
+            text(Some(&serde_json::to_string(&r.domains)?)),
+        ];
+        let cells: Vec<String> = row.iter().map(csv_cell).collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+// ── Import ────────────────────────────────────────────────────────────────────
+
+/// Parse `data` as a `format` proxy list and add the proxies this instance
+/// doesn't have yet, with their health and domain history.
+pub fn import(proxies: &ProxyRepo, format: ReportFormat, data: &str) -> Result<ProxyImport> {
+    let records = match format {
+        ReportFormat::Json => {
+            let list: ProxyList = serde_json::from_str(data)
+                .map_err(|e| ManifoldError::InvalidArg(format!("invalid proxy list: {e}")))?;
+            if list.format > PROXY_LIST_FORMAT {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "proxy list format {} is newer than this version of Manifold ({PROXY_LIST_FORMAT})",
+                    list.format
+                )));
+            }
+            list.proxies
+        }
+        ReportFormat::Csv => from_csv(data)?,
+    };
+    let records = records
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| validate(r).map_err(|e| row_error(i, e)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut existing: Vec<(ProxyType, String, u16)> = proxies
+        .list()?
+        .into_iter()
+        .map(|p| (p.proxy_type, p.host, p.port))
+        .collect();
+    let mut import = ProxyImport {
+        added: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for record in records {
+        let key = (record.proxy_type.clone(), record.host.clone(), record.port);
+        if existing.contains(&key) {
+            import.skipped.push(record.name);
+            continue;
+        }
+        existing.push(key);
+
+        let proxy = proxies.add(AddProxyRequest {
+            name: record.name,
+            proxy_type: record.proxy_type.to_string(),
+            host: record.host,
+            port: record.port,
+            username: record.username,
+            password: record.password,
+            country: record.country,
+            region: record.region,
+            ssh_key_path: None,
+            tags: Some(record.tags),
+            notes: Some(record.notes),
+        })?;
+        proxies.set_check_mode(&proxy.id, record.check_mode)?;
+        proxies.set_check_targets(&proxy.id, &record.check_targets)?;
+        for d in record.domains {
+            proxies.restore_domain_status(&ProxyDomainStatus {
+                proxy_id: proxy.id.clone(),
+                domain: d.domain,
+                blocks: d.blocks,
+                captchas: d.captchas,
+                last_hit_at: d.last_hit_at,
+                cooldown_until: d.cooldown_until,
+            })?;
+        }
+        import
+            .added
+            .push(proxies.restore_health(&proxy.id, &record.health)?);
+    }
+    Ok(import)
+}
+
+/// `record` with its host, targets and domains normalised, or why it can't
+/// be added.
+fn validate(mut record: ExportedProxy) -> Result<ExportedProxy> {
+    if record.name.trim().is_empty() {
+        return Err(ManifoldError::InvalidArg("name cannot be empty".into()));
+    }
+    if record.port == 0 {
+        return Err(ManifoldError::InvalidArg("port cannot be 0".into()));
+    }
+    record.host = normalize_host(&record.host)?;
+    record.check_targets = normalize_check_targets(&record.check_targets)?;
+    for d in &mut record.domains {
+        d.domain = normalize_domain(&d.domain)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("invalid domain: {:?}", d.domain)))?;
+    }
+    Ok(record)
+}
+
+fn row_error(index: usize, err: ManifoldError) -> ManifoldError {
+    let detail = match err {
+        ManifoldError::InvalidArg(msg) => msg,
+        other => other.to_string(),
+    };
+    ManifoldError::InvalidArg(format!("proxy {}: {detail}", index + 1))
+}
+
+fn from_csv(data: &str) -> Result<Vec<ExportedProxy>> {
+    let mut rows = parse_csv(data)?.into_iter();
+    let header = rows
+        .next()
+        .ok_or_else(|| ManifoldError::InvalidArg("empty proxy list".into()))?;
+    for required in ["name", "proxy_type", "host", "port"] {
+        if !header.iter().any(|h| h == required) {
+            return Err(ManifoldError::InvalidArg(format!(
+                "proxy list has no {required:?} column"
+            )));
+        }
+    }
+    rows.enumerate()
+        .map(|(i, row)| {
+            let cell = |name: &str| {
+                header
+                    .iter()
+                    .position(|h| h == name)
+                    .and_then(|col| row.get(col))
+                    .map(String::as_str)
+                    .filter(|v| !v.is_empty())
+            };
+            csv_record(&cell).map_err(|e| row_error(i, e))
+        })
+        .collect()
+}
+
+fn csv_record<'a>(cell: &dyn Fn(&str) -> Option<&'a str>) -> Result<ExportedProxy> {
+    fn parsed<T: std::str::FromStr>(name: &str, value: Option<&str>) -> Result<Option<T>> {
+        value
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| ManifoldError::InvalidArg(format!("{name}: invalid value {v:?}")))
+            })
+            .transpose()
+    }
+    let flag = |name: &str| -> Result<Option<bool>> {
+        Ok(parsed::<u8>(name, cell(name))?.map(|v| v != 0))
+    };
+    let json = |name: &str| -> Result<Option<serde_json::Value>> {
+        cell(name)
+            .map(|v| {
+                serde_json::from_str(v)
+                    .map_err(|e| ManifoldError::InvalidArg(format!("{name}: {e}")))
+            })
+            .transpose()
+    };
+    let last_checked = cell("last_checked")
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|e| ManifoldError::InvalidArg(format!("last_checked: {e}")))
+        })
+        .transpose()?;
+    Ok(ExportedProxy {
+        name: cell("name").unwrap_or_default().to_string(),
+        proxy_type: cell("proxy_type").unwrap_or_default().parse()?,
+        host: cell("host").unwrap_or_default().to_string(),
+        port: parsed("port", cell("port"))?.unwrap_or(0),
+        username: cell("username").map(str::to_string),
+        password: cell("password").map(str::to_string),
+        country: cell("country").map(str::to_string),
+        region: cell("region").map(str::to_string),
+        tags: json("tags")?
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default(),
+        notes: cell("notes").unwrap_or_default().to_string(),
+        check_mode: parsed("check_mode", cell("check_mode"))?.unwrap_or_default(),
+        check_targets: json("check_targets")?
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default(),
+        health: HealthSnapshot {
+            healthy: flag("healthy")?.unwrap_or(false),
+            latency_ms: parsed("latency_ms", cell("latency_ms"))?,
+            last_checked,
+            supports_get: flag("supports_get")?,
+            supports_connect: flag("supports_connect")?,
+            throughput_kbps: parsed("throughput_kbps", cell("throughput_kbps"))?,
+            anonymity: parsed("anonymity", cell("anonymity"))?,
+        },
+        domains: json("domains")?
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+/// RFC 4180 rows, with the apostrophe `csv_cell` puts before formula-like
+/// text removed again.  Blank lines are skipped.
+fn parse_csv(data: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(unescape(std::mem::take(&mut field))),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                row.push(unescape(std::mem::take(&mut field)));
+                let done = std::mem::take(&mut row);
+                if done.iter().any(|f| !f.is_empty()) {
+                    rows.push(done);
+                }
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ManifoldError::InvalidArg(
+            "proxy list ends inside a quoted field".into(),
+        ));
+    }
+    row.push(unescape(field));
+    if row.iter().any(|f| !f.is_empty()) {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn unescape(field: String) -> String {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@', '\t', '\r']) => rest.to_string(),
+        _ => field,
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+    use crate::proxy::{AnonymityLevel, DomainHit};
+
+    fn seeded_repo() -> ProxyRepo {
+        let repo = ProxyRepo::new(Db::open_in_memory().unwrap());
+        let proxy = repo
+            .add(AddProxyRequest {
+                name: "-de, \"residential\"".into(),
+                proxy_type: "socks5".into(),
+                host: "10.0.0.7".into(),
+                port: 1080,
+                username: Some("alice".into()),
+                password: Some("secret".into()),
+                country: Some("DE".into()),
+                region: None,
+                ssh_key_path: None,
+                tags: Some(vec!["mobile 4G".into()]),
+                notes: Some("=client A only".into()),
+            })
+            .unwrap();
+        repo.restore_health(
+            &proxy.id,
+            &HealthSnapshot {
+                healthy: true,
+                latency_ms: Some(182),
+                last_checked: Some(Utc::now()),
+                supports_get: None,
+                supports_connect: Some(true),
+                throughput_kbps: Some(2400),
+                anonymity: Some(AnonymityLevel::Elite),
+            },
+        )
+        .unwrap();
+        repo.record_domain_hit(&proxy.id, "shop.example.com", DomainHit::Captcha)
+            .unwrap();
+        repo
+    }
+
+    #[test]
+    fn both_formats_carry_the_track_record_to_another_instance() {
+        let source = seeded_repo();
+        let original = &source.list().unwrap()[0];
+        for format in [ReportFormat::Json, ReportFormat::Csv] {
+            let data = export(&source, format, true).unwrap();
+            let target = ProxyRepo::new(Db::open_in_memory().unwrap());
+            let import = import(&target, format, &data).unwrap();
+            assert_eq!(import.added.len(), 1, "{format:?}");
+            let copy = &import.added[0];
+            assert_eq!(copy.name, original.name);
+            assert_eq!(copy.password.as_deref(), Some("secret"));
+            assert_eq!(copy.tags, original.tags);
+            assert_eq!(copy.notes, original.notes);
+            let (a, b) = (copy.health_snapshot(), original.health_snapshot());
+            assert_eq!(a.latency_ms, b.latency_ms);
+            assert_eq!(a.anonymity, b.anonymity);
+            assert_eq!(a.supports_connect, b.supports_connect);
+            assert_eq!(
+                a.last_checked.map(|t| t.timestamp()),
+                b.last_checked.map(|t| t.timestamp())
+            );
+            let domains = target.list_domain_status(Some(&copy.id)).unwrap();
+            assert_eq!(domains.len(), 1);
+            assert_eq!(domains[0].captchas, 1);
+
+            // A second import finds the proxy already there
+            let again = super::import(&target, format, &data).unwrap();
+            assert!(again.added.is_empty());
+            assert_eq!(again.skipped, vec![original.name.clone()]);
+        }
+    }
+
+    #[test]
+    fn export_strips_credentials_and_import_rejects_bad_rows_up_front() {
+        let source = seeded_repo();
+        let json = export(&source, ReportFormat::Json, false).unwrap();
+        assert!(!json.contains("secret") && !json.contains("alice"));
+
+        let csv = "name,proxy_type,host,port\nok,http,10.0.0.1,8080\nbad,http,10.0.0.2,0\n";
+        let target = ProxyRepo::new(Db::open_in_memory().unwrap());
+        let err = import(&target, ReportFormat::Csv, csv).unwrap_err();
+        assert!(err.to_string().contains("proxy 2"), "{err}");
+        assert!(target.list().unwrap().is_empty());
+    }
+}
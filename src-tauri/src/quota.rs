@@ -0,0 +1,302 @@
+// ── Manifold profile disk quotas ──────────────────────────────────────────────
+//
+// Chromium user-data dirs only grow: HTTP and code caches, shader caches and
+// service-worker scripts pile up until a handful of busy profiles fill the
+// disk.  Each profile can carry its own size limit (falling back to an
+// app-wide default); a background sweep measures every data dir, logs a
+// warning event when usage crosses 80 % of the limit, and — when enabled —
+// empties the cache folders of profiles over the limit.  Cookies, storage
+// and history are never touched, so clearing is invisible to sites.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::Result;
+use crate::events::{EventKind, EventRepo, NewEvent};
+use crate::profile::{dir_size, ProfileRepo};
+
+/// `settings` key holding `QuotaSettings` (JSON).
+pub const QUOTA_SETTINGS_KEY: &str = "disk_quota";
+
+/// Share of the limit at which a warning is logged.
+pub const WARN_RATIO: f64 = 0.8;
+
+/// How often the background sweep runs.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Regenerable cache folders, found at the top of the user-data dir or
+/// inside a Chromium profile dir (`Default`, `Profile 1` …).
+pub const CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "DawnWebGPUCache",
+    "GraphiteDawnCache",
+    "ShaderCache",
+    "GrShaderCache",
+    "component_crx_cache",
+];
+
+const MB: u64 = 1024 * 1024;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// App-wide quota settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotaSettings {
+    /// Limit for profiles without their own.  `None` means unlimited.
+    pub default_quota_mb: Option<u64>,
+    /// Empty cache folders of idle profiles over their limit.
+    pub auto_clear_cache: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLevel {
+    Ok,
+    /// At or above `WARN_RATIO` of the limit.
+    Warning,
+    /// At or above the limit.
+    Exceeded,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaUsage {
+    pub profile_id: String,
+    pub bytes: u64,
+    /// `None` when the profile has no limit.
+    pub quota_bytes: Option<u64>,
+    pub level: QuotaLevel,
+    /// Freed by clearing caches during this sweep.
+    pub cleared_bytes: u64,
+}
+
+pub fn quota_level(bytes: u64, quota_bytes: Option<u64>) -> QuotaLevel {
+    match quota_bytes {
+        Some(q) if bytes >= q => QuotaLevel::Exceeded,
+        Some(q) if bytes as f64 >= q as f64 * WARN_RATIO => QuotaLevel::Warning,
+        _ => QuotaLevel::Ok,
+    }
+}
+
+/// Delete the cache folders of a user-data dir.  Returns the bytes freed.
+pub fn clear_cache_dirs(data_dir: &Path) -> u64 {
+    let mut roots = vec![data_dir.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(data_dir) {
+        roots.extend(
+            entries
+                .flatten()
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| e.path()),
+        );
+    }
+
+    let mut freed = 0;
+    for root in roots {
+        for name in CACHE_DIRS {
+            let dir = root.join(name);
+            if !dir.is_dir() {
+                continue;
+            }
+            let bytes = dir_size(&dir);
+            if std::fs::remove_dir_all(&dir).is_ok() {
+                freed += bytes;
+            }
+        }
+    }
+    freed
+}
+
+// ── Sweep ─────────────────────────────────────────────────────────────────────
+
+/// Measure every profile's data dir under `profiles_root`.  With `clear`,
+/// caches of idle profiles over their limit are emptied first.
+pub fn measure(
+    db: &Db,
+    profiles_root: &Path,
+    settings: &QuotaSettings,
+    clear: bool,
+) -> Result<Vec<QuotaUsage>> {
+    let rows: Vec<(String, String, Option<i64>)> = db.with_conn(|conn| {
+        let mut stmt = conn.prepare("SELECT id, status, disk_quota_mb FROM profiles")?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(rows)
+    })?;
+
+    let mut usage = Vec::with_capacity(rows.len());
+    for (id, status, quota_mb) in rows {
+        let data_dir = profiles_root.join(&id);
+        let quota_bytes = quota_mb
+            .map(|mb| mb.max(0) as u64)
+            .or(settings.default_quota_mb)
+            .map(|mb| mb.saturating_mul(MB));
+        let mut bytes = dir_size(&data_dir);
+        let mut level = quota_level(bytes, quota_bytes);
+
+        // A running browser holds its caches open; leave it alone
+        let mut cleared_bytes = 0;
+        if clear && level == QuotaLevel::Exceeded && status == "idle" {
+            cleared_bytes = clear_cache_dirs(&data_dir);
+            bytes = bytes.saturating_sub(cleared_bytes);
+            level = quota_level(bytes, quota_bytes);
+        }
+
+        usage.push(QuotaUsage {
+            profile_id: id,
+            bytes,
+            quota_bytes,
+            level,
+            cleared_bytes,
+        });
+    }
+    Ok(usage)
+}
+
+/// One background pass: measure (clearing caches when enabled) and log an
+/// event for every profile whose level rose since the previous pass.
+pub fn run_sweep(
+    db: &Db,
+    profiles_root: &Path,
+    settings: &QuotaSettings,
+    last_levels: &mut HashMap<String, QuotaLevel>,
+) -> Result<Vec<QuotaUsage>> {
+    let usage = measure(db, profiles_root, settings, settings.auto_clear_cache)?;
+    let events = EventRepo::new(db.clone());
+    for u in &usage {
+        let previous = last_levels.insert(u.profile_id.clone(), u.level);
+        if u.level == QuotaLevel::Ok && u.cleared_bytes == 0 {
+            continue;
+        }
+        if previous.is_some_and(|p| p >= u.level) && u.cleared_bytes == 0 {
+            continue;
+        }
+        let severity = match u.level {
+            QuotaLevel::Exceeded => "critical",
+            _ => "warning",
+        };
+        events.record(NewEvent {
+            profile_id: Some(u.profile_id.clone()),
+            kind: EventKind::DiskQuota,
+            severity: Some(severity.into()),
+            domain: None,
+            detail: Some(serde_json::json!({
+                "bytes": u.bytes,
+                "quota_bytes": u.quota_bytes,
+                "level": u.level,
+                "cleared_bytes": u.cleared_bytes,
+            })),
+        })?;
+    }
+    Ok(usage)
+}
+
+/// Run the sweep now and then every `SWEEP_INTERVAL` on a background thread
+/// for the lifetime of the app.
+pub fn spawn_scheduler(db: Db) {
+    std::thread::spawn(move || {
+        let mut last_levels = HashMap::new();
+        loop {
+            let profiles_root = crate::db::profiles_dir();
+            let result = ProfileRepo::new(db.clone())
+                .quota_settings()
+                .and_then(|settings| run_sweep(&db, &profiles_root, &settings, &mut last_levels));
+            if let Err(e) = result {
+                eprintln!("[quota] sweep failed: {e}");
+            }
+            std::thread::sleep(SWEEP_INTERVAL);
+        }
+    });
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_profile(db: &Db, id: &str, status: &str, quota_mb: Option<i64>) {
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, status, created_at, disk_quota_mb) VALUES (?1, ?1, '{}', ?2, '2025-01-01T00:00:00+00:00', ?3)",
+                rusqlite::params![id, status, quota_mb],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    fn write(path: &Path, bytes: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; bytes]).unwrap();
+    }
+
+    #[test]
+    fn levels_follow_the_warning_ratio() {
+        assert_eq!(quota_level(10, None), QuotaLevel::Ok);
+        assert_eq!(quota_level(79, Some(100)), QuotaLevel::Ok);
+        assert_eq!(quota_level(80, Some(100)), QuotaLevel::Warning);
+        assert_eq!(quota_level(100, Some(100)), QuotaLevel::Exceeded);
+    }
+
+    #[test]
+    fn clearing_removes_only_cache_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("Default/Cache/Cache_Data/data_1"), 500);
+        write(&dir.path().join("ShaderCache/index"), 100);
+        write(&dir.path().join("Default/Cookies"), 50);
+
+        assert_eq!(clear_cache_dirs(dir.path()), 600);
+        assert!(!dir.path().join("Default/Cache").exists());
+        assert!(dir.path().join("Default/Cookies").exists());
+    }
+
+    #[test]
+    fn sweep_clears_idle_profiles_and_logs_rising_levels_once() {
+        let db = Db::open_in_memory().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        insert_profile(&db, "idle", "idle", Some(1));
+        insert_profile(&db, "busy", "running", Some(1));
+        for id in ["idle", "busy"] {
+            write(
+                &root.path().join(id).join("Default/Cache/data"),
+                MB as usize,
+            );
+            write(&root.path().join(id).join("Default/Cookies"), 10);
+        }
+        let settings = QuotaSettings {
+            default_quota_mb: None,
+            auto_clear_cache: true,
+        };
+
+        let mut last = HashMap::new();
+        let usage = run_sweep(&db, root.path(), &settings, &mut last).unwrap();
+        let idle = usage.iter().find(|u| u.profile_id == "idle").unwrap();
+        assert_eq!(idle.cleared_bytes, MB);
+        assert_eq!(idle.level, QuotaLevel::Ok);
+        let busy = usage.iter().find(|u| u.profile_id == "busy").unwrap();
+        assert_eq!(busy.level, QuotaLevel::Exceeded);
+        assert_eq!(busy.cleared_bytes, 0);
+
+        // Unchanged levels are not logged again
+        run_sweep(&db, root.path(), &settings, &mut last).unwrap();
+        let logged = EventRepo::new(db.clone())
+            .list_before(None, 100, None)
+            .unwrap();
+        assert_eq!(
+            logged
+                .iter()
+                .filter(|e| e.kind == EventKind::DiskQuota)
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,590 @@
+// ── Manifold real-device reference corpus ─────────────────────────────────────
+//
+// The generator's pools are our guess at what real machines look like.  A
+// user with access to real devices can do better: the collection page
+// (static/collect-fingerprint.html) reads what any site can read from the
+// browser it is opened in and prints it as JSON, which is ingested here as a
+// reference.  Generated fingerprints are then compared against the corpus
+// of the same platform: a device value no real reference shows (a GPU
+// nobody in the corpus has, an unusual screen) is where a profile is most
+// likely to stand out.
+//
+// Only device properties are compared.  Locale and timezone follow the
+// proxy, not the device, and are GeoValidator's business.
+//
+// A reference can also be cloned into a profile.  What the page can't see
+// (noise levels, fonts, media preferences, WebRTC hostnames) comes from a
+// generated fingerprint of the same OS release, so the clone is as
+// consistent as a generated profile; what it did see is the real device's.
+
+use chrono::{DateTime, Utc};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::{chrome_version, Fingerprint, FingerprintOrchestrator};
+use crate::fonts::{OsRelease, TextRendering};
+use crate::intl::IntlProfile;
+use crate::profile::{CreateProfileRequest, Profile, ProfileRepo, UpdateProfileRequest};
+
+/// Seeds tried for a base fingerprint of the captured OS release.
+const MAX_BASE_ATTEMPTS: u32 = 10_000;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What the collection page captures.  Every field is optional: browsers
+/// without UA-CH or WebGL simply leave theirs out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceCapture {
+    pub user_agent: Option<String>,
+    pub platform: Option<String>,
+    pub ua_platform: Option<String>,
+    pub ua_platform_version: Option<String>,
+    pub hardware_concurrency: Option<u32>,
+    pub device_memory: Option<f64>,
+    pub screen_width: Option<u32>,
+    pub screen_height: Option<u32>,
+    pub pixel_ratio: Option<f64>,
+    pub color_depth: Option<u32>,
+    pub webgl_vendor: Option<String>,
+    pub webgl_renderer: Option<String>,
+    pub timezone: Option<String>,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceFingerprint {
+    pub id: String,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    pub capture: DeviceCapture,
+}
+
+/// Payload of `create_profile_from_capture`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaptureProfileRequest {
+    pub reference_id: String,
+    pub name: String,
+    /// Seed of the fields the capture doesn't cover; random if `None`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub proxy_id: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub behavior_profile: Option<String>,
+}
+
+/// How often a generated value occurs among the references.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldCoverage {
+    pub field: String,
+    pub value: String,
+    /// References showing the same value.
+    pub seen_in: u32,
+    /// References that captured the field at all.
+    pub captured_in: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NearestReference {
+    pub id: String,
+    pub label: String,
+    pub matching: u32,
+    /// Fields whose value differs from the reference's.
+    pub mismatched: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferenceComparison {
+    /// References of the fingerprint's platform the comparison used.
+    pub references: u32,
+    /// Share of compared fields whose value some reference shows, 0–1;
+    /// `None` with nothing to compare against.
+    pub score: Option<f64>,
+    pub fields: Vec<FieldCoverage>,
+    pub nearest: Option<NearestReference>,
+}
+
+// ── Comparison ────────────────────────────────────────────────────────────────
+
+/// The compared fields of a capture as `(field, value)`, values rendered
+/// the same way for captures and fingerprints.
+fn device_values(c: &DeviceCapture) -> Vec<(&'static str, Option<String>)> {
+    let major = c
+        .user_agent
+        .as_deref()
+        .and_then(chrome_version)
+        .and_then(|v| v.split('.').next())
+        .map(str::to_string);
+    let screen = c
+        .screen_width
+        .zip(c.screen_height)
+        .map(|(w, h)| format!("{w}x{h}"));
+    vec![
+        ("platform", c.ua_platform.clone()),
+        ("chrome_major", major),
+        ("screen", screen),
+        ("pixel_ratio", c.pixel_ratio.map(|r| format!("{r}"))),
+        ("color_depth", c.color_depth.map(|d| d.to_string())),
+        (
+            "hardware_concurrency",
+            c.hardware_concurrency.map(|n| n.to_string()),
+        ),
+        ("device_memory", c.device_memory.map(|m| format!("{m}"))),
+        ("webgl_renderer", c.webgl_renderer.clone()),
+    ]
+}
+
+/// A fingerprint as the collection page would have captured it.
+pub fn capture_of(fp: &Fingerprint) -> DeviceCapture {
+    DeviceCapture {
+        user_agent: Some(fp.user_agent.clone()),
+        platform: Some(fp.platform.clone()),
+        ua_platform: Some(fp.ua_platform.clone()),
+        ua_platform_version: Some(fp.ua_platform_version.clone()),
+        hardware_concurrency: Some(fp.hardware_concurrency.into()),
+        device_memory: Some(fp.device_memory),
+        screen_width: Some(fp.screen_width),
+        screen_height: Some(fp.screen_height),
+        pixel_ratio: Some(fp.pixel_ratio),
+        color_depth: Some(fp.color_depth.into()),
+        webgl_vendor: Some(fp.webgl_vendor.clone()),
+        webgl_renderer: Some(fp.webgl_renderer.clone()),
+        timezone: Some(fp.timezone.clone()),
+        languages: fp
+            .accept_language
+            .split(',')
+            .map(|l| l.split(';').next().unwrap_or_default().trim().to_string())
+            .collect(),
+    }
+}
+
+/// Compare `fp` with the references of its platform.
+pub fn compare(fp: &Fingerprint, corpus: &[ReferenceFingerprint]) -> ReferenceComparison {
+    let refs: Vec<&ReferenceFingerprint> = corpus
+        .iter()
+        .filter(|r| r.capture.ua_platform.as_deref() == Some(fp.ua_platform.as_str()))
+        .collect();
+    let ours = device_values(&capture_of(fp));
+    let theirs: Vec<_> = refs.iter().map(|r| device_values(&r.capture)).collect();
+
+    let mut fields = Vec::new();
+    for (i, (field, value)) in ours.iter().enumerate() {
+        let Some(value) = value else { continue };
+        let captured: Vec<&Option<String>> = theirs.iter().map(|t| &t[i].1).collect();
+        fields.push(FieldCoverage {
+            field: field.to_string(),
+            value: value.clone(),
+            seen_in: captured
+                .iter()
+                .filter(|v| v.as_ref() == Some(value))
+                .count() as u32,
+            captured_in: captured.iter().filter(|v| v.is_some()).count() as u32,
+        });
+    }
+
+    let compared: Vec<&FieldCoverage> = fields.iter().filter(|f| f.captured_in > 0).collect();
+    let score = (!compared.is_empty())
+        .then(|| compared.iter().filter(|f| f.seen_in > 0).count() as f64 / compared.len() as f64);
+
+    let nearest = refs
+        .iter()
+        .zip(&theirs)
+        .map(|(r, values)| {
+            let mut matching = 0;
+            let mut mismatched = Vec::new();
+            for ((field, ours), (_, theirs)) in ours.iter().zip(values) {
+                match (ours, theirs) {
+                    (Some(a), Some(b)) if a == b => matching += 1,
+                    (Some(_), Some(_)) => mismatched.push(field.to_string()),
+                    _ => {}
+                }
+            }
+            NearestReference {
+                id: r.id.clone(),
+                label: r.label.clone(),
+                matching,
+                mismatched,
+            }
+        })
+        .max_by_key(|n| n.matching);
+
+    ReferenceComparison {
+        references: refs.len() as u32,
+        score,
+        fields,
+        nearest,
+    }
+}
+
+// ── Cloning ───────────────────────────────────────────────────────────────────
+
+/// A generated fingerprint of `capture`'s platform and OS release, found by
+/// walking seeds drawn from `seed`.
+fn base_for(capture: &DeviceCapture, seed: u64) -> Result<Fingerprint> {
+    let platform = capture.ua_platform.as_deref().unwrap_or_default();
+    let release = capture
+        .ua_platform_version
+        .as_deref()
+        .and_then(|v| OsRelease::from_platform(platform, v));
+    let mut rng = SmallRng::seed_from_u64(seed);
+    for _ in 0..MAX_BASE_ATTEMPTS {
+        let fp = FingerprintOrchestrator::generate(rng.gen());
+        if fp.ua_platform == platform
+            && release.is_none_or(|r| {
+                OsRelease::from_platform(&fp.ua_platform, &fp.ua_platform_version) == Some(r)
+            })
+        {
+            return Ok(fp);
+        }
+    }
+    Err(ManifoldError::InvalidArg(format!(
+        "no generated fingerprint matches the captured platform {platform:?}"
+    )))
+}
+
+/// `base` with every value `capture` has replaced by the captured one.
+pub fn fingerprint_from_capture(capture: &DeviceCapture, base: Fingerprint) -> Fingerprint {
+    let mut fp = base;
+    if let Some(ua) = &capture.user_agent {
+        // Brand versions follow the major; the UA string is the device's
+        if let Some(major) = chrome_version(ua)
+            .and_then(|v| v.split('.').next())
+            .and_then(|m| m.parse().ok())
+        {
+            FingerprintOrchestrator::set_chrome_major(&mut fp, major);
+        }
+        fp.user_agent = ua.clone();
+    }
+    if let Some(platform) = &capture.platform {
+        fp.platform = platform.clone();
+    }
+    if let Some(version) = &capture.ua_platform_version {
+        fp.ua_platform_version = version.clone();
+        fp.text_rendering = fp.text_rendering.map(|t| {
+            TextRendering::for_platform(&fp.ua_platform, version, t.measure_scale, t.baseline_shift)
+        });
+    }
+    if let Some(n) = capture.hardware_concurrency {
+        fp.hardware_concurrency = n.min(u8::MAX.into()) as u8;
+    }
+    if let Some(memory) = capture.device_memory {
+        fp.device_memory = memory;
+    }
+    if let (Some(w), Some(h)) = (capture.screen_width, capture.screen_height) {
+        // The base's window chrome and taskbar, on the device's screen
+        let chrome_w = fp.screen_width.saturating_sub(fp.viewport_width);
+        let chrome_h = fp.screen_height.saturating_sub(fp.viewport_height);
+        fp.screen_width = w;
+        fp.screen_height = h;
+        fp.viewport_width = w.saturating_sub(chrome_w);
+        fp.viewport_height = h.saturating_sub(chrome_h);
+    }
+    if let Some(ratio) = capture.pixel_ratio {
+        fp.pixel_ratio = ratio;
+    }
+    if let Some(depth) = capture.color_depth {
+        fp.color_depth = depth.min(u8::MAX.into()) as u8;
+    }
+    if let Some(vendor) = &capture.webgl_vendor {
+        fp.webgl_vendor = vendor.clone();
+    }
+    if let Some(renderer) = &capture.webgl_renderer {
+        fp.webgl_renderer = renderer.clone();
+    }
+    if let Some(timezone) = &capture.timezone {
+        fp.timezone = timezone.clone();
+    }
+    if let Some(locale) = capture.languages.first() {
+        fp.locale = locale.clone();
+        fp.intl = Some(IntlProfile::for_locale(locale));
+        fp.accept_language = capture
+            .languages
+            .iter()
+            .enumerate()
+            .map(|(i, lang)| match i {
+                0 => lang.clone(),
+                _ => format!("{lang};q=0.{}", 10usize.saturating_sub(i).max(1)),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+    }
+    fp
+}
+
+/// Create a profile cloning `reference`'s device.  With a proxy that has a
+/// country, locale and timezone follow the proxy instead of the device.
+pub fn create_from_capture(
+    profiles: &ProfileRepo,
+    reference: &ReferenceFingerprint,
+    req: CaptureProfileRequest,
+) -> Result<Profile> {
+    let base = base_for(&reference.capture, req.seed.unwrap_or_else(rand::random))?;
+    let mut fingerprint = fingerprint_from_capture(&reference.capture, base);
+    if let Some((country, region)) = profiles.proxy_geo(req.proxy_id.as_deref())? {
+        FingerprintOrchestrator::enforce_geo_region(&mut fingerprint, &country, region.as_deref());
+    }
+    let created = profiles.create(CreateProfileRequest {
+        name: req.name,
+        seed: Some(fingerprint.seed),
+        proxy_id: req.proxy_id,
+        notes: req.notes,
+        tags: req.tags,
+        behavior_profile: req.behavior_profile,
+        persona: None,
+    })?;
+    let updated = profiles.update(
+        &created.id,
+        UpdateProfileRequest {
+            name: None,
+            fingerprint: Some(fingerprint),
+            human: None,
+            proxy_id: None,
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+            tls_bridge: None,
+            persona: None,
+        },
+    );
+    if updated.is_err() {
+        profiles.delete(&created.id).ok();
+    }
+    updated
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct ReferenceRepo {
+    db: Db,
+}
+
+impl ReferenceRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Store a capture from the collection page.
+    pub fn ingest(
+        &self,
+        label: Option<String>,
+        data: serde_json::Value,
+    ) -> Result<ReferenceFingerprint> {
+        let mut capture: DeviceCapture = serde_json::from_value(data)
+            .map_err(|e| ManifoldError::InvalidArg(format!("reference fingerprint: {e}")))?;
+        if capture.ua_platform.is_none() {
+            capture.ua_platform = capture
+                .platform
+                .as_deref()
+                .and_then(ua_platform_of)
+                .map(str::to_string);
+        }
+        if capture.user_agent.is_none() || capture.ua_platform.is_none() {
+            return Err(ManifoldError::InvalidArg(
+                "reference fingerprint: user_agent and a known platform are required".into(),
+            ));
+        }
+        let reference = ReferenceFingerprint {
+            id: Uuid::new_v4().to_string(),
+            label: label
+                .filter(|l| !l.trim().is_empty())
+                .or_else(|| capture.ua_platform.clone())
+                .unwrap_or_default(),
+            created_at: Utc::now(),
+            capture,
+        };
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO reference_fingerprints (id, label, created_at, data) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    reference.id,
+                    reference.label,
+                    reference.created_at.to_rfc3339(),
+                    serde_json::to_string(&reference.capture)?,
+                ],
+            )?;
+            Ok(())
+        })?;
+        Ok(reference)
+    }
+
+    pub fn list(&self) -> Result<Vec<ReferenceFingerprint>> {
+        let rows: Vec<(String, String, String, String)> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, label, created_at, data FROM reference_fingerprints ORDER BY created_at",
+            )?;
+            let rows = stmt
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+        rows.into_iter()
+            .map(|(id, label, created_at, data)| {
+                Ok(ReferenceFingerprint {
+                    id,
+                    label,
+                    created_at: DateTime::parse_from_rfc3339(&created_at)
+                        .map_err(|e| ManifoldError::Other(e.to_string()))?
+                        .with_timezone(&Utc),
+                    capture: serde_json::from_str(&data)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<ReferenceFingerprint> {
+        self.list()?
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("no reference fingerprint {id:?}")))
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM reference_fingerprints WHERE id = ?1",
+                params![id],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+/// `navigator.platform` → UA-CH platform, for browsers without UA-CH.
+fn ua_platform_of(platform: &str) -> Option<&'static str> {
+    if platform.starts_with("Win") {
+        Some("Windows")
+    } else if platform.starts_with("Mac") {
+        Some("macOS")
+    } else if platform.starts_with("Linux") {
+        Some("Linux")
+    } else {
+        None
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::FingerprintOrchestrator;
+
+    #[test]
+    fn compares_against_references_of_the_same_platform() {
+        let repo = ReferenceRepo::new(Db::open_in_memory().unwrap());
+        let fp = FingerprintOrchestrator::generate(11);
+
+        let mut same = serde_json::to_value(capture_of(&fp)).unwrap();
+        same["webgl_renderer"] = "Some Other GPU".into();
+        same["unknown_key"] = true.into();
+        repo.ingest(Some("desk".into()), same).unwrap();
+        let other_platform = if fp.ua_platform == "Windows" {
+            "MacIntel"
+        } else {
+            "Win32"
+        };
+        repo.ingest(
+            None,
+            serde_json::json!({ "user_agent": fp.user_agent, "platform": other_platform }),
+        )
+        .unwrap();
+        assert!(repo
+            .ingest(None, serde_json::json!({ "screen_width": 1 }))
+            .is_err());
+
+        let corpus = repo.list().unwrap();
+        assert_eq!(corpus.len(), 2);
+        assert_ne!(corpus[1].label, "");
+
+        let cmp = compare(&fp, &corpus);
+        assert_eq!(cmp.references, 1);
+        let gpu = cmp
+            .fields
+            .iter()
+            .find(|f| f.field == "webgl_renderer")
+            .unwrap();
+        assert_eq!((gpu.seen_in, gpu.captured_in), (0, 1));
+        let nearest = cmp.nearest.unwrap();
+        assert_eq!(nearest.label, "desk");
+        assert_eq!(nearest.mismatched, vec!["webgl_renderer".to_string()]);
+        let score = cmp.score.unwrap();
+        assert!(score > 0.8 && score < 1.0, "{score}");
+
+        repo.delete(&corpus[0].id).unwrap();
+        assert!(compare(&fp, &repo.list().unwrap()).score.is_none());
+    }
+
+    #[test]
+    fn profile_from_capture_clones_the_device_and_follows_the_proxy() {
+        let db = Db::open_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = ProfileRepo::new_with_root(db.clone(), dir.path().into());
+        let refs = ReferenceRepo::new(db.clone());
+        let reference = refs
+            .ingest(
+                Some("laptop".into()),
+                serde_json::json!({
+                    "user_agent": "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36",
+                    "platform": "Win32",
+                    "ua_platform": "Windows",
+                    "ua_platform_version": "15.0.0",
+                    "hardware_concurrency": 12,
+                    "screen_width": 2560,
+                    "screen_height": 1440,
+                    "webgl_renderer": "ANGLE (NVIDIA, NVIDIA GeForce RTX 4070 Direct3D11 vs_5_0 ps_5_0, D3D11)",
+                    "timezone": "Europe/Paris",
+                    "languages": ["fr-FR", "fr", "en"],
+                }),
+            )
+            .unwrap();
+        let request = |proxy_id: Option<&str>| CaptureProfileRequest {
+            reference_id: reference.id.clone(),
+            name: "clone".into(),
+            seed: Some(5),
+            proxy_id: proxy_id.map(str::to_string),
+            notes: None,
+            tags: None,
+            behavior_profile: None,
+        };
+
+        let fp = create_from_capture(&profiles, &reference, request(None))
+            .unwrap()
+            .fingerprint;
+        assert_eq!(fp.hardware_concurrency, 12);
+        assert_eq!((fp.screen_width, fp.screen_height), (2560, 1440));
+        assert!(fp.viewport_height < 1440);
+        assert!(fp.webgl_renderer.contains("RTX 4070"));
+        assert_eq!(fp.accept_language, "fr-FR,fr;q=0.9,en;q=0.8");
+        assert_eq!(fp.timezone, "Europe/Paris");
+        assert!(fp.ua_brands.iter().any(|b| b.version == "131"));
+        // Fonts came from a Windows 11 fingerprint
+        assert!(fp
+            .font_subset
+            .iter()
+            .all(|f| crate::fonts::foreign_font(OsRelease::Windows11, f).is_none()));
+
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO proxies (id, name, proxy_type, host, port, healthy, country) VALUES ('px1','de','http','h',1,0,'DE')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        let fp = create_from_capture(&profiles, &reference, request(Some("px1")))
+            .unwrap()
+            .fingerprint;
+        assert_eq!(fp.timezone, "Europe/Berlin");
+        assert_eq!(fp.screen_width, 2560);
+    }
+}
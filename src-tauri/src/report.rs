@@ -0,0 +1,322 @@
+// ── Manifold workspace report ─────────────────────────────────────────────────
+//
+// Read-only export of the open workspace for compliance reporting and
+// spreadsheet analysis: profiles, proxies and the event log, either as three
+// CSV files in a directory or as one JSON document.
+//
+// Only a selected set of columns is exported.  Proxy credentials, the
+// fingerprint and persona blobs and profile notes never leave the database;
+// the report says what exists and what happened, not how to reproduce it.
+//
+// Tables are read `PAGE_SIZE` rows at a time by rowid and each page is
+// written out before the next is read, so memory stays flat however long the
+// event log is and the database lock is only held for one page at a time.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use rusqlite::params;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::Result;
+
+/// Rows read per query.
+const PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// `profiles.csv`, `proxies.csv` and `events.csv` in a directory.
+    Csv,
+    /// One JSON file with a `profiles`, `proxies` and `events` array.
+    Json,
+}
+
+/// What `export_workspace_report` wrote.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceReport {
+    pub format: ReportFormat,
+    pub files: Vec<PathBuf>,
+    pub profiles: u64,
+    pub proxies: u64,
+    pub events: u64,
+}
+
+/// One exported table: its name and the columns taken from it.
+struct TableSpec {
+    name: &'static str,
+    columns: &'static [&'static str],
+    /// Columns holding JSON text, embedded as JSON in the JSON report.
+    json_columns: &'static [&'static str],
+}
+
+const PROFILES: TableSpec = TableSpec {
+    name: "profiles",
+    columns: &[
+        "id",
+        "name",
+        "status",
+        "proxy_id",
+        "tags",
+        "auto_age",
+        "disk_quota_mb",
+        "created_at",
+        "last_used",
+    ],
+    json_columns: &["tags"],
+};
+
+const PROXIES: TableSpec = TableSpec {
+    name: "proxies",
+    columns: &[
+        "id",
+        "name",
+        "proxy_type",
+        "host",
+        "port",
+        "country",
+        "anonymity",
+        "healthy",
+        "latency_ms",
+        "throughput_kbps",
+        "last_checked",
+    ],
+    json_columns: &[],
+};
+
+const EVENTS: TableSpec = TableSpec {
+    name: "events",
+    columns: &[
+        "id",
+        "profile_id",
+        "kind",
+        "severity",
+        "domain",
+        "detail",
+        "created_at",
+    ],
+    json_columns: &["detail"],
+};
+
+// ── Export ────────────────────────────────────────────────────────────────────
+
+/// Write the report to `path`: a directory (created if missing) for CSV, a
+/// file for JSON.
+pub fn export(db: &Db, format: ReportFormat, path: &Path) -> Result<WorkspaceReport> {
+    match format {
+        ReportFormat::Csv => export_csv(db, path),
+        ReportFormat::Json => export_json(db, path),
+    }
+}
+
+fn export_csv(db: &Db, dir: &Path) -> Result<WorkspaceReport> {
+    std::fs::create_dir_all(dir)?;
+    let mut files = Vec::new();
+    let mut counts = [0u64; 3];
+    for (spec, count) in [&PROFILES, &PROXIES, &EVENTS].into_iter().zip(&mut counts) {
+        let file = dir.join(format!("{}.csv", spec.name));
+        let mut out = BufWriter::new(File::create(&file)?);
+        writeln!(out, "{}", spec.columns.join(","))?;
+        *count = for_each_row(db, spec, |row| {
+            let cells: Vec<String> = row.iter().map(csv_cell).collect();
+            writeln!(out, "{}", cells.join(","))?;
+            Ok(())
+        })?;
+        out.flush()?;
+        files.push(file);
+    }
+    let [profiles, proxies, events] = counts;
+    Ok(WorkspaceReport {
+        format: ReportFormat::Csv,
+        files,
+        profiles,
+        proxies,
+        events,
+    })
+}
+
+fn export_json(db: &Db, file: &Path) -> Result<WorkspaceReport> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut out = BufWriter::new(File::create(file)?);
+    write!(
+        out,
+        "{{\"exported_at\":{}",
+        serde_json::to_string(&Utc::now().to_rfc3339())?
+    )?;
+    let mut counts = [0u64; 3];
+    for (spec, count) in [&PROFILES, &PROXIES, &EVENTS].into_iter().zip(&mut counts) {
+        write!(out, ",\"{}\":[", spec.name)?;
+        let mut first = true;
+        *count = for_each_row(db, spec, |row| {
+            if !std::mem::take(&mut first) {
+                write!(out, ",")?;
+            }
+            let object: serde_json::Map<String, serde_json::Value> = spec
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(col, value)| (col.to_string(), json_value(spec, col, value)))
+                .collect();
+            serde_json::to_writer(&mut out, &object)?;
+            Ok(())
+        })?;
+        write!(out, "]")?;
+    }
+    writeln!(out, "}}")?;
+    out.flush()?;
+    let [profiles, proxies, events] = counts;
+    Ok(WorkspaceReport {
+        format: ReportFormat::Json,
+        files: vec![file.to_path_buf()],
+        profiles,
+        proxies,
+        events,
+    })
+}
+
+/// Call `f` with every row of `spec`'s columns, in rowid order, one page at
+/// a time.  Returns the number of rows.
+fn for_each_row(
+    db: &Db,
+    spec: &TableSpec,
+    mut f: impl FnMut(&[Value]) -> Result<()>,
+) -> Result<u64> {
+    let sql = format!(
+        "SELECT rowid, {} FROM {} WHERE rowid > ?1 ORDER BY rowid LIMIT ?2",
+        spec.columns.join(", "),
+        spec.name
+    );
+    let mut after = i64::MIN;
+    let mut total = 0;
+    loop {
+        let page: Vec<(i64, Vec<Value>)> = db.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(&sql)?;
+            let rows = stmt
+                .query_map(params![after, PAGE_SIZE], |r| {
+                    let values = (1..=spec.columns.len())
+                        .map(|i| r.get::<_, Value>(i))
+                        .collect::<rusqlite::Result<Vec<_>>>()?;
+                    Ok((r.get(0)?, values))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows)
+        })?;
+        let Some(&(last, _)) = page.last() else {
+            return Ok(total);
+        };
+        for (_, row) in &page {
+            f(row)?;
+        }
+        total += page.len() as u64;
+        after = last;
+    }
+}
+
+// ── Cell encoding ─────────────────────────────────────────────────────────────
+
+/// RFC 4180 field.  Text that a spreadsheet would read as a formula gets a
+/// leading apostrophe, so exported names and details can't run as formulas.
+pub(crate) fn csv_cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::Integer(i) => return i.to_string(),
+        Value::Real(f) => return f.to_string(),
+        Value::Text(s) => s.clone(),
+        Value::Blob(b) => hex::encode(b),
+    };
+    let text = if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{text}")
+    } else {
+        text
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn json_value(spec: &TableSpec, column: &str, value: &Value) -> serde_json::Value {
+    if let (Value::Text(s), true) = (value, spec.json_columns.contains(&column)) {
+        if let Ok(parsed) = serde_json::from_str(s) {
+            return parsed;
+        }
+    }
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(i) => (*i).into(),
+        Value::Real(f) => (*f).into(),
+        Value::Text(s) => s.clone().into(),
+        Value::Blob(b) => hex::encode(b).into(),
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_db(events: usize) -> Db {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, tags, created_at) VALUES ('p1', '=HYPERLINK(\"x\")', '{}', '[\"shop\"]', '2025-01-01T00:00:00+00:00')",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO proxies (id, name, proxy_type, host, port, username, password_enc) VALUES ('x1', 'res, DE', 'http', '10.0.0.1', 8080, 'user', 'secret')",
+                [],
+            )?;
+            for i in 0..events {
+                conn.execute(
+                    "INSERT INTO events (profile_id, kind, detail, created_at) VALUES ('p1', 'launch', ?1, '2025-01-01T00:00:00+00:00')",
+                    params![format!("{{\"n\":{i}}}")],
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn csv_pages_through_every_row_and_escapes_cells() {
+        let db = seeded_db(PAGE_SIZE as usize * 2 + 7);
+        let dir = tempfile::tempdir().unwrap();
+        let report = export(&db, ReportFormat::Csv, dir.path()).unwrap();
+        assert_eq!((report.profiles, report.proxies), (1, 1));
+        assert_eq!(report.events, PAGE_SIZE as u64 * 2 + 7);
+
+        let events = std::fs::read_to_string(dir.path().join("events.csv")).unwrap();
+        assert_eq!(events.lines().count() as u64, report.events + 1);
+        let proxies = std::fs::read_to_string(dir.path().join("proxies.csv")).unwrap();
+        assert!(proxies.contains("\"res, DE\""));
+        assert!(!proxies.contains("secret") && !proxies.contains("user,"));
+        let profiles = std::fs::read_to_string(dir.path().join("profiles.csv")).unwrap();
+        assert!(profiles.contains("\"'=HYPERLINK(\"\"x\"\")\""));
+    }
+
+    #[test]
+    fn json_is_one_document_with_embedded_json_columns() {
+        let db = seeded_db(3);
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out/report.json");
+        let report = export(&db, ReportFormat::Json, &file).unwrap();
+        assert_eq!(report.files, vec![file.clone()]);
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(doc["profiles"][0]["tags"][0], "shop");
+        assert_eq!(doc["events"].as_array().unwrap().len(), 3);
+        assert_eq!(doc["events"][2]["detail"]["n"], 2);
+        assert_eq!(doc["proxies"][0]["port"], 8080);
+        assert!(doc["proxies"][0].get("password_enc").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+// ── Manifold request header values ────────────────────────────────────────────
+//
+// Next to their order (header_order.rs), the values Chrome puts in its
+// content-negotiation headers are version-specific: `zstd` joined
+// Accept-Encoding in Chrome 123, and every request destination has its own
+// fixed Accept string.  A profile claiming Chrome 130 that still offers
+// `gzip, deflate, br` — or fetches images with `*/*` — is recognisable from
+// one request.  This module generates the values for the profile's major,
+// ships them in the launch config, and the bridge's header route applies
+// them to what Playwright sends.
+//
+// Fetch metadata is Chrome's own and mostly correct already; the navigation
+// values here only fill requests where Playwright dropped them.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine_quirks::chrome_major;
+use crate::fingerprint::Fingerprint;
+
+/// First Chrome major that offers zstd content encoding.
+const ZSTD_SINCE: u32 = 123;
+
+/// `Accept` of documents, top-level or framed.
+const DOCUMENT_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,\
+     image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7";
+
+/// `Accept` by `sec-fetch-dest`.  `empty` (fetch/XHR) is left alone: pages
+/// set their own and Chrome's default is `*/*` anyway.
+const ACCEPT_BY_DEST: &[(&str, &str)] = &[
+    ("document", DOCUMENT_ACCEPT),
+    ("iframe", DOCUMENT_ACCEPT),
+    (
+        "image",
+        "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8",
+    ),
+    ("style", "text/css,*/*;q=0.1"),
+    ("script", "*/*"),
+    ("font", "*/*"),
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// `sec-fetch-*` values of one kind of request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchMetadata {
+    pub site: String,
+    pub mode: String,
+    /// `?1` on user-activated navigations; absent otherwise.
+    pub user: Option<String>,
+    pub dest: String,
+}
+
+/// The header values Chrome sends for the profile's major.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestHeaderProfile {
+    pub chrome_major: Option<u32>,
+    /// `Accept-Encoding` of every request.
+    pub accept_encoding: String,
+    /// `Accept` by `sec-fetch-dest`.
+    pub accept: BTreeMap<String, String>,
+    /// Fetch metadata of a typed-in top-level navigation.
+    pub navigation: FetchMetadata,
+}
+
+// ── Generation ────────────────────────────────────────────────────────────────
+
+/// `Accept-Encoding` of Chrome `major` (unknown majors get the current one).
+pub fn accept_encoding(major: Option<u32>) -> &'static str {
+    if major.is_none_or(|m| m >= ZSTD_SINCE) {
+        "gzip, deflate, br, zstd"
+    } else {
+        "gzip, deflate, br"
+    }
+}
+
+/// The request header values of the Chrome major in `fp.user_agent`.
+pub fn request_header_profile(fp: &Fingerprint) -> RequestHeaderProfile {
+    let major = chrome_major(fp);
+    RequestHeaderProfile {
+        chrome_major: major,
+        accept_encoding: accept_encoding(major).into(),
+        accept: ACCEPT_BY_DEST
+            .iter()
+            .map(|&(dest, accept)| (dest.to_string(), accept.to_string()))
+            .collect(),
+        navigation: FetchMetadata {
+            site: "none".into(),
+            mode: "navigate".into(),
+            user: Some("?1".into()),
+            dest: "document".into(),
+        },
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fingerprint::{with_chrome_version, FingerprintOrchestrator};
+
+    #[test]
+    fn zstd_follows_chrome_major() {
+        let mut fp = FingerprintOrchestrator::generate(7);
+        fp.user_agent = with_chrome_version(&fp.user_agent, "122.0.6261.94");
+        let old = request_header_profile(&fp);
+        assert_eq!(old.chrome_major, Some(122));
+        assert_eq!(old.accept_encoding, "gzip, deflate, br");
+
+        fp.user_agent = with_chrome_version(&fp.user_agent, "123.0.0.0");
+        let new = request_header_profile(&fp);
+        assert_eq!(new.accept_encoding, "gzip, deflate, br, zstd");
+        assert!(new.accept["document"].starts_with("text/html,"));
+        assert!(!new.accept["document"].contains(' '));
+        assert_eq!(new.accept["style"], "text/css,*/*;q=0.1");
+        assert!(!new.accept.contains_key("empty"));
+        assert_eq!(accept_encoding(None), "gzip, deflate, br, zstd");
+    }
+}
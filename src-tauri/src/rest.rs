@@ -0,0 +1,327 @@
+// ── Manifold rest-period recommendations ──────────────────────────────────────
+//
+// A profile that just hit a captcha wall or a block on a site should not be
+// sent straight back to it: coming back within minutes looks like the same
+// automation retrying, and escalates a soft flag into a ban.  The detection
+// events the bridge and frontend record give each profile a "last
+// detection"; from the incidents of the past week this module derives how
+// long the profile should rest before its next launch against a domain.
+//
+// The rest is the base of the latest incident's severity, stretched for
+// sites known to hold grudges and doubled for every earlier incident within
+// a day of it (the same escalation as proxy domain cooldowns), then counted
+// from the latest incident.  Launch schedulers read `rest_until`.
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::db::Db;
+use crate::error::Result;
+use crate::proxy::normalize_domain;
+
+/// Incidents older than this don't count.
+const WINDOW_DAYS: i64 = 7;
+
+/// Incidents this close to the latest one escalate its rest.
+const ESCALATION_HOURS: i64 = 24;
+
+/// Longest rest ever recommended.
+const MAX_REST_HOURS: i64 = 72;
+
+/// Sites that remember flagged devices for long, with the factor applied to
+/// the rest and why.  Matched on the domain or any parent of it.
+const DOMAIN_HEURISTICS: &[(&str, f64, &str)] = &[
+    (
+        "google.com",
+        2.0,
+        "Google keeps risk scores per device for days",
+    ),
+    (
+        "amazon.com",
+        2.0,
+        "Amazon links devices across flagged sessions",
+    ),
+    (
+        "facebook.com",
+        3.0,
+        "Meta escalates repeat checkpoints to bans",
+    ),
+    (
+        "instagram.com",
+        3.0,
+        "Meta escalates repeat checkpoints to bans",
+    ),
+    (
+        "linkedin.com",
+        3.0,
+        "LinkedIn restricts accounts after repeat checks",
+    ),
+    (
+        "ticketmaster.com",
+        2.0,
+        "queue protection remembers flagged devices",
+    ),
+    (
+        "nike.com",
+        2.0,
+        "release protection remembers flagged devices",
+    ),
+];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// A detection event, as used for the recommendation.
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub at: DateTime<Utc>,
+    pub severity: String,
+    pub domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestRecommendation {
+    pub profile_id: String,
+    /// The domain the recommendation is for; `None` covers every domain.
+    pub domain: Option<String>,
+    /// The profile's most recent detection on any domain.
+    pub last_detection: Option<Incident>,
+    /// Incidents on `domain` within the past week.
+    pub incidents: u32,
+    pub rest_minutes: i64,
+    /// When the next launch against `domain` is fine; `None` without
+    /// incidents.
+    pub rest_until: Option<DateTime<Utc>>,
+    /// `rest_until` has passed (or there is nothing to rest from).
+    pub ready: bool,
+    /// What the rest was derived from, for display.
+    pub reasons: Vec<String>,
+}
+
+/// Rest after a single incident of `severity`.
+fn base_rest(severity: &str) -> Duration {
+    match severity {
+        "info" => Duration::minutes(15),
+        "critical" => Duration::hours(6),
+        // "warning" and anything unrecognised
+        _ => Duration::hours(1),
+    }
+}
+
+/// The stretch factor and reason for `domain`, if it is a known site.
+fn domain_factor(domain: &str) -> Option<(f64, &'static str)> {
+    DOMAIN_HEURISTICS
+        .iter()
+        .find(|(site, _, _)| {
+            domain == *site
+                || domain
+                    .strip_suffix(site)
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+        .map(|&(_, factor, reason)| (factor, reason))
+}
+
+/// Recommend a rest from `incidents` (any order) at time `now`.
+pub fn recommend(
+    profile_id: &str,
+    domain: Option<&str>,
+    last_detection: Option<Incident>,
+    incidents: &[Incident],
+    now: DateTime<Utc>,
+) -> RestRecommendation {
+    let mut rec = RestRecommendation {
+        profile_id: profile_id.into(),
+        domain: domain.map(str::to_string),
+        last_detection,
+        incidents: incidents.len() as u32,
+        rest_minutes: 0,
+        rest_until: None,
+        ready: true,
+        reasons: Vec::new(),
+    };
+    let Some(latest) = incidents.iter().max_by_key(|i| i.at) else {
+        return rec;
+    };
+
+    let mut rest = base_rest(&latest.severity);
+    rec.reasons.push(format!(
+        "latest incident is {}: {} min",
+        latest.severity,
+        rest.num_minutes()
+    ));
+    let earlier = incidents
+        .iter()
+        .filter(|i| i.at < latest.at && latest.at - i.at <= Duration::hours(ESCALATION_HOURS))
+        .count() as u32;
+    if earlier > 0 {
+        rest = rest * (1 << earlier.min(10));
+        rec.reasons.push(format!(
+            "{earlier} more incident(s) within {ESCALATION_HOURS} h: doubled per incident"
+        ));
+    }
+    if let Some((factor, why)) = latest.domain.as_deref().and_then(domain_factor) {
+        rest = Duration::seconds((rest.num_seconds() as f64 * factor) as i64);
+        rec.reasons.push(format!("×{factor}: {why}"));
+    }
+    if rest > Duration::hours(MAX_REST_HOURS) {
+        rest = Duration::hours(MAX_REST_HOURS);
+        rec.reasons.push(format!("capped at {MAX_REST_HOURS} h"));
+    }
+
+    let until = latest.at + rest;
+    rec.rest_minutes = rest.num_minutes();
+    rec.rest_until = Some(until);
+    rec.ready = until <= now;
+    rec
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct RestRepo {
+    db: Db,
+}
+
+impl RestRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// The rest `profile_id` should take before launching against `domain`
+    /// (any domain when `None`).
+    pub fn recommend(&self, profile_id: &str, domain: Option<&str>) -> Result<RestRecommendation> {
+        let domain = domain.and_then(normalize_domain);
+        let now = Utc::now();
+        let since = now - Duration::days(WINDOW_DAYS);
+        let rows: Vec<(String, String, Option<String>)> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT created_at, severity, domain FROM events
+                   WHERE profile_id = ?1 AND kind = 'detection'
+                   ORDER BY id DESC"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id], |r| {
+                    Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+
+        let all: Vec<Incident> = rows
+            .into_iter()
+            .filter_map(|(at, severity, domain)| {
+                Some(Incident {
+                    at: DateTime::parse_from_rfc3339(&at).ok()?.with_timezone(&Utc),
+                    severity,
+                    domain: domain.as_deref().and_then(normalize_domain),
+                })
+            })
+            .collect();
+        let last_detection = all.first().cloned();
+        let recent: Vec<Incident> = all
+            .into_iter()
+            .filter(|i| i.at >= since)
+            .filter(|i| domain.is_none() || i.domain == domain)
+            .collect();
+        Ok(recommend(
+            profile_id,
+            domain.as_deref(),
+            last_detection,
+            &recent,
+            now,
+        ))
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventKind, EventRepo, NewEvent};
+
+    fn incident(minutes_ago: i64, severity: &str, domain: &str) -> Incident {
+        Incident {
+            at: Utc::now() - Duration::minutes(minutes_ago),
+            severity: severity.into(),
+            domain: Some(domain.into()),
+        }
+    }
+
+    #[test]
+    fn repeat_incidents_and_strict_sites_stretch_the_rest() {
+        let now = Utc::now();
+        let one = recommend(
+            "p1",
+            None,
+            None,
+            &[incident(10, "warning", "shop.test")],
+            now,
+        );
+        assert_eq!(one.rest_minutes, 60);
+        assert!(!one.ready);
+
+        let repeated = [
+            incident(10, "warning", "shop.test"),
+            incident(120, "info", "shop.test"),
+            incident(60 * 30, "critical", "shop.test"),
+        ];
+        // Only the incident within 24 h of the latest one escalates
+        assert_eq!(
+            recommend("p1", None, None, &repeated, now).rest_minutes,
+            120
+        );
+
+        let google = recommend(
+            "p1",
+            None,
+            None,
+            &[incident(10, "critical", "accounts.google.com")],
+            now,
+        );
+        assert_eq!(google.rest_minutes, 12 * 60);
+        assert!(google.reasons.iter().any(|r| r.contains("Google")));
+        assert!(domain_factor("notgoogle.com").is_none());
+
+        let storm: Vec<Incident> = (0..8).map(|i| incident(i, "critical", "x.test")).collect();
+        assert_eq!(
+            recommend("p1", None, None, &storm, now).rest_minutes,
+            MAX_REST_HOURS * 60
+        );
+
+        let old = recommend("p1", None, None, &[incident(120, "info", "x.test")], now);
+        assert!(old.ready);
+        assert!(recommend("p1", None, None, &[], now).rest_until.is_none());
+    }
+
+    #[test]
+    fn recommendation_reads_the_profiles_detections_for_the_domain() {
+        let db = Db::open_in_memory().unwrap();
+        let events = EventRepo::new(db.clone());
+        for (kind, domain) in [
+            (EventKind::Detection, "https://www.shop.test/cart"),
+            (EventKind::Launch, "shop.test"),
+            (EventKind::Detection, "other.test"),
+        ] {
+            events
+                .record(NewEvent {
+                    profile_id: Some("p1".into()),
+                    kind,
+                    severity: Some("warning".into()),
+                    domain: Some(domain.into()),
+                    detail: None,
+                })
+                .unwrap();
+        }
+
+        let repo = RestRepo::new(db);
+        let rec = repo.recommend("p1", Some("shop.test")).unwrap();
+        assert_eq!(rec.incidents, 1);
+        assert_eq!(rec.rest_minutes, 60);
+        assert_eq!(
+            rec.last_detection.unwrap().domain.as_deref(),
+            Some("other.test")
+        );
+        assert_eq!(repo.recommend("p1", None).unwrap().incidents, 2);
+        assert!(repo.recommend("p2", None).unwrap().ready);
+    }
+}
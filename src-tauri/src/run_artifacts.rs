@@ -0,0 +1,325 @@
+// ── Manifold workflow run artifacts ───────────────────────────────────────────
+//
+// Everything needed to debug a workflow run after the fact, bundled into one
+// archive per run: the step report, the outputs, the bridge's log lines and,
+// for every step that finally failed, a screenshot of the page and an
+// excerpt of the requests that led up to it.
+//
+// Archives are JSON files under the profile's sessions dir
+// (`sessions/runs/<run_id>.json`, screenshots as base64 PNG) so they go with
+// the profile and stay out of the session bundle list.  The `workflow_runs`
+// table indexes them.
+//
+// The archive never holds the bound steps, so vault secrets filled into a
+// run stay out of it; the bridge drops cookie and authorization header
+// values from the request excerpt.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::Db;
+use crate::error::{Context, ManifoldError, Result};
+use crate::workflow::{StepPhase, WorkflowRunReport, WorkflowRunStatus};
+
+/// Version of the archive JSON.
+pub const ARCHIVE_FORMAT: u32 = 1;
+
+/// Requests kept in a failure's excerpt, most recent last.
+pub const HAR_EXCERPT_ENTRIES: usize = 50;
+
+/// Log lines kept per run; older ones are dropped first.
+pub const MAX_LOG_LINES: usize = 2_000;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// The page as it was when a step gave up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureCapture {
+    pub phase: StepPhase,
+    pub index: usize,
+    pub error: Option<String>,
+    pub url: Option<String>,
+    /// Base64 PNG of the viewport; `None` when the page couldn't be shot.
+    pub screenshot: Option<String>,
+    /// The last requests, as the bridge's compact HAR records.
+    pub har: Vec<Value>,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// The archive of one run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunArchive {
+    pub format: u32,
+    pub workflow_name: String,
+    pub report: WorkflowRunReport,
+    pub failures: Vec<FailureCapture>,
+    pub log: Vec<String>,
+}
+
+/// A row of `workflow_runs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub workflow_id: String,
+    pub profile_id: String,
+    pub status: WorkflowRunStatus,
+    pub failures: u32,
+    pub archive_path: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// The bridge's log lines for a run, shared with the threads reading its
+/// output.
+#[derive(Debug, Clone, Default)]
+pub struct RunLog(Arc<Mutex<VecDeque<String>>>);
+
+impl RunLog {
+    pub fn push(&self, line: impl Into<String>) {
+        let mut lines = self.0.lock().unwrap();
+        if lines.len() == MAX_LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.into());
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+// ── Archive ───────────────────────────────────────────────────────────────────
+
+/// Where a run's archive lives under `profiles_root`.
+pub fn archive_path(profiles_root: &Path, profile_id: &str, run_id: &str) -> PathBuf {
+    profiles_root
+        .join(profile_id)
+        .join("sessions")
+        .join("runs")
+        .join(format!("{run_id}.json"))
+}
+
+/// Write the archive under `profiles_root` and return its index row.
+pub fn write_archive(profiles_root: &Path, archive: &RunArchive) -> Result<WorkflowRun> {
+    let report = &archive.report;
+    let path = archive_path(profiles_root, &report.profile_id, &report.run_id);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("failed to create runs dir")?;
+    }
+    std::fs::write(&path, serde_json::to_vec(archive)?).context("failed to write run archive")?;
+    Ok(WorkflowRun {
+        id: report.run_id.clone(),
+        workflow_id: report.workflow_id.clone(),
+        profile_id: report.profile_id.clone(),
+        status: report.status,
+        failures: archive.failures.len() as u32,
+        archive_path: path.to_string_lossy().into_owned(),
+        started_at: report.started_at,
+        finished_at: report.finished_at,
+    })
+}
+
+pub fn read_archive(run: &WorkflowRun) -> Result<RunArchive> {
+    let bytes = std::fs::read(&run.archive_path).context("failed to read run archive")?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+type RunRow = (String, String, String, String, u32, String, String, String);
+
+pub struct WorkflowRunRepo {
+    db: Db,
+}
+
+impl WorkflowRunRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn record(&self, run: &WorkflowRun) -> Result<()> {
+        let status = serde_json::to_value(run.status)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO workflow_runs
+                       (id, workflow_id, profile_id, status, failures, archive_path,
+                        started_at, finished_at)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+                params![
+                    run.id,
+                    run.workflow_id,
+                    run.profile_id,
+                    status.as_str(),
+                    run.failures,
+                    run.archive_path,
+                    run.started_at.to_rfc3339(),
+                    run.finished_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Runs newest first, optionally of one profile and/or workflow.
+    pub fn list(
+        &self,
+        profile_id: Option<&str>,
+        workflow_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WorkflowRun>> {
+        let rows: Vec<RunRow> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT id, workflow_id, profile_id, status, failures, archive_path,
+                          started_at, finished_at
+                   FROM workflow_runs
+                   WHERE (?1 IS NULL OR profile_id = ?1)
+                     AND (?2 IS NULL OR workflow_id = ?2)
+                   ORDER BY started_at DESC
+                   LIMIT ?3"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id, workflow_id, limit], row)?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+        rows.into_iter().map(from_row).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<WorkflowRun> {
+        let found: Option<RunRow> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    r#"SELECT id, workflow_id, profile_id, status, failures, archive_path,
+                              started_at, finished_at
+                       FROM workflow_runs WHERE id = ?1"#,
+                    params![id],
+                    row,
+                )
+                .optional()?)
+        })?;
+        let found = found.ok_or_else(|| ManifoldError::InvalidArg(format!("no run {id:?}")))?;
+        from_row(found)
+    }
+}
+
+fn row(r: &rusqlite::Row<'_>) -> rusqlite::Result<RunRow> {
+    Ok((
+        r.get(0)?,
+        r.get(1)?,
+        r.get(2)?,
+        r.get(3)?,
+        r.get(4)?,
+        r.get(5)?,
+        r.get(6)?,
+        r.get(7)?,
+    ))
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(s)
+        .map_err(|e| ManifoldError::Other(e.to_string()))?
+        .with_timezone(&Utc))
+}
+
+fn from_row(
+    (id, workflow_id, profile_id, status, failures, archive_path, started_at, finished_at): RunRow,
+) -> Result<WorkflowRun> {
+    Ok(WorkflowRun {
+        id,
+        workflow_id,
+        profile_id,
+        status: serde_json::from_value(Value::String(status))?,
+        failures,
+        archive_path,
+        started_at: parse_time(&started_at)?,
+        finished_at: parse_time(&finished_at)?,
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+    use crate::workflow::{StepOutcome, StepStatus};
+    use serde_json::json;
+
+    #[test]
+    fn archives_are_written_per_profile_and_indexed() {
+        let db = Db::open_in_memory().unwrap();
+        let profile = ProfileRepo::new(db.clone())
+            .create(CreateProfileRequest {
+                name: "shop".into(),
+                seed: Some(2),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                persona: None,
+            })
+            .unwrap();
+        let root = std::env::temp_dir().join(format!("manifold-runs-{}", uuid::Uuid::new_v4()));
+
+        let log = RunLog::default();
+        for i in 0..MAX_LOG_LINES + 2 {
+            log.push(format!("line {i}"));
+        }
+        let now = Utc::now();
+        let archive = RunArchive {
+            format: ARCHIVE_FORMAT,
+            workflow_name: "checkout".into(),
+            report: WorkflowRunReport {
+                run_id: "run-1".into(),
+                workflow_id: "wf".into(),
+                profile_id: profile.id.clone(),
+                status: WorkflowRunStatus::Aborted,
+                steps: vec![StepOutcome {
+                    phase: StepPhase::Main,
+                    index: 0,
+                    name: None,
+                    status: StepStatus::Failed,
+                    attempts: 1,
+                    selector: None,
+                    error: Some("timeout".into()),
+                }],
+                outputs: Vec::new(),
+                started_at: now,
+                finished_at: now,
+            },
+            failures: vec![FailureCapture {
+                phase: StepPhase::Main,
+                index: 0,
+                error: Some("timeout".into()),
+                url: Some("https://shop.example/cart".into()),
+                screenshot: Some("iVBORw0KGgo=".into()),
+                har: vec![json!({"method": "GET", "status": 502})],
+                captured_at: now,
+            }],
+            log: log.lines(),
+        };
+        assert_eq!(archive.log.len(), MAX_LOG_LINES);
+        assert_eq!(archive.log[0], "line 2");
+
+        let run = write_archive(&root, &archive).unwrap();
+        assert!(run.archive_path.ends_with("runs/run-1.json"));
+        let repo = WorkflowRunRepo::new(db.clone());
+        repo.record(&run).unwrap();
+
+        let stored = repo.get("run-1").unwrap();
+        assert_eq!(stored, run);
+        assert_eq!(read_archive(&stored).unwrap().failures, archive.failures);
+        assert_eq!(repo.list(Some(&profile.id), None, 10).unwrap(), [run]);
+        assert!(repo.list(None, Some("other"), 10).unwrap().is_empty());
+
+        ProfileRepo::new(db).delete(&profile.id).unwrap();
+        assert!(repo.get("run-1").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,222 @@
+// ── Manifold run outputs ──────────────────────────────────────────────────────
+//
+// What a workflow run captured from its pages.  Extraction steps (text,
+// attributes, tables) hand back a JSON value each; the run keeps them in
+// capture order under the step's key and stores them in `run_outputs` once
+// it ends, whether or not it succeeded — a run that fails half-way still
+// keeps what it read before the failure.
+//
+// Exports are JSON (the values as captured) or CSV with one row per value:
+// a step that read a list or a table gives one row per item, and items that
+// aren't plain text (table rows) are written as JSON in the value cell.
+
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+use rusqlite::types::Value as SqlValue;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::report::{csv_cell, ReportFormat};
+
+const CSV_COLUMNS: [&str; 6] = ["run_id", "key", "step", "item", "value", "captured_at"];
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// One value captured by an extraction step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOutput {
+    pub key: String,
+    /// Index of the step in its phase.
+    pub step: usize,
+    /// A string, a list of strings or a table (list of rows).
+    pub value: Value,
+    pub captured_at: DateTime<Utc>,
+}
+
+/// The JSON export of a run's outputs.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutputs {
+    pub run_id: String,
+    pub workflow_id: Option<String>,
+    pub profile_id: Option<String>,
+    pub outputs: Vec<RunOutput>,
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct RunOutputRepo {
+    db: Db,
+}
+
+impl RunOutputRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Store everything a run captured, in order.
+    pub fn save(
+        &self,
+        run_id: &str,
+        workflow_id: &str,
+        profile_id: &str,
+        outputs: &[RunOutput],
+    ) -> Result<()> {
+        self.db.with_conn(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for (seq, output) in outputs.iter().enumerate() {
+                tx.execute(
+                    r#"INSERT INTO run_outputs
+                           (run_id, seq, workflow_id, profile_id, key, step, value, captured_at)
+                       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+                    params![
+                        run_id,
+                        seq as i64,
+                        workflow_id,
+                        profile_id,
+                        output.key,
+                        output.step as i64,
+                        serde_json::to_string(&output.value)?,
+                        output.captured_at.to_rfc3339(),
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// A run's outputs in capture order, with the workflow and profile the
+    /// run belonged to (`None` when it captured nothing).
+    pub fn get(&self, run_id: &str) -> Result<RunOutputs> {
+        type Row = (String, String, String, i64, String, String);
+        let rows: Vec<Row> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                r#"SELECT workflow_id, profile_id, key, step, value, captured_at
+                   FROM run_outputs WHERE run_id = ?1 ORDER BY seq"#,
+            )?;
+            let rows = stmt
+                .query_map(params![run_id], |r| {
+                    Ok((
+                        r.get(0)?,
+                        r.get(1)?,
+                        r.get(2)?,
+                        r.get(3)?,
+                        r.get(4)?,
+                        r.get(5)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+        let (workflow_id, profile_id) = rows
+            .first()
+            .map(|r| (Some(r.0.clone()), Some(r.1.clone())))
+            .unwrap_or_default();
+        let outputs = rows
+            .into_iter()
+            .map(|(_, _, key, step, value, captured_at)| {
+                Ok(RunOutput {
+                    key,
+                    step: step as usize,
+                    value: serde_json::from_str(&value)?,
+                    captured_at: DateTime::parse_from_rfc3339(&captured_at)
+                        .map_err(|e| ManifoldError::Other(e.to_string()))?
+                        .with_timezone(&Utc),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(RunOutputs {
+            run_id: run_id.into(),
+            workflow_id,
+            profile_id,
+            outputs,
+        })
+    }
+}
+
+// ── Export ────────────────────────────────────────────────────────────────────
+
+/// A run's outputs as JSON or CSV.
+pub fn export(outputs: &RunOutputs, format: ReportFormat) -> Result<String> {
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(outputs)?),
+        ReportFormat::Csv => Ok(to_csv(outputs)),
+    }
+}
+
+fn to_csv(outputs: &RunOutputs) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+    for output in &outputs.outputs {
+        // A list gives one row per item; a single value is item 0
+        let items: Vec<&Value> = match &output.value {
+            Value::Array(items) => items.iter().collect(),
+            value => vec![value],
+        };
+        for (item, value) in items.into_iter().enumerate() {
+            let value = match value {
+                Value::Null => SqlValue::Null,
+                Value::String(s) => SqlValue::Text(s.clone()),
+                other => SqlValue::Text(other.to_string()),
+            };
+            let row = [
+                SqlValue::Text(outputs.run_id.clone()),
+                SqlValue::Text(output.key.clone()),
+                SqlValue::Integer(output.step as i64),
+                SqlValue::Integer(item as i64),
+                value,
+                SqlValue::Text(output.captured_at.to_rfc3339()),
+            ];
+            let cells: Vec<String> = row.iter().map(csv_cell).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn outputs_round_trip_and_export_one_csv_row_per_item() {
+        let repo = RunOutputRepo::new(Db::open_in_memory().unwrap());
+        let captured_at = Utc::now();
+        let outputs = vec![
+            RunOutput {
+                key: "balance".into(),
+                step: 3,
+                value: json!("€1,024.50"),
+                captured_at,
+            },
+            RunOutput {
+                key: "orders".into(),
+                step: 4,
+                value: json!([{"id": "A1", "total": "12"}, {"id": "A2", "total": "7"}]),
+                captured_at,
+            },
+        ];
+        repo.save("run-1", "wf", "p1", &outputs).unwrap();
+
+        let stored = repo.get("run-1").unwrap();
+        assert_eq!(stored.outputs, outputs);
+        assert_eq!(stored.profile_id.as_deref(), Some("p1"));
+        assert!(repo.get("other").unwrap().outputs.is_empty());
+
+        let csv = export(&stored, ReportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4, "{csv}");
+        assert!(lines[1].contains("\"€1,024.50\""));
+        assert!(lines[3].contains(r#""{""id"":""A2"",""total"":""7""}""#));
+
+        let json: Value =
+            serde_json::from_str(&export(&stored, ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["outputs"][1]["value"][0]["id"], "A1");
+    }
+}
@@ -0,0 +1,330 @@
+// ── Manifold session records ──────────────────────────────────────────────────
+//
+// One row in `sessions` per browser launch.  The row is opened when the
+// bridge is spawned and closed when it is stopped (normally or via panic
+// shutdown), which gives the dashboard real session durations.
+
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::behavior_audit::BehaviorAudit;
+use crate::db::{Db, Page};
+use crate::error::Result;
+use crate::launch_mode::LaunchMode;
+
+/// A session during which the host clock jumped.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlaggedSession {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub clock_jumps: u32,
+}
+
+/// A session with how its browser was shown.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// `None` for sessions from before launch modes were recorded.
+    pub launch_mode: Option<LaunchMode>,
+}
+
+/// A session's behavior audit.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedSession {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub audit: BehaviorAudit,
+}
+
+pub struct SessionRepo {
+    db: Db,
+}
+
+impl SessionRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Open a new session row for `profile_id` and return its id.
+    pub fn start(&self, profile_id: &str) -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO sessions (id, profile_id, started_at) VALUES (?1, ?2, ?3)",
+                params![id, profile_id, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+        Ok(id)
+    }
+
+    /// How many sessions `profile_id` has had.
+    pub fn count(&self, profile_id: &str) -> Result<u64> {
+        self.db.with_conn(|conn| {
+            let n: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM sessions WHERE profile_id = ?1",
+                params![profile_id],
+                |r| r.get(0),
+            )?;
+            Ok(n as u64)
+        })
+    }
+
+    /// Close every still-open session of `profile_id`.  Returns how many rows
+    /// were closed (0 is not an error — the profile may never have launched).
+    pub fn end_open(&self, profile_id: &str) -> Result<usize> {
+        self.db.with_conn(|conn| {
+            let n = conn.execute(
+                "UPDATE sessions SET ended_at = ?1 WHERE profile_id = ?2 AND ended_at IS NULL",
+                params![Utc::now().to_rfc3339(), profile_id],
+            )?;
+            Ok(n)
+        })
+    }
+
+    /// Count a clock jump against the session.
+    pub fn flag_clock_jump(&self, session_id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE sessions SET clock_jumps = clock_jumps + 1 WHERE id = ?1",
+                params![session_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Record the mode the session's browser was launched in.
+    pub fn set_launch_mode(&self, session_id: &str, mode: LaunchMode) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE sessions SET launch_mode = ?1 WHERE id = ?2",
+                params![mode.to_string(), session_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The profile's sessions, newest first.
+    pub fn list(&self, profile_id: &str, page: &Page) -> Result<Vec<SessionSummary>> {
+        let parse = |s: String| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+        };
+        self.db.with_conn(|conn| {
+            page.check_cursor(conn, "sessions")?;
+            let mut stmt = conn.prepare(
+                r#"SELECT id, started_at, ended_at, launch_mode FROM sessions
+                   WHERE profile_id = ?1
+                     AND (?2 IS NULL
+                          OR (started_at, id) < (SELECT started_at, id FROM sessions WHERE id = ?2))
+                   ORDER BY started_at DESC, id DESC
+                   LIMIT ?3"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id, page.after, page.sql_limit()], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, Option<String>>(2)?,
+                        r.get::<_, Option<String>>(3)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows
+                .into_iter()
+                .map(|(id, started, ended, mode)| SessionSummary {
+                    id,
+                    started_at: parse(started).unwrap_or_else(Utc::now),
+                    ended_at: ended.and_then(parse),
+                    launch_mode: mode.and_then(|m| m.parse().ok()),
+                })
+                .collect())
+        })
+    }
+
+    pub fn save_behavior_audit(&self, session_id: &str, audit: &BehaviorAudit) -> Result<()> {
+        let json = serde_json::to_string(audit)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE sessions SET behavior_audit = ?1 WHERE id = ?2",
+                params![json, session_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The profile's audited sessions, newest first.
+    pub fn behavior_audits(&self, profile_id: &str, page: &Page) -> Result<Vec<AuditedSession>> {
+        self.db.with_conn(|conn| {
+            page.check_cursor(conn, "sessions")?;
+            let mut stmt = conn.prepare(
+                r#"SELECT id, started_at, behavior_audit FROM sessions
+                   WHERE profile_id = ?1 AND behavior_audit IS NOT NULL
+                     AND (?2 IS NULL
+                          OR (started_at, id) < (SELECT started_at, id FROM sessions WHERE id = ?2))
+                   ORDER BY started_at DESC, id DESC
+                   LIMIT ?3"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id, page.after, page.sql_limit()], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, String>(2)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|(id, started, json)| {
+                    Some(AuditedSession {
+                        id,
+                        started_at: DateTime::parse_from_rfc3339(&started)
+                            .ok()?
+                            .with_timezone(&Utc),
+                        audit: serde_json::from_str(&json).ok()?,
+                    })
+                })
+                .collect())
+        })
+    }
+
+    /// The profile's sessions with at least one clock jump, newest first.
+    pub fn clock_flagged(&self, profile_id: &str, page: &Page) -> Result<Vec<FlaggedSession>> {
+        let parse = |s: String| {
+            DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+        };
+        self.db.with_conn(|conn| {
+            page.check_cursor(conn, "sessions")?;
+            let mut stmt = conn.prepare(
+                r#"SELECT id, started_at, ended_at, clock_jumps FROM sessions
+                   WHERE profile_id = ?1 AND clock_jumps > 0
+                     AND (?2 IS NULL
+                          OR (started_at, id) < (SELECT started_at, id FROM sessions WHERE id = ?2))
+                   ORDER BY started_at DESC, id DESC
+                   LIMIT ?3"#,
+            )?;
+            let rows = stmt
+                .query_map(params![profile_id, page.after, page.sql_limit()], |r| {
+                    Ok((
+                        r.get::<_, String>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, Option<String>>(2)?,
+                        r.get::<_, u32>(3)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(rows
+                .into_iter()
+                .map(|(id, started, ended, clock_jumps)| FlaggedSession {
+                    id,
+                    started_at: parse(started).unwrap_or_else(Utc::now),
+                    ended_at: ended.and_then(parse),
+                    clock_jumps,
+                })
+                .collect())
+        })
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_repo() -> SessionRepo {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, created_at) VALUES ('p1','p','{}','2025-01-01T00:00:00+00:00')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        SessionRepo::new(db)
+    }
+
+    #[test]
+    fn start_then_end_closes_session() {
+        let repo = make_repo();
+        repo.start("p1").unwrap();
+        assert_eq!(repo.end_open("p1").unwrap(), 1);
+        // Second stop finds nothing open
+        assert_eq!(repo.end_open("p1").unwrap(), 0);
+    }
+
+    #[test]
+    fn sessions_record_their_launch_mode() {
+        let repo = make_repo();
+        let old = repo.start("p1").unwrap();
+        let headed = repo.start("p1").unwrap();
+        repo.set_launch_mode(&headed, LaunchMode::Headed).unwrap();
+        let modes: Vec<_> = repo
+            .list("p1", &Page::default())
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.id, s.launch_mode))
+            .collect();
+        assert!(modes.contains(&(headed, Some(LaunchMode::Headed))));
+        assert!(modes.contains(&(old, None)));
+    }
+
+    #[test]
+    fn clock_jumps_flag_the_session() {
+        let repo = make_repo();
+        let quiet = repo.start("p1").unwrap();
+        let jumped = repo.start("p1").unwrap();
+        repo.flag_clock_jump(&jumped).unwrap();
+        repo.flag_clock_jump(&jumped).unwrap();
+        let flagged = repo.clock_flagged("p1", &Page::default()).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].id, jumped);
+        assert_eq!(flagged[0].clock_jumps, 2);
+        assert_ne!(flagged[0].id, quiet);
+    }
+
+    #[test]
+    fn behavior_audits_are_stored_per_session() {
+        let repo = make_repo();
+        let unaudited = repo.start("p1").unwrap();
+        let audited = repo.start("p1").unwrap();
+        repo.save_behavior_audit(&audited, &crate::behavior_audit::audit(&[]))
+            .unwrap();
+        let audits = repo.behavior_audits("p1", &Page::default()).unwrap();
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].id, audited);
+        assert_eq!(audits[0].audit.score, None);
+        assert_ne!(audits[0].id, unaudited);
+    }
+
+    #[test]
+    fn flagged_sessions_page_by_cursor() {
+        let repo = make_repo();
+        for _ in 0..5 {
+            let id = repo.start("p1").unwrap();
+            repo.flag_clock_jump(&id).unwrap();
+        }
+        let all = repo.clock_flagged("p1", &Page::default()).unwrap();
+        let first = repo.clock_flagged("p1", &Page::new(Some(2), None)).unwrap();
+        let rest = repo
+            .clock_flagged("p1", &Page::new(None, Some(first[1].id.clone())))
+            .unwrap();
+        let paged: Vec<&str> = first.iter().chain(&rest).map(|s| s.id.as_str()).collect();
+        let expected: Vec<&str> = all.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(paged, expected);
+        assert_eq!(all.len(), 5);
+
+        let gone = Page::new(None, Some("deleted".into()));
+        assert!(repo.clock_flagged("p1", &gone).is_err());
+    }
+}
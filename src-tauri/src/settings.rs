@@ -0,0 +1,315 @@
+// ── Manifold app settings ─────────────────────────────────────────────────────
+//
+// Typed view over the `settings` table.  Core app settings (ports, check
+// concurrency) live under one key; the sections other modules already
+// keep under their own keys — proxy check targets, launch environment, power,
+// disk quota, cache prewarming, the priors source, notifications and costs —
+// are gathered into the same `Settings` value so the frontend reads and edits
+// everything through one pair of commands.
+// Updates are JSON merge patches checked against the typed schema: a
+// misspelt key is an error instead of a silently ignored field.
+//
+// The generic `save_data` / `load_data` blobs the frontend persists live here
+// too (under `data:<key>`), replacing the loose `<key>.json` files they used
+// to be written to; those are imported once at startup.
+
+use std::path::Path;
+
+use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::cache_prewarm::{CachePrewarmSettings, CACHE_PREWARM_KEY};
+use crate::cost::{CostSettings, COST_SETTINGS_KEY};
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::launch_env::{LaunchEnvSettings, LAUNCH_ENV_KEY};
+use crate::notifications::NotificationSettings;
+use crate::power::{PowerSettings, POWER_SETTINGS_KEY};
+use crate::priors::PriorsSource;
+use crate::proxy::ProxyRepo;
+use crate::quota::{QuotaSettings, QUOTA_SETTINGS_KEY};
+
+/// `settings` key holding `AppSettings` (JSON).
+pub const APP_SETTINGS_KEY: &str = "app";
+
+/// Prefix of the keys holding frontend `save_data` blobs.
+const DATA_KEY_PREFIX: &str = "data:";
+
+/// Suffix given to a key file once imported.
+const MIGRATED_SUFFIX: &str = "migrated";
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// Core app settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppSettings {
+    /// Bridge WebSocket port used at startup.
+    pub bridge_port: u16,
+    /// TLS bridge port used at startup.
+    pub tls_bridge_port: u16,
+    /// Proxies health-checked in parallel by `check_all_proxies`.
+    pub proxy_check_concurrency: u32,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            bridge_port: 8766,
+            tls_bridge_port: 8767,
+            proxy_check_concurrency: 4,
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.bridge_port < 1024 || self.tls_bridge_port < 1024 {
+            return Err(ManifoldError::InvalidArg("ports must be >= 1024".into()));
+        }
+        if self.bridge_port == self.tls_bridge_port {
+            return Err(ManifoldError::InvalidArg(
+                "bridge and TLS bridge ports must differ".into(),
+            ));
+        }
+        if self.proxy_check_concurrency == 0 {
+            return Err(ManifoldError::InvalidArg(
+                "proxy check concurrency must be at least 1".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Every setting, as returned by `get_settings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub app: AppSettings,
+    /// App-wide proxy health-check targets.
+    pub proxy_check_targets: Vec<String>,
+    pub launch_env: LaunchEnvSettings,
+    pub power: PowerSettings,
+    pub disk_quota: QuotaSettings,
+    pub cache_prewarm: CachePrewarmSettings,
+    /// Where newer population priors are fetched from.
+    pub priors_source: PriorsSource,
+    pub notifications: NotificationSettings,
+    pub costs: CostSettings,
+}
+
+// ── Raw access ────────────────────────────────────────────────────────────────
+
+/// A JSON value from `settings`; the default when unset or unreadable.
+pub fn load<T: DeserializeOwned + Default>(db: &Db, key: &str) -> Result<T> {
+    Ok(load_raw(db, key)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+pub fn store<T: Serialize>(db: &Db, key: &str, value: &T) -> Result<()> {
+    store_raw(db, key, &serde_json::to_string(value)?)
+}
+
+fn load_raw(db: &Db, key: &str) -> Result<Option<String>> {
+    db.with_conn(|conn| {
+        Ok(conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |r| r.get(0),
+            )
+            .optional()?)
+    })
+}
+
+fn store_raw(db: &Db, key: &str, value: &str) -> Result<()> {
+    db.with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    })
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct SettingsRepo {
+    db: Db,
+}
+
+impl SettingsRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn app(&self) -> Result<AppSettings> {
+        load(&self.db, APP_SETTINGS_KEY)
+    }
+
+    pub fn get(&self) -> Result<Settings> {
+        Ok(Settings {
+            app: self.app()?,
+            proxy_check_targets: ProxyRepo::new(self.db.clone()).default_check_targets()?,
+            launch_env: load(&self.db, LAUNCH_ENV_KEY)?,
+            power: load(&self.db, POWER_SETTINGS_KEY)?,
+            disk_quota: load(&self.db, QUOTA_SETTINGS_KEY)?,
+            cache_prewarm: load(&self.db, CACHE_PREWARM_KEY)?,
+            priors_source: crate::priors::source(&self.db)?,
+            notifications: crate::notifications::load_settings(&self.db)?,
+            costs: load(&self.db, COST_SETTINGS_KEY)?,
+        })
+    }
+
+    /// Apply a JSON merge patch (RFC 7396) to the current settings.  The
+    /// result must match the schema and pass validation before anything is
+    /// written.
+    pub fn update(&self, patch: &serde_json::Value) -> Result<Settings> {
+        let mut merged = serde_json::to_value(self.get()?)?;
+        merge_patch(&mut merged, patch);
+        let next: Settings = serde_json::from_value(merged)
+            .map_err(|e| ManifoldError::InvalidArg(format!("settings: {e}")))?;
+        next.app.validate()?;
+        next.launch_env.validate()?;
+        next.cache_prewarm.validate()?;
+        next.priors_source.validate()?;
+        next.notifications.validate()?;
+        next.costs.validate()?;
+
+        ProxyRepo::new(self.db.clone()).set_default_check_targets(&next.proxy_check_targets)?;
+        store(&self.db, APP_SETTINGS_KEY, &next.app)?;
+        store(&self.db, LAUNCH_ENV_KEY, &next.launch_env)?;
+        store(&self.db, POWER_SETTINGS_KEY, &next.power)?;
+        store(&self.db, QUOTA_SETTINGS_KEY, &next.disk_quota)?;
+        store(&self.db, CACHE_PREWARM_KEY, &next.cache_prewarm)?;
+        crate::priors::set_source(&self.db, &next.priors_source)?;
+        crate::notifications::store_settings(&self.db, &next.notifications)?;
+        store(&self.db, COST_SETTINGS_KEY, &next.costs)?;
+        self.get()
+    }
+
+    // ── Frontend data ─────────────────────────────────────────────────────────
+
+    pub fn load_data(&self, key: &str) -> Result<Option<String>> {
+        load_raw(&self.db, &data_key(key)?)
+    }
+
+    pub fn save_data(&self, key: &str, data: &str) -> Result<()> {
+        store_raw(&self.db, &data_key(key)?, data)
+    }
+
+    /// Import `<key>.json` files written by older builds' `save_data` from
+    /// `dir`.  Each imported file is renamed to `<key>.json.migrated`; keys
+    /// already in the DB win.  Returns the number of files imported.
+    pub fn migrate_key_files(&self, dir: &Path) -> Result<usize> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut imported = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if self.load_data(key)?.is_none() {
+                self.save_data(key, &std::fs::read_to_string(&path)?)?;
+                imported += 1;
+            }
+            let mut done = path.clone().into_os_string();
+            done.push(format!(".{MIGRATED_SUFFIX}"));
+            std::fs::rename(&path, done)?;
+        }
+        Ok(imported)
+    }
+}
+
+fn data_key(key: &str) -> Result<String> {
+    if key.is_empty() || key.len() > 128 {
+        return Err(ManifoldError::InvalidArg(format!(
+            "invalid data key {key:?}"
+        )));
+    }
+    Ok(format!("{DATA_KEY_PREFIX}{key}"))
+}
+
+/// RFC 7396: objects merge recursively, `null` removes, anything else
+/// replaces.
+fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let Some(patch) = patch.as_object() else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(Default::default());
+    }
+    let target = target.as_object_mut().expect("made an object above");
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(
+                target.entry(key.clone()).or_insert(serde_json::Value::Null),
+                value,
+            );
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn defaults_until_updated_and_patch_merges() {
+        let repo = SettingsRepo::new(Db::open_in_memory().unwrap());
+        let s = repo.get().unwrap();
+        assert_eq!(s.app, AppSettings::default());
+        assert!(!s.proxy_check_targets.is_empty());
+
+        let s = repo
+            .update(&json!({
+                "app": { "proxy_check_concurrency": 8 },
+                "power": { "resume_on_wake": true },
+            }))
+            .unwrap();
+        assert_eq!(s.app.proxy_check_concurrency, 8);
+        assert_eq!(s.app.bridge_port, 8766, "untouched fields are kept");
+        assert!(s.power.resume_on_wake);
+        assert_eq!(repo.get().unwrap(), s);
+    }
+
+    #[test]
+    fn unknown_keys_and_invalid_values_are_rejected() {
+        let repo = SettingsRepo::new(Db::open_in_memory().unwrap());
+        let err = repo.update(&json!({ "app": { "bridge_prot": 9000 } }));
+        assert!(matches!(err, Err(ManifoldError::InvalidArg(_))));
+        let err = repo.update(&json!({ "app": { "tls_bridge_port": 8766 } }));
+        assert!(matches!(err, Err(ManifoldError::InvalidArg(_))));
+        // Nothing was written
+        assert_eq!(repo.app().unwrap(), AppSettings::default());
+    }
+
+    #[test]
+    fn key_files_are_imported_once() {
+        let repo = SettingsRepo::new(Db::open_in_memory().unwrap());
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("sources.json"), "[1,2]").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "skip").unwrap();
+
+        assert_eq!(repo.migrate_key_files(dir.path()).unwrap(), 1);
+        assert_eq!(repo.load_data("sources").unwrap().as_deref(), Some("[1,2]"));
+        assert!(dir.path().join("sources.json.migrated").exists());
+        assert_eq!(repo.migrate_key_files(dir.path()).unwrap(), 0);
+    }
+}
@@ -0,0 +1,731 @@
+// ── Manifold profile sharing ──────────────────────────────────────────────────
+//
+// A share bundle hands a profile to another Manifold user: the fingerprint,
+// behavior tier, persona, notes and tags always travel, along with the
+// proxies it routes through.  What else goes in is up to the sender —
+// `ShareOptions` strips proxy credentials and the browser's cookie jar by
+// default, so a bundle can be passed around without handing over logins.
+//
+// Import validates the whole bundle before writing anything, then fills in
+// what was stripped from the receiver's side: a shared proxy that matches
+// one the receiver already has (same type, host and port) is reused with
+// the receiver's credentials, others are added and reported as needing
+// credentials, and a profile without cookies starts from an empty browser.
+//
+// Launch environment, disk quota and SSH key paths are specific to the
+// sender's machine and never exported.
+//
+// Cookie values are encrypted with the `os_crypt` key in `Local State`,
+// and that key only opens where it was made: on Windows it's wrapped with
+// DPAPI for the sending account, and elsewhere the browser's fixed
+// `--password-store=basic` / `--use-mock-keychain` key differs per OS.  A
+// bundle's cookies are therefore tagged with their origin, and import
+// refuses them anywhere else — sharing cookies only works between profiles
+// of the same account on the same machine (or the same OS outside Windows).
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ManifoldError, Result};
+use crate::fingerprint::Fingerprint;
+use crate::human::HumanBehavior;
+use crate::persona::Persona;
+use crate::profile::{
+    BatchProfilePatch, CreateProfileRequest, Profile, ProfileRepo, ProfileSelection, ProfileStatus,
+    UpdateProfileRequest,
+};
+use crate::proxy::{normalize_host, AddProxyRequest, Proxy, ProxyRepo, ProxyType};
+
+/// Bundle layout version; bumped on incompatible changes.
+pub const SHARE_FORMAT: u32 = 1;
+
+/// Chromium's cookie databases, relative to the user-data dir.  The only
+/// files a bundle may carry besides `Local State`.
+const COOKIE_FILES: &[&str] = &["Default/Cookies", "Default/Network/Cookies"];
+
+/// Holds the key the cookie values are encrypted with.  Only `os_crypt` is
+/// exported; the rest identifies the sender's install.
+const LOCAL_STATE: &str = "Local State";
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// What `share_profile` leaves out.  Both default to stripped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShareOptions {
+    /// Drop proxy usernames and passwords.
+    pub strip_proxy_credentials: bool,
+    /// Leave the browser's cookie jar behind.
+    pub strip_cookies: bool,
+}
+
+impl Default for ShareOptions {
+    fn default() -> Self {
+        Self {
+            strip_proxy_credentials: true,
+            strip_cookies: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareBundle {
+    pub format: u32,
+    pub exported_at: DateTime<Utc>,
+    pub profile: SharedProfile,
+    pub proxies: Vec<SharedProxy>,
+    /// Empty when cookies were stripped.
+    #[serde(default)]
+    pub cookies: Vec<SharedFile>,
+    /// Where `cookies` can be decrypted; see `cookie_origin`.  Set whenever
+    /// cookies are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookie_origin: Option<String>,
+    pub stripped: ShareOptions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedProfile {
+    pub name: String,
+    pub fingerprint: Fingerprint,
+    pub human: HumanBehavior,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub tls_bridge: Option<bool>,
+    pub auto_age: bool,
+    pub persona: Option<Persona>,
+    /// Sender-side proxy ids, resolved against `ShareBundle::proxies`.
+    pub proxy_id: Option<String>,
+    pub proxy_chain: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedProxy {
+    /// The sender's id; only links the profile to this entry.
+    pub id: String,
+    pub name: String,
+    pub proxy_type: ProxyType,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub country: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedFile {
+    /// Relative to the user-data dir, `/`-separated.
+    pub path: String,
+    /// Base64 file contents.
+    pub data: String,
+}
+
+/// What `share_profile` wrote.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareSummary {
+    pub path: PathBuf,
+    pub proxies: usize,
+    pub cookie_files: usize,
+    pub stripped: ShareOptions,
+}
+
+/// What `import_shared_profile` created.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedShare {
+    pub profile: Profile,
+    /// Local proxies the bundle's proxies were matched to.
+    pub reused_proxies: Vec<String>,
+    pub created_proxies: Vec<String>,
+    /// Created proxies whose credentials were stripped; set them before
+    /// launching.
+    pub needs_credentials: Vec<String>,
+}
+
+// ── Export ────────────────────────────────────────────────────────────────────
+
+/// Build `id`'s bundle and write it to `path`.
+pub fn export(
+    profiles: &ProfileRepo,
+    proxies: &ProxyRepo,
+    id: &str,
+    options: &ShareOptions,
+    path: &Path,
+) -> Result<ShareSummary> {
+    let bundle = build(profiles, proxies, id, options)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&bundle)?)?;
+    Ok(ShareSummary {
+        path: path.to_path_buf(),
+        proxies: bundle.proxies.len(),
+        cookie_files: bundle.cookies.len(),
+        stripped: bundle.stripped,
+    })
+}
+
+fn build(
+    profiles: &ProfileRepo,
+    proxies: &ProxyRepo,
+    id: &str,
+    options: &ShareOptions,
+) -> Result<ShareBundle> {
+    let profile = profiles.get(id)?;
+
+    let mut ids: Vec<&String> = profile.proxy_id.iter().collect();
+    ids.extend(&profile.proxy_chain);
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    let shared_proxies = ids
+        .into_iter()
+        .map(|id| Ok(share_proxy(proxies.get(id)?, options)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let cookies = if options.strip_cookies {
+        Vec::new()
+    } else {
+        // A running browser holds the jar open mid-write
+        if matches!(
+            profile.status,
+            ProfileStatus::Running | ProfileStatus::Suspended
+        ) {
+            return Err(ManifoldError::InvalidArg(
+                "stop the profile before sharing its cookies".into(),
+            ));
+        }
+        read_cookies(Path::new(&profile.data_dir))?
+    };
+    let origin = (!cookies.is_empty()).then(cookie_origin);
+
+    Ok(ShareBundle {
+        format: SHARE_FORMAT,
+        exported_at: Utc::now(),
+        profile: SharedProfile {
+            name: profile.name,
+            fingerprint: profile.fingerprint,
+            human: profile.human,
+            notes: profile.notes,
+            tags: profile.tags,
+            tls_bridge: profile.tls_bridge,
+            auto_age: profile.auto_age,
+            persona: profile.persona,
+            proxy_id: profile.proxy_id,
+            proxy_chain: profile.proxy_chain,
+        },
+        proxies: shared_proxies,
+        cookies,
+        cookie_origin: origin,
+        stripped: options.clone(),
+    })
+}
+
+fn share_proxy(proxy: Proxy, options: &ShareOptions) -> SharedProxy {
+    let (username, password) = if options.strip_proxy_credentials {
+        (None, None)
+    } else {
+        (proxy.username, proxy.password)
+    };
+    SharedProxy {
+        id: proxy.id,
+        name: proxy.name,
+        proxy_type: proxy.proxy_type,
+        host: proxy.host,
+        port: proxy.port,
+        username,
+        password,
+        country: proxy.country,
+        region: proxy.region,
+    }
+}
+
+fn read_cookies(data_dir: &Path) -> Result<Vec<SharedFile>> {
+    let mut files = Vec::new();
+    for rel in COOKIE_FILES {
+        match std::fs::read(data_dir.join(rel)) {
+            Ok(bytes) => files.push(SharedFile {
+                path: rel.to_string(),
+                data: B64.encode(bytes),
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if files.is_empty() {
+        return Ok(files);
+    }
+
+    let local_state = match std::fs::read_to_string(data_dir.join(LOCAL_STATE)) {
+        Ok(raw) => serde_json::from_str::<serde_json::Value>(&raw).ok(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    if let Some(os_crypt) = local_state.and_then(|s| s.get("os_crypt").cloned()) {
+        let state = serde_json::json!({ "os_crypt": os_crypt });
+        files.push(SharedFile {
+            path: LOCAL_STATE.into(),
+            data: B64.encode(serde_json::to_vec(&state)?),
+        });
+    }
+    Ok(files)
+}
+
+/// Identifies where this machine's browser cookies can be decrypted: the
+/// OS, plus on Windows a hash of the machine and account DPAPI binds the
+/// `os_crypt` key to.
+fn cookie_origin() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        let machine = std::process::Command::new("reg")
+            .args([
+                "query",
+                r"HKLM\SOFTWARE\Microsoft\Cryptography",
+                "/v",
+                "MachineGuid",
+            ])
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .unwrap_or_default();
+        let home = dirs::home_dir().unwrap_or_default();
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(machine.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(home.to_string_lossy().as_bytes());
+        format!("windows:{}", &hasher.finalize().to_hex()[..32])
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        std::env::consts::OS.to_string()
+    }
+}
+
+// ── Import ────────────────────────────────────────────────────────────────────
+
+/// Read and validate the bundle at `path`, then create its profile and any
+/// proxies the receiver doesn't have yet.
+pub fn import(profiles: &ProfileRepo, proxies: &ProxyRepo, path: &Path) -> Result<ImportedShare> {
+    let raw = std::fs::read_to_string(path)?;
+    let bundle: ShareBundle = serde_json::from_str(&raw)
+        .map_err(|e| ManifoldError::InvalidArg(format!("not a share bundle: {e}")))?;
+    let cookies = validate(&bundle, &cookie_origin())?;
+
+    let existing = proxies.list()?;
+    let mut local_ids: BTreeMap<&str, String> = BTreeMap::new();
+    let mut reused_proxies = Vec::new();
+    let mut created_proxies = Vec::new();
+    for shared in &bundle.proxies {
+        if let Some(local) = existing.iter().find(|p| same_endpoint(p, shared)) {
+            local_ids.insert(&shared.id, local.id.clone());
+            reused_proxies.push(local.id.clone());
+            continue;
+        }
+        let added = proxies.add(AddProxyRequest {
+            name: shared.name.clone(),
+            proxy_type: shared.proxy_type.to_string(),
+            host: shared.host.clone(),
+            port: shared.port,
+            username: shared.username.clone(),
+            password: shared.password.clone(),
+            country: shared.country.clone(),
+            region: shared.region.clone(),
+            ssh_key_path: None,
+            tags: None,
+            notes: None,
+        });
+        match added {
+            Ok(p) => {
+                local_ids.insert(&shared.id, p.id.clone());
+                created_proxies.push(p.id);
+            }
+            Err(e) => {
+                discard_proxies(proxies, &created_proxies);
+                return Err(e);
+            }
+        }
+    }
+
+    let profile = create_profile(profiles, &bundle.profile, &local_ids, &cookies);
+    let profile = match profile {
+        Ok(p) => p,
+        Err(e) => {
+            discard_proxies(proxies, &created_proxies);
+            return Err(e);
+        }
+    };
+    let needs_credentials = if bundle.stripped.strip_proxy_credentials {
+        created_proxies.clone()
+    } else {
+        Vec::new()
+    };
+    Ok(ImportedShare {
+        profile,
+        reused_proxies,
+        created_proxies,
+        needs_credentials,
+    })
+}
+
+/// Create the shared profile with its proxy ids mapped to local ones, and
+/// write its cookie files.  Nothing is left behind on failure.
+fn create_profile(
+    profiles: &ProfileRepo,
+    shared: &SharedProfile,
+    local_ids: &BTreeMap<&str, String>,
+    cookies: &[(String, Vec<u8>)],
+) -> Result<Profile> {
+    let created = profiles.create(CreateProfileRequest {
+        name: shared.name.clone(),
+        seed: Some(shared.fingerprint.seed),
+        proxy_id: shared
+            .proxy_id
+            .as_ref()
+            .map(|id| local_ids[id.as_str()].clone()),
+        notes: Some(shared.notes.clone()),
+        tags: Some(shared.tags.clone()),
+        behavior_profile: None,
+        persona: shared.persona.clone(),
+    })?;
+    let chain: Vec<String> = shared
+        .proxy_chain
+        .iter()
+        .map(|id| local_ids[id.as_str()].clone())
+        .collect();
+
+    let finish = || -> Result<Profile> {
+        // `create` derives the fingerprint from the seed; take the shared one
+        let mut profile = profiles.update(
+            &created.id,
+            UpdateProfileRequest {
+                name: None,
+                fingerprint: Some(shared.fingerprint.clone()),
+                human: Some(shared.human.clone()),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                tls_bridge: None,
+                persona: None,
+            },
+        )?;
+        if shared.tls_bridge.is_some() {
+            // `update` leaves the TLS bridge alone; only a batch patch sets it
+            let set = profiles.batch_update(
+                ProfileSelection::Ids(vec![profile.id.clone()]),
+                BatchProfilePatch {
+                    tls_bridge: shared.tls_bridge,
+                    ..BatchProfilePatch::default()
+                },
+            )?;
+            if !set.applied {
+                let error = set.results.into_iter().find_map(|r| r.error);
+                return Err(ManifoldError::Other(format!(
+                    "failed to set the TLS bridge: {}",
+                    error.unwrap_or_default()
+                )));
+            }
+            profile.tls_bridge = shared.tls_bridge;
+        }
+        profiles.set_auto_age(&profile.id, shared.auto_age)?;
+        profile.auto_age = shared.auto_age;
+        if !chain.is_empty() {
+            profiles.set_proxy_chain(&profile.id, &chain)?;
+            profile.proxy_chain = chain.clone();
+        }
+        let data_dir = Path::new(&profile.data_dir);
+        for (rel, bytes) in cookies {
+            let file = data_dir.join(rel);
+            if let Some(parent) = file.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(file, bytes)?;
+        }
+        Ok(profile)
+    };
+    finish().inspect_err(|_| {
+        profiles.delete(&created.id).ok();
+    })
+}
+
+/// Check everything that could fail half-way through an import.  `origin`
+/// is this machine's `cookie_origin`.  Returns the decoded cookie files.
+fn validate(bundle: &ShareBundle, origin: &str) -> Result<Vec<(String, Vec<u8>)>> {
+    if bundle.format != SHARE_FORMAT {
+        return Err(ManifoldError::InvalidArg(format!(
+            "unsupported share bundle format {}",
+            bundle.format
+        )));
+    }
+    let profile = &bundle.profile;
+    if profile.name.trim().is_empty() {
+        return Err(ManifoldError::InvalidArg(
+            "shared profile has no name".into(),
+        ));
+    }
+    profile.human.validate_strict()?;
+
+    let mut ids = HashSet::new();
+    for proxy in &bundle.proxies {
+        normalize_host(&proxy.host)?;
+        if proxy.port == 0 {
+            return Err(ManifoldError::InvalidArg(format!(
+                "shared proxy {} has port 0",
+                proxy.name
+            )));
+        }
+        if !ids.insert(proxy.id.as_str()) {
+            return Err(ManifoldError::InvalidArg(format!(
+                "shared proxy id {} appears twice",
+                proxy.id
+            )));
+        }
+    }
+    for id in profile.proxy_id.iter().chain(&profile.proxy_chain) {
+        if !ids.contains(id.as_str()) {
+            return Err(ManifoldError::InvalidArg(format!(
+                "shared profile uses proxy {id}, which the bundle doesn't include"
+            )));
+        }
+    }
+
+    if bundle.stripped.strip_cookies && !bundle.cookies.is_empty() {
+        return Err(ManifoldError::InvalidArg(
+            "bundle says cookies were stripped but carries cookie files".into(),
+        ));
+    }
+    if !bundle.cookies.is_empty() && bundle.cookie_origin.as_deref() != Some(origin) {
+        return Err(ManifoldError::InvalidArg(
+            "the bundle's cookies are encrypted for another machine or account and \
+             can't be read here; ask for a bundle with cookies stripped"
+                .into(),
+        ));
+    }
+    bundle
+        .cookies
+        .iter()
+        .map(|file| {
+            // Fixed names only: nothing in a bundle picks where files land
+            if file.path != LOCAL_STATE && !COOKIE_FILES.contains(&file.path.as_str()) {
+                return Err(ManifoldError::InvalidArg(format!(
+                    "unexpected file {:?} in share bundle",
+                    file.path
+                )));
+            }
+            let bytes = B64.decode(&file.data).map_err(|e| {
+                ManifoldError::InvalidArg(format!("{} is not valid base64: {e}", file.path))
+            })?;
+            Ok((file.path.clone(), bytes))
+        })
+        .collect()
+}
+
+/// A local proxy the shared one can be swapped for.  Credentials that were
+/// kept must match too; stripped ones are taken from the local proxy.
+fn same_endpoint(local: &Proxy, shared: &SharedProxy) -> bool {
+    local.proxy_type == shared.proxy_type
+        && local.host.eq_ignore_ascii_case(&shared.host)
+        && local.port == shared.port
+        && (shared.username.is_none() || local.username == shared.username)
+}
+
+fn discard_proxies(proxies: &ProxyRepo, ids: &[String]) {
+    for id in ids {
+        proxies.delete(id).ok();
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+
+    struct Fixture {
+        profiles: ProfileRepo,
+        proxies: ProxyRepo,
+        _dir: tempfile::TempDir,
+    }
+
+    fn fixture() -> Fixture {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Db::open_in_memory().unwrap();
+        Fixture {
+            profiles: ProfileRepo::new_with_root(db.clone(), dir.path().join("profiles")),
+            proxies: ProxyRepo::new(db),
+            _dir: dir,
+        }
+    }
+
+    fn add_proxy(proxies: &ProxyRepo, host: &str, user: Option<&str>) -> Proxy {
+        proxies
+            .add(AddProxyRequest {
+                name: host.into(),
+                proxy_type: "socks5".into(),
+                host: host.into(),
+                port: 1080,
+                username: user.map(String::from),
+                password: user.map(|_| "secret".to_string()),
+                country: Some("DE".into()),
+                region: None,
+                ssh_key_path: None,
+                tags: None,
+                notes: None,
+            })
+            .unwrap()
+    }
+
+    /// A profile on `x1` with a two-hop chain and a cookie jar.
+    fn shared_profile(f: &Fixture) -> Profile {
+        let x1 = add_proxy(&f.proxies, "10.0.0.1", Some("alice"));
+        let x2 = add_proxy(&f.proxies, "10.0.0.2", Some("alice"));
+        let p = f
+            .profiles
+            .create(CreateProfileRequest {
+                name: "shop".into(),
+                seed: Some(7),
+                proxy_id: Some(x1.id.clone()),
+                notes: Some("main account".into()),
+                tags: Some(vec!["eu".into()]),
+                behavior_profile: None,
+                persona: None,
+            })
+            .unwrap();
+        f.profiles.set_proxy_chain(&p.id, &[x1.id, x2.id]).unwrap();
+        f.profiles
+            .batch_update(
+                ProfileSelection::Ids(vec![p.id.clone()]),
+                BatchProfilePatch {
+                    tls_bridge: Some(true),
+                    ..BatchProfilePatch::default()
+                },
+            )
+            .unwrap();
+        let data = Path::new(&p.data_dir);
+        std::fs::create_dir_all(data.join("Default/Network")).unwrap();
+        std::fs::write(data.join("Default/Network/Cookies"), b"jar").unwrap();
+        std::fs::write(
+            data.join(LOCAL_STATE),
+            r#"{"os_crypt":{"encrypted_key":"k"},"user_experience_metrics":{"client_id":"c"}}"#,
+        )
+        .unwrap();
+        f.profiles.get(&p.id).unwrap()
+    }
+
+    #[test]
+    fn defaults_strip_credentials_and_cookies() {
+        let f = fixture();
+        let p = shared_profile(&f);
+        let bundle = build(&f.profiles, &f.proxies, &p.id, &ShareOptions::default()).unwrap();
+        assert_eq!(bundle.proxies.len(), 2);
+        assert!(bundle
+            .proxies
+            .iter()
+            .all(|x| x.username.is_none() && x.password.is_none()));
+        assert!(bundle.cookies.is_empty());
+        assert_eq!(bundle.cookie_origin, None);
+        assert_eq!(bundle.profile.fingerprint.seed, 7);
+
+        let keep = ShareOptions {
+            strip_proxy_credentials: false,
+            strip_cookies: false,
+        };
+        let bundle = build(&f.profiles, &f.proxies, &p.id, &keep).unwrap();
+        assert_eq!(bundle.proxies[0].password.as_deref(), Some("secret"));
+        let paths: Vec<&str> = bundle.cookies.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["Default/Network/Cookies", LOCAL_STATE]);
+        let state = B64.decode(&bundle.cookies[1].data).unwrap();
+        assert_eq!(state, br#"{"os_crypt":{"encrypted_key":"k"}}"#);
+        assert_eq!(bundle.cookie_origin, Some(cookie_origin()));
+    }
+
+    #[test]
+    fn import_reuses_matching_proxies_and_restores_cookies() {
+        let sender = fixture();
+        let p = shared_profile(&sender);
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("shop.manifold.json");
+        let keep_cookies = ShareOptions {
+            strip_cookies: false,
+            ..ShareOptions::default()
+        };
+        export(
+            &sender.profiles,
+            &sender.proxies,
+            &p.id,
+            &keep_cookies,
+            &file,
+        )
+        .unwrap();
+
+        let receiver = fixture();
+        let local = add_proxy(&receiver.proxies, "10.0.0.1", Some("bob"));
+        let imported = import(&receiver.profiles, &receiver.proxies, &file).unwrap();
+        assert_eq!(imported.reused_proxies, std::slice::from_ref(&local.id));
+        assert_eq!(imported.created_proxies.len(), 1);
+        assert_eq!(imported.needs_credentials, imported.created_proxies);
+
+        let profile = receiver.profiles.get(&imported.profile.id).unwrap();
+        assert_ne!(profile.id, p.id);
+        assert_eq!(profile.fingerprint.user_agent, p.fingerprint.user_agent);
+        assert_eq!(profile.proxy_id.as_deref(), Some(local.id.as_str()));
+        assert_eq!(
+            profile.proxy_chain,
+            [local.id, imported.created_proxies[0].clone()]
+        );
+        assert_eq!(profile.tags, ["eu"]);
+        assert_eq!(profile.tls_bridge, Some(true));
+        let data = Path::new(&profile.data_dir);
+        assert_eq!(
+            std::fs::read(data.join("Default/Network/Cookies")).unwrap(),
+            b"jar"
+        );
+    }
+
+    #[test]
+    fn import_rejects_bundles_that_write_elsewhere() {
+        let f = fixture();
+        let p = shared_profile(&f);
+        let keep = ShareOptions {
+            strip_proxy_credentials: false,
+            strip_cookies: false,
+        };
+        let mut bundle = build(&f.profiles, &f.proxies, &p.id, &keep).unwrap();
+        bundle.cookies[0].path = "../../evil".into();
+        assert!(validate(&bundle, &cookie_origin()).is_err());
+
+        let mut bundle = build(&f.profiles, &f.proxies, &p.id, &keep).unwrap();
+        bundle.proxies.pop();
+        assert!(validate(&bundle, &cookie_origin()).is_err());
+
+        let mut bundle = build(&f.profiles, &f.proxies, &p.id, &keep).unwrap();
+        bundle.format = SHARE_FORMAT + 1;
+        assert!(validate(&bundle, &cookie_origin()).is_err());
+    }
+
+    #[test]
+    fn import_rejects_cookies_from_another_origin() {
+        let f = fixture();
+        let p = shared_profile(&f);
+        let keep = ShareOptions {
+            strip_proxy_credentials: false,
+            strip_cookies: false,
+        };
+        let mut bundle = build(&f.profiles, &f.proxies, &p.id, &keep).unwrap();
+        assert!(validate(&bundle, &cookie_origin()).is_ok());
+        assert!(validate(&bundle, "windows:0123").is_err());
+
+        bundle.cookie_origin = None;
+        assert!(validate(&bundle, &cookie_origin()).is_err());
+
+        let stripped = build(&f.profiles, &f.proxies, &p.id, &ShareOptions::default()).unwrap();
+        assert!(validate(&stripped, "windows:0123").is_ok());
+    }
+}
@@ -0,0 +1,323 @@
+// ── Manifold SSH tunnels ──────────────────────────────────────────────────────
+//
+// `Ssh` proxies are SSH servers rather than proxies: Manifold runs
+// `ssh -N -D 127.0.0.1:<port>` against them and hands the launcher the local
+// SOCKS5 endpoint that dynamic forward exposes.  Tunnels are owned by the app
+// — a monitor thread restarts any that drop (with backoff) until they are
+// stopped explicitly.
+//
+// Key auth uses `ssh_key_path`; password auth goes through `sshpass -e`,
+// which must be installed, so the password never appears on a command line.
+
+use std::collections::HashMap;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::error::{ManifoldError, Result};
+use crate::proxy::{Proxy, ProxyType};
+
+/// How long a fresh tunnel gets to open its local port.
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+const MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+/// Restart backoff doubles per consecutive failure up to this cap.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+// ── Command line ──────────────────────────────────────────────────────────────
+
+/// Program, arguments and environment for a dynamic forward on `local_port`.
+pub fn ssh_command(proxy: &Proxy, local_port: u16) -> (String, Vec<String>, Vec<(String, String)>) {
+    let mut args: Vec<String> = Vec::new();
+    let mut env = Vec::new();
+    let use_password = proxy.ssh_key_path.is_none() && proxy.password.is_some();
+
+    let program = if use_password {
+        args.extend(["-e".into(), "ssh".into()]);
+        env.push(("SSHPASS".into(), proxy.password.clone().unwrap_or_default()));
+        "sshpass".to_string()
+    } else {
+        "ssh".to_string()
+    };
+
+    args.extend([
+        "-N".into(),
+        "-D".into(),
+        format!("127.0.0.1:{local_port}"),
+        "-p".into(),
+        proxy.port.to_string(),
+    ]);
+    for opt in [
+        "ExitOnForwardFailure=yes",
+        "ServerAliveInterval=15",
+        "ServerAliveCountMax=3",
+        "StrictHostKeyChecking=accept-new",
+    ] {
+        args.extend(["-o".into(), opt.into()]);
+    }
+    if !use_password {
+        // Never fall back to an interactive prompt nobody can answer
+        args.extend(["-o".into(), "BatchMode=yes".into()]);
+    }
+    if let Some(key) = &proxy.ssh_key_path {
+        args.extend(["-i".into(), key.clone()]);
+    }
+    args.push(match &proxy.username {
+        Some(user) => format!("{user}@{}", proxy.host),
+        None => proxy.host.clone(),
+    });
+
+    (program, args, env)
+}
+
+pub fn free_local_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+// ── Tunnel manager ────────────────────────────────────────────────────────────
+
+struct Tunnel {
+    proxy: Proxy,
+    local_port: u16,
+    child: Child,
+    started_at: Instant,
+    /// Consecutive restarts without the tunnel staying up for `MAX_BACKOFF`.
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Tunnel {
+    fn spawn(proxy: &Proxy, local_port: u16) -> Result<Child> {
+        let (program, args, env) = ssh_command(proxy, local_port);
+        Command::new(&program)
+            .args(&args)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| ManifoldError::spawn(program, e))
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+type Tunnels = Mutex<HashMap<String, Tunnel>>;
+
+/// Owns every running SSH tunnel, keyed by proxy id.
+pub struct SshTunnelManager {
+    tunnels: Arc<Tunnels>,
+}
+
+impl SshTunnelManager {
+    pub fn new() -> Self {
+        let tunnels: Arc<Tunnels> = Arc::default();
+        let weak = Arc::downgrade(&tunnels);
+        std::thread::spawn(move || monitor(weak));
+        Self { tunnels }
+    }
+
+    /// Local SOCKS5 port for `proxy`, starting its tunnel if needed.
+    pub fn ensure(&self, proxy: &Proxy) -> Result<u16> {
+        if proxy.proxy_type != ProxyType::Ssh {
+            return Err(ManifoldError::InvalidArg(format!(
+                "{} is not an SSH proxy",
+                proxy.name
+            )));
+        }
+        if let Some(t) = self.tunnels.lock().unwrap().get(&proxy.id) {
+            return Ok(t.local_port);
+        }
+
+        let local_port = free_local_port()?;
+        let mut child = Tunnel::spawn(proxy, local_port)?;
+        let deadline = Instant::now() + READY_TIMEOUT;
+        while TcpStream::connect(("127.0.0.1", local_port)).is_err() {
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(ManifoldError::ProxyCheck(format!(
+                    "ssh tunnel to {} exited ({status})",
+                    proxy.host
+                )));
+            }
+            if Instant::now() > deadline {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(ManifoldError::ProxyCheck(format!(
+                    "ssh tunnel to {} did not come up",
+                    proxy.host
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        self.tunnels.lock().unwrap().insert(
+            proxy.id.clone(),
+            Tunnel {
+                proxy: proxy.clone(),
+                local_port,
+                child,
+                started_at: Instant::now(),
+                failures: 0,
+                retry_at: None,
+            },
+        );
+        Ok(local_port)
+    }
+
+    /// `proxy` as the launcher should see it: SSH proxies become their local
+    /// SOCKS5 endpoint, everything else is returned unchanged.
+    pub fn resolve(&self, proxy: &Proxy) -> Result<Proxy> {
+        if proxy.proxy_type != ProxyType::Ssh {
+            return Ok(proxy.clone());
+        }
+        let port = self.ensure(proxy)?;
+        Ok(Proxy {
+            proxy_type: ProxyType::Socks5,
+            host: "127.0.0.1".into(),
+            port,
+            username: None,
+            password: None,
+            ssh_key_path: None,
+            ..proxy.clone()
+        })
+    }
+
+    pub fn stop(&self, proxy_id: &str) {
+        self.tunnels.lock().unwrap().remove(proxy_id);
+    }
+
+    pub fn stop_all(&self) {
+        self.tunnels.lock().unwrap().clear();
+    }
+}
+
+impl Default for SshTunnelManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Restart tunnels whose ssh process exited, until the manager is dropped.
+fn monitor(tunnels: Weak<Tunnels>) {
+    loop {
+        std::thread::sleep(MONITOR_INTERVAL);
+        let Some(tunnels) = tunnels.upgrade() else {
+            return;
+        };
+        let now = Instant::now();
+        for t in tunnels.lock().unwrap().values_mut() {
+            if t.retry_at.is_none() {
+                match t.child.try_wait() {
+                    Ok(None) => {
+                        if now.duration_since(t.started_at) > MAX_BACKOFF {
+                            t.failures = 0;
+                        }
+                        continue;
+                    }
+                    _ => {
+                        let backoff = Duration::from_secs(1 << t.failures.min(6)).min(MAX_BACKOFF);
+                        eprintln!(
+                            "[ssh] tunnel to {} dropped; restarting in {}s",
+                            t.proxy.host,
+                            backoff.as_secs()
+                        );
+                        t.retry_at = Some(now + backoff);
+                    }
+                }
+            }
+            if t.retry_at.is_some_and(|at| at <= now) {
+                match Tunnel::spawn(&t.proxy, t.local_port) {
+                    Ok(child) => {
+                        t.child = child;
+                        t.started_at = now;
+                        t.retry_at = None;
+                    }
+                    Err(e) => {
+                        eprintln!("[ssh] restart failed: {e}");
+                        t.retry_at = Some(now + MAX_BACKOFF);
+                    }
+                }
+                t.failures += 1;
+            }
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssh_proxy() -> Proxy {
+        Proxy {
+            id: "s".into(),
+            name: "jump".into(),
+            proxy_type: ProxyType::Ssh,
+            host: "jump.example.com".into(),
+            port: 2222,
+            username: Some("me".into()),
+            password: None,
+            country: None,
+            region: None,
+            ssh_key_path: Some("/keys/id_ed25519".into()),
+            pinned_ip: None,
+            check_mode: Default::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
+            healthy: false,
+            latency_ms: None,
+            last_checked: None,
+        }
+    }
+
+    #[test]
+    fn key_auth_runs_ssh_in_batch_mode() {
+        let (program, args, env) = ssh_command(&ssh_proxy(), 40000);
+        assert_eq!(program, "ssh");
+        assert!(env.is_empty());
+        let joined = args.join(" ");
+        assert!(joined.contains("-N -D 127.0.0.1:40000 -p 2222"));
+        assert!(joined.contains("BatchMode=yes"));
+        assert!(joined.contains("-i /keys/id_ed25519"));
+        assert_eq!(args.last().unwrap(), "me@jump.example.com");
+    }
+
+    #[test]
+    fn password_auth_goes_through_sshpass_env() {
+        let proxy = Proxy {
+            ssh_key_path: None,
+            password: Some("hunter2".into()),
+            ..ssh_proxy()
+        };
+        let (program, args, env) = ssh_command(&proxy, 40000);
+        assert_eq!(program, "sshpass");
+        assert_eq!(&args[..2], ["-e", "ssh"]);
+        assert_eq!(env, vec![("SSHPASS".to_string(), "hunter2".to_string())]);
+        assert!(!args.iter().any(|a| a.contains("hunter2")));
+        assert!(!args.iter().any(|a| a == "BatchMode=yes"));
+    }
+
+    #[test]
+    fn resolve_passes_non_ssh_proxies_through() {
+        let manager = SshTunnelManager::new();
+        let http = Proxy {
+            proxy_type: ProxyType::Http,
+            ..ssh_proxy()
+        };
+        let resolved = manager.resolve(&http).unwrap();
+        assert_eq!(resolved.host, "jump.example.com");
+        assert!(manager.ensure(&http).is_err());
+        assert!(manager.tunnels.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+// ── Manifold dashboard statistics ─────────────────────────────────────────────
+//
+// All aggregation happens in SQL so the frontend can draw its charts from a
+// few dozen numbers instead of pulling every profile, proxy and event.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::db::Db;
+use crate::error::Result;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// One point of a per-day series (`day` is `YYYY-MM-DD`, UTC).
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DayCount {
+    pub day: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountryProxyCount {
+    /// ISO-3166-1 alpha-2 code, or `None` for proxies without a country.
+    pub country: Option<String>,
+    pub total: u64,
+    pub healthy: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardStats {
+    pub total_profiles: u64,
+    /// Profile count per status ("idle", "running", "error").
+    pub profiles_by_status: BTreeMap<String, u64>,
+    pub launches_per_day: Vec<DayCount>,
+    pub proxies_by_country: Vec<CountryProxyCount>,
+    /// Mean duration of finished sessions in the window, in seconds.
+    pub avg_session_secs: Option<f64>,
+    pub detections_per_day: Vec<DayCount>,
+    /// Size of the time window the per-day series cover.
+    pub window_days: u32,
+}
+
+// ── Aggregation ───────────────────────────────────────────────────────────────
+
+/// Compute the dashboard aggregates over the last `window_days` days.
+pub fn dashboard_stats(db: &Db, window_days: u32) -> Result<DashboardStats> {
+    let since = (Utc::now() - Duration::days(window_days as i64)).to_rfc3339();
+
+    db.with_conn(|conn| {
+        let mut profiles_by_status = BTreeMap::new();
+        {
+            let mut stmt = conn.prepare("SELECT status, COUNT(*) FROM profiles GROUP BY status")?;
+            let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?;
+            for row in rows {
+                let (status, n) = row?;
+                profiles_by_status.insert(status, n as u64);
+            }
+        }
+        let total_profiles = profiles_by_status.values().sum();
+
+        let mut proxies_by_country = Vec::new();
+        {
+            let mut stmt = conn.prepare(
+                r#"SELECT country, COUNT(*), COALESCE(SUM(healthy), 0)
+                   FROM proxies
+                   GROUP BY country
+                   ORDER BY COUNT(*) DESC"#,
+            )?;
+            let rows = stmt.query_map([], |r| {
+                Ok(CountryProxyCount {
+                    country: r.get(0)?,
+                    total: r.get::<_, i64>(1)? as u64,
+                    healthy: r.get::<_, i64>(2)? as u64,
+                })
+            })?;
+            for row in rows {
+                proxies_by_country.push(row?);
+            }
+        }
+
+        let avg_session_secs: Option<f64> = conn.query_row(
+            r#"SELECT AVG((julianday(ended_at) - julianday(started_at)) * 86400.0)
+               FROM sessions
+               WHERE ended_at IS NOT NULL AND started_at >= ?1"#,
+            params![since],
+            |r| r.get(0),
+        )?;
+
+        Ok(DashboardStats {
+            total_profiles,
+            profiles_by_status,
+            launches_per_day: events_per_day(conn, "launch", &since)?,
+            proxies_by_country,
+            avg_session_secs,
+            detections_per_day: events_per_day(conn, "detection", &since)?,
+            window_days,
+        })
+    })
+}
+
+fn events_per_day(conn: &rusqlite::Connection, kind: &str, since: &str) -> Result<Vec<DayCount>> {
+    let mut stmt = conn.prepare(
+        r#"SELECT substr(created_at, 1, 10) AS day, COUNT(*)
+           FROM events
+           WHERE kind = ?1 AND created_at >= ?2
+           GROUP BY day
+           ORDER BY day ASC"#,
+    )?;
+    let rows = stmt.query_map(params![kind, since], |r| {
+        Ok(DayCount {
+            day: r.get(0)?,
+            count: r.get::<_, i64>(1)? as u64,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventKind, EventRepo, NewEvent};
+
+    fn seed_db() -> Db {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute_batch(
+                r#"INSERT INTO profiles (id, name, fingerprint_json, status, created_at)
+                   VALUES ('p1','a','{}','idle','2025-01-01T00:00:00+00:00'),
+                          ('p2','b','{}','running','2025-01-01T00:00:00+00:00'),
+                          ('p3','c','{}','idle','2025-01-01T00:00:00+00:00');
+                   INSERT INTO proxies (id, name, proxy_type, host, port, country, healthy)
+                   VALUES ('x1','x','http','h',1,'US',1),
+                          ('x2','y','http','h',1,'US',0),
+                          ('x3','z','http','h',1,NULL,0);"#,
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn counts_profiles_by_status() {
+        let stats = dashboard_stats(&seed_db(), 30).unwrap();
+        assert_eq!(stats.total_profiles, 3);
+        assert_eq!(stats.profiles_by_status.get("idle"), Some(&2));
+        assert_eq!(stats.profiles_by_status.get("running"), Some(&1));
+    }
+
+    #[test]
+    fn groups_proxies_by_country() {
+        let stats = dashboard_stats(&seed_db(), 30).unwrap();
+        let us = stats
+            .proxies_by_country
+            .iter()
+            .find(|c| c.country.as_deref() == Some("US"))
+            .unwrap();
+        assert_eq!((us.total, us.healthy), (2, 1));
+        assert!(stats.proxies_by_country.iter().any(|c| c.country.is_none()));
+    }
+
+    #[test]
+    fn launches_and_detections_are_bucketed_by_day() {
+        let db = seed_db();
+        let events = EventRepo::new(db.clone());
+        events
+            .record(NewEvent::simple(Some("p1"), EventKind::Launch))
+            .unwrap();
+        events
+            .record(NewEvent::simple(Some("p2"), EventKind::Launch))
+            .unwrap();
+        events
+            .record(NewEvent::simple(Some("p2"), EventKind::Detection))
+            .unwrap();
+
+        let stats = dashboard_stats(&db, 7).unwrap();
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            stats.launches_per_day,
+            vec![DayCount {
+                day: today.clone(),
+                count: 2
+            }]
+        );
+        assert_eq!(
+            stats.detections_per_day,
+            vec![DayCount {
+                day: today,
+                count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn avg_session_duration_uses_finished_sessions_only() {
+        let db = seed_db();
+        let start = (Utc::now() - Duration::minutes(10)).to_rfc3339();
+        let end = Utc::now().to_rfc3339();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO sessions (id, profile_id, started_at, ended_at) VALUES ('s1','p1',?1,?2)",
+                params![start, end],
+            )?;
+            conn.execute(
+                "INSERT INTO sessions (id, profile_id, started_at) VALUES ('s2','p1',?1)",
+                params![start],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let avg = dashboard_stats(&db, 1).unwrap().avg_session_secs.unwrap();
+        assert!((avg - 600.0).abs() < 2.0, "expected ~600 s, got {avg}");
+    }
+
+    #[test]
+    fn empty_db_has_no_average() {
+        let stats = dashboard_stats(&Db::open_in_memory().unwrap(), 30).unwrap();
+        assert_eq!(stats.total_profiles, 0);
+        assert!(stats.avg_session_secs.is_none());
+    }
+}
@@ -0,0 +1,1254 @@
+// ── Manifold synthetic identities ─────────────────────────────────────────────
+//
+// Form-fill data for a profile — name, birthdate, phone, postal address —
+// that fits where the profile appears to be.  The country is the profile's
+// geo: its exit proxy's country, else its persona's home city, else the
+// region of its fingerprint locale.  Names, cities, postal codes, phone
+// numbers and date formats come from that country's tables, so a profile
+// browsing through a German proxy with a de-DE locale signs up as someone
+// living in Germany.
+//
+// Generation is seeded by the fingerprint seed and the country: the same
+// profile always gets the same identity for the same geo.  A persona's name,
+// age bracket and home city are kept when set.  The identity is stored
+// encrypted per profile and regenerated when the profile's geo no longer
+// matches it.
+//
+// Postal codes and phone numbers are checked against per-country formats,
+// both as they are generated and by `validate`, which also flags a stored
+// identity or persona home city from another country than the profile's.
+// Phones are kept in national form and in E.164.
+//
+// Workflows see it as `{{identity.<field>}}` variables, under the vault and
+// the run's parameters.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::persona::AgeBracket;
+use crate::profile::Profile;
+
+/// Prefix of the workflow variables an identity fills in.
+pub const VARIABLE_PREFIX: &str = "identity.";
+
+/// Mixed into the fingerprint seed so identities don't share its stream.
+const IDENTITY_SALT: u64 = 0x1d3e_7a6b_51c0_9f24;
+
+/// Letters valid in both Canadian and British postcodes.
+const POSTAL_LETTERS: &[u8] = b"ABEGHJLNPRSTWXYZ";
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticIdentity {
+    /// ISO-3166-1 alpha-2.
+    pub country: String,
+    pub country_name: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub full_name: String,
+    pub birthdate: NaiveDate,
+    /// The birthdate as the country writes it.
+    pub birthdate_local: String,
+    /// National format, as typed into a local form.
+    pub phone: String,
+    pub phone_e164: String,
+    pub address_line1: String,
+    pub city: String,
+    pub region: String,
+    pub postal_code: String,
+}
+
+impl SyntheticIdentity {
+    /// The identity as workflow variables, `identity.first_name` and so on.
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        let fields = [
+            ("country", self.country.clone()),
+            ("country_name", self.country_name.clone()),
+            ("first_name", self.first_name.clone()),
+            ("last_name", self.last_name.clone()),
+            ("full_name", self.full_name.clone()),
+            ("birthdate", self.birthdate.to_string()),
+            ("birthdate_local", self.birthdate_local.clone()),
+            ("birth_day", self.birthdate.day().to_string()),
+            ("birth_month", self.birthdate.month().to_string()),
+            ("birth_year", self.birthdate.year().to_string()),
+            ("phone", self.phone.clone()),
+            ("phone_e164", self.phone_e164.clone()),
+            ("address_line1", self.address_line1.clone()),
+            ("city", self.city.clone()),
+            ("region", self.region.clone()),
+            ("postal_code", self.postal_code.clone()),
+        ];
+        fields
+            .into_iter()
+            .map(|(name, value)| (format!("{VARIABLE_PREFIX}{name}"), value))
+            .collect()
+    }
+}
+
+// ── Country tables ────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+enum AddressStyle {
+    /// "12 High Street"
+    HouseFirst,
+    /// "Hauptstraße 12"
+    StreetFirst,
+    /// "Calle Mayor, 12"
+    StreetComma,
+}
+
+struct City {
+    name: &'static str,
+    region: &'static str,
+    /// `#` any digit, `%` 1-9, `@` a letter, anything else as is.
+    postal: &'static str,
+    /// Phone area code, where numbers carry one.
+    area: &'static str,
+}
+
+struct Country {
+    code: &'static str,
+    name: &'static str,
+    dial: &'static str,
+    given: &'static [&'static str],
+    family: &'static [&'static str],
+    /// Two family names, father's then mother's.
+    two_surnames: bool,
+    streets: &'static [&'static str],
+    address: AddressStyle,
+    max_house_number: u32,
+    cities: &'static [City],
+    /// National formats: `{area}` the city's area code, `#` any digit,
+    /// `N` 2-9.
+    phones: &'static [&'static str],
+    /// Digits of a number after the country code.
+    national_digits: (usize, usize),
+    /// National numbers start with a `0` that E.164 drops.
+    trunk_zero: bool,
+    /// `#` a digit, `@` a letter, `*` either, anything else as is.
+    postal_formats: &'static [&'static str],
+    date_format: &'static str,
+}
+
+const fn city(
+    name: &'static str,
+    region: &'static str,
+    postal: &'static str,
+    area: &'static str,
+) -> City {
+    City {
+        name,
+        region,
+        postal,
+        area,
+    }
+}
+
+const COUNTRIES: &[Country] = &[
+    Country {
+        code: "US",
+        name: "United States",
+        dial: "1",
+        given: &[
+            "James", "Michael", "Robert", "David", "Daniel", "Matthew", "Emily", "Sarah",
+            "Jessica", "Ashley", "Jennifer", "Megan",
+        ],
+        family: &[
+            "Smith", "Johnson", "Williams", "Brown", "Jones", "Miller", "Davis", "Wilson",
+            "Anderson", "Taylor", "Moore", "Clark",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Maple Ave",
+            "Oak St",
+            "Washington Blvd",
+            "Park Ave",
+            "Lincoln St",
+            "Cedar Ln",
+            "Lakeview Dr",
+            "Elm St",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 9_999,
+        cities: &[
+            city("New York", "NY", "100##", "212"),
+            city("Chicago", "IL", "606##", "312"),
+            city("Houston", "TX", "770##", "713"),
+            city("Phoenix", "AZ", "850##", "602"),
+            city("Seattle", "WA", "981##", "206"),
+            city("Denver", "CO", "802##", "303"),
+            city("Atlanta", "GA", "303##", "404"),
+            city("Los Angeles", "CA", "900##", "213"),
+        ],
+        phones: &["({area}) N##-####"],
+        national_digits: (10, 10),
+        trunk_zero: false,
+        postal_formats: &["#####", "#####-####"],
+        date_format: "%m/%d/%Y",
+    },
+    Country {
+        code: "CA",
+        name: "Canada",
+        dial: "1",
+        given: &[
+            "Liam",
+            "Noah",
+            "Ethan",
+            "Benjamin",
+            "Lucas",
+            "Olivia",
+            "Emma",
+            "Charlotte",
+            "Chloe",
+            "Sophie",
+        ],
+        family: &[
+            "Smith",
+            "Brown",
+            "Tremblay",
+            "Martin",
+            "Roy",
+            "Wilson",
+            "Macdonald",
+            "Taylor",
+            "Campbell",
+            "Anderson",
+        ],
+        two_surnames: false,
+        streets: &[
+            "King St",
+            "Queen St",
+            "Main St",
+            "Church St",
+            "Wellington St",
+            "Maple Ave",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 2_999,
+        cities: &[
+            city("Toronto", "ON", "M5V #@#", "416"),
+            city("Vancouver", "BC", "V6B #@#", "604"),
+            city("Montreal", "QC", "H2X #@#", "514"),
+            city("Calgary", "AB", "T2P #@#", "403"),
+        ],
+        phones: &["({area}) N##-####"],
+        national_digits: (10, 10),
+        trunk_zero: false,
+        postal_formats: &["@#@ #@#"],
+        date_format: "%Y-%m-%d",
+    },
+    Country {
+        code: "GB",
+        name: "United Kingdom",
+        dial: "44",
+        given: &[
+            "Oliver",
+            "George",
+            "Harry",
+            "Jack",
+            "Thomas",
+            "Charlotte",
+            "Amelia",
+            "Sophie",
+            "Emily",
+            "Jessica",
+        ],
+        family: &[
+            "Smith", "Jones", "Taylor", "Brown", "Williams", "Wilson", "Evans", "Roberts",
+            "Walker", "Wright",
+        ],
+        two_surnames: false,
+        streets: &[
+            "High Street",
+            "Station Road",
+            "Church Lane",
+            "Victoria Road",
+            "Green Lane",
+            "Park Road",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 180,
+        cities: &[
+            city("London", "Greater London", "SE1 #@@", ""),
+            city("Manchester", "Greater Manchester", "M1 #@@", ""),
+            city("Birmingham", "West Midlands", "B1 #@@", ""),
+            city("Leeds", "West Yorkshire", "LS1 #@@", ""),
+            city("Edinburgh", "Scotland", "EH1 #@@", ""),
+        ],
+        phones: &[
+            "074## ######",
+            "075## ######",
+            "077## ######",
+            "078## ######",
+            "079## ######",
+        ],
+        national_digits: (10, 10),
+        trunk_zero: true,
+        postal_formats: &[
+            "@# #@@", "@## #@@", "@@# #@@", "@@## #@@", "@#@ #@@", "@@#@ #@@",
+        ],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "IE",
+        name: "Ireland",
+        dial: "353",
+        given: &[
+            "Jack", "James", "Noah", "Conor", "Seán", "Emily", "Grace", "Fiadh", "Sophie", "Aoife",
+        ],
+        family: &[
+            "Murphy",
+            "Kelly",
+            "O'Sullivan",
+            "Walsh",
+            "Smith",
+            "O'Brien",
+            "Byrne",
+            "Ryan",
+            "O'Connor",
+            "McCarthy",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Main Street",
+            "Church Road",
+            "Parnell Street",
+            "Patrick Street",
+            "Green Lane",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 150,
+        cities: &[
+            city("Dublin", "County Dublin", "D02 @#@#", ""),
+            city("Cork", "County Cork", "T12 @#@#", ""),
+            city("Galway", "County Galway", "H91 @#@#", ""),
+        ],
+        phones: &[
+            "083 ### ####",
+            "085 ### ####",
+            "086 ### ####",
+            "087 ### ####",
+        ],
+        national_digits: (9, 9),
+        trunk_zero: true,
+        postal_formats: &["@## ****", "@#@ ****"],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "AU",
+        name: "Australia",
+        dial: "61",
+        given: &[
+            "Oliver",
+            "Jack",
+            "William",
+            "Noah",
+            "Lachlan",
+            "Charlotte",
+            "Olivia",
+            "Mia",
+            "Ava",
+            "Chloe",
+        ],
+        family: &[
+            "Smith", "Jones", "Williams", "Brown", "Wilson", "Taylor", "Johnson", "White",
+            "Martin", "Anderson",
+        ],
+        two_surnames: false,
+        streets: &[
+            "George St",
+            "Queen St",
+            "Victoria Rd",
+            "Church St",
+            "High St",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 400,
+        cities: &[
+            city("Sydney", "NSW", "20##", ""),
+            city("Melbourne", "VIC", "30##", ""),
+            city("Brisbane", "QLD", "40##", ""),
+            city("Perth", "WA", "60##", ""),
+        ],
+        phones: &["04## ### ###"],
+        national_digits: (9, 9),
+        trunk_zero: true,
+        postal_formats: &["####"],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "DE",
+        name: "Deutschland",
+        dial: "49",
+        given: &[
+            "Lukas", "Leon", "Finn", "Jonas", "Paul", "Anna", "Lena", "Laura", "Julia", "Sophie",
+        ],
+        family: &[
+            "Müller",
+            "Schmidt",
+            "Schneider",
+            "Fischer",
+            "Weber",
+            "Meyer",
+            "Wagner",
+            "Becker",
+            "Hoffmann",
+            "Schulz",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Hauptstraße",
+            "Bahnhofstraße",
+            "Schillerstraße",
+            "Gartenstraße",
+            "Lindenstraße",
+            "Goethestraße",
+        ],
+        address: AddressStyle::StreetFirst,
+        max_house_number: 120,
+        cities: &[
+            city("Berlin", "Berlin", "104##", ""),
+            city("München", "Bayern", "803##", ""),
+            city("Hamburg", "Hamburg", "2035#", ""),
+            city("Köln", "Nordrhein-Westfalen", "506##", ""),
+            city("Frankfurt am Main", "Hessen", "603##", ""),
+        ],
+        phones: &[
+            "0151 ########",
+            "0160 #######",
+            "0170 #######",
+            "0171 #######",
+            "0176 ########",
+        ],
+        national_digits: (10, 11),
+        trunk_zero: true,
+        postal_formats: &["#####"],
+        date_format: "%d.%m.%Y",
+    },
+    Country {
+        code: "AT",
+        name: "Österreich",
+        dial: "43",
+        given: &[
+            "Maximilian",
+            "Lukas",
+            "Tobias",
+            "David",
+            "Anna",
+            "Lena",
+            "Hannah",
+            "Sarah",
+        ],
+        family: &[
+            "Gruber", "Huber", "Bauer", "Wagner", "Müller", "Pichler", "Steiner", "Moser",
+        ],
+        two_surnames: false,
+        streets: &["Hauptstraße", "Bahnhofstraße", "Kirchengasse", "Schulgasse"],
+        address: AddressStyle::StreetFirst,
+        max_house_number: 80,
+        cities: &[
+            city("Wien", "Wien", "10%0", ""),
+            city("Graz", "Steiermark", "80%0", ""),
+            city("Linz", "Oberösterreich", "40%0", ""),
+        ],
+        phones: &[
+            "0660 #######",
+            "0664 #######",
+            "0676 #######",
+            "0699 ########",
+        ],
+        national_digits: (10, 13),
+        trunk_zero: true,
+        postal_formats: &["####"],
+        date_format: "%d.%m.%Y",
+    },
+    Country {
+        code: "FR",
+        name: "France",
+        dial: "33",
+        given: &[
+            "Lucas", "Hugo", "Louis", "Nathan", "Thomas", "Camille", "Léa", "Manon", "Chloé",
+            "Julie",
+        ],
+        family: &[
+            "Martin", "Bernard", "Dubois", "Thomas", "Robert", "Richard", "Petit", "Durand",
+            "Leroy", "Moreau",
+        ],
+        two_surnames: false,
+        streets: &[
+            "rue de la République",
+            "avenue Victor Hugo",
+            "rue Pasteur",
+            "boulevard Voltaire",
+            "rue des Lilas",
+        ],
+        address: AddressStyle::HouseFirst,
+        max_house_number: 120,
+        cities: &[
+            city("Paris", "Île-de-France", "7501#", ""),
+            city("Lyon", "Auvergne-Rhône-Alpes", "6900%", ""),
+            city("Marseille", "Provence-Alpes-Côte d'Azur", "1300%", ""),
+            city("Lille", "Hauts-de-France", "59000", ""),
+        ],
+        phones: &["06 ## ## ## ##", "07 ## ## ## ##"],
+        national_digits: (9, 9),
+        trunk_zero: true,
+        postal_formats: &["#####"],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "ES",
+        name: "España",
+        dial: "34",
+        given: &[
+            "Hugo",
+            "Martín",
+            "Lucas",
+            "Alejandro",
+            "Pablo",
+            "Lucía",
+            "Sofía",
+            "María",
+            "Carmen",
+            "Laura",
+        ],
+        family: &[
+            "García",
+            "Rodríguez",
+            "González",
+            "Fernández",
+            "López",
+            "Martínez",
+            "Sánchez",
+            "Pérez",
+            "Gómez",
+            "Martín",
+        ],
+        two_surnames: true,
+        streets: &[
+            "Calle Mayor",
+            "Calle de Alcalá",
+            "Avenida de la Constitución",
+            "Calle Real",
+            "Gran Vía",
+        ],
+        address: AddressStyle::StreetComma,
+        max_house_number: 150,
+        cities: &[
+            city("Madrid", "Comunidad de Madrid", "280%#", ""),
+            city("Barcelona", "Cataluña", "080%#", ""),
+            city("Valencia", "Comunidad Valenciana", "460%#", ""),
+            city("Sevilla", "Andalucía", "410%#", ""),
+        ],
+        phones: &["6## ## ## ##"],
+        national_digits: (9, 9),
+        trunk_zero: false,
+        postal_formats: &["#####"],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "IT",
+        name: "Italia",
+        dial: "39",
+        given: &[
+            "Leonardo",
+            "Francesco",
+            "Alessandro",
+            "Lorenzo",
+            "Marco",
+            "Sofia",
+            "Giulia",
+            "Aurora",
+            "Alice",
+            "Chiara",
+        ],
+        family: &[
+            "Rossi", "Russo", "Ferrari", "Esposito", "Bianchi", "Romano", "Colombo", "Ricci",
+            "Marino", "Greco",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Via Roma",
+            "Via Garibaldi",
+            "Corso Italia",
+            "Via Dante",
+            "Via Mazzini",
+        ],
+        address: AddressStyle::StreetComma,
+        max_house_number: 120,
+        cities: &[
+            city("Roma", "Lazio", "001%#", ""),
+            city("Milano", "Lombardia", "201%#", ""),
+            city("Napoli", "Campania", "801%#", ""),
+            city("Torino", "Piemonte", "101%#", ""),
+        ],
+        phones: &["3## ### ####"],
+        national_digits: (9, 10),
+        trunk_zero: false,
+        postal_formats: &["#####"],
+        date_format: "%d/%m/%Y",
+    },
+    Country {
+        code: "NL",
+        name: "Nederland",
+        dial: "31",
+        given: &[
+            "Daan", "Sem", "Lucas", "Bram", "Finn", "Emma", "Julia", "Tess", "Sophie", "Lotte",
+        ],
+        family: &[
+            "de Jong",
+            "Jansen",
+            "de Vries",
+            "van den Berg",
+            "van Dijk",
+            "Bakker",
+            "Visser",
+            "Smit",
+            "Meijer",
+            "de Boer",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Kerkstraat",
+            "Dorpsstraat",
+            "Stationsweg",
+            "Molenstraat",
+            "Schoolstraat",
+        ],
+        address: AddressStyle::StreetFirst,
+        max_house_number: 200,
+        cities: &[
+            city("Amsterdam", "Noord-Holland", "10%# @@", ""),
+            city("Rotterdam", "Zuid-Holland", "30%# @@", ""),
+            city("Utrecht", "Utrecht", "35%# @@", ""),
+        ],
+        phones: &["06 ########"],
+        national_digits: (9, 9),
+        trunk_zero: true,
+        postal_formats: &["#### @@"],
+        date_format: "%d-%m-%Y",
+    },
+    Country {
+        code: "BR",
+        name: "Brasil",
+        dial: "55",
+        given: &[
+            "Miguel",
+            "Arthur",
+            "Heitor",
+            "Davi",
+            "Gael",
+            "Helena",
+            "Alice",
+            "Laura",
+            "Maria",
+            "Valentina",
+        ],
+        family: &[
+            "Silva",
+            "Santos",
+            "Oliveira",
+            "Souza",
+            "Rodrigues",
+            "Ferreira",
+            "Alves",
+            "Pereira",
+            "Lima",
+            "Gomes",
+        ],
+        two_surnames: false,
+        streets: &[
+            "Rua São João",
+            "Rua XV de Novembro",
+            "Avenida Brasil",
+            "Rua das Palmeiras",
+            "Rua Sete de Setembro",
+        ],
+        address: AddressStyle::StreetComma,
+        max_house_number: 2_000,
+        cities: &[
+            city("São Paulo", "SP", "01###-###", "11"),
+            city("Rio de Janeiro", "RJ", "20###-###", "21"),
+            city("Belo Horizonte", "MG", "30###-###", "31"),
+            city("Curitiba", "PR", "80###-###", "41"),
+        ],
+        phones: &["({area}) 9####-####"],
+        national_digits: (10, 11),
+        trunk_zero: false,
+        postal_formats: &["#####-###"],
+        date_format: "%d/%m/%Y",
+    },
+];
+
+/// Country codes identities can be generated for.
+pub fn supported_countries() -> Vec<&'static str> {
+    COUNTRIES.iter().map(|c| c.code).collect()
+}
+
+fn country_data(code: &str) -> Result<&'static Country> {
+    COUNTRIES
+        .iter()
+        .find(|c| c.code.eq_ignore_ascii_case(code))
+        .ok_or_else(|| {
+            ManifoldError::InvalidArg(format!(
+                "no identity data for country {code:?}; supported: {}",
+                supported_countries().join(", ")
+            ))
+        })
+}
+
+// ── Validation ────────────────────────────────────────────────────────────────
+
+/// Whether `text` fits `format` (`#` digit, `@` letter, `*` either).
+fn matches_format(text: &str, format: &str) -> bool {
+    text.chars().count() == format.chars().count()
+        && text.chars().zip(format.chars()).all(|(c, f)| match f {
+            '#' => c.is_ascii_digit(),
+            '@' => c.is_ascii_uppercase(),
+            '*' => c.is_ascii_digit() || c.is_ascii_uppercase(),
+            literal => c == literal,
+        })
+}
+
+/// Whether `code` is shaped like a postal code of `country`.
+pub fn postal_code_valid(country: &str, code: &str) -> bool {
+    let Ok(data) = country_data(country) else {
+        return false;
+    };
+    let code = code.trim().to_uppercase();
+    data.postal_formats.iter().any(|f| matches_format(&code, f))
+}
+
+/// `phone` of `country` in E.164 (`+4915123456789`), from its national form
+/// or an international one (`+49 …`, `0049 …`); `None` when it isn't a
+/// number of that country.
+pub fn normalize_phone(country: &str, phone: &str) -> Option<String> {
+    let data = country_data(country).ok()?;
+    let phone = phone.trim();
+    if !phone
+        .chars()
+        .all(|c| c.is_ascii_digit() || " +-.()/".contains(c))
+    {
+        return None;
+    }
+    let plus = phone.starts_with('+');
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    let national = if plus || digits.starts_with("00") {
+        let international = if plus { &digits[..] } else { &digits[2..] };
+        international.strip_prefix(data.dial)?.to_string()
+    } else if data.dial == "1" && digits.len() == 11 {
+        digits.strip_prefix('1')?.to_string()
+    } else if data.trunk_zero {
+        digits.strip_prefix('0')?.to_string()
+    } else {
+        digits
+    };
+
+    let (min, max) = data.national_digits;
+    let leading_zero_ok = !data.trunk_zero && data.dial != "1";
+    let nanp_ok = data.dial != "1" || {
+        let b = national.as_bytes();
+        // Area code and exchange both start 2-9
+        b.len() == 10 && b[0] >= b'2' && b[3] >= b'2'
+    };
+    let ok = (min..=max).contains(&national.len())
+        && (leading_zero_ok || !national.starts_with('0'))
+        && nanp_ok;
+    ok.then(|| format!("+{}{national}", data.dial))
+}
+
+/// One thing wrong with a stored identity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdentityIssue {
+    /// Machine-readable: `unsupported_country`, `country_mismatch`,
+    /// `postal_code`, `phone`, `persona_home_city`.
+    pub code: String,
+    pub field: String,
+    pub message: String,
+}
+
+impl IdentityIssue {
+    fn new(code: &str, field: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Problems with a profile's identity data given the profile's geo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityValidation {
+    pub profile_id: String,
+    /// The profile's geo country, when it has one.
+    pub country: Option<String>,
+    pub has_identity: bool,
+    pub issues: Vec<IdentityIssue>,
+}
+
+/// Check `identity` and the profile's persona against `country`, the
+/// profile's geo; without one the identity's own country is used.
+pub fn validate(
+    profile: &Profile,
+    country: Option<&str>,
+    identity: Option<&SyntheticIdentity>,
+) -> IdentityValidation {
+    let mut issues = Vec::new();
+    let country = country.map(str::to_uppercase);
+
+    if let (Some(country), Some(city)) = (
+        country.as_deref(),
+        profile.persona.as_ref().and_then(|p| p.city_locale()),
+    ) {
+        if city.country != country {
+            issues.push(IdentityIssue::new(
+                "persona_home_city",
+                "persona.home_city",
+                format!(
+                    "the persona lives in {} but the profile is in {country}",
+                    city.country
+                ),
+            ));
+        }
+    }
+
+    if let Some(identity) = identity {
+        let expected = country.as_deref().unwrap_or(&identity.country);
+        if !identity.country.eq_ignore_ascii_case(expected) {
+            issues.push(IdentityIssue::new(
+                "country_mismatch",
+                "country",
+                format!(
+                    "the identity is from {} but the profile is in {expected}",
+                    identity.country
+                ),
+            ));
+        }
+        if country_data(expected).is_err() {
+            issues.push(IdentityIssue::new(
+                "unsupported_country",
+                "country",
+                format!("no address or phone rules for {expected}"),
+            ));
+        } else {
+            if !postal_code_valid(expected, &identity.postal_code) {
+                issues.push(IdentityIssue::new(
+                    "postal_code",
+                    "postal_code",
+                    format!(
+                        "{:?} is not a postal code in {expected}",
+                        identity.postal_code
+                    ),
+                ));
+            }
+            let national = normalize_phone(expected, &identity.phone);
+            if normalize_phone(expected, &identity.phone_e164).as_ref()
+                != Some(&identity.phone_e164)
+            {
+                issues.push(IdentityIssue::new(
+                    "phone",
+                    "phone_e164",
+                    format!(
+                        "{:?} is not an E.164 number in {expected}",
+                        identity.phone_e164
+                    ),
+                ));
+            } else if national.as_ref() != Some(&identity.phone_e164) {
+                issues.push(IdentityIssue::new(
+                    "phone",
+                    "phone",
+                    format!(
+                        "{:?} is not a number in {expected} or differs from {}",
+                        identity.phone, identity.phone_e164
+                    ),
+                ));
+            }
+        }
+    }
+
+    IdentityValidation {
+        profile_id: profile.id.clone(),
+        country,
+        has_identity: identity.is_some(),
+        issues,
+    }
+}
+
+// ── Generation ────────────────────────────────────────────────────────────────
+
+/// The country a profile appears to be in: `proxy_country` (its exit
+/// proxy's), else its persona's home city, else its locale's region.
+pub fn profile_country(profile: &Profile, proxy_country: Option<&str>) -> Option<String> {
+    proxy_country
+        .map(str::to_string)
+        .or_else(|| {
+            let persona = profile.persona.as_ref()?;
+            Some(persona.city_locale()?.country.to_string())
+        })
+        .or_else(|| {
+            let region = profile.fingerprint.locale.split(['-', '_']).nth(1)?;
+            (region.len() == 2).then(|| region.to_string())
+        })
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty())
+}
+
+/// Expand a `#`/`%`/`@`/`N` pattern.
+fn fill_pattern(rng: &mut SmallRng, pattern: &str) -> String {
+    pattern
+        .chars()
+        .map(|c| match c {
+            '#' => char::from(b'0' + rng.gen_range(0..10)),
+            '%' => char::from(b'0' + rng.gen_range(1..10)),
+            'N' => char::from(b'0' + rng.gen_range(2..10)),
+            '@' => char::from(*POSTAL_LETTERS.choose(rng).unwrap()),
+            other => other,
+        })
+        .collect()
+}
+
+fn age_range(bracket: AgeBracket) -> (i64, i64) {
+    match bracket {
+        AgeBracket::Age18To24 => (18, 24),
+        AgeBracket::Age25To34 => (25, 34),
+        AgeBracket::Age35To44 => (35, 44),
+        AgeBracket::Age45To54 => (45, 54),
+        AgeBracket::Age55Plus => (55, 70),
+    }
+}
+
+/// A birthdate making the person `min..=max` years old on `today`.
+fn birthdate(rng: &mut SmallRng, today: NaiveDate, (min, max): (i64, i64)) -> NaiveDate {
+    let years_ago = |years: i64| {
+        today
+            .with_year(today.year() - years as i32)
+            // 29 February in a year without one
+            .unwrap_or_else(|| today - Duration::days(365 * years))
+    };
+    let latest = years_ago(min);
+    let earliest = years_ago(max + 1) + Duration::days(1);
+    let span = (latest - earliest).num_days();
+    earliest + Duration::days(rng.gen_range(0..=span))
+}
+
+/// Generate `profile`'s identity for `country` as of `today`.
+pub fn generate(profile: &Profile, country: &str, today: NaiveDate) -> Result<SyntheticIdentity> {
+    let data = country_data(country)?;
+    let code_bits = data.code.bytes().fold(0u64, |acc, b| (acc << 8) | b as u64);
+    let mut rng = SmallRng::seed_from_u64(profile.fingerprint.seed ^ IDENTITY_SALT ^ code_bits);
+    let pick = |rng: &mut SmallRng, list: &'static [&'static str]| *list.choose(rng).unwrap();
+
+    let mut first_name = pick(&mut rng, data.given).to_string();
+    let mut last_name = pick(&mut rng, data.family).to_string();
+    if data.two_surnames {
+        let second = pick(&mut rng, data.family);
+        if second != last_name {
+            last_name = format!("{last_name} {second}");
+        }
+    }
+    let persona = profile.persona.as_ref();
+    if let Some((first, last)) = persona.and_then(|p| p.name.trim().split_once(' ')) {
+        first_name = first.to_string();
+        last_name = last.trim().to_string();
+    }
+
+    let bracket = persona.map(|p| p.age_bracket).unwrap_or_default();
+    let birthdate = birthdate(&mut rng, today, age_range(bracket));
+
+    let home = persona
+        .and_then(|p| p.home_city.as_deref())
+        .and_then(|home| {
+            data.cities
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(home.trim()))
+        });
+    let city = match home {
+        Some(city) => city,
+        None => data.cities.choose(&mut rng).unwrap(),
+    };
+
+    let street = pick(&mut rng, data.streets);
+    let number = rng.gen_range(1..=data.max_house_number);
+    let address_line1 = match data.address {
+        AddressStyle::HouseFirst => format!("{number} {street}"),
+        AddressStyle::StreetFirst => format!("{street} {number}"),
+        AddressStyle::StreetComma => format!("{street}, {number}"),
+    };
+
+    let phone_pattern = pick(&mut rng, data.phones).replace("{area}", city.area);
+    let phone = fill_pattern(&mut rng, &phone_pattern);
+    let postal_code = fill_pattern(&mut rng, city.postal);
+    // The tables have to produce what the validators accept
+    let phone_e164 = normalize_phone(data.code, &phone).ok_or_else(|| {
+        ManifoldError::Other(format!("generated an invalid {} phone: {phone}", data.code))
+    })?;
+    if !postal_code_valid(data.code, &postal_code) {
+        return Err(ManifoldError::Other(format!(
+            "generated an invalid {} postal code: {postal_code}",
+            data.code
+        )));
+    }
+
+    Ok(SyntheticIdentity {
+        country: data.code.into(),
+        country_name: data.name.into(),
+        full_name: format!("{first_name} {last_name}"),
+        first_name,
+        last_name,
+        birthdate,
+        birthdate_local: birthdate.format(data.date_format).to_string(),
+        phone,
+        phone_e164,
+        address_line1,
+        city: city.name.into(),
+        region: city.region.into(),
+        postal_code,
+    })
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct SyntheticIdentityRepo {
+    db: Db,
+}
+
+impl SyntheticIdentityRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn get(&self, profile_id: &str) -> Result<Option<SyntheticIdentity>> {
+        let stored: Option<String> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT identity FROM synthetic_identities WHERE profile_id = ?1",
+                    params![profile_id],
+                    |r| r.get(0),
+                )
+                .optional()?)
+        })?;
+        stored
+            .map(|s| Ok(serde_json::from_str(&self.db.decrypt_field(&s)?)?))
+            .transpose()
+    }
+
+    /// Store or replace a profile's identity.
+    pub fn save(&self, profile_id: &str, identity: &SyntheticIdentity) -> Result<()> {
+        let encrypted = self.db.encrypt_field(&serde_json::to_string(identity)?)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                r#"INSERT INTO synthetic_identities (profile_id, country, identity, created_at)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT(profile_id) DO UPDATE SET
+                       country = excluded.country,
+                       identity = excluded.identity,
+                       created_at = excluded.created_at"#,
+                params![
+                    profile_id,
+                    identity.country,
+                    encrypted,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, profile_id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM synthetic_identities WHERE profile_id = ?1",
+                params![profile_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// The profile's stored identity, generated and stored first when there
+    /// is none, its country isn't `country` any more, or `regenerate`.
+    pub fn ensure(
+        &self,
+        profile: &Profile,
+        country: &str,
+        regenerate: bool,
+    ) -> Result<SyntheticIdentity> {
+        if !regenerate {
+            if let Some(stored) = self.get(&profile.id)? {
+                if stored.country.eq_ignore_ascii_case(country) {
+                    return Ok(stored);
+                }
+            }
+        }
+        let identity = generate(profile, country, Utc::now().date_naive())?;
+        self.save(&profile.id, &identity)?;
+        Ok(identity)
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persona::Persona;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+
+    fn profile(db: &Db, persona: Option<Persona>) -> Profile {
+        ProfileRepo::new(db.clone())
+            .create(CreateProfileRequest {
+                name: "signup".into(),
+                seed: Some(42),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                persona,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn identities_are_seeded_per_profile_and_fit_the_country() {
+        let db = Db::open_in_memory().unwrap();
+        let p = profile(&db, None);
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+
+        let de = generate(&p, "de", today).unwrap();
+        assert_eq!(de, generate(&p, "DE", today).unwrap());
+        assert_eq!(de.country, "DE");
+        assert!(de.phone.starts_with("01"), "{}", de.phone);
+        assert!(de.phone_e164.starts_with("+491"), "{}", de.phone_e164);
+        assert_eq!(de.postal_code.len(), 5);
+        assert!(de.address_line1.ends_with(char::is_numeric));
+        assert_eq!(
+            de.birthdate_local,
+            de.birthdate.format("%d.%m.%Y").to_string()
+        );
+        // No persona: the default 25-34 bracket
+        let age = today.years_since(de.birthdate).unwrap();
+        assert!((25..=34).contains(&age), "{age}");
+
+        let us = generate(&p, "US", today).unwrap();
+        let area = &us.phone[1..4];
+        assert_eq!(us.phone_e164.len(), 12);
+        assert!(us.phone_e164.starts_with(&format!("+1{area}")));
+        assert!(us.address_line1.starts_with(char::is_numeric));
+
+        let vars = us.variables();
+        assert_eq!(vars["identity.city"], us.city);
+        assert_eq!(vars["identity.birth_year"], us.birthdate.year().to_string());
+        assert!(vars.keys().all(|k| crate::workflow::is_variable_name(k)));
+
+        assert!(matches!(
+            generate(&p, "ZZ", today),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+    }
+
+    #[test]
+    fn personas_and_geo_drive_the_identity() {
+        let db = Db::open_in_memory().unwrap();
+        let p = profile(
+            &db,
+            Some(Persona {
+                name: "Ann Marie Keller".into(),
+                age_bracket: AgeBracket::Age45To54,
+                interests: Vec::new(),
+                home_city: Some("Chicago".into()),
+            }),
+        );
+        assert_eq!(profile_country(&p, Some("gb")).as_deref(), Some("GB"));
+        assert_eq!(profile_country(&p, None).as_deref(), Some("US"));
+
+        let today = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+        let us = generate(&p, "US", today).unwrap();
+        assert_eq!(
+            (us.first_name.as_str(), us.last_name.as_str()),
+            ("Ann", "Marie Keller")
+        );
+        assert_eq!((us.city.as_str(), us.region.as_str()), ("Chicago", "IL"));
+        assert!(us.postal_code.starts_with("606"));
+        assert!((45..=54).contains(&today.years_since(us.birthdate).unwrap()));
+
+        let repo = SyntheticIdentityRepo::new(db.clone());
+        assert!(repo.get(&p.id).unwrap().is_none());
+        let stored = repo.ensure(&p, "US", false).unwrap();
+        assert_eq!(repo.ensure(&p, "us", false).unwrap(), stored);
+        // The geo moved: the stored identity no longer fits
+        let gb = repo.ensure(&p, "GB", false).unwrap();
+        assert_eq!(gb.country, "GB");
+        assert_eq!(repo.get(&p.id).unwrap(), Some(gb));
+
+        ProfileRepo::new(db).delete(&p.id).unwrap();
+        assert!(repo.get(&p.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn validators_accept_every_generated_identity_and_catch_foreign_data() {
+        let db = Db::open_in_memory().unwrap();
+        let mut p = profile(&db, None);
+        let today = NaiveDate::from_ymd_opt(2026, 6, 15).unwrap();
+        for seed in 0..25 {
+            p.fingerprint.seed = seed;
+            for country in supported_countries() {
+                let identity = generate(&p, country, today).unwrap();
+                let report = validate(&p, Some(country), Some(&identity));
+                assert!(
+                    report.issues.is_empty(),
+                    "{identity:?}: {:?}",
+                    report.issues
+                );
+            }
+        }
+
+        assert!(postal_code_valid("gb", "sw1a 1aa"));
+        assert!(!postal_code_valid("DE", "1010"));
+        assert!(!postal_code_valid("NL", "1011AB"));
+        assert_eq!(
+            normalize_phone("DE", "0049 (0)151 2345 6789"),
+            None,
+            "the trunk 0 doesn't belong after the country code"
+        );
+        assert_eq!(
+            normalize_phone("DE", "+49 151 23456789").as_deref(),
+            Some("+4915123456789")
+        );
+        assert_eq!(
+            normalize_phone("US", "1-212-555-0123").as_deref(),
+            Some("+12125550123")
+        );
+        assert_eq!(normalize_phone("US", "(012) 555-0123"), None);
+        assert_eq!(
+            normalize_phone("IT", "06 6982 1234").as_deref(),
+            Some("+390669821234")
+        );
+        assert_eq!(normalize_phone("FR", "+49 151 23456789"), None);
+
+        // A British identity left on a profile that now browses from Germany
+        let gb = generate(&p, "GB", today).unwrap();
+        let report = validate(&p, Some("de"), Some(&gb));
+        let codes: Vec<_> = report.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, ["country_mismatch", "postal_code", "phone"]);
+        assert_eq!(report.country.as_deref(), Some("DE"));
+
+        p.persona = Some(Persona {
+            name: String::new(),
+            home_city: Some("Boston".into()),
+            ..Persona::default()
+        });
+        let report = validate(&p, Some("DE"), None);
+        assert!(!report.has_identity);
+        assert_eq!(report.issues[0].field, "persona.home_city");
+    }
+}
@@ -0,0 +1,267 @@
+// ── Manifold session timeline ─────────────────────────────────────────────────
+//
+// An exported session bundle (`export_session`) holds what the frontend's
+// recorder logged — navigations, clicks, typing, scrolls, extracts, script
+// runs, screenshots and bridge errors — next to the raw HAR.  The timeline
+// turns both into one ordered list of typed entries the frontend can render
+// and step through without parsing HAR itself:
+//
+//   navigation   recorder navigations; from HAR documents for bundles
+//                exported before the recorder's log was included
+//   action       click / type / scroll / extract / execute
+//   wait         an idle gap of at least WAIT_GAP_MS between two entries
+//   error        bridge errors, and documents answered with an HTTP error
+//   screenshot   when one was taken (the image itself isn't in the bundle)
+//
+// Typed text never reaches the timeline, only its length: it may be a
+// password the recorder saw going into a field.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Idle time between two entries shown as a wait.
+pub const WAIT_GAP_MS: i64 = 2_000;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineKind {
+    Navigation {
+        url: String,
+        status: Option<u16>,
+    },
+    Action {
+        /// click | type | scroll | extract | execute
+        action: String,
+        selector: Option<String>,
+        /// Characters typed, for `type`.
+        chars: Option<usize>,
+    },
+    Wait {
+        ms: i64,
+    },
+    Error {
+        message: String,
+    },
+    Screenshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    pub at: DateTime<Utc>,
+    /// Milliseconds since the session's first entry.
+    pub offset_ms: i64,
+    #[serde(flatten)]
+    pub kind: TimelineKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionTimeline {
+    pub profile_id: String,
+    /// The bundle's name, as `list_sessions` reports it.
+    pub session: String,
+    pub exported_at: Option<DateTime<Utc>>,
+    pub entries: Vec<TimelineEntry>,
+}
+
+// ── Assembly ──────────────────────────────────────────────────────────────────
+
+/// The timeline of an exported session bundle.
+pub fn build(profile_id: &str, session: &str, bundle: &Value) -> SessionTimeline {
+    let mut timed: Vec<(i64, TimelineKind)> = bundle["events"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(recorder_event)
+        .collect();
+    let recorded_navigations = timed
+        .iter()
+        .any(|(_, kind)| matches!(kind, TimelineKind::Navigation { .. }));
+    let har = bundle["har"]["log"]["entries"]
+        .as_array()
+        .into_iter()
+        .flatten();
+    timed.extend(har.filter_map(|entry| har_document(entry, !recorded_navigations)));
+    // Stable: entries logged in the same millisecond keep their order
+    timed.sort_by_key(|(ms, _)| *ms);
+
+    let start = timed.first().map_or(0, |(ms, _)| *ms);
+    let mut entries = Vec::with_capacity(timed.len());
+    let mut previous = start;
+    for (ms, kind) in timed {
+        if ms - previous >= WAIT_GAP_MS {
+            entries.push(entry(
+                previous,
+                start,
+                TimelineKind::Wait { ms: ms - previous },
+            ));
+        }
+        entries.push(entry(ms, start, kind));
+        previous = ms;
+    }
+
+    SessionTimeline {
+        profile_id: profile_id.into(),
+        session: session.into(),
+        exported_at: bundle["exported_at"]
+            .as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc)),
+        entries,
+    }
+}
+
+fn entry(ms: i64, start: i64, kind: TimelineKind) -> TimelineEntry {
+    TimelineEntry {
+        at: DateTime::from_timestamp_millis(ms).unwrap_or_default(),
+        offset_ms: ms - start,
+        kind,
+    }
+}
+
+/// One recorder event (`{ts, type, data}`); entropy snapshots and the
+/// session's "stopped" marker aren't timeline entries.
+fn recorder_event(event: &Value) -> Option<(i64, TimelineKind)> {
+    let ts = event["ts"].as_f64()? as i64;
+    let data = &event["data"];
+    let text = |key: &str| data[key].as_str().map(str::to_string);
+    let kind = match event["type"].as_str()? {
+        "navigate" => {
+            let url = text("url").filter(|u| u != "stopped")?;
+            TimelineKind::Navigation {
+                url,
+                status: data["status"].as_u64().map(|s| s as u16),
+            }
+        }
+        action @ ("click" | "type" | "scroll" | "extract" | "execute") => TimelineKind::Action {
+            action: action.into(),
+            selector: text("selector"),
+            chars: data["text"].as_str().map(|t| t.chars().count()),
+        },
+        "screenshot" => TimelineKind::Screenshot,
+        "error" => TimelineKind::Error {
+            message: text("error").unwrap_or_default(),
+        },
+        _ => return None,
+    };
+    Some((ts, kind))
+}
+
+/// An HTML document from the HAR: an error when it was answered with one,
+/// else a navigation when `navigations` (no recorder log to take them from).
+fn har_document(entry: &Value, navigations: bool) -> Option<(i64, TimelineKind)> {
+    let mime = entry["response"]["content"]["mimeType"].as_str()?;
+    if !mime.starts_with("text/html") {
+        return None;
+    }
+    let ms = DateTime::parse_from_rfc3339(entry["startedDateTime"].as_str()?)
+        .ok()?
+        .timestamp_millis();
+    let url = entry["request"]["url"].as_str()?.to_string();
+    let status = entry["response"]["status"].as_u64().map(|s| s as u16);
+    match status {
+        Some(status) if status >= 400 => Some((
+            ms,
+            TimelineKind::Error {
+                message: format!("HTTP {status} for {url}"),
+            },
+        )),
+        _ if navigations => Some((ms, TimelineKind::Navigation { url, status })),
+        _ => None,
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const T0: i64 = 1_767_225_600_000;
+
+    fn har_entry(offset_ms: i64, url: &str, status: u16, mime: &str) -> Value {
+        let at = DateTime::from_timestamp_millis(T0 + offset_ms).unwrap();
+        json!({
+            "startedDateTime": at.to_rfc3339(),
+            "request": { "method": "GET", "url": url },
+            "response": { "status": status, "content": { "mimeType": mime } },
+        })
+    }
+
+    #[test]
+    fn recorder_log_and_har_make_one_ordered_timeline() {
+        let bundle = json!({
+            "exported_at": "2026-01-01T00:01:00Z",
+            "events": [
+                { "ts": T0, "type": "navigate", "data": { "url": "https://a.test/", "status": 200 } },
+                { "ts": T0 + 500, "type": "click", "data": { "selector": "#login" } },
+                { "ts": T0 + 800, "type": "type", "data": { "selector": "#pw", "text": "hunter2" } },
+                { "ts": T0 + 900, "type": "entropy", "data": {} },
+                { "ts": T0 + 5_800, "type": "screenshot", "data": {} },
+                { "ts": T0 + 6_000, "type": "error", "data": { "error": "click failed" } },
+                { "ts": T0 + 6_100, "type": "navigate", "data": { "url": "stopped" } },
+            ],
+            "har": { "log": { "entries": [
+                har_entry(1_000, "https://a.test/app.js", 200, "application/javascript"),
+                har_entry(2_000, "https://a.test/next", 200, "text/html; charset=utf-8"),
+                har_entry(5_900, "https://a.test/blocked", 403, "text/html"),
+            ] } },
+        });
+        let timeline = build("p1", "20260101T000100", &bundle);
+        let kinds: Vec<&TimelineKind> = timeline.entries.iter().map(|e| &e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &TimelineKind::Navigation {
+                    url: "https://a.test/".into(),
+                    status: Some(200)
+                },
+                &TimelineKind::Action {
+                    action: "click".into(),
+                    selector: Some("#login".into()),
+                    chars: None
+                },
+                &TimelineKind::Action {
+                    action: "type".into(),
+                    selector: Some("#pw".into()),
+                    chars: Some(7)
+                },
+                &TimelineKind::Wait { ms: 5_000 },
+                &TimelineKind::Screenshot,
+                &TimelineKind::Error {
+                    message: "HTTP 403 for https://a.test/blocked".into()
+                },
+                &TimelineKind::Error {
+                    message: "click failed".into()
+                },
+            ]
+        );
+        assert_eq!(timeline.entries[3].offset_ms, 800);
+        assert_eq!(timeline.entries[6].offset_ms, 6_000);
+        assert!(timeline.exported_at.is_some());
+        let json = serde_json::to_value(&timeline.entries[3]).unwrap();
+        assert_eq!(json["kind"], "wait");
+        assert_eq!(json["ms"], 5_000);
+    }
+
+    #[test]
+    fn bundles_without_a_recorder_log_take_navigations_from_har() {
+        let bundle = json!({
+            "har": { "log": { "entries": [
+                har_entry(0, "https://a.test/", 200, "text/html"),
+                har_entry(300, "https://a.test/x.css", 200, "text/css"),
+                har_entry(400, "https://a.test/two", 302, "text/html"),
+            ] } },
+        });
+        let timeline = build("p1", "s", &bundle);
+        assert_eq!(timeline.entries.len(), 2);
+        assert!(timeline
+            .entries
+            .iter()
+            .all(|e| matches!(e.kind, TimelineKind::Navigation { .. })));
+        assert!(build("p1", "s", &json!({})).entries.is_empty());
+    }
+}
@@ -1,27 +1,292 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
 use tokio::sync::Mutex;
 
 use rustls::pki_types::ServerName;
+use serde::Serialize;
 use tokio_rustls::rustls::{ClientConfig, RootCertStore};
 use tokio_rustls::TlsConnector;
 use webpki_roots::TLS_SERVER_ROOTS;
 
+use crate::bandwidth::{CountingStream, UsageMeter};
+use crate::fingerprint::Fingerprint;
+use crate::header_order::chrome_header_order;
+use crate::proxy::{Proxy, ProxyType};
+
 // ── TLS Bridge Configuration ───────────────────────────────────────────────
 
 #[derive(Debug, Clone)]
 pub struct TlsBridgeConfig {
     /// Seed for bridge identification
     pub seed: u64,
+    /// Proxy outbound connections go through (HTTP CONNECT or SOCKS5), so
+    /// JA4-controlled traffic still exits from the profile's IP.  SSH
+    /// proxies must already be resolved to their local tunnel.
+    pub upstream: Option<Proxy>,
+    /// ClientHello features matched to the fingerprint's browser.
+    pub tls: TlsProfile,
+    /// Where the bytes exchanged with the upstream are charged.
+    pub usage: Option<UsageMeter>,
 }
 
 impl TlsBridgeConfig {
     pub fn new(seed: u64) -> Self {
-        Self { seed }
+        Self {
+            seed,
+            upstream: None,
+            tls: TlsProfile::default(),
+            usage: None,
+        }
+    }
+
+    pub fn with_tls_profile(mut self, tls: TlsProfile) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_upstream(mut self, upstream: Option<Proxy>) -> Self {
+        self.upstream = upstream;
+        self
+    }
+
+    pub fn with_usage_meter(mut self, usage: Option<UsageMeter>) -> Self {
+        self.usage = usage;
+        self
+    }
+}
+
+// ── ClientHello profile ─────────────────────────────────────────────────────
+
+/// First Chrome major that sends an ECH GREASE extension.
+pub const ECH_GREASE_SINCE: u32 = 117;
+/// First Chrome major with a hybrid post-quantum key share.  124–130 sent
+/// X25519Kyber768Draft00, which rustls doesn't implement; those majors get
+/// its successor X25519MLKEM768 (131+) instead.  JA4 doesn't hash groups,
+/// so only JA3-style fingerprints see the difference.
+pub const PQ_KEY_SHARE_SINCE: u32 = 124;
+
+/// Optional ClientHello features whose presence must match the claimed
+/// browser version — sending them from an "old" Chrome is as much a tell
+/// as omitting them from a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TlsProfile {
+    /// Offer X25519MLKEM768 first (hybrid post-quantum key share).
+    pub post_quantum: bool,
+    /// Send a GREASE `encrypted_client_hello` extension.  Restricts the
+    /// bridge to TLS 1.3, as ECH requires.
+    pub ech_grease: bool,
+}
+
+impl TlsProfile {
+    /// Features of the given Chrome major; `None` (not Chrome) gets neither.
+    pub fn for_chrome(major: Option<u32>) -> Self {
+        let major = major.unwrap_or(0);
+        Self {
+            post_quantum: major >= PQ_KEY_SHARE_SINCE,
+            ech_grease: major >= ECH_GREASE_SINCE,
+        }
+    }
+
+    /// Profile for a user agent string.
+    pub fn for_user_agent(user_agent: &str) -> Self {
+        let major = crate::fingerprint::chrome_version(user_agent)
+            .and_then(|v| v.split('.').next()?.parse().ok());
+        Self::for_chrome(major)
+    }
+}
+
+/// Build the outbound client config for `profile`.
+pub fn build_client_config(profile: &TlsProfile) -> std::io::Result<ClientConfig> {
+    use rustls::crypto::aws_lc_rs::{self as provider, kx_group};
+
+    let mut root_store = RootCertStore::empty();
+    root_store.extend(TLS_SERVER_ROOTS.iter().cloned());
+
+    // Order matters: the first group gets the key share
+    let mut kx_groups = vec![kx_group::X25519, kx_group::SECP256R1, kx_group::SECP384R1];
+    if profile.post_quantum {
+        kx_groups.insert(0, kx_group::X25519MLKEM768);
+    }
+    let crypto = Arc::new(rustls::crypto::CryptoProvider {
+        kx_groups,
+        ..provider::default_provider()
+    });
+
+    let to_io = std::io::Error::other;
+    let builder = ClientConfig::builder_with_provider(crypto);
+    let builder = if profile.ech_grease {
+        use rand::RngCore;
+        // Any 32 bytes are a valid X25519 public key for the placeholder
+        let mut placeholder = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut placeholder);
+        let grease = rustls::client::EchGreaseConfig::new(
+            provider::hpke::DH_KEM_X25519_HKDF_SHA256_AES_128,
+            rustls::crypto::hpke::HpkePublicKey(placeholder),
+        );
+        builder.with_ech(grease.into()).map_err(to_io)?
+    } else {
+        builder
+            .with_safe_default_protocol_versions()
+            .map_err(to_io)?
+    };
+    Ok(builder
+        .with_root_certificates(root_store)
+        .with_no_client_auth())
+}
+
+// ── HTTP/3 policy ───────────────────────────────────────────────────────────
+
+/// Chromium flags for a browser routed through the bridge.  The bridge only
+/// carries TCP, so h3 is blocked outright: with QUIC enabled, `Alt-Svc: h3`
+/// upgrades would either bypass the bridge over UDP or fail and fall back
+/// per-origin — both patterns Chrome never shows.  With QUIC off, every
+/// origin consistently speaks h2/h1 over TLS, as Chrome does behind an
+/// enterprise proxy.
+pub const BRIDGE_CHROMIUM_ARGS: [&str; 1] = ["--disable-quic"];
+
+// ── Upstream proxy ──────────────────────────────────────────────────────────
+
+/// Upper bound on reaching the upstream proxy itself.
+const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Open a TCP stream to `host:port`, through `upstream` when set.
+pub async fn connect_upstream(
+    upstream: Option<&Proxy>,
+    host: &str,
+    port: u16,
+) -> std::io::Result<TcpStream> {
+    let Some(proxy) = upstream else {
+        return TcpStream::connect((host, port)).await;
+    };
+    // Dial the proxy the way health checks do: its pinned IP, or its
+    // resolved addresses raced Happy Eyeballs style
+    let dial = proxy.clone();
+    let (stream, _) = tokio::task::spawn_blocking(move || {
+        crate::dns::connect_proxy(&dial, UPSTREAM_CONNECT_TIMEOUT)
+    })
+    .await
+    .map_err(std::io::Error::other)?
+    .map_err(|e| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, e))?;
+    stream.set_nonblocking(true)?;
+    let mut stream = TcpStream::from_std(stream)?;
+    match proxy.proxy_type {
+        ProxyType::Http | ProxyType::Https => http_connect(&mut stream, proxy, host, port).await?,
+        ProxyType::Socks5 => socks5_connect(&mut stream, proxy, host, port).await?,
+        ProxyType::Ssh => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "SSH proxies must be resolved to their local tunnel first",
+            ))
+        }
     }
+    Ok(stream)
+}
+
+async fn http_connect(
+    stream: &mut TcpStream,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> std::io::Result<()> {
+    let mut req = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+        use base64::Engine;
+        let creds = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{pass}"));
+        req.push_str(&format!("Proxy-Authorization: Basic {creds}\r\n"));
+    }
+    req.push_str("\r\n");
+    stream.write_all(req.as_bytes()).await?;
+
+    // Read byte-wise so nothing past the header block is consumed
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte).await? == 0 || head.len() > 8192 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "upstream proxy closed during CONNECT",
+            ));
+        }
+        head.push(byte[0]);
+    }
+    let status = String::from_utf8_lossy(&head);
+    let status = status.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            format!("upstream CONNECT rejected: {status}"),
+        ));
+    }
+    Ok(())
+}
+
+async fn socks5_connect(
+    stream: &mut TcpStream,
+    proxy: &Proxy,
+    host: &str,
+    port: u16,
+) -> std::io::Result<()> {
+    let refused = |msg: &str| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, msg);
+
+    let greeting: &[u8] = if proxy.username.is_some() {
+        &[0x05, 0x02, 0x00, 0x02]
+    } else {
+        &[0x05, 0x01, 0x00]
+    };
+    stream.write_all(greeting).await?;
+    let mut resp = [0u8; 2];
+    stream.read_exact(&mut resp).await?;
+    match resp {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] => {
+            // Username/password sub-negotiation (RFC 1929)
+            let user = proxy.username.as_deref().unwrap_or("");
+            let pass = proxy.password.as_deref().unwrap_or("");
+            let mut auth = vec![0x01u8, user.len() as u8];
+            auth.extend_from_slice(user.as_bytes());
+            auth.push(pass.len() as u8);
+            auth.extend_from_slice(pass.as_bytes());
+            stream.write_all(&auth).await?;
+            let mut ar = [0u8; 2];
+            stream.read_exact(&mut ar).await?;
+            if ar[1] != 0x00 {
+                return Err(refused("upstream SOCKS5 authentication failed"));
+            }
+        }
+        _ => return Err(refused("upstream is not a usable SOCKS5 server")),
+    }
+
+    // CONNECT by domain name so the exit resolves the target
+    let mut req = vec![0x05, 0x01, 0x00, 0x03, host.len() as u8];
+    req.extend_from_slice(host.as_bytes());
+    req.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&req).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0x00 {
+        return Err(refused(&format!(
+            "upstream SOCKS5 CONNECT failed (code {})",
+            reply[1]
+        )));
+    }
+    let addr_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        _ => return Err(refused("bad SOCKS5 reply")),
+    };
+    let mut rest = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut rest).await?;
+    Ok(())
 }
 
 // ── TLS Bridge Server ───────────────────────────────────────────────────────
@@ -36,12 +301,19 @@ pub struct TlsBridge {
 }
 
 impl TlsBridge {
-    /// Create new TLS bridge on specified port
-    pub async fn new(port: u16, seed: u64) -> std::io::Result<Self> {
+    /// Create new TLS bridge on specified port with full configuration
+    pub async fn with_config(port: u16, config: TlsBridgeConfig) -> std::io::Result<Self> {
         let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-        let config = TlsBridgeConfig::new(seed);
 
-        println!("[tls-bridge] Started on port {} with seed: {}", port, seed);
+        println!(
+            "[tls-bridge] Started on port {} with seed: {}{}",
+            port,
+            config.seed,
+            match &config.upstream {
+                Some(p) => format!(" via {}", p.to_playwright_server()),
+                None => String::new(),
+            }
+        );
 
         Ok(TlsBridge {
             listener,
@@ -89,7 +361,7 @@ impl TlsBridge {
     /// Handle individual proxy connection
     async fn handle_connection(
         mut client_stream: TcpStream,
-        _config: TlsBridgeConfig,
+        config: TlsBridgeConfig,
     ) -> std::io::Result<()> {
         // Read CONNECT request from Playwright
         let mut buffer = [0u8; 4096];
@@ -139,26 +411,27 @@ impl TlsBridge {
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
 
         // Establish outbound TLS connection with fingerprinting
-        let mut root_store = RootCertStore::empty();
-        root_store.extend(TLS_SERVER_ROOTS.iter().cloned());
-        let tls_config = ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
+        let tls_config = build_client_config(&config.tls)?;
         let connector = TlsConnector::from(std::sync::Arc::new(tls_config));
-        let tcp_stream = TcpStream::connect((host.as_str(), port))
+        let tcp_stream = connect_upstream(config.upstream.as_ref(), &host, port)
             .await
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
         let host_static: &'static str = Box::leak(host.into_boxed_str());
         let server_name = ServerName::try_from(host_static).unwrap();
-        let target_stream = connector
-            .connect(server_name, tcp_stream)
+        let mut target_stream = connector
+            .connect(server_name, CountingStream::new(tcp_stream))
             .await
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
 
         // Proxy data bidirectionally
-        Self::proxy_streams(client_stream, target_stream).await?;
+        let result = Self::proxy_streams(client_stream, &mut target_stream).await;
 
-        Ok(())
+        // Charged even when the connection ended in an error: the bytes
+        // went through the proxy all the same
+        if let Some(meter) = &config.usage {
+            meter.record(target_stream.get_ref().0.usage());
+        }
+        result
     }
 
     /// Bidirectional data proxying
@@ -181,6 +454,269 @@ impl TlsBridge {
     }
 }
 
+// ── JA4 preview ─────────────────────────────────────────────────────────────
+
+/// The JA4 / JA4H a profile's traffic shows through the bridge, computed
+/// from the ClientHello the bridge actually builds for it (no packet
+/// capture needed) and from Chrome's header order.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpectedJa4 {
+    /// Whether the profile routes through the bridge at all.  Without it the
+    /// browser's own ClientHello is seen and `ja4` doesn't apply.
+    pub bridge_enabled: bool,
+    pub chrome_major: Option<u32>,
+    pub post_quantum: bool,
+    pub ech_grease: bool,
+    /// e.g. `t13d1516h2_8daaf6152771_e5627efa2ab1`; sites log this.
+    pub ja4: String,
+    /// Unhashed JA4 (`ja4_r`): the sorted ciphers and extensions, to see
+    /// which part of a mismatch differs.
+    pub ja4_r: String,
+    /// JA4H of a first navigation to a site: no cookie, no referer.
+    pub ja4h: String,
+}
+
+/// The ClientHello fields JA4 is computed from.
+#[derive(Debug, Default)]
+struct HelloFields {
+    legacy_version: u16,
+    ciphers: Vec<u16>,
+    extensions: Vec<u16>,
+    versions: Vec<u16>,
+    alpn: Vec<Vec<u8>>,
+    signature_algorithms: Vec<u16>,
+}
+
+/// Expected JA4 and JA4H for a profile's fingerprint.
+pub fn expected_ja4(fp: &Fingerprint, bridge_enabled: bool) -> std::io::Result<ExpectedJa4> {
+    let tls = TlsProfile::for_user_agent(&fp.user_agent);
+    let hello = parse_client_hello(&client_hello(&tls, "example.com")?)?;
+    let (ja4, ja4_r) = ja4(&hello);
+    let headers = chrome_header_order(fp);
+    Ok(ExpectedJa4 {
+        bridge_enabled,
+        chrome_major: headers.chrome_major,
+        post_quantum: tls.post_quantum,
+        ech_grease: tls.ech_grease,
+        ja4,
+        ja4_r,
+        // The bridge offers no ALPN, so origins speak HTTP/1.1 through it
+        ja4h: ja4h(
+            "GET",
+            "11",
+            &first_navigation(&headers.navigation.http1),
+            &fp.accept_language,
+        ),
+    })
+}
+
+/// The first TLS record the bridge sends to `server_name`.
+fn client_hello(profile: &TlsProfile, server_name: &str) -> std::io::Result<Vec<u8>> {
+    let config = Arc::new(build_client_config(profile)?);
+    let name = ServerName::try_from(server_name.to_string()).map_err(std::io::Error::other)?;
+    let mut conn = rustls::ClientConnection::new(config, name).map_err(std::io::Error::other)?;
+    let mut out = Vec::new();
+    while conn.wants_write() {
+        conn.write_tls(&mut out)?;
+    }
+    Ok(out)
+}
+
+fn parse_client_hello(record: &[u8]) -> std::io::Result<HelloFields> {
+    let bad = || std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed ClientHello");
+    let mut r = Reader(record);
+    // Record header, then the handshake header
+    if r.u8().ok_or_else(bad)? != 22 {
+        return Err(bad());
+    }
+    r.take(4).ok_or_else(bad)?;
+    if r.u8().ok_or_else(bad)? != 1 {
+        return Err(bad());
+    }
+    r.take(3).ok_or_else(bad)?;
+
+    let mut hello = HelloFields {
+        legacy_version: r.u16().ok_or_else(bad)?,
+        ..HelloFields::default()
+    };
+    r.take(32).ok_or_else(bad)?; // random
+    r.vec8().ok_or_else(bad)?; // session id
+    hello.ciphers = Reader(r.vec16().ok_or_else(bad)?).u16s();
+    r.vec8().ok_or_else(bad)?; // compression methods
+
+    let mut exts = Reader(r.vec16().ok_or_else(bad)?);
+    while !exts.0.is_empty() {
+        let kind = exts.u16().ok_or_else(bad)?;
+        let mut body = Reader(exts.vec16().ok_or_else(bad)?);
+        hello.extensions.push(kind);
+        match kind {
+            0x0010 => {
+                let mut list = Reader(body.vec16().ok_or_else(bad)?);
+                while let Some(proto) = list.vec8() {
+                    hello.alpn.push(proto.to_vec());
+                }
+            }
+            0x000d => hello.signature_algorithms = Reader(body.vec16().ok_or_else(bad)?).u16s(),
+            0x002b => hello.versions = Reader(body.vec8().ok_or_else(bad)?).u16s(),
+            _ => {}
+        }
+    }
+    Ok(hello)
+}
+
+/// Big-endian cursor over a TLS message.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn vec8(&mut self) -> Option<&'a [u8]> {
+        let n = self.u8()? as usize;
+        self.take(n)
+    }
+
+    fn vec16(&mut self) -> Option<&'a [u8]> {
+        let n = self.u16()? as usize;
+        self.take(n)
+    }
+
+    fn u16s(mut self) -> Vec<u16> {
+        std::iter::from_fn(|| self.u16()).collect()
+    }
+}
+
+/// RFC 8701 GREASE values (`0x0a0a`, `0x1a1a`, …), which JA4 ignores.
+fn is_grease(v: u16) -> bool {
+    v & 0x0f0f == 0x0a0a && v >> 8 == v & 0xff
+}
+
+/// First 12 hex digits of the SHA-256 of `text`; zeros for nothing.
+fn ja4_hash(text: &str) -> String {
+    use sha2::{Digest, Sha256};
+    if text.is_empty() {
+        return "0".repeat(12);
+    }
+    hex::encode(Sha256::digest(text.as_bytes()))[..12].to_string()
+}
+
+fn hex_list(values: &[u16]) -> String {
+    values
+        .iter()
+        .map(|v| format!("{v:04x}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// JA4 and its raw form for a parsed ClientHello (FoxIO spec, TCP).
+fn ja4(hello: &HelloFields) -> (String, String) {
+    let version = hello
+        .versions
+        .iter()
+        .copied()
+        .filter(|v| !is_grease(*v))
+        .max()
+        .unwrap_or(hello.legacy_version);
+    let version = match version {
+        0x0304 => "13",
+        0x0303 => "12",
+        0x0302 => "11",
+        0x0301 => "10",
+        _ => "00",
+    };
+    let sni = if hello.extensions.contains(&0x0000) {
+        'd'
+    } else {
+        'i'
+    };
+    let mut ciphers: Vec<u16> = hello
+        .ciphers
+        .iter()
+        .copied()
+        .filter(|v| !is_grease(*v))
+        .collect();
+    let extensions: Vec<u16> = hello
+        .extensions
+        .iter()
+        .copied()
+        .filter(|v| !is_grease(*v))
+        .collect();
+    let alpn = match hello.alpn.first() {
+        Some(p) if !p.is_empty() => format!("{}{}", p[0] as char, p[p.len() - 1] as char),
+        _ => "00".into(),
+    };
+    let a = format!(
+        "t{version}{sni}{:02}{:02}{alpn}",
+        ciphers.len().min(99),
+        extensions.len().min(99)
+    );
+
+    ciphers.sort_unstable();
+    let mut sorted_ext: Vec<u16> = extensions
+        .into_iter()
+        .filter(|e| *e != 0x0000 && *e != 0x0010)
+        .collect();
+    sorted_ext.sort_unstable();
+    let b = hex_list(&ciphers);
+    let mut c = hex_list(&sorted_ext);
+    if !hello.signature_algorithms.is_empty() {
+        c = format!("{c}_{}", hex_list(&hello.signature_algorithms));
+    }
+    (
+        format!("{a}_{}_{}", ja4_hash(&b), ja4_hash(&c)),
+        format!("{a}_{b}_{c}"),
+    )
+}
+
+/// Headers of a typed-in navigation: no cookie or referer yet, and no
+/// `cache-control`, which Chrome only adds on reload.
+fn first_navigation(order: &[String]) -> Vec<String> {
+    order
+        .iter()
+        .filter(|h| {
+            !["cache-control", "referer", "cookie", "origin"]
+                .contains(&h.to_ascii_lowercase().as_str())
+        })
+        .cloned()
+        .collect()
+}
+
+/// JA4H for a request without cookies or referer; `headers` in wire order.
+fn ja4h(method: &str, version: &str, headers: &[String], accept_language: &str) -> String {
+    let method: String = method.to_ascii_lowercase().chars().take(2).collect();
+    // First language only: "en-US,en;q=0.9" → "enus", "de" → "de00"
+    let first = accept_language.split([',', ';']).next().unwrap_or_default();
+    let lang: String = first
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| *c != '-')
+        .chain(std::iter::repeat('0'))
+        .take(4)
+        .collect();
+    format!(
+        "{method}{version}nn{:02}{lang}_{}_{}_{}",
+        headers.len().min(99),
+        ja4_hash(&headers.join(",")),
+        ja4_hash(""),
+        ja4_hash("")
+    )
+}
+
 // ── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -191,5 +727,179 @@ mod tests {
     fn test_bridge_config() {
         let config = TlsBridgeConfig::new(12345);
         assert_eq!(config.seed, 12345);
+        assert!(config.upstream.is_none());
+        assert_eq!(config.tls, TlsProfile::default());
+    }
+
+    #[test]
+    fn tls_profile_follows_chrome_major() {
+        let ua = |v: &str| {
+            format!("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v} Safari/537.36")
+        };
+        assert_eq!(
+            TlsProfile::for_user_agent(&ua("133.0.6943.98")),
+            TlsProfile {
+                post_quantum: true,
+                ech_grease: true
+            }
+        );
+        assert_eq!(
+            TlsProfile::for_user_agent(&ua("120.0.6099.71")),
+            TlsProfile {
+                post_quantum: false,
+                ech_grease: true
+            }
+        );
+        assert_eq!(
+            TlsProfile::for_user_agent(&ua("110.0.5481.77")),
+            TlsProfile::default()
+        );
+        assert_eq!(
+            TlsProfile::for_user_agent(
+                "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0"
+            ),
+            TlsProfile::default()
+        );
+    }
+
+    #[test]
+    fn ja4_matches_the_spec_example() {
+        // Chrome example from the FoxIO JA4 specification
+        let hello = HelloFields {
+            legacy_version: 0x0303,
+            ciphers: vec![
+                0x0a0a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8,
+                0xc013, 0xc014, 0x009c, 0x009d, 0x002f, 0x0035,
+            ],
+            extensions: vec![
+                0x1a1a, 0x0000, 0x0017, 0xff01, 0x000a, 0x000b, 0x0023, 0x0010, 0x0005, 0x000d,
+                0x0012, 0x0033, 0x002d, 0x002b, 0x001b, 0x4469, 0x0015,
+            ],
+            versions: vec![0x2a2a, 0x0304, 0x0303],
+            alpn: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            signature_algorithms: vec![
+                0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601,
+            ],
+        };
+        let (ja4, raw) = ja4(&hello);
+        assert_eq!(ja4, "t13d1516h2_8daaf6152771_e5627efa2ab1");
+        assert!(raw.starts_with("t13d1516h2_002f,0035,009c"));
+    }
+
+    #[test]
+    fn expected_ja4_reads_the_bridge_client_hello() {
+        let mut fp = crate::fingerprint::FingerprintOrchestrator::generate(1);
+        fp.user_agent = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.6943.98 Safari/537.36".into();
+        fp.accept_language = "en-US,en;q=0.9".into();
+        let expected = expected_ja4(&fp, true).unwrap();
+        assert!(expected.ech_grease && expected.post_quantum);
+        // TLS 1.3 only with ECH, SNI present, no ALPN offered
+        assert!(expected.ja4.starts_with("t13d"), "{}", expected.ja4);
+        assert!(expected.ja4[..10].ends_with("00"), "{}", expected.ja4);
+        assert!(expected.ja4_r.contains("fe0d"), "{}", expected.ja4_r);
+        assert!(expected.ja4h.starts_with("ge11nn"), "{}", expected.ja4h);
+        assert!(expected.ja4h[..12].ends_with("enus"), "{}", expected.ja4h);
+        assert!(expected.ja4h.ends_with("_000000000000_000000000000"));
+
+        fp.user_agent = fp.user_agent.replace("133.0.6943.98", "110.0.5481.77");
+        let older = expected_ja4(&fp, true).unwrap();
+        assert!(!older.ja4_r.contains("fe0d"));
+        assert_ne!(older.ja4, expected.ja4);
+    }
+
+    #[test]
+    fn client_config_builds_for_every_profile() {
+        for (post_quantum, ech_grease) in
+            [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let profile = TlsProfile {
+                post_quantum,
+                ech_grease,
+            };
+            let config = build_client_config(&profile).unwrap();
+            let first = config.crypto_provider().kx_groups[0].name();
+            assert_eq!(first == rustls::NamedGroup::X25519MLKEM768, post_quantum);
+        }
+    }
+
+    fn upstream(proxy_type: ProxyType, port: u16) -> Proxy {
+        Proxy {
+            id: "u".into(),
+            name: "upstream".into(),
+            proxy_type,
+            host: "127.0.0.1".into(),
+            port,
+            username: Some("user".into()),
+            password: Some("pass".into()),
+            country: None,
+            region: None,
+            ssh_key_path: None,
+            pinned_ip: None,
+            check_mode: Default::default(),
+            supports_get: None,
+            supports_connect: None,
+            check_targets: Vec::new(),
+            throughput_kbps: None,
+            anonymity: None,
+            tags: Vec::new(),
+            notes: String::new(),
+            healthy: true,
+            latency_ms: None,
+            last_checked: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn connects_through_http_upstream_with_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = conn.read(&mut buf).await.unwrap();
+            conn.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let proxy = upstream(ProxyType::Http, port);
+        connect_upstream(Some(&proxy), "example.com", 443)
+            .await
+            .unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT example.com:443 HTTP/1.1\r\n"));
+        // base64("user:pass")
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn connects_through_socks5_upstream_with_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 4];
+            conn.read_exact(&mut greeting).await.unwrap();
+            conn.write_all(&[0x05, 0x02]).await.unwrap();
+            let mut auth = [0u8; 11]; // 01 04 user 04 pass
+            conn.read_exact(&mut auth).await.unwrap();
+            conn.write_all(&[0x01, 0x00]).await.unwrap();
+            let mut req = [0u8; 5 + 11 + 2]; // header + "example.com" + port
+            conn.read_exact(&mut req).await.unwrap();
+            conn.write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0, 80])
+                .await
+                .unwrap();
+            (auth, req)
+        });
+
+        let proxy = upstream(ProxyType::Socks5, port);
+        connect_upstream(Some(&proxy), "example.com", 443)
+            .await
+            .unwrap();
+        let (auth, req) = server.await.unwrap();
+        assert_eq!(&auth[2..6], b"user");
+        assert_eq!(&req[5..16], b"example.com");
+        assert_eq!(&req[16..], &443u16.to_be_bytes());
     }
 }
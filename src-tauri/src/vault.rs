@@ -0,0 +1,146 @@
+// ── Manifold credential vault ─────────────────────────────────────────────────
+//
+// Named secrets kept per profile (the account's username, password, TOTP
+// seed, a PIN) so workflows can refer to them as `{{name}}` instead of
+// carrying them in their steps.  Values are stored encrypted with the
+// workspace master key, like proxy credentials, and never leave the backend
+// except into a run's steps: the frontend only ever sees the names.
+//
+// Secrets go with their profile when it is deleted.  Duplicates and share
+// bundles don't carry them.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rusqlite::params;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+pub struct VaultRepo {
+    db: Db,
+}
+
+impl VaultRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Store or replace a profile's secret.
+    pub fn set(&self, profile_id: &str, name: &str, value: &str) -> Result<()> {
+        let name = name.trim();
+        if !crate::workflow::is_variable_name(name) {
+            return Err(ManifoldError::InvalidArg(format!(
+                "secret name {name:?} must be letters, digits, '_', '-' or '.'"
+            )));
+        }
+        let encrypted = self.db.encrypt_field(value)?;
+        self.db.with_conn(|conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM profiles WHERE id = ?1)",
+                params![profile_id],
+                |r| r.get(0),
+            )?;
+            if !exists {
+                return Err(ManifoldError::ProfileNotFound(profile_id.to_string()));
+            }
+            conn.execute(
+                r#"INSERT INTO profile_secrets (profile_id, name, value, updated_at)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT(profile_id, name) DO UPDATE SET
+                       value = excluded.value,
+                       updated_at = excluded.updated_at"#,
+                params![profile_id, name, encrypted, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, profile_id: &str, name: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM profile_secrets WHERE profile_id = ?1 AND name = ?2",
+                params![profile_id, name],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Names of a profile's secrets, sorted.
+    pub fn names(&self, profile_id: &str) -> Result<Vec<String>> {
+        self.db.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT name FROM profile_secrets WHERE profile_id = ?1 ORDER BY name")?;
+            let names = stmt
+                .query_map(params![profile_id], |r| r.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(names)
+        })
+    }
+
+    /// A profile's secrets, decrypted.
+    pub fn values(&self, profile_id: &str) -> Result<BTreeMap<String, String>> {
+        let rows: Vec<(String, String)> = self.db.with_conn(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT name, value FROM profile_secrets WHERE profile_id = ?1")?;
+            let rows = stmt
+                .query_map(params![profile_id], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+        rows.into_iter()
+            .map(|(name, value)| Ok((name, self.db.decrypt_field(&value)?)))
+            .collect()
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::{CreateProfileRequest, ProfileRepo};
+
+    #[test]
+    fn secrets_are_per_profile_and_go_with_it() {
+        let db = Db::open_in_memory().unwrap();
+        let profiles = ProfileRepo::new(db.clone());
+        let vault = VaultRepo::new(db);
+        let profile = profiles
+            .create(CreateProfileRequest {
+                name: "shop".into(),
+                seed: Some(1),
+                proxy_id: None,
+                notes: None,
+                tags: None,
+                behavior_profile: None,
+                persona: None,
+            })
+            .unwrap();
+
+        vault.set(&profile.id, "password", "hunter2").unwrap();
+        vault.set(&profile.id, "password", "correct horse").unwrap();
+        vault
+            .set(&profile.id, "username", "ann@example.com")
+            .unwrap();
+        assert_eq!(vault.names(&profile.id).unwrap(), ["password", "username"]);
+        assert_eq!(
+            vault.values(&profile.id).unwrap()["password"],
+            "correct horse"
+        );
+
+        assert!(matches!(
+            vault.set(&profile.id, "{{bad}}", "x"),
+            Err(ManifoldError::InvalidArg(_))
+        ));
+        assert!(matches!(
+            vault.set("missing", "password", "x"),
+            Err(ManifoldError::ProfileNotFound(_))
+        ));
+
+        vault.delete(&profile.id, "username").unwrap();
+        assert_eq!(vault.names(&profile.id).unwrap(), ["password"]);
+        profiles.delete(&profile.id).unwrap();
+        assert!(vault.values(&profile.id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+// ── Manifold per-profile VPN tunnels ──────────────────────────────────────────
+//
+// Many users have WireGuard configs rather than proxies.  A profile can carry
+// one (stored encrypted); at launch Manifold runs it in userspace through
+// `wireproxy`, which exposes the tunnel as a SOCKS5 listener on 127.0.0.1.
+// Only the browser is pointed at that listener, so the host's own routing is
+// never touched, and the tunnel is torn down when the profile stops.
+//
+// OpenVPN is not supported: it needs a TUN device and system routes, which
+// would send every process on the machine through the profile's exit.
+
+use std::collections::BTreeMap;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+
+/// How long `wireproxy` gets to complete the handshake and bind its port.
+const READY_TIMEOUT: Duration = Duration::from_secs(20);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VpnKind {
+    #[default]
+    Wireguard,
+}
+
+impl std::fmt::Display for VpnKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Wireguard => write!(f, "wireguard"),
+        }
+    }
+}
+
+impl std::str::FromStr for VpnKind {
+    type Err = ManifoldError;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "wireguard" => Ok(Self::Wireguard),
+            "openvpn" => Err(ManifoldError::InvalidArg(
+                "OpenVPN configs are not supported (they need a system-wide TUN device)".into(),
+            )),
+            other => Err(ManifoldError::InvalidArg(format!(
+                "unknown VPN kind: {other:?}"
+            ))),
+        }
+    }
+}
+
+/// What the frontend may see of a stored config — never the private key.
+#[derive(Debug, Clone, Serialize)]
+pub struct VpnSummary {
+    pub profile_id: String,
+    pub kind: VpnKind,
+    pub addresses: Vec<String>,
+    pub endpoints: Vec<String>,
+    pub dns: Option<String>,
+}
+
+// ── WireGuard config parsing ──────────────────────────────────────────────────
+
+/// `[Section]` → list of key/value maps, in file order.
+type IniSections = Vec<(String, BTreeMap<String, String>)>;
+
+fn parse_ini(text: &str) -> IniSections {
+    let mut sections: IniSections = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), BTreeMap::new()));
+        } else if let (Some((key, value)), Some((_, map))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            map.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    sections
+}
+
+/// Validate a wg-quick style config and summarise it.
+pub fn summarize_wireguard(profile_id: &str, config: &str) -> Result<VpnSummary> {
+    let sections = parse_ini(config);
+    let invalid = |msg: &str| ManifoldError::InvalidArg(format!("WireGuard config: {msg}"));
+
+    let interface = sections
+        .iter()
+        .find(|(name, _)| name == "Interface")
+        .map(|(_, m)| m)
+        .ok_or_else(|| invalid("missing [Interface]"))?;
+    if !interface.contains_key("PrivateKey") {
+        return Err(invalid("[Interface] has no PrivateKey"));
+    }
+    let addresses: Vec<String> = interface
+        .get("Address")
+        .ok_or_else(|| invalid("[Interface] has no Address"))?
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .collect();
+
+    let mut endpoints = Vec::new();
+    for (_, peer) in sections.iter().filter(|(name, _)| name == "Peer") {
+        if !peer.contains_key("PublicKey") {
+            return Err(invalid("[Peer] has no PublicKey"));
+        }
+        endpoints.push(
+            peer.get("Endpoint")
+                .ok_or_else(|| invalid("[Peer] has no Endpoint"))?
+                .clone(),
+        );
+    }
+    if endpoints.is_empty() {
+        return Err(invalid("no [Peer] section"));
+    }
+
+    Ok(VpnSummary {
+        profile_id: profile_id.to_string(),
+        kind: VpnKind::Wireguard,
+        addresses,
+        endpoints,
+        dns: interface.get("DNS").cloned(),
+    })
+}
+
+/// The wireproxy config: the WireGuard config plus a SOCKS5 listener.
+pub fn wireproxy_config(config: &str, socks_port: u16) -> String {
+    format!(
+        "{}\n\n[Socks5]\nBindAddress = 127.0.0.1:{socks_port}\n",
+        config.trim_end()
+    )
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct VpnRepo {
+    db: Db,
+}
+
+impl VpnRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    /// Attach (or replace) the profile's VPN config.
+    pub fn set(&self, profile_id: &str, kind: VpnKind, config: &str) -> Result<VpnSummary> {
+        let summary = match kind {
+            VpnKind::Wireguard => summarize_wireguard(profile_id, config)?,
+        };
+        let config_enc = self.db.encrypt_field(config)?;
+        self.db.with_conn(|conn| {
+            let exists: bool = conn
+                .query_row(
+                    "SELECT 1 FROM profiles WHERE id = ?1",
+                    params![profile_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if !exists {
+                return Err(ManifoldError::ProfileNotFound(profile_id.into()));
+            }
+            conn.execute(
+                r#"INSERT INTO vpn_configs (profile_id, kind, config_enc, updated_at)
+                   VALUES (?1, ?2, ?3, ?4)
+                   ON CONFLICT(profile_id) DO UPDATE SET
+                       kind = excluded.kind,
+                       config_enc = excluded.config_enc,
+                       updated_at = excluded.updated_at"#,
+                params![
+                    profile_id,
+                    kind.to_string(),
+                    config_enc,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+        Ok(summary)
+    }
+
+    /// Decrypted config for launching, if the profile has one.
+    pub fn config(&self, profile_id: &str) -> Result<Option<(VpnKind, String)>> {
+        let row: Option<(String, String)> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT kind, config_enc FROM vpn_configs WHERE profile_id = ?1",
+                    params![profile_id],
+                    |r| Ok((r.get(0)?, r.get(1)?)),
+                )
+                .optional()?)
+        })?;
+        row.map(|(kind, enc)| Ok((kind.parse()?, self.db.decrypt_field(&enc)?)))
+            .transpose()
+    }
+
+    pub fn summary(&self, profile_id: &str) -> Result<Option<VpnSummary>> {
+        self.config(profile_id)?
+            .map(|(kind, config)| match kind {
+                VpnKind::Wireguard => summarize_wireguard(profile_id, &config),
+            })
+            .transpose()
+    }
+
+    pub fn remove(&self, profile_id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM vpn_configs WHERE profile_id = ?1",
+                params![profile_id],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+// ── Runtime tunnel ────────────────────────────────────────────────────────────
+
+/// A running `wireproxy` process.  Killed when dropped.
+pub struct VpnTunnel {
+    child: Child,
+    port: u16,
+}
+
+impl VpnTunnel {
+    /// Bring the tunnel up and wait until its SOCKS5 port accepts.
+    pub fn start(kind: VpnKind, config: &str) -> Result<Self> {
+        let VpnKind::Wireguard = kind;
+        let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+        let path = write_private_file(&wireproxy_config(config, port))?;
+
+        let spawned = Command::new("wireproxy")
+            .arg("-c")
+            .arg(&path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                std::fs::remove_file(&path).ok();
+                return Err(ManifoldError::spawn("wireproxy", e));
+            }
+        };
+
+        let deadline = Instant::now() + READY_TIMEOUT;
+        let ready = loop {
+            if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                break Ok(());
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                break Err(format!("wireproxy exited ({status})"));
+            }
+            if Instant::now() > deadline {
+                break Err("wireproxy did not come up".to_string());
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        };
+        // wireproxy has read its config by now; don't leave the key on disk
+        std::fs::remove_file(&path).ok();
+
+        match ready {
+            Ok(()) => Ok(Self { child, port }),
+            Err(e) => {
+                child.kill().ok();
+                child.wait().ok();
+                Err(ManifoldError::Other(e))
+            }
+        }
+    }
+
+    /// `server` value for the Playwright proxy config.
+    pub fn server_url(&self) -> String {
+        format!("socks5://127.0.0.1:{}", self.port)
+    }
+}
+
+impl Drop for VpnTunnel {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+/// Write `contents` to a fresh temp file readable only by the current user.
+fn write_private_file(contents: &str) -> Result<PathBuf> {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("manifold-wg-{}.conf", uuid::Uuid::new_v4()));
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    opts.open(&path)?.write_all(contents.as_bytes())?;
+    Ok(path)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WG: &str = r#"
+[Interface]
+PrivateKey = cGxhY2Vob2xkZXIgcHJpdmF0ZSBrZXkgMDAwMDAwMDA=
+Address = 10.64.0.2/32, fd00::2/128
+DNS = 10.64.0.1
+
+[Peer]
+PublicKey = cGxhY2Vob2xkZXIgcHVibGljIGtleSAwMDAwMDAwMDA=
+AllowedIPs = 0.0.0.0/0, ::/0
+Endpoint = vpn.example.net:51820 # exit
+"#;
+
+    fn repo_with_profile() -> VpnRepo {
+        let db = Db::open_in_memory().unwrap();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (id, name, fingerprint_json, created_at) VALUES ('p1', 'p', '{}', '2025-01-01T00:00:00+00:00')",
+                [],
+            )?;
+            Ok(())
+        })
+        .unwrap();
+        VpnRepo::new(db)
+    }
+
+    #[test]
+    fn summarizes_valid_config_without_secrets() {
+        let s = summarize_wireguard("p1", WG).unwrap();
+        assert_eq!(s.addresses, vec!["10.64.0.2/32", "fd00::2/128"]);
+        assert_eq!(s.endpoints, vec!["vpn.example.net:51820"]);
+        assert_eq!(s.dns.as_deref(), Some("10.64.0.1"));
+        assert!(!serde_json::to_string(&s).unwrap().contains("PrivateKey"));
+    }
+
+    #[test]
+    fn rejects_incomplete_configs() {
+        assert!(summarize_wireguard("p1", "[Interface]\nAddress = 10.0.0.2/32").is_err());
+        let no_peer = WG.split("[Peer]").next().unwrap();
+        assert!(summarize_wireguard("p1", no_peer).is_err());
+        assert!("openvpn".parse::<VpnKind>().is_err());
+    }
+
+    #[test]
+    fn wireproxy_config_appends_socks_listener() {
+        let cfg = wireproxy_config(WG, 41000);
+        assert!(cfg.contains("[Peer]"));
+        assert!(cfg.ends_with("[Socks5]\nBindAddress = 127.0.0.1:41000\n"));
+    }
+
+    #[test]
+    fn config_round_trips_encrypted_and_cascades() {
+        let repo = repo_with_profile();
+        repo.set("p1", VpnKind::Wireguard, WG).unwrap();
+        let (kind, config) = repo.config("p1").unwrap().unwrap();
+        assert_eq!(kind, VpnKind::Wireguard);
+        assert_eq!(config, WG);
+        assert!(repo.set("missing", VpnKind::Wireguard, WG).is_err());
+
+        repo.db
+            .with_conn(|conn| {
+                conn.execute("DELETE FROM profiles WHERE id = 'p1'", [])?;
+                Ok(())
+            })
+            .unwrap();
+        assert!(repo.config("p1").unwrap().is_none());
+    }
+}
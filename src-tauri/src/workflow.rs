@@ -0,0 +1,1341 @@
+// ── Manifold workflows ────────────────────────────────────────────────────────
+//
+// A workflow is an ordered list of page actions (navigate, click, fill,
+// wait) run against a launched profile, plus cleanup steps (log out, clear
+// a cart) that run after them.  The definition is stored as JSON in the
+// `workflows` table; the bridge only performs single actions, while this
+// module decides what happens when one fails.
+//
+// Each step carries a policy:
+//
+//   * retries with exponential backoff between attempts;
+//   * alternative selectors, tried in order after the step's own selector
+//     on every attempt, for pages whose markup varies between deployments;
+//   * what to do once every attempt failed: `abort` stops the run on the
+//     spot, `skip` records the failure and carries on, `cleanup` jumps
+//     straight to the cleanup steps.
+//
+// Cleanup steps run after the main steps unless the run was aborted.  A
+// failing cleanup step never stops the remaining ones.
+//
+// Extraction steps (`extract_text`, `extract_attribute`, `extract_table`)
+// read from the page instead of acting on it; what they return is kept
+// under the step's key as the run's outputs (run_outputs.rs).
+//
+// Any text in a step may refer to `{{name}}` variables.  They are filled in
+// when a run starts from the run's parameters, falling back to the
+// profile's vault (vault.rs); a run whose variables aren't all supplied
+// doesn't start.
+//
+// When a step gives up, the runner is told while the page is still as the
+// step left it; the bridge runner captures it for the run's artifacts
+// (run_artifacts.rs).
+//
+// A `manual_intervention` step hands the page to a human (an unsupported
+// captcha, a 2FA push to approve): the run pauses, the operator is prompted
+// and `resume_workflow` carries on.  No resume within the step's timeout
+// counts as a failed attempt, so its policy decides whether to prompt again,
+// skip or give up.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::run_artifacts::{FailureCapture, HAR_EXCERPT_ENTRIES};
+use crate::run_outputs::RunOutput;
+
+/// Version of the stored definition JSON.
+pub const WORKFLOW_FORMAT: u32 = 1;
+
+/// Upper bound on a step's retries.
+pub const MAX_RETRIES: u32 = 10;
+
+/// Upper bound on a single backoff delay.
+pub const MAX_BACKOFF_MS: u64 = 60_000;
+
+/// Prefix of the stdout line answering one step in workflow mode.
+pub const STEP_RESULT_LINE_PREFIX: &str = "STEP_RESULT ";
+
+/// How long the bridge gets to answer one step.
+pub const STEP_TIMEOUT_SECS: u64 = 90;
+
+/// Upper bound on how long a run waits for the operator.
+pub const MAX_INTERVENTION_SECS: u64 = 6 * 60 * 60;
+
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 15_000;
+const DEFAULT_INTERVENTION_SECS: u64 = 10 * 60;
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// One page action, as the bridge performs it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepAction {
+    Navigate {
+        url: String,
+    },
+    Click {
+        selector: String,
+    },
+    Fill {
+        selector: String,
+        value: String,
+    },
+    WaitFor {
+        selector: String,
+        #[serde(default = "default_wait_timeout_ms")]
+        timeout_ms: u64,
+    },
+    Sleep {
+        ms: u64,
+    },
+    /// Inner text of the first match, or of every match with `all`.
+    ExtractText {
+        selector: String,
+        key: String,
+        #[serde(default)]
+        all: bool,
+    },
+    /// An attribute of the first match, or of every match with `all`.
+    ExtractAttribute {
+        selector: String,
+        attribute: String,
+        key: String,
+        #[serde(default)]
+        all: bool,
+    },
+    /// A `<table>` as rows: objects keyed by its header cells, or arrays of
+    /// cell texts when it has no header.
+    ExtractTable {
+        selector: String,
+        key: String,
+    },
+    /// Pause until the operator resumes the run; never sent to the bridge.
+    ManualIntervention {
+        prompt: String,
+        #[serde(default = "default_intervention_secs")]
+        timeout_secs: u64,
+    },
+}
+
+fn default_wait_timeout_ms() -> u64 {
+    DEFAULT_WAIT_TIMEOUT_MS
+}
+
+fn default_intervention_secs() -> u64 {
+    DEFAULT_INTERVENTION_SECS
+}
+
+impl StepAction {
+    /// The element the action targets, if it targets one.
+    pub fn selector(&self) -> Option<&str> {
+        match self {
+            StepAction::Click { selector }
+            | StepAction::Fill { selector, .. }
+            | StepAction::WaitFor { selector, .. }
+            | StepAction::ExtractText { selector, .. }
+            | StepAction::ExtractAttribute { selector, .. }
+            | StepAction::ExtractTable { selector, .. } => Some(selector),
+            StepAction::Navigate { .. }
+            | StepAction::Sleep { .. }
+            | StepAction::ManualIntervention { .. } => None,
+        }
+    }
+
+    /// The output key of an extraction step.
+    pub fn output_key(&self) -> Option<&str> {
+        match self {
+            StepAction::ExtractText { key, .. }
+            | StepAction::ExtractAttribute { key, .. }
+            | StepAction::ExtractTable { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// The same action aimed at `selector` instead.
+    fn with_selector(&self, selector: &str) -> StepAction {
+        let mut action = self.clone();
+        match &mut action {
+            StepAction::Click { selector: s }
+            | StepAction::Fill { selector: s, .. }
+            | StepAction::WaitFor { selector: s, .. }
+            | StepAction::ExtractText { selector: s, .. }
+            | StepAction::ExtractAttribute { selector: s, .. }
+            | StepAction::ExtractTable { selector: s, .. } => *s = selector.to_string(),
+            StepAction::Navigate { .. }
+            | StepAction::Sleep { .. }
+            | StepAction::ManualIntervention { .. } => {}
+        }
+        action
+    }
+}
+
+/// What a step does once all its attempts failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    /// Stop the run; cleanup steps are not run.
+    #[default]
+    Abort,
+    /// Record the failure and go on with the next step.
+    Skip,
+    /// Skip the remaining steps and run the cleanup steps.
+    Cleanup,
+}
+
+/// How a step is retried and what happens when it keeps failing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepPolicy {
+    /// Attempts after the first.
+    pub retries: u32,
+    /// Delay before the first retry.
+    pub backoff_ms: u64,
+    /// Each further retry waits this much longer than the one before.
+    pub backoff_multiplier: f64,
+    /// Tried after the step's own selector on every attempt.
+    pub alt_selectors: Vec<String>,
+    pub on_error: OnError,
+}
+
+impl Default for StepPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            backoff_ms: 1_000,
+            backoff_multiplier: 2.0,
+            alt_selectors: Vec::new(),
+            on_error: OnError::Abort,
+        }
+    }
+}
+
+impl StepPolicy {
+    /// Delay before retry number `retry` (1-based), capped at MAX_BACKOFF_MS.
+    pub fn backoff(&self, retry: u32) -> u64 {
+        let factor = self.backoff_multiplier.powi(retry.saturating_sub(1) as i32);
+        (self.backoff_ms as f64 * factor).min(MAX_BACKOFF_MS as f64) as u64
+    }
+}
+
+/// A step as stored: the action and its policy side by side, e.g.
+/// `{"type":"click","selector":"#login","retries":2,"on_error":"skip"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    /// Shown in run reports instead of the step number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub action: StepAction,
+    #[serde(flatten)]
+    pub policy: StepPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    pub cleanup: Vec<WorkflowStep>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Workflow {
+    /// URL of the first navigate step: where the run starts.
+    pub fn start_url(&self) -> Option<&str> {
+        self.steps.iter().find_map(|s| match &s.action {
+            StepAction::Navigate { url } => Some(url.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// The stored JSON of a workflow.
+#[derive(Serialize, Deserialize)]
+struct Definition {
+    format: u32,
+    steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    cleanup: Vec<WorkflowStep>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepPhase {
+    Main,
+    Cleanup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Ok,
+    /// Failed, and the policy said to carry on.
+    Skipped,
+    Failed,
+}
+
+/// What happened to one step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutcome {
+    pub phase: StepPhase,
+    pub index: usize,
+    pub name: Option<String>,
+    pub status: StepStatus,
+    pub attempts: u32,
+    /// The alternative selector that worked, when the step's own didn't.
+    pub selector: Option<String>,
+    /// The last failure.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowRunStatus {
+    /// Every step succeeded or was skipped by its policy.
+    Succeeded,
+    /// A step failed and the run jumped to cleanup.
+    Failed,
+    /// A step failed and the run stopped without cleanup.
+    Aborted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunReport {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub profile_id: String,
+    pub status: WorkflowRunStatus,
+    pub steps: Vec<StepOutcome>,
+    /// What the extraction steps captured, in order.
+    pub outputs: Vec<RunOutput>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+// ── Validation ────────────────────────────────────────────────────────────────
+
+fn validate_step(step: &WorkflowStep, at: &str) -> Result<()> {
+    let invalid = |msg: String| Err(ManifoldError::InvalidArg(format!("{at}: {msg}")));
+    match &step.action {
+        // Checked again once its variables are filled in
+        StepAction::Navigate { url } if !variables_in(url).is_empty() => {}
+        StepAction::Navigate { url } => match url::Url::parse(url) {
+            Ok(u) if matches!(u.scheme(), "http" | "https") => {}
+            Ok(_) => return invalid("navigate url must be http(s)".into()),
+            Err(e) => return invalid(format!("navigate url: {e}")),
+        },
+        StepAction::Sleep { ms } if *ms > MAX_BACKOFF_MS => {
+            return invalid(format!("sleep is limited to {MAX_BACKOFF_MS} ms"))
+        }
+        StepAction::ManualIntervention {
+            prompt,
+            timeout_secs,
+        } => {
+            if prompt.trim().is_empty() {
+                return invalid("manual_intervention needs a prompt".into());
+            }
+            if !(1..=MAX_INTERVENTION_SECS).contains(timeout_secs) {
+                return invalid(format!(
+                    "manual_intervention timeout is 1 to {MAX_INTERVENTION_SECS} s"
+                ));
+            }
+        }
+        _ => {}
+    }
+    if step.action.selector().is_some_and(|s| s.trim().is_empty()) {
+        return invalid("selector cannot be empty".into());
+    }
+    if step
+        .action
+        .output_key()
+        .is_some_and(|k| k.trim().is_empty())
+    {
+        return invalid("extraction key cannot be empty".into());
+    }
+    if let StepAction::ExtractAttribute { attribute, .. } = &step.action {
+        if attribute.trim().is_empty() {
+            return invalid("attribute cannot be empty".into());
+        }
+    }
+    let policy = &step.policy;
+    if !policy.alt_selectors.is_empty() && step.action.selector().is_none() {
+        return invalid("alt_selectors need a step that targets an element".into());
+    }
+    if policy.alt_selectors.iter().any(|s| s.trim().is_empty()) {
+        return invalid("alt_selectors cannot be empty".into());
+    }
+    if policy.retries > MAX_RETRIES {
+        return invalid(format!("retries is limited to {MAX_RETRIES}"));
+    }
+    if policy.backoff_ms > MAX_BACKOFF_MS {
+        return invalid(format!("backoff_ms is limited to {MAX_BACKOFF_MS}"));
+    }
+    if !(policy.backoff_multiplier.is_finite() && policy.backoff_multiplier >= 1.0) {
+        return invalid("backoff_multiplier must be at least 1".into());
+    }
+    Ok(())
+}
+
+/// Check a definition before it is stored.
+pub fn validate(name: &str, steps: &[WorkflowStep], cleanup: &[WorkflowStep]) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(ManifoldError::InvalidArg(
+            "workflow name cannot be empty".into(),
+        ));
+    }
+    if steps.is_empty() {
+        return Err(ManifoldError::InvalidArg(
+            "workflow needs at least one step".into(),
+        ));
+    }
+    for (i, step) in steps.iter().enumerate() {
+        validate_step(step, &format!("step {}", i + 1))?;
+    }
+    for (i, step) in cleanup.iter().enumerate() {
+        validate_step(step, &format!("cleanup step {}", i + 1))?;
+    }
+    Ok(())
+}
+
+// ── Variables ─────────────────────────────────────────────────────────────────
+
+/// Whether `name` can be used as `{{name}}`.
+pub fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Byte range and name of each `{{name}}` in `text`; braces around
+/// anything that isn't a variable name are plain text.
+fn variable_spans(text: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while let Some(open) = text[from..].find("{{").map(|i| from + i) {
+        let Some(close) = text[open + 2..].find("}}").map(|i| open + 2 + i) else {
+            break;
+        };
+        let name = text[open + 2..close].trim();
+        if is_variable_name(name) {
+            spans.push((open..close + 2, name));
+            from = close + 2;
+        } else {
+            from = open + 1;
+        }
+    }
+    spans
+}
+
+fn variables_in(text: &str) -> Vec<&str> {
+    variable_spans(text)
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Apply `f` to every string in a JSON value.
+fn for_each_string(value: &mut Value, f: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::String(s) => f(s),
+        Value::Array(items) => items.iter_mut().for_each(|v| for_each_string(v, f)),
+        Value::Object(map) => map.values_mut().for_each(|v| for_each_string(v, f)),
+        _ => {}
+    }
+}
+
+/// Every variable the workflow's steps and cleanup steps refer to.
+pub fn referenced_variables(workflow: &Workflow) -> Result<BTreeSet<String>> {
+    let mut names = BTreeSet::new();
+    let mut steps = serde_json::to_value((&workflow.steps, &workflow.cleanup))?;
+    for_each_string(&mut steps, &mut |s| {
+        names.extend(variables_in(s).into_iter().map(str::to_string));
+    });
+    Ok(names)
+}
+
+/// The workflow with its variables filled in from `vars`.  Fails, naming
+/// them, when any are missing; the bound steps are validated again.
+pub fn bind(workflow: &Workflow, vars: &BTreeMap<String, String>) -> Result<Workflow> {
+    let missing: Vec<String> = referenced_variables(workflow)?
+        .into_iter()
+        .filter(|name| !vars.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(ManifoldError::InvalidArg(format!(
+            "workflow variables not supplied: {}",
+            missing.join(", ")
+        )));
+    }
+    let mut steps = serde_json::to_value((&workflow.steps, &workflow.cleanup))?;
+    for_each_string(&mut steps, &mut |s| {
+        let spans: Vec<_> = variable_spans(s)
+            .into_iter()
+            .map(|(range, name)| (range, name.to_string()))
+            .collect();
+        for (range, name) in spans.into_iter().rev() {
+            s.replace_range(range, &vars[&name]);
+        }
+    });
+    let (steps, cleanup): (Vec<WorkflowStep>, Vec<WorkflowStep>) = serde_json::from_value(steps)?;
+    validate(&workflow.name, &steps, &cleanup)?;
+    Ok(Workflow {
+        steps,
+        cleanup,
+        ..workflow.clone()
+    })
+}
+
+// ── Execution ─────────────────────────────────────────────────────────────────
+
+/// Performs actions on the profile's page.
+pub trait StepRunner {
+    /// Run one action; extraction steps return what they read, `Err` says
+    /// why it failed.
+    fn perform(&mut self, action: &StepAction) -> std::result::Result<Option<Value>, String>;
+    /// Pause until the operator resumes the run; `Err` when they didn't
+    /// within `timeout_secs`.
+    fn intervene(&mut self, prompt: &str, timeout_secs: u64) -> std::result::Result<(), String>;
+    /// Hold off for `ms` before a retry.
+    fn wait(&mut self, ms: u64);
+    /// A step gave up after every attempt; the page is as it left it.
+    fn step_failed(&mut self, _outcome: &StepOutcome) {}
+}
+
+/// Run one step under its policy; also returns what an extraction step read.
+fn run_step(
+    runner: &mut dyn StepRunner,
+    step: &WorkflowStep,
+    phase: StepPhase,
+    index: usize,
+) -> (StepOutcome, Option<Value>) {
+    let policy = &step.policy;
+    let selectors: Vec<Option<&str>> = match step.action.selector() {
+        Some(own) => std::iter::once(own)
+            .chain(policy.alt_selectors.iter().map(String::as_str))
+            .map(Some)
+            .collect(),
+        None => vec![None],
+    };
+    let mut outcome = StepOutcome {
+        phase,
+        index,
+        name: step.name.clone(),
+        status: StepStatus::Failed,
+        attempts: 0,
+        selector: None,
+        error: None,
+    };
+    for attempt in 0..=policy.retries {
+        if attempt > 0 {
+            runner.wait(policy.backoff(attempt));
+        }
+        outcome.attempts = attempt + 1;
+        for (i, selector) in selectors.iter().enumerate() {
+            let action = match selector {
+                Some(s) if i > 0 => step.action.with_selector(s),
+                _ => step.action.clone(),
+            };
+            let result = match &action {
+                StepAction::ManualIntervention {
+                    prompt,
+                    timeout_secs,
+                } => runner.intervene(prompt, *timeout_secs).map(|()| None),
+                _ => runner.perform(&action),
+            };
+            match result {
+                Ok(value) => {
+                    outcome.status = StepStatus::Ok;
+                    outcome.selector = selector.filter(|_| i > 0).map(str::to_string);
+                    outcome.error = None;
+                    return (outcome, value);
+                }
+                Err(e) => outcome.error = Some(e),
+            }
+        }
+    }
+    (outcome, None)
+}
+
+/// Run `workflow` through `runner` and report every step.
+pub fn execute(
+    workflow: &Workflow,
+    run_id: &str,
+    profile_id: &str,
+    runner: &mut dyn StepRunner,
+) -> WorkflowRunReport {
+    let started_at = Utc::now();
+    let mut steps = Vec::new();
+    let mut outputs = Vec::new();
+    let mut status = WorkflowRunStatus::Succeeded;
+    let mut capture = |step: &WorkflowStep, index: usize, value: Option<Value>| {
+        if let (Some(key), Some(value)) = (step.action.output_key(), value) {
+            outputs.push(RunOutput {
+                key: key.to_string(),
+                step: index,
+                value,
+                captured_at: Utc::now(),
+            });
+        }
+    };
+
+    for (index, step) in workflow.steps.iter().enumerate() {
+        let (mut outcome, value) = run_step(runner, step, StepPhase::Main, index);
+        capture(step, index, value);
+        let failed = outcome.status == StepStatus::Failed;
+        if failed {
+            runner.step_failed(&outcome);
+        }
+        if failed && step.policy.on_error == OnError::Skip {
+            outcome.status = StepStatus::Skipped;
+        }
+        steps.push(outcome);
+        if failed {
+            match step.policy.on_error {
+                OnError::Skip => {}
+                OnError::Abort => {
+                    status = WorkflowRunStatus::Aborted;
+                    break;
+                }
+                OnError::Cleanup => {
+                    status = WorkflowRunStatus::Failed;
+                    break;
+                }
+            }
+        }
+    }
+
+    if status != WorkflowRunStatus::Aborted {
+        for (index, step) in workflow.cleanup.iter().enumerate() {
+            let (outcome, value) = run_step(runner, step, StepPhase::Cleanup, index);
+            capture(step, index, value);
+            if outcome.status == StepStatus::Failed {
+                runner.step_failed(&outcome);
+            }
+            steps.push(outcome);
+        }
+    }
+
+    WorkflowRunReport {
+        run_id: run_id.into(),
+        workflow_id: workflow.id.clone(),
+        profile_id: profile_id.into(),
+        status,
+        steps,
+        outputs,
+        started_at,
+        finished_at: Utc::now(),
+    }
+}
+
+/// The bridge's answer to one step.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StepResult {
+    pub ok: bool,
+    /// What an extraction step read.
+    #[serde(default)]
+    pub value: Option<Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Pull a step result out of the bridge's stdout line, if this is one.
+pub fn parse_step_result_line(line: &str) -> Option<StepResult> {
+    let json = line.trim().strip_prefix(STEP_RESULT_LINE_PREFIX)?;
+    Some(serde_json::from_str(json).unwrap_or_else(|e| StepResult {
+        ok: false,
+        value: None,
+        error: Some(format!("unreadable step result: {e}")),
+    }))
+}
+
+/// Waits for the operator on a `manual_intervention` step: the prompt and
+/// timeout in, whether the run was resumed out.
+pub type Operator = Box<dyn FnMut(&str, u64) -> std::result::Result<(), String> + Send>;
+
+/// Runs steps through a workflow-mode bridge: one action JSON per line on
+/// its stdin, answered by one `STEP_RESULT` line each.
+pub struct BridgeStepRunner<W: Write> {
+    input: W,
+    results: Receiver<StepResult>,
+    timeout: Duration,
+    operator: Option<Operator>,
+    failures: Vec<FailureCapture>,
+}
+
+impl<W: Write> BridgeStepRunner<W> {
+    pub fn new(input: W, results: Receiver<StepResult>, timeout: Duration) -> Self {
+        Self {
+            input,
+            results,
+            timeout,
+            operator: None,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Pause on `manual_intervention` steps through `operator`; without one
+    /// they fail.
+    pub fn with_operator(mut self, operator: Operator) -> Self {
+        self.operator = Some(operator);
+        self
+    }
+
+    /// What the bridge captured at each failed step, in order.
+    pub fn failures(&self) -> &[FailureCapture] {
+        &self.failures
+    }
+
+    /// Send one command line and wait for its `STEP_RESULT`.
+    fn request(&mut self, command: &impl Serialize) -> std::result::Result<Option<Value>, String> {
+        let json = serde_json::to_string(command).map_err(|e| e.to_string())?;
+        writeln!(self.input, "{json}")
+            .and_then(|_| self.input.flush())
+            .map_err(|e| format!("bridge is gone: {e}"))?;
+        match self.results.recv_timeout(self.timeout) {
+            Ok(StepResult {
+                ok: true, value, ..
+            }) => Ok(value),
+            Ok(StepResult { error, .. }) => Err(error.unwrap_or_else(|| "step failed".into())),
+            Err(_) => Err("bridge did not answer".into()),
+        }
+    }
+}
+
+/// The bridge's answer to a `capture_failure` command.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PageCapture {
+    url: Option<String>,
+    screenshot: Option<String>,
+    har: Vec<Value>,
+}
+
+impl<W: Write> StepRunner for BridgeStepRunner<W> {
+    fn perform(&mut self, action: &StepAction) -> std::result::Result<Option<Value>, String> {
+        self.request(action)
+    }
+
+    fn intervene(&mut self, prompt: &str, timeout_secs: u64) -> std::result::Result<(), String> {
+        match &mut self.operator {
+            Some(operator) => operator(prompt, timeout_secs),
+            None => Err("no operator to resume the run".into()),
+        }
+    }
+
+    fn wait(&mut self, ms: u64) {
+        std::thread::sleep(Duration::from_millis(ms));
+    }
+
+    fn step_failed(&mut self, outcome: &StepOutcome) {
+        let command = serde_json::json!({
+            "type": "capture_failure",
+            "har_entries": HAR_EXCERPT_ENTRIES,
+        });
+        // A bridge that can't capture still leaves a record of the failure
+        let page: PageCapture = self
+            .request(&command)
+            .ok()
+            .flatten()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        self.failures.push(FailureCapture {
+            phase: outcome.phase,
+            index: outcome.index,
+            error: outcome.error.clone(),
+            url: page.url,
+            screenshot: page.screenshot,
+            har: page.har,
+            captured_at: Utc::now(),
+        });
+    }
+}
+
+// ── Operator interventions ────────────────────────────────────────────────────
+
+/// A run paused on a `manual_intervention` step.
+#[derive(Debug, Clone, Serialize)]
+pub struct Intervention {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub profile_id: String,
+    pub prompt: String,
+    pub requested_at: DateTime<Utc>,
+    /// When the step gives up waiting.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Intervention {
+    pub fn new(
+        run_id: &str,
+        workflow_id: &str,
+        profile_id: &str,
+        prompt: &str,
+        timeout_secs: u64,
+    ) -> Self {
+        let requested_at = Utc::now();
+        Self {
+            run_id: run_id.into(),
+            workflow_id: workflow_id.into(),
+            profile_id: profile_id.into(),
+            prompt: prompt.into(),
+            requested_at,
+            expires_at: requested_at + chrono::Duration::seconds(timeout_secs as i64),
+        }
+    }
+}
+
+/// Runs waiting for the operator, by run id.
+#[derive(Default)]
+pub struct Interventions {
+    waiting: Mutex<HashMap<String, (Intervention, Sender<()>)>>,
+}
+
+impl Interventions {
+    /// Register `intervention`, `announce` it and block until the run is
+    /// resumed or the intervention expires.
+    pub fn wait(
+        &self,
+        intervention: Intervention,
+        announce: impl FnOnce(&Intervention),
+    ) -> std::result::Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+        let run_id = intervention.run_id.clone();
+        let timeout = (intervention.expires_at - intervention.requested_at)
+            .to_std()
+            .unwrap_or_default();
+        // Registered before the prompt goes out, so an instant resume lands
+        self.waiting
+            .lock()
+            .unwrap()
+            .insert(run_id.clone(), (intervention.clone(), tx));
+        announce(&intervention);
+        let resumed = rx.recv_timeout(timeout).is_ok();
+        self.waiting.lock().unwrap().remove(&run_id);
+        if resumed {
+            Ok(())
+        } else {
+            Err(format!(
+                "operator did not resume within {} s",
+                timeout.as_secs()
+            ))
+        }
+    }
+
+    /// Let a paused run carry on.
+    pub fn resume(&self, run_id: &str) -> Result<()> {
+        let (_, tx) = self.waiting.lock().unwrap().remove(run_id).ok_or_else(|| {
+            ManifoldError::InvalidArg(format!("run {run_id:?} is not waiting for the operator"))
+        })?;
+        tx.send(())
+            .map_err(|_| ManifoldError::Other(format!("run {run_id:?} stopped waiting")))
+    }
+
+    /// Runs waiting right now, oldest first.
+    pub fn pending(&self) -> Vec<Intervention> {
+        let mut pending: Vec<Intervention> = self
+            .waiting
+            .lock()
+            .unwrap()
+            .values()
+            .map(|(i, _)| i.clone())
+            .collect();
+        pending.sort_by_key(|i| i.requested_at);
+        pending
+    }
+}
+
+// ── Repository ────────────────────────────────────────────────────────────────
+
+pub struct WorkflowRepo {
+    db: Db,
+}
+
+impl WorkflowRepo {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+
+    pub fn create(
+        &self,
+        name: String,
+        steps: Vec<WorkflowStep>,
+        cleanup: Vec<WorkflowStep>,
+    ) -> Result<Workflow> {
+        validate(&name, &steps, &cleanup)?;
+        let now = Utc::now();
+        let workflow = Workflow {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            steps,
+            cleanup,
+            created_at: now,
+            updated_at: now,
+        };
+        let definition = definition_json(&workflow)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO workflows (id, name, definition, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    workflow.id,
+                    workflow.name,
+                    definition,
+                    now.to_rfc3339(),
+                    now.to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+        Ok(workflow)
+    }
+
+    /// Replace a workflow's name and steps.
+    pub fn update(
+        &self,
+        id: &str,
+        name: String,
+        steps: Vec<WorkflowStep>,
+        cleanup: Vec<WorkflowStep>,
+    ) -> Result<Workflow> {
+        validate(&name, &steps, &cleanup)?;
+        let mut workflow = self.get(id)?;
+        workflow.name = name.trim().to_string();
+        workflow.steps = steps;
+        workflow.cleanup = cleanup;
+        workflow.updated_at = Utc::now();
+        let definition = definition_json(&workflow)?;
+        self.db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE workflows SET name = ?2, definition = ?3, updated_at = ?4 WHERE id = ?1",
+                params![
+                    workflow.id,
+                    workflow.name,
+                    definition,
+                    workflow.updated_at.to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+        Ok(workflow)
+    }
+
+    pub fn list(&self) -> Result<Vec<Workflow>> {
+        let rows: Vec<WorkflowRow> = self.db.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, definition, created_at, updated_at FROM workflows ORDER BY name",
+            )?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+                })?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(rows)
+        })?;
+        rows.into_iter().map(from_row).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<Workflow> {
+        let row: Option<WorkflowRow> = self.db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT id, name, definition, created_at, updated_at FROM workflows WHERE id = ?1",
+                    params![id],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+                )
+                .optional()?)
+        })?;
+        row.map(from_row)
+            .unwrap_or_else(|| Err(ManifoldError::InvalidArg(format!("no workflow {id:?}"))))
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        self.db.with_conn(|conn| {
+            conn.execute("DELETE FROM workflows WHERE id = ?1", params![id])?;
+            Ok(())
+        })
+    }
+}
+
+type WorkflowRow = (String, String, String, String, String);
+
+fn definition_json(workflow: &Workflow) -> Result<String> {
+    Ok(serde_json::to_string(&Definition {
+        format: WORKFLOW_FORMAT,
+        steps: workflow.steps.clone(),
+        cleanup: workflow.cleanup.clone(),
+    })?)
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(s)
+        .map_err(|e| ManifoldError::Other(e.to_string()))?
+        .with_timezone(&Utc))
+}
+
+fn from_row((id, name, definition, created_at, updated_at): WorkflowRow) -> Result<Workflow> {
+    let definition: Definition = serde_json::from_str(&definition)?;
+    if definition.format > WORKFLOW_FORMAT {
+        return Err(ManifoldError::Other(format!(
+            "workflow {id:?} was saved by a newer version (format {})",
+            definition.format
+        )));
+    }
+    Ok(Workflow {
+        id,
+        name,
+        steps: definition.steps,
+        cleanup: definition.cleanup,
+        created_at: parse_time(&created_at)?,
+        updated_at: parse_time(&updated_at)?,
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails every action whose selector is in `broken`, `fail_times` more
+    /// times for the rest; records actions and waits.
+    #[derive(Default)]
+    struct ScriptedRunner {
+        broken: Vec<&'static str>,
+        fail_times: u32,
+        performed: Vec<StepAction>,
+        waits: Vec<u64>,
+    }
+
+    impl StepRunner for ScriptedRunner {
+        fn perform(&mut self, action: &StepAction) -> std::result::Result<Option<Value>, String> {
+            self.performed.push(action.clone());
+            if action.selector().is_some_and(|s| self.broken.contains(&s)) {
+                return Err("no such element".into());
+            }
+            if self.fail_times > 0 {
+                self.fail_times -= 1;
+                return Err("timeout".into());
+            }
+            // Extraction steps read their selector back
+            Ok(action
+                .output_key()
+                .and(action.selector())
+                .map(|s| Value::String(format!("text of {s}"))))
+        }
+
+        fn intervene(
+            &mut self,
+            _prompt: &str,
+            _timeout_secs: u64,
+        ) -> std::result::Result<(), String> {
+            Ok(())
+        }
+
+        fn wait(&mut self, ms: u64) {
+            self.waits.push(ms);
+        }
+    }
+
+    fn step(json: &str) -> WorkflowStep {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn workflow(steps: Vec<WorkflowStep>, cleanup: Vec<WorkflowStep>) -> Workflow {
+        Workflow {
+            id: "wf".into(),
+            name: "login".into(),
+            steps,
+            cleanup,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn retries_back_off_and_fall_back_to_alternative_selectors() {
+        let wf = workflow(
+            vec![step(
+                r##"{"type":"click","selector":"#old","alt_selectors":["#new"],"retries":3,"backoff_ms":100}"##,
+            )],
+            Vec::new(),
+        );
+        let mut runner = ScriptedRunner {
+            broken: vec!["#old"],
+            fail_times: 1,
+            ..Default::default()
+        };
+        let report = execute(&wf, "run", "p1", &mut runner);
+        assert_eq!(report.status, WorkflowRunStatus::Succeeded);
+        assert_eq!(report.steps[0].attempts, 2);
+        assert_eq!(report.steps[0].selector.as_deref(), Some("#new"));
+        assert_eq!(runner.waits, vec![100]);
+        assert_eq!(wf.steps[0].policy.backoff(3), 400);
+    }
+
+    #[test]
+    fn on_error_aborts_skips_or_jumps_to_cleanup() {
+        let failing = |on_error: &str| {
+            step(&format!(
+                r##"{{"type":"click","selector":"#gone","on_error":"{on_error}"}}"##
+            ))
+        };
+        let next = step(r##"{"type":"fill","selector":"#q","value":"x"}"##);
+        let cleanup = vec![
+            step(r##"{"type":"click","selector":"#gone"}"##),
+            step(r##"{"type":"click","selector":"#logout"}"##),
+        ];
+        let run = |on_error: &str| {
+            let wf = workflow(vec![failing(on_error), next.clone()], cleanup.clone());
+            let mut runner = ScriptedRunner {
+                broken: vec!["#gone"],
+                ..Default::default()
+            };
+            execute(&wf, "run", "p1", &mut runner)
+        };
+
+        let aborted = run("abort");
+        assert_eq!(aborted.status, WorkflowRunStatus::Aborted);
+        assert_eq!(aborted.steps.len(), 1);
+
+        let skipped = run("skip");
+        assert_eq!(skipped.status, WorkflowRunStatus::Succeeded);
+        let statuses: Vec<_> = skipped.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                StepStatus::Skipped,
+                StepStatus::Ok,
+                StepStatus::Failed,
+                StepStatus::Ok
+            ]
+        );
+
+        let cleaned = run("cleanup");
+        assert_eq!(cleaned.status, WorkflowRunStatus::Failed);
+        let phases: Vec<_> = cleaned.steps.iter().map(|s| s.phase).collect();
+        assert_eq!(
+            phases,
+            [StepPhase::Main, StepPhase::Cleanup, StepPhase::Cleanup]
+        );
+    }
+
+    #[test]
+    fn extraction_steps_keep_their_values_when_a_later_step_fails() {
+        let wf = workflow(
+            vec![
+                step(r##"{"type":"extract_text","selector":".balance","key":"balance"}"##),
+                step(
+                    r##"{"type":"extract_table","selector":"#old","key":"orders","alt_selectors":["#orders"]}"##,
+                ),
+                step(r##"{"type":"click","selector":"#gone","on_error":"cleanup"}"##),
+            ],
+            vec![step(
+                r##"{"type":"extract_attribute","selector":"a.logout","attribute":"href","key":"logout"}"##,
+            )],
+        );
+        validate(&wf.name, &wf.steps, &wf.cleanup).unwrap();
+        let mut runner = ScriptedRunner {
+            broken: vec!["#old", "#gone"],
+            ..Default::default()
+        };
+        let report = execute(&wf, "run", "p1", &mut runner);
+        assert_eq!(report.status, WorkflowRunStatus::Failed);
+        let keys: Vec<_> = report.outputs.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, ["balance", "orders", "logout"]);
+        assert_eq!(report.outputs[1].value, "text of #orders");
+        assert_eq!(report.outputs[2].step, 0);
+
+        let blank_key = r##"{"type":"extract_text","selector":".x","key":" "}"##;
+        assert!(validate("wf", &[step(blank_key)], &[]).is_err());
+    }
+
+    #[test]
+    fn manual_intervention_waits_for_resume_or_times_out() {
+        let interventions = std::sync::Arc::new(Interventions::default());
+        let wf = workflow(
+            vec![step(
+                r#"{"type":"manual_intervention","prompt":"Approve the 2FA push","timeout_secs":5,"retries":1,"backoff_ms":0}"#,
+            )],
+            Vec::new(),
+        );
+        validate(&wf.name, &wf.steps, &wf.cleanup).unwrap();
+
+        let board = interventions.clone();
+        let operator: Operator = Box::new(move |prompt, timeout_secs| {
+            let intervention = Intervention::new("run", "wf", "p1", prompt, timeout_secs);
+            // The operator answers as soon as the prompt goes out
+            board.wait(intervention, |i| {
+                assert_eq!(board.pending().len(), 1);
+                board.resume(&i.run_id).unwrap();
+            })
+        });
+        let (_tx, rx) = mpsc::channel();
+        let mut runner = BridgeStepRunner::new(Vec::new(), rx, Duration::from_millis(10))
+            .with_operator(operator);
+        let report = execute(&wf, "run", "p1", &mut runner);
+        assert_eq!(report.status, WorkflowRunStatus::Succeeded);
+        assert!(interventions.pending().is_empty());
+        assert!(interventions.resume("run").is_err());
+
+        let lapsed = interventions.wait(Intervention::new("r2", "wf", "p1", "solve", 0), |_| {});
+        assert!(lapsed.unwrap_err().contains("did not resume"));
+
+        let (_tx, rx) = mpsc::channel();
+        let mut alone = BridgeStepRunner::new(Vec::new(), rx, Duration::from_millis(10));
+        let report = execute(&wf, "run", "p1", &mut alone);
+        assert_eq!(report.status, WorkflowRunStatus::Aborted);
+        assert_eq!(report.steps[0].attempts, 2);
+        // Interventions never reach the bridge; only the failure capture does
+        let sent = String::from_utf8(alone.input).unwrap();
+        assert_eq!(sent.lines().count(), 1, "{sent}");
+        assert!(sent.contains("capture_failure"));
+        assert_eq!(alone.failures.len(), 1);
+    }
+
+    #[test]
+    fn variables_come_from_the_run_and_must_all_be_supplied() {
+        let wf = workflow(
+            vec![
+                step(r#"{"type":"navigate","url":"https://{{ host }}/login"}"#),
+                step(r##"{"type":"fill","selector":"#user","value":"{{username}}"}"##),
+                step(
+                    r##"{"type":"fill","selector":"#pass","value":"{{password}}","alt_selectors":["input[name={{field}}]"]}"##,
+                ),
+                step(r##"{"type":"fill","selector":"#note","value":"{{ not a var }} {x}"}"##),
+            ],
+            vec![step(r##"{"type":"click","selector":"{{logout}}"}"##)],
+        );
+        validate(&wf.name, &wf.steps, &wf.cleanup).unwrap();
+        let names: Vec<String> = referenced_variables(&wf).unwrap().into_iter().collect();
+        assert_eq!(names, ["field", "host", "logout", "password", "username"]);
+
+        let mut vars: BTreeMap<String, String> = [
+            ("host", "shop.example.com"),
+            ("username", "ann"),
+            ("password", "p{{w}}d"),
+            ("field", "pw"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let err = bind(&wf, &vars).unwrap_err().to_string();
+        assert!(err.contains("logout"), "{err}");
+
+        vars.insert("logout".into(), "a.logout".into());
+        let bound = bind(&wf, &vars).unwrap();
+        assert_eq!(bound.start_url(), Some("https://shop.example.com/login"));
+        assert_eq!(
+            bound.steps[2].action,
+            StepAction::Fill {
+                selector: "#pass".into(),
+                value: "p{{w}}d".into(),
+            },
+            "values are not expanded again"
+        );
+        assert_eq!(bound.steps[2].policy.alt_selectors, ["input[name=pw]"]);
+        assert_eq!(bound.steps[3].action, wf.steps[3].action);
+
+        vars.insert("host".into(), "bad host".into());
+        assert!(bind(&wf, &vars).is_err(), "bound urls are validated");
+    }
+
+    #[test]
+    fn repo_round_trips_definitions_and_rejects_bad_policies() {
+        let repo = WorkflowRepo::new(Db::open_in_memory().unwrap());
+        let steps = vec![
+            step(r#"{"type":"navigate","url":"https://shop.example.com"}"#),
+            step(r##"{"type":"wait_for","selector":"#ready","retries":2,"on_error":"cleanup"}"##),
+        ];
+        let created = repo
+            .create("checkout".into(), steps.clone(), Vec::new())
+            .unwrap();
+        let loaded = repo.get(&created.id).unwrap();
+        assert_eq!(loaded.steps, steps);
+        assert_eq!(loaded.start_url(), Some("https://shop.example.com"));
+        let StepAction::WaitFor { timeout_ms, .. } = loaded.steps[1].action else {
+            panic!("wait_for step");
+        };
+        assert_eq!(timeout_ms, DEFAULT_WAIT_TIMEOUT_MS);
+
+        for bad in [
+            r#"{"type":"navigate","url":"file:///etc/passwd"}"#,
+            r##"{"type":"sleep","ms":10,"alt_selectors":["#x"]}"##,
+            r##"{"type":"click","selector":"#x","retries":99}"##,
+            r##"{"type":"click","selector":"#x","backoff_multiplier":0.5}"##,
+            r#"{"type":"manual_intervention","prompt":" "}"#,
+            r#"{"type":"manual_intervention","prompt":"solve","timeout_secs":0}"#,
+        ] {
+            let err = repo.create("bad".into(), vec![step(bad)], Vec::new());
+            assert!(matches!(err, Err(ManifoldError::InvalidArg(_))), "{bad}");
+        }
+        repo.delete(&created.id).unwrap();
+        assert!(repo.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bridge_runner_maps_step_result_lines() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(parse_step_result_line(r#"STEP_RESULT {"ok":true}"#).unwrap())
+            .unwrap();
+        tx.send(parse_step_result_line(r#"STEP_RESULT {"ok":true,"value":["a","b"]}"#).unwrap())
+            .unwrap();
+        tx.send(parse_step_result_line(r#"STEP_RESULT {"ok":false,"error":"timeout"}"#).unwrap())
+            .unwrap();
+        let mut runner = BridgeStepRunner::new(Vec::new(), rx, Duration::from_millis(10));
+        let action = StepAction::Click {
+            selector: "#a".into(),
+        };
+        assert_eq!(runner.perform(&action), Ok(None));
+        assert_eq!(
+            runner.perform(&action),
+            Ok(Some(serde_json::json!(["a", "b"])))
+        );
+        assert_eq!(runner.perform(&action), Err("timeout".into()));
+        assert_eq!(runner.perform(&action), Err("bridge did not answer".into()));
+
+        tx.send(
+            parse_step_result_line(
+                r#"STEP_RESULT {"ok":true,"value":{"url":"https://a.test/","har":[{"status":500}]}}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let outcome = StepOutcome {
+            phase: StepPhase::Cleanup,
+            index: 1,
+            name: None,
+            status: StepStatus::Failed,
+            attempts: 1,
+            selector: None,
+            error: Some("timeout".into()),
+        };
+        runner.step_failed(&outcome);
+        runner.step_failed(&outcome);
+        let failures = runner.failures();
+        assert_eq!(failures[0].url.as_deref(), Some("https://a.test/"));
+        assert_eq!(failures[0].har.len(), 1);
+        assert_eq!(failures[0].screenshot, None);
+        // An unanswered capture still records the failure
+        assert_eq!(failures[1].url, None);
+        assert_eq!(failures[1].error.as_deref(), Some("timeout"));
+
+        let sent = String::from_utf8(runner.input).unwrap();
+        assert_eq!(sent.lines().count(), 6);
+        assert!(sent.contains(r#"{"har_entries":50,"type":"capture_failure"}"#));
+        assert!(sent.starts_with(r##"{"type":"click","selector":"#a"}"##));
+        assert!(parse_step_result_line("PREFLIGHT {}").is_none());
+    }
+}
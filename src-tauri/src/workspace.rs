@@ -0,0 +1,295 @@
+// ── Manifold workspaces ───────────────────────────────────────────────────────
+//
+// A workspace is a fully isolated Manifold setup — its own database, its own
+// profiles dir and its own master key — so work, personal and per-client
+// profiles, proxies and history never mix.  The original single database is
+// the `default` workspace; others live under `workspaces/<name>/` next to it.
+// The registry (`workspaces.json`) lists them and remembers which one was
+// active, which is the one opened at startup unless `MANIFOLD_WORKSPACE`
+// names another.
+//
+// Master keys are never stored.  They come from `switch_workspace` or from
+// the environment (`MANIFOLD_MASTER_KEY` for the default workspace,
+// `MANIFOLD_MASTER_KEY_<NAME>` for the others); a check value written on
+// first open makes a wrong key fail up front rather than on the first
+// encrypted field.  That first open also issues the workspace's recovery
+// codes (`master_key`).
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::Db;
+use crate::error::{ManifoldError, Result};
+use crate::profile::{ProfileRepo, ProfileStatus, TransitionReason};
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Overrides the registry's active workspace at startup.
+pub const WORKSPACE_ENV: &str = "MANIFOLD_WORKSPACE";
+
+const REGISTRY_FILE: &str = "workspaces.json";
+const WORKSPACES_DIR: &str = "workspaces";
+const DB_FILE: &str = "manifold.db";
+
+/// `settings` key holding the encrypted check value.
+pub const KEY_CHECK_KEY: &str = "key_check";
+const KEY_CHECK_PLAINTEXT: &str = "manifold-workspace";
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Workspace {
+    pub name: String,
+    pub db_path: PathBuf,
+    pub profiles_dir: PathBuf,
+    pub active: bool,
+    /// `None` for the default workspace.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Workspace {
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_WORKSPACE
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WorkspaceEntry {
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+/// `workspaces.json`.  The default workspace is implicit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceRegistry {
+    #[serde(skip)]
+    base: PathBuf,
+    /// `None` means the default workspace.
+    active: Option<String>,
+    workspaces: Vec<WorkspaceEntry>,
+}
+
+impl WorkspaceRegistry {
+    /// The registry next to the default database.
+    pub fn load_default() -> Result<Self> {
+        let db_path = crate::db::default_db_path();
+        Self::load(db_path.parent().unwrap_or_else(|| Path::new(".")))
+    }
+
+    pub fn load(base: &Path) -> Result<Self> {
+        let mut registry: Self = match std::fs::read_to_string(base.join(REGISTRY_FILE)) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e.into()),
+        };
+        registry.base = base.to_path_buf();
+        Ok(registry)
+    }
+
+    fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.base)?;
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(self.base.join(REGISTRY_FILE), json)?;
+        Ok(())
+    }
+
+    /// Name of the workspace opened at startup.
+    pub fn active(&self) -> &str {
+        self.active.as_deref().unwrap_or(DEFAULT_WORKSPACE)
+    }
+
+    pub fn list(&self) -> Vec<Workspace> {
+        std::iter::once(self.workspace(DEFAULT_WORKSPACE, None))
+            .chain(
+                self.workspaces
+                    .iter()
+                    .map(|w| self.workspace(&w.name, Some(w.created_at))),
+            )
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Result<Workspace> {
+        self.list()
+            .into_iter()
+            .find(|w| w.name == name)
+            .ok_or_else(|| ManifoldError::InvalidArg(format!("unknown workspace {name:?}")))
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<Workspace> {
+        validate_name(name)?;
+        if self.get(name).is_ok() {
+            return Err(ManifoldError::InvalidArg(format!(
+                "workspace {name:?} already exists"
+            )));
+        }
+        self.workspaces.push(WorkspaceEntry {
+            name: name.into(),
+            created_at: Utc::now(),
+        });
+        self.save()?;
+        self.get(name)
+    }
+
+    /// Remember `name` as the workspace to open at startup.
+    pub fn set_active(&mut self, name: &str) -> Result<()> {
+        self.get(name)?;
+        self.active = (name != DEFAULT_WORKSPACE).then(|| name.to_string());
+        self.save()
+    }
+
+    fn workspace(&self, name: &str, created_at: Option<DateTime<Utc>>) -> Workspace {
+        let (db_path, profiles_dir) = if name == DEFAULT_WORKSPACE {
+            (self.base.join(DB_FILE), crate::db::default_profiles_dir())
+        } else {
+            let dir = self.base.join(WORKSPACES_DIR).join(name);
+            (dir.join(DB_FILE), dir.join("profiles"))
+        };
+        Workspace {
+            name: name.into(),
+            db_path,
+            profiles_dir,
+            active: self.active() == name,
+            created_at,
+        }
+    }
+}
+
+/// Lowercase letters, digits, `-` and `_`; at most 32 characters.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        return Err(ManifoldError::InvalidArg(format!(
+            "invalid workspace name {name:?} (use a-z, 0-9, '-' and '_')"
+        )));
+    }
+    Ok(())
+}
+
+/// Environment variable holding a workspace's master key.
+pub fn master_key_env(name: &str) -> String {
+    if name == DEFAULT_WORKSPACE {
+        "MANIFOLD_MASTER_KEY".into()
+    } else {
+        format!(
+            "MANIFOLD_MASTER_KEY_{}",
+            name.to_ascii_uppercase().replace('-', "_")
+        )
+    }
+}
+
+// ── Opening ───────────────────────────────────────────────────────────────────
+
+/// Open a workspace's database and check `master_key` against it.  No
+/// bridge survives a restart or a switch, so profiles it left Running or
+/// Suspended are stopped.
+pub fn open(workspace: &Workspace, master_key: Option<&str>) -> Result<Db> {
+    let db = Db::open(&workspace.db_path, master_key)?;
+    check_master_key(&db)?;
+
+    let stale = ProfileRepo::new_with_root(db.clone(), workspace.profiles_dir.clone())
+        .transition_all(
+            &[ProfileStatus::Running, ProfileStatus::Suspended],
+            ProfileStatus::Idle,
+            TransitionReason::Startup,
+        )?;
+    if !stale.is_empty() {
+        eprintln!(
+            "[workspace] {}: reset {} stale profile status(es)",
+            workspace.name,
+            stale.len()
+        );
+    }
+    Ok(db)
+}
+
+/// Make `workspace`'s profiles dir the one `db::profiles_dir` returns.
+pub fn use_profiles_dir(workspace: &Workspace) {
+    crate::db::set_profiles_dir((!workspace.is_default()).then(|| workspace.profiles_dir.clone()));
+}
+
+/// Compare the database's check value with its master key, writing it —
+/// and issuing the first recovery codes — on the first open.
+pub fn check_master_key(db: &Db) -> Result<()> {
+    match crate::settings::load::<Option<String>>(db, KEY_CHECK_KEY)? {
+        Some(stored) => {
+            if db.decrypt_field(&stored).ok().as_deref() == Some(KEY_CHECK_PLAINTEXT) {
+                Ok(())
+            } else {
+                Err(ManifoldError::Crypto(
+                    "master key does not match this workspace".into(),
+                ))
+            }
+        }
+        None => {
+            crate::settings::store(db, KEY_CHECK_KEY, &db.encrypt_field(KEY_CHECK_PLAINTEXT)?)?;
+            crate::master_key::issue_on_setup(db)
+        }
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_creates_and_remembers_active_workspace() {
+        let base = tempfile::tempdir().unwrap();
+        let mut registry = WorkspaceRegistry::load(base.path()).unwrap();
+        assert_eq!(registry.active(), DEFAULT_WORKSPACE);
+
+        let ws = registry.create("client-x").unwrap();
+        assert_eq!(
+            ws.db_path,
+            base.path().join("workspaces/client-x/manifold.db")
+        );
+        assert!(registry.create("client-x").is_err());
+        assert!(registry.create("Client X").is_err());
+        registry.set_active("client-x").unwrap();
+
+        let registry = WorkspaceRegistry::load(base.path()).unwrap();
+        assert_eq!(registry.active(), "client-x");
+        let names: Vec<_> = registry.list().into_iter().map(|w| w.name).collect();
+        assert_eq!(names, [DEFAULT_WORKSPACE, "client-x"]);
+        assert!(registry.get("nope").is_err());
+        assert_eq!(master_key_env("client-x"), "MANIFOLD_MASTER_KEY_CLIENT_X");
+    }
+
+    #[test]
+    fn wrong_master_key_is_rejected() {
+        let base = tempfile::tempdir().unwrap();
+        let ws = WorkspaceRegistry::load(base.path())
+            .unwrap()
+            .create("work")
+            .unwrap();
+        drop(open(&ws, Some("correct horse")).unwrap());
+        assert!(open(&ws, Some("correct horse")).is_ok());
+        assert!(matches!(
+            open(&ws, Some("battery staple")),
+            Err(ManifoldError::Crypto(_))
+        ));
+        assert!(open(&ws, None).is_err());
+    }
+
+    #[test]
+    fn replaced_db_is_seen_by_every_clone() {
+        let base = tempfile::tempdir().unwrap();
+        let mut registry = WorkspaceRegistry::load(base.path()).unwrap();
+        let a = open(&registry.create("a").unwrap(), None).unwrap();
+        let b = open(&registry.create("b").unwrap(), None).unwrap();
+        crate::settings::store(&b, "marker", &"b").unwrap();
+
+        let held = a.clone();
+        a.replace_with(b).unwrap();
+        let marker: String = crate::settings::load(&held, "marker").unwrap();
+        assert_eq!(marker, "b");
+    }
+}
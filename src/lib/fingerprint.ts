@@ -393,6 +393,7 @@ export function generateFingerprintFallback(seedInput?: number): Fingerprint {
     webgl_noise: webglNoise,
     audio_noise: audioNoise,
     font_subset: fontSubset,
+    text_rendering: null, // derived from the OS build by the backend
     user_agent: ua.userAgent,
     platform: ua.platform,
     accept_language: locale.acceptLanguage,
@@ -404,11 +405,18 @@ export function generateFingerprintFallback(seedInput?: number): Fingerprint {
     viewport_height: screen.viewportHeight,
     color_depth: 24,
     pixel_ratio: screen.pixelRatio,
+    color_gamut: 'srgb',
+    hdr: false,
+    prefers_color_scheme: 'light', // seeded by the backend
+    prefers_reduced_motion: false,
+    forced_colors: false,
+    timing_noise: null, // seeded by the backend
     webrtc_mode: 'fake_mdns',
     webrtc_fake_mdns: generateMdnsHostname(rng),
     webrtc_fake_ip: generateFakeLocalIp(rng),
     timezone: locale.timezone,
     locale: locale.locale,
+    intl: null, // derived from the locale by the backend
     ua_brands: uaBrands,
     ua_mobile: false,
     ua_platform: ua.uaPlatform,
@@ -132,7 +132,7 @@ function _scheduleReconnect(): void {
   if (_destroyed || _reconnectTimer !== null) return;
   _reconnectTimer = setTimeout(() => {
     _reconnectTimer = null;
-    _open();
+    void _open();
   }, RECONNECT_DELAY_MS);
 }
 
@@ -247,13 +247,30 @@ function _dispatch(msg: BridgeServerMsg): void {
 // WebSocket lifecycle
 // ─────────────────────────────────────────────────────────────────────────────
 
-function _open(): void {
+/** The bridge URL with a one-time handshake ticket (bridge_auth.rs); every
+ *  connect needs a fresh one.  The bare URL when Tauri is unavailable.
+ *  Bridges on an agent node keep the node's host and port. */
+async function _ticketUrl(): Promise<string> {
+  try {
+    const url = await invoke<string>("get_bridge_url");
+    return url.replace(/localhost:\d+/, `localhost:${port}`);
+  } catch {
+    return `ws://localhost:${port}`;
+  }
+}
+
+async function _open(): Promise<void> {
   if (_destroyed) return;
 
   connecting = true;
+  const url = await _ticketUrl();
+  if (_destroyed) {
+    connecting = false;
+    return;
+  }
 
   try {
-    const socket = new WebSocket(`ws://localhost:${port}`);
+    const socket = new WebSocket(url);
     _socket = socket;
 
     socket.onopen = () => {
@@ -262,7 +279,7 @@ function _open(): void {
       connected = true;
       connecting = false;
       _startHeartbeat();
-      _log("info", `Connected to bridge ws://localhost:${port}`);
+      _log("info", `Connected to bridge ws://${new URL(url).host}`);
     };
 
     socket.onmessage = (ev: MessageEvent) => {
@@ -308,7 +325,7 @@ async function connect(wsPort?: number): Promise<void> {
   } else {
     try {
       const url = await invoke<string>("get_bridge_url");
-      const match = url.match(/:(\d+)$/);
+      const match = url.match(/localhost:(\d+)/);
       if (match) port = parseInt(match[1], 10);
     } catch {
       // use default
@@ -316,7 +333,7 @@ async function connect(wsPort?: number): Promise<void> {
   }
 
   _destroyed = false;
-  _open();
+  await _open();
 }
 
 function disconnect(): void {
@@ -512,6 +529,11 @@ function exportEntropyJson(): string {
   return JSON.stringify(entropySnaps, null, 2);
 }
 
+/** Export the recorder's event log as JSON */
+function exportEventsJson(): string {
+  return JSON.stringify(eventLog, null, 2);
+}
+
 /** Download a blob in the browser */
 function downloadBlob(
   content: string,
@@ -660,6 +682,7 @@ export const bridgeStore = {
   // ── Exports ───────────────────────────────────────────────────────────────
   exportHarJson,
   exportEntropyJson,
+  exportEventsJson,
   downloadHar,
   downloadEntropy,
 };
@@ -9,6 +9,7 @@ import type {
   ProfileTarget,
   BehaviorProfile,
   EmergencyRotateMode,
+  PoolStatus,
 } from "$lib/types";
 import { resolvePlatform } from "$lib/constants/platforms";
 import { generateFingerprintFallback } from "$lib/fingerprint";
@@ -343,6 +344,8 @@ async function updateProfile(
     notes: notes ?? null,
     tags: payload.tags ?? null,
     behaviorProfile: payload.behavior_profile ?? null,
+    persona: payload.persona ?? null,
+    clearPersona: payload.persona === null,
   });
 
   if (!raw) {
@@ -364,6 +367,8 @@ async function updateProfile(
         payload.proxy_id !== undefined ? payload.proxy_id : existing.proxy_id,
       notes: notes ?? existing.notes,
       tags: payload.tags ?? existing.tags,
+      persona:
+        payload.persona !== undefined ? payload.persona : existing.persona,
       status: existing.status,
     };
 
@@ -539,7 +544,12 @@ async function cloneProfile(
 // Launch / stop
 // ─────────────────────────────────────────────────────────────────────────────
 
-async function launchProfile(id: string, url?: string): Promise<number> {
+/** Launch a profile's bridge, locally or on the execution node `nodeId`. */
+async function launchProfile(
+  id: string,
+  url?: string,
+  nodeId?: string,
+): Promise<number> {
   // If no explicit URL, use the profile's target URL
   const profile = profiles.find((p) => p.id === id);
   const launchUrl = url ?? profile?.target?.url ?? "about:blank";
@@ -547,6 +557,7 @@ async function launchProfile(id: string, url?: string): Promise<number> {
   const port = await safeInvoke<number>("launch_profile", {
     id,
     url: launchUrl,
+    nodeId: nodeId ?? null,
   });
 
   if (!port) {
@@ -570,6 +581,17 @@ async function launchSelected(): Promise<void> {
   await Promise.all([...selectedIds].map((id) => launchProfile(id)));
 }
 
+/** Queue the selected profiles on the execution node pool; they start as
+ *  nodes have room and are moved if their node goes offline. */
+async function launchSelectedOnNodes(): Promise<PoolStatus | null> {
+  return safeInvoke<PoolStatus>("enqueue_launches", {
+    launches: [...selectedIds].map((id) => ({
+      profile_id: id,
+      url: profiles.find((p) => p.id === id)?.target?.url ?? null,
+    })),
+  });
+}
+
 async function stopSelected(): Promise<void> {
   await Promise.all([...selectedIds].map((id) => stopProfile(id)));
 }
@@ -713,6 +735,7 @@ export const profileStore = {
   launchProfile,
   stopProfile,
   launchSelected,
+  launchSelectedOnNodes,
   stopSelected,
 
   // ── Emergency ─────────────────────────────────────────────────────────────
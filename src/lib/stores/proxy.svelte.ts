@@ -426,15 +426,7 @@ async function loadProxies(): Promise<void> {
 
 async function addProxy(payload: AddProxyPayload): Promise<Proxy> {
   const invoke = await requireInvoke();
-  const raw = await invoke<Proxy>("add_proxy", {
-    name: payload.name,
-    proxyType: payload.proxy_type,
-    host: payload.host,
-    port: payload.port,
-    username: payload.username ?? null,
-    password: payload.password ?? null,
-    country: payload.country ?? null,
-  });
+  const raw = await invoke<Proxy>("add_proxy", { proxy: payload });
 
   // Backend strips password via #[serde(skip_serializing)].
   // Preserve it client-side so the automation bridge can authenticate.
@@ -463,16 +455,7 @@ async function updateProxy(
   payload: UpdateProxyPayload,
 ): Promise<Proxy> {
   const invoke = await requireInvoke();
-  const raw = await invoke<Proxy>("update_proxy", {
-    id,
-    name: payload.name ?? null,
-    proxyType: payload.proxy_type ?? null,
-    host: payload.host ?? null,
-    port: payload.port ?? null,
-    username: payload.username ?? null,
-    password: payload.password ?? null,
-    country: payload.country ?? null,
-  });
+  const raw = await invoke<Proxy>("update_proxy", { id, patch: payload });
 
   // Backend strips password via #[serde(skip_serializing)].
   // If the payload included a new password, keep it client-side.
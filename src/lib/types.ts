@@ -7,9 +7,64 @@
 // Primitives
 // ─────────────────────────────────────────────────────────────────────────────
 
-export type ProfileStatus = "idle" | "running" | "error";
-export type ProxyType = "http" | "https" | "socks5";
+export type ProfileStatus = "idle" | "running" | "error" | "suspended";
+
+export type TransitionReason =
+  | "launch"
+  | "launch_failed"
+  | "stop"
+  | "exited"
+  | "crashed"
+  | "replaced"
+  | "failover"
+  | "panic"
+  | "sleep"
+  | "wake"
+  | "startup"
+  | "manual";
+
+/** One attempted status change (list_status_transitions) */
+export interface StatusTransition {
+  profile_id: string;
+  from: ProfileStatus;
+  to: ProfileStatus;
+  reason: TransitionReason;
+  /** False when the state machine rejected the change */
+  allowed: boolean;
+  at: string;
+}
+export type ProxyType = "http" | "https" | "socks5" | "ssh";
 export type WebRtcMode = "block" | "fake_mdns" | "passthrough";
+export type ColorGamut = "srgb" | "p3";
+export type ColorScheme = "light" | "dark";
+
+/** Intl data of the fingerprint's locale (intl.rs) */
+export interface IntlProfile {
+  calendar: string;
+  numbering_system: string;
+  hour_cycle: string;
+  /** 1 = Monday … 7 = Sunday */
+  first_day_of_week: number;
+  weekend: number[];
+  minimal_days: number;
+  currency: string;
+  currency_sample: string;
+}
+/** performance.now() bucketing and input-event latency (fingerprint.rs) */
+export interface TimingNoise {
+  precision_us: number;
+  event_jitter_ms: number;
+}
+/** Multi-monitor desktop and the ranges windows open in (fingerprint.rs) */
+export interface ScreenLayout {
+  monitors: number;
+  /** screen.availLeft / availTop: top-left of the browser's monitor */
+  avail_left: number;
+  avail_top: number;
+  /** Inclusive ranges each session's screenX / screenY are drawn from */
+  window_x: [number, number];
+  window_y: [number, number];
+}
 export type BehaviorProfile = "bot" | "fast" | "normal" | "cautious";
 export type FontSubset = "full" | "reduced" | "paranoid";
 export type RotationPolicy = "manual" | "interval" | "on_ban" | "poisson";
@@ -23,8 +78,24 @@ export interface UaBrand {
   version: string;
 }
 
+export type TextAntialiasing = "subpixel" | "grayscale";
+
+/** Emoji / glyph rendering of the claimed OS build */
+export interface TextRendering {
+  emoji_font: string;
+  /** Newest Unicode Emoji version the emoji font covers, e.g. "15.0" */
+  emoji_version: string;
+  antialiasing: TextAntialiasing;
+  /** Multiplier applied to measureText widths */
+  measure_scale: number;
+  /** Vertical glyph offset in px */
+  baseline_shift: number;
+}
+
 export interface Fingerprint {
   seed: number;
+  /** Generator version; reseeding from `seed` stays on it */
+  generator: number;
 
   // Canvas
   canvas_noise: number; // 0.0 – 1.0
@@ -39,6 +110,7 @@ export interface Fingerprint {
 
   // Fonts
   font_subset: string[];
+  text_rendering: TextRendering | null;
 
   // Navigator
   user_agent: string;
@@ -52,8 +124,23 @@ export interface Fingerprint {
   screen_height: number;
   viewport_width: number;
   viewport_height: number;
+  /** Viewport varies slightly from session to session */
+  window_variation?: boolean;
+  /** Null keeps a maximised window on the primary monitor */
+  screen_layout?: ScreenLayout | null;
   color_depth: number; // 24 | 30
   pixel_ratio: number; // 1.0 | 1.25 | 1.5 | 2.0
+  color_gamut: ColorGamut;
+  hdr: boolean;
+
+  // Media preferences
+  prefers_color_scheme: ColorScheme;
+  prefers_reduced_motion: boolean;
+  forced_colors: boolean;
+
+  // Timing
+  /** Null for profiles created before timing noise was seeded */
+  timing_noise: TimingNoise | null;
 
   // WebRTC
   webrtc_mode: WebRtcMode;
@@ -63,6 +150,8 @@ export interface Fingerprint {
   // Locale
   timezone: string; // IANA e.g. "America/New_York"
   locale: string; // BCP-47 e.g. "en-US"
+  /** Null for profiles created before Intl data was stored */
+  intl: IntlProfile | null;
 
   // UA-CH
   ua_brands: UaBrand[];
@@ -71,6 +160,10 @@ export interface Fingerprint {
   ua_platform_version: string;
   ua_architecture: string;
   ua_bitness: string;
+  /** The UA string has the frozen `<major>.0.0.0` version */
+  ua_reduction?: boolean;
+  /** Full Chrome version while `ua_reduction` is on */
+  ua_full_version?: string | null;
 
   // Permissions
   permissions: Record<string, string>;
@@ -90,6 +183,40 @@ export interface MouseConfig {
   micro_jitter_prob: number;
 }
 
+/** Timestamped cursor path from `generate_mouse_path` (motion.rs) */
+export interface PathPoint {
+  x: number;
+  y: number;
+  /** ms since the move started */
+  t: number;
+}
+
+export interface MousePath {
+  points: PathPoint[];
+  overshoot: boolean;
+}
+
+/** `export_mouse_trace`: one entry per path point */
+export interface MouseTrace {
+  x: number[];
+  y: number[];
+  t: number[];
+  velocity: number[];
+  curvature: number[];
+}
+
+/** `generate_scroll_pattern`: wheel ticks adding up to the requested delta */
+export interface ScrollTick {
+  delta_y: number;
+  delay_ms: number;
+}
+
+export interface ScrollPattern {
+  ticks: ScrollTick[];
+  corrections: number;
+  reading_pauses: number;
+}
+
 export interface TypingConfig {
   base_wpm: number;
   wpm_jitter: number;
@@ -170,6 +297,13 @@ export type TargetTag =
 // Profile
 // ─────────────────────────────────────────────────────────────────────────────
 
+export interface Persona {
+  name: string;
+  age_bracket?: "18-24" | "25-34" | "35-44" | "45-54" | "55+";
+  interests?: string[];
+  home_city?: string | null;
+}
+
 export interface Profile {
   id: string;
   name: string;
@@ -187,6 +321,18 @@ export interface Profile {
   aggression?: number;
   /** Enable TLS bridge for JA4 fingerprinting control */
   tls_bridge?: boolean;
+  /** Let scheduled aging bump the Chrome version */
+  auto_age?: boolean;
+  /** Who the profile is; drives warm-up plans and locale */
+  persona?: Persona | null;
+  /** Extra bridge environment variables, over the scrubbed host env */
+  launch_env?: Record<string, string>;
+  /** Data dir size limit in MiB; null uses the app-wide default */
+  disk_quota_mb?: number | null;
+  /** Launch mode and fallbacks (set_profile_launch_policy) */
+  launch_policy?: LaunchPolicy;
+  /** Chrome version held for legacy sites (set_profile_pinned_version); aging skips it */
+  pinned_browser_version?: string | null;
   // Derived client-side (not persisted separately)
   target?: ProfileTarget;
 }
@@ -209,6 +355,316 @@ export interface UpdateProfilePayload {
   tags?: string[];
   behavior_profile?: BehaviorProfile;
   tls_bridge?: boolean;
+  /** null clears the persona; a change re-derives locale and timezone */
+  persona?: Persona | null;
+}
+
+/** list_clock_flagged_sessions: sessions where the host clock jumped */
+export interface FlaggedSession {
+  id: string;
+  started_at: string;
+  ended_at: string | null;
+  clock_jumps: number;
+}
+
+/** One statistical check of a session's dispatched input (behavior_audit.rs) */
+export interface AuditCheck {
+  name:
+    | "move_timing"
+    | "velocity_entropy"
+    | "velocity_kurtosis"
+    | "keystroke_timing"
+    | "click_dwell";
+  status: LeakCheckStatus;
+  value: number | null;
+  detail: string;
+}
+
+export interface BehaviorAudit {
+  samples: number;
+  /** 0–100 humanness score; null when every check was skipped */
+  score: number | null;
+  checks: AuditCheck[];
+  audited_at: string;
+}
+
+/** list_behavior_audits */
+export interface AuditedSession {
+  id: string;
+  started_at: string;
+  audit: BehaviorAudit;
+}
+
+/** list_launch_conflicts: a rule seen from one of its profiles (launch_conflicts.rs) */
+export interface ConflictRule {
+  profile_id: string;
+  other_id: string;
+  other_name: string;
+  reason: string;
+  created_at: string;
+}
+
+/** check_launch_conflicts: an online profile that blocks the launch */
+export interface LaunchConflict {
+  other_id: string;
+  other_name: string;
+  other_status: ProfileStatus;
+  reason: string;
+}
+
+/** get_bridge_info: the bridge launches will use */
+export interface LocatedBridge {
+  runtime: {
+    kind: "sidecar" | "packaged_js" | "source";
+    path: string;
+  };
+  /** null for a source checkout */
+  manifest: { version: string; protocol: number; sha3_256: string } | null;
+}
+
+/** App-wide bridge launch environment (get/set_launch_env_settings) */
+export interface LaunchEnvSettings {
+  /** Host variables passed through on top of the built-in allowlist */
+  allowlist: string[];
+  /** Directory holding node/npx; null searches the host PATH */
+  node_dir: string | null;
+}
+
+export interface OrphanedDir {
+  name: string;
+  path: string;
+  bytes: number;
+  /** Always false on a dry run */
+  removed: boolean;
+}
+
+export interface GcReport {
+  dry_run: boolean;
+  orphans: OrphanedDir[];
+  reclaimed_bytes: number;
+}
+
+/** delete_proxy: detach it from profiles still using it, or refuse */
+export type OnDelete = "nullify" | "block";
+
+export type IssueKind = "dangling_proxy" | "dangling_chain_hop" | "orphan_row";
+
+export interface IntegrityIssue {
+  kind: IssueKind;
+  table: string;
+  /** Row id (rowid for tables keyed otherwise) */
+  row: string;
+  /** Table the missing row belongs to */
+  parent: string;
+  missing: string | null;
+}
+
+/** check_integrity / repair_integrity */
+export interface IntegrityReport {
+  issues: IntegrityIssue[];
+  /** Issues fixed; 0 for check_integrity */
+  repaired: number;
+}
+
+export interface QuotaSettings {
+  /** Limit for profiles without their own; null is unlimited */
+  default_quota_mb: number | null;
+  /** Empty cache folders of idle profiles over their limit */
+  auto_clear_cache: boolean;
+}
+
+/** get/set_cache_prewarm_settings */
+export interface CachePrewarmSettings {
+  /** Copy the shared cache layer into new profiles; cache state is a fingerprint */
+  enabled: boolean;
+  /** Hosts (and subdomains) whose entries harvest_shared_cache keeps */
+  hosts: string[];
+  /** Top-level sites whose partitioned entries harvest_shared_cache keeps */
+  top_frame_sites: string[];
+}
+
+/** get_shared_cache */
+export interface SharedCache {
+  entries: number;
+  bytes: number;
+}
+
+/** harvest_shared_cache */
+export interface HarvestReport {
+  scanned: number;
+  /** Entries new to the shared layer */
+  added: number;
+  shared: SharedCache;
+}
+
+export interface QuotaUsage {
+  profile_id: string;
+  bytes: number;
+  quota_bytes: number | null;
+  level: "ok" | "warning" | "exceeded";
+  cleared_bytes: number;
+}
+
+export interface PowerSettings {
+  /** Relaunch profiles paused for host sleep; otherwise they are stopped */
+  resume_on_wake: boolean;
+}
+
+export type AlertKind =
+  | "proxy_burned"
+  | "panic_shutdown"
+  | "db_nearly_full"
+  | "workflow_intervention";
+
+/** Sinks an alert kind is sent to */
+export interface AlertRoute {
+  native: boolean;
+  telegram: boolean;
+}
+
+export interface NotificationSettings {
+  /** null disables the Telegram sink for every alert kind */
+  telegram: { bot_token: string; chat_id: string } | null;
+  proxy_burned: AlertRoute;
+  panic_shutdown: AlertRoute;
+  db_nearly_full: AlertRoute;
+  workflow_intervention: AlertRoute;
+  /** Free space below which the database volume counts as nearly full */
+  db_min_free_mb: number;
+}
+
+/** test_notifications: one entry per sink; error is null when sent */
+export interface SinkResult {
+  sink: string;
+  error: string | null;
+}
+
+export interface AppSettings {
+  /** Bridge WebSocket port used at startup */
+  bridge_port: number;
+  /** TLS bridge port used at startup */
+  tls_bridge_port: number;
+  /** Proxies health-checked in parallel */
+  proxy_check_concurrency: number;
+}
+
+/** get/set_priors_source: both or neither set */
+export interface PriorsSource {
+  /** https URL of the signed dataset document */
+  url: string | null;
+  /** Base64 Ed25519 public key the dataset must be signed with */
+  public_key: string | null;
+}
+
+/** Population shares of one field's values */
+export interface Distribution {
+  shares: Record<string, number>;
+  /** Values not listed; they split the remaining share evenly */
+  unlisted: number;
+}
+
+/** get_priors: the population dataset in use */
+export interface Priors {
+  version: number;
+  published: string;
+  platform: Distribution;
+  /** Chrome majors behind stable; negative ones are ahead of it */
+  chrome_lag: Distribution;
+  screen: Distribution;
+  pixel_ratio: Distribution;
+  hardware_concurrency: Distribution;
+  device_memory: Distribution;
+  color_depth: Distribution;
+  color_gamut: Distribution;
+  hdr: Distribution;
+  color_scheme: Distribution;
+  reduced_motion: Distribution;
+  forced_colors: Distribution;
+  /** By ua_platform */
+  gpu_vendor: Record<string, Distribution>;
+  gpu_models: Record<string, number>;
+  timezone: Distribution;
+  locale: Distribution;
+  font_coverage: Distribution;
+  uhd_countries: string[];
+  low_dpr_countries: string[];
+}
+
+/** get_priors_status / update_priors */
+export interface PriorsStatus {
+  version: number;
+  published: string;
+  /** A fetched dataset is in use rather than the built-in one */
+  downloaded: boolean;
+  fetched_at: string | null;
+}
+
+/** get_settings; update_settings takes a JSON merge patch of this shape */
+export interface Settings {
+  app: AppSettings;
+  proxy_check_targets: string[];
+  launch_env: LaunchEnvSettings;
+  power: PowerSettings;
+  disk_quota: QuotaSettings;
+  cache_prewarm: CachePrewarmSettings;
+  priors_source: PriorsSource;
+  notifications: NotificationSettings;
+  costs: CostSettings;
+}
+
+export type ReportFormat = "csv" | "json";
+
+/** export_workspace_report: what was written and how many rows */
+export interface WorkspaceReport {
+  format: ReportFormat;
+  files: string[];
+  profiles: number;
+  proxies: number;
+  events: number;
+}
+
+/** An isolated database + profiles dir + master key */
+export interface Workspace {
+  name: string;
+  db_path: string;
+  profiles_dir: string;
+  /** Opened at startup */
+  active: boolean;
+  /** null for the default workspace */
+  created_at: string | null;
+}
+
+/** Payload of the "master-key-rotation" event */
+export interface RotationProgress {
+  /** Table (or "settings") being re-encrypted */
+  table: string;
+  done: number;
+  total: number;
+}
+
+export interface RecoveryStatus {
+  /** The workspace has a master key */
+  encrypted: boolean;
+  /** Recovery codes still unused */
+  codes_left: number;
+}
+
+export type LeakCheckStatus = "pass" | "fail" | "skipped";
+
+export interface LeakCheck {
+  name: "webrtc" | "dns" | "ipv6" | "timezone" | "ua_consistency" | "engine";
+  status: LeakCheckStatus;
+  detail: string;
+}
+
+/** Latest run_leak_test result, stored per profile */
+export interface LeakTestReport {
+  profile_id: string;
+  passed: boolean;
+  exit_ip: string | null;
+  exit_country_code: string | null;
+  checks: LeakCheck[];
+  ran_at: string;
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -227,17 +683,59 @@ export interface Proxy {
    *  the automation bridge can configure authenticated proxies. */
   password: string | null;
   country: string | null;
+  /** State, province or city in `country` ("UT", "Salt Lake City"); pins
+   *  the timezone. Filled from the measured exit by a leak test unless set here */
+  region?: string | null;
+  /** Private key file for SSH proxies (password auth without one) */
+  ssh_key_path?: string | null;
+  pinned_ip?: string | null;
+  /** How health checks talk to HTTP(S) proxies */
+  check_mode?: ProxyCheckMode;
+  /** null until a check has tried that mode */
+  supports_get?: boolean | null;
+  supports_connect?: boolean | null;
+  /** Health-check URLs for this proxy; empty = app-wide list */
+  check_targets?: string[];
+  /** KB/s from the last speed test */
+  throughput_kbps?: number | null;
+  /** Transparent proxies forward the real IP and are refused at launch */
+  anonymity?: ProxyAnonymity | null;
+  /** Free-form labels ("client A only", "mobile 4G") */
+  tags?: string[];
+  notes?: string;
   healthy: boolean;
   latency_ms: number | null;
   last_checked: string | null;
   rotation_policy?: ProxyRotationPolicy;
 }
 
+/** list_proxies filter; every set field must match */
+export interface ProxyFilter {
+  tag?: string | null;
+  /** Case-insensitive substring of the name or notes */
+  search?: string | null;
+}
+
+export interface TargetLatency {
+  target: string;
+  latency_ms: number | null;
+  error: string | null;
+}
+
+export type ProxyAnonymity = "transparent" | "anonymous" | "elite";
+
+export type ProxyCheckMode = "get" | "connect" | "both";
+
 export interface ProxyHealth {
   id: string;
   healthy: boolean;
   latency_ms: number | null;
   error: string | null;
+  supports_get: boolean | null;
+  supports_connect: boolean | null;
+  /** Only set by measure_proxy_speed */
+  throughput_kbps: number | null;
+  anonymity: ProxyAnonymity | null;
   checked_at: string;
 }
 
@@ -249,6 +747,9 @@ export interface AddProxyPayload {
   username?: string;
   password?: string;
   country?: string;
+  region?: string;
+  tags?: string[];
+  notes?: string;
 }
 
 export interface UpdateProxyPayload {
@@ -259,6 +760,9 @@ export interface UpdateProxyPayload {
   username?: string;
   password?: string;
   country?: string;
+  region?: string;
+  tags?: string[];
+  notes?: string;
 }
 
 /** BrightData/Luminati zone configuration — UI-level, not persisted directly */
@@ -702,3 +1206,905 @@ export interface Toast {
   message: string;
   type: "info" | "success" | "error" | "warning";
 }
+
+/** Timing of one command (metrics.rs); percentiles over its recent calls */
+export interface CommandMetric {
+  command: string;
+  calls: number;
+  mean_ms: number;
+  p50_ms: number;
+  p95_ms: number;
+  p99_ms: number;
+  max_ms: number;
+  mean_arg_bytes: number;
+  max_arg_bytes: number;
+}
+
+export interface SlowCall {
+  command: string;
+  duration_ms: number;
+  arg_bytes: number;
+  at: string;
+}
+
+/** get_command_metrics: slowest commands first, newest slow calls first */
+export interface MetricsReport {
+  commands: CommandMetric[];
+  slow: SlowCall[];
+}
+
+/** share_profile: what the bundle leaves out (both stripped by default) */
+export interface ShareOptions {
+  strip_proxy_credentials: boolean;
+  strip_cookies: boolean;
+}
+
+export interface ShareSummary {
+  path: string;
+  proxies: number;
+  cookie_files: number;
+  stripped: ShareOptions;
+}
+
+/** import_shared_profile: set credentials on `needs_credentials` before launching */
+export interface ImportedShare {
+  profile: Profile;
+  reused_proxies: string[];
+  created_proxies: string[];
+  needs_credentials: string[];
+}
+
+/** Hamming distance between two noised probes */
+export interface ProbeDistance {
+  differing: number;
+  total: number;
+}
+
+export interface NoiseDistance {
+  canvas: ProbeDistance;
+  webgl: ProbeDistance;
+  audio: ProbeDistance;
+}
+
+export interface FieldChange {
+  /** Dotted path, e.g. "intl.calendar" */
+  field: string;
+  before: unknown;
+  after: unknown;
+}
+
+/** diff_fingerprints: what changes going from `a` to `b` */
+export interface FingerprintDiff {
+  changes: FieldChange[];
+  unchanged: number;
+  noise: NoiseDistance;
+}
+
+/** A locale with the Accept-Language chain and timezone it comes with */
+export interface GeoChoice {
+  locale: string;
+  accept_language: string;
+  timezone: string;
+}
+
+/** list_geo_options: empty lists for countries without canonical choices */
+export interface GeoOptions {
+  country: string;
+  locales: string[];
+  timezones: string[];
+  accept_languages: string[];
+  choices: GeoChoice[];
+}
+
+/** Surprisal of one field's value against its population prior */
+export interface FieldEntropy {
+  field: string;
+  value: string;
+  probability: number;
+  bits: number;
+  /** Shannon entropy of the field: what an average user's value carries */
+  typical_bits: number;
+  unusual: boolean;
+}
+
+/** get_entropy_report: fields most unusual first; totals are upper bounds */
+export interface EntropyReport {
+  seed: number;
+  total_bits: number;
+  typical_bits: number;
+  fields: FieldEntropy[];
+}
+
+/** A seed whose fingerprint no longer matches its golden vector */
+export interface VectorDrift {
+  seed: number;
+  changes: FieldChange[];
+}
+
+/** verify_fingerprint_vectors: empty `drifted` means identities are stable */
+export interface VectorReport {
+  generator: string;
+  current: string;
+  checked: number;
+  drifted: VectorDrift[];
+}
+
+/** compute_expected_ja4: the fingerprints the TLS bridge presents */
+export interface ExpectedJa4 {
+  /** False when the browser's own ClientHello is seen instead */
+  bridge_enabled: boolean;
+  chrome_major: number | null;
+  post_quantum: boolean;
+  ech_grease: boolean;
+  ja4: string;
+  /** Unhashed JA4, to see which part of a mismatch differs */
+  ja4_r: string;
+  /** First navigation to a site: no cookie, no referer */
+  ja4h: string;
+}
+
+export interface ProxyBandwidth {
+  /** null for direct traffic */
+  proxy_id: string | null;
+  /** null when the proxy was deleted */
+  name: string | null;
+  bytes_up: number;
+  bytes_down: number;
+}
+
+export interface ProfileBandwidth {
+  profile_id: string;
+  /** null when the profile was deleted */
+  name: string | null;
+  sessions: number;
+  bytes_up: number;
+  bytes_down: number;
+}
+
+/** One proxy's traffic on one UTC day */
+export interface DailyBandwidth {
+  /** YYYY-MM-DD */
+  day: string;
+  proxy_id: string | null;
+  bytes_up: number;
+  bytes_down: number;
+}
+
+/** get_bandwidth_report: traffic since `since` (UTC), for proxy bills */
+export interface BandwidthReport {
+  since: string;
+  bytes_up: number;
+  bytes_down: number;
+  by_proxy: ProxyBandwidth[];
+  by_profile: ProfileBandwidth[];
+  by_day: DailyBandwidth[];
+}
+
+export interface CaptchaProvider {
+  name: string;
+  price_per_solve: number;
+}
+
+/** Prices are all in `currency` */
+export interface CostSettings {
+  currency: string;
+  captcha_providers: CaptchaProvider[];
+}
+
+/** get_proxy_cost / set_proxy_cost; null where the plan doesn't charge that way */
+export interface ProxyCost {
+  price_per_gb: number | null;
+  /** For 30 days of the IP */
+  price_per_ip: number | null;
+}
+
+export type CostPeriod = "day" | "week" | "month";
+
+export interface Costs {
+  bandwidth: number;
+  ips: number;
+  captchas: number;
+  total: number;
+}
+
+export interface ProfileCost {
+  profile_id: string;
+  /** null when the profile was deleted */
+  name: string | null;
+  tags: string[];
+  bytes: number;
+  captcha_solves: number;
+  costs: Costs;
+}
+
+/** A profile with several tags counts in each of their groups */
+export interface GroupCost {
+  /** null for untagged profiles */
+  tag: string | null;
+  profiles: number;
+  costs: Costs;
+}
+
+/** get_cost_report */
+export interface CostReport {
+  period: CostPeriod;
+  since: string;
+  currency: string;
+  costs: Costs;
+  /** IP cost of priced proxies no profile is assigned to */
+  unassigned_ips: number;
+  by_profile: ProfileCost[];
+  by_group: GroupCost[];
+  /** Proxies with traffic but no price per GB */
+  unpriced_proxies: string[];
+  unpriced_providers: string[];
+}
+
+/** An event row's place in the log's hash chain */
+export interface ChainLink {
+  id: number;
+  hash: string;
+}
+
+export type ChainBreak = "unhashed" | "altered" | "missing" | "rewritten";
+
+/** verify_event_log; keep `head` to pass back as the next anchor */
+export interface EventLogVerification {
+  checked: number;
+  head: ChainLink | null;
+  broken: { id: number; kind: ChainBreak } | null;
+}
+
+/** A detection event, as weighed by recommend_rest */
+export interface Incident {
+  at: string;
+  severity: string;
+  domain: string | null;
+}
+
+/** recommend_rest: schedulers wait until `rest_until` */
+export interface RestRecommendation {
+  profile_id: string;
+  /** null covers every domain */
+  domain: string | null;
+  /** Most recent detection on any domain */
+  last_detection: Incident | null;
+  /** On `domain` within the past week */
+  incidents: number;
+  rest_minutes: number;
+  rest_until: string | null;
+  ready: boolean;
+  reasons: string[];
+}
+
+/** Relative weights; empty maps keep the generator's own shares */
+export interface GenerationPolicy {
+  /** Keys: "windows" | "macos" | "linux" */
+  os: Record<string, number>;
+  /** ISO 3166-1 alpha-2 country → weight */
+  countries: Record<string, number>;
+  /** Weigh countries by the proxy pool instead of `countries` */
+  match_proxy_pool: boolean;
+}
+
+/** bulk_create_profiles */
+export interface BulkCreateRequest {
+  count: number;
+  /** Profiles are named `<prefix>-001` … */
+  name_prefix: string;
+  tags?: string[];
+  behavior_profile?: string | null;
+  policy?: GenerationPolicy;
+  /** Give each profile the least used proxy in its country */
+  assign_proxies?: boolean;
+}
+
+/** Named `navigator.permissions` habits, applied by `set_profile_permissions`. */
+export type PermissionPreset = "privacy-conscious" | "default-user" | "power-user";
+
+/** A preset and the `PermissionState` it sets per permission name. */
+export interface PresetStates {
+  preset: PermissionPreset;
+  permissions: Record<string, "granted" | "denied" | "prompt">;
+}
+
+/** Device values read by static/collect-fingerprint.html */
+export interface DeviceCapture {
+  user_agent: string | null;
+  platform: string | null;
+  ua_platform: string | null;
+  ua_platform_version: string | null;
+  hardware_concurrency: number | null;
+  device_memory: number | null;
+  screen_width: number | null;
+  screen_height: number | null;
+  pixel_ratio: number | null;
+  color_depth: number | null;
+  webgl_vendor: string | null;
+  webgl_renderer: string | null;
+  timezone: string | null;
+  languages: string[];
+}
+
+export interface ReferenceFingerprint {
+  id: string;
+  label: string;
+  created_at: string;
+  capture: DeviceCapture;
+}
+
+export interface FieldCoverage {
+  field: string;
+  value: string;
+  /** References showing the same value */
+  seen_in: number;
+  /** References that captured the field */
+  captured_in: number;
+}
+
+export interface NearestReference {
+  id: string;
+  label: string;
+  matching: number;
+  mismatched: string[];
+}
+
+/** compare_to_references */
+export interface ReferenceComparison {
+  /** References of the profile's platform */
+  references: number;
+  /** 0–1; null without references to compare against */
+  score: number | null;
+  fields: FieldCoverage[];
+  nearest: NearestReference | null;
+}
+
+/** create_profile_from_capture */
+export interface CaptureProfileRequest {
+  reference_id: string;
+  name: string;
+  /** Seed of the fields the capture lacks; random when omitted */
+  seed?: number | null;
+  proxy_id?: string | null;
+  notes?: string | null;
+  tags?: string[] | null;
+  behavior_profile?: string | null;
+}
+
+/** Result of import_proxies (export_proxies returns the list as text) */
+export interface ProxyImport {
+  added: Proxy[];
+  /** Names of proxies this instance already had (same type, host, port) */
+  skipped: string[];
+}
+
+/** suggest_proxy: one proxy scored for a profile */
+export interface ProxyCandidate {
+  proxy_id: string;
+  proxy_name: string;
+  country: string | null;
+  /** 0–1, higher is better */
+  score: number;
+  /** 0–1: share of the fingerprint's geo consistency kept in this country */
+  country_fit: number;
+  latency_ms: number | null;
+  /** Profiles already using the proxy */
+  load: number;
+  /** Domains the proxy is cooling down for */
+  cooldowns: number;
+}
+
+export interface ProxyAssignment {
+  profile_id: string;
+  profile_name: string;
+  proxy: ProxyCandidate;
+}
+
+/** auto_assign_proxies */
+export interface AutoAssignResult {
+  assigned: ProxyAssignment[];
+  /** Ids of profiles no usable proxy was found for */
+  unmatched: string[];
+}
+
+/** preflight_profile: the workflow's start page and the selectors it needs */
+export interface PreflightPlan {
+  url: string;
+  selectors: string[];
+}
+
+export interface SelectorCheck {
+  selector: string;
+  count: number;
+  /** Set when the selector couldn't be evaluated */
+  error?: string | null;
+}
+
+export interface PreflightReport {
+  profile_id: string;
+  /** Leak test passed, the page loaded and every selector matched */
+  ready: boolean;
+  leak_test: LeakTestReport;
+  url: string;
+  status: number | null;
+  final_url: string | null;
+  selectors: SelectorCheck[];
+  /** Why the profile isn't ready, one line per problem */
+  issues: string[];
+  ran_at: string;
+}
+
+/** One page action; mirrors `StepAction` in src-tauri/src/workflow.rs */
+export type StepAction =
+  | { type: "navigate"; url: string }
+  | { type: "click"; selector: string }
+  | { type: "fill"; selector: string; value: string }
+  | { type: "wait_for"; selector: string; timeout_ms?: number }
+  | { type: "sleep"; ms: number }
+  /** Inner text of the first match, or of every match with `all` */
+  | { type: "extract_text"; selector: string; key: string; all?: boolean }
+  | {
+      type: "extract_attribute";
+      selector: string;
+      attribute: string;
+      key: string;
+      all?: boolean;
+    }
+  /** Rows keyed by header cell, or arrays of cell texts without a header */
+  | { type: "extract_table"; selector: string; key: string }
+  /** Pause until resume_workflow(run_id); fails after timeout_secs (default 600) */
+  | { type: "manual_intervention"; prompt: string; timeout_secs?: number };
+
+/** What a step does once all its attempts failed */
+export type OnError = "abort" | "skip" | "cleanup";
+
+export interface StepPolicy {
+  /** Attempts after the first */
+  retries?: number;
+  /** Delay before the first retry (default 1000) */
+  backoff_ms?: number;
+  /** Each further retry waits this much longer (default 2) */
+  backoff_multiplier?: number;
+  /** Tried after the step's own selector on every attempt */
+  alt_selectors?: string[];
+  on_error?: OnError;
+}
+
+/**
+ * Text in a step may use `{{name}}` variables, filled in from run_workflow's
+ * params and then the profile's vault (list_profile_secrets)
+ */
+export type WorkflowStep = StepAction & StepPolicy & { name?: string };
+
+export interface Workflow {
+  id: string;
+  name: string;
+  steps: WorkflowStep[];
+  /** Run after the steps unless a step aborted the run */
+  cleanup: WorkflowStep[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface StepOutcome {
+  phase: "main" | "cleanup";
+  index: number;
+  name: string | null;
+  status: "ok" | "skipped" | "failed";
+  attempts: number;
+  /** The alternative selector that worked, when the step's own didn't */
+  selector: string | null;
+  error: string | null;
+}
+
+/** run_workflow */
+export interface WorkflowRunReport {
+  run_id: string;
+  workflow_id: string;
+  profile_id: string;
+  status: "succeeded" | "failed" | "aborted";
+  steps: StepOutcome[];
+  /** What the extraction steps captured, in order */
+  outputs: RunOutput[];
+  started_at: string;
+  finished_at: string;
+}
+
+/** Payload of the `workflow-intervention` event and list_interventions */
+export interface Intervention {
+  run_id: string;
+  workflow_id: string;
+  profile_id: string;
+  prompt: string;
+  requested_at: string;
+  /** When the step gives up waiting */
+  expires_at: string;
+}
+
+/** One value captured by an extraction step */
+export interface RunOutput {
+  key: string;
+  /** Index of the step in its phase */
+  step: number;
+  value: unknown;
+  captured_at: string;
+}
+
+/** list_run_outputs; export_run_outputs(run_id, "json") */
+export interface RunOutputs {
+  run_id: string;
+  workflow_id: string | null;
+  profile_id: string | null;
+  outputs: RunOutput[];
+}
+
+/** run_workflow_on_group options; every field may be left out */
+export interface GroupRunOptions {
+  /** Run parameters for every profile; the vault fills in the rest */
+  params?: Record<string, string>;
+  stop_on_failure?: boolean;
+  /** A previous group run: only its unsuccessful profiles are run */
+  resume_from?: string;
+}
+
+/** Payload of the `workflow-group-progress` event */
+export interface ProfileRunResult {
+  profile_id: string;
+  profile_name: string;
+  /** null when the run never started */
+  run_id: string | null;
+  status: WorkflowRunReport["status"] | null;
+  error: string | null;
+}
+
+/** run_workflow_on_group / get_group_run; group_id is a profile tag */
+export interface GroupRunReport {
+  id: string;
+  workflow_id: string;
+  group_id: string;
+  resumed_from: string | null;
+  results: ProfileRunResult[];
+  succeeded: number;
+  failed: number;
+  started_at: string;
+  finished_at: string;
+}
+
+/** list_workflow_runs */
+export interface WorkflowRun {
+  id: string;
+  workflow_id: string;
+  profile_id: string;
+  status: WorkflowRunReport["status"];
+  /** Steps that gave up, each with a capture in the archive */
+  failures: number;
+  archive_path: string;
+  started_at: string;
+  finished_at: string;
+}
+
+/** The page as a failed step left it */
+export interface FailureCapture {
+  phase: StepOutcome["phase"];
+  index: number;
+  error: string | null;
+  url: string | null;
+  /** Base64 PNG */
+  screenshot: string | null;
+  /** The last requests, cookie and authorization values redacted */
+  har: unknown[];
+  captured_at: string;
+}
+
+/** get_workflow_run_archive */
+export interface RunArchive {
+  format: number;
+  workflow_name: string;
+  report: WorkflowRunReport;
+  failures: FailureCapture[];
+  /** The bridge's output lines, oldest dropped past 2000 */
+  log: string[];
+}
+
+/** get_synthetic_identity; workflows read it as `{{identity.<field>}}` */
+export interface SyntheticIdentity {
+  /** ISO-3166-1 alpha-2, from the profile's geo */
+  country: string;
+  country_name: string;
+  first_name: string;
+  last_name: string;
+  full_name: string;
+  /** YYYY-MM-DD */
+  birthdate: string;
+  /** The birthdate as the country writes it */
+  birthdate_local: string;
+  /** National format */
+  phone: string;
+  phone_e164: string;
+  address_line1: string;
+  city: string;
+  region: string;
+  postal_code: string;
+}
+
+export interface IdentityIssue {
+  code:
+    | "unsupported_country"
+    | "country_mismatch"
+    | "postal_code"
+    | "phone"
+    | "persona_home_city";
+  field: string;
+  message: string;
+}
+
+/** validate_identity */
+export interface IdentityValidation {
+  profile_id: string;
+  /** The profile's geo country, when it has one */
+  country: string | null;
+  has_identity: boolean;
+  issues: IdentityIssue[];
+}
+
+/** How a clipboard value is kept in the audit log: never its content */
+export interface RedactedValue {
+  chars: number;
+  shape: "digits" | "alphanumeric" | "text" | "multiline";
+  /** One "•" per character, up to 12 */
+  masked: string;
+}
+
+/** Detail of a `clipboard` event (clipboard_write, clipboard_read) */
+export interface ClipboardEventDetail {
+  action: "write" | "paste" | "read";
+  value: RedactedValue;
+}
+
+export type HostCondition =
+  | "locale"
+  | "virtual_machine"
+  | "remote_desktop"
+  | "display_scaling";
+
+export interface HostEnvironment {
+  os: string;
+  /** BCP-47, or "C" / "POSIX"; null when unset */
+  locale: string | null;
+  /** What gave a virtual machine away */
+  virtual_machine: string | null;
+  remote_session: string | null;
+  display_scale: number | null;
+  cpu_cores: number | null;
+}
+
+export interface HostFinding {
+  condition: HostCondition;
+  message: string;
+}
+
+export interface HostProfileConflict {
+  profile_id: string;
+  profile_name: string;
+  condition: HostCondition;
+  /** The fingerprint field in conflict */
+  field: string;
+  message: string;
+}
+
+/** get_host_audit, and the "host-audit" event at startup */
+export interface HostAudit {
+  host: HostEnvironment;
+  findings: HostFinding[];
+  conflicts: HostProfileConflict[];
+  audited_at: string;
+}
+
+export type GpuTier = "software" | "integrated" | "discrete";
+
+/** get_host_gpu */
+export interface HostGpu {
+  vendor: string | null;
+  renderer: string | null;
+  tier: GpuTier | null;
+  /** GL_MAX_TEXTURE_SIZE, when the host's GL could be queried */
+  max_texture_size: number | null;
+  /** The host's GL extensions by their WebGL names; empty when unknown */
+  webgl_extensions: string[];
+  source: "glxinfo" | "system_profiler" | "Win32_VideoController" | "none";
+  probed_at: string;
+}
+
+/** get_host_clock */
+export interface HostClock {
+  server: string;
+  /** Host minus NTP time; positive when the host runs ahead */
+  skew_ms: number;
+  round_trip_ms: number;
+  measured_at: string;
+}
+
+/** get_clock_tolerance / set_clock_tolerance: past soft_ms geo validation
+ *  warns about the host clock, past hard_ms it fails */
+export interface ClockTolerance {
+  soft_ms: number;
+  hard_ms: number;
+}
+
+export type LaunchMode = "headed" | "headless_new" | "virtual_display";
+
+export interface LaunchPolicy {
+  mode: LaunchMode;
+  /** Tried in order when the host can't run `mode` */
+  fallback: LaunchMode[];
+}
+
+export interface SkippedMode {
+  mode: LaunchMode;
+  reason: string;
+}
+
+/** check_launch_mode; also the detail of launch events */
+export interface LaunchPlan {
+  mode: LaunchMode;
+  skipped: SkippedMode[];
+  /** Detection risks of the chosen mode for this profile */
+  warnings: string[];
+}
+
+/** list_profile_sessions */
+export interface SessionSummary {
+  id: string;
+  started_at: string;
+  ended_at: string | null;
+  launch_mode: LaunchMode | null;
+}
+
+export type TimelineKind =
+  | { kind: "navigation"; url: string; status: number | null }
+  | {
+      kind: "action";
+      action: "click" | "type" | "scroll" | "extract" | "execute";
+      selector: string | null;
+      /** Characters typed, for `type`; the text itself isn't kept */
+      chars: number | null;
+    }
+  | { kind: "wait"; ms: number }
+  | { kind: "error"; message: string }
+  | { kind: "screenshot" };
+
+export type TimelineEntry = TimelineKind & {
+  at: string;
+  /** Milliseconds since the session's first entry */
+  offset_ms: number;
+};
+
+/** get_session_timeline */
+export interface SessionTimeline {
+  profile_id: string;
+  session: string;
+  exported_at: string | null;
+  entries: TimelineEntry[];
+}
+
+export interface HarHeaderCheck {
+  url: string;
+  kind: "navigation" | "fetch" | "subresource";
+  check: {
+    in_order: boolean;
+    first_out_of_order: string | null;
+    unexpected: string[];
+    miscased: string[];
+  };
+}
+
+/** verify_session_headers; also the header_order of session bundles */
+export interface HeaderOrderReport {
+  checked: number;
+  in_order: number;
+  /** Entries that failed, in HAR order */
+  failures: HarHeaderCheck[];
+}
+
+/** import_session_har */
+export interface ImportedSession {
+  /** The new bundle's name, as list_sessions reports it */
+  session: string;
+  path: string;
+  entries: number;
+  /** The HAR's log.creator.name, e.g. "WebInspector" */
+  creator: string | null;
+  first_request_at: string | null;
+  header_order: HeaderOrderReport;
+}
+
+// ── Execution nodes (nodes.rs) ───────────────────────────────────────────────
+
+export type NodeKind = "ssh" | "agent";
+
+/** add_node / update_node */
+export interface NodeSpec {
+  name: string;
+  kind: NodeKind;
+  host: string;
+  ssh_port?: number;
+  ssh_user?: string | null;
+  ssh_key_path?: string | null;
+  /** Agent nodes: https base URL of the launch agent */
+  agent_url?: string | null;
+  /** Write-only; omitted on update to keep the stored token */
+  agent_token?: string | null;
+  bridge_dir?: string;
+  bridge_command?: string;
+  ws_port?: number;
+  tls_bridge_port?: number;
+  /** Agent nodes: listen on all interfaces over wss:// instead of loopback behind an SSH forward */
+  expose_ws?: boolean;
+  /** Pool launches taken at once, besides the launch_profile bridge */
+  capacity?: number;
+}
+
+/** check_node; also the latest health of list_nodes entries */
+export interface NodeHealth {
+  online: boolean;
+  latency_ms: number | null;
+  error: string | null;
+  checked_at: string;
+}
+
+/** list_nodes */
+export interface ExecutionNode extends Required<Omit<NodeSpec, "agent_token">> {
+  id: string;
+  enabled: boolean;
+  health: NodeHealth | null;
+  created_at: string;
+}
+
+// ── Node pool (node_pool.rs) ─────────────────────────────────────────────────
+
+/** A profile to launch through the pool (enqueue_launches) */
+export interface LaunchRequest {
+  profile_id: string;
+  url?: string | null;
+  target_domain?: string | null;
+}
+
+export interface QueuedLaunch extends LaunchRequest {
+  queued_at: string;
+  /** Starts so far */
+  attempts: number;
+  /** Why the previous start ended, for a launch queued again */
+  last_error: string | null;
+}
+
+/** A pool launch running, or starting, on a node */
+export interface Placement extends LaunchRequest {
+  node_id: string;
+  node_name: string;
+  slot: number;
+  ws_host: string;
+  /** null while the launch is starting */
+  ws_port: number | null;
+  /** The bridge serves wss:// (agent nodes with expose_ws) */
+  ws_tls: boolean;
+  started_at: string;
+  attempts: number;
+}
+
+export interface NodeLoad {
+  node_id: string;
+  name: string;
+  running: number;
+  capacity: number;
+  /** Whether the launch_profile bridge runs there too */
+  foreground: boolean;
+  /** Enabled and online at its latest check */
+  available: boolean;
+}
+
+/** get_node_pool / enqueue_launches */
+export interface PoolStatus {
+  nodes: NodeLoad[];
+  placements: Placement[];
+  queue: QueuedLaunch[];
+}
@@ -6,6 +6,7 @@
  */
 
 import type { LoginFormConfig } from "../../../playwright-bridge/login-types";
+import { invoke } from "@tauri-apps/api/core";
 import { ws } from "$lib/websocket";
 import type { WsServerMessage, ScrapedFormSelectors as ScrapedFormSelectorsWs } from "$lib/types";
 
@@ -389,6 +390,10 @@ async function scrapeViaPlaywrightBridge(
   url: string,
   timeout: number,
 ): Promise<ScrapedFormSelectors | null> {
+  // The bridge only accepts handshakes with a ticket from the backend
+  const bridgeUrl = await invoke<string>("get_bridge_url").catch(
+    () => `ws://localhost:${BRIDGE_WS_PORT}`,
+  );
   return new Promise((resolve, reject) => {
     let settled = false;
     const timer = setTimeout(() => {
@@ -401,7 +406,7 @@ async function scrapeViaPlaywrightBridge(
 
     let ws: WebSocket;
     try {
-      ws = new WebSocket(`ws://localhost:${BRIDGE_WS_PORT}`);
+      ws = new WebSocket(bridgeUrl);
     } catch (e) {
       clearTimeout(timer);
       reject(e);